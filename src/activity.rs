@@ -0,0 +1,128 @@
+//! Dziennik aktywności okna głównego
+//!
+//! Pasek stanu pokazuje tylko ostatni komunikat. Zdarzenia sesji (start,
+//! wyniki i błędy dostawców, anulowania) trafiają dodatkowo do bufora
+//! o stałej pojemności, z którego korzysta zwijany panel aktywności.
+//! Po zapełnieniu najstarsze zdarzenia wypadają.
+
+use std::collections::VecDeque;
+
+/// Ile zdarzeń pamięta dziennik
+pub const CAPACITY: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActivityKind {
+    Session,
+    Finished,
+    Error,
+    Cancelled,
+    Info,
+}
+
+impl ActivityKind {
+    pub fn icon(&self) -> &'static str {
+        match self {
+            ActivityKind::Session => "▶️",
+            ActivityKind::Finished => "✅",
+            ActivityKind::Error => "❌",
+            ActivityKind::Cancelled => "🚫",
+            ActivityKind::Info => "ℹ️",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActivityEvent {
+    /// Czas lokalny "HH:MM:SS"
+    pub time: String,
+    pub kind: ActivityKind,
+    pub text: String,
+}
+
+impl ActivityEvent {
+    /// Np. "12:03:45 ✅ OpenAI: gotowe w 2.1 s"
+    pub fn line(&self) -> String {
+        format!("{} {} {}", self.time, self.kind.icon(), self.text)
+    }
+}
+
+/// Bufor ostatnich zdarzeń, od najstarszego
+pub struct ActivityLog {
+    events: VecDeque<ActivityEvent>,
+    capacity: usize,
+}
+
+impl Default for ActivityLog {
+    fn default() -> Self {
+        Self::new(CAPACITY)
+    }
+}
+
+impl ActivityLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            events: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Dodaje zdarzenie; zwraca najstarsze, jeśli musiało wypaść
+    pub fn push(&mut self, event: ActivityEvent) -> Option<ActivityEvent> {
+        let evicted = if self.events.len() >= self.capacity {
+            self.events.pop_front()
+        } else {
+            None
+        };
+        self.events.push_back(event);
+        evicted
+    }
+
+    pub fn events(&self) -> impl DoubleEndedIterator<Item = &ActivityEvent> {
+        self.events.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    /// Cały dziennik do skopiowania, linia na zdarzenie
+    pub fn text(&self) -> String {
+        self.events.iter().map(ActivityEvent::line).collect::<Vec<_>>().join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(time: &str, kind: ActivityKind, text: &str) -> ActivityEvent {
+        ActivityEvent {
+            time: time.to_string(),
+            kind,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_push_evicts_oldest() {
+        let mut log = ActivityLog::new(2);
+        assert_eq!(log.push(event("12:00:00", ActivityKind::Session, "Sesja 1")), None);
+        assert_eq!(log.push(event("12:00:02", ActivityKind::Finished, "OpenAI: gotowe")), None);
+        let evicted = log.push(event("12:00:03", ActivityKind::Error, "Groq: HTTP 500"));
+        assert_eq!(evicted.map(|e| e.text), Some("Sesja 1".to_string()));
+        assert_eq!(log.len(), 2);
+        assert_eq!(log.text(), "12:00:02 ✅ OpenAI: gotowe\n12:00:03 ❌ Groq: HTTP 500");
+
+        log.clear();
+        assert!(log.is_empty());
+        assert_eq!(ActivityLog::new(0).push(event("12:00:00", ActivityKind::Info, "x")), None);
+    }
+}
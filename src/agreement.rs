@@ -0,0 +1,122 @@
+//! Zgodność dostawców na poziomie zdań
+//!
+//! Każde zdanie wyniku jest porównywane ze zdaniami pozostałych wyników.
+//! Wynik zdania to średnia (po innych dostawcach) najlepszego podobieństwa
+//! słów do któregoś z ich zdań. Zdania, co do których modele się zgadzają,
+//! zostają bez tła; pozostałe dostają delikatne podświetlenie.
+
+use similar::TextDiff;
+
+use crate::merge::sentence_spans;
+
+/// Od tego wyniku zdanie uznaje się za zgodne
+pub const HIGH: f64 = 0.9;
+/// Poniżej tego wyniku modele wyraźnie się różnią
+pub const MEDIUM: f64 = 0.6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    High,
+    Medium,
+    Low,
+}
+
+impl Level {
+    pub fn from_score(score: f64) -> Self {
+        if score >= HIGH {
+            Self::High
+        } else if score >= MEDIUM {
+            Self::Medium
+        } else {
+            Self::Low
+        }
+    }
+}
+
+/// Zdanie wyniku z oceną zgodności
+#[derive(Debug, Clone, PartialEq)]
+pub struct SentenceScore {
+    /// Zakres w znakach (nie bajtach), do podświetlenia w buforze GTK
+    pub start: usize,
+    pub end: usize,
+    /// 0.0–1.0
+    pub score: f64,
+}
+
+impl SentenceScore {
+    pub fn level(&self) -> Level {
+        Level::from_score(self.score)
+    }
+}
+
+/// Podobieństwo dwóch zdań na poziomie słów (1.0 = identyczne)
+pub fn similarity(a: &str, b: &str) -> f64 {
+    f64::from(TextDiff::from_words(a, b).ratio())
+}
+
+/// Oceny zdań `text` względem wyników innych dostawców; bez innych wyników lista jest pusta
+pub fn sentence_scores(text: &str, others: &[&str]) -> Vec<SentenceScore> {
+    if others.is_empty() {
+        return Vec::new();
+    }
+    let other_sentences: Vec<Vec<&str>> = others
+        .iter()
+        .map(|other| sentence_spans(other).into_iter().map(|(s, e)| &other[s..e]).collect())
+        .collect();
+
+    sentence_spans(text)
+        .into_iter()
+        .map(|(start, end)| {
+            let sentence = &text[start..end];
+            let total: f64 = other_sentences
+                .iter()
+                .map(|sentences| {
+                    sentences
+                        .iter()
+                        .map(|other| similarity(sentence, other))
+                        .fold(0.0, f64::max)
+                })
+                .sum();
+            SentenceScore {
+                start: text[..start].chars().count(),
+                end: text[..end].chars().count(),
+                score: total / other_sentences.len() as f64,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identical_sentences_score_high() {
+        let text = "Ala ma kota. Kot ma Alę.";
+        let scores = sentence_scores(text, &["Ala ma kota. Kot ma Alę.", "Kot ma Alę. Ala ma kota."]);
+
+        assert_eq!(scores.len(), 2);
+        assert!(scores.iter().all(|s| s.level() == Level::High));
+        assert_eq!((scores[1].start, scores[1].end), (13, 24));
+    }
+
+    #[test]
+    fn test_divergent_sentence_scores_low() {
+        let text = "Wczoraj padał deszcz. Spotkanie przeniesiono na piątek.";
+        let others = [
+            "Wczoraj padał deszcz. Zebranie odbędzie się w czwartek rano.",
+            "Wczoraj padał deszcz. Termin narady jest nadal nieznany.",
+        ];
+        let scores = sentence_scores(text, &others);
+
+        assert_eq!(scores[0].level(), Level::High);
+        assert_eq!(scores[1].level(), Level::Low);
+    }
+
+    #[test]
+    fn test_no_other_results() {
+        assert!(sentence_scores("Jedno zdanie.", &[]).is_empty());
+        assert_eq!(Level::from_score(0.75), Level::Medium);
+        assert!((similarity("a b c", "a b c") - 1.0).abs() < 1e-9);
+    }
+}
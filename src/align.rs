@@ -0,0 +1,187 @@
+//! Wyrównanie wyników dostawców zdanie po zdaniu
+//!
+//! Wyniki są dokładane po kolei: zdania kolejnego wyniku dopasowywane są do
+//! istniejących wierszy programowaniem dynamicznym (jak przy wyrównywaniu
+//! sekwencji), tak żeby suma podobieństw dopasowanych par była największa,
+//! a kolejność zdań zachowana. Zdanie bez pary dostaje własny wiersz.
+//! Wiersz jest sporny, gdy któremuś dostawcy brakuje zdania albo zdania
+//! różnią się choćby jednym słowem; przy korekcie każda różnica jest warta
+//! przejrzenia.
+
+use crate::agreement::{similarity, Level};
+use crate::merge::split_sentences;
+
+/// Najmniejsze podobieństwo, przy którym zdania trafiają do jednego wiersza
+pub const MATCH_THRESHOLD: f64 = 0.5;
+
+/// Jeden wiersz wyrównania: zdanie każdego dostawcy albo `None`
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlignedRow {
+    pub cells: Vec<Option<String>>,
+    /// Najmniejsze podobieństwo między zdaniami w wierszu (0.0, gdy komuś brakuje zdania)
+    pub agreement: f64,
+}
+
+impl AlignedRow {
+    pub fn is_contested(&self) -> bool {
+        self.agreement < 1.0
+    }
+
+    /// Jak bardzo zdania się różnią (do kolorowania wiersza)
+    pub fn level(&self) -> Level {
+        Level::from_score(self.agreement)
+    }
+}
+
+/// Najlepsze podobieństwo zdania do któregoś ze zdań w wierszu
+fn row_similarity(cells: &[Option<String>], sentence: &str) -> f64 {
+    cells
+        .iter()
+        .flatten()
+        .map(|cell| similarity(cell, sentence))
+        .fold(0.0, f64::max)
+}
+
+/// Dokłada zdania źródła `source` do wierszy, zachowując kolejność obu ciągów
+fn add_source(rows: Vec<Vec<Option<String>>>, sentences: Vec<String>, source: usize, width: usize) -> Vec<Vec<Option<String>>> {
+    let (n, m) = (rows.len(), sentences.len());
+    let mut score = vec![vec![0.0_f64; m + 1]; n + 1];
+    for i in 1..=n {
+        for j in 1..=m {
+            let sim = row_similarity(&rows[i - 1], &sentences[j - 1]);
+            let matched = if sim >= MATCH_THRESHOLD {
+                score[i - 1][j - 1] + sim
+            } else {
+                f64::MIN
+            };
+            score[i][j] = matched.max(score[i - 1][j]).max(score[i][j - 1]);
+        }
+    }
+
+    // Od końca: `rows` i `sentences` mają zawsze długość `i` i `j`
+    let mut merged = Vec::with_capacity(n + m);
+    let (mut rows, mut sentences) = (rows, sentences);
+    let (mut i, mut j) = (n, m);
+    while i > 0 || j > 0 {
+        if i > 0 && j > 0 {
+            let sim = row_similarity(&rows[i - 1], &sentences[j - 1]);
+            if sim >= MATCH_THRESHOLD && (score[i][j] - (score[i - 1][j - 1] + sim)).abs() < 1e-12 {
+                let mut row = rows.pop().expect("i > 0");
+                row[source] = sentences.pop();
+                merged.push(row);
+                i -= 1;
+                j -= 1;
+                continue;
+            }
+        }
+        if i > 0 && (j == 0 || score[i][j] == score[i - 1][j]) {
+            merged.push(rows.pop().expect("i > 0"));
+            i -= 1;
+        } else {
+            let mut row = vec![None; width];
+            row[source] = sentences.pop();
+            merged.push(row);
+            j -= 1;
+        }
+    }
+
+    merged.reverse();
+    merged
+}
+
+fn row_agreement(cells: &[Option<String>], available: &[bool]) -> f64 {
+    let present: Vec<&str> = cells.iter().flatten().map(String::as_str).collect();
+    let missing = cells.iter().zip(available).any(|(cell, available)| *available && cell.is_none());
+    if missing {
+        return 0.0;
+    }
+
+    let mut min = 1.0_f64;
+    for (k, a) in present.iter().enumerate() {
+        for b in &present[k + 1..] {
+            min = min.min(similarity(a, b));
+        }
+    }
+    min
+}
+
+/// Wyrównuje wyniki (`None` = dostawca bez wyniku); kolumny w kolejności `texts`
+pub fn align(texts: &[Option<&str>]) -> Vec<AlignedRow> {
+    let width = texts.len();
+    let available: Vec<bool> = texts.iter().map(|t| t.is_some_and(|t| !t.trim().is_empty())).collect();
+
+    let mut rows: Vec<Vec<Option<String>>> = Vec::new();
+    for (source, text) in texts.iter().enumerate() {
+        if !available[source] {
+            continue;
+        }
+        let sentences = text.map(split_sentences).unwrap_or_default();
+        rows = add_source(rows, sentences, source, width);
+    }
+
+    rows.into_iter()
+        .map(|cells| AlignedRow {
+            agreement: row_agreement(&cells, &available),
+            cells,
+        })
+        .collect()
+}
+
+/// Liczba spornych wierszy
+pub fn contested_count(rows: &[AlignedRow]) -> usize {
+    rows.iter().filter(|r| r.is_contested()).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cells(row: &AlignedRow) -> Vec<Option<&str>> {
+        row.cells.iter().map(|c| c.as_deref()).collect()
+    }
+
+    #[test]
+    fn test_identical_results_are_not_contested() {
+        let text = "Ala ma kota. Kot ma Alę.";
+        let rows = align(&[Some(text), Some(text), None, Some(text)]);
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(cells(&rows[0]), vec![Some("Ala ma kota."), Some("Ala ma kota."), None, Some("Ala ma kota.")]);
+        assert_eq!(contested_count(&rows), 0);
+    }
+
+    #[test]
+    fn test_inserted_sentence_gets_own_row() {
+        let rows = align(&[
+            Some("Pierwsze zdanie tutaj. Trzecie zdanie na końcu."),
+            Some("Pierwsze zdanie tutaj. Zupełnie nowa wstawka od modelu. Trzecie zdanie na końcu."),
+        ]);
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(cells(&rows[1]), vec![None, Some("Zupełnie nowa wstawka od modelu.")]);
+        assert!(!rows[0].is_contested());
+        assert!(rows[1].is_contested());
+        assert_eq!(rows[1].level(), Level::Low);
+        assert!(!rows[2].is_contested());
+    }
+
+    #[test]
+    fn test_reworded_sentence_is_contested_in_same_row() {
+        let rows = align(&[
+            Some("Spotkanie odbędzie się w piątek o dziesiątej rano. Proszę o potwierdzenie."),
+            Some("Spotkanie odbędzie się w czwartek o dziesiątej rano. Proszę o potwierdzenie."),
+            Some("Spotkanie odbędzie się w piątek o dziesiątej rano. Proszę o potwierdzenie."),
+        ]);
+
+        assert_eq!(rows.len(), 2);
+        assert!(rows[0].cells.iter().all(Option::is_some));
+        assert!(rows[0].is_contested());
+        assert_eq!(rows[0].level(), Level::High);
+        assert!(!rows[1].is_contested());
+    }
+
+    #[test]
+    fn test_no_results() {
+        assert!(align(&[None, Some("  "), None]).is_empty());
+    }
+}
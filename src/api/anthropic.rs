@@ -1,9 +1,12 @@
-use crate::api::http_client::get_client;
+use crate::api::http_client::{body_stream, get_client, read_json, status_error, JsonBody, WithProfile};
+use crate::api::token_usage::{self, TokenUsage};
+use crate::api::{Provider, RequestOptions, TEMPERATURE};
+use crate::conversation::{user_message, Exchange};
 use crate::error::{ApiError, DEFAULT_TIMEOUT};
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 
-const ANTHROPIC_API_URL: &str = "https://api.anthropic.com/v1/messages";
+pub const ANTHROPIC_API_URL: &str = "https://api.anthropic.com/v1/messages";
 const ANTHROPIC_VERSION: &str = "2023-06-01";
 
 #[derive(Debug, Serialize)]
@@ -26,6 +29,27 @@ struct Message {
 #[derive(Debug, Deserialize)]
 struct MessagesResponse {
     content: Vec<ContentBlock>,
+    #[serde(default)]
+    usage: Option<Usage>,
+}
+
+/// Zużycie w odpowiedzi; przy strumieniu wejście przychodzi w `message_start`,
+/// a wyjście w `message_delta`
+#[derive(Debug, Deserialize, Default)]
+struct Usage {
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
+}
+
+impl Usage {
+    fn token_usage(&self) -> TokenUsage {
+        TokenUsage {
+            prompt_tokens: self.input_tokens,
+            completion_tokens: self.output_tokens,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -41,6 +65,18 @@ struct StreamEvent {
     event_type: String,
     #[serde(default)]
     delta: Option<StreamDelta>,
+    /// `message_start`: wiadomość z początkowym zużyciem
+    #[serde(default)]
+    message: Option<StreamMessage>,
+    /// `message_delta`: końcowa liczba tokenów odpowiedzi
+    #[serde(default)]
+    usage: Option<Usage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamMessage {
+    #[serde(default)]
+    usage: Option<Usage>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -50,6 +86,53 @@ struct StreamDelta {
     text: Option<String>,
 }
 
+/// Treść żądania wysyłanego do API (bez sieci, do testów i podglądu)
+pub fn build_request_body(
+    model: &str,
+    text_to_correct: &str,
+    instruction_prompt: &str,
+    system_prompt: &str,
+    streaming: bool,
+) -> serde_json::Value {
+    let message = user_message(instruction_prompt, text_to_correct);
+    let options = RequestOptions { streaming, ..Default::default() };
+    serde_json::to_value(build_request(model, &[], &message, system_prompt, options))
+        .expect("request is always serializable")
+}
+
+fn build_request(
+    model: &str,
+    history: &[Exchange],
+    message: &str,
+    system_prompt: &str,
+    options: RequestOptions,
+) -> MessagesRequest {
+    let mut messages = Vec::new();
+    for exchange in history {
+        messages.push(Message {
+            role: "user".to_string(),
+            content: exchange.user.clone(),
+        });
+        messages.push(Message {
+            role: "assistant".to_string(),
+            content: exchange.assistant.clone(),
+        });
+    }
+    messages.push(Message {
+        role: "user".to_string(),
+        content: message.to_string(),
+    });
+
+    MessagesRequest {
+        model: model.to_string(),
+        messages,
+        max_tokens: 4096,
+        system: system_prompt.to_string(),
+        temperature: TEMPERATURE,
+        stream: options.streaming,
+    }
+}
+
 pub async fn correct_text_anthropic(
     api_key: &str,
     model: &str,
@@ -74,43 +157,56 @@ pub async fn correct_text_anthropic_with_callback<F>(
 where
     F: Fn(&str) + Send + 'static,
 {
+    if text_to_correct.is_empty() {
+        return Err(ApiError::Response("Text to correct is empty".to_string()));
+    }
+
+    let message = user_message(instruction_prompt, text_to_correct);
+    let options = RequestOptions { streaming, ..Default::default() };
+    converse_anthropic_with_callback(api_key, model, &[], &message, system_prompt, options, on_chunk).await
+}
+
+/// Wysyła wcześniejsze wymiany rozmowy i nową wiadomość użytkownika
+pub async fn converse_anthropic_with_callback<F>(
+    api_key: &str,
+    model: &str,
+    history: &[Exchange],
+    message: &str,
+    system_prompt: &str,
+    options: RequestOptions,
+    on_chunk: Option<F>,
+) -> Result<String, ApiError>
+where
+    F: Fn(&str) + Send + 'static,
+{
+    let streaming = options.streaming;
     if api_key.is_empty() {
         return Err(ApiError::Response("API key is empty".to_string()));
     }
     if model.is_empty() {
         return Err(ApiError::Response("Model is empty".to_string()));
     }
-    if text_to_correct.is_empty() {
-        return Err(ApiError::Response("Text to correct is empty".to_string()));
+    if message.is_empty() {
+        return Err(ApiError::Response("Message is empty".to_string()));
     }
 
     let client = get_client();
 
-    let messages = vec![Message {
-        role: "user".to_string(),
-        content: format!("{}\n\n---\n{}\n---", instruction_prompt, text_to_correct),
-    }];
-
-    let request = MessagesRequest {
-        model: model.to_string(),
-        messages,
-        max_tokens: 4096,
-        system: system_prompt.to_string(),
-        temperature: 0.7,
-        stream: streaming,
-    };
+    // Anthropic nie ma trybu JSON; format wymusza sam prompt
+    let request = build_request(model, history, message, system_prompt, options);
 
     let response = client
         .post(ANTHROPIC_API_URL)
         .header("x-api-key", api_key)
         .header("anthropic-version", ANTHROPIC_VERSION)
         .header("Content-Type", "application/json")
-        .json(&request)
+        .json_body(Provider::Anthropic, &request, options)
+        .with_profile(options)
         .send()
         .await
         .map_err(|e| {
             if e.is_timeout() {
-                ApiError::Timeout(format!("Request timed out after {}s", DEFAULT_TIMEOUT))
+                ApiError::Timeout(format!("Request timed out after {}s", options.timeout_secs(DEFAULT_TIMEOUT)))
             } else if e.is_connect() {
                 ApiError::Connection(e.to_string())
             } else {
@@ -119,17 +215,14 @@ where
         })?;
 
     if !response.status().is_success() {
-        return Err(ApiError::Response(format!(
-            "HTTP {}: {}",
-            response.status(),
-            response.text().await.unwrap_or_default()
-        )));
+        return Err(status_error(response).await);
     }
 
     if streaming {
-        let mut stream = response.bytes_stream();
+        let mut stream = body_stream(Provider::Anthropic, response);
         let mut collected_text = String::new();
         let mut buffer = String::new();
+        let mut usage = Usage::default();
 
         while let Some(chunk_result) = stream.next().await {
             let chunk = chunk_result.map_err(|e| ApiError::Response(e.to_string()))?;
@@ -140,6 +233,12 @@ where
                 if line.starts_with("data: ") {
                     let data = &line[6..];
                     if let Ok(event) = serde_json::from_str::<StreamEvent>(data) {
+                        if let Some(start) = event.message.as_ref().and_then(|m| m.usage.as_ref()) {
+                            usage.input_tokens = start.input_tokens;
+                        }
+                        if let Some(delta) = &event.usage {
+                            usage.output_tokens = delta.output_tokens;
+                        }
                         if event.event_type == "content_block_delta" {
                             if let Some(delta) = event.delta {
                                 if let Some(text) = delta.text {
@@ -156,16 +255,22 @@ where
             buffer.clear();
         }
 
+        if usage.input_tokens > 0 || usage.output_tokens > 0 {
+            token_usage::report(usage.token_usage());
+        }
         if collected_text.is_empty() {
             Err(ApiError::Response("No content in streaming response".to_string()))
         } else {
             Ok(collected_text.trim().to_string())
         }
     } else {
-        let completion: MessagesResponse = response.json().await.map_err(|e| {
+        let completion: MessagesResponse = read_json(Provider::Anthropic, response).await.map_err(|e| {
             ApiError::Response(format!("Failed to parse response: {}", e))
         })?;
 
+        if let Some(usage) = &completion.usage {
+            token_usage::report(usage.token_usage());
+        }
         completion
             .content
             .into_iter()
@@ -180,6 +285,35 @@ where
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_request_carries_conversation_history() {
+        let history = vec![Exchange {
+            user: user_message("Correct", "ala ma kota"),
+            assistant: "Ala ma kota.".to_string(),
+        }];
+        let options = RequestOptions::default();
+        let request = build_request("claude-3-7-sonnet-latest", &history, "krócej", "System", options);
+
+        let roles: Vec<&str> = request.messages.iter().map(|m| m.role.as_str()).collect();
+        assert_eq!(roles, ["user", "assistant", "user"]);
+        assert_eq!(request.messages[2].content, "krócej");
+        assert_eq!(request.system, "System");
+    }
+
+    #[test]
+    fn test_stream_events_carry_usage() {
+        let start: StreamEvent = serde_json::from_str(
+            r#"{"type": "message_start", "message": {"id": "msg_1", "usage": {"input_tokens": 25, "output_tokens": 1}}}"#,
+        )
+        .unwrap();
+        let delta: StreamEvent = serde_json::from_str(
+            r#"{"type": "message_delta", "delta": {"stop_reason": "end_turn"}, "usage": {"output_tokens": 15}}"#,
+        )
+        .unwrap();
+        assert_eq!(start.message.and_then(|m| m.usage).map(|u| u.input_tokens), Some(25));
+        assert_eq!(delta.usage.map(|u| u.token_usage().completion_tokens), Some(15));
+    }
+
     #[tokio::test]
     async fn test_anthropic_empty_api_key() {
         let result = correct_text_anthropic(
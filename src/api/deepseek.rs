@@ -1,9 +1,12 @@
-use crate::api::http_client::get_client;
+use crate::api::http_client::{body_stream, get_client, read_json, status_error, JsonBody, WithProfile};
+use crate::api::token_usage::{self, StreamOptions, TokenUsage};
+use crate::api::{Provider, RequestOptions, ResponseFormat, TEMPERATURE};
+use crate::conversation::{user_message, Exchange};
 use crate::error::{ApiError, DEEPSEEK_TIMEOUT};
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 
-const DEEPSEEK_API_URL: &str = "https://api.deepseek.com/chat/completions";
+pub const DEEPSEEK_API_URL: &str = "https://api.deepseek.com/chat/completions";
 
 #[derive(Debug, Serialize)]
 struct ChatCompletionRequest {
@@ -13,6 +16,10 @@ struct ChatCompletionRequest {
     max_tokens: u32,
     #[serde(skip_serializing_if = "std::ops::Not::not")]
     stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<StreamOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<ResponseFormat>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -24,6 +31,8 @@ struct Message {
 #[derive(Debug, Deserialize)]
 struct ChatCompletionResponse {
     choices: Vec<Choice>,
+    #[serde(default)]
+    usage: Option<TokenUsage>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -38,7 +47,10 @@ struct MessageContent {
 
 #[derive(Debug, Deserialize)]
 struct StreamChunk {
+    #[serde(default)]
     choices: Vec<StreamChoice>,
+    #[serde(default)]
+    usage: Option<TokenUsage>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -52,6 +64,57 @@ struct Delta {
     content: Option<String>,
 }
 
+/// Treść żądania wysyłanego do API (bez sieci, do testów i podglądu)
+pub fn build_request_body(
+    model: &str,
+    text_to_correct: &str,
+    instruction_prompt: &str,
+    system_prompt: &str,
+    streaming: bool,
+) -> serde_json::Value {
+    let message = user_message(instruction_prompt, text_to_correct);
+    let options = RequestOptions { streaming, ..Default::default() };
+    serde_json::to_value(build_request(model, &[], &message, system_prompt, options))
+        .expect("request is always serializable")
+}
+
+fn build_request(
+    model: &str,
+    history: &[Exchange],
+    message: &str,
+    system_prompt: &str,
+    options: RequestOptions,
+) -> ChatCompletionRequest {
+    let mut messages = vec![Message {
+        role: "system".to_string(),
+        content: system_prompt.to_string(),
+    }];
+    for exchange in history {
+        messages.push(Message {
+            role: "user".to_string(),
+            content: exchange.user.clone(),
+        });
+        messages.push(Message {
+            role: "assistant".to_string(),
+            content: exchange.assistant.clone(),
+        });
+    }
+    messages.push(Message {
+        role: "user".to_string(),
+        content: message.to_string(),
+    });
+
+    ChatCompletionRequest {
+        model: model.to_string(),
+        messages,
+        temperature: TEMPERATURE,
+        max_tokens: 4096,
+        stream: options.streaming,
+        stream_options: options.streaming.then(StreamOptions::with_usage),
+        response_format: options.json.then(ResponseFormat::json_object),
+    }
+}
+
 pub async fn correct_text_deepseek(
     api_key: &str,
     model: &str,
@@ -76,47 +139,54 @@ pub async fn correct_text_deepseek_with_callback<F>(
 where
     F: Fn(&str) + Send + 'static,
 {
+    if text_to_correct.is_empty() {
+        return Err(ApiError::Response("Text to correct is empty".to_string()));
+    }
+
+    let message = user_message(instruction_prompt, text_to_correct);
+    let options = RequestOptions { streaming, ..Default::default() };
+    converse_deepseek_with_callback(api_key, model, &[], &message, system_prompt, options, on_chunk).await
+}
+
+/// Wysyła wcześniejsze wymiany rozmowy i nową wiadomość użytkownika
+pub async fn converse_deepseek_with_callback<F>(
+    api_key: &str,
+    model: &str,
+    history: &[Exchange],
+    message: &str,
+    system_prompt: &str,
+    options: RequestOptions,
+    on_chunk: Option<F>,
+) -> Result<String, ApiError>
+where
+    F: Fn(&str) + Send + 'static,
+{
+    let streaming = options.streaming;
     if api_key.is_empty() {
         return Err(ApiError::Response("API key is empty".to_string()));
     }
     if model.is_empty() {
         return Err(ApiError::Response("Model is empty".to_string()));
     }
-    if text_to_correct.is_empty() {
-        return Err(ApiError::Response("Text to correct is empty".to_string()));
+    if message.is_empty() {
+        return Err(ApiError::Response("Message is empty".to_string()));
     }
 
     let client = get_client();
 
-    let messages = vec![
-        Message {
-            role: "system".to_string(),
-            content: system_prompt.to_string(),
-        },
-        Message {
-            role: "user".to_string(),
-            content: format!("{}\n\n---\n{}\n---", instruction_prompt, text_to_correct),
-        },
-    ];
-
-    let request = ChatCompletionRequest {
-        model: model.to_string(),
-        messages,
-        temperature: 0.7,
-        max_tokens: 4096,
-        stream: streaming,
-    };
+    let request = build_request(model, history, message, system_prompt, options);
 
     let response = client
         .post(DEEPSEEK_API_URL)
         .header("Authorization", format!("Bearer {}", api_key))
         .header("Content-Type", "application/json")
-        .json(&request)
+        .json_body(Provider::DeepSeek, &request, options)
+        .with_profile(options)
         .send()
         .await
         .map_err(|e| {
             if e.is_timeout() {
-                ApiError::Timeout(format!("Request timed out after {}s", DEEPSEEK_TIMEOUT))
+                ApiError::Timeout(format!("Request timed out after {}s", options.timeout_secs(DEEPSEEK_TIMEOUT)))
             } else if e.is_connect() {
                 ApiError::Connection(e.to_string())
             } else {
@@ -125,15 +195,11 @@ where
         })?;
 
     if !response.status().is_success() {
-        return Err(ApiError::Response(format!(
-            "HTTP {}: {}",
-            response.status(),
-            response.text().await.unwrap_or_default()
-        )));
+        return Err(status_error(response).await);
     }
 
     if streaming {
-        let mut stream = response.bytes_stream();
+        let mut stream = body_stream(Provider::DeepSeek, response);
         let mut collected_text = String::new();
         let mut buffer = String::new();
 
@@ -150,6 +216,9 @@ where
                     }
 
                     if let Ok(chunk_data) = serde_json::from_str::<StreamChunk>(data) {
+                        if let Some(usage) = chunk_data.usage {
+                            token_usage::report(usage);
+                        }
                         if let Some(choice) = chunk_data.choices.first() {
                             if let Some(content) = &choice.delta.content {
                                 collected_text.push_str(content);
@@ -170,10 +239,13 @@ where
             Ok(collected_text.trim().to_string())
         }
     } else {
-        let completion: ChatCompletionResponse = response.json().await.map_err(|e| {
+        let completion: ChatCompletionResponse = read_json(Provider::DeepSeek, response).await.map_err(|e| {
             ApiError::Response(format!("Failed to parse response: {}", e))
         })?;
 
+        if let Some(usage) = completion.usage {
+            token_usage::report(usage);
+        }
         completion
             .choices
             .first()
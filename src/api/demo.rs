@@ -0,0 +1,249 @@
+//! Dostawca demonstracyjny: powtarzalne, sztuczne poprawki bez kluczy i sieci
+//!
+//! Włączany przez `--demo` albo `DemoMode` w konfiguracji. Każdy dostawca
+//! poprawia tekst tymi samymi prostymi regułami (literówki, spacje, wielkie
+//! litery, kropka na końcu) z drobną własną odmianą i strumieniuje wynik
+//! słowo po słowie z innym tempem, żeby okno wyglądało jak przy prawdziwych API.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use crate::api::Provider;
+use crate::config::Config;
+use crate::error::ApiError;
+
+static DEMO_FLAG: AtomicBool = AtomicBool::new(false);
+
+/// Częste błędy poprawiane przez wszystkich dostawców demonstracyjnych
+const TYPOS: &[(&str, &str)] = &[
+    ("wziąść", "wziąć"),
+    ("poszłem", "poszedłem"),
+    ("napewno", "na pewno"),
+    ("narazie", "na razie"),
+    ("conajmniej", "co najmniej"),
+    ("wogóle", "w ogóle"),
+    ("spowrotem", "z powrotem"),
+    ("ktury", "który"),
+    ("teh", "the"),
+    ("recieve", "receive"),
+];
+
+/// Włącza tryb demonstracyjny niezależnie od konfiguracji (flaga `--demo`)
+pub fn enable() {
+    DEMO_FLAG.store(true, Ordering::SeqCst);
+}
+
+/// Wyłącza tryb włączony przez `enable` (np. po przykładzie z samouczka)
+pub fn disable() {
+    DEMO_FLAG.store(false, Ordering::SeqCst);
+}
+
+pub fn is_active(config: &Config) -> bool {
+    DEMO_FLAG.load(Ordering::SeqCst) || config.settings.demo_mode
+}
+
+/// Opóźnienie przed pierwszym fragmentem, jak czas odpowiedzi serwera
+pub fn first_chunk_delay(provider: Provider) -> Duration {
+    Duration::from_millis(match provider {
+        Provider::OpenAI => 400,
+        Provider::Anthropic => 700,
+        Provider::Gemini => 250,
+        Provider::DeepSeek => 1100,
+        Provider::Mistral => 500,
+        Provider::Groq => 120,
+    })
+}
+
+/// Odstęp między kolejnymi słowami strumienia
+pub fn chunk_delay(provider: Provider) -> Duration {
+    Duration::from_millis(match provider {
+        Provider::OpenAI => 40,
+        Provider::Anthropic => 60,
+        Provider::Gemini => 25,
+        Provider::DeepSeek => 80,
+        Provider::Mistral => 35,
+        Provider::Groq => 10,
+    })
+}
+
+fn fix_typo(word: &str) -> String {
+    let start = word.find(|c: char| c.is_alphanumeric()).unwrap_or(word.len());
+    let end = word
+        .rfind(|c: char| c.is_alphanumeric())
+        .map(|i| i + word[i..].chars().next().map_or(1, char::len_utf8))
+        .unwrap_or(start);
+    let core = &word[start..end];
+    let lower = core.to_lowercase();
+
+    match TYPOS.iter().find(|(typo, _)| *typo == lower) {
+        Some((_, fixed)) => {
+            let fixed = if core.chars().next().is_some_and(char::is_uppercase) {
+                capitalize(fixed)
+            } else {
+                fixed.to_string()
+            };
+            format!("{}{}{}", &word[..start], fixed, &word[end..])
+        }
+        None => word.to_string(),
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Usuwa powtórzone słowo ("to to" → "to")
+fn drop_repeated(words: Vec<String>) -> Vec<String> {
+    let mut kept: Vec<String> = Vec::with_capacity(words.len());
+    for word in words {
+        if kept.last().is_some_and(|last| last.to_lowercase() == word.to_lowercase()) {
+            continue;
+        }
+        kept.push(word);
+    }
+    kept
+}
+
+/// Zamienia proste cudzysłowy na polskie „…”
+fn polish_quotes(line: &str) -> String {
+    let mut opening = true;
+    line.chars()
+        .map(|c| {
+            if c != '"' {
+                return c;
+            }
+            let quote = if opening { '„' } else { '”' };
+            opening = !opening;
+            quote
+        })
+        .collect()
+}
+
+fn correct_line(provider: Provider, line: &str, sentence_start: &mut bool) -> String {
+    let mut words: Vec<String> = line.split_whitespace().map(fix_typo).collect();
+    if provider == Provider::DeepSeek {
+        words = drop_repeated(words);
+    }
+
+    for word in &mut words {
+        if *sentence_start && word.chars().any(char::is_alphabetic) {
+            *word = capitalize(word);
+        }
+        *sentence_start = word.ends_with(['.', '!', '?']);
+    }
+
+    let line = words.join(" ");
+    match provider {
+        Provider::Anthropic => line.replace(" - ", " – "),
+        Provider::Gemini => polish_quotes(&line),
+        _ => line,
+    }
+}
+
+/// Powtarzalna poprawka tekstu dla danego dostawcy
+pub fn correction(provider: Provider, text: &str) -> String {
+    let mut sentence_start = true;
+    let mut lines: Vec<String> = text
+        .trim()
+        .lines()
+        .map(|line| {
+            if line.trim().is_empty() {
+                sentence_start = true;
+            }
+            correct_line(provider, line, &mut sentence_start)
+        })
+        .collect();
+
+    if let Some(last) = lines.last_mut() {
+        if last.ends_with(char::is_alphanumeric) {
+            last.push('.');
+        }
+    }
+    lines.join("\n")
+}
+
+/// Jak prawdziwi dostawcy, ale bez sieci: wynik przychodzi słowo po słowie
+pub async fn correct_text_demo_with_callback<F>(
+    provider: Provider,
+    text: &str,
+    on_chunk: Option<F>,
+) -> Result<String, ApiError>
+where
+    F: Fn(&str) + Send + 'static,
+{
+    let result = correction(provider, text);
+
+    tokio::time::sleep(first_chunk_delay(provider)).await;
+    if let Some(on_chunk) = on_chunk {
+        for chunk in result.split_inclusive(char::is_whitespace) {
+            on_chunk(chunk);
+            tokio::time::sleep(chunk_delay(provider)).await;
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_correction_common_rules() {
+        let text = "ala  ma kota. napewno poszłem\ndo domu";
+        assert_eq!(
+            correction(Provider::OpenAI, text),
+            "Ala ma kota. Na pewno poszedłem\ndo domu."
+        );
+        assert_eq!(correction(Provider::OpenAI, "  "), "");
+        assert_eq!(correction(Provider::OpenAI, "Gotowe!"), "Gotowe!");
+    }
+
+    #[test]
+    fn test_correction_differs_per_provider() {
+        let text = "to to jest \"test\" - prawda";
+        assert_eq!(correction(Provider::OpenAI, text), "To to jest \"test\" - prawda.");
+        assert_eq!(correction(Provider::Anthropic, text), "To to jest \"test\" – prawda.");
+        assert_eq!(correction(Provider::Gemini, text), "To to jest „test” - prawda.");
+        assert_eq!(correction(Provider::DeepSeek, text), "To jest \"test\" - prawda.");
+        assert_eq!(correction(Provider::Mistral, text), "To to jest \"test\" - prawda.");
+        assert_eq!(correction(Provider::Groq, text), "To to jest \"test\" - prawda.");
+    }
+
+    #[test]
+    fn test_correction_is_deterministic() {
+        let text = "wogóle nie wiem, ktury to";
+        for provider in Provider::all() {
+            assert_eq!(correction(*provider, text), correction(*provider, text));
+        }
+        assert_eq!(correction(Provider::OpenAI, text), "W ogóle nie wiem, który to.");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_demo_streams_whole_result() {
+        let chunks = Arc::new(Mutex::new(Vec::new()));
+        let sink = chunks.clone();
+        let on_chunk = move |chunk: &str| sink.lock().unwrap().push(chunk.to_string());
+
+        let result = correct_text_demo_with_callback(Provider::Gemini, "ala ma kota", Some(on_chunk))
+            .await
+            .unwrap();
+
+        assert_eq!(result, "Ala ma kota.");
+        let chunks = chunks.lock().unwrap();
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks.concat(), result);
+    }
+
+    #[test]
+    fn test_is_active_from_config() {
+        let mut config = Config::default();
+        config.settings.demo_mode = true;
+        assert!(is_active(&config));
+    }
+}
@@ -1,10 +1,16 @@
-use crate::api::http_client::{get_client, get_streaming_client};
+use crate::api::http_client::{body_stream, get_client, get_streaming_client, read_json, status_error, JsonBody, WithProfile};
+use crate::api::token_usage::{self, TokenUsage};
+use crate::api::{Provider, RequestOptions};
+use crate::conversation::{user_message, Exchange};
 use crate::error::{ApiError, DEFAULT_TIMEOUT};
 use futures::StreamExt;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
-const GEMINI_API_BASE: &str = "https://generativelanguage.googleapis.com/v1beta/models";
+pub const GEMINI_API_BASE: &str = "https://generativelanguage.googleapis.com/v1beta/models";
+
+/// Budżet tokenów na "myślenie" modelu; korekta go nie potrzebuje
+pub const THINKING_BUDGET: i32 = 0;
 
 #[derive(Debug, Serialize)]
 struct GeminiRequest {
@@ -19,6 +25,8 @@ struct GeminiRequest {
 struct GenerationConfig {
     #[serde(rename = "thinkingConfig")]
     thinking_config: ThinkingConfig,
+    #[serde(rename = "responseMimeType", skip_serializing_if = "Option::is_none")]
+    response_mime_type: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -46,6 +54,29 @@ struct TextPart {
 #[derive(Debug, Deserialize)]
 struct GeminiResponse {
     candidates: Option<Vec<Candidate>>,
+    /// Przy strumieniu w każdym fragmencie, narastająco
+    #[serde(rename = "usageMetadata", default)]
+    usage_metadata: Option<UsageMetadata>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UsageMetadata {
+    #[serde(rename = "promptTokenCount", default)]
+    prompt_token_count: u64,
+    #[serde(rename = "candidatesTokenCount", default)]
+    candidates_token_count: u64,
+    #[serde(rename = "thoughtsTokenCount", default)]
+    thoughts_token_count: u64,
+}
+
+impl UsageMetadata {
+    /// Tokeny "myślenia" są płatne jak odpowiedź
+    fn token_usage(&self) -> TokenUsage {
+        TokenUsage {
+            prompt_tokens: self.prompt_token_count,
+            completion_tokens: self.candidates_token_count + self.thoughts_token_count,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -63,6 +94,44 @@ struct ContentPart {
     text: Option<String>,
 }
 
+/// Treść żądania wysyłanego do API (bez sieci, do testów i podglądu).
+/// Model i tryb strumieniowy trafiają do adresu URL, nie do treści.
+pub fn build_request_body(
+    text_to_correct: &str,
+    instruction_prompt: &str,
+    system_prompt: &str,
+) -> serde_json::Value {
+    let message = user_message(instruction_prompt, text_to_correct);
+    serde_json::to_value(build_request(&[], &message, system_prompt, RequestOptions::default()))
+        .expect("request is always serializable")
+}
+
+fn build_request(history: &[Exchange], message: &str, system_prompt: &str, options: RequestOptions) -> GeminiRequest {
+    let content = |role: &str, text: &str| GeminiContent {
+        role: role.to_string(),
+        parts: vec![TextPart { text: text.to_string() }],
+    };
+    let mut contents = Vec::new();
+    for exchange in history {
+        contents.push(content("user", &exchange.user));
+        contents.push(content("model", &exchange.assistant));
+    }
+    contents.push(content("user", message));
+
+    GeminiRequest {
+        contents,
+        system_instruction: Some(SystemInstruction {
+            parts: vec![TextPart { text: system_prompt.to_string() }],
+        }),
+        generation_config: GenerationConfig {
+            thinking_config: ThinkingConfig {
+                thinking_budget: THINKING_BUDGET,
+            },
+            response_mime_type: options.json.then(|| "application/json".to_string()),
+        },
+    }
+}
+
 pub async fn correct_text_gemini(
     api_key: &str,
     model: &str,
@@ -87,39 +156,47 @@ pub async fn correct_text_gemini_with_callback<F>(
 where
     F: Fn(&str) + Send + 'static,
 {
+    if text_to_correct.is_empty() {
+        return Err(ApiError::Response("Text to correct is empty".to_string()));
+    }
+
+    let message = user_message(instruction_prompt, text_to_correct);
+    let options = RequestOptions { streaming, ..Default::default() };
+    converse_gemini_with_callback(api_key, model, &[], &message, system_prompt, options, on_chunk).await
+}
+
+/// Wysyła wcześniejsze wymiany rozmowy i nową wiadomość użytkownika
+pub async fn converse_gemini_with_callback<F>(
+    api_key: &str,
+    model: &str,
+    history: &[Exchange],
+    message: &str,
+    system_prompt: &str,
+    options: RequestOptions,
+    on_chunk: Option<F>,
+) -> Result<String, ApiError>
+where
+    F: Fn(&str) + Send + 'static,
+{
+    let streaming = options.streaming;
     if api_key.is_empty() {
         return Err(ApiError::Response("API key is empty".to_string()));
     }
     if model.is_empty() {
         return Err(ApiError::Response("Model is empty".to_string()));
     }
-    if text_to_correct.is_empty() {
-        return Err(ApiError::Response("Text to correct is empty".to_string()));
+    if message.is_empty() {
+        return Err(ApiError::Response("Message is empty".to_string()));
     }
 
     let client = if streaming { get_streaming_client() } else { get_client() };
 
-    let user_content = format!("{}\n\n---\n{}\n---", instruction_prompt, text_to_correct);
-
-    let request = GeminiRequest {
-        contents: vec![GeminiContent {
-            role: "user".to_string(),
-            parts: vec![TextPart { text: user_content }],
-        }],
-        system_instruction: Some(SystemInstruction {
-            parts: vec![TextPart { text: system_prompt.to_string() }],
-        }),
-        generation_config: GenerationConfig {
-            thinking_config: ThinkingConfig {
-                thinking_budget: 0,
-            },
-        },
-    };
+    let request = build_request(history, message, system_prompt, options);
 
     if streaming {
-        stream_gemini_request_with_callback(client, api_key, model, request, on_chunk).await
+        stream_gemini_request_with_callback(&client, api_key, model, request, options, on_chunk).await
     } else {
-        batch_gemini_request(client, api_key, model, request).await
+        batch_gemini_request(&client, api_key, model, request, options).await
     }
 }
 
@@ -128,18 +205,20 @@ async fn batch_gemini_request(
     api_key: &str,
     model: &str,
     request: GeminiRequest,
+    options: RequestOptions,
 ) -> Result<String, ApiError> {
     let url = format!("{}/{}:generateContent?key={}", GEMINI_API_BASE, model, api_key);
 
     let response = client
         .post(&url)
         .header("Content-Type", "application/json")
-        .json(&request)
+        .json_body(Provider::Gemini, &request, options)
+        .with_profile(options)
         .send()
         .await
         .map_err(|e| {
             if e.is_timeout() {
-                ApiError::Timeout(format!("Request timed out after {}s", DEFAULT_TIMEOUT))
+                ApiError::Timeout(format!("Request timed out after {}s", options.timeout_secs(DEFAULT_TIMEOUT)))
             } else if e.is_connect() {
                 ApiError::Connection(e.to_string())
             } else {
@@ -148,17 +227,16 @@ async fn batch_gemini_request(
         })?;
 
     if !response.status().is_success() {
-        return Err(ApiError::Response(format!(
-            "HTTP {}: {}",
-            response.status(),
-            response.text().await.unwrap_or_default()
-        )));
+        return Err(status_error(response).await);
     }
 
-    let completion: GeminiResponse = response.json().await.map_err(|e| {
+    let completion: GeminiResponse = read_json(Provider::Gemini, response).await.map_err(|e| {
         ApiError::Response(format!("Failed to parse response: {}", e))
     })?;
 
+    if let Some(metadata) = &completion.usage_metadata {
+        token_usage::report(metadata.token_usage());
+    }
     completion
         .candidates
         .and_then(|c| c.into_iter().next())
@@ -175,6 +253,7 @@ async fn stream_gemini_request_with_callback<F>(
     api_key: &str,
     model: &str,
     request: GeminiRequest,
+    options: RequestOptions,
     on_chunk: Option<F>,
 ) -> Result<String, ApiError>
 where
@@ -185,12 +264,13 @@ where
     let response = client
         .post(&url)
         .header("Content-Type", "application/json")
-        .json(&request)
+        .json_body(Provider::Gemini, &request, options)
+        .with_profile(options)
         .send()
         .await
         .map_err(|e| {
             if e.is_timeout() {
-                ApiError::Timeout(format!("Request timed out after {}s", DEFAULT_TIMEOUT))
+                ApiError::Timeout(format!("Request timed out after {}s", options.timeout_secs(DEFAULT_TIMEOUT)))
             } else if e.is_connect() {
                 ApiError::Connection(e.to_string())
             } else {
@@ -199,16 +279,13 @@ where
         })?;
 
     if !response.status().is_success() {
-        return Err(ApiError::Response(format!(
-            "HTTP {}: {}",
-            response.status(),
-            response.text().await.unwrap_or_default()
-        )));
+        return Err(status_error(response).await);
     }
 
-    let mut stream = response.bytes_stream();
+    let mut stream = body_stream(Provider::Gemini, response);
     let mut collected_text = String::new();
     let mut buffer = String::new();
+    let mut usage = None;
 
     while let Some(chunk_result) = stream.next().await {
         let chunk = chunk_result.map_err(|e| ApiError::Response(e.to_string()))?;
@@ -223,6 +300,9 @@ where
                 }
 
                 if let Ok(resp) = serde_json::from_str::<GeminiResponse>(data) {
+                    if let Some(metadata) = &resp.usage_metadata {
+                        usage = Some(metadata.token_usage());
+                    }
                     if let Some(candidates) = resp.candidates {
                         if let Some(candidate) = candidates.first() {
                             if let Some(content) = &candidate.content {
@@ -248,6 +328,9 @@ where
         buffer.clear();
     }
 
+    if let Some(usage) = usage {
+        token_usage::report(usage);
+    }
     if collected_text.is_empty() {
         Err(ApiError::Response("No content in streaming response".to_string()))
     } else {
@@ -259,6 +342,33 @@ where
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_request_carries_conversation_history() {
+        let history = vec![Exchange {
+            user: user_message("Correct", "ala ma kota"),
+            assistant: "Ala ma kota.".to_string(),
+        }];
+        let options = RequestOptions::default();
+        let request = serde_json::to_value(build_request(&history, "krócej", "System", options)).unwrap();
+
+        assert_eq!(request["contents"][0]["role"], "user");
+        assert_eq!(request["contents"][1]["role"], "model");
+        assert_eq!(request["contents"][1]["parts"][0]["text"], "Ala ma kota.");
+        assert_eq!(request["contents"][2]["parts"][0]["text"], "krócej");
+    }
+
+    #[test]
+    fn test_usage_counts_thoughts_as_output() {
+        let response: GeminiResponse = serde_json::from_str(
+            r#"{"candidates": [], "usageMetadata": {"promptTokenCount": 50, "candidatesTokenCount": 20, "thoughtsTokenCount": 7, "totalTokenCount": 77}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            response.usage_metadata.unwrap().token_usage(),
+            TokenUsage { prompt_tokens: 50, completion_tokens: 27 }
+        );
+    }
+
     #[tokio::test]
     async fn test_gemini_empty_api_key() {
         let result = correct_text_gemini(
@@ -1,42 +1,404 @@
+use flate2::write::{GzDecoder, GzEncoder};
+use flate2::Compression;
+use futures_util::{Stream, StreamExt};
 use once_cell::sync::Lazy;
-use reqwest::Client;
-use std::net::{IpAddr, Ipv4Addr};
-use std::time::Duration;
-
-use crate::error::{CONNECTION_TIMEOUT, DEFAULT_TIMEOUT};
-
-pub static SHARED_CLIENT: Lazy<Client> = Lazy::new(|| {
-    Client::builder()
-        .timeout(Duration::from_secs(DEFAULT_TIMEOUT))
-        .connect_timeout(Duration::from_secs(CONNECTION_TIMEOUT))
-        .pool_max_idle_per_host(10)
-        .pool_idle_timeout(Duration::from_secs(30))
-        .tcp_keepalive(Duration::from_secs(30))
-        .tcp_nodelay(true)
-        // Force IPv4 to avoid IPv6 connection issues
-        .local_address(IpAddr::V4(Ipv4Addr::UNSPECIFIED))
-        .build()
-        .expect("Failed to create HTTP client")
-});
-
-pub static STREAMING_CLIENT: Lazy<Client> = Lazy::new(|| {
-    Client::builder()
-        .connect_timeout(Duration::from_secs(CONNECTION_TIMEOUT))
-        .read_timeout(Duration::from_secs(120))
-        .pool_max_idle_per_host(10)
-        .pool_idle_timeout(Duration::from_secs(90))
-        .tcp_keepalive(Duration::from_secs(30))
-        .tcp_nodelay(true)
-        // Force IPv4 to avoid IPv6 connection issues
-        .local_address(IpAddr::V4(Ipv4Addr::UNSPECIFIED))
-        .build()
-        .expect("Failed to create streaming HTTP client")
-});
-
-pub fn get_client() -> &'static Client {
-    &SHARED_CLIENT
-}
-
-pub fn get_streaming_client() -> &'static Client {
-    &STREAMING_CLIENT
+use reqwest::header::{ACCEPT_ENCODING, CONTENT_ENCODING, RETRY_AFTER};
+use reqwest::{Client, ClientBuilder, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, SystemTime};
+
+use crate::api::{rate_limit, Provider, RequestOptions};
+use crate::config::Network;
+use crate::error::{ApiError, CONNECTION_TIMEOUT, DEFAULT_TIMEOUT, QUICK_TIMEOUT};
+
+/// Klienci HTTP zbudowani dla ustawień sieci; przebudowywani, gdy ustawienia się zmienią
+struct Clients {
+    network: Network,
+    /// Hosty z poprawnym nadpisaniem DNS (po odrzuceniu błędnych adresów)
+    overridden_hosts: Vec<String>,
+    shared: Client,
+    streaming: Client,
+}
+
+impl Clients {
+    fn build(network: &Network) -> Self {
+        let local_address = local_address(&network.ip_version);
+        let overrides = dns_overrides(&network.dns_overrides);
+        for (host, addrs) in &overrides {
+            let ips: Vec<String> = addrs.iter().map(|a| a.ip().to_string()).collect();
+            tracing::info!("DNS override: {} -> {}", host, ips.join(", "));
+        }
+        let shared = Client::builder()
+            .timeout(Duration::from_secs(DEFAULT_TIMEOUT))
+            .connect_timeout(Duration::from_secs(CONNECTION_TIMEOUT))
+            .pool_max_idle_per_host(10)
+            .pool_idle_timeout(Duration::from_secs(30))
+            .tcp_keepalive(Duration::from_secs(30))
+            .tcp_nodelay(true)
+            .local_address(local_address);
+        let shared = with_overrides(shared, &overrides)
+            .build()
+            .expect("Failed to create HTTP client");
+        let streaming = Client::builder()
+            .connect_timeout(Duration::from_secs(CONNECTION_TIMEOUT))
+            .read_timeout(Duration::from_secs(120))
+            .pool_max_idle_per_host(10)
+            .pool_idle_timeout(Duration::from_secs(90))
+            .tcp_keepalive(Duration::from_secs(30))
+            .tcp_nodelay(true)
+            .local_address(local_address);
+        let streaming = with_overrides(streaming, &overrides)
+            .build()
+            .expect("Failed to create streaming HTTP client");
+        Self {
+            network: network.clone(),
+            overridden_hosts: overrides.into_iter().map(|(host, _)| host).collect(),
+            shared,
+            streaming,
+        }
+    }
+}
+
+static CLIENTS: Lazy<RwLock<Clients>> = Lazy::new(|| RwLock::new(Clients::build(&Network::default())));
+
+/// Adres lokalny wymuszający rodzinę adresów: "ipv4", "ipv6", a przy "auto"
+/// (i nieznanej wartości) brak - klient próbuje obu naraz (happy eyeballs)
+pub fn local_address(ip_version: &str) -> Option<IpAddr> {
+    match ip_version.trim().to_ascii_lowercase().as_str() {
+        "ipv4" => Some(IpAddr::V4(Ipv4Addr::UNSPECIFIED)),
+        "ipv6" => Some(IpAddr::V6(Ipv6Addr::UNSPECIFIED)),
+        _ => None,
+    }
+}
+
+/// Nadpisania DNS z konfiguracji; błędne adresy są pomijane z ostrzeżeniem.
+/// Port jest zerowy, bo i tak obowiązuje port z adresu URL.
+pub fn dns_overrides(overrides: &BTreeMap<String, String>) -> Vec<(String, Vec<SocketAddr>)> {
+    overrides
+        .iter()
+        .filter_map(|(host, ips)| {
+            let addrs: Vec<SocketAddr> = ips
+                .split(',')
+                .map(str::trim)
+                .filter(|ip| !ip.is_empty())
+                .filter_map(|ip| match ip.parse::<IpAddr>() {
+                    Ok(ip) => Some(SocketAddr::new(ip, 0)),
+                    Err(_) => {
+                        tracing::warn!("Ignoring invalid DNS override for {}: {}", host, ip);
+                        None
+                    }
+                })
+                .collect();
+            let host = host.trim().to_ascii_lowercase();
+            (!host.is_empty() && !addrs.is_empty()).then_some((host, addrs))
+        })
+        .collect()
+}
+
+fn with_overrides(builder: ClientBuilder, overrides: &[(String, Vec<SocketAddr>)]) -> ClientBuilder {
+    overrides
+        .iter()
+        .fold(builder, |builder, (host, addrs)| builder.resolve_to_addrs(host, addrs))
+}
+
+/// Czy dla hosta działa nadpisanie DNS (do logów przy każdym żądaniu); wpisy
+/// bez żadnego poprawnego adresu się nie liczą
+pub fn has_dns_override(host: &str) -> bool {
+    CLIENTS
+        .read()
+        .unwrap()
+        .overridden_hosts
+        .iter()
+        .any(|h| h.eq_ignore_ascii_case(host))
+}
+
+/// Przebudowuje klientów, jeśli ustawienia sieci różnią się od bieżących
+pub fn configure(network: &Network) {
+    if CLIENTS.read().unwrap().network == *network {
+        return;
+    }
+    let mut clients = CLIENTS.write().unwrap();
+    if clients.network != *network {
+        tracing::info!("HTTP clients rebuilt, IP version: {}", network.ip_version);
+        *clients = Clients::build(network);
+    }
+}
+
+pub fn get_client() -> Client {
+    CLIENTS.read().unwrap().shared.clone()
+}
+
+pub fn get_streaming_client() -> Client {
+    CLIENTS.read().unwrap().streaming.clone()
+}
+
+/// Profil limitów czasu żądania: tryb szybki skraca limit każdego żądania,
+/// w zwykłym obowiązują limity klienta
+pub trait WithProfile {
+    fn with_profile(self, options: RequestOptions) -> Self;
+}
+
+impl WithProfile for RequestBuilder {
+    fn with_profile(self, options: RequestOptions) -> Self {
+        if options.quick {
+            self.timeout(Duration::from_secs(QUICK_TIMEOUT))
+        } else {
+            self
+        }
+    }
+}
+
+/// Od jakiego rozmiaru treść żądania jest kompresowana
+pub const COMPRESS_MIN_BYTES: usize = 8 * 1024;
+
+/// Rozmiar ostatniej wymiany z dostawcą (do okna surowej odpowiedzi)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Transfer {
+    /// Treść żądania przed kompresją
+    pub request_bytes: usize,
+    /// Treść żądania po kompresji gzip; `None`, gdy poszła bez kompresji
+    pub compressed_bytes: Option<usize>,
+    /// Odpowiedź tak, jak przyszła z sieci (przed rozpakowaniem gzip)
+    pub response_bytes: usize,
+}
+
+impl Transfer {
+    /// Opis w rodzaju "wysłano 2,1 kB (gzip z 9,8 kB), odebrano 1,4 kB"
+    pub fn summary(&self) -> String {
+        let sent = match self.compressed_bytes {
+            Some(compressed) => format!("{} (gzip z {})", format_bytes(compressed), format_bytes(self.request_bytes)),
+            None => format_bytes(self.request_bytes),
+        };
+        format!("wysłano {}, odebrano {}", sent, format_bytes(self.response_bytes))
+    }
+}
+
+pub fn format_bytes(bytes: usize) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} kB", bytes as f64 / 1024.0).replace('.', ",")
+    }
+}
+
+static TRANSFERS: Lazy<Mutex<HashMap<&'static str, Transfer>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Rozmiary ostatniego żądania do dostawcy
+pub fn last_transfer(provider: Provider) -> Option<Transfer> {
+    TRANSFERS.lock().unwrap().get(provider.name()).copied()
+}
+
+/// Dolicza odebrane bajty (strumień przychodzi kawałkami)
+pub fn record_received(provider: Provider, bytes: usize) {
+    if let Some(transfer) = TRANSFERS.lock().unwrap().get_mut(provider.name()) {
+        transfer.response_bytes += bytes;
+    }
+}
+
+pub fn gzip(data: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data)?;
+    encoder.finish()
+}
+
+/// Treść JSON żądania: duża i u dostawcy przyjmującego kompresję idzie jako gzip.
+/// Zapisuje rozmiary dla `last_transfer`.
+pub trait JsonBody {
+    fn json_body<T: Serialize + ?Sized>(self, provider: Provider, body: &T, options: RequestOptions) -> Self;
+}
+
+impl JsonBody for RequestBuilder {
+    fn json_body<T: Serialize + ?Sized>(self, provider: Provider, body: &T, options: RequestOptions) -> Self {
+        let Ok(json) = serde_json::to_vec(body) else {
+            return self.json(body);
+        };
+        let compressed = if options.compress && json.len() >= COMPRESS_MIN_BYTES {
+            gzip(&json)
+                .inspect_err(|e| tracing::warn!("Request compression failed, sending uncompressed: {}", e))
+                .ok()
+        } else {
+            None
+        };
+
+        let transfer = Transfer {
+            request_bytes: json.len(),
+            compressed_bytes: compressed.as_ref().map(Vec::len),
+            response_bytes: 0,
+        };
+        TRANSFERS.lock().unwrap().insert(provider.name(), transfer);
+        tracing::debug!("{} request body: {}", provider.name(), transfer.summary());
+
+        // Odpowiedź też w gzip; rozpakowuje ją `BodyDecoder`
+        let builder = self.header(ACCEPT_ENCODING, "gzip");
+        match compressed {
+            Some(compressed) => builder.header(CONTENT_ENCODING, "gzip").body(compressed),
+            None => builder.body(json),
+        }
+    }
+}
+
+/// Rozpakowuje odpowiedź gzip kawałek po kawałku. Odpowiedzi rozpakowujemy
+/// sami, a nie w kliencie, bo klient ukrywa rozmiar przesłanych danych.
+pub struct BodyDecoder {
+    gzip: Option<GzDecoder<Vec<u8>>>,
+}
+
+impl BodyDecoder {
+    pub fn new(response: &Response) -> Self {
+        let gzip = response
+            .headers()
+            .get(CONTENT_ENCODING)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.trim().eq_ignore_ascii_case("gzip"));
+        Self::with_gzip(gzip)
+    }
+
+    fn with_gzip(gzip: bool) -> Self {
+        Self {
+            gzip: gzip.then(|| GzDecoder::new(Vec::new())),
+        }
+    }
+
+    /// Rozpakowana część kawałka (przy gzip może być pusta, dopóki nie dojdzie reszta)
+    pub fn decode(&mut self, chunk: &[u8]) -> std::io::Result<Vec<u8>> {
+        match &mut self.gzip {
+            Some(decoder) => {
+                decoder.write_all(chunk)?;
+                decoder.flush()?;
+                Ok(std::mem::take(decoder.get_mut()))
+            }
+            None => Ok(chunk.to_vec()),
+        }
+    }
+}
+
+/// Strumień odpowiedzi: dolicza bajty z sieci i oddaje je rozpakowane
+pub fn body_stream(provider: Provider, response: Response) -> impl Stream<Item = Result<Vec<u8>, String>> {
+    let mut decoder = BodyDecoder::new(&response);
+    response.bytes_stream().map(move |chunk| {
+        let chunk = chunk.map_err(|e| e.to_string())?;
+        record_received(provider, chunk.len());
+        decoder.decode(&chunk).map_err(|e| format!("Invalid gzip response: {}", e))
+    })
+}
+
+/// Cała treść odpowiedzi po rozpakowaniu; bajty z sieci dolicza do `provider`
+async fn read_body(provider: Option<Provider>, response: Response) -> Result<Vec<u8>, String> {
+    let mut decoder = BodyDecoder::new(&response);
+    let body = response.bytes().await.map_err(|e| e.to_string())?;
+    if let Some(provider) = provider {
+        record_received(provider, body.len());
+    }
+    decoder.decode(&body).map_err(|e| format!("Invalid gzip response: {}", e))
+}
+
+/// Odczytuje odpowiedź JSON i dolicza jej rozmiar
+pub async fn read_json<T: DeserializeOwned>(provider: Provider, response: Response) -> Result<T, String> {
+    let body = read_body(Some(provider), response).await?;
+    serde_json::from_slice(&body).map_err(|e| e.to_string())
+}
+
+/// Błąd odpowiedzi spoza 2xx; 429 z czasem z `Retry-After` i treścią trafia do kolejki limitów
+pub async fn status_error(response: Response) -> ApiError {
+    let status = response.status();
+    let retry_after = response
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| rate_limit::parse_retry_after(value, SystemTime::now()));
+    let body = read_body(None, response).await.unwrap_or_default();
+    let body = String::from_utf8_lossy(&body).into_owned();
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        return ApiError::RateLimited(retry_after, body);
+    }
+    ApiError::Response(format!("HTTP {}: {}", status, body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    #[test]
+    fn test_gzip_roundtrip() {
+        let json = serde_json::to_vec(&serde_json::json!({ "text": "Ala ma kota. ".repeat(1000) })).unwrap();
+        let compressed = gzip(&json).unwrap();
+        assert!(compressed.len() < json.len() / 10);
+
+        let mut decoded = Vec::new();
+        GzDecoder::new(compressed.as_slice()).read_to_end(&mut decoded).unwrap();
+        assert_eq!(decoded, json);
+    }
+
+    #[test]
+    fn test_body_decoder_streams_gzip_chunks() {
+        let text = "data: {\"text\": \"Ala ma kota.\"}\n\n".repeat(500);
+        let compressed = gzip(text.as_bytes()).unwrap();
+
+        let mut decoder = BodyDecoder::with_gzip(true);
+        let mut decoded = Vec::new();
+        let mut chunks = compressed.chunks(64);
+        decoded.extend(decoder.decode(chunks.next().unwrap()).unwrap());
+        assert!(!decoded.is_empty(), "first chunk should already yield text");
+        for chunk in chunks {
+            decoded.extend(decoder.decode(chunk).unwrap());
+        }
+        assert_eq!(String::from_utf8(decoded).unwrap(), text);
+
+        let mut plain = BodyDecoder::with_gzip(false);
+        assert_eq!(plain.decode(b"abc").unwrap(), b"abc");
+    }
+
+    #[test]
+    fn test_local_address() {
+        assert_eq!(local_address("auto"), None);
+        assert_eq!(local_address(""), None);
+        assert_eq!(local_address("IPv4"), Some(IpAddr::V4(Ipv4Addr::UNSPECIFIED)));
+        assert_eq!(local_address("ipv6"), Some(IpAddr::V6(Ipv6Addr::UNSPECIFIED)));
+    }
+
+    #[test]
+    fn test_dns_overrides() {
+        let config = BTreeMap::from([
+            ("API.openai.com".to_string(), "162.159.140.245, 2606:4700::6812:1".to_string()),
+            ("api.anthropic.com".to_string(), "not-an-ip".to_string()),
+        ]);
+        let overrides = dns_overrides(&config);
+
+        assert_eq!(overrides.len(), 1);
+        assert_eq!(overrides[0].0, "api.openai.com");
+        let ips: Vec<String> = overrides[0].1.iter().map(|a| a.ip().to_string()).collect();
+        assert_eq!(ips, ["162.159.140.245", "2606:4700::6812:1"]);
+        assert!(overrides[0].1.iter().all(|a| a.port() == 0));
+    }
+
+    #[test]
+    fn test_has_dns_override_ignores_invalid_entries() {
+        let network = Network {
+            dns_overrides: BTreeMap::from([
+                ("API.openai.com".to_string(), "162.159.140.245".to_string()),
+                ("api.anthropic.com".to_string(), "not-an-ip".to_string()),
+            ]),
+            ..Network::default()
+        };
+        configure(&network);
+
+        assert!(has_dns_override("api.openai.com"));
+        assert!(!has_dns_override("api.anthropic.com"));
+        assert!(!has_dns_override("api.mistral.ai"));
+    }
+
+    #[test]
+    fn test_transfer_summary() {
+        let plain = Transfer { request_bytes: 512, compressed_bytes: None, response_bytes: 2048 };
+        assert_eq!(plain.summary(), "wysłano 512 B, odebrano 2,0 kB");
+        let compressed = Transfer { request_bytes: 10_240, compressed_bytes: Some(1_536), response_bytes: 300 };
+        assert_eq!(compressed.summary(), "wysłano 1,5 kB (gzip z 10,0 kB), odebrano 300 B");
+    }
 }
@@ -0,0 +1,283 @@
+use crate::api::http_client::{body_stream, get_client, get_streaming_client, read_json, status_error, JsonBody, WithProfile};
+use crate::api::token_usage::{self, TokenUsage};
+use crate::api::{Provider, RequestOptions, ResponseFormat, TEMPERATURE};
+use crate::conversation::{user_message, Exchange};
+use crate::error::{ApiError, DEFAULT_TIMEOUT};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+
+pub const MISTRAL_API_URL: &str = "https://api.mistral.ai/v1/chat/completions";
+
+#[derive(Debug, Serialize)]
+struct ChatCompletionRequest {
+    model: String,
+    messages: Vec<Message>,
+    temperature: f32,
+    max_tokens: u32,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<ResponseFormat>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct Message {
+    role: String,
+    content: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionResponse {
+    choices: Vec<Choice>,
+    #[serde(default)]
+    usage: Option<TokenUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Choice {
+    message: MessageContent,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageContent {
+    content: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamChunk {
+    #[serde(default)]
+    choices: Vec<StreamChoice>,
+    #[serde(default)]
+    usage: Option<TokenUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamChoice {
+    delta: Delta,
+}
+
+#[derive(Debug, Deserialize)]
+struct Delta {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+/// Treść żądania wysyłanego do API (bez sieci, do testów i podglądu)
+pub fn build_request_body(
+    model: &str,
+    text_to_correct: &str,
+    instruction_prompt: &str,
+    system_prompt: &str,
+    streaming: bool,
+) -> serde_json::Value {
+    let message = user_message(instruction_prompt, text_to_correct);
+    let options = RequestOptions { streaming, ..Default::default() };
+    serde_json::to_value(build_request(model, &[], &message, system_prompt, options))
+        .expect("request is always serializable")
+}
+
+fn build_request(
+    model: &str,
+    history: &[Exchange],
+    message: &str,
+    system_prompt: &str,
+    options: RequestOptions,
+) -> ChatCompletionRequest {
+    let mut messages = vec![Message {
+        role: "system".to_string(),
+        content: system_prompt.to_string(),
+    }];
+    for exchange in history {
+        messages.push(Message {
+            role: "user".to_string(),
+            content: exchange.user.clone(),
+        });
+        messages.push(Message {
+            role: "assistant".to_string(),
+            content: exchange.assistant.clone(),
+        });
+    }
+    messages.push(Message {
+        role: "user".to_string(),
+        content: message.to_string(),
+    });
+
+    ChatCompletionRequest {
+        model: model.to_string(),
+        messages,
+        temperature: TEMPERATURE,
+        max_tokens: 4096,
+        stream: options.streaming,
+        response_format: options.json.then(ResponseFormat::json_object),
+    }
+}
+
+pub async fn correct_text_mistral(
+    api_key: &str,
+    model: &str,
+    text_to_correct: &str,
+    instruction_prompt: &str,
+    system_prompt: &str,
+) -> Result<String, ApiError> {
+    correct_text_mistral_with_callback::<fn(&str)>(
+        api_key, model, text_to_correct, instruction_prompt, system_prompt, true, None
+    ).await
+}
+
+pub async fn correct_text_mistral_with_callback<F>(
+    api_key: &str,
+    model: &str,
+    text_to_correct: &str,
+    instruction_prompt: &str,
+    system_prompt: &str,
+    streaming: bool,
+    on_chunk: Option<F>,
+) -> Result<String, ApiError>
+where
+    F: Fn(&str) + Send + 'static,
+{
+    if text_to_correct.is_empty() {
+        return Err(ApiError::Response("Text to correct is empty".to_string()));
+    }
+
+    let message = user_message(instruction_prompt, text_to_correct);
+    let options = RequestOptions { streaming, ..Default::default() };
+    converse_mistral_with_callback(api_key, model, &[], &message, system_prompt, options, on_chunk).await
+}
+
+/// Wysyła wcześniejsze wymiany rozmowy i nową wiadomość użytkownika
+pub async fn converse_mistral_with_callback<F>(
+    api_key: &str,
+    model: &str,
+    history: &[Exchange],
+    message: &str,
+    system_prompt: &str,
+    options: RequestOptions,
+    on_chunk: Option<F>,
+) -> Result<String, ApiError>
+where
+    F: Fn(&str) + Send + 'static,
+{
+    let streaming = options.streaming;
+    if api_key.is_empty() {
+        return Err(ApiError::Response("API key is empty".to_string()));
+    }
+    if model.is_empty() {
+        return Err(ApiError::Response("Model is empty".to_string()));
+    }
+    if message.is_empty() {
+        return Err(ApiError::Response("Message is empty".to_string()));
+    }
+
+    let client = if streaming { get_streaming_client() } else { get_client() };
+
+    let request = build_request(model, history, message, system_prompt, options);
+
+    let response = client
+        .post(MISTRAL_API_URL)
+        .header("Authorization", format!("Bearer {}", api_key))
+        .header("Content-Type", "application/json")
+        .json_body(Provider::Mistral, &request, options)
+        .with_profile(options)
+        .send()
+        .await
+        .map_err(|e| {
+            if e.is_timeout() {
+                ApiError::Timeout(format!("Request timed out after {}s", options.timeout_secs(DEFAULT_TIMEOUT)))
+            } else if e.is_connect() {
+                ApiError::Connection(e.to_string())
+            } else {
+                ApiError::Response(e.to_string())
+            }
+        })?;
+
+    if !response.status().is_success() {
+        return Err(status_error(response).await);
+    }
+
+    if streaming {
+        let mut stream = body_stream(Provider::Mistral, response);
+        let mut collected_text = String::new();
+        let mut buffer = String::new();
+
+        while let Some(chunk_result) = stream.next().await {
+            let chunk = chunk_result.map_err(|e| ApiError::Response(e.to_string()))?;
+            let chunk_str = String::from_utf8_lossy(&chunk);
+            buffer.push_str(&chunk_str);
+
+            for line in buffer.lines() {
+                if line.starts_with("data: ") {
+                    let data = &line[6..];
+                    if data.trim() == "[DONE]" {
+                        break;
+                    }
+
+                    if let Ok(chunk_data) = serde_json::from_str::<StreamChunk>(data) {
+                        if let Some(usage) = chunk_data.usage {
+                            token_usage::report(usage);
+                        }
+                        if let Some(choice) = chunk_data.choices.first() {
+                            if let Some(content) = &choice.delta.content {
+                                collected_text.push_str(content);
+                                if let Some(ref callback) = on_chunk {
+                                    callback(content);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            buffer.clear();
+        }
+
+        if collected_text.is_empty() {
+            Err(ApiError::Response("No content in streaming response".to_string()))
+        } else {
+            Ok(collected_text.trim().to_string())
+        }
+    } else {
+        let completion: ChatCompletionResponse = read_json(Provider::Mistral, response).await.map_err(|e| {
+            ApiError::Response(format!("Failed to parse response: {}", e))
+        })?;
+
+        if let Some(usage) = completion.usage {
+            token_usage::report(usage);
+        }
+        completion
+            .choices
+            .first()
+            .map(|choice| choice.message.content.trim().to_string())
+            .ok_or_else(|| ApiError::Response("No choices in response".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_request_body() {
+        let body = build_request_body("mistral-small-latest", "tekst", "Correct", "System", true);
+        assert_eq!(body["model"], "mistral-small-latest");
+        assert_eq!(body["messages"][0]["role"], "system");
+        assert_eq!(body["messages"][1]["content"], user_message("Correct", "tekst"));
+        assert_eq!(body["stream"], true);
+        // Mistral dołącza zużycie do ostatniego fragmentu bez prośby
+        assert!(body.get("stream_options").is_none());
+        assert!(body.get("response_format").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_mistral_empty_api_key() {
+        let result = correct_text_mistral(
+            "",
+            "mistral-small-latest",
+            "test text",
+            "Correct this",
+            "You are a helpful assistant",
+        )
+        .await;
+
+        assert!(result.is_err());
+    }
+}
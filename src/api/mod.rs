@@ -3,6 +3,23 @@ pub mod openai;
 pub mod anthropic;
 pub mod gemini;
 pub mod deepseek;
+pub mod mistral;
+pub mod groq;
+pub mod demo;
+pub mod rate_limit;
+pub mod registry;
+pub mod token_usage;
+
+use crate::config::{Config, ReasoningEffort};
+use crate::conversation::{user_message, Exchange};
+use crate::edits;
+use crate::error::{ApiError, QUICK_RETRIES, QUICK_TIMEOUT};
+use crate::structured::{self, StructuredResult};
+use crate::subject;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Provider {
@@ -10,15 +27,515 @@ pub enum Provider {
     Anthropic,
     Gemini,
     DeepSeek,
+    Mistral,
+    Groq,
 }
 
 impl Provider {
+    pub fn all() -> &'static [Provider] {
+        &[
+            Provider::OpenAI,
+            Provider::Anthropic,
+            Provider::Gemini,
+            Provider::DeepSeek,
+            Provider::Mistral,
+            Provider::Groq,
+        ]
+    }
+
+    /// Wpis dostawcy w `registry::PROVIDERS`
+    pub fn info(&self) -> &'static registry::ProviderInfo {
+        let index = match self {
+            Provider::OpenAI => 0,
+            Provider::Anthropic => 1,
+            Provider::Gemini => 2,
+            Provider::DeepSeek => 3,
+            Provider::Mistral => 4,
+            Provider::Groq => 5,
+        };
+        &registry::PROVIDERS[index]
+    }
+
     pub fn name(&self) -> &'static str {
+        self.info().name
+    }
+
+    pub fn api_key<'a>(&self, config: &'a Config) -> &'a str {
+        match self {
+            Provider::OpenAI => &config.api_keys.openai,
+            Provider::Anthropic => &config.api_keys.anthropic,
+            Provider::Gemini => &config.api_keys.gemini,
+            Provider::DeepSeek => &config.api_keys.deepseek,
+            Provider::Mistral => &config.api_keys.mistral,
+            Provider::Groq => &config.api_keys.groq,
+        }
+    }
+
+    pub fn model<'a>(&self, config: &'a Config) -> &'a str {
         match self {
-            Provider::OpenAI => "OpenAI",
-            Provider::Anthropic => "Anthropic",
-            Provider::Gemini => "Gemini",
-            Provider::DeepSeek => "DeepSeek",
+            Provider::OpenAI => &config.models.openai,
+            Provider::Anthropic => &config.models.anthropic,
+            Provider::Gemini => &config.models.gemini,
+            Provider::DeepSeek => &config.models.deepseek,
+            Provider::Mistral => &config.models.mistral,
+            Provider::Groq => &config.models.groq,
+        }
+    }
+
+    /// Znane modele dostawcy do wyboru przy ponownym zapytaniu
+    pub fn known_models(&self) -> &'static [&'static str] {
+        self.info().known_models
+    }
+
+    /// Model z konfiguracji, a po nim pozostałe znane modele
+    pub fn model_choices(&self, config: &Config) -> Vec<String> {
+        let current = self.model(config);
+        std::iter::once(current)
+            .chain(self.known_models().iter().copied().filter(|m| *m != current))
+            .filter(|m| !m.trim().is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    pub fn set_api_key(&self, config: &mut Config, key: &str) {
+        let slot = match self {
+            Provider::OpenAI => &mut config.api_keys.openai,
+            Provider::Anthropic => &mut config.api_keys.anthropic,
+            Provider::Gemini => &mut config.api_keys.gemini,
+            Provider::DeepSeek => &mut config.api_keys.deepseek,
+            Provider::Mistral => &mut config.api_keys.mistral,
+            Provider::Groq => &mut config.api_keys.groq,
+        };
+        *slot = key.to_string();
+    }
+
+    pub fn set_model(&self, config: &mut Config, model: &str) {
+        let slot = match self {
+            Provider::OpenAI => &mut config.models.openai,
+            Provider::Anthropic => &mut config.models.anthropic,
+            Provider::Gemini => &mut config.models.gemini,
+            Provider::DeepSeek => &mut config.models.deepseek,
+            Provider::Mistral => &mut config.models.mistral,
+            Provider::Groq => &mut config.models.groq,
+        };
+        *slot = model.to_string();
+    }
+
+    /// Host API dostawcy (klucz nadpisań DNS)
+    pub fn host(&self) -> &'static str {
+        self.info().host
+    }
+
+    /// Czy API przyjmuje treść żądania skompresowaną gzipem (`Content-Encoding: gzip`)
+    pub fn accepts_compressed_requests(&self) -> bool {
+        self.info().compressed_requests
+    }
+
+    /// Czy dostawca może odpowiedzieć: ma klucz albo działa tryb demonstracyjny
+    pub fn is_available(&self, config: &Config) -> bool {
+        demo::is_active(config) || !self.api_key(config).trim().is_empty()
+    }
+}
+
+/// Temperatura próbkowania wysyłana do OpenAI, Anthropic, DeepSeek, Mistral i Groq
+pub const TEMPERATURE: f32 = 0.7;
+
+/// Model i parametry, którymi powstał wynik (stopka panelu, historia, eksport)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Generation {
+    pub model: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thinking_budget: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning_effort: Option<ReasoningEffort>,
+}
+
+impl Generation {
+    /// Np. "gpt-5-mini · temp. 0.7 · rozumowanie high"
+    pub fn label(&self) -> String {
+        std::iter::once(self.model.clone()).chain(self.parameters()).collect::<Vec<_>>().join(" · ")
+    }
+
+    /// Parametry bez modelu, np. ["temp. 0.7", "rozumowanie high"]
+    pub fn parameters(&self) -> Vec<String> {
+        let mut parts = Vec::new();
+        if let Some(temperature) = self.temperature {
+            parts.push(format!("temp. {}", temperature));
+        }
+        if let Some(budget) = self.thinking_budget {
+            parts.push(format!("budżet myślenia {}", budget));
+        }
+        if let Some(effort) = self.reasoning_effort {
+            parts.push(format!("rozumowanie {}", effort.as_str()));
+        }
+        parts
+    }
+}
+
+impl Provider {
+    /// Parametry zapytania z tą konfiguracją (po podmianie modelu przy ponownym zapytaniu)
+    pub fn generation(&self, config: &Config) -> Generation {
+        if demo::is_active(config) {
+            return Generation {
+                model: "demo".to_string(),
+                temperature: None,
+                thinking_budget: None,
+                reasoning_effort: None,
+            };
+        }
+        let (temperature, thinking_budget) = if self.info().sends_temperature {
+            (Some(TEMPERATURE), None)
+        } else {
+            (None, Some(gemini::THINKING_BUDGET))
+        };
+        Generation {
+            model: self.model(config).to_string(),
+            temperature,
+            thinking_budget,
+            reasoning_effort: self.info().reasoning_effort.then_some(config.ai_settings.reasoning_effort),
+        }
+    }
+}
+
+static REQUEST_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Krótki identyfikator jednego wywołania dostawcy (8 znaków szesnastkowych),
+/// pokazywany w logach, błędach panelu i oknie surowej odpowiedzi
+pub fn new_request_id() -> String {
+    use std::hash::{BuildHasher, Hasher};
+
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write_u64(REQUEST_COUNTER.fetch_add(1, Ordering::Relaxed));
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos(),
+    );
+    format!("{:08x}", hasher.finish() as u32)
+}
+
+/// Tryb JSON API zgodnych z OpenAI: model musi zwrócić poprawny obiekt JSON
+#[derive(Debug, Serialize)]
+pub struct ResponseFormat {
+    #[serde(rename = "type")]
+    format_type: String,
+}
+
+impl ResponseFormat {
+    pub fn json_object() -> Self {
+        Self {
+            format_type: "json_object".to_string(),
+        }
+    }
+}
+
+/// Sposób wysłania żądania: strumieniowanie, tryb JSON dostawcy i profil limitów czasu
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RequestOptions {
+    pub streaming: bool,
+    pub json: bool,
+    /// Tryb szybki: krótszy limit czasu i mniej ponowień (krótkie poprawki na bieżąco)
+    pub quick: bool,
+    /// Kompresja dużej treści żądania (gzip)
+    pub compress: bool,
+}
+
+impl RequestOptions {
+    /// Limit czasu do komunikatu błędu; `default` to limit dostawcy w zwykłym trybie
+    pub fn timeout_secs(&self, default: u64) -> u64 {
+        if self.quick {
+            QUICK_TIMEOUT
+        } else {
+            default
+        }
+    }
+
+    /// Ile razy ponowić żądanie po przekroczeniu czasu albo błędzie połączenia;
+    /// zwykły tryb nie ponawia
+    pub fn retries(&self) -> u32 {
+        if self.quick {
+            QUICK_RETRIES
+        } else {
+            0
+        }
+    }
+}
+
+/// Treść żądania, które `correct_text_with_callback` wyśle do dostawcy
+pub fn build_request_body(
+    provider: Provider,
+    config: &Config,
+    text: &str,
+    instruction_prompt: &str,
+    system_prompt: &str,
+    streaming: bool,
+) -> serde_json::Value {
+    let model = provider.model(config);
+
+    match provider {
+        Provider::OpenAI => {
+            openai::build_request_body(model, text, instruction_prompt, system_prompt, streaming)
+        }
+        Provider::Anthropic => {
+            anthropic::build_request_body(model, text, instruction_prompt, system_prompt, streaming)
+        }
+        Provider::Gemini => gemini::build_request_body(text, instruction_prompt, system_prompt),
+        Provider::DeepSeek => {
+            deepseek::build_request_body(model, text, instruction_prompt, system_prompt, streaming)
+        }
+        Provider::Mistral => {
+            mistral::build_request_body(model, text, instruction_prompt, system_prompt, streaming)
+        }
+        Provider::Groq => {
+            groq::build_request_body(model, text, instruction_prompt, system_prompt, streaming)
+        }
+    }
+}
+
+/// Wysyła tekst do wskazanego dostawcy z kluczem i modelem z konfiguracji
+pub async fn correct_text(
+    provider: Provider,
+    config: &Config,
+    text: &str,
+    instruction_prompt: &str,
+    system_prompt: &str,
+) -> Result<String, ApiError> {
+    correct_text_with_callback::<fn(&str)>(provider, config, text, instruction_prompt, system_prompt, None)
+        .await
+}
+
+/// Jak `correct_text`, ale przekazuje kolejne fragmenty strumienia do `on_chunk`
+pub async fn correct_text_with_callback<F>(
+    provider: Provider,
+    config: &Config,
+    text: &str,
+    instruction_prompt: &str,
+    system_prompt: &str,
+    on_chunk: Option<F>,
+) -> Result<String, ApiError>
+where
+    F: Fn(&str) + Send + 'static,
+{
+    if demo::is_active(config) {
+        return demo::correct_text_demo_with_callback(provider, text, on_chunk).await;
+    }
+    if text.is_empty() {
+        return Err(ApiError::Response("Text to correct is empty".to_string()));
+    }
+
+    let message = user_message(instruction_prompt, text);
+    converse_with_callback(provider, config, &[], &message, system_prompt, on_chunk).await
+}
+
+/// Oszczędny tryb: model zwraca tylko listę zmian, nanoszonych lokalnie.
+/// Gdy lista nie pasuje do tekstu, tekst jest przepisywany w całości jak zwykle.
+pub async fn correct_text_with_edits<F>(
+    provider: Provider,
+    config: &Config,
+    text: &str,
+    instruction_prompt: &str,
+    system_prompt: &str,
+    on_chunk: Option<F>,
+) -> Result<String, ApiError>
+where
+    F: Fn(&str) + Send + 'static,
+{
+    if demo::is_active(config) || text.is_empty() {
+        return correct_text_with_callback(provider, config, text, instruction_prompt, system_prompt, on_chunk).await;
+    }
+
+    let message = user_message(instruction_prompt, text);
+    let edits_prompt = edits::system_prompt(system_prompt);
+    let options = RequestOptions::default();
+    let response = send::<fn(&str)>(provider, config, &[], &message, &edits_prompt, options, None).await?;
+
+    match edits::apply_response(text, &response) {
+        Ok(corrected) => {
+            if let Some(on_chunk) = on_chunk {
+                on_chunk(&corrected);
+            }
+            Ok(corrected)
+        }
+        Err(e) => {
+            tracing::warn!("{} edit list rejected ({}), falling back to a full rewrite", provider.name(), e);
+            correct_text_with_callback(provider, config, text, instruction_prompt, system_prompt, on_chunk).await
+        }
+    }
+}
+
+/// Kolejna wiadomość do dostawcy poprzedzona wcześniejszymi wymianami (tryb rozmowy)
+pub async fn converse_with_callback<F>(
+    provider: Provider,
+    config: &Config,
+    history: &[Exchange],
+    message: &str,
+    system_prompt: &str,
+    on_chunk: Option<F>,
+) -> Result<String, ApiError>
+where
+    F: Fn(&str) + Send + 'static,
+{
+    if demo::is_active(config) {
+        // Demo poprawia swoją ostatnią odpowiedź, bo nie rozumie poleceń
+        let text = history.last().map_or(message, |e| e.assistant.as_str());
+        return demo::correct_text_demo_with_callback(provider, text, on_chunk).await;
+    }
+
+    let options = RequestOptions { streaming: true, ..Default::default() };
+    send(provider, config, history, message, system_prompt, options, on_chunk).await
+}
+
+/// Poprawka jako obiekt `{corrected, changes[], confidence}` (tryb JSON dostawcy)
+pub async fn correct_text_structured(
+    provider: Provider,
+    config: &Config,
+    text: &str,
+    instruction_prompt: &str,
+    system_prompt: &str,
+) -> Result<StructuredResult, ApiError> {
+    if demo::is_active(config) {
+        return Ok(structured::from_diff(text, &demo::correction(provider, text), 1.0));
+    }
+    if text.is_empty() {
+        return Err(ApiError::Response("Text to correct is empty".to_string()));
+    }
+
+    let message = user_message(instruction_prompt, text);
+    let system_prompt = structured::system_prompt(system_prompt);
+    let options = RequestOptions { json: true, ..Default::default() };
+    let response = send::<fn(&str)>(provider, config, &[], &message, &system_prompt, options, None).await?;
+    structured::parse(&response).map_err(ApiError::Response)
+}
+
+/// Propozycje tematu dla poprawionej treści maila
+pub async fn suggest_subjects(provider: Provider, config: &Config, body: &str) -> Result<Vec<String>, ApiError> {
+    if demo::is_active(config) {
+        return Ok(subject::demo_suggestions(body));
+    }
+    if body.trim().is_empty() {
+        return Err(ApiError::Response("Email body is empty".to_string()));
+    }
+
+    let message = user_message(&subject::instruction(), body);
+    let options = RequestOptions::default();
+    let response = send::<fn(&str)>(provider, config, &[], &message, subject::SYSTEM_PROMPT, options, None).await?;
+    let subjects = subject::parse(&response);
+    if subjects.is_empty() {
+        return Err(ApiError::Response("No subject lines in response".to_string()));
+    }
+    Ok(subjects)
+}
+
+/// Czy błąd warto ponowić (nic nie dotarło do modelu albo odpowiedź nie zdążyła)
+fn is_retryable(error: &ApiError) -> bool {
+    matches!(error, ApiError::Timeout(_) | ApiError::Connection(_))
+}
+
+/// Wysyła żądanie z profilem limitów z konfiguracji; ponawia tylko, dopóki
+/// żaden fragment strumienia nie trafił jeszcze do `on_chunk`. Po 429 czeka
+/// w kolejce `rate_limit` do końca blokady dostawcy.
+async fn send<F>(
+    provider: Provider,
+    config: &Config,
+    history: &[Exchange],
+    message: &str,
+    system_prompt: &str,
+    options: RequestOptions,
+    on_chunk: Option<F>,
+) -> Result<String, ApiError>
+where
+    F: Fn(&str) + Send + 'static,
+{
+    let options = RequestOptions {
+        quick: config.settings.quick_mode,
+        compress: config.network.compress_requests && provider.accepts_compressed_requests(),
+        ..options
+    };
+    http_client::configure(&config.network);
+    if http_client::has_dns_override(provider.host()) {
+        tracing::info!("{}: using DNS override for {}", provider.name(), provider.host());
+    }
+    let on_chunk = on_chunk.map(|f| Arc::new(Mutex::new(f)));
+    let streamed = Arc::new(AtomicBool::new(false));
+
+    let mut attempt = 0;
+    let mut rate_limited = 0;
+    loop {
+        rate_limit::wait_turn(provider).await;
+        let callback = on_chunk.clone().map(|f| {
+            let streamed = streamed.clone();
+            move |chunk: &str| {
+                streamed.store(true, Ordering::SeqCst);
+                (f.lock().unwrap())(chunk)
+            }
+        });
+        match send_once(provider, config, history, message, system_prompt, options, callback).await {
+            Err(ApiError::RateLimited(retry_after, body))
+                if rate_limited < rate_limit::MAX_RETRIES
+                    && !streamed.load(Ordering::SeqCst)
+                    && !rate_limit::is_quota_exhausted(&body) =>
+            {
+                rate_limited += 1;
+                let wait = rate_limit::LIMITER.block(provider, retry_after, Instant::now());
+                tracing::warn!(
+                    "{} rate limited, retrying in {:.1}s ({}/{})",
+                    provider.name(),
+                    wait.as_secs_f64(),
+                    rate_limited,
+                    rate_limit::MAX_RETRIES
+                );
+            }
+            Err(e) if attempt < options.retries() && is_retryable(&e) && !streamed.load(Ordering::SeqCst) => {
+                attempt += 1;
+                tracing::warn!("{} request failed ({}), retry {}/{}", provider.name(), e, attempt, options.retries());
+            }
+            result => return result,
+        }
+    }
+}
+
+async fn send_once<F>(
+    provider: Provider,
+    config: &Config,
+    history: &[Exchange],
+    message: &str,
+    system_prompt: &str,
+    options: RequestOptions,
+    on_chunk: Option<F>,
+) -> Result<String, ApiError>
+where
+    F: Fn(&str) + Send + 'static,
+{
+    let api_key = provider.api_key(config);
+    let model = provider.model(config);
+
+    match provider {
+        Provider::OpenAI => {
+            openai::converse_openai_with_callback(api_key, model, history, message, system_prompt, options, on_chunk)
+                .await
+        }
+        Provider::Anthropic => {
+            anthropic::converse_anthropic_with_callback(api_key, model, history, message, system_prompt, options, on_chunk)
+                .await
+        }
+        Provider::Gemini => {
+            gemini::converse_gemini_with_callback(api_key, model, history, message, system_prompt, options, on_chunk)
+                .await
+        }
+        Provider::DeepSeek => {
+            deepseek::converse_deepseek_with_callback(api_key, model, history, message, system_prompt, options, on_chunk)
+                .await
+        }
+        Provider::Mistral => {
+            mistral::converse_mistral_with_callback(api_key, model, history, message, system_prompt, options, on_chunk)
+                .await
+        }
+        Provider::Groq => {
+            groq::converse_groq_with_callback(api_key, model, history, message, system_prompt, options, on_chunk)
+                .await
         }
     }
 }
@@ -27,11 +544,123 @@ impl Provider {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_provider_hosts_match_urls() {
+        assert!(openai::OPENAI_API_URL.contains(Provider::OpenAI.host()));
+        assert!(anthropic::ANTHROPIC_API_URL.contains(Provider::Anthropic.host()));
+        assert!(gemini::GEMINI_API_BASE.contains(Provider::Gemini.host()));
+        assert!(deepseek::DEEPSEEK_API_URL.contains(Provider::DeepSeek.host()));
+        assert!(mistral::MISTRAL_API_URL.contains(Provider::Mistral.host()));
+        assert!(groq::GROQ_API_URL.contains(Provider::Groq.host()));
+    }
+
+    #[test]
+    fn test_generation_label() {
+        let config = Config::default();
+        assert_eq!(
+            Provider::OpenAI.generation(&config).label(),
+            "gpt-5-mini · temp. 0.7 · rozumowanie high"
+        );
+        assert_eq!(
+            Provider::Gemini.generation(&config).label(),
+            "gemini-2.5-flash · budżet myślenia 0"
+        );
+    }
+
     #[test]
     fn test_provider_names() {
         assert_eq!(Provider::OpenAI.name(), "OpenAI");
         assert_eq!(Provider::Anthropic.name(), "Anthropic");
         assert_eq!(Provider::Gemini.name(), "Gemini");
         assert_eq!(Provider::DeepSeek.name(), "DeepSeek");
+        assert_eq!(Provider::Mistral.name(), "Mistral");
+        assert_eq!(Provider::Groq.name(), "Groq");
+    }
+
+    #[test]
+    fn test_provider_config_lookup() {
+        let mut config = Config::default();
+        config.api_keys.gemini = "AIza-test".to_string();
+
+        assert_eq!(Provider::Gemini.api_key(&config), "AIza-test");
+        assert_eq!(Provider::Gemini.model(&config), "gemini-2.5-flash");
+        assert_eq!(Provider::all().len(), 6);
+    }
+
+    #[test]
+    fn test_model_choices_start_with_configured_model() {
+        let mut config = Config::default();
+        assert_eq!(
+            Provider::Gemini.model_choices(&config),
+            vec!["gemini-2.5-flash", "gemini-2.5-pro", "gemini-2.5-flash-lite"]
+        );
+
+        Provider::Gemini.set_model(&mut config, "gemini-2.5-pro");
+        assert_eq!(Provider::Gemini.model(&config), "gemini-2.5-pro");
+        assert_eq!(
+            Provider::Gemini.model_choices(&config),
+            vec!["gemini-2.5-pro", "gemini-2.5-flash", "gemini-2.5-flash-lite"]
+        );
+
+        Provider::DeepSeek.set_model(&mut config, "deepseek-custom");
+        assert_eq!(
+            Provider::DeepSeek.model_choices(&config),
+            vec!["deepseek-custom", "deepseek-chat", "deepseek-reasoner"]
+        );
+    }
+
+    #[test]
+    fn test_build_request_body_uses_config_model() {
+        let config = Config::default();
+
+        let openai = build_request_body(Provider::OpenAI, &config, "tekst", "Correct", "System", true);
+        assert_eq!(openai["model"], "gpt-5-mini");
+        assert_eq!(openai["stream"], true);
+        assert_eq!(openai["messages"][0]["content"], "System");
+
+        let anthropic = build_request_body(Provider::Anthropic, &config, "tekst", "Correct", "System", false);
+        assert_eq!(anthropic["system"], "System");
+        assert!(anthropic.get("stream").is_none());
+
+        let gemini = build_request_body(Provider::Gemini, &config, "tekst", "Correct", "System", true);
+        assert!(gemini.get("model").is_none());
+        assert_eq!(gemini["contents"][0]["parts"][0]["text"], "Correct\n\n---\ntekst\n---");
+    }
+
+    #[test]
+    fn test_quick_profile() {
+        let normal = RequestOptions::default();
+        assert_eq!((normal.timeout_secs(35), normal.retries()), (35, 0));
+
+        let quick = RequestOptions { quick: true, ..normal };
+        assert_eq!((quick.timeout_secs(35), quick.retries()), (QUICK_TIMEOUT, QUICK_RETRIES));
+
+        assert!(is_retryable(&ApiError::Timeout("12s".to_string())));
+        assert!(!is_retryable(&ApiError::Response("HTTP 401".to_string())));
+    }
+
+    #[test]
+    fn test_request_ids_are_short_and_unique() {
+        let ids: std::collections::HashSet<String> = (0..100).map(|_| new_request_id()).collect();
+        assert_eq!(ids.len(), 100);
+        assert!(ids.iter().all(|id| id.len() == 8 && id.chars().all(|c| c.is_ascii_hexdigit())));
+    }
+
+    #[tokio::test]
+    async fn test_structured_requires_text() {
+        let config = Config::default();
+        let result = correct_text_structured(Provider::OpenAI, &config, "", "Correct", "System").await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_correct_text_dispatch_empty_key() {
+        let config = Config::default();
+        let result = correct_text(Provider::Anthropic, &config, "tekst", "Correct", "System").await;
+
+        match result {
+            Err(ApiError::Response(msg)) => assert_eq!(msg, "API key is empty"),
+            _ => panic!("Expected Response error"),
+        }
     }
 }
@@ -1,10 +1,13 @@
-use crate::api::http_client::{get_client, get_streaming_client};
+use crate::api::http_client::{body_stream, get_client, get_streaming_client, read_json, status_error, JsonBody, WithProfile};
+use crate::api::token_usage::{self, StreamOptions, TokenUsage};
+use crate::api::{Provider, RequestOptions, ResponseFormat, TEMPERATURE};
+use crate::conversation::{user_message, Exchange};
 use crate::error::{ApiError, DEFAULT_TIMEOUT};
 use futures::StreamExt;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
-const OPENAI_API_URL: &str = "https://api.openai.com/v1/chat/completions";
+pub const OPENAI_API_URL: &str = "https://api.openai.com/v1/chat/completions";
 
 #[derive(Debug, Serialize)]
 struct ChatCompletionRequest {
@@ -14,6 +17,10 @@ struct ChatCompletionRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     max_completion_tokens: Option<u32>,
     stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<StreamOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<ResponseFormat>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -25,6 +32,8 @@ struct Message {
 #[derive(Debug, Deserialize)]
 struct ChatCompletionResponse {
     choices: Vec<Choice>,
+    #[serde(default)]
+    usage: Option<TokenUsage>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -39,7 +48,10 @@ struct MessageContent {
 
 #[derive(Debug, Deserialize)]
 struct StreamChunk {
+    #[serde(default)]
     choices: Vec<StreamChoice>,
+    #[serde(default)]
+    usage: Option<TokenUsage>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -53,6 +65,57 @@ struct Delta {
     content: Option<String>,
 }
 
+/// Treść żądania wysyłanego do API (bez sieci, do testów i podglądu)
+pub fn build_request_body(
+    model: &str,
+    text_to_correct: &str,
+    instruction_prompt: &str,
+    system_prompt: &str,
+    streaming: bool,
+) -> serde_json::Value {
+    let message = user_message(instruction_prompt, text_to_correct);
+    let options = RequestOptions { streaming, ..Default::default() };
+    serde_json::to_value(build_request(model, &[], &message, system_prompt, options))
+        .expect("request is always serializable")
+}
+
+fn build_request(
+    model: &str,
+    history: &[Exchange],
+    message: &str,
+    system_prompt: &str,
+    options: RequestOptions,
+) -> ChatCompletionRequest {
+    let mut messages = vec![Message {
+        role: "system".to_string(),
+        content: system_prompt.to_string(),
+    }];
+    for exchange in history {
+        messages.push(Message {
+            role: "user".to_string(),
+            content: exchange.user.clone(),
+        });
+        messages.push(Message {
+            role: "assistant".to_string(),
+            content: exchange.assistant.clone(),
+        });
+    }
+    messages.push(Message {
+        role: "user".to_string(),
+        content: message.to_string(),
+    });
+
+    ChatCompletionRequest {
+        model: model.to_string(),
+        messages,
+        temperature: TEMPERATURE,
+        max_completion_tokens: Some(4096),
+        stream: options.streaming,
+        stream_options: options.streaming.then(StreamOptions::with_usage),
+        response_format: options.json.then(ResponseFormat::json_object),
+    }
+}
+
 pub async fn correct_text_openai(
     api_key: &str,
     model: &str,
@@ -72,45 +135,51 @@ pub async fn correct_text_openai_with_callback<F>(
     system_prompt: &str,
     streaming: bool,
     on_chunk: Option<F>,
-) -> Result<String, ApiError> 
+) -> Result<String, ApiError>
+where
+    F: Fn(&str) + Send + 'static,
+{
+    if text_to_correct.is_empty() {
+        return Err(ApiError::Response("Text to correct is empty".to_string()));
+    }
+
+    let message = user_message(instruction_prompt, text_to_correct);
+    let options = RequestOptions { streaming, ..Default::default() };
+    converse_openai_with_callback(api_key, model, &[], &message, system_prompt, options, on_chunk).await
+}
+
+/// Wysyła wcześniejsze wymiany rozmowy i nową wiadomość użytkownika
+pub async fn converse_openai_with_callback<F>(
+    api_key: &str,
+    model: &str,
+    history: &[Exchange],
+    message: &str,
+    system_prompt: &str,
+    options: RequestOptions,
+    on_chunk: Option<F>,
+) -> Result<String, ApiError>
 where
     F: Fn(&str) + Send + 'static,
 {
+    let streaming = options.streaming;
     if api_key.is_empty() {
         return Err(ApiError::Response("API key is empty".to_string()));
     }
     if model.is_empty() {
         return Err(ApiError::Response("Model is empty".to_string()));
     }
-    if text_to_correct.is_empty() {
-        return Err(ApiError::Response("Text to correct is empty".to_string()));
+    if message.is_empty() {
+        return Err(ApiError::Response("Message is empty".to_string()));
     }
 
     let client = if streaming { get_streaming_client() } else { get_client() };
 
-    let messages = vec![
-        Message {
-            role: "system".to_string(),
-            content: system_prompt.to_string(),
-        },
-        Message {
-            role: "user".to_string(),
-            content: format!("{}\n\n---\n{}\n---", instruction_prompt, text_to_correct),
-        },
-    ];
-
-    let request = ChatCompletionRequest {
-        model: model.to_string(),
-        messages,
-        temperature: 0.7,
-        max_completion_tokens: Some(4096),
-        stream: streaming,
-    };
+    let request = build_request(model, history, message, system_prompt, options);
 
     if streaming {
-        stream_openai_request_with_callback(&client, api_key, request, on_chunk).await
+        stream_openai_request_with_callback(&client, api_key, request, options, on_chunk).await
     } else {
-        batch_openai_request(&client, api_key, request).await
+        batch_openai_request(&client, api_key, request, options).await
     }
 }
 
@@ -118,17 +187,19 @@ async fn batch_openai_request(
     client: &Client,
     api_key: &str,
     request: ChatCompletionRequest,
+    options: RequestOptions,
 ) -> Result<String, ApiError> {
     let response = client
         .post(OPENAI_API_URL)
         .header("Authorization", format!("Bearer {}", api_key))
         .header("Content-Type", "application/json")
-        .json(&request)
+        .json_body(Provider::OpenAI, &request, options)
+        .with_profile(options)
         .send()
         .await
         .map_err(|e| {
             if e.is_timeout() {
-                ApiError::Timeout(format!("Request timed out after {}s", DEFAULT_TIMEOUT))
+                ApiError::Timeout(format!("Request timed out after {}s", options.timeout_secs(DEFAULT_TIMEOUT)))
             } else if e.is_connect() {
                 ApiError::Connection(e.to_string())
             } else {
@@ -137,17 +208,16 @@ async fn batch_openai_request(
         })?;
 
     if !response.status().is_success() {
-        return Err(ApiError::Response(format!(
-            "HTTP {}: {}",
-            response.status(),
-            response.text().await.unwrap_or_default()
-        )));
+        return Err(status_error(response).await);
     }
 
-    let completion: ChatCompletionResponse = response.json().await.map_err(|e| {
+    let completion: ChatCompletionResponse = read_json(Provider::OpenAI, response).await.map_err(|e| {
         ApiError::Response(format!("Failed to parse response: {}", e))
     })?;
 
+    if let Some(usage) = completion.usage {
+        token_usage::report(usage);
+    }
     completion
         .choices
         .first()
@@ -159,6 +229,7 @@ async fn stream_openai_request_with_callback<F>(
     client: &Client,
     api_key: &str,
     request: ChatCompletionRequest,
+    options: RequestOptions,
     on_chunk: Option<F>,
 ) -> Result<String, ApiError> 
 where
@@ -168,12 +239,13 @@ where
         .post(OPENAI_API_URL)
         .header("Authorization", format!("Bearer {}", api_key))
         .header("Content-Type", "application/json")
-        .json(&request)
+        .json_body(Provider::OpenAI, &request, options)
+        .with_profile(options)
         .send()
         .await
         .map_err(|e| {
             if e.is_timeout() {
-                ApiError::Timeout(format!("Request timed out after {}s", DEFAULT_TIMEOUT))
+                ApiError::Timeout(format!("Request timed out after {}s", options.timeout_secs(DEFAULT_TIMEOUT)))
             } else if e.is_connect() {
                 ApiError::Connection(e.to_string())
             } else {
@@ -182,14 +254,10 @@ where
         })?;
 
     if !response.status().is_success() {
-        return Err(ApiError::Response(format!(
-            "HTTP {}: {}",
-            response.status(),
-            response.text().await.unwrap_or_default()
-        )));
+        return Err(status_error(response).await);
     }
 
-    let mut stream = response.bytes_stream();
+    let mut stream = body_stream(Provider::OpenAI, response);
     let mut collected_text = String::new();
     let mut buffer = String::new();
 
@@ -206,6 +274,9 @@ where
                 }
 
                 if let Ok(chunk_data) = serde_json::from_str::<StreamChunk>(data) {
+                    if let Some(usage) = chunk_data.usage {
+                        token_usage::report(usage);
+                    }
                     if let Some(choice) = chunk_data.choices.first() {
                         if let Some(content) = &choice.delta.content {
                             collected_text.push_str(content);
@@ -232,6 +303,49 @@ where
 mod tests {
     use super::*;
 
+    fn history() -> Vec<Exchange> {
+        vec![Exchange {
+            user: user_message("Correct", "ala ma kota"),
+            assistant: "Ala ma kota.".to_string(),
+        }]
+    }
+
+    #[test]
+    fn test_request_carries_conversation_history() {
+        let options = RequestOptions { streaming: true, ..Default::default() };
+        let request = build_request("gpt-5-mini", &history(), "krócej", "System", options);
+
+        let roles: Vec<&str> = request.messages.iter().map(|m| m.role.as_str()).collect();
+        assert_eq!(roles, ["system", "user", "assistant", "user"]);
+        assert_eq!(request.messages[1].content, "Correct\n\n---\nala ma kota\n---");
+        assert_eq!(request.messages[2].content, "Ala ma kota.");
+        assert_eq!(request.messages[3].content, "krócej");
+    }
+
+    #[test]
+    fn test_json_mode_sets_response_format() {
+        let plain = serde_json::to_value(build_request("gpt-5-mini", &[], "tekst", "System", RequestOptions::default())).unwrap();
+        assert!(plain.get("response_format").is_none());
+
+        let options = RequestOptions { json: true, ..Default::default() };
+        let json = serde_json::to_value(build_request("gpt-5-mini", &[], "tekst", "System", options)).unwrap();
+        assert_eq!(json["response_format"]["type"], "json_object");
+    }
+
+    #[test]
+    fn test_streaming_asks_for_usage() {
+        let options = RequestOptions { streaming: true, ..Default::default() };
+        let streaming = serde_json::to_value(build_request("gpt-5-mini", &[], "tekst", "System", options)).unwrap();
+        assert_eq!(streaming["stream_options"]["include_usage"], true);
+        let batch = serde_json::to_value(build_request("gpt-5-mini", &[], "tekst", "System", RequestOptions::default())).unwrap();
+        assert!(batch.get("stream_options").is_none());
+
+        // Ostatni fragment strumienia ma puste `choices` i samo zużycie
+        let last: StreamChunk =
+            serde_json::from_str(r#"{"choices": [], "usage": {"prompt_tokens": 40, "completion_tokens": 12}}"#).unwrap();
+        assert_eq!(last.usage, Some(TokenUsage { prompt_tokens: 40, completion_tokens: 12 }));
+    }
+
     #[tokio::test]
     async fn test_openai_empty_api_key() {
         let result = correct_text_openai(
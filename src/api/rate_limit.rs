@@ -0,0 +1,162 @@
+//! Kolejkowanie zapytań po przekroczeniu limitu dostawcy (HTTP 429)
+//!
+//! Odpowiedź 429 blokuje dostawcę na czas z nagłówka `Retry-After` (albo
+//! domyślny). Do końca blokady kolejne zapytania do tego dostawcy czekają przed
+//! wysłaniem, a odrzucone zapytanie jest ponawiane. Czekające zapytanie
+//! zgłasza czas oczekiwania przez powiadamiacz z `with_notifier`. Odpowiedź
+//! zgłaszająca wyczerpany limit konta nie jest ponawiana.
+
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::api::Provider;
+
+/// Blokada, gdy odpowiedź 429 nie podała `Retry-After`
+pub const DEFAULT_WAIT: Duration = Duration::from_secs(10);
+/// Najdłuższe czekanie na jedną blokadę; dłuższe `Retry-After` jest przycinane
+pub const MAX_WAIT: Duration = Duration::from_secs(120);
+/// Ile razy ponowić zapytanie odrzucone limitem
+pub const MAX_RETRIES: u32 = 3;
+/// Kody błędu 429 oznaczające wyczerpane środki konta; czekanie ich nie zmieni
+const QUOTA_ERRORS: &[&str] = &["insufficient_quota"];
+
+type Notifier = Arc<dyn Fn(Duration) + Send + Sync>;
+
+tokio::task_local! {
+    static NOTIFIER: Notifier;
+}
+
+/// Blokady dostawców po odpowiedzi 429
+#[derive(Default)]
+pub struct RateLimiter {
+    blocked_until: Mutex<HashMap<&'static str, Instant>>,
+}
+
+impl RateLimiter {
+    /// Blokuje dostawcę na `retry_after` (albo `DEFAULT_WAIT`); dłuższa blokada wygrywa.
+    /// Zwraca czas blokady.
+    pub fn block(&self, provider: Provider, retry_after: Option<Duration>, now: Instant) -> Duration {
+        let wait = retry_after.unwrap_or(DEFAULT_WAIT).min(MAX_WAIT);
+        let mut blocked = self.blocked_until.lock().unwrap();
+        let until = blocked.entry(provider.name()).or_insert(now);
+        *until = (*until).max(now + wait);
+        wait
+    }
+
+    /// Ile jeszcze trwa blokada dostawcy
+    pub fn remaining(&self, provider: Provider, now: Instant) -> Option<Duration> {
+        self.blocked_until
+            .lock()
+            .unwrap()
+            .get(provider.name())
+            .map(|until| until.saturating_duration_since(now))
+            .filter(|wait| !wait.is_zero())
+    }
+}
+
+/// Blokady wspólne dla wszystkich paneli
+pub static LIMITER: Lazy<RateLimiter> = Lazy::new(RateLimiter::default);
+
+/// Czas oczekiwania z `Retry-After`: liczba sekund albo data HTTP
+pub fn parse_retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    if let Ok(secs) = value.parse::<f64>() {
+        // Ujemne, NaN i zbyt duże (np. 1e20) nie mieszczą się w Duration
+        return Duration::try_from_secs_f64(secs).ok();
+    }
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(date.duration_since(now).unwrap_or(Duration::ZERO))
+}
+
+/// Czy treść odpowiedzi 429 zgłasza wyczerpany limit konta (np. `insufficient_quota` OpenAI),
+/// a nie chwilowy limit zapytań
+pub fn is_quota_exhausted(body: &str) -> bool {
+    QUOTA_ERRORS.iter().any(|code| body.contains(code))
+}
+
+/// Wykonuje `future` z powiadamiaczem wywoływanym, gdy zapytanie czeka na koniec blokady
+pub async fn with_notifier<F>(notifier: impl Fn(Duration) + Send + Sync + 'static, future: F) -> F::Output
+where
+    F: Future,
+{
+    NOTIFIER.scope(Arc::new(notifier), future).await
+}
+
+/// Czeka na koniec blokady dostawcy (kolejka zapytań po 429)
+pub async fn wait_turn(provider: Provider) {
+    while let Some(wait) = LIMITER.remaining(provider, Instant::now()) {
+        tracing::info!("{} rate limited, waiting {:.1}s", provider.name(), wait.as_secs_f64());
+        let _ = NOTIFIER.try_with(|notify| notify(wait));
+        tokio::time::sleep(wait).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_445_412_480);
+        assert_eq!(parse_retry_after("30", now), Some(Duration::from_secs(30)));
+        assert_eq!(parse_retry_after(" 1.5 ", now), Some(Duration::from_millis(1500)));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:20 GMT", now),
+            Some(Duration::from_secs(20))
+        );
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:27:00 GMT", now), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("soon", now), None);
+        assert_eq!(parse_retry_after("-3", now), None);
+        assert_eq!(parse_retry_after("-0.5", now), None);
+        assert_eq!(parse_retry_after("1e20", now), None);
+        assert_eq!(parse_retry_after("NaN", now), None);
+        assert_eq!(parse_retry_after("inf", now), None);
+    }
+
+    #[test]
+    fn test_is_quota_exhausted() {
+        let quota = r#"{"error":{"message":"You exceeded your current quota","type":"insufficient_quota","code":"insufficient_quota"}}"#;
+        assert!(is_quota_exhausted(quota));
+        let rate = r#"{"error":{"message":"Rate limit reached for requests","type":"requests","code":"rate_limit_exceeded"}}"#;
+        assert!(!is_quota_exhausted(rate));
+        assert!(!is_quota_exhausted(""));
+    }
+
+    #[test]
+    fn test_block_and_remaining() {
+        let limiter = RateLimiter::default();
+        let now = Instant::now();
+        assert_eq!(limiter.remaining(Provider::OpenAI, now), None);
+
+        assert_eq!(limiter.block(Provider::OpenAI, Some(Duration::from_secs(5)), now), Duration::from_secs(5));
+        assert_eq!(limiter.remaining(Provider::OpenAI, now), Some(Duration::from_secs(5)));
+        assert_eq!(limiter.remaining(Provider::Gemini, now), None);
+
+        // Krótsza blokada nie skraca dłuższej; brak nagłówka to DEFAULT_WAIT, a zbyt długa jest przycinana
+        limiter.block(Provider::OpenAI, Some(Duration::from_secs(1)), now);
+        assert_eq!(limiter.remaining(Provider::OpenAI, now), Some(Duration::from_secs(5)));
+        assert_eq!(limiter.block(Provider::Groq, None, now), DEFAULT_WAIT);
+        assert_eq!(limiter.block(Provider::Groq, Some(Duration::from_secs(3600)), now), MAX_WAIT);
+        assert_eq!(limiter.remaining(Provider::OpenAI, now + Duration::from_secs(6)), None);
+    }
+
+    #[tokio::test]
+    async fn test_notifier_is_scoped() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        let inside = with_notifier(
+            move |wait| sink.lock().unwrap().push(wait),
+            async { NOTIFIER.try_with(|notify| notify(Duration::from_secs(2))).is_ok() },
+        )
+        .await;
+        assert!(inside);
+        assert!(NOTIFIER.try_with(|_| ()).is_err());
+        assert_eq!(*seen.lock().unwrap(), vec![Duration::from_secs(2)]);
+    }
+}
@@ -0,0 +1,363 @@
+//! Dane dostawców w jednym miejscu
+//!
+//! Nazwa, kolory panelu, domyślny i znane modele, host API oraz możliwości
+//! każdego dostawcy. Okno główne, ustawienia i domyślna konfiguracja biorą je
+//! stąd zamiast trzymać własne kopie. Kolejność wpisów to kolejność
+//! `Provider::all()`; panele okna (dostawca i opcjonalnie model) wybiera
+//! `panel_specs`.
+
+use super::Provider;
+use crate::config::Config;
+
+/// Kolor w RGB
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    pub fn to_css(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+
+    pub fn to_rgba(&self, alpha: f32) -> String {
+        format!("rgba({}, {}, {}, {})", self.r, self.g, self.b, alpha)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProviderInfo {
+    pub provider: Provider,
+    pub name: &'static str,
+    /// Nagłówek panelu i przycisk „Użyj”
+    pub color: Rgb,
+    /// Przycisk „Użyj” po najechaniu
+    pub hover_color: Rgb,
+    pub default_model: &'static str,
+    /// Znane modele do wyboru przy ponownym zapytaniu (domyślny pierwszy)
+    pub known_models: &'static [&'static str],
+    /// Host API (klucz nadpisań DNS)
+    pub host: &'static str,
+    /// Czy API przyjmuje treść żądania skompresowaną gzipem (`Content-Encoding: gzip`)
+    pub compressed_requests: bool,
+    /// Czy zapytanie ustawia temperaturę; bez niej wysyłany jest budżet myślenia
+    pub sends_temperature: bool,
+    /// Czy modeli dostawcy dotyczy ustawienie `ReasoningEffort`
+    pub reasoning_effort: bool,
+}
+
+pub const PROVIDERS: [ProviderInfo; 6] = [
+    ProviderInfo {
+        provider: Provider::OpenAI,
+        name: "OpenAI",
+        color: Rgb::new(16, 163, 127),
+        hover_color: Rgb::new(13, 138, 106),
+        default_model: "gpt-5-mini",
+        known_models: &["gpt-5-mini", "gpt-5", "gpt-5-nano", "gpt-4.1", "gpt-4o-mini"],
+        host: "api.openai.com",
+        compressed_requests: false,
+        sends_temperature: true,
+        reasoning_effort: true,
+    },
+    ProviderInfo {
+        provider: Provider::Anthropic,
+        name: "Anthropic",
+        color: Rgb::new(217, 119, 6),
+        hover_color: Rgb::new(184, 99, 5),
+        default_model: "claude-3-7-sonnet-latest",
+        known_models: &[
+            "claude-3-7-sonnet-latest",
+            "claude-sonnet-4-0",
+            "claude-opus-4-1",
+            "claude-3-5-haiku-latest",
+        ],
+        host: "api.anthropic.com",
+        compressed_requests: false,
+        sends_temperature: true,
+        reasoning_effort: false,
+    },
+    ProviderInfo {
+        provider: Provider::Gemini,
+        name: "Gemini",
+        color: Rgb::new(66, 133, 244),
+        hover_color: Rgb::new(51, 103, 214),
+        default_model: "gemini-2.5-flash",
+        known_models: &["gemini-2.5-flash", "gemini-2.5-pro", "gemini-2.5-flash-lite"],
+        host: "generativelanguage.googleapis.com",
+        compressed_requests: true,
+        sends_temperature: false,
+        reasoning_effort: false,
+    },
+    ProviderInfo {
+        provider: Provider::DeepSeek,
+        name: "DeepSeek",
+        color: Rgb::new(124, 58, 237),
+        hover_color: Rgb::new(100, 41, 201),
+        default_model: "deepseek-chat",
+        known_models: &["deepseek-chat", "deepseek-reasoner"],
+        host: "api.deepseek.com",
+        compressed_requests: false,
+        sends_temperature: true,
+        reasoning_effort: false,
+    },
+    ProviderInfo {
+        provider: Provider::Mistral,
+        name: "Mistral",
+        color: Rgb::new(250, 82, 15),
+        hover_color: Rgb::new(200, 65, 12),
+        default_model: "mistral-small-latest",
+        known_models: &[
+            "mistral-small-latest",
+            "mistral-medium-latest",
+            "mistral-large-latest",
+            "ministral-8b-latest",
+        ],
+        host: "api.mistral.ai",
+        compressed_requests: false,
+        sends_temperature: true,
+        reasoning_effort: false,
+    },
+    ProviderInfo {
+        provider: Provider::Groq,
+        name: "Groq",
+        color: Rgb::new(245, 80, 54),
+        hover_color: Rgb::new(196, 64, 43),
+        default_model: "llama-3.3-70b-versatile",
+        known_models: &[
+            "llama-3.3-70b-versatile",
+            "llama-3.1-8b-instant",
+            "openai/gpt-oss-120b",
+            "openai/gpt-oss-20b",
+        ],
+        host: "api.groq.com",
+        compressed_requests: false,
+        sends_temperature: true,
+        reasoning_effort: false,
+    },
+];
+
+/// Liczba paneli przy pustym `PanelProviders`
+pub const DEFAULT_PANEL_COUNT: usize = 4;
+
+/// Najwięcej paneli w oknie (zapytań wysyłanych równolegle)
+pub const MAX_PANELS: usize = 8;
+
+/// Nazwy dostawców w kolejności `Provider::all()`
+pub const PROVIDER_NAMES: [&str; 6] = [
+    PROVIDERS[0].name,
+    PROVIDERS[1].name,
+    PROVIDERS[2].name,
+    PROVIDERS[3].name,
+    PROVIDERS[4].name,
+    PROVIDERS[5].name,
+];
+
+/// Dostawca o danej nazwie (bez rozróżniania wielkości liter)
+pub fn find(name: &str) -> Option<&'static ProviderInfo> {
+    PROVIDERS.iter().find(|info| info.name.eq_ignore_ascii_case(name.trim()))
+}
+
+/// Panel okna: dostawca i opcjonalnie własny model
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PanelSpec {
+    pub provider: Provider,
+    /// Model tylko dla tego panelu; `None` to model dostawcy z `[Models]`
+    pub model: Option<String>,
+}
+
+impl PanelSpec {
+    pub fn new(provider: Provider) -> Self {
+        Self { provider, model: None }
+    }
+
+    /// Wpis `PanelProviders`: `"Dostawca"` albo `"Dostawca:model"`
+    pub fn parse(entry: &str) -> Option<Self> {
+        let (name, model) = match entry.split_once(':') {
+            Some((name, model)) => (name, Some(model.trim())),
+            None => (entry, None),
+        };
+        Some(Self {
+            provider: find(name)?.provider,
+            model: model.filter(|m| !m.is_empty()).map(str::to_string),
+        })
+    }
+
+    pub fn to_entry(&self) -> String {
+        match &self.model {
+            Some(model) => format!("{}:{}", self.provider.name(), model),
+            None => self.provider.name().to_string(),
+        }
+    }
+
+    /// Model, którym odpowiada panel
+    pub fn model<'a>(&'a self, config: &'a Config) -> &'a str {
+        self.model.as_deref().unwrap_or_else(|| self.provider.model(config))
+    }
+
+    /// Konfiguracja z modelem panelu w miejscu modelu dostawcy
+    pub fn config(&self, config: &Config) -> Config {
+        let mut config = config.clone();
+        if let Some(model) = &self.model {
+            self.provider.set_model(&mut config, model);
+        }
+        config
+    }
+}
+
+/// Panele z ustawienia `PanelProviders`, po jednym na wpis
+///
+/// Ten sam dostawca może mieć kilka paneli, np. z różnymi modelami. Nieznane
+/// nazwy są pomijane, a wpisy ponad `MAX_PANELS` obcinane. Pusta lista daje
+/// pierwszych `DEFAULT_PANEL_COUNT` dostawców z `Provider::all()`.
+pub fn panel_specs(entries: &[String]) -> Vec<PanelSpec> {
+    let panels: Vec<PanelSpec> = entries
+        .iter()
+        .filter_map(|entry| PanelSpec::parse(entry))
+        .take(MAX_PANELS)
+        .collect();
+    if !panels.is_empty() {
+        return panels;
+    }
+    Provider::all()
+        .iter()
+        .take(DEFAULT_PANEL_COUNT)
+        .map(|provider| PanelSpec::new(*provider))
+        .collect()
+}
+
+/// Dostawcy paneli bez powtórzeń, w kolejności paneli
+pub fn panel_providers(panels: &[PanelSpec]) -> Vec<Provider> {
+    let mut providers: Vec<Provider> = Vec::with_capacity(panels.len());
+    for panel in panels {
+        if !providers.contains(&panel.provider) {
+            providers.push(panel.provider);
+        }
+    }
+    providers
+}
+
+/// Etykiety paneli: nazwa dostawcy, a przy własnym modelu także model.
+/// Powtórzone etykiety dostają numer, żeby każdy panel miał własną nazwę
+/// (kolejność komórek, historia rozmowy, reguły okien).
+pub fn panel_labels(panels: &[PanelSpec]) -> Vec<String> {
+    let base: Vec<String> = panels
+        .iter()
+        .map(|panel| match &panel.model {
+            Some(model) => format!("{} ({})", panel.provider.name(), model),
+            None => panel.provider.name().to_string(),
+        })
+        .collect();
+    base.iter()
+        .enumerate()
+        .map(|(i, label)| {
+            if base.iter().filter(|other| *other == label).count() == 1 {
+                return label.clone();
+            }
+            let number = base[..=i].iter().filter(|other| *other == label).count();
+            format!("{} {}", label, number)
+        })
+        .collect()
+}
+
+/// Kolumny siatki paneli: 2×2 dla czterech, trzy kolumny od pięciu
+pub fn grid_columns(count: usize) -> usize {
+    (1..).find(|columns| columns * columns >= count).unwrap_or(1)
+}
+
+/// Klasy CSS nagłówków paneli i przycisków „Użyj” (`panel-header-N`, `use-button-N`)
+pub fn panel_css(panels: &[Provider]) -> String {
+    panels
+        .iter()
+        .map(|provider| provider.info())
+        .enumerate()
+        .map(|(i, info)| {
+            format!(
+                ".use-button-{i} {{ background-color: {color}; }}\n\
+                 .use-button-{i}:hover {{ background-color: {hover}; }}\n\
+                 .panel-header-{i} {{ background-color: {color}; border-radius: 8px 8px 0 0; }}\n",
+                i = i,
+                color = info.color.to_css(),
+                hover = info.hover_color.to_css(),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_matches_provider_order() {
+        assert_eq!(PROVIDERS.len(), Provider::all().len());
+        for (info, provider) in PROVIDERS.iter().zip(Provider::all()) {
+            assert_eq!(info.provider, *provider);
+            assert_eq!(provider.info(), info);
+            assert_eq!(info.known_models.first(), Some(&info.default_model));
+        }
+        assert_eq!(PROVIDER_NAMES, ["OpenAI", "Anthropic", "Gemini", "DeepSeek", "Mistral", "Groq"]);
+        assert_eq!(find(" gemini").map(|i| i.provider), Some(Provider::Gemini));
+        assert!(find("Cohere").is_none());
+    }
+
+    #[test]
+    fn test_panel_specs() {
+        use Provider::*;
+        let providers = |entries: &[&str]| -> Vec<Provider> {
+            let entries: Vec<String> = entries.iter().map(|e| e.to_string()).collect();
+            panel_specs(&entries).iter().map(|p| p.provider).collect()
+        };
+        assert_eq!(providers(&[]), [OpenAI, Anthropic, Gemini, DeepSeek]);
+        assert_eq!(providers(&["Cohere"]), [OpenAI, Anthropic, Gemini, DeepSeek]);
+        assert_eq!(providers(&["mistral", "Cohere", "Gemini"]), [Mistral, Gemini]);
+        assert_eq!(providers(&["Groq"; 12]).len(), MAX_PANELS);
+
+        let entries = ["OpenAI", "openai: gpt-4o-mini ", "Groq:openai/gpt-oss-20b", "Gemini:"].map(String::from);
+        let panels = panel_specs(&entries);
+        assert_eq!(panels[0], PanelSpec::new(OpenAI));
+        assert_eq!(panels[1].model.as_deref(), Some("gpt-4o-mini"));
+        assert_eq!(panels[2].to_entry(), "Groq:openai/gpt-oss-20b");
+        assert_eq!(panels[3], PanelSpec::new(Gemini));
+
+        let config = Config::default();
+        assert_eq!(panels[0].model(&config), config.models.openai);
+        assert_eq!(panels[1].model(&config), "gpt-4o-mini");
+        assert_eq!(panels[1].config(&config).models.openai, "gpt-4o-mini");
+        assert_eq!(panels[0].config(&config), config);
+        assert_eq!(panel_providers(&panels), [OpenAI, Groq, Gemini]);
+    }
+
+    #[test]
+    fn test_panel_labels_are_unique() {
+        let entries = ["OpenAI", "OpenAI:gpt-4o-mini", "Gemini", "OpenAI", "Gemini:gemini-2.5-pro"].map(String::from);
+        assert_eq!(
+            panel_labels(&panel_specs(&entries)),
+            ["OpenAI 1", "OpenAI (gpt-4o-mini)", "Gemini", "OpenAI 2", "Gemini (gemini-2.5-pro)"]
+        );
+    }
+
+    #[test]
+    fn test_grid_columns() {
+        assert_eq!([1, 2, 3, 4, 5, 6, 8].map(grid_columns), [1, 2, 2, 2, 3, 3, 3]);
+    }
+
+    #[test]
+    fn test_panel_css() {
+        let defaults: Vec<Provider> = panel_specs(&[]).iter().map(|p| p.provider).collect();
+        let css = panel_css(&defaults);
+        assert!(css.contains(".use-button-0 { background-color: #10a37f; }"));
+        assert!(css.contains(".use-button-1:hover { background-color: #b86305; }"));
+        assert!(css.contains(".panel-header-3 { background-color: #7c3aed; border-radius: 8px 8px 0 0; }"));
+        assert_eq!(PROVIDERS[2].color.to_rgba(0.5), "rgba(66, 133, 244, 0.5)");
+
+        let css = panel_css(&[Provider::Mistral]);
+        assert!(css.contains(".panel-header-0 { background-color: #fa520f;"));
+        assert!(!css.contains("use-button-1"));
+    }
+}
@@ -0,0 +1,90 @@
+//! Zużycie tokenów podane przez dostawcę w odpowiedzi
+//!
+//! Dostawcy zwracają liczbę tokenów promptu i odpowiedzi (przy strumieniu
+//! zwykle w ostatnim fragmencie). Moduły dostawców zgłaszają ją przez
+//! `report`, a odbiorca ustawiony w `with_reporter` przekazuje ją dalej,
+//! np. do liczenia kosztów. Bez odbiorcy zgłoszenie jest pomijane.
+
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::Arc;
+
+/// Tokeny jednego wywołania; nazwy pól jak w API zgodnych z OpenAI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct TokenUsage {
+    #[serde(default)]
+    pub prompt_tokens: u64,
+    #[serde(default)]
+    pub completion_tokens: u64,
+}
+
+impl std::ops::Add for TokenUsage {
+    type Output = TokenUsage;
+
+    fn add(self, other: TokenUsage) -> TokenUsage {
+        TokenUsage {
+            prompt_tokens: self.prompt_tokens + other.prompt_tokens,
+            completion_tokens: self.completion_tokens + other.completion_tokens,
+        }
+    }
+}
+
+/// Przy strumieniu API zgodne z OpenAI podają zużycie tokenów tylko na prośbę, w ostatnim fragmencie
+#[derive(Debug, Serialize)]
+pub struct StreamOptions {
+    include_usage: bool,
+}
+
+impl StreamOptions {
+    pub fn with_usage() -> Self {
+        Self { include_usage: true }
+    }
+}
+
+type Reporter = Arc<dyn Fn(TokenUsage) + Send + Sync>;
+
+tokio::task_local! {
+    static REPORTER: Reporter;
+}
+
+/// Wykonuje `future` z odbiorcą zużycia zgłaszanego przez dostawców
+pub async fn with_reporter<F>(reporter: impl Fn(TokenUsage) + Send + Sync + 'static, future: F) -> F::Output
+where
+    F: Future,
+{
+    REPORTER.scope(Arc::new(reporter), future).await
+}
+
+/// Zgłasza zużycie z odpowiedzi dostawcy
+pub fn report(usage: TokenUsage) {
+    tracing::debug!("Token usage: {} prompt, {} completion", usage.prompt_tokens, usage.completion_tokens);
+    let _ = REPORTER.try_with(|reporter| reporter(usage));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_parse_openai_usage() {
+        let usage: TokenUsage =
+            serde_json::from_str(r#"{"prompt_tokens": 120, "completion_tokens": 45, "total_tokens": 165}"#).unwrap();
+        assert_eq!(usage, TokenUsage { prompt_tokens: 120, completion_tokens: 45 });
+        assert_eq!(
+            usage + TokenUsage { prompt_tokens: 10, completion_tokens: 5 },
+            TokenUsage { prompt_tokens: 130, completion_tokens: 50 }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_report_reaches_scoped_reporter() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        let usage = TokenUsage { prompt_tokens: 3, completion_tokens: 2 };
+        with_reporter(move |usage| sink.lock().unwrap().push(usage), async move { report(usage) }).await;
+        // Poza zakresem zgłoszenie nikogo nie dociera
+        report(usage);
+        assert_eq!(*seen.lock().unwrap(), vec![usage]);
+    }
+}
@@ -1,57 +1,217 @@
-use crate::api::anthropic::correct_text_anthropic;
-use crate::api::deepseek::correct_text_deepseek;
-use crate::api::gemini::correct_text_gemini;
-use crate::api::openai::correct_text_openai_with_callback;
+use crate::api::http_client::{last_transfer, Transfer};
+use crate::api::registry::{self, PanelSpec};
+use crate::api::{
+    converse_with_callback, correct_text_with_callback, correct_text_with_edits, rate_limit, suggest_subjects, token_usage,
+    Generation, Provider,
+};
+use crate::api::token_usage::TokenUsage;
+use crate::audit::{AuditEntry, AuditLog};
+use crate::breaker::CircuitBreaker;
+use crate::chooser;
 use crate::clipboard;
-use crate::config::Config;
-use crate::diff_gtk::set_text_with_diff;
-use crate::hotkey::{HotkeyEvent, HotkeyManager};
-use crate::prompts::{get_instruction_prompt, get_system_prompt, CorrectionStyle};
-use crate::tray::TrayManager;
-use crate::ui::SettingsDialog;
+use crate::config::{Appearance, Config, CustomStyle, Delivery, Job, StyleGuide};
+use crate::controller::{Delivery, ProviderEvents, SessionBackend, SessionController, SessionMessage};
+use crate::conversation::{self, Conversation, Exchange};
+use crate::diff;
+use crate::activity::{ActivityEvent, ActivityKind, ActivityLog};
+use crate::agreement;
+use crate::app_styles::{self, AppChoice};
+use crate::align;
+use crate::diff_gtk::{apply_agreement_heatmap, highlight_char_ranges, set_text_with_diff};
+use crate::digest;
+use crate::edits;
+use crate::facts::{self, FactCheck};
+use crate::gestures::{self, Swipe};
+use crate::history::{now_secs, History, HistoryEntry, Retention};
+use crate::history_cipher::{HistoryKey, KeyFile};
+use crate::instructions::{self, RecentInstructions};
+use crate::jank;
+use crate::length;
+use crate::matrix::{self, MatrixRow};
+use crate::metrics::Metrics;
+use crate::normalize;
+use crate::paste_guard;
+use crate::pipeline;
+use crate::platform::SourceApp;
+use crate::power::{self, PowerState};
+use crate::hotkey::{DoublePressAction, DoublePressDetector, HotkeyBindings, HotkeyConflict, HotkeyEvent, HotkeyManager};
+use crate::hotkey_portal::{self, PortalHotkeyEvent, PortalHotkeyHandle, PortalHotkeyManager};
+use crate::protect::{self, ProtectedText};
+use crate::prompts::{get_custom_system_prompt, get_instruction_prompt, get_system_prompt, instruction_style, usable_custom_styles, CorrectionStyle, LanguageContext, INSTRUCTION_STYLE};
+use crate::readability;
+use crate::rewrap;
+use crate::session::SessionContext;
+use crate::shutdown::SHUTDOWN;
+use crate::shortcuts;
+use crate::sound::{self, SoundEvent};
+use crate::styleguide;
+use crate::tokens::{self, TokenCount};
+use crate::tutorial::{self, Target};
+use crate::tray::{format_progress, RecentOutputs, TrayManager};
+use crate::usage::{self, BudgetStatus, UsageTracker};
+use crate::review::Review;
+use crate::scheduler::{self, JobState, JobStatus, LocalTime, Task};
+use crate::ui::appearance;
+use crate::ui::result_popup::monitor_at;
+use crate::ui::{ask_hotkey_alternative, ask_instruction, ask_model, ConflictChoice, ConsensusDialog, HistoryBrowser, PanelStatus, MergeDialog, ResultPopup, ReviewDialog, SettingsDialog, StreamBridge, StylePalette};
+use crate::window_rules::WindowRules;
 
 use gtk4::prelude::*;
-use gtk4::{gdk, glib};
+use gtk4::{gdk, gio, glib};
 use libadwaita as adw;
 use libadwaita::prelude::*;
 use std::cell::RefCell;
 use std::rc::Rc;
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use futures::future::{BoxFuture, FutureExt};
+use once_cell::sync::{Lazy, OnceCell};
 use std::sync::Arc;
 use std::time::Instant;
-use tracing::{error, info};
+use tracing::{error, info, warn, Instrument};
 
-const API_NAMES: [&str; 4] = ["OpenAI", "Anthropic", "Gemini", "DeepSeek"];
+/// Panele ustalone przy tworzeniu okna (`PanelProviders`)
+static PANELS: OnceCell<Vec<PanelSpec>> = OnceCell::new();
 
-#[derive(Clone, Copy)]
-pub struct ApiColor {
-    pub r: u8,
-    pub g: u8,
-    pub b: u8,
+/// Etykiety paneli (unikalne także przy powtórzonym dostawcy)
+static PANEL_LABELS: Lazy<Vec<String>> = Lazy::new(|| registry::panel_labels(panel_specs()));
+
+/// Etykiety w kolejności paneli
+static API_NAMES: Lazy<Vec<&str>> = Lazy::new(|| PANEL_LABELS.iter().map(String::as_str).collect());
+
+/// Panele w kolejności indeksów
+fn panel_specs() -> &'static [PanelSpec] {
+    PANELS.get_or_init(|| registry::panel_specs(&[]))
+}
+
+fn panel_provider(index: usize) -> Provider {
+    panel_specs()[index].provider
+}
+
+/// Szerokość okna (sp), poniżej której panele są w karuzeli zamiast w siatce
+const NARROW_WIDTH: f64 = 720.0;
+
+/// Jak długo okno ze skrótu czeka na sesję (np. wybór w palecie stylów)
+const PENDING_SOURCE_TTL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Zapytanie do jednego dostawcy
+#[derive(Clone)]
+enum ApiRequest {
+    /// Poprawka tekstu (z podmienionymi chronionymi fragmentami)
+    Correct { text: String, instruction: String },
+    /// Dopracowanie poprzedniej odpowiedzi w trybie rozmowy
+    Refine { history: Vec<Exchange>, message: String },
 }
 
-impl ApiColor {
-    pub const OPENAI: ApiColor = ApiColor { r: 16, g: 163, b: 127 };
-    pub const ANTHROPIC: ApiColor = ApiColor { r: 217, g: 119, b: 6 };
-    pub const GEMINI: ApiColor = ApiColor { r: 66, g: 133, b: 244 };
-    pub const DEEPSEEK: ApiColor = ApiColor { r: 124, g: 58, b: 237 };
+impl ApiRequest {
+    /// Wiadomość użytkownika zapamiętywana w rozmowie
+    fn user_message(&self) -> String {
+        match self {
+            ApiRequest::Correct { text, instruction } => conversation::user_message(instruction, text),
+            ApiRequest::Refine { message, .. } => message.clone(),
+        }
+    }
 
-    pub fn for_index(index: usize) -> ApiColor {
-        match index {
-            0 => Self::OPENAI,
-            1 => Self::ANTHROPIC,
-            2 => Self::GEMINI,
-            3 => Self::DEEPSEEK,
-            _ => Self::OPENAI,
+    /// Tokeny całego promptu dla modelu, razem z historią rozmowy
+    fn prompt_tokens(&self, provider: Provider, model: &str, system_prompt: &str) -> TokenCount {
+        let prompt = tokens::count_prompt(provider, model, system_prompt, &self.user_message());
+        match self {
+            ApiRequest::Correct { .. } => prompt,
+            ApiRequest::Refine { history, .. } => history.iter().fold(prompt, |total, e| {
+                total + tokens::count_prompt(provider, model, &e.user, &e.assistant)
+            }),
         }
     }
 
-    pub fn to_css(&self) -> String {
-        format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    /// Spodziewana długość odpowiedzi: poprawka jest mniej więcej tak długa jak tekst
+    fn expected_reply_tokens(&self, provider: Provider, model: &str) -> usize {
+        match self {
+            ApiRequest::Correct { text, .. } => tokens::count(provider, model, text).tokens,
+            ApiRequest::Refine { history, .. } => history
+                .last()
+                .map_or(0, |e| tokens::count(provider, model, &e.assistant).tokens),
+        }
     }
-    
-    pub fn to_rgba(&self, alpha: f32) -> String {
-        format!("rgba({}, {}, {}, {})", self.r, self.g, self.b, alpha)
+}
+
+/// Zapytanie z identyfikatorem wywołania i span logów, w którym ma się wykonać
+struct PreparedRequest {
+    request: ApiRequest,
+    request_id: String,
+    span: tracing::Span,
+}
+
+/// Zapytania sesji wysyłane do prawdziwych API
+struct ApiBackend {
+    /// Konfiguracja każdego panelu (z modelem panelu)
+    configs: Vec<Config>,
+    system_prompt: String,
+    protected: ProtectedText,
+}
+
+impl SessionBackend<PreparedRequest> for ApiBackend {
+    fn send(&self, index: usize, prepared: PreparedRequest, events: ProviderEvents) -> BoxFuture<'static, Result<String, String>> {
+        let provider = panel_provider(index);
+        let config = self.configs[index].clone();
+        let system = self.system_prompt.clone();
+        let protected = self.protected.clone();
+        let PreparedRequest { request, request_id, span } = prepared;
+
+        let notify = {
+            let events = events.clone();
+            move |wait| events.rate_limited(wait)
+        };
+        let report_usage = {
+            let events = events.clone();
+            move |usage| events.usage(usage)
+        };
+
+        let request = rate_limit::with_notifier(notify, async move {
+            info!("Sending request");
+            let on_chunk = || {
+                let events = events.clone();
+                move |chunk: &str| events.chunk(chunk)
+            };
+            let user_message = request.user_message();
+
+            let result = match request {
+                ApiRequest::Correct { text, instruction } if config.edit_mode.applies_to(&text) => {
+                    correct_text_with_edits(provider, &config, &text, &instruction, &system, Some(on_chunk())).await
+                }
+                ApiRequest::Correct { text, instruction } => {
+                    correct_text_with_callback(provider, &config, &text, &instruction, &system, Some(on_chunk())).await
+                }
+                ApiRequest::Refine { history, message } => {
+                    converse_with_callback(provider, &config, &history, &message, &system, Some(on_chunk())).await
+                }
+            };
+
+            // Za długi wynik wraca raz do modelu; przy błędzie zostaje pierwsza wersja
+            let max_chars = config.length.max_chars;
+            let too_long = result
+                .as_ref()
+                .ok()
+                .and_then(|raw| length::exceeds_limit(&protected.restore(raw).text, max_chars));
+            let result = match (result, too_long) {
+                (Ok(raw), Some(chars)) if !events.is_cancelled() => {
+                    info!("{} result has {} characters (limit {}), asking for a shorter one", API_NAMES[index], chars, max_chars);
+                    events.shortening(chars).await;
+                    let history = [Exchange {
+                        user: user_message,
+                        assistant: raw.clone(),
+                    }];
+                    let message = length::shorten_message(max_chars, chars);
+                    converse_with_callback(provider, &config, &history, &message, &system, Some(on_chunk()))
+                        .await
+                        .or(Ok(raw))
+                }
+                (result, _) => result,
+            };
+
+            if let Err(e) = &result {
+                warn!("Request failed: {}", e);
+            }
+            result.map_err(|e| MainWindow::error_with_request_id(e, &request_id))
+        });
+        token_usage::with_reporter(report_usage, request).instrument(span).boxed()
     }
 }
 
@@ -62,12 +222,37 @@ struct PanelState {
     progress_bar: gtk4::ProgressBar,
     time_label: gtk4::Label,
     status_icon: gtk4::Label,
+    /// Ikona symboliczna zamiast `status_icon` w trybie `MinimalEmoji`
+    status_image: gtk4::Image,
+    /// Stan panelu słownie, obok ikony
+    status_label: gtk4::Label,
     name_label: gtk4::Label,
+    /// Wybór modelu dostawcy w nagłówku; nieaktywny, gdy panel ma własny model
+    model_dropdown: gtk4::DropDown,
     header_box: gtk4::Box,
+    frame: gtk4::Frame,
     use_button: gtk4::Button,
     cancel_button: gtk4::Button,
+    pause_button: gtk4::Button,
+    restore_button: gtk4::Button,
+    warnings_label: gtk4::Label,
+    readability_label: gtk4::Label,
+    /// Model i parametry wyniku w stopce panelu
+    model_label: gtk4::Label,
+    stream: Rc<RefCell<StreamBridge>>,
     result_text: Rc<RefCell<String>>,
+    /// Odpowiedź modelu przed przywróceniem chronionych fragmentów
+    raw_response: Rc<RefCell<String>>,
+    /// Identyfikator ostatniego wywołania dostawcy (w logach i komunikatach błędów)
+    request_id: Rc<RefCell<String>>,
+    /// Rozmiary przesłanych danych przy ostatnim wyniku
+    transfer: Rc<RefCell<Option<Transfer>>>,
+    /// Dostawca podał zużycie tokenów dla bieżącego zapytania (koszt już doliczony)
+    usage_reported: Rc<RefCell<bool>>,
+    generation: Rc<RefCell<Option<Generation>>>,
     start_time: Rc<RefCell<Option<Instant>>>,
+    /// Czas odpowiedzi dostawcy w bieżącej sesji
+    latency: Rc<RefCell<Option<std::time::Duration>>>,
     is_processing: Rc<RefCell<bool>>,
     is_completed: Rc<RefCell<bool>>,
     has_error: Rc<RefCell<bool>>,
@@ -75,16 +260,87 @@ struct PanelState {
 
 struct AppState {
     config: Rc<RefCell<Config>>,
-    session_id: Arc<AtomicU64>,
-    cancel_flags: [Arc<AtomicBool>; 4],
+    /// Numer sesji, anulowanie i wyniki dostawców
+    session: SessionController,
     original_text: Rc<RefCell<String>>,
-    panels: [PanelState; 4],
+    /// Styl bieżącej sesji (do historii i powtórzenia sesji)
+    session_style: Rc<RefCell<CorrectionStyle>>,
+    /// Własny styl albo polecenie wpisane ręcznie, zastępujące styl w bieżącej sesji
+    session_custom: Rc<RefCell<Option<CustomStyle>>>,
+    panels: Vec<PanelState>,
     status_label: gtk4::Label,
     session_label: gtk4::Label,
     api_counter_label: gtk4::Label,
+    /// Wydatki wszystkich dostawców w bieżącym miesiącu
+    cost_label: gtk4::Label,
     hint_label: gtk4::Label,
-    completed_count: Rc<RefCell<u32>>,
     window: adw::ApplicationWindow,
+    tray: Option<Rc<RefCell<TrayManager>>>,
+    session_context: Rc<RefCell<SessionContext>>,
+    attach_button: gtk4::MenuButton,
+    power: PowerState,
+    breaker: Rc<RefCell<CircuitBreaker>>,
+    metrics: Metrics,
+    /// Klasa okna aktywnego w chwili naciśnięcia skrótu (cel wklejenia)
+    target_app: Rc<RefCell<Option<String>>>,
+    /// Okno ze skrótu czekające na następną sesję, z chwilą naciśnięcia
+    pending_source: Rc<RefCell<Option<(SourceApp, Instant)>>>,
+    /// Aplikacja, z której pochodzi tekst bieżącej sesji (do historii)
+    session_source: Rc<RefCell<Option<String>>>,
+    /// Skrót globalny i skróty stylów; zmiana przerejestrowuje je w wątku skrótu
+    hotkey_bindings: tokio::sync::watch::Sender<HotkeyBindings>,
+    /// Skrót przez portal GlobalShortcuts (tylko Wayland)
+    portal_hotkey: Option<PortalHotkeyHandle>,
+    window_rules: Rc<RefCell<WindowRules>>,
+    /// Panel dostawcy zapamiętanego dla aplikacji źródłowej
+    preferred_panel: Rc<RefCell<Option<usize>>>,
+    /// Tokeny promptu wysłanego do każdego panelu w bieżącej sesji (do szacowania kosztu)
+    prompt_tokens: Rc<RefCell<Vec<usize>>>,
+    /// Klucz historii po odblokowaniu hasłem (do końca działania programu)
+    history_key: Rc<RefCell<Option<HistoryKey>>>,
+    /// Odblokowanie historii anulowane; automatyczne zapisy nie pytają ponownie do końca działania
+    history_unlock_declined: Rc<RefCell<bool>>,
+    /// Początek bieżącej sesji (sekundy od epoki UNIX, do historii)
+    session_started: Rc<RefCell<u64>>,
+    /// Wpis historii z wynikiem wybranym wcześniej dla tego samego tekstu
+    previous_choice: Rc<RefCell<Option<HistoryEntry>>>,
+    /// Ostatnie wymiany z dostawcami w trybie rozmowy
+    conversation: Rc<RefCell<Conversation>>,
+    /// Chronione fragmenty bieżącej sesji (do odpowiedzi na dopracowania)
+    session_protected: Rc<RefCell<ProtectedText>>,
+    refine_bar: gtk4::Box,
+    panels_grid: gtk4::Grid,
+    /// Panele na wąskim oknie (zamiast siatki)
+    carousel: adw::Carousel,
+    narrow: Rc<RefCell<bool>>,
+    /// Nagłówek, pasek informacji i pasek narzędzi, chowane w oknie kompaktowym
+    chrome: Vec<gtk4::Widget>,
+    /// Zdarzenia sesji w zwijanym panelu aktywności (najnowsze na górze listy)
+    activity: Rc<RefCell<ActivityLog>>,
+    activity_pane: gtk4::Expander,
+    activity_list: gtk4::ListBox,
+    window_mode: Rc<RefCell<WindowMode>>,
+    /// Szerokość ręcznego łamania wierszy tekstu wejściowego (do łamania wyniku)
+    input_wrap: Rc<RefCell<Option<usize>>>,
+    /// Komunikaty o błędach w oknie (zamiast tylko w dzienniku)
+    toasts: adw::ToastOverlay,
+    /// Ostatnie użyte wyniki do ponownego skopiowania z zasobnika
+    recent_outputs: Rc<RefCell<RecentOutputs>>,
+    recent_instructions: Rc<RefCell<RecentInstructions>>,
+    /// Szacowane koszty dostawców w bieżącym miesiącu
+    usage: Rc<RefCell<UsageTracker>>,
+    /// Wyniki pośrednie ostatniego potoku stylów
+    pipeline_steps: Rc<RefCell<Vec<pipeline::Step>>>,
+    /// Skala tekstu w panelach zmieniana szczypaniem
+    text_zoom: Rc<RefCell<f64>>,
+    zoom_css: gtk4::CssProvider,
+}
+
+/// Pełne okno (uruchomienie ręczne) albo same panele bez ramki (skrót globalny)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WindowMode {
+    Full,
+    Compact,
 }
 
 pub struct MainWindow;
@@ -92,13 +348,17 @@ pub struct MainWindow;
 impl MainWindow {
     pub fn new(app: &adw::Application) -> adw::ApplicationWindow {
         let config_path = Config::get_config_path();
-        let config = Config::load(&config_path).unwrap_or_default();
+        let (config, config_problems) = Config::load_reporting(&config_path);
+        let chosen = PANELS.get_or_init(|| registry::panel_specs(&config.settings.panel_providers));
+        info!("Panels: {:?}", chosen.iter().map(PanelSpec::to_entry).collect::<Vec<_>>());
         
         let window = adw::ApplicationWindow::builder()
             .application(app)
             .title("PoprawiaczTekstuRs - Multi-API")
             .default_width(1200)
             .default_height(800)
+            .width_request(360)
+            .height_request(400)
             .build();
 
         Self::setup_layer_shell(&window);
@@ -107,12 +367,15 @@ impl MainWindow {
         let main_box = gtk4::Box::new(gtk4::Orientation::Vertical, 0);
         main_box.add_css_class("main-container");
 
-        let (header, settings_btn, paste_btn) = Self::build_header();
+        let (header, settings_btn, paste_btn, attach_btn) = Self::build_header();
         main_box.append(&header);
 
-        let (info_bar, status_label, session_label, api_counter_label, hint_label) = Self::build_info_bar();
+        let (info_bar, status_label, session_label, api_counter_label, cost_label, hint_label) = Self::build_info_bar();
         main_box.append(&info_bar);
 
+        let (activity_pane, activity_list, activity_copy_btn) = Self::build_activity_pane();
+        main_box.append(&activity_pane);
+
         let panels_grid = gtk4::Grid::builder()
             .row_spacing(12)
             .column_spacing(12)
@@ -124,39 +387,200 @@ impl MainWindow {
             .vexpand(true)
             .build();
 
-        let panels = Self::create_panels();
+        let panels = Self::create_panels(&config);
         
         for (i, panel) in panels.iter().enumerate() {
-            let row = (i / 2) as i32;
-            let col = (i % 2) as i32;
-            
-            let panel_frame = Self::build_panel_frame(i, panel);
-            panels_grid.attach(&panel_frame, col, row, 1, 1);
+            Self::build_panel_frame(i, panel);
         }
+        Self::place_panels(&panels_grid, &panels, &config.settings.panel_order(&API_NAMES[..]));
 
         main_box.append(&panels_grid);
 
-        let (toolbar, cancel_btn, original_btn, hide_btn) = Self::build_toolbar();
+        let carousel = adw::Carousel::builder()
+            .hexpand(true)
+            .vexpand(true)
+            .spacing(12)
+            .build();
+        let carousel_box = gtk4::Box::new(gtk4::Orientation::Vertical, 6);
+        carousel_box.set_margin_start(12);
+        carousel_box.set_margin_end(12);
+        carousel_box.set_margin_top(12);
+        carousel_box.set_visible(false);
+        carousel_box.append(&carousel);
+        carousel_box.append(&adw::CarouselIndicatorDots::builder().carousel(&carousel).build());
+        main_box.append(&carousel_box);
+
+        let (refine_bar, refine_entry, refine_btn) = Self::build_refine_bar();
+        refine_bar.set_visible(config.conversation.enabled);
+        main_box.append(&refine_bar);
+
+        let (toolbar, cancel_btn, original_btn, merge_btn, review_btn, matrix_btn, hide_btn) = Self::build_toolbar();
+        let repeat_btn = gtk4::Button::with_label("🔁 Powtórz ostatni tekst");
+        repeat_btn.set_tooltip_text(Some("Wyślij jeszcze raz tekst ostatniej sesji, bez kopiowania go do schowka"));
+        toolbar.insert_child_after(&repeat_btn, Some(&cancel_btn));
+        let repeat_style_btn = Self::build_repeat_style_button();
+        toolbar.insert_child_after(&repeat_style_btn, Some(&repeat_btn));
+        let consensus_btn = gtk4::Button::with_label("🔀 Zgodność");
+        consensus_btn.set_tooltip_text(Some("Wyniki zdanie po zdaniu z zaznaczonymi miejscami, w których dostawcy się różnią"));
+        toolbar.insert_child_after(&consensus_btn, Some(&review_btn));
+        let (char_limit_btn, char_limit_spin) = Self::build_char_limit_button(config.length.max_chars);
+        toolbar.insert_child_after(&char_limit_btn, Some(&matrix_btn));
+        let costs_btn = gtk4::Button::with_label("💰 Koszty");
+        costs_btn.set_tooltip_text(Some("Szacowane koszty dostawców w tym miesiącu i pozostały budżet"));
+        toolbar.insert_child_after(&costs_btn, Some(&char_limit_btn));
+        let jobs_btn = gtk4::Button::with_label("🗓 Zadania");
+        jobs_btn.set_tooltip_text(Some("Zaplanowane zadania wsadowe i wynik ich ostatniego uruchomienia"));
+        toolbar.insert_child_after(&jobs_btn, Some(&costs_btn));
+        let preview_btn = gtk4::Button::with_label("🔍 Podgląd promptu");
+        preview_btn.set_tooltip_text(Some("Prompt, który dostanie każdy panel, z szacowaną liczbą tokenów"));
+        toolbar.insert_child_after(&preview_btn, Some(&jobs_btn));
+        let history_btn = gtk4::Button::with_label("🕘 Historia");
+        history_btn.set_tooltip_text(Some("Wcześniejsze poprawki z wyszukiwaniem i ponownym kopiowaniem"));
+        toolbar.insert_child_after(&history_btn, Some(&preview_btn));
         main_box.append(&toolbar);
 
-        window.set_content(Some(&main_box));
+        let toasts = adw::ToastOverlay::new();
+        toasts.set_child(Some(&main_box));
+        window.set_content(Some(&toasts));
+        status_label.connect_label_notify(|label| {
+            let text = label.label();
+            let plain = appearance::plain(&text);
+            if plain != text.as_str() {
+                label.set_label(plain);
+            }
+        });
+        Self::apply_appearance(&window, &config.appearance);
+
+        let breaker = CircuitBreaker::new(
+            API_NAMES.len(),
+            config.circuit_breaker.failure_threshold,
+            config.circuit_breaker.cooldown(),
+        );
 
+        let conversation = Conversation::new(config.conversation.limit());
         let state = Rc::new(RefCell::new(AppState {
             config: Rc::new(RefCell::new(config)),
-            session_id: Arc::new(AtomicU64::new(0)),
-            cancel_flags: std::array::from_fn(|_| Arc::new(AtomicBool::new(false))),
+            session: SessionController::new(panels.len()),
             original_text: Rc::new(RefCell::new(String::new())),
+            session_style: Rc::new(RefCell::new(CorrectionStyle::Normal)),
+            session_custom: Rc::new(RefCell::new(None)),
             panels: panels.clone(),
             status_label,
             session_label,
             api_counter_label,
+            cost_label,
             hint_label,
-            completed_count: Rc::new(RefCell::new(0)),
             window: window.clone(),
+            tray: None,
+            session_context: Rc::new(RefCell::new(SessionContext::new())),
+            attach_button: attach_btn,
+            power: PowerState::new(),
+            breaker: Rc::new(RefCell::new(breaker)),
+            metrics: Metrics::new(),
+            target_app: Rc::new(RefCell::new(None)),
+            pending_source: Rc::new(RefCell::new(None)),
+            session_source: Rc::new(RefCell::new(None)),
+            hotkey_bindings: tokio::sync::watch::channel(HotkeyBindings::default()).0,
+            portal_hotkey: None,
+            window_rules: Rc::new(RefCell::new(WindowRules::load(WindowRules::default_path()))),
+            preferred_panel: Rc::new(RefCell::new(None)),
+            prompt_tokens: Rc::new(RefCell::new(vec![0; panel_specs().len()])),
+            history_key: Rc::new(RefCell::new(None)),
+            history_unlock_declined: Rc::new(RefCell::new(false)),
+            session_started: Rc::new(RefCell::new(0)),
+            previous_choice: Rc::new(RefCell::new(None)),
+            conversation: Rc::new(RefCell::new(conversation)),
+            session_protected: Rc::new(RefCell::new(ProtectedText::new("", &[]))),
+            refine_bar,
+            panels_grid,
+            carousel,
+            narrow: Rc::new(RefCell::new(false)),
+            chrome: vec![
+                header.clone().upcast(),
+                info_bar.clone().upcast(),
+                activity_pane.clone().upcast(),
+                toolbar.clone().upcast(),
+            ],
+            activity: Rc::new(RefCell::new(ActivityLog::default())),
+            activity_pane,
+            activity_list,
+            window_mode: Rc::new(RefCell::new(WindowMode::Full)),
+            input_wrap: Rc::new(RefCell::new(None)),
+            toasts,
+            recent_outputs: Rc::new(RefCell::new(RecentOutputs::default())),
+            recent_instructions: Rc::new(RefCell::new(RecentInstructions::load(RecentInstructions::default_path()))),
+            usage: Rc::new(RefCell::new(UsageTracker::load(UsageTracker::default_path()))),
+            pipeline_steps: Rc::new(RefCell::new(Vec::new())),
+            text_zoom: Rc::new(RefCell::new(1.0)),
+            zoom_css: gtk4::CssProvider::new(),
         }));
 
+        let mut shortcut_buttons = vec![("paste", paste_btn.clone())];
+        // Skróty wyników są tylko dla pierwszych czterech paneli
+        let use_results = ["use-result-1", "use-result-2", "use-result-3", "use-result-4"];
+        shortcut_buttons.extend(use_results.into_iter().zip(&panels).map(|(name, panel)| (name, panel.use_button.clone())));
+        shortcut_buttons.extend([
+            ("cancel-all", cancel_btn.clone()),
+            ("repeat-last", repeat_btn.clone()),
+            ("merge", merge_btn.clone()),
+            ("review", review_btn.clone()),
+            ("settings", settings_btn.clone()),
+            ("hide", hide_btn.clone()),
+        ]);
+        Self::setup_shortcuts(&window, shortcut_buttons, &state.borrow().config.borrow().shortcuts);
+        for problem in &config_problems {
+            Self::show_error_toast(&state, &format!("Konfiguracja: {}", problem), None);
+        }
+
         Self::connect_panel_buttons(state.clone());
+        Self::refresh_attachments(&state);
+        Self::connect_merge_button(state.clone(), merge_btn);
+        Self::connect_review_button(state.clone(), review_btn);
+        Self::connect_consensus_button(state.clone(), consensus_btn);
+        Self::connect_matrix_button(state.clone(), matrix_btn);
+        let state_clone = state.clone();
+        repeat_btn.connect_clicked(move |_| {
+            let state = state_clone.clone();
+            glib::spawn_future_local(async move {
+                Self::repeat_last_text(&state, None).await;
+            });
+        });
+        let repeat_style_action = gio::SimpleAction::new("repeat-style", Some(glib::VariantTy::STRING));
+        let state_clone = state.clone();
+        repeat_style_action.connect_activate(move |_, param| {
+            let Some(style) = param.and_then(|p| p.get::<String>()) else {
+                return;
+            };
+            let state = state_clone.clone();
+            glib::spawn_future_local(async move {
+                Self::repeat_last_text(&state, Some(CorrectionStyle::from_str(&style))).await;
+            });
+        });
+        window.add_action(&repeat_style_action);
+        Self::connect_refine_bar(state.clone(), refine_entry, refine_btn);
+        Self::connect_char_limit(state.clone(), char_limit_btn, char_limit_spin);
+        let state_clone = state.clone();
+        costs_btn.connect_clicked(move |_| Self::show_usage(&state_clone));
+        let state_clone = state.clone();
+        jobs_btn.connect_clicked(move |_| Self::show_jobs(&state_clone));
+        let state_clone = state.clone();
+        preview_btn.connect_clicked(move |_| Self::show_prompt_preview(&state_clone));
+        let state_clone = state.clone();
+        activity_copy_btn.connect_clicked(move |_| {
+            let text = state_clone.borrow().activity.borrow().text();
+            match clipboard::write_text(&text) {
+                Ok(()) => state_clone.borrow().status_label.set_text("📋 Skopiowano dziennik aktywności"),
+                Err(e) => Self::show_error_toast(&state_clone, &format!("Nie udało się skopiować dziennika: {}", e), None),
+            }
+        });
+        let state_clone = state.clone();
+        history_btn.connect_clicked(move |_| {
+            let state = state_clone.clone();
+            glib::spawn_future_local(async move { Self::show_history_browser(&state).await });
+        });
         
+        Self::setup_tutorial(&state, paste_btn.clone().upcast(), settings_btn.clone().upcast());
+
         Self::connect_buttons(
             state.clone(),
             settings_btn,
@@ -164,16 +588,59 @@ impl MainWindow {
             cancel_btn,
             original_btn,
             hide_btn,
-            window.clone(),
         );
 
+        Self::setup_power_save(state.clone());
+        Self::setup_metrics(&state);
+        Self::update_cost_badge(&state);
         Self::setup_hotkey(state.clone());
-        Self::setup_tray(window.clone());
+        Self::setup_tray(state.clone());
+        Self::setup_shutdown(&state);
+        Self::refresh_snippet_menus(&state);
         Self::setup_close_handler(window.clone());
+        Self::setup_gestures(&state);
+        Self::setup_panel_drag(&state);
+        Self::setup_window_modes(&state);
+        Self::setup_launch_text(&state);
+        Self::setup_breakpoint(&state, &carousel_box);
+        Self::prune_history(&state);
+        Self::setup_digest(state.clone());
+        Self::setup_scheduler(state.clone());
 
         window
     }
 
+    /// Akcje okna wywoływane skrótami; każda klika odpowiadający jej przycisk
+    fn setup_shortcuts(
+        window: &adw::ApplicationWindow,
+        buttons: Vec<(&'static str, gtk4::Button)>,
+        overrides: &std::collections::BTreeMap<String, String>,
+    ) {
+        for (name, button) in buttons {
+            let action = gio::SimpleAction::new(name, None);
+            let button_weak = button.downgrade();
+            action.connect_activate(move |_, _| {
+                if let Some(button) = button_weak.upgrade() {
+                    if button.is_sensitive() {
+                        button.emit_clicked();
+                    }
+                }
+            });
+            window.add_action(&action);
+        }
+        Self::apply_shortcuts(window, overrides);
+    }
+
+    fn apply_shortcuts(window: &adw::ApplicationWindow, overrides: &std::collections::BTreeMap<String, String>) {
+        let Some(app) = window.application() else {
+            return;
+        };
+        for action in shortcuts::ACTIONS {
+            let accels: Vec<&str> = shortcuts::effective_accel(action, overrides).into_iter().collect();
+            app.set_accels_for_action(&shortcuts::detailed_name(action), &accels);
+        }
+    }
+
     fn setup_layer_shell(_window: &adw::ApplicationWindow) {
         #[cfg(feature = "wayland")]
         {
@@ -184,6 +651,43 @@ impl MainWindow {
         }
     }
 
+    /// Ustawia `[appearance]`: animacje GTK i emoji; przyciski odzyskują emoji dopiero po ponownym uruchomieniu
+    fn apply_appearance(window: &adw::ApplicationWindow, settings: &Appearance) {
+        appearance::set_minimal_emoji(settings.minimal_emoji);
+        if let Some(gtk_settings) = gtk4::Settings::default() {
+            if settings.reduced_motion {
+                gtk_settings.set_gtk_enable_animations(false);
+            } else {
+                gtk_settings.reset_property("gtk-enable-animations");
+            }
+        }
+        if settings.minimal_emoji {
+            Self::strip_button_emoji(window.upcast_ref());
+        }
+    }
+
+    /// Czy GTK animuje (wyłączone przez `ReducedMotion` albo ustawienia systemu)
+    fn animations_enabled() -> bool {
+        gtk4::Settings::default().is_some_and(|settings| settings.is_gtk_enable_animations())
+    }
+
+    /// Usuwa emoji z początku napisów przycisków w całym drzewie widżetów
+    fn strip_button_emoji(widget: &gtk4::Widget) {
+        if let Some(button) = widget.downcast_ref::<gtk4::Button>() {
+            if let Some(label) = button.label() {
+                let plain = appearance::strip_leading_emoji(&label);
+                if plain != label.as_str() {
+                    button.set_label(plain);
+                }
+            }
+        }
+        let mut child = widget.first_child();
+        while let Some(widget) = child {
+            Self::strip_button_emoji(&widget);
+            child = widget.next_sibling();
+        }
+    }
+
     fn apply_css() {
         let css = r#"
             .main-container {
@@ -204,6 +708,24 @@ impl MainWindow {
                 color: #a0a0a0;
                 margin-left: 16px;
             }
+            .review-chosen {
+                border: 2px solid #3584e4;
+            }
+            .styleguide-warnings {
+                font-size: 12px;
+                color: #e5a50a;
+                margin: 4px 12px 0 12px;
+            }
+            .readability-label {
+                font-size: 11px;
+                opacity: 0.7;
+                margin: 4px 12px 0 12px;
+            }
+            .model-label {
+                font-size: 10px;
+                opacity: 0.5;
+                margin: 2px 12px 0 12px;
+            }
             .hint-label {
                 font-size: 13px;
                 color: #808080;
@@ -229,6 +751,10 @@ impl MainWindow {
                 font-size: 16px;
                 padding-left: 8px;
             }
+            .panel-status {
+                font-size: 12px;
+                opacity: 0.7;
+            }
             .cancel-btn {
                 padding: 2px 6px;
                 min-width: 24px;
@@ -253,14 +779,6 @@ impl MainWindow {
             .use-button:disabled {
                 opacity: 0.5;
             }
-            .use-button-0 { background-color: #10a37f; }
-            .use-button-0:hover { background-color: #0d8a6a; }
-            .use-button-1 { background-color: #d97706; }
-            .use-button-1:hover { background-color: #b86305; }
-            .use-button-2 { background-color: #4285f4; }
-            .use-button-2:hover { background-color: #3367d6; }
-            .use-button-3 { background-color: #7c3aed; }
-            .use-button-3:hover { background-color: #6429c9; }
             textview {
                 background-color: #2a2a32;
                 color: #e0e0e0;
@@ -271,10 +789,7 @@ impl MainWindow {
                 background-color: #2a2a32;
                 color: #e0e0e0;
             }
-            .panel-header-0 { background-color: #10a37f; border-radius: 8px 8px 0 0; }
-            .panel-header-1 { background-color: #d97706; border-radius: 8px 8px 0 0; }
-            .panel-header-2 { background-color: #4285f4; border-radius: 8px 8px 0 0; }
-            .panel-header-3 { background-color: #7c3aed; border-radius: 8px 8px 0 0; }
+            .preferred-panel { box-shadow: inset 0 0 0 2px #facc15; }
             progressbar trough {
                 min-height: 3px;
                 background-color: rgba(255,255,255,0.1);
@@ -285,17 +800,24 @@ impl MainWindow {
             }
         "#;
 
+        // Kolory dostawców z rejestru
+        let css = format!("{}{}", css, registry::panel_css(&panel_specs().iter().map(|panel| panel.provider).collect::<Vec<_>>()));
+
         let provider = gtk4::CssProvider::new();
-        provider.load_from_data(css);
+        provider.load_from_data(&css);
 
+        let Some(display) = gdk::Display::default() else {
+            warn!("No display available, skipping CSS");
+            return;
+        };
         gtk4::style_context_add_provider_for_display(
-            &gdk::Display::default().expect("Could not get display"),
+            &display,
             &provider,
             gtk4::STYLE_PROVIDER_PRIORITY_APPLICATION,
         );
     }
 
-    fn build_header() -> (adw::HeaderBar, gtk4::Button, gtk4::Button) {
+    fn build_header() -> (adw::HeaderBar, gtk4::Button, gtk4::Button, gtk4::MenuButton) {
         let header = adw::HeaderBar::new();
         header.set_title_widget(Some(&gtk4::Label::new(Some("PoprawiaczTekstuRs - Multi-API"))));
 
@@ -307,10 +829,15 @@ impl MainWindow {
         paste_btn.add_css_class("suggested-action");
         header.pack_start(&paste_btn);
 
-        (header, settings_btn, paste_btn)
+        let attach_btn = gtk4::MenuButton::new();
+        attach_btn.set_label("📎");
+        attach_btn.set_tooltip_text(Some("Załączniki sesji (style guide, glosariusz)"));
+        header.pack_start(&attach_btn);
+
+        (header, settings_btn, paste_btn, attach_btn)
     }
 
-    fn build_info_bar() -> (gtk4::Box, gtk4::Label, gtk4::Label, gtk4::Label, gtk4::Label) {
+    fn build_info_bar() -> (gtk4::Box, gtk4::Label, gtk4::Label, gtk4::Label, gtk4::Label, gtk4::Label) {
         let info_bar = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
         info_bar.add_css_class("info-bar");
 
@@ -323,10 +850,14 @@ impl MainWindow {
         session_label.add_css_class("info-label");
         info_bar.append(&session_label);
 
-        let api_counter_label = gtk4::Label::new(Some("🤖 API: 0/4"));
+        let api_counter_label = gtk4::Label::new(Some(&format!("🤖 API: 0/{}", API_NAMES.len())));
         api_counter_label.add_css_class("info-label");
         info_bar.append(&api_counter_label);
 
+        let cost_label = gtk4::Label::new(Some("💰 0.00 USD"));
+        cost_label.add_css_class("info-label");
+        info_bar.append(&cost_label);
+
         let spacer = gtk4::Box::new(gtk4::Orientation::Horizontal, 0);
         spacer.set_hexpand(true);
         info_bar.append(&spacer);
@@ -335,11 +866,38 @@ impl MainWindow {
         hint_label.add_css_class("hint-label");
         info_bar.append(&hint_label);
 
-        (info_bar, status_label, session_label, api_counter_label, hint_label)
+        (info_bar, status_label, session_label, api_counter_label, cost_label, hint_label)
     }
 
-    fn create_panels() -> [PanelState; 4] {
-        std::array::from_fn(|i| {
+    /// Zwinięty panel aktywności pod paskiem informacji
+    fn build_activity_pane() -> (gtk4::Expander, gtk4::ListBox, gtk4::Button) {
+        let list = gtk4::ListBox::new();
+        list.set_selection_mode(gtk4::SelectionMode::None);
+        let scrolled = gtk4::ScrolledWindow::builder()
+            .hscrollbar_policy(gtk4::PolicyType::Never)
+            .min_content_height(80)
+            .max_content_height(160)
+            .propagate_natural_height(true)
+            .child(&list)
+            .build();
+
+        let copy_button = gtk4::Button::with_label("📋 Kopiuj dziennik");
+        copy_button.set_halign(gtk4::Align::End);
+        copy_button.add_css_class("flat");
+        let content = gtk4::Box::new(gtk4::Orientation::Vertical, 4);
+        content.set_margin_start(12);
+        content.set_margin_end(12);
+        content.append(&scrolled);
+        content.append(&copy_button);
+
+        let pane = gtk4::Expander::builder().label("📜 Aktywność").child(&content).build();
+        pane.set_margin_start(12);
+        pane.set_margin_end(12);
+        (pane, list, copy_button)
+    }
+
+    fn create_panels(config: &Config) -> Vec<PanelState> {
+        (0..panel_specs().len()).map(|i| {
             let text_view = gtk4::TextView::builder()
                 .editable(false)
                 .wrap_mode(gtk4::WrapMode::Word)
@@ -358,8 +916,19 @@ impl MainWindow {
             progress_bar.set_visible(false);
             progress_bar.set_fraction(0.0);
 
-            let status_icon = gtk4::Label::new(Some(""));
+            // Ikona jest ozdobnikiem; stan czyta się z `status_label` i opisu ramki
+            let status_icon = gtk4::Label::builder()
+                .accessible_role(gtk4::AccessibleRole::Presentation)
+                .build();
             status_icon.add_css_class("status-icon");
+            let status_image = gtk4::Image::builder()
+                .accessible_role(gtk4::AccessibleRole::Presentation)
+                .visible(false)
+                .build();
+            status_image.add_css_class("status-icon");
+
+            let status_label = gtk4::Label::new(None);
+            status_label.add_css_class("panel-status");
 
             let time_label = gtk4::Label::new(None);
             time_label.add_css_class("time-label");
@@ -370,14 +939,39 @@ impl MainWindow {
             let header_box = gtk4::Box::new(gtk4::Orientation::Horizontal, 6);
             header_box.add_css_class(&format!("panel-header-{}", i));
 
+            let model_dropdown = gtk4::DropDown::builder().valign(gtk4::Align::Center).build();
+            model_dropdown.add_css_class("flat");
+            Self::fill_model_dropdown(&model_dropdown, i, config);
+            if panel_specs()[i].model.is_some() {
+                model_dropdown.set_sensitive(false);
+                model_dropdown.set_tooltip_text(Some("Model tego panelu jest ustawiony w Ustawienia → API → Panele"));
+            } else {
+                model_dropdown.set_tooltip_text(Some("Model dostawcy; wybór zapisuje się w ustawieniach"));
+            }
+
             let cancel_button = gtk4::Button::with_label("✕");
             cancel_button.add_css_class("cancel-btn");
             cancel_button.add_css_class("flat");
             cancel_button.set_sensitive(false);
             cancel_button.set_tooltip_text(Some("Anuluj to API"));
 
+            let pause_button = gtk4::Button::with_label("⏸");
+            pause_button.add_css_class("cancel-btn");
+            pause_button.add_css_class("flat");
+            pause_button.set_sensitive(false);
+            pause_button.set_tooltip_text(Some("Wstrzymaj dopisywanie tekstu"));
+
+            let restore_button = gtk4::Button::with_label("↺");
+            restore_button.add_css_class("cancel-btn");
+            restore_button.add_css_class("flat");
+            restore_button.set_sensitive(false);
+            restore_button.set_tooltip_text(Some("Przywróć wynik API (Ctrl+Z / Ctrl+Shift+Z cofa i ponawia zmiany)"));
+
             header_box.append(&status_icon);
+            header_box.append(&status_image);
+            header_box.append(&status_label);
             header_box.append(&name_label);
+            header_box.append(&model_dropdown);
             header_box.append(&spinner);
             header_box.append(&time_label);
             
@@ -385,8 +979,30 @@ impl MainWindow {
             spacer.set_hexpand(true);
             header_box.append(&spacer);
             
+            header_box.append(&restore_button);
+            header_box.append(&pause_button);
             header_box.append(&cancel_button);
 
+            let warnings_label = gtk4::Label::new(None);
+            warnings_label.add_css_class("styleguide-warnings");
+            warnings_label.set_halign(gtk4::Align::Start);
+            warnings_label.set_wrap(true);
+            warnings_label.set_visible(false);
+
+            let readability_label = gtk4::Label::new(None);
+            readability_label.add_css_class("readability-label");
+            readability_label.set_halign(gtk4::Align::Start);
+            readability_label.set_visible(false);
+
+            let model_label = gtk4::Label::new(None);
+            model_label.add_css_class("model-label");
+            model_label.set_halign(gtk4::Align::Start);
+            model_label.set_selectable(true);
+            model_label.set_visible(false);
+
+            let frame = gtk4::Frame::new(None);
+            frame.update_property(&[gtk4::accessible::Property::Label(&PanelStatus::Idle.accessible_label(API_NAMES[i]))]);
+
             let use_button = gtk4::Button::with_label(&format!("📋 Użyj {}", API_NAMES[i]));
             use_button.add_css_class("use-button");
             use_button.add_css_class(&format!("use-button-{}", i));
@@ -398,21 +1014,72 @@ impl MainWindow {
                 progress_bar,
                 time_label,
                 status_icon,
+                status_image,
+                status_label,
                 name_label,
+                model_dropdown,
                 header_box,
+                frame,
                 use_button,
                 cancel_button,
+                pause_button,
+                restore_button,
+                warnings_label,
+                readability_label,
+                model_label,
+                stream: Rc::new(RefCell::new(StreamBridge::new())),
                 result_text: Rc::new(RefCell::new(String::new())),
+                raw_response: Rc::new(RefCell::new(String::new())),
+                transfer: Rc::new(RefCell::new(None)),
+                usage_reported: Rc::new(RefCell::new(false)),
+                generation: Rc::new(RefCell::new(None)),
+                request_id: Rc::new(RefCell::new(String::new())),
                 start_time: Rc::new(RefCell::new(None)),
+                latency: Rc::new(RefCell::new(None)),
                 is_processing: Rc::new(RefCell::new(false)),
                 is_completed: Rc::new(RefCell::new(false)),
                 has_error: Rc::new(RefCell::new(false)),
             }
-        })
+        }).collect()
+    }
+
+    /// Modele do wyboru w nagłówku panelu; bieżący jest pierwszy i zaznaczony
+    fn fill_model_dropdown(dropdown: &gtk4::DropDown, index: usize, config: &Config) {
+        let spec = &panel_specs()[index];
+        let models = spec.provider.model_choices(&spec.config(config));
+        let names: Vec<&str> = models.iter().map(String::as_str).collect();
+        dropdown.set_model(Some(&gtk4::StringList::new(&names)));
+        dropdown.set_selected(0);
+    }
+
+    /// Po zmianie modeli w konfiguracji odświeża listy w nagłówkach paneli
+    fn refresh_model_dropdowns(state: &Rc<RefCell<AppState>>) {
+        let state_ref = state.borrow();
+        let config = state_ref.config.borrow().clone();
+        for (i, panel) in state_ref.panels.iter().enumerate() {
+            Self::fill_model_dropdown(&panel.model_dropdown, i, &config);
+        }
+    }
+
+    /// Zmienia model dostawcy panelu z nagłówka i zapisuje go w `[Models]`.
+    /// Wybór dotyczy wszystkich paneli tego dostawcy bez własnego modelu.
+    fn change_panel_model(state: &Rc<RefCell<AppState>>, index: usize, model: &str) {
+        let provider = panel_provider(index);
+        let mut config = state.borrow().config.borrow().clone();
+        if panel_specs()[index].model.is_some() || provider.model(&config) == model {
+            return;
+        }
+        info!("{} model changed to {} from the panel header", provider.name(), model);
+        provider.set_model(&mut config, model);
+        state
+            .borrow()
+            .status_label
+            .set_text(&format!("🤖 {}: model {}", API_NAMES[index], model));
+        Self::save_config_in_background(state, config);
     }
 
     fn build_panel_frame(index: usize, panel: &PanelState) -> gtk4::Frame {
-        let frame = gtk4::Frame::new(None);
+        let frame = panel.frame.clone();
         frame.add_css_class("panel-frame");
         frame.set_hexpand(true);
         frame.set_vexpand(true);
@@ -431,6 +1098,9 @@ impl MainWindow {
             .build();
 
         vbox.append(&scrolled);
+        vbox.append(&panel.warnings_label);
+        vbox.append(&panel.readability_label);
+        vbox.append(&panel.model_label);
 
         let button_box = gtk4::Box::new(gtk4::Orientation::Horizontal, 0);
         button_box.set_margin_start(8);
@@ -447,7 +1117,7 @@ impl MainWindow {
         frame
     }
 
-    fn build_toolbar() -> (gtk4::Box, gtk4::Button, gtk4::Button, gtk4::Button) {
+    fn build_toolbar() -> (gtk4::Box, gtk4::Button, gtk4::Button, gtk4::Button, gtk4::Button, gtk4::Button, gtk4::Button) {
         let toolbar = gtk4::Box::new(gtk4::Orientation::Horizontal, 12);
         toolbar.set_margin_start(12);
         toolbar.set_margin_end(12);
@@ -461,6 +1131,18 @@ impl MainWindow {
         let original_btn = gtk4::Button::with_label("⚙️ Ustawienia");
         toolbar.append(&original_btn);
 
+        let merge_btn = gtk4::Button::with_label("🧩 Złóż wynik");
+        merge_btn.set_tooltip_text(Some("Złóż wynik z fragmentów różnych paneli"));
+        toolbar.append(&merge_btn);
+
+        let review_btn = gtk4::Button::with_label("🔎 Przegląd zmian");
+        review_btn.set_tooltip_text(Some("Porównaj zmiany wszystkich dostawców jedna po drugiej"));
+        toolbar.append(&review_btn);
+
+        let matrix_btn = gtk4::Button::with_label("📊 Porównanie");
+        matrix_btn.set_tooltip_text(Some("Eksportuj porównanie dostawców z tej sesji do HTML"));
+        toolbar.append(&matrix_btn);
+
         let spacer = gtk4::Box::new(gtk4::Orientation::Horizontal, 0);
         spacer.set_hexpand(true);
         toolbar.append(&spacer);
@@ -468,10 +1150,121 @@ impl MainWindow {
         let hide_btn = gtk4::Button::with_label("🔽 Minimalizuj");
         toolbar.append(&hide_btn);
 
-        (toolbar, cancel_btn, original_btn, hide_btn)
+        (toolbar, cancel_btn, original_btn, merge_btn, review_btn, matrix_btn, hide_btn)
+    }
+
+    /// Przycisk z okienkiem „zmieść w N znaków” (0 wyłącza limit)
+    fn build_char_limit_button(max_chars: u32) -> (gtk4::MenuButton, gtk4::SpinButton) {
+        let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 6);
+        vbox.set_margin_start(6);
+        vbox.set_margin_end(6);
+        vbox.set_margin_top(6);
+        vbox.set_margin_bottom(6);
+
+        let label = gtk4::Label::new(Some("Zmieść wynik w N znaków (0 = bez limitu)"));
+        label.set_halign(gtk4::Align::Start);
+        vbox.append(&label);
+
+        let spin = gtk4::SpinButton::with_range(0.0, 10_000.0, 10.0);
+        spin.set_value(max_chars as f64);
+        vbox.append(&spin);
+
+        let presets = gtk4::Box::new(gtk4::Orientation::Horizontal, 6);
+        for (chars, name) in [(160, "SMS"), (280, "X"), (155, "Meta")] {
+            let button = gtk4::Button::with_label(&format!("{} ({})", name, chars));
+            button.add_css_class("flat");
+            let spin = spin.clone();
+            button.connect_clicked(move |_| spin.set_value(chars as f64));
+            presets.append(&button);
+        }
+        vbox.append(&presets);
+
+        let popover = gtk4::Popover::new();
+        popover.set_child(Some(&vbox));
+
+        let button = gtk4::MenuButton::new();
+        button.set_label(&length::limit_label(max_chars));
+        button.set_tooltip_text(Some("Limit znaków wyniku; za długie wyniki są automatycznie skracane"));
+        button.set_popover(Some(&popover));
+
+        (button, spin)
+    }
+
+    /// Menu "Powtórz innym stylem" obok przycisku powtórzenia
+    fn build_repeat_style_button() -> gtk4::MenuButton {
+        let menu = gio::Menu::new();
+        for style in CorrectionStyle::all() {
+            let label = format!("{} {}", style.emoji(), style.display_name_pl());
+            let item = gio::MenuItem::new(Some(&label), None);
+            item.set_action_and_target_value(Some("win.repeat-style"), Some(&style.as_str().to_variant()));
+            menu.append_item(&item);
+        }
+
+        let button = gtk4::MenuButton::new();
+        button.set_icon_name("pan-down-symbolic");
+        button.set_tooltip_text(Some("Powtórz ostatni tekst innym stylem"));
+        button.set_menu_model(Some(&menu));
+        button
+    }
+
+    /// Limit z okienka trafia do konfiguracji i jest zapisywany po jego zamknięciu
+    fn connect_char_limit(state: Rc<RefCell<AppState>>, button: gtk4::MenuButton, spin: gtk4::SpinButton) {
+        let button_weak = button.downgrade();
+        spin.connect_value_changed(move |spin| {
+            if let Some(button) = button_weak.upgrade() {
+                button.set_label(&length::limit_label(spin.value() as u32));
+            }
+        });
+
+        let Some(popover) = button.popover() else {
+            return;
+        };
+        popover.connect_closed(move |_| {
+            let max_chars = spin.value() as u32;
+            let mut config = state.borrow().config.borrow().clone();
+            if config.length.max_chars == max_chars {
+                return;
+            }
+            config.length.max_chars = max_chars;
+            info!("Character limit set to {}", max_chars);
+            Self::save_config_in_background(&state, config);
+        });
+    }
+
+    /// Pole poleceń dopracowania wyników (tryb rozmowy)
+    fn build_refine_bar() -> (gtk4::Box, gtk4::Entry, gtk4::Button) {
+        let bar = gtk4::Box::new(gtk4::Orientation::Horizontal, 6);
+        bar.set_margin_start(12);
+        bar.set_margin_end(12);
+
+        let entry = gtk4::Entry::builder()
+            .placeholder_text("Dopracuj wyniki, np. krócej, bardziej formalnie…")
+            .hexpand(true)
+            .build();
+        bar.append(&entry);
+
+        let button = gtk4::Button::with_label("💬 Dopracuj");
+        button.set_tooltip_text(Some("Wyślij polecenie do każdego dostawcy razem z jego poprzednimi odpowiedziami"));
+        bar.append(&button);
+
+        (bar, entry, button)
+    }
+
+    fn connect_refine_bar(state: Rc<RefCell<AppState>>, entry: gtk4::Entry, button: gtk4::Button) {
+        let send = Rc::new(move |entry: &gtk4::Entry| {
+            let request = entry.text().to_string();
+            if Self::refine_results(&state, &request) {
+                entry.set_text("");
+            }
+        });
+
+        let send_clone = send.clone();
+        entry.connect_activate(move |entry| send_clone(entry));
+        button.connect_clicked(move |_| send(&entry));
     }
 
     fn connect_panel_buttons(state: Rc<RefCell<AppState>>) {
+        type PanelHandler = fn(&Rc<RefCell<AppState>>, usize);
         let state_ref = state.borrow();
         
         for (i, panel) in state_ref.panels.iter().enumerate() {
@@ -489,495 +1282,4060 @@ impl MainWindow {
             panel.cancel_button.connect_clicked(move |_| {
                 Self::cancel_single_api(&state_clone, index);
             });
+
+            let panel_clone = panel.clone();
+            panel.pause_button.connect_clicked(move |_| {
+                Self::toggle_stream_pause(&panel_clone);
+            });
+
+            let state_clone = state.clone();
+            let index = i;
+            panel.restore_button.connect_clicked(move |_| {
+                Self::restore_api_result(&state_clone, index);
+            });
+
+            let state_clone = state.clone();
+            panel.model_dropdown.connect_selected_notify(move |dropdown| {
+                let model = dropdown.selected_item().and_downcast::<gtk4::StringObject>();
+                if let Some(model) = model {
+                    Self::change_panel_model(&state_clone, i, &model.string());
+                }
+            });
+
+            let reset_action = gio::SimpleAction::new("reset-breaker", None);
+            let state_clone = state.clone();
+            let index = i;
+            reset_action.connect_activate(move |_, _| {
+                Self::reset_circuit_breaker(&state_clone, index);
+            });
+            let snippet_action = gio::SimpleAction::new("insert-snippet", Some(glib::VariantTy::INT32));
+            let state_clone = state.clone();
+            let index = i;
+            snippet_action.connect_activate(move |_, param| {
+                if let Some(snippet) = param.and_then(|p| p.get::<i32>()) {
+                    Self::insert_snippet(&state_clone, index, snippet as usize);
+                }
+            });
+
+            let actions = gio::SimpleActionGroup::new();
+            actions.add_action(&reset_action);
+            actions.add_action(&snippet_action);
+
+            let handlers: [(&str, PanelHandler); 10] = [
+                ("copy", Self::copy_panel),
+                ("copy-markdown", Self::copy_panel_markdown),
+                ("rerun", Self::rerun_provider),
+                ("rerun-model", Self::rerun_with_model),
+                ("show-raw", Self::show_raw_response),
+                ("show-pipeline", Self::show_pipeline_steps),
+                ("compare", Self::compare_with_original),
+                ("compare-previous", Self::compare_with_previous),
+                ("save", Self::save_panel_to_file),
+                ("subject-lines", Self::suggest_subject_lines),
+            ];
+            for (name, handler) in handlers {
+                let action = gio::SimpleAction::new(name, None);
+                let state_clone = state.clone();
+                let index = i;
+                action.connect_activate(move |_, _| handler(&state_clone, index));
+                actions.add_action(&action);
+            }
+            panel.text_view.insert_action_group("panel", Some(&actions));
         }
     }
 
-    fn use_api_result(state: &Rc<RefCell<AppState>>, index: usize, panel: &PanelState) {
-        let text = panel.result_text.borrow().clone();
-        if text.is_empty() {
+    /// Odbudowuje menu kontekstowe paneli i podmenu zasobnika po zmianie wstawek
+    fn refresh_snippet_menus(state: &Rc<RefCell<AppState>>) {
+        let state_ref = state.borrow();
+        let names: Vec<String> = state_ref
+            .config
+            .borrow()
+            .snippets
+            .iter()
+            .map(|s| s.name.clone())
+            .collect();
+
+        let menu = gio::Menu::new();
+        let result_section = gio::Menu::new();
+        result_section.append(Some("📋 Kopiuj wynik"), Some("panel.copy"));
+        result_section.append(Some("📝 Kopiuj jako Markdown (ze zmianami)"), Some("panel.copy-markdown"));
+        result_section.append(Some("🔍 Porównaj z oryginałem"), Some("panel.compare"));
+        result_section.append(Some("🕘 Porównaj z poprzednim wyborem"), Some("panel.compare-previous"));
+        result_section.append(Some("📄 Surowa odpowiedź"), Some("panel.show-raw"));
+        result_section.append(Some("⛓ Kroki potoku"), Some("panel.show-pipeline"));
+        result_section.append(Some("💾 Zapisz do pliku…"), Some("panel.save"));
+        result_section.append(Some("✉️ Propozycje tematu maila"), Some("panel.subject-lines"));
+        menu.append_section(None, &result_section);
+
+        let provider_section = gio::Menu::new();
+        provider_section.append(Some("🔄 Uruchom ponownie tego dostawcę"), Some("panel.rerun"));
+        provider_section.append(Some("🧪 Uruchom ponownie z modelem…"), Some("panel.rerun-model"));
+        provider_section.append(Some("🔌 Włącz ponownie dostawcę"), Some("panel.reset-breaker"));
+        menu.append_section(None, &provider_section);
+        if !names.is_empty() {
+            let snippets_menu = gio::Menu::new();
+            for (i, name) in names.iter().enumerate() {
+                let item = gio::MenuItem::new(Some(name), None);
+                item.set_action_and_target_value(Some("panel.insert-snippet"), Some(&(i as i32).to_variant()));
+                snippets_menu.append_item(&item);
+            }
+            menu.append_submenu(Some("📝 Wstaw wstawkę"), &snippets_menu);
+        }
+        for panel in &state_ref.panels {
+            panel.text_view.set_extra_menu(Some(&menu));
+        }
+
+        if let Some(tray) = &state_ref.tray {
+            tray.borrow().set_snippets(names);
+        }
+    }
+
+    /// Tekst gotowego wyniku panelu (z edycjami użytkownika)
+    fn completed_panel_text(state: &Rc<RefCell<AppState>>, index: usize) -> Option<String> {
+        let state_ref = state.borrow();
+        let panel = &state_ref.panels[index];
+        if !*panel.is_completed.borrow() {
+            state_ref.status_label.set_text(&format!("⚠️ {}: brak wyniku", API_NAMES[index]));
+            return None;
+        }
+        Some(Self::panel_text(panel))
+    }
+
+    fn copy_panel(state: &Rc<RefCell<AppState>>, index: usize) {
+        let Some(text) = Self::completed_panel_text(state, index) else {
             return;
+        };
+        match clipboard::write_text(&text) {
+            Ok(()) => state.borrow().status_label.set_text(&format!("📋 Skopiowano wynik: {}", API_NAMES[index])),
+            Err(e) => {
+                error!("Failed to copy result: {}", e);
+                let state_clone = state.clone();
+                Self::show_error_toast(
+                    state,
+                    &format!("Nie udało się skopiować wyniku: {}", e),
+                    Some(Box::new(move || Self::copy_panel(&state_clone, index))),
+                );
+            }
         }
+    }
 
-        if let Err(e) = clipboard::write_text(&text) {
-            error!("Failed to copy text: {}", e);
+    /// Błąd, o którym użytkownik musi wiedzieć; `retry` dodaje przycisk "Ponów"
+    fn show_error_toast(state: &Rc<RefCell<AppState>>, message: &str, retry: Option<Box<dyn Fn()>>) {
+        let toast = adw::Toast::builder()
+            .title(message)
+            .use_markup(false)
+            .priority(adw::ToastPriority::High)
+            .timeout(10)
+            .build();
+        if let Some(retry) = retry {
+            toast.set_button_label(Some("Ponów"));
+            toast.connect_button_clicked(move |_| retry());
+        }
+        state.borrow().toasts.add_toast(toast);
+    }
+
+    /// Kopiuje wynik jako Markdown z zaznaczonymi zmianami względem oryginału
+    fn copy_panel_markdown(state: &Rc<RefCell<AppState>>, index: usize) {
+        let Some(text) = Self::completed_panel_text(state, index) else {
             return;
+        };
+        let markdown = diff::to_markdown(&state.borrow().original_text.borrow(), &text);
+        match clipboard::write_text(&markdown) {
+            Ok(()) => state
+                .borrow()
+                .status_label
+                .set_text(&format!("📝 Skopiowano jako Markdown: {}", API_NAMES[index])),
+            Err(e) => {
+                error!("Failed to copy Markdown: {}", e);
+                let state_clone = state.clone();
+                Self::show_error_toast(
+                    state,
+                    &format!("Nie udało się skopiować Markdown: {}", e),
+                    Some(Box::new(move || Self::copy_panel_markdown(&state_clone, index))),
+                );
+            }
         }
+    }
 
-        info!("Copied result from {} to clipboard", API_NAMES[index]);
+    fn show_raw_response(state: &Rc<RefCell<AppState>>, index: usize) {
+        let state_ref = state.borrow();
+        let raw = state_ref.panels[index].raw_response.borrow().clone();
+        let request_id = state_ref.panels[index].request_id.borrow().clone();
+        if raw.is_empty() {
+            let id = if request_id.is_empty() { String::new() } else { format!(" (ID: {})", request_id) };
+            state_ref.status_label.set_text(&format!("⚠️ {}: brak odpowiedzi{}", API_NAMES[index], id));
+            return;
+        }
+        let title = if request_id.is_empty() {
+            format!("Surowa odpowiedź - {}", API_NAMES[index])
+        } else {
+            format!("Surowa odpowiedź - {} (ID: {})", API_NAMES[index], request_id)
+        };
+        let text = match *state_ref.panels[index].transfer.borrow() {
+            Some(transfer) => format!("📶 {}\n\n{}", transfer.summary(), raw),
+            None => raw,
+        };
+        Self::show_text_dialog(&state_ref.window, &title, &text);
+    }
 
+    /// Wyniki pośrednie potoku stylów (dostępne w panelu dostawcy potoku)
+    fn show_pipeline_steps(state: &Rc<RefCell<AppState>>, _index: usize) {
         let state_ref = state.borrow();
-        state_ref.window.set_visible(false);
-        drop(state_ref);
+        let steps = state_ref.pipeline_steps.borrow();
+        if steps.is_empty() {
+            state_ref.status_label.set_text("⚠️ W tej sesji nie było potoku stylów");
+            return;
+        }
+        let text = pipeline::format_steps(&state_ref.original_text.borrow(), &steps);
+        Self::show_text_dialog(&state_ref.window, "Kroki potoku", &text);
+    }
 
-        std::thread::spawn(move || {
-            std::thread::sleep(std::time::Duration::from_millis(300));
-            
-            #[cfg(target_os = "linux")]
-            {
-                let _ = std::process::Command::new("xdotool")
-                    .args(["key", "ctrl+v"])
-                    .spawn();
-            }
-            
-            #[cfg(target_os = "windows")]
-            {
-                use std::process::Command;
-                let _ = Command::new("powershell")
-                    .args(["-Command", "[System.Windows.Forms.SendKeys]::SendWait('^v')"])
-                    .spawn();
-            }
-        });
+    fn compare_with_original(state: &Rc<RefCell<AppState>>, index: usize) {
+        let Some(text) = Self::completed_panel_text(state, index) else {
+            return;
+        };
+        let state_ref = state.borrow();
+        let original = state_ref.original_text.borrow().clone();
+        Self::show_compare_dialog(
+            &state_ref.window,
+            &format!("Porównanie z oryginałem - {}", API_NAMES[index]),
+            ["Oryginał", "Wynik"],
+            &original,
+            &text,
+        );
+    }
 
-        info!("Used result from {} and simulated Ctrl+V", API_NAMES[index]);
+    /// Wynik panelu obok wyniku wybranego poprzednio dla tego samego tekstu
+    fn compare_with_previous(state: &Rc<RefCell<AppState>>, index: usize) {
+        let Some(text) = Self::completed_panel_text(state, index) else {
+            return;
+        };
+        let state_ref = state.borrow();
+        let Some(previous) = state_ref.previous_choice.borrow().clone() else {
+            state_ref
+                .status_label
+                .set_text("⚠️ Tego tekstu nie ma w historii (albo historia jest zablokowana)");
+            return;
+        };
+        Self::show_compare_dialog(
+            &state_ref.window,
+            &format!("Porównanie z poprzednim wyborem - {}", API_NAMES[index]),
+            [format!("Poprzednio: {}", previous.provider).as_str(), "Wynik"],
+            &previous.result,
+            &text,
+        );
     }
 
-    fn cancel_single_api(state: &Rc<RefCell<AppState>>, index: usize) {
+    fn save_panel_to_file(state: &Rc<RefCell<AppState>>, index: usize) {
+        let Some(text) = Self::completed_panel_text(state, index) else {
+            return;
+        };
         let state_ref = state.borrow();
-        
-        state_ref.cancel_flags[index].store(true, Ordering::SeqCst);
-        
-        let panel = &state_ref.panels[index];
-        panel.spinner.stop();
-        panel.spinner.set_visible(false);
-        panel.progress_bar.set_visible(false);
-        panel.cancel_button.set_sensitive(false);
-        panel.status_icon.set_text("❌");
-        panel.name_label.set_text(&format!("{} (anulowano)", API_NAMES[index]));
-        panel.text_view.buffer().set_text("❌ Anulowano");
-        *panel.is_processing.borrow_mut() = false;
-        *panel.has_error.borrow_mut() = true;
+        let chooser = gtk4::FileChooserNative::new(
+            Some("Zapisz wynik"),
+            Some(&state_ref.window),
+            gtk4::FileChooserAction::Save,
+            Some("Zapisz"),
+            Some("Anuluj"),
+        );
+        chooser.set_current_name(&format!("poprawka-{}.txt", API_NAMES[index].to_lowercase()));
+        drop(state_ref);
 
-        info!("Cancelled API {}", API_NAMES[index]);
+        let state_clone = state.clone();
+        chooser.connect_response(move |chooser, response| {
+            if response == gtk4::ResponseType::Accept {
+                if let Some(path) = chooser.file().and_then(|f| f.path()) {
+                    Self::write_result_file(&state_clone, path, text.clone());
+                }
+            }
+            chooser.destroy();
+        });
+        chooser.show();
     }
 
-    fn connect_buttons(
-        state: Rc<RefCell<AppState>>,
-        settings_btn: gtk4::Button,
-        paste_btn: gtk4::Button,
-        cancel_btn: gtk4::Button,
-        original_btn: gtk4::Button,
-        hide_btn: gtk4::Button,
-        window: adw::ApplicationWindow,
-    ) {
-        let state_clone = state.clone();
-        paste_btn.connect_clicked(move |_| {
-            glib::spawn_future_local({
-                let state = state_clone.clone();
-                async move {
-                    Self::handle_hotkey_triggered(&state).await;
+    /// Prosi dostawcę panelu o tematy maila dla poprawionej treści
+    fn suggest_subject_lines(state: &Rc<RefCell<AppState>>, index: usize) {
+        let Some(body) = Self::completed_panel_text(state, index) else {
+            return;
+        };
+        let state_ref = state.borrow();
+        let config = panel_specs()[index].config(&state_ref.config.borrow());
+        state_ref
+            .status_label
+            .set_text(&format!("✉️ {}: szukanie tematu maila...", API_NAMES[index]));
+        drop(state_ref);
+        info!("Asking {} for subject lines", API_NAMES[index]);
+
+        let provider = panel_provider(index);
+        let state = state.clone();
+        glib::spawn_future_local(async move {
+            let result = crate::TOKIO_RUNTIME
+                .spawn(async move { suggest_subjects(provider, &config, &body).await })
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|r| r.map_err(|e| e.to_string()));
+            match result {
+                Ok(subjects) => {
+                    state.borrow().status_label.set_text(&format!("✉️ Tematy maila: {}", API_NAMES[index]));
+                    Self::show_subject_lines(&state, index, &subjects);
                 }
-            });
+                Err(e) => {
+                    error!("Subject line suggestions failed: {}", e);
+                    let state_clone = state.clone();
+                    Self::show_error_toast(
+                        &state,
+                        &format!("{}: nie udało się zaproponować tematu: {}", API_NAMES[index], e),
+                        Some(Box::new(move || Self::suggest_subject_lines(&state_clone, index))),
+                    );
+                }
+            }
         });
+    }
 
-        let state_clone = state.clone();
-        cancel_btn.connect_clicked(move |_| {
-            Self::cancel_all_processing(&state_clone);
-        });
+    /// Okienko z tematami pod nagłówkiem panelu; kliknięcie kopiuje temat
+    fn show_subject_lines(state: &Rc<RefCell<AppState>>, index: usize, subjects: &[String]) {
+        let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 6);
+        vbox.set_margin_top(6);
+        vbox.set_margin_bottom(6);
+        vbox.set_margin_start(6);
+        vbox.set_margin_end(6);
+        let title = gtk4::Label::new(Some("Kliknij, aby skopiować temat"));
+        title.add_css_class("dim-label");
+        vbox.append(&title);
 
-        let state_clone = state.clone();
-        let window_clone = window.clone();
-        original_btn.connect_clicked(move |_| {
-            let state_ref = state_clone.borrow();
-            let config = state_ref.config.borrow().clone();
-            drop(state_ref);
-            
-            let dialog = SettingsDialog::new(&window_clone, &config);
-            
-            let state_for_save = state_clone.clone();
-            dialog.connect_save(move |new_config| {
-                let config_path = Config::get_config_path();
-                if let Err(e) = new_config.save(&config_path) {
-                    error!("Failed to save config: {}", e);
-                } else {
-                    let state_ref = state_for_save.borrow();
-                    *state_ref.config.borrow_mut() = new_config;
-                    info!("Settings saved successfully");
-                }
+        let popover = gtk4::Popover::new();
+        for subject in subjects {
+            let button = gtk4::Button::with_label(subject);
+            button.add_css_class("flat");
+            button.set_tooltip_text(Some("Kopiuj temat"));
+            let state_clone = state.clone();
+            let popover_clone = popover.clone();
+            let subject = subject.clone();
+            button.connect_clicked(move |_| {
+                Self::copy_subject_line(&state_clone, &subject);
+                popover_clone.popdown();
             });
-            
-            dialog.present();
+            vbox.append(&button);
+        }
+        popover.set_child(Some(&vbox));
+        popover.set_parent(&state.borrow().panels[index].header_box);
+        popover.connect_closed(|popover| {
+            let popover = popover.clone();
+            // Odpięcie dopiero po obsłudze zamknięcia
+            glib::idle_add_local_once(move || popover.unparent());
         });
+        popover.popup();
+    }
 
-        let window_weak = window.downgrade();
-        hide_btn.connect_clicked(move |_| {
-            if let Some(win) = window_weak.upgrade() {
-                win.set_visible(false);
-                info!("Window hidden to tray");
+    fn copy_subject_line(state: &Rc<RefCell<AppState>>, subject: &str) {
+        match clipboard::write_text(subject) {
+            Ok(()) => state.borrow().status_label.set_text(&format!("📋 Skopiowano temat: {}", subject)),
+            Err(e) => {
+                error!("Failed to copy subject line: {}", e);
+                let state_clone = state.clone();
+                let subject = subject.to_string();
+                Self::show_error_toast(
+                    state,
+                    &format!("Nie udało się skopiować tematu: {}", e),
+                    Some(Box::new(move || Self::copy_subject_line(&state_clone, &subject))),
+                );
             }
-        });
-
-        let state_clone = state.clone();
-        let window_clone = window.clone();
-        settings_btn.connect_clicked(move |_| {
-            let state_ref = state_clone.borrow();
-            let config = state_ref.config.borrow().clone();
-            drop(state_ref);
-            
-            let dialog = SettingsDialog::new(&window_clone, &config);
-            
-            let state_for_save = state_clone.clone();
-            dialog.connect_save(move |new_config| {
-                let config_path = Config::get_config_path();
-                if let Err(e) = new_config.save(&config_path) {
-                    error!("Failed to save config: {}", e);
-                } else {
-                    let state_ref = state_for_save.borrow();
-                    *state_ref.config.borrow_mut() = new_config;
-                    info!("Settings saved successfully");
-                }
-            });
-            
-            dialog.present();
-        });
+        }
     }
 
-    fn cancel_all_processing(state: &Rc<RefCell<AppState>>) {
+    /// Wstawia wstawkę w miejscu kursora gotowego wyniku
+    fn insert_snippet(state: &Rc<RefCell<AppState>>, index: usize, snippet: usize) {
         let state_ref = state.borrow();
-        
-        for flag in &state_ref.cancel_flags {
-            flag.store(true, Ordering::SeqCst);
+        let Some(text) = state_ref.config.borrow().snippets.get(snippet).map(|s| s.text.clone()) else {
+            return;
+        };
+        let panel = &state_ref.panels[index];
+        if !*panel.is_completed.borrow() {
+            return;
         }
-        
-        for (i, panel) in state_ref.panels.iter().enumerate() {
-            panel.spinner.stop();
-            panel.spinner.set_visible(false);
-            panel.progress_bar.set_visible(false);
-            panel.progress_bar.set_fraction(0.0);
-            panel.cancel_button.set_sensitive(false);
-            
-            if *panel.is_processing.borrow() {
-                panel.status_icon.set_text("❌");
-                panel.name_label.set_text(&format!("{} (anulowano)", API_NAMES[i]));
-                panel.text_view.buffer().set_text("❌ Anulowano");
-                *panel.is_processing.borrow_mut() = false;
+        panel.text_view.buffer().insert_interactive_at_cursor(&text, true);
+    }
+
+    fn write_result_file(state: &Rc<RefCell<AppState>>, path: std::path::PathBuf, text: String) {
+        match std::fs::write(&path, &text) {
+            Ok(()) => {
+                info!("Result saved to {}", path.display());
+                state.borrow().status_label.set_text(&format!("💾 Zapisano wynik: {}", path.display()));
+            }
+            Err(e) => {
+                error!("Failed to save result: {}", e);
+                state.borrow().status_label.set_text(&format!("❌ Nie udało się zapisać wyniku: {}", e));
+                let state_clone = state.clone();
+                Self::show_error_toast(
+                    state,
+                    &format!("Nie udało się zapisać wyniku: {}", e),
+                    Some(Box::new(move || Self::write_result_file(&state_clone, path.clone(), text.clone()))),
+                );
             }
         }
-        
-        state_ref.status_label.set_text("❌ Anulowano przetwarzanie");
-        state_ref.hint_label.set_text("");
-        
-        info!("Cancelled all processing");
     }
 
-    fn show_original_text_dialog(parent: &adw::ApplicationWindow, text: &str) {
-        let dialog = gtk4::Window::builder()
-            .title("Oryginalny tekst")
-            .transient_for(parent)
-            .modal(true)
-            .default_width(500)
-            .default_height(400)
-            .build();
-
-        let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 12);
-        vbox.set_margin_start(12);
-        vbox.set_margin_end(12);
-        vbox.set_margin_top(12);
-        vbox.set_margin_bottom(12);
+    /// Wkleja wstawkę z zasobnika do aktywnej aplikacji
+    fn paste_snippet(state: &Rc<RefCell<AppState>>, index: usize) {
+        let Some(snippet) = state.borrow().config.borrow().snippets.get(index).cloned() else {
+            return;
+        };
+        if let Err(e) = clipboard::write_text(&snippet.text) {
+            error!("Failed to copy snippet: {}", e);
+            let state_clone = state.clone();
+            Self::show_error_toast(
+                state,
+                &format!("Nie udało się skopiować wstawki „{}”: {}", snippet.name, e),
+                Some(Box::new(move || Self::paste_snippet(&state_clone, index))),
+            );
+            return;
+        }
 
-        let text_view = gtk4::TextView::builder()
-            .editable(false)
-            .wrap_mode(gtk4::WrapMode::Word)
-            .build();
-        text_view.buffer().set_text(text);
+        std::thread::spawn(|| {
+            std::thread::sleep(std::time::Duration::from_millis(300));
+            if let Err(e) = crate::platform::simulate_paste() {
+                warn!("Snippet copied, paste simulation failed: {}", e);
+            }
+        });
+        info!("Pasted snippet '{}' from tray", snippet.name);
+    }
 
-        let scrolled = gtk4::ScrolledWindow::builder()
-            .hscrollbar_policy(gtk4::PolicyType::Never)
-            .vscrollbar_policy(gtk4::PolicyType::Automatic)
-            .hexpand(true)
-            .vexpand(true)
-            .child(&text_view)
-            .build();
+    /// Odbudowuje listę załączników w popoverze przycisku 📎
+    fn refresh_attachments(state: &Rc<RefCell<AppState>>) {
+        let state_ref = state.borrow();
+        let context = state_ref.session_context.borrow();
 
-        vbox.append(&scrolled);
+        let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 6);
+        vbox.set_margin_start(6);
+        vbox.set_margin_end(6);
+        vbox.set_margin_top(6);
+        vbox.set_margin_bottom(6);
 
-        let button_box = gtk4::Box::new(gtk4::Orientation::Horizontal, 6);
-        button_box.set_halign(gtk4::Align::End);
+        if context.is_empty() {
+            let label = gtk4::Label::new(Some("Brak załączników"));
+            label.add_css_class("dim-label");
+            vbox.append(&label);
+        }
+        for attachment in context.attachments() {
+            let text = if attachment.truncated {
+                format!("📄 {} (obcięty)", attachment.name)
+            } else {
+                format!("📄 {}", attachment.name)
+            };
+            let label = gtk4::Label::new(Some(&text));
+            label.set_halign(gtk4::Align::Start);
+            vbox.append(&label);
+        }
 
-        let text_clone = text.to_string();
-        let copy_btn = gtk4::Button::with_label("📋 Kopiuj");
-        copy_btn.connect_clicked(move |_| {
-            let _ = clipboard::write_text(&text_clone);
+        let add_btn = gtk4::Button::with_label("➕ Dołącz plik...");
+        let state_clone = state.clone();
+        add_btn.connect_clicked(move |_| {
+            Self::choose_attachment(&state_clone);
         });
-        button_box.append(&copy_btn);
+        vbox.append(&add_btn);
 
-        let dialog_weak = dialog.downgrade();
-        let close_btn = gtk4::Button::with_label("Zamknij");
-        close_btn.connect_clicked(move |_| {
-            if let Some(d) = dialog_weak.upgrade() {
-                d.close();
-            }
+        let clear_btn = gtk4::Button::with_label("🗑 Wyczyść");
+        clear_btn.set_sensitive(!context.is_empty());
+        let state_clone = state.clone();
+        clear_btn.connect_clicked(move |_| {
+            state_clone.borrow().session_context.borrow_mut().clear();
+            info!("Session attachments cleared");
+            Self::refresh_attachments(&state_clone);
         });
-        button_box.append(&close_btn);
+        vbox.append(&clear_btn);
 
-        vbox.append(&button_box);
-        dialog.set_child(Some(&vbox));
-        dialog.present();
-    }
+        let popover = gtk4::Popover::new();
+        popover.set_child(Some(&vbox));
+        state_ref.attach_button.set_popover(Some(&popover));
 
-    fn setup_close_handler(window: adw::ApplicationWindow) {
-        window.connect_close_request(move |win| {
-            win.set_visible(false);
-            info!("Window hidden (close intercepted)");
-            glib::Propagation::Stop
-        });
+        let count = context.attachments().len();
+        if count == 0 {
+            state_ref.attach_button.set_label("📎");
+        } else {
+            state_ref.attach_button.set_label(&format!("📎 {}", count));
+        }
     }
 
-    fn setup_hotkey(state: Rc<RefCell<AppState>>) {
-        let (async_tx, async_rx) = async_channel::unbounded::<HotkeyEvent>();
-        
-        std::thread::spawn(move || {
-            let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
-            
-            if let Ok(_manager) = HotkeyManager::new(tx) {
-                info!("Hotkey manager created");
-                
-                let rt = tokio::runtime::Runtime::new().unwrap();
-                rt.block_on(async {
-                    while let Some(event) = rx.recv().await {
-                        let _ = async_tx.send(event).await;
-                    }
-                });
-            }
-        });
+    fn choose_attachment(state: &Rc<RefCell<AppState>>) {
+        let state_ref = state.borrow();
+        if let Some(popover) = state_ref.attach_button.popover() {
+            popover.popdown();
+        }
 
-        glib::spawn_future_local(async move {
-            while let Ok(event) = async_rx.recv().await {
-                match event {
-                    HotkeyEvent::Triggered => {
-                        info!("Hotkey triggered");
-                        let state_ref = state.borrow();
-                        state_ref.window.set_visible(true);
-                        state_ref.window.present();
-                        drop(state_ref);
-                        Self::handle_hotkey_triggered(&state).await;
+        let chooser = gtk4::FileChooserNative::new(
+            Some("Dołącz plik kontekstu"),
+            Some(&state_ref.window),
+            gtk4::FileChooserAction::Open,
+            Some("Dołącz"),
+            Some("Anuluj"),
+        );
+
+        let filter = gtk4::FileFilter::new();
+        filter.set_name(Some("Pliki tekstowe"));
+        filter.add_mime_type("text/*");
+        chooser.add_filter(&filter);
+
+        if let Some(dir) = state_ref.session_context.borrow().working_dir() {
+            let _ = chooser.set_current_folder(Some(&gtk4::gio::File::for_path(dir)));
+        }
+        drop(state_ref);
+
+        let state_clone = state.clone();
+        chooser.connect_response(move |chooser, response| {
+            if response == gtk4::ResponseType::Accept {
+                if let Some(path) = chooser.file().and_then(|f| f.path()) {
+                    let state_ref = state_clone.borrow();
+                    let result = state_ref
+                        .session_context
+                        .borrow_mut()
+                        .attach_file(&path)
+                        .map(|a| a.name.clone());
+                    match result {
+                        Ok(name) => {
+                            info!("Attached {} to session", name);
+                            state_ref.status_label.set_text(&format!("📎 Dołączono: {}", name));
+                        }
+                        Err(e) => {
+                            error!("Attachment failed: {}", e);
+                            state_ref.status_label.set_text(&format!("❌ {}", e));
+                        }
                     }
+                    drop(state_ref);
+                    Self::refresh_attachments(&state_clone);
                 }
             }
+            chooser.destroy();
         });
+
+        chooser.show();
     }
 
-    async fn handle_hotkey_triggered(state: &Rc<RefCell<AppState>>) {
-        info!("Paste button clicked, reading clipboard...");
-        match clipboard::read_text() {
-            Ok(text) => {
-                info!("Clipboard read OK, {} chars", text.len());
-                if !text.is_empty() {
-                    Self::prepare_processing_session(state, &text);
-                    
-                    let state_ref = state.borrow();
-                    let config = state_ref.config.borrow().clone();
-                    let cancel_flags = state_ref.cancel_flags.clone();
-                    let session = state_ref.session_id.load(Ordering::SeqCst);
-                    drop(state_ref);
+    /// Po zakończeniu panel staje się edytowalny; cofanie zmian zapewnia bufor GTK
+    fn set_panel_editable(panel: &PanelState, editable: bool) {
+        panel.text_view.set_editable(editable);
+        panel.text_view.set_cursor_visible(editable);
+        panel.restore_button.set_sensitive(editable);
+    }
 
-                    Self::process_with_apis(state.clone(), text, config, cancel_flags, session).await;
-                } else {
-                    let state_ref = state.borrow();
-                    state_ref.status_label.set_text("⚠️ Brak tekstu w schowku");
-                }
-            }
-            Err(e) => {
-                error!("Clipboard read failed: {}", e);
-                let state_ref = state.borrow();
-                state_ref.status_label.set_text(&format!("❌ Blad schowka: {}", e));
+    fn panel_text(panel: &PanelState) -> String {
+        let buffer = panel.text_view.buffer();
+        buffer.text(&buffer.start_iter(), &buffer.end_iter(), false).to_string()
+    }
+
+    /// Przywraca nieedytowany wynik modelu jako krok, który można cofnąć
+    fn restore_api_result(state: &Rc<RefCell<AppState>>, index: usize) {
+        let state_ref = state.borrow();
+        let panel = &state_ref.panels[index];
+        if !*panel.is_completed.borrow() {
+            return;
+        }
+
+        let original = state_ref.original_text.borrow().clone();
+        let result = panel.result_text.borrow().clone();
+        let highlight = state_ref.config.borrow().settings.highlight_diffs;
+
+        let buffer = panel.text_view.buffer();
+        let _timer = jank::measure("restore_result", result.chars().count());
+        buffer.begin_user_action();
+        set_text_with_diff(&buffer, &original, &result, highlight);
+        buffer.end_user_action();
+
+        Self::show_style_violations(panel, &state_ref.config.borrow().styleguide, &result);
+        info!("Restored original result of {}", API_NAMES[index]);
+    }
+
+    fn toggle_stream_pause(panel: &PanelState) {
+        let mut stream = panel.stream.borrow_mut();
+
+        if stream.is_paused() {
+            let pending = stream.resume();
+            if !pending.is_empty() {
+                let buffer = panel.text_view.buffer();
+                buffer.insert(&mut buffer.end_iter(), &pending);
             }
+            panel.pause_button.set_label("⏸");
+            panel.pause_button.set_tooltip_text(Some("Wstrzymaj dopisywanie tekstu"));
+        } else {
+            stream.pause();
+            panel.pause_button.set_label("▶");
+            panel.pause_button.set_tooltip_text(Some("Wznów i dopisz zaległy tekst"));
         }
     }
 
-    fn prepare_processing_session(state: &Rc<RefCell<AppState>>, text: &str) {
+    fn append_stream_chunk(state: &Rc<RefCell<AppState>>, index: usize, chunk: &str) {
         let state_ref = state.borrow();
-        
-        *state_ref.original_text.borrow_mut() = text.to_string();
-        
-        let session = state_ref.session_id.fetch_add(1, Ordering::SeqCst) + 1;
-        state_ref.session_label.set_text(&format!("📝 Sesja: {}", session));
-        
-        *state_ref.completed_count.borrow_mut() = 0;
-        state_ref.api_counter_label.set_text("🤖 API: 0/4");
-        
-        state_ref.status_label.set_text("🔄 Wysyłanie do 4 API równocześnie...");
-        state_ref.hint_label.set_text(&format!("({} znaków)", text.len()));
-        
-        for flag in &state_ref.cancel_flags {
-            flag.store(false, Ordering::SeqCst);
+        let panel = &state_ref.panels[index];
+        if !*panel.is_processing.borrow() {
+            return;
         }
-        
-        for (i, panel) in state_ref.panels.iter().enumerate() {
-            *panel.is_processing.borrow_mut() = true;
-            *panel.is_completed.borrow_mut() = false;
-            *panel.has_error.borrow_mut() = false;
-            *panel.start_time.borrow_mut() = Some(Instant::now());
-            *panel.result_text.borrow_mut() = String::new();
-            
-            panel.spinner.set_visible(true);
-            panel.spinner.start();
-            panel.progress_bar.set_visible(true);
-            panel.progress_bar.set_fraction(0.0);
-            panel.cancel_button.set_sensitive(true);
-            panel.use_button.set_sensitive(false);
-            panel.status_icon.set_text("🤖");
-            panel.name_label.set_text(API_NAMES[i]);
-            panel.time_label.set_text("");
-            panel.text_view.buffer().set_text("🔄 Przygotowanie...");
+
+        let mut stream = panel.stream.borrow_mut();
+        if !stream.has_received() {
+            panel.text_view.buffer().set_text("");
+            Self::set_panel_status(panel, index, PanelStatus::Processing);
         }
+        stream.push(chunk);
+        drop(stream);
+
+        Self::flush_stream(panel);
     }
 
-    async fn process_with_apis(
-        state: Rc<RefCell<AppState>>,
-        text: String,
-        config: Config,
-        cancel_flags: [Arc<AtomicBool>; 4],
-        session: u64,
-    ) {
-        let system_prompt = get_system_prompt(CorrectionStyle::Normal);
-        let instruction = get_instruction_prompt(CorrectionStyle::Normal);
-
-        let (tx, rx) = async_channel::unbounded::<(usize, Result<String, String>)>();
-
-        for i in 0..4 {
-            let text = text.clone();
-            let config = config.clone();
-            let system = system_prompt.to_string();
-            let instr = instruction.to_string();
-            let cancel = cancel_flags[i].clone();
-            let tx = tx.clone();
-
-            crate::TOKIO_RUNTIME.spawn(async move {
-                let result = match i {
-                    0 => correct_text_openai_with_callback::<fn(&str)>(
-                        &config.api_keys.openai,
-                        &config.models.openai,
-                        &text,
-                        &instr,
-                        &system,
-                        true,
-                        None,
-                    ).await,
-                    1 => correct_text_anthropic(
-                        &config.api_keys.anthropic,
-                        &config.models.anthropic,
-                        &text,
-                        &instr,
-                        &system,
-                    ).await,
-                    2 => correct_text_gemini(
-                        &config.api_keys.gemini,
-                        &config.models.gemini,
-                        &text,
-                        &instr,
-                        &system,
-                    ).await,
-                    3 => correct_text_deepseek(
-                        &config.api_keys.deepseek,
-                        &config.models.deepseek,
-                        &text,
-                        &instr,
-                        &system,
-                    ).await,
-                    _ => Err(crate::error::ApiError::Response("Unknown API".to_string())),
-                };
+    /// Dopisuje zebrane fragmenty, o ile pozwala na to limit odświeżania
+    fn flush_stream(panel: &PanelState) {
+        let mut stream = panel.stream.borrow_mut();
 
-                if !cancel.load(Ordering::SeqCst) {
-                    let _ = tx.send((i, result.map_err(|e| e.to_string()))).await;
-                }
-            });
+        if let Some(text) = stream.take_ready(Instant::now()) {
+            let _timer = jank::measure("stream_flush", text.chars().count());
+            let buffer = panel.text_view.buffer();
+            buffer.insert(&mut buffer.end_iter(), &text);
+            if !stream.is_paused() && panel.pause_button.label().as_deref() != Some("⏸") {
+                panel.pause_button.set_label("⏸");
+                panel.pause_button.set_tooltip_text(Some("Wstrzymaj dopisywanie tekstu"));
+            }
+        } else if stream.is_paused() {
+            panel
+                .pause_button
+                .set_tooltip_text(Some(&format!("Wznów ({} znaków w buforze)", stream.pending_len())));
         }
+    }
 
-        drop(tx);
+    fn reset_stream(panel: &PanelState) {
+        panel.stream.borrow_mut().reset();
+        panel.pause_button.set_sensitive(false);
+        panel.pause_button.set_label("⏸");
+        panel.pause_button.set_tooltip_text(Some("Wstrzymaj dopisywanie tekstu"));
+    }
 
-        while let Ok((index, result)) = rx.recv().await {
-            Self::update_panel_result(&state, index, result, session);
+    fn use_api_result(state: &Rc<RefCell<AppState>>, index: usize, panel: &PanelState) {
+        let text = Self::panel_text(panel);
+        if text.is_empty() {
+            return;
         }
 
-        Self::finalize_processing(&state);
+        if Self::deliver_text(state, API_NAMES[index], &text) {
+            let generation = panel.generation.borrow().clone();
+            Self::record_history(state, API_NAMES[index], generation, &text);
+            Self::remember_provider(state, index);
+        }
     }
 
-    fn update_panel_result(
-        state: &Rc<RefCell<AppState>>,
-        index: usize,
-        result: Result<String, String>,
-        _session: u64,
-    ) {
+    /// Zapamiętuje wybranego dostawcę dla aplikacji źródłowej
+    fn remember_provider(state: &Rc<RefCell<AppState>>, index: usize) {
         let state_ref = state.borrow();
-        let panel = &state_ref.panels[index];
-        
-        panel.spinner.stop();
-        panel.spinner.set_visible(false);
-        panel.progress_bar.set_visible(false);
-        panel.cancel_button.set_sensitive(false);
-        *panel.is_processing.borrow_mut() = false;
+        let Some(app) = state_ref.target_app.borrow().clone() else {
+            return;
+        };
+        if let Err(e) = state_ref.window_rules.borrow_mut().remember(&app, API_NAMES[index]) {
+            error!("Failed to save window rules: {}", e);
+        }
+    }
 
-        let elapsed = panel.start_time.borrow()
-            .map(|t| t.elapsed().as_secs_f64())
-            .unwrap_or(0.0);
+    /// Wyróżnia panel dostawcy wybranego ostatnio w aplikacji źródłowej
+    fn highlight_preferred_panel(state: &Rc<RefCell<AppState>>) {
+        let state_ref = state.borrow();
+        let preferred = state_ref.target_app.borrow().as_deref().and_then(|app| {
+            let rules = state_ref.window_rules.borrow();
+            let provider = rules.preferred(app)?;
+            API_NAMES.iter().position(|name| *name == provider)
+        });
+        *state_ref.preferred_panel.borrow_mut() = preferred;
 
-        match result {
-            Ok(corrected) => {
-                *panel.result_text.borrow_mut() = corrected.clone();
-                *panel.is_completed.borrow_mut() = true;
+        for (i, panel) in state_ref.panels.iter().enumerate() {
+            if preferred == Some(i) {
+                panel.header_box.add_css_class("preferred-panel");
+                panel.use_button.set_tooltip_text(Some("Ostatnio wybierany w tej aplikacji"));
+            } else {
+                panel.header_box.remove_css_class("preferred-panel");
+                panel.use_button.set_tooltip_text(None);
+            }
+        }
+    }
+
+    /// Kopiuje tekst do schowka; po osiągnięciu progu ukrywania chowa okno i symuluje Ctrl+V,
+    /// wcześniej wynik zostaje tylko w schowku. Zwraca `false`, gdy kopiowanie się nie udało.
+    fn deliver_text(state: &Rc<RefCell<AppState>>, source: &str, text: &str) -> bool {
+        let rewrapped = {
+            let state_ref = state.borrow();
+            let width = *state_ref.input_wrap.borrow();
+            width
+                .filter(|_| state_ref.config.borrow().settings.rewrap_output)
+                .map(|width| rewrap::wrap(text, width))
+        };
+        let text = rewrapped.as_deref().unwrap_or(text);
+        let format = {
+            let state_ref = state.borrow();
+            let target_app = state_ref.target_app.borrow();
+            clipboard::choose_format(&state_ref.config.borrow().rich_clipboard, target_app.as_deref(), text)
+        };
+        if let Err(e) = clipboard::write_formatted(text, format) {
+            error!("Failed to copy text: {}", e);
+            let (state_clone, source, text) = (state.clone(), source.to_string(), text.to_string());
+            Self::show_error_toast(
+                state,
+                &format!("Nie udało się skopiować wyniku do schowka: {}", e),
+                Some(Box::new(move || {
+                    Self::deliver_text(&state_clone, &source, &text);
+                })),
+            );
+            return false;
+        }
+
+        info!("Copied result from {} to clipboard ({:?})", source, format);
+        Self::remember_output(state, text);
+
+        let state_ref = state.borrow();
+        let completed = state_ref.session.completed();
+        if state_ref.config.borrow().settings.delivery(completed) == Delivery::CopyOnly {
+            info!(
+                "Copied result from {} without hiding or pasting ({} results received)",
+                source, completed
+            );
+            state_ref
+                .status_label
+                .set_text("📋 Wynik w schowku - okno zostaje, więc nie wklejono (próg ukrywania)");
+            return true;
+        }
+        state_ref.window.set_visible(false);
+        drop(state_ref);
+
+        // Po schowaniu okna fokus wraca do okna docelowego, dopiero wtedy można je sprawdzić
+        let (state, source) = (state.clone(), source.to_string());
+        glib::timeout_add_local_once(std::time::Duration::from_millis(300), move || {
+            Self::paste_into_focused(&state, &source);
+        });
+        true
+    }
+
+    /// Symuluje Ctrl+V, chyba że okno z fokusem jest na liście `[paste_guard]`;
+    /// wtedy wynik zostaje tylko w schowku
+    fn paste_into_focused(state: &Rc<RefCell<AppState>>, source: &str) {
+        let guard = state.borrow().config.borrow().paste_guard.clone();
+        let focused = guard.enabled.then(crate::platform::active_window).flatten();
+        let message = match paste_guard::check(&guard, focused.as_ref()) {
+            paste_guard::Verdict::Paste => None,
+            paste_guard::Verdict::Blocked(pattern) => {
+                let window = focused.as_ref().map(SourceApp::display_name).unwrap_or_default();
+                warn!("Auto-paste into {:?} blocked by pattern {:?}", window, pattern);
+                Some(format!("Nie wklejono do „{}” (pasuje do „{}”) - wynik jest w schowku", window, pattern))
+            }
+            paste_guard::Verdict::UnknownWindow => {
+                warn!("Auto-paste skipped: the focused window is unknown");
+                Some("Nie wklejono, bo nie udało się sprawdzić okna z fokusem - wynik jest w schowku".to_string())
+            }
+        };
+        if let Some(message) = message {
+            // Okno jest już schowane, więc toast nie byłby widoczny; pokazanie okna zabrałoby fokus
+            let state_ref = state.borrow();
+            match state_ref.window.application() {
+                Some(app) => {
+                    let notification = gio::Notification::new("🔒 Wklejanie zablokowane");
+                    notification.set_body(Some(&message));
+                    app.send_notification(Some("paste-blocked"), &notification);
+                }
+                None => state_ref.toasts.add_toast(adw::Toast::new(&format!("🔒 {}", message))),
+            }
+            return;
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            let _ = std::process::Command::new("xdotool")
+                .args(["key", "ctrl+v"])
+                .spawn();
+        }
+
+        #[cfg(target_os = "windows")]
+        {
+            use std::process::Command;
+            let _ = Command::new("powershell")
+                .args(["-Command", "[System.Windows.Forms.SendKeys]::SendWait('^v')"])
+                .spawn();
+        }
+
+        info!("Used result from {} and simulated Ctrl+V", source);
+    }
+
+    /// Dopisuje wynik do podmenu "Ostatnie wyniki" w zasobniku
+    fn remember_output(state: &Rc<RefCell<AppState>>, text: &str) {
+        let state_ref = state.borrow();
+        let mut recent = state_ref.recent_outputs.borrow_mut();
+        recent.push(text);
+        if let Some(tray) = &state_ref.tray {
+            tray.borrow().set_recent_outputs(recent.labels());
+        }
+    }
+
+    /// Kopiuje ponownie wynik wybrany z podmenu zasobnika (bez wklejania)
+    fn copy_recent_output(state: &Rc<RefCell<AppState>>, index: usize) {
+        let Some(text) = state.borrow().recent_outputs.borrow().get(index).map(str::to_string) else {
+            return;
+        };
+        match clipboard::write_text(&text) {
+            Ok(()) => info!("Copied recent output {} from tray", index + 1),
+            Err(e) => {
+                error!("Failed to copy recent output: {}", e);
+                let state_clone = state.clone();
+                Self::show_error_toast(
+                    state,
+                    &format!("Nie udało się skopiować ostatniego wyniku: {}", e),
+                    Some(Box::new(move || Self::copy_recent_output(&state_clone, index))),
+                );
+            }
+        }
+    }
+
+    /// Nazwa stylu bieżącej sesji do historii i dziennika audytu
+    fn style_name(state: &AppState) -> String {
+        match state.session_custom.borrow().as_ref() {
+            Some(custom) => custom.name.trim().to_string(),
+            None => state.session_style.borrow().as_str().to_string(),
+        }
+    }
+
+    /// Zapisuje wywołanie dostawcy w dzienniku audytu; treść tylko przy `IncludeContent`
+    fn record_audit(state: &Rc<RefCell<AppState>>, config: &Config, index: usize, result: Option<&str>) {
+        if !config.audit.enabled {
+            return;
+        }
+        let state_ref = state.borrow();
+        let generation = Generation {
+            model: panel_specs()[index].model(config).to_string(),
+            ..panel_provider(index).generation(config)
+        };
+        let entry = AuditEntry::new(
+            &config.audit,
+            API_NAMES[index],
+            &generation,
+            &Self::style_name(&state_ref),
+            &state_ref.original_text.borrow(),
+            result,
+        );
+        if let Err(e) = AuditLog::new(AuditLog::default_path()).append(&entry) {
+            error!("Failed to write audit log: {}", e);
+        }
+    }
+
+    fn record_history(state: &Rc<RefCell<AppState>>, provider: &str, generation: Option<Generation>, text: &str) {
+        let state_ref = state.borrow();
+        let config = state_ref.config.borrow();
+        if !config.history.enabled || !Retention::from_settings(&config.history).keeps_entries() {
+            return;
+        }
+        drop(config);
+
+        let entry = HistoryEntry {
+            timestamp: now_secs(),
+            started: Some(*state_ref.session_started.borrow()),
+            style: Self::style_name(&state_ref),
+            provider: provider.to_string(),
+            original: state_ref.original_text.borrow().clone(),
+            result: text.to_string(),
+            generation,
+            source_app: state_ref.session_source.borrow().clone(),
+        };
+        drop(state_ref);
+
+        let state = state.clone();
+        glib::spawn_future_local(async move {
+            let Some(history) = Self::open_history(&state, false).await else {
+                warn!("History locked, entry not saved");
+                return;
+            };
+            if let Err(e) = history.append(&entry) {
+                error!("Failed to write history: {}", e);
+            }
+        });
+    }
+
+    /// Dopisuje zdarzenie do dziennika aktywności; bufor ma stałą pojemność,
+    /// więc po jego zapełnieniu z listy znika najstarszy wiersz
+    fn log_activity(state_ref: &AppState, kind: ActivityKind, text: &str) {
+        let time = glib::DateTime::now_local()
+            .ok()
+            .and_then(|t| t.format("%H:%M:%S").ok())
+            .map(|t| t.to_string())
+            .unwrap_or_default();
+        let event = ActivityEvent {
+            time,
+            kind,
+            text: text.to_string(),
+        };
+
+        let row = gtk4::Label::new(Some(&event.line()));
+        row.set_xalign(0.0);
+        row.set_wrap(true);
+        row.set_selectable(true);
+        state_ref.activity_list.prepend(&row);
+
+        let mut activity = state_ref.activity.borrow_mut();
+        if activity.push(event).is_some() {
+            if let Some(oldest) = state_ref.activity_list.last_child() {
+                state_ref.activity_list.remove(&oldest);
+            }
+        }
+        state_ref
+            .activity_pane
+            .set_label(Some(&format!("📜 Aktywność ({})", activity.len())));
+    }
+
+    /// Okno historii poprawek; zaszyfrowana historia wymaga hasła
+    async fn show_history_browser(state: &Rc<RefCell<AppState>>) {
+        let Some(history) = Self::open_history(state, true).await else {
+            state.borrow().status_label.set_text("🔒 Historia jest zablokowana");
+            return;
+        };
+        let entries = match history.load() {
+            Ok(entries) => entries,
+            Err(e) => {
+                error!("Failed to read history: {}", e);
+                state
+                    .borrow()
+                    .status_label
+                    .set_text(&format!("❌ Nie można odczytać historii: {}", e));
+                return;
+            }
+        };
+        info!("History browser opened with {} entries", entries.len());
+
+        let window = state.borrow().window.clone();
+        HistoryBrowser::new(&window, entries, |text| {
+            clipboard::write_text(text).map_err(|e| e.to_string())
+        })
+        .present();
+    }
+
+    /// Historia gotowa do użycia; przy szyfrowaniu pyta o hasło raz na uruchomienie.
+    /// Plik klucza zostaje, dopóki historia nie zostanie odszyfrowana, więc po wyłączeniu
+    /// szyfrowania (np. ręcznie w pliku) pierwsze otwarcie pyta o hasło i zapisuje ją jawnie.
+    /// `ask_again` (działanie użytkownika) pyta także po wcześniejszym anulowaniu.
+    async fn open_history(state: &Rc<RefCell<AppState>>, ask_again: bool) -> Option<History> {
+        let history_path = History::default_path();
+        let encrypt = state.borrow().config.borrow().history.encrypt;
+        if !encrypt && !KeyFile::path_for(&history_path).exists() {
+            return Some(History::new(&history_path));
+        }
+
+        let key = Self::history_key(state, ask_again).await?;
+        let history = History::new(&history_path).with_key(key);
+        if encrypt {
+            return Some(history);
+        }
+        match Self::write_plain_history(state, &history) {
+            Ok(count) => {
+                info!("History encryption turned off, {} entries decrypted", count);
+                Some(History::new(&history_path))
+            }
+            Err(e) => {
+                error!("Failed to decrypt history: {}", e);
+                Some(history)
+            }
+        }
+    }
+
+    /// Klucz historii z tego uruchomienia albo z hasła; anulowanie jest zapamiętywane
+    async fn history_key(state: &Rc<RefCell<AppState>>, ask_again: bool) -> Option<HistoryKey> {
+        if let Some(key) = state.borrow().history_key.borrow().clone() {
+            return Some(key);
+        }
+        if !ask_again && *state.borrow().history_unlock_declined.borrow() {
+            return None;
+        }
+
+        let Some(key) = Self::unlock_history(state).await else {
+            info!("History not unlocked, automatic writes won't ask again until restart");
+            *state.borrow().history_unlock_declined.borrow_mut() = true;
+            return None;
+        };
+        *state.borrow().history_unlock_declined.borrow_mut() = false;
+        *state.borrow().history_key.borrow_mut() = Some(key.clone());
+        Self::prune_history(state);
+        Some(key)
+    }
+
+    /// Zapisuje odszyfrowaną historię jawnie i usuwa plik klucza; zwraca liczbę wpisów
+    fn write_plain_history(state: &Rc<RefCell<AppState>>, encrypted: &History) -> Result<usize, String> {
+        let entries = encrypted.load().map_err(|e| e.to_string())?;
+        History::new(encrypted.path())
+            .save_all(&entries)
+            .map_err(|e| e.to_string())?;
+        std::fs::remove_file(KeyFile::path_for(encrypted.path())).map_err(|e| e.to_string())?;
+        *state.borrow().history_key.borrow_mut() = None;
+        Ok(entries.len())
+    }
+
+    /// Pyta o hasło aż do skutku albo anulowania; za pierwszym razem ustawia hasło
+    /// i szyfruje dotychczasowe wpisy
+    async fn unlock_history(state: &Rc<RefCell<AppState>>) -> Option<HistoryKey> {
+        let history_path = History::default_path();
+        let key_path = KeyFile::path_for(&history_path);
+        let key_file = match KeyFile::load(&key_path) {
+            Ok(key_file) => key_file,
+            Err(e) => {
+                error!("Failed to read history key file: {}", e);
+                return None;
+            }
+        };
+
+        let window = state.borrow().window.clone();
+        let mut last_error: Option<String> = None;
+        loop {
+            let passphrase =
+                crate::ui::ask_passphrase(&window, key_file.is_none(), last_error.as_deref()).await?;
+
+            let existing = key_file.clone();
+            let result = crate::TOKIO_RUNTIME
+                .spawn_blocking(move || match existing {
+                    Some(key_file) => key_file.unlock(&passphrase).map(|key| (key, None)),
+                    None => KeyFile::create(&passphrase).map(|(key_file, key)| (key, Some(key_file))),
+                })
+                .await;
+
+            match result {
+                Ok(Ok((key, None))) => {
+                    info!("History unlocked");
+                    return Some(key);
+                }
+                Ok(Ok((key, Some(new_key_file)))) => {
+                    let plain = History::new(&history_path);
+                    let migrated = plain.load().and_then(|entries| {
+                        new_key_file.save(&key_path)?;
+                        History::new(&history_path).with_key(key.clone()).save_all(&entries)?;
+                        Ok(entries.len())
+                    });
+                    match migrated {
+                        Ok(count) => info!("History encryption enabled, {} entries encrypted", count),
+                        Err(e) => {
+                            error!("Failed to encrypt history: {}", e);
+                            return None;
+                        }
+                    }
+                    return Some(key);
+                }
+                Ok(Err(e)) => last_error = Some(e),
+                Err(e) => {
+                    error!("History unlock task failed: {}", e);
+                    return None;
+                }
+            }
+        }
+    }
+
+    /// Przycina historię według ustawień; szyfrowaną dopiero po odblokowaniu
+    fn prune_history(state: &Rc<RefCell<AppState>>) {
+        let settings = state.borrow().config.borrow().history.clone();
+        let retention = Retention::from_settings(&settings);
+        if retention == Retention::All {
+            return;
+        }
+
+        let mut history = History::new(History::default_path());
+        let key = state.borrow().history_key.borrow().clone();
+        match key {
+            Some(key) => history = history.with_key(key),
+            None if settings.encrypt && retention != Retention::Nothing => {
+                info!("Encrypted history will be pruned after unlock");
+                return;
+            }
+            None => {}
+        }
+
+        match history.prune(retention, now_secs()) {
+            Ok(0) => {}
+            Ok(removed) => info!("Pruned {} history entries ({:?})", removed, retention),
+            Err(e) => error!("Failed to prune history: {}", e),
+        }
+    }
+
+    /// Odszyfrowuje historię i usuwa plik klucza (po wyłączeniu szyfrowania)
+    async fn decrypt_history(state: &Rc<RefCell<AppState>>) -> Result<(), String> {
+        let history_path = History::default_path();
+        let key_path = KeyFile::path_for(&history_path);
+        if !key_path.exists() {
+            return Ok(());
+        }
+
+        let key = Self::history_key(state, true)
+            .await
+            .ok_or("Wyłączenie szyfrowania historii wymaga hasła")?;
+        let count = Self::write_plain_history(state, &History::new(&history_path).with_key(key))?;
+        info!("History decrypted, {} entries", count);
+        Ok(())
+    }
+
+    fn connect_merge_button(state: Rc<RefCell<AppState>>, merge_btn: gtk4::Button) {
+        merge_btn.connect_clicked(move |_| {
+            let state_ref = state.borrow();
+            let sources: Vec<(&str, String)> = state_ref
+                .panels
+                .iter()
+                .enumerate()
+                .map(|(i, panel)| {
+                    let text = if *panel.is_completed.borrow() {
+                        Self::panel_text(panel)
+                    } else {
+                        String::new()
+                    };
+                    (API_NAMES[i], text)
+                })
+                .collect();
+
+            let completed = sources.iter().filter(|(_, t)| !t.trim().is_empty()).count();
+            if completed < 2 {
+                state_ref.status_label.set_text("⚠️ Do złożenia wyniku potrzebne są co najmniej 2 wyniki");
+                return;
+            }
+
+            let dialog = MergeDialog::new(&state_ref.window, &sources);
+            drop(state_ref);
+
+            let state_clone = state.clone();
+            dialog.connect_compose(move |text| {
+                if Self::deliver_text(&state_clone, "Złożony", &text) {
+                    Self::record_history(&state_clone, "Złożony", None, &text);
+                }
+            });
+            dialog.present();
+        });
+    }
+
+    fn connect_review_button(state: Rc<RefCell<AppState>>, review_btn: gtk4::Button) {
+        review_btn.connect_clicked(move |_| {
+            let state_ref = state.borrow();
+            let results: Vec<Option<String>> = state_ref
+                .panels
+                .iter()
+                .map(|panel| (*panel.is_completed.borrow()).then(|| Self::panel_text(panel)))
+                .collect();
+
+            if results.iter().all(Option::is_none) {
+                state_ref.status_label.set_text("⚠️ Brak wyników do przeglądu");
+                return;
+            }
+
+            let original = state_ref.original_text.borrow().clone();
+            let results: Vec<Option<&str>> = results.iter().map(|r| r.as_deref()).collect();
+            let review = Review::new(&original, &results);
+
+            let dialog = ReviewDialog::new(&state_ref.window, &API_NAMES[..], review);
+            drop(state_ref);
+
+            let state_clone = state.clone();
+            dialog.connect_accept(move |text| {
+                if Self::deliver_text(&state_clone, "Przegląd", &text) {
+                    Self::record_history(&state_clone, "Przegląd", None, &text);
+                }
+            });
+            dialog.present();
+        });
+    }
+
+    fn connect_consensus_button(state: Rc<RefCell<AppState>>, consensus_btn: gtk4::Button) {
+        consensus_btn.connect_clicked(move |_| {
+            let state_ref = state.borrow();
+            let results: Vec<Option<String>> = state_ref
+                .panels
+                .iter()
+                .map(|panel| (*panel.is_completed.borrow()).then(|| Self::panel_text(panel)))
+                .collect();
+
+            if results.iter().flatten().count() < 2 {
+                state_ref.status_label.set_text("⚠️ Do porównania potrzebne są co najmniej dwa wyniki");
+                return;
+            }
+
+            let results: Vec<Option<&str>> = results.iter().map(|r| r.as_deref()).collect();
+            let rows = align::align(&results);
+            info!("Consensus view: {} of {} sentences contested", align::contested_count(&rows), rows.len());
+            ConsensusDialog::new(&state_ref.window, &API_NAMES[..], &rows).present();
+        });
+    }
+
+    fn connect_matrix_button(state: Rc<RefCell<AppState>>, matrix_btn: gtk4::Button) {
+        matrix_btn.connect_clicked(move |_| {
+            let state_ref = state.borrow();
+            if state_ref.panels.iter().all(|p| !*p.is_completed.borrow()) {
+                state_ref.status_label.set_text("⚠️ Brak wyników do porównania");
+                return;
+            }
+
+            let chooser = gtk4::FileChooserNative::new(
+                Some("Eksportuj porównanie dostawców"),
+                Some(&state_ref.window),
+                gtk4::FileChooserAction::Save,
+                Some("Zapisz"),
+                Some("Anuluj"),
+            );
+            chooser.set_current_name("porownanie.html");
+            drop(state_ref);
+
+            let state_clone = state.clone();
+            chooser.connect_response(move |chooser, response| {
+                if response == gtk4::ResponseType::Accept {
+                    if let Some(path) = chooser.file().and_then(|f| f.path()) {
+                        Self::export_matrix(&state_clone, &path);
+                    }
+                }
+                chooser.destroy();
+            });
+            chooser.show();
+        });
+    }
+
+    fn export_matrix(state: &Rc<RefCell<AppState>>, path: &std::path::Path) {
+        let state_ref = state.borrow();
+        let config = state_ref.config.borrow();
+        let rows: Vec<MatrixRow> = panel_specs()
+            .iter()
+            .zip(API_NAMES.iter())
+            .zip(state_ref.panels.iter())
+            .zip(state_ref.prompt_tokens.borrow().iter())
+            .map(|(((spec, name), panel), &input_tokens)| {
+                let model = panel
+                    .generation
+                    .borrow()
+                    .as_ref()
+                    .map_or_else(|| spec.model(&config).to_string(), |g| g.model.clone());
+                let result = (*panel.is_completed.borrow()).then(|| panel.result_text.borrow().clone());
+                MatrixRow {
+                    provider: name.to_string(),
+                    parameters: panel.generation.borrow().as_ref().map(Generation::label).unwrap_or_default(),
+                    latency: *panel.latency.borrow(),
+                    input_tokens: input_tokens as u64,
+                    output_tokens: result
+                        .as_deref()
+                        .map_or(0, |text| tokens::count(spec.provider, &model, text).tokens as u64),
+                    model,
+                    result,
+                }
+            })
+            .collect();
+
+        let html = matrix::render_html(&state_ref.original_text.borrow(), &rows, &config.pricing);
+        match std::fs::write(path, html) {
+            Ok(()) => {
+                info!("Comparison matrix exported to {}", path.display());
+                state_ref.status_label.set_text(&format!("📊 Zapisano porównanie: {}", path.display()));
+            }
+            Err(e) => {
+                error!("Failed to export comparison matrix: {}", e);
+                state_ref.status_label.set_text(&format!("❌ Nie udało się zapisać porównania: {}", e));
+            }
+        }
+    }
+
+    fn cancel_single_api(state: &Rc<RefCell<AppState>>, index: usize) {
+        let state_ref = state.borrow();
+        
+        state_ref.session.cancel(index);
+        
+        let panel = &state_ref.panels[index];
+        panel.spinner.stop();
+        panel.spinner.set_visible(false);
+        panel.progress_bar.set_visible(false);
+        panel.cancel_button.set_sensitive(false);
+        Self::reset_stream(panel);
+        Self::set_panel_status(panel, index, PanelStatus::Cancelled);
+        panel.name_label.set_text(API_NAMES[index]);
+        panel.text_view.buffer().set_text("❌ Anulowano");
+        *panel.is_processing.borrow_mut() = false;
+        *panel.has_error.borrow_mut() = true;
+        Self::log_activity(&state_ref, ActivityKind::Cancelled, &format!("{}: anulowano", API_NAMES[index]));
+
+        info!("Cancelled API {}", API_NAMES[index]);
+    }
+
+    /// Gesty: przesunięcie panelu w lewo, okna w dół i szczypanie tekstu
+    fn setup_gestures(state: &Rc<RefCell<AppState>>) {
+        let state_ref = state.borrow();
+
+        for (index, panel) in state_ref.panels.iter().enumerate() {
+            let swipe = gtk4::GestureSwipe::new();
+            swipe.set_touch_only(false);
+            let state_clone = state.clone();
+            swipe.connect_swipe(move |gesture, vx, vy| {
+                // W karuzeli przesunięcie w bok przełącza panele
+                if Self::is_mouse_drag(gesture) || *state_clone.borrow().narrow.borrow() {
+                    return;
+                }
+                if gestures::classify(vx, vy) == Some(Swipe::Left) {
+                    Self::dismiss_panel(&state_clone, index);
+                }
+            });
+            panel.frame.add_controller(swipe);
+        }
+
+        let swipe = gtk4::GestureSwipe::new();
+        swipe.set_touch_only(false);
+        let state_clone = state.clone();
+        swipe.connect_swipe(move |gesture, vx, vy| {
+            if Self::is_mouse_drag(gesture) {
+                return;
+            }
+            if gestures::classify(vx, vy) == Some(Swipe::Down) {
+                Self::hide_to_tray(&state_clone.borrow(), "swipe down");
+            }
+        });
+        state_ref.window.add_controller(swipe);
+
+        if let Some(display) = gdk::Display::default() {
+            // Nad arkuszem okna, żeby rozmiar tekstu go nadpisywał; selektor w
+            // `zoom_css` ogranicza go do okna głównego
+            gtk4::style_context_add_provider_for_display(
+                &display,
+                &state_ref.zoom_css,
+                gtk4::STYLE_PROVIDER_PRIORITY_APPLICATION + 1,
+            );
+        }
+
+        let zoom = gtk4::GestureZoom::new();
+        let zoom_at_start = Rc::new(RefCell::new(1.0));
+        let text_zoom = state_ref.text_zoom.clone();
+        let start_clone = zoom_at_start.clone();
+        zoom.connect_begin(move |_, _| {
+            *start_clone.borrow_mut() = *text_zoom.borrow();
+        });
+        let text_zoom = state_ref.text_zoom.clone();
+        let provider = state_ref.zoom_css.clone();
+        zoom.connect_scale_changed(move |_, scale| {
+            let value = gestures::clamp_zoom(*zoom_at_start.borrow() * scale);
+            *text_zoom.borrow_mut() = value;
+            provider.load_from_data(&gestures::zoom_css(value));
+        });
+        state_ref.window.add_controller(zoom);
+    }
+
+    /// Ustawia ramki paneli w siatce (2×2 dla czterech) w kolejności `order` (numery paneli)
+    fn place_panels(grid: &gtk4::Grid, panels: &[PanelState], order: &[usize]) {
+        for panel in panels {
+            Self::detach_panel(&panel.frame);
+        }
+        let columns = registry::grid_columns(panels.len());
+        for (position, &index) in order.iter().enumerate() {
+            grid.attach(&panels[index].frame, (position % columns) as i32, (position / columns) as i32, 1, 1);
+        }
+    }
+
+    /// To samo na wąskim oknie: panele jako strony karuzeli
+    fn place_panels_in_carousel(carousel: &adw::Carousel, panels: &[PanelState], order: &[usize]) {
+        for panel in panels {
+            Self::detach_panel(&panel.frame);
+        }
+        for &index in order {
+            carousel.append(&panels[index].frame);
+        }
+    }
+
+    fn detach_panel(frame: &gtk4::Frame) {
+        let Some(parent) = frame.parent() else {
+            return;
+        };
+        if let Some(grid) = parent.downcast_ref::<gtk4::Grid>() {
+            grid.remove(frame);
+        } else if let Some(carousel) = parent.downcast_ref::<adw::Carousel>() {
+            carousel.remove(frame);
+        }
+    }
+
+    /// Układa panele w siatce albo karuzeli, zależnie od szerokości okna
+    fn arrange_panels(state_ref: &AppState, order: &[usize]) {
+        if *state_ref.narrow.borrow() {
+            Self::place_panels_in_carousel(&state_ref.carousel, &state_ref.panels, order);
+        } else {
+            Self::place_panels(&state_ref.panels_grid, &state_ref.panels, order);
+        }
+    }
+
+    /// Poniżej `NARROW_WIDTH` siatka 2×2 zamienia się w karuzelę przewijaną gestem
+    fn setup_breakpoint(state: &Rc<RefCell<AppState>>, carousel_box: &gtk4::Box) {
+        let state_ref = state.borrow();
+        let condition = adw::BreakpointCondition::new_length(
+            adw::BreakpointConditionLengthType::MaxWidth,
+            NARROW_WIDTH,
+            adw::LengthUnit::Sp,
+        );
+        let breakpoint = adw::Breakpoint::new(condition);
+        breakpoint.add_setter(&state_ref.panels_grid, "visible", Some(&false.to_value()));
+        breakpoint.add_setter(carousel_box, "visible", Some(&true.to_value()));
+
+        for narrow in [true, false] {
+            let state_clone = state.clone();
+            let handler = move |_: &adw::Breakpoint| {
+                let state_ref = state_clone.borrow();
+                *state_ref.narrow.borrow_mut() = narrow;
+                let order = state_ref.config.borrow().settings.panel_order(&API_NAMES[..]);
+                Self::arrange_panels(&state_ref, &order);
+                info!("Panel layout: {}", if narrow { "carousel" } else { "grid" });
+            };
+            if narrow {
+                breakpoint.connect_apply(handler);
+            } else {
+                breakpoint.connect_unapply(handler);
+            }
+        }
+
+        state_ref.window.add_breakpoint(breakpoint);
+    }
+
+    /// Nagłówek panelu można przeciągnąć na inny panel, żeby zamienić je miejscami
+    fn setup_panel_drag(state: &Rc<RefCell<AppState>>) {
+        let state_ref = state.borrow();
+        for (index, panel) in state_ref.panels.iter().enumerate() {
+            let source = gtk4::DragSource::new();
+            source.set_actions(gdk::DragAction::MOVE);
+            source.connect_prepare(move |_, _, _| {
+                Some(gdk::ContentProvider::for_value(&(index as u32).to_value()))
+            });
+            panel.header_box.add_controller(source);
+
+            let target = gtk4::DropTarget::new(u32::static_type(), gdk::DragAction::MOVE);
+            let state_clone = state.clone();
+            target.connect_drop(move |_, value, _, _| {
+                let Ok(from) = value.get::<u32>() else {
+                    return false;
+                };
+                if from as usize != index {
+                    Self::swap_panels(&state_clone, from as usize, index);
+                }
+                true
+            });
+            panel.frame.add_controller(target);
+        }
+    }
+
+    /// Zamienia panele miejscami i zapamiętuje kolejność w konfiguracji
+    fn swap_panels(state: &Rc<RefCell<AppState>>, a: usize, b: usize) {
+        let mut config = state.borrow().config.borrow().clone();
+        config.settings.swap_panels(&API_NAMES[..], a, b);
+        {
+            let state_ref = state.borrow();
+            Self::arrange_panels(&state_ref, &config.settings.panel_order(&API_NAMES[..]));
+        }
+        info!("Swapped panels {} and {}", API_NAMES[a], API_NAMES[b]);
+
+        Self::save_config_in_background(state, config);
+    }
+
+    /// Zapisuje konfigurację zmienioną w oknie (poza ustawieniami); błąd pokazuje z "Ponów"
+    fn save_config_in_background(state: &Rc<RefCell<AppState>>, config: Config) {
+        let state = state.clone();
+        glib::spawn_future_local(async move {
+            if let Err(e) = Self::save_settings(state.clone(), config.clone()).await {
+                Self::show_config_save_error(&state, config, &e);
+            }
+        });
+    }
+
+    fn show_config_save_error(state: &Rc<RefCell<AppState>>, config: Config, e: &str) {
+        let state_clone = state.clone();
+        Self::show_error_toast(
+            state,
+            &format!("Nie udało się zapisać konfiguracji: {}", e),
+            Some(Box::new(move || Self::save_config_in_background(&state_clone, config.clone()))),
+        );
+    }
+
+    /// Przeciągnięcie myszą (np. przy zaznaczaniu tekstu) nie jest gestem
+    fn is_mouse_drag(gesture: &gtk4::GestureSwipe) -> bool {
+        gesture.device().is_some_and(|device| device.source() == gdk::InputSource::Mouse)
+    }
+
+    /// Chowa panel do końca sesji (wraca przy następnym tekście)
+    fn dismiss_panel(state: &Rc<RefCell<AppState>>, index: usize) {
+        let processing = *state.borrow().panels[index].is_processing.borrow();
+        if processing {
+            Self::cancel_single_api(state, index);
+        }
+        state.borrow().panels[index].frame.set_visible(false);
+        info!("Dismissed panel {} for this session", API_NAMES[index]);
+    }
+
+    fn connect_buttons(
+        state: Rc<RefCell<AppState>>,
+        settings_btn: gtk4::Button,
+        paste_btn: gtk4::Button,
+        cancel_btn: gtk4::Button,
+        original_btn: gtk4::Button,
+        hide_btn: gtk4::Button,
+    ) {
+        let state_clone = state.clone();
+        paste_btn.connect_clicked(move |_| {
+            glib::spawn_future_local({
+                let state = state_clone.clone();
+                async move {
+                    Self::handle_paste_clicked(&state).await;
+                }
+            });
+        });
+
+        let state_clone = state.clone();
+        cancel_btn.connect_clicked(move |_| {
+            Self::cancel_all_processing(&state_clone);
+        });
+
+        let state_clone = state.clone();
+        original_btn.connect_clicked(move |_| {
+            Self::show_settings(&state_clone);
+        });
+
+        let state_clone = state.clone();
+        hide_btn.connect_clicked(move |_| {
+            Self::hide_to_tray(&state_clone.borrow(), "button");
+        });
+
+        let state_clone = state.clone();
+        settings_btn.connect_clicked(move |_| {
+            Self::show_settings(&state_clone);
+        });
+    }
+
+    fn show_settings(state: &Rc<RefCell<AppState>>) {
+        let state_ref = state.borrow();
+        let config = state_ref.config.borrow().clone();
+        let window = state_ref.window.clone();
+        let portal = state_ref.portal_hotkey.clone();
+        drop(state_ref);
+
+        let dialog = SettingsDialog::new(&window, &config);
+        dialog.show_usage(&state.borrow().usage.borrow(), &usage::month_key(now_secs()));
+
+        let state_for_save = state.clone();
+        dialog.connect_save(move |new_config| Self::save_settings(state_for_save.clone(), new_config));
+
+        if let Some(portal) = portal {
+            dialog.connect_portal_rebind(portal.trigger(), move || {
+                let portal = portal.clone();
+                async move { portal.rebind().await }
+            });
+        }
+
+        dialog.present();
+    }
+
+    /// Zapisuje konfigurację poza wątkiem GTK i dopiero po udanym zapisie ją stosuje
+    async fn save_settings(state: Rc<RefCell<AppState>>, new_config: Config) -> Result<(), String> {
+        let was_encrypted = state.borrow().config.borrow().history.encrypt;
+        if was_encrypted && !new_config.history.encrypt {
+            Self::decrypt_history(&state).await?;
+        }
+
+        let config_path = Config::get_config_path();
+        let to_save = new_config.clone();
+        crate::TOKIO_RUNTIME
+            .spawn_blocking(move || to_save.save(&config_path).map_err(|e| e.to_string()))
+            .await
+            .map_err(|e| e.to_string())?
+            .inspect_err(|e| error!("Failed to save config: {}", e))?;
+
+        let state_ref = state.borrow();
+        let history_changed = state_ref.config.borrow().history != new_config.history;
+        let models_changed = state_ref.config.borrow().models != new_config.models;
+        Self::apply_shortcuts(&state_ref.window, &new_config.shortcuts);
+        if state_ref.config.borrow().appearance != new_config.appearance {
+            info!(
+                "Appearance: reduced motion {}, minimal emoji {}",
+                new_config.appearance.reduced_motion, new_config.appearance.minimal_emoji
+            );
+            Self::apply_appearance(&state_ref.window, &new_config.appearance);
+        }
+        if state_ref.config.borrow().global_hotkey != new_config.global_hotkey {
+            let bindings = HotkeyBindings::from_config(&new_config.global_hotkey);
+            info!(
+                "Global hotkey changed to {} ({} style hotkeys)",
+                bindings.main.description(),
+                bindings.styles.len()
+            );
+            Self::show_hotkey_hint(&state_ref, bindings.main.description());
+            state_ref.hotkey_bindings.send_replace(bindings);
+        }
+        state_ref
+            .refine_bar
+            .set_visible(new_config.conversation.enabled && *state_ref.window_mode.borrow() == WindowMode::Full);
+        Self::arrange_panels(&state_ref, &new_config.settings.panel_order(&API_NAMES[..]));
+        if registry::panel_specs(&new_config.settings.panel_providers) != panel_specs() {
+            info!("Panels changed, applied after restart");
+            state_ref
+                .toasts
+                .add_toast(adw::Toast::new("Nowi dostawcy paneli pojawią się po ponownym uruchomieniu"));
+        }
+        state_ref
+            .conversation
+            .borrow_mut()
+            .set_limit(new_config.conversation.limit());
+        if let Some(tray) = &state_ref.tray {
+            tray.borrow().set_quick_mode(new_config.settings.quick_mode);
+        }
+        *state_ref.config.borrow_mut() = new_config;
+        drop(state_ref);
+        Self::refresh_snippet_menus(&state);
+        Self::update_cost_badge(&state);
+        if models_changed {
+            Self::refresh_model_dropdowns(&state);
+        }
+        if history_changed {
+            Self::prune_history(&state);
+        }
+        info!("Settings saved successfully");
+        Ok(())
+    }
+
+    fn cancel_all_processing(state: &Rc<RefCell<AppState>>) {
+        let state_ref = state.borrow();
+        
+        state_ref.session.cancel_all();
+        
+        for (i, panel) in state_ref.panels.iter().enumerate() {
+            panel.spinner.stop();
+            panel.spinner.set_visible(false);
+            panel.progress_bar.set_visible(false);
+            panel.progress_bar.set_fraction(0.0);
+            panel.cancel_button.set_sensitive(false);
+            Self::reset_stream(panel);
+            
+            if *panel.is_processing.borrow() {
+                Self::set_panel_status(panel, i, PanelStatus::Cancelled);
+                panel.name_label.set_text(API_NAMES[i]);
+                panel.text_view.buffer().set_text("❌ Anulowano");
+                *panel.is_processing.borrow_mut() = false;
+            }
+        }
+        
+        state_ref.status_label.set_text("❌ Anulowano przetwarzanie");
+        state_ref.hint_label.set_text("");
+        Self::log_activity(&state_ref, ActivityKind::Cancelled, "Anulowano przetwarzanie");
+        
+        info!("Cancelled all processing");
+    }
+
+    fn show_text_dialog(parent: &adw::ApplicationWindow, title: &str, text: &str) {
+        let dialog = gtk4::Window::builder()
+            .title(title)
+            .transient_for(parent)
+            .modal(true)
+            .default_width(500)
+            .default_height(400)
+            .build();
+
+        let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 12);
+        vbox.set_margin_start(12);
+        vbox.set_margin_end(12);
+        vbox.set_margin_top(12);
+        vbox.set_margin_bottom(12);
+
+        let text_view = gtk4::TextView::builder()
+            .editable(false)
+            .wrap_mode(gtk4::WrapMode::Word)
+            .build();
+        text_view.buffer().set_text(text);
+
+        let scrolled = gtk4::ScrolledWindow::builder()
+            .hscrollbar_policy(gtk4::PolicyType::Never)
+            .vscrollbar_policy(gtk4::PolicyType::Automatic)
+            .hexpand(true)
+            .vexpand(true)
+            .child(&text_view)
+            .build();
+
+        vbox.append(&scrolled);
+
+        let button_box = gtk4::Box::new(gtk4::Orientation::Horizontal, 6);
+        button_box.set_halign(gtk4::Align::End);
+
+        let text_clone = text.to_string();
+        let copy_btn = gtk4::Button::with_label("📋 Kopiuj");
+        copy_btn.connect_clicked(move |_| {
+            let _ = clipboard::write_text(&text_clone);
+        });
+        button_box.append(&copy_btn);
+
+        let dialog_weak = dialog.downgrade();
+        let close_btn = gtk4::Button::with_label("Zamknij");
+        close_btn.connect_clicked(move |_| {
+            if let Some(d) = dialog_weak.upgrade() {
+                d.close();
+            }
+        });
+        button_box.append(&close_btn);
+
+        vbox.append(&button_box);
+        dialog.set_child(Some(&vbox));
+        dialog.present();
+    }
+
+    /// Tekst bazowy obok wyniku z podświetlonymi zmianami; `labels` to nagłówki obu kolumn
+    fn show_compare_dialog(
+        parent: &adw::ApplicationWindow,
+        title: &str,
+        labels: [&str; 2],
+        original: &str,
+        corrected: &str,
+    ) {
+        let dialog = gtk4::Window::builder()
+            .title(title)
+            .transient_for(parent)
+            .modal(true)
+            .default_width(900)
+            .default_height(450)
+            .build();
+
+        let paned = gtk4::Paned::new(gtk4::Orientation::Horizontal);
+        paned.set_margin_start(12);
+        paned.set_margin_end(12);
+        paned.set_margin_top(12);
+        paned.set_margin_bottom(12);
+        paned.set_wide_handle(true);
+
+        for (label, text, highlight) in [(labels[0], original, false), (labels[1], corrected, true)] {
+            let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 6);
+            let heading = gtk4::Label::new(Some(label));
+            heading.add_css_class("heading");
+            heading.set_xalign(0.0);
+            vbox.append(&heading);
+
+            let text_view = gtk4::TextView::builder()
+                .editable(false)
+                .wrap_mode(gtk4::WrapMode::Word)
+                .build();
+            if highlight {
+                let _timer = jank::measure("comparison_diff", text.chars().count());
+                set_text_with_diff(&text_view.buffer(), original, text, true);
+            } else {
+                text_view.buffer().set_text(text);
+            }
+
+            let scrolled = gtk4::ScrolledWindow::builder()
+                .hscrollbar_policy(gtk4::PolicyType::Never)
+                .hexpand(true)
+                .vexpand(true)
+                .child(&text_view)
+                .build();
+            vbox.append(&scrolled);
+
+            if highlight {
+                paned.set_end_child(Some(&vbox));
+            } else {
+                paned.set_start_child(Some(&vbox));
+            }
+        }
+        paned.set_position(440);
+
+        dialog.set_child(Some(&paned));
+        dialog.present();
+    }
+
+    /// Przełącza okno między pełnym a kompaktowym (same panele, bez ramki)
+    fn set_window_mode(state: &Rc<RefCell<AppState>>, mode: WindowMode) {
+        let state_ref = state.borrow();
+        if *state_ref.window_mode.borrow() == mode {
+            return;
+        }
+        *state_ref.window_mode.borrow_mut() = mode;
+
+        let full = mode == WindowMode::Full;
+        for widget in &state_ref.chrome {
+            widget.set_visible(full);
+        }
+        state_ref
+            .refine_bar
+            .set_visible(full && state_ref.config.borrow().conversation.enabled);
+
+        // Ramkę widocznego okna menedżer okien przyjmuje dopiero po ponownym pokazaniu
+        let window = &state_ref.window;
+        let was_visible = window.is_visible();
+        if was_visible {
+            window.set_visible(false);
+        }
+        window.set_decorated(full);
+        if was_visible {
+            window.set_visible(true);
+            window.present();
+            if !full {
+                Self::center_compact_window(window);
+            }
+        }
+        info!("Window mode: {:?}", mode);
+    }
+
+    /// `win.process-text` z parą (tekst, styl) od `--text`/`--style`; pusty styl to standardowa korekta
+    fn setup_launch_text(state: &Rc<RefCell<AppState>>) {
+        let window = state.borrow().window.clone();
+        let action = gio::SimpleAction::new("process-text", Some(glib::VariantTy::new("(ss)").unwrap()));
+        let state_clone = state.clone();
+        action.connect_activate(move |_, param| {
+            let Some((text, style)) = param.and_then(|p| p.get::<(String, String)>()) else {
+                return;
+            };
+            if text.trim().is_empty() {
+                state_clone.borrow().status_label.set_text("⚠️ Brak tekstu w --text");
+                return;
+            }
+            let style = if style.is_empty() {
+                CorrectionStyle::Normal
+            } else {
+                CorrectionStyle::from_str(&style)
+            };
+            info!("Processing {} chars from the command line", text.chars().count());
+            let state = state_clone.clone();
+            glib::spawn_future_local(async move { Self::start_session(&state, text, style).await });
+        });
+        window.add_action(&action);
+    }
+
+    /// `win.show-full` (uruchomienie ręczne) pokazuje pełne okno; Escape chowa kompaktowe
+    fn setup_window_modes(state: &Rc<RefCell<AppState>>) {
+        let window = state.borrow().window.clone();
+
+        let action = gio::SimpleAction::new("show-full", None);
+        let state_clone = state.clone();
+        action.connect_activate(move |_, _| {
+            Self::set_window_mode(&state_clone, WindowMode::Full);
+            let window = state_clone.borrow().window.clone();
+            window.set_visible(true);
+            window.present();
+        });
+        window.add_action(&action);
+
+        let keys = gtk4::EventControllerKey::new();
+        let state_clone = state.clone();
+        keys.connect_key_pressed(move |_, key, _, _| {
+            let state_ref = state_clone.borrow();
+            if key == gdk::Key::Escape && *state_ref.window_mode.borrow() == WindowMode::Compact {
+                state_ref.window.set_visible(false);
+                info!("Compact window hidden (Escape)");
+                return glib::Propagation::Stop;
+            }
+            glib::Propagation::Proceed
+        });
+        window.add_controller(keys);
+    }
+
+    /// Samouczek pierwszego uruchomienia, gdy okno pojawi się po raz pierwszy
+    fn setup_tutorial(state: &Rc<RefCell<AppState>>, paste: gtk4::Widget, settings: gtk4::Widget) {
+        if !tutorial::should_show(&state.borrow().config.borrow()) {
+            return;
+        }
+        let started = Rc::new(RefCell::new(false));
+        let state_clone = state.clone();
+        state.borrow().window.connect_map(move |_| {
+            if started.replace(true) {
+                return;
+            }
+            info!("Starting first-run tutorial");
+            let targets = Rc::new((paste.clone(), settings.clone()));
+            // Pokazanie dymka przed ułożeniem okna kończy się ostrzeżeniem GTK
+            let state = state_clone.clone();
+            glib::idle_add_local_once(move || Self::show_tutorial_step(&state, targets, 0));
+        });
+    }
+
+    fn tutorial_target(state: &Rc<RefCell<AppState>>, targets: &(gtk4::Widget, gtk4::Widget), target: Target) -> gtk4::Widget {
+        let state_ref = state.borrow();
+        match target {
+            Target::PasteButton => targets.0.clone(),
+            Target::SettingsButton => targets.1.clone(),
+            Target::StatusBar => state_ref.status_label.clone().upcast(),
+            Target::FirstPanel => {
+                let first = state_ref.config.borrow().settings.panel_order(&API_NAMES[..])[0];
+                state_ref.panels[first].use_button.clone().upcast()
+            }
+        }
+    }
+
+    fn show_tutorial_step(state: &Rc<RefCell<AppState>>, targets: Rc<(gtk4::Widget, gtk4::Widget)>, index: usize) {
+        let Some(step) = tutorial::STEPS.get(index).copied() else {
+            Self::finish_tutorial(state);
+            return;
+        };
+        let parent = Self::tutorial_target(state, &targets, step.target);
+
+        let popover = gtk4::Popover::new();
+        popover.set_autohide(false);
+        popover.set_parent(&parent);
+
+        let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 8);
+        vbox.set_margin_start(6);
+        vbox.set_margin_end(6);
+        vbox.set_margin_top(6);
+        vbox.set_margin_bottom(6);
+
+        let title = gtk4::Label::new(Some(step.title));
+        title.add_css_class("heading");
+        title.set_halign(gtk4::Align::Start);
+        vbox.append(&title);
+
+        let hotkey = HotkeyBindings::from_config(&state.borrow().config.borrow().global_hotkey).main;
+        let body = gtk4::Label::new(Some(&step.text(hotkey.description())));
+        body.set_wrap(true);
+        body.set_max_width_chars(40);
+        body.set_xalign(0.0);
+        vbox.append(&body);
+
+        let buttons = gtk4::Box::new(gtk4::Orientation::Horizontal, 6);
+        buttons.set_halign(gtk4::Align::End);
+        let skip = gtk4::Button::with_label("Pomiń");
+        skip.add_css_class("flat");
+        let next = gtk4::Button::with_label(step.button);
+        next.add_css_class("suggested-action");
+        if index + 1 < tutorial::STEPS.len() {
+            buttons.append(&skip);
+        }
+        buttons.append(&next);
+        vbox.append(&buttons);
+        popover.set_child(Some(&vbox));
+
+        let state_clone = state.clone();
+        let popover_clone = popover.clone();
+        skip.connect_clicked(move |_| {
+            popover_clone.popdown();
+            popover_clone.unparent();
+            info!("Tutorial skipped at step {}", index + 1);
+            Self::finish_tutorial(&state_clone);
+        });
+
+        let state_clone = state.clone();
+        let popover_clone = popover.clone();
+        next.connect_clicked(move |_| {
+            popover_clone.popdown();
+            popover_clone.unparent();
+            let state = state_clone.clone();
+            let targets = targets.clone();
+            if step.target == Target::PasteButton {
+                glib::spawn_future_local(async move {
+                    Self::run_tutorial_sample(&state).await;
+                    Self::show_tutorial_step(&state, targets, index + 1);
+                });
+            } else {
+                Self::show_tutorial_step(&state, targets, index + 1);
+            }
+        });
+
+        popover.popup();
+    }
+
+    /// Przykładowa korekta przez dostawcę demonstracyjnego (bez kluczy i sieci)
+    async fn run_tutorial_sample(state: &Rc<RefCell<AppState>>) {
+        let demo_was_active = crate::api::demo::is_active(&state.borrow().config.borrow());
+        crate::api::demo::enable();
+        Self::start_session(state, tutorial::SAMPLE_TEXT.to_string(), CorrectionStyle::Normal).await;
+        if !demo_was_active {
+            crate::api::demo::disable();
+        }
+    }
+
+    fn finish_tutorial(state: &Rc<RefCell<AppState>>) {
+        let mut config = state.borrow().config.borrow().clone();
+        config.settings.tutorial_done = true;
+        Self::save_config_in_background(state, config);
+    }
+
+    fn setup_close_handler(window: adw::ApplicationWindow) {
+        window.connect_close_request(move |win| {
+            win.set_visible(false);
+            info!("Window hidden (close intercepted)");
+            glib::Propagation::Stop
+        });
+    }
+
+    /// Podpowiedź skrótu; przy portalu pokazuje skrót, który portal naprawdę przypisał
+    fn show_hotkey_hint(state_ref: &AppState, combination: &str) {
+        let description = state_ref
+            .portal_hotkey
+            .as_ref()
+            .and_then(PortalHotkeyHandle::trigger)
+            .unwrap_or_else(|| combination.to_string());
+        state_ref
+            .status_label
+            .set_text(&format!("⌨️ {} - zaznacz tekst i naciśnij", description));
+    }
+
+    fn setup_hotkey(state: Rc<RefCell<AppState>>) {
+        let (async_tx, async_rx) = async_channel::unbounded::<HotkeyEvent>();
+        let interval = state.borrow().config.borrow().polling.hotkey_interval();
+        let power = state.borrow().power.clone();
+        let bindings = HotkeyBindings::from_config(&state.borrow().config.borrow().global_hotkey);
+        Self::show_hotkey_hint(&state.borrow(), bindings.main.description());
+        let main = bindings.main.clone();
+        if hotkey_portal::is_wayland() {
+            Self::setup_portal_hotkey(&state, &bindings, async_tx.clone());
+        }
+        state.borrow().hotkey_bindings.send_replace(bindings);
+        let mut combos = state.borrow().hotkey_bindings.subscribe();
+        // Skróty stylów rejestruje pętla zdarzeń przy pierwszym przebiegu
+        combos.mark_changed();
+        let (conflicts, conflict) = tokio::sync::watch::channel(None);
+        Self::watch_hotkey_conflicts(&state, conflict);
+        
+        let thread = std::thread::spawn(move || {
+            let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+            
+            if let Ok(manager) = HotkeyManager::new(tx, main, conflicts) {
+                info!("Hotkey manager created");
+                let mut shutdown = SHUTDOWN.signal();
+                
+                let rt = tokio::runtime::Runtime::new().unwrap();
+                rt.block_on(async {
+                    let event_loop = manager.start_event_loop(interval, power, combos);
+                    loop {
+                        tokio::select! {
+                            event = rx.recv() => match event {
+                                Some(event) => {
+                                    let _ = async_tx.send(event).await;
+                                }
+                                None => break,
+                            },
+                            _ = shutdown.wait() => break,
+                        }
+                    }
+                    // Przerwana pętla zwalnia menedżera, a jego Drop wyrejestrowuje skrót
+                    event_loop.abort();
+                    let _ = event_loop.await;
+                });
+                rt.shutdown_background();
+            }
+        });
+        SHUTDOWN.track("hotkey", thread);
+
+        glib::spawn_future_local(async move {
+            let mut detector = DoublePressDetector::new(std::time::Duration::ZERO);
+            // Pojedyncze naciśnięcie czekające, czy nie przyjdzie drugie
+            let pending: Rc<RefCell<Option<glib::SourceId>>> = Rc::new(RefCell::new(None));
+
+            while let Ok(event) = async_rx.recv().await {
+                match event {
+                    HotkeyEvent::Triggered => {
+                        info!("Hotkey triggered");
+                        Self::capture_target_app(&state);
+                        let compact = state.borrow().config.borrow().settings.compact_hotkey_window;
+                        Self::set_window_mode(&state, if compact { WindowMode::Compact } else { WindowMode::Full });
+
+                        let double_press = state.borrow().config.borrow().double_press.clone();
+                        let action = DoublePressAction::from_config(&double_press.action);
+                        if action == DoublePressAction::None {
+                            Self::handle_hotkey_pressed(&state).await;
+                            continue;
+                        }
+
+                        if detector.window() != double_press.window() {
+                            detector = DoublePressDetector::new(double_press.window());
+                        }
+                        if detector.press(Instant::now()) {
+                            if let Some(source) = pending.borrow_mut().take() {
+                                source.remove();
+                            }
+                            info!("Hotkey double press: {}", action.as_str());
+                            let state = state.clone();
+                            glib::spawn_future_local(async move {
+                                Self::run_double_press_action(&state, action).await;
+                            });
+                        } else {
+                            let state = state.clone();
+                            let pending_clone = pending.clone();
+                            let source = glib::timeout_add_local_once(detector.window(), move || {
+                                pending_clone.borrow_mut().take();
+                                glib::spawn_future_local(async move {
+                                    Self::handle_hotkey_pressed(&state).await;
+                                });
+                            });
+                            *pending.borrow_mut() = Some(source);
+                        }
+                    }
+                    HotkeyEvent::Style(style) => {
+                        info!("Style hotkey triggered: {}", style.as_str());
+                        Self::capture_target_app(&state);
+                        let compact = state.borrow().config.borrow().settings.compact_hotkey_window;
+                        Self::set_window_mode(&state, if compact { WindowMode::Compact } else { WindowMode::Full });
+                        Self::present_for_session(&state);
+                        Self::process_clipboard_from_hotkey(&state, move |_| style).await;
+                    }
+                }
+            }
+        });
+    }
+
+    /// Pokazuje okno konfliktu, gdy skrót główny zajął inny program. Z portalem
+    /// skrót i tak działa, więc konflikt skrótu X11 trafia tylko do logu.
+    fn watch_hotkey_conflicts(state: &Rc<RefCell<AppState>>, mut conflicts: tokio::sync::watch::Receiver<Option<HotkeyConflict>>) {
+        let state = state.clone();
+        glib::spawn_future_local(async move {
+            while conflicts.changed().await.is_ok() {
+                let Some(conflict) = conflicts.borrow_and_update().clone() else {
+                    continue;
+                };
+                if state.borrow().portal_hotkey.is_some() {
+                    info!("Hotkey {} taken, portal shortcut stays active", conflict.combination);
+                    continue;
+                }
+                Self::resolve_hotkey_conflict(&state, &conflict).await;
+            }
+        });
+    }
+
+    /// Po nieudanej zmianie skrótu podpowiedź zostaje przy poprzednim, który nadal działa
+    async fn resolve_hotkey_conflict(state: &Rc<RefCell<AppState>>, conflict: &HotkeyConflict) {
+        let window = {
+            let state_ref = state.borrow();
+            match &conflict.active {
+                Some(active) => {
+                    Self::show_hotkey_hint(&state_ref, active);
+                    state_ref.toasts.add_toast(adw::Toast::new(&format!(
+                        "Nie udało się zmienić skrótu na {} - nadal działa {}",
+                        conflict.combination, active
+                    )));
+                }
+                None => state_ref
+                    .status_label
+                    .set_text(&format!("⚠️ Skrót {} jest zajęty przez inny program", conflict.combination)),
+            }
+            state_ref.window.clone()
+        };
+        match ask_hotkey_alternative(&window, conflict).await {
+            ConflictChoice::Use(combination) => {
+                info!("Switching hotkey from {} to {}", conflict.combination, combination);
+                let mut config = state.borrow().config.borrow().clone();
+                config.global_hotkey.combination = combination.clone();
+                Self::save_config_in_background(state, config);
+                state
+                    .borrow()
+                    .toasts
+                    .add_toast(adw::Toast::new(&format!("Skrót zmieniony na {}", combination)));
+            }
+            ConflictChoice::Settings => Self::show_settings(state),
+            ConflictChoice::Skip => info!("Hotkey conflict dismissed, {} stays unregistered", conflict.combination),
+        }
+    }
+
+    /// Skrót główny i skróty stylów przez portal GlobalShortcuts. Skróty X11 działają
+    /// dalej jako zapas: kombinację przechwyconą przez kompozytor dostaje tylko portal.
+    fn setup_portal_hotkey(state: &Rc<RefCell<AppState>>, bindings: &HotkeyBindings, events: async_channel::Sender<HotkeyEvent>) {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let (manager, handle) = PortalHotkeyManager::new(tx, bindings);
+        let mut trigger = handle.subscribe();
+        state.borrow_mut().portal_hotkey = Some(handle);
+
+        // Sesja portalu działa na wspólnym runtime; wątek jest tylko po to, żeby zamknięcie na nią poczekało
+        let thread = std::thread::spawn(move || {
+            crate::TOKIO_RUNTIME.block_on(async {
+                let forward = async {
+                    while let Some(event) = rx.recv().await {
+                        let event = match event {
+                            PortalHotkeyEvent::Triggered => HotkeyEvent::Triggered,
+                            PortalHotkeyEvent::Style(style) => HotkeyEvent::Style(style),
+                        };
+                        let _ = events.send(event).await;
+                    }
+                };
+                let (result, _) = tokio::join!(manager.run(SHUTDOWN.signal()), forward);
+                if let Err(e) = result {
+                    warn!("Portal hotkey unavailable, using X11 hotkey only: {}", e);
+                }
+            });
+        });
+        SHUTDOWN.track("portal-hotkey", thread);
+
+        let state = state.clone();
+        glib::spawn_future_local(async move {
+            while trigger.changed().await.is_ok() {
+                let state_ref = state.borrow();
+                let main = state_ref.hotkey_bindings.borrow().main.clone();
+                Self::show_hotkey_hint(&state_ref, main.description());
+            }
+        });
+    }
+
+    /// Zapamiętuje okno aktywne w chwili naciśnięcia skrótu (cel wklejenia i źródło tekstu)
+    fn capture_target_app(state: &Rc<RefCell<AppState>>) {
+        let source = crate::platform::active_window();
+        info!("Target application: {:?}", source);
+        let state_ref = state.borrow();
+        *state_ref.target_app.borrow_mut() = source.as_ref().map(|s| s.class.clone()).filter(|c| !c.is_empty());
+        *state_ref.pending_source.borrow_mut() = source.map(|source| (source, Instant::now()));
+    }
+
+    /// Okno ze skrótu, jeśli sesja zaczyna się niedługo po naciśnięciu; starsze
+    /// (skrót bez sesji, np. pusty schowek) nie trafia do sesji z przycisku
+    fn pending_source(state_ref: &AppState) -> Option<SourceApp> {
+        state_ref
+            .pending_source
+            .borrow()
+            .as_ref()
+            .filter(|(_, at)| at.elapsed() < PENDING_SOURCE_TTL)
+            .map(|(source, _)| source.clone())
+    }
+
+    fn present_for_session(state: &Rc<RefCell<AppState>>) {
+        let state_ref = state.borrow();
+        if state_ref.config.borrow().settings.should_auto_present(0) {
+            state_ref.window.set_visible(true);
+            state_ref.window.present();
+            if *state_ref.window_mode.borrow() == WindowMode::Compact {
+                Self::center_compact_window(&state_ref.window);
+            }
+        }
+    }
+
+    /// Środkuje okno kompaktowe na monitorze pod kursorem (bez kursora: na pierwszym).
+    /// Przesunięcie przez xdotool działa tylko na X11; na Wayland okno ustawia kompozytor.
+    fn center_compact_window(window: &adw::ApplicationWindow) {
+        let window_weak = window.downgrade();
+        // Rozmiar okna jest znany dopiero po jego zmapowaniu
+        glib::timeout_add_local_once(std::time::Duration::from_millis(100), move || {
+            let Some(window) = window_weak.upgrade() else {
+                return;
+            };
+            let cursor = crate::platform::cursor_position().unwrap_or((0, 0));
+            let Some(screen) = monitor_at(cursor) else {
+                info!("Monitor unknown, compact window placed by the window manager");
+                return;
+            };
+            let (x, y) = chooser::center_in((window.width(), window.height()), screen);
+            let title = window.title().unwrap_or_default();
+            if let Err(e) = crate::platform::move_window(&title, x, y) {
+                warn!("Could not center compact window: {}", e);
+            }
+        });
+    }
+
+    async fn run_double_press_action(state: &Rc<RefCell<AppState>>, action: DoublePressAction) {
+        match action {
+            DoublePressAction::None => Self::handle_hotkey_pressed(state).await,
+            DoublePressAction::Translate => {
+                Self::present_for_session(state);
+                Self::process_clipboard_from_hotkey(state, CorrectionStyle::translation_for).await;
+            }
+            DoublePressAction::RerunLast => Self::repeat_last_text(state, None).await,
+            DoublePressAction::Palette => Self::show_style_palette(state),
+        }
+    }
+
+    /// Okienko wyboru stylu przy kursorze; wybrany styl dostaje tekst ze schowka,
+    /// przy `ConfirmClipboard` dopiero po "Przetwórz"
+    fn show_style_palette(state: &Rc<RefCell<AppState>>) {
+        let config = state.borrow().config.borrow().clone();
+        let pipelines: Vec<String> = config.pipelines.iter().map(pipeline::label).collect();
+        let custom_styles: Vec<CustomStyle> = usable_custom_styles(&config.custom_styles).into_iter().cloned().collect();
+        let names: Vec<String> = custom_styles.iter().map(|custom| custom.name.trim().to_string()).collect();
+        let palette = StylePalette::new(&state.borrow().window, &names, &pipelines);
+
+        let state_clone = state.clone();
+        palette.connect_pick(move |style| {
+            let state = state_clone.clone();
+            glib::spawn_future_local(async move {
+                Self::present_for_session(&state);
+                Self::process_clipboard_from_hotkey(&state, move |_| style).await;
+            });
+        });
+
+        let state_clone = state.clone();
+        palette.connect_custom_style(move |index| {
+            let Some(custom) = custom_styles.get(index).cloned() else {
+                return;
+            };
+            let state = state_clone.clone();
+            glib::spawn_future_local(async move {
+                Self::present_for_session(&state);
+                if let Some(text) = Self::confirmed_clipboard(&state).await {
+                    Self::start_session_with(&state, text, CorrectionStyle::Normal, Some(custom)).await;
+                }
+            });
+        });
+
+        let state_clone = state.clone();
+        palette.connect_custom(move || {
+            let state = state_clone.clone();
+            glib::spawn_future_local(async move {
+                Self::run_custom_instruction(&state).await;
+            });
+        });
+
+        let state_clone = state.clone();
+        palette.connect_pipeline(move |index| {
+            let state = state_clone.clone();
+            glib::spawn_future_local(async move {
+                Self::run_pipeline(&state, index).await;
+            });
+        });
+
+        palette.present_near_cursor();
+        info!("Style palette shown");
+    }
+
+    /// Pyta o własne polecenie i wysyła z nim tekst ze schowka (tylko ta sesja)
+    async fn run_custom_instruction(state: &Rc<RefCell<AppState>>) {
+        Self::present_for_session(state);
+        let window = state.borrow().window.clone();
+        let recent = state.borrow().recent_instructions.borrow().items().to_vec();
+        let Some(instruction) = ask_instruction(&window, &recent).await else {
+            return;
+        };
+        if let Err(e) = state.borrow().recent_instructions.borrow_mut().remember(&instruction) {
+            error!("Failed to save recent instructions: {}", e);
+        }
+
+        let Some(text) = Self::confirmed_clipboard(state).await else {
+            return;
+        };
+        Self::start_session_with(state, text, CorrectionStyle::Normal, Some(instruction_style(&instruction))).await;
+    }
+
+    /// Tekst ze schowka; brak tekstu albo błąd trafia na pasek stanu
+    fn clipboard_text(state: &Rc<RefCell<AppState>>) -> Option<String> {
+        match clipboard::read_text() {
+            Ok(text) if !text.is_empty() => Some(text),
+            Ok(_) => {
+                state.borrow().status_label.set_text("⚠️ Brak tekstu w schowku");
+                None
+            }
+            Err(e) => {
+                error!("Clipboard read failed: {}", e);
+                state.borrow().status_label.set_text(&format!("❌ Blad schowka: {}", e));
+                None
+            }
+        }
+    }
+
+    /// Potok stylów z konfiguracji: tekst ze schowka przechodzi przez kolejne style u jednego dostawcy
+    async fn run_pipeline(state: &Rc<RefCell<AppState>>, index: usize) {
+        Self::present_for_session(state);
+        let config = state.borrow().config.borrow().clone();
+        let Some(chain) = config.pipelines.get(index).cloned() else {
+            return;
+        };
+        let (provider, styles) = match pipeline::resolve(&chain) {
+            Ok(resolved) => resolved,
+            Err(e) => {
+                warn!("Invalid pipeline: {}", e);
+                state.borrow().status_label.set_text(&format!("❌ {}", e));
+                return;
+            }
+        };
+        let Some(panel_index) = panel_specs().iter().position(|panel| panel.provider == provider) else {
+            warn!("Pipeline '{}': {} has no panel", chain.name, provider.name());
+            state
+                .borrow()
+                .status_label
+                .set_text(&format!("❌ Potok „{}”: {} nie ma panelu w oknie", chain.name, provider.name()));
+            return;
+        };
+        let Some(text) = Self::confirmed_clipboard(state).await else {
+            return;
+        };
+        let text = Self::normalize_input(state, &text);
+
+        Self::prepare_processing_session(state, &text);
+        {
+            let state_ref = state.borrow();
+            *state_ref.session_style.borrow_mut() = *styles.last().unwrap_or(&CorrectionStyle::Normal);
+            *state_ref.session_custom.borrow_mut() = None;
+            state_ref.pipeline_steps.borrow_mut().clear();
+            state_ref.metrics.record_session();
+            state_ref
+                .status_label
+                .set_text(&format!("⛓ Potok „{}” przez {}…", chain.name, provider.name()));
+        }
+        for i in (0..panel_specs().len()).filter(|&i| i != panel_index) {
+            Self::show_pipeline_skipped(state, i, &chain.name);
+        }
+        info!("Pipeline '{}': {} steps via {}", chain.name, styles.len(), provider.name());
+
+        let session = state.borrow().session.session();
+        let (request_id, span) = Self::start_request_span(state, panel_index);
+        let (tx, rx) = async_channel::unbounded::<usize>();
+        let task = {
+            let (config, text, styles) = (panel_specs()[panel_index].config(&config), text.clone(), styles.clone());
+            crate::TOKIO_RUNTIME.spawn(
+                async move {
+                    pipeline::run(provider, &config, &text, &styles, move |n| {
+                        let _ = tx.try_send(n);
+                    })
+                    .await
+                }
+                .instrument(span),
+            )
+        };
+        while let Ok(step) = rx.recv().await {
+            if Self::is_stale(state, session) {
+                continue;
+            }
+            let style = styles[step];
+            state.borrow().panels[panel_index].text_view.buffer().set_text(&format!(
+                "⛓ Krok {}/{}: {} {}…",
+                step + 1,
+                styles.len(),
+                style.emoji(),
+                style.display_name_pl()
+            ));
+        }
+
+        let (steps, result) = match task.await {
+            Ok(Ok(steps)) => {
+                let output = steps.last().map(|s| s.output.clone()).unwrap_or_default();
+                (steps, Ok(output))
+            }
+            Ok(Err((step, e, steps))) => {
+                let message = format!("Krok {} ({}): {}", step + 1, styles[step].display_name_pl(), e);
+                warn!("Pipeline request {} failed: {}", request_id, message);
+                (steps, Err(Self::error_with_request_id(message, &request_id)))
+            }
+            Err(e) => (Vec::new(), Err(e.to_string())),
+        };
+        Self::record_audit(state, &config, panel_index, result.as_deref().ok());
+        if Self::is_stale(state, session) {
+            info!("Dropping stale pipeline result from session {}", session);
+            return;
+        }
+        *state.borrow().pipeline_steps.borrow_mut() = steps;
+        state.borrow().session.finish(panel_index, result.is_ok());
+        Self::update_panel_result(state, panel_index, result, session);
+        Self::finalize_processing(state);
+    }
+
+    /// Wysyła jeszcze raz tekst ostatniej sesji, np. po poprawieniu klucza API,
+    /// bez ponownego kopiowania; bez podanego stylu w tym samym co poprzednio
+    async fn repeat_last_text(state: &Rc<RefCell<AppState>>, style: Option<CorrectionStyle>) {
+        let state_ref = state.borrow();
+        let text = state_ref.original_text.borrow().clone();
+        if text.trim().is_empty() {
+            state_ref.status_label.set_text("⚠️ Brak ostatniego tekstu do powtórzenia");
+            return;
+        }
+        if state_ref.panels.iter().any(|p| *p.is_processing.borrow()) {
+            state_ref.status_label.set_text("⏳ Poczekaj na zakończenie bieżących zapytań");
+            return;
+        }
+        let (style, custom) = match style {
+            Some(style) => (style, None),
+            None => (*state_ref.session_style.borrow(), state_ref.session_custom.borrow().clone()),
+        };
+        drop(state_ref);
+
+        info!("Repeating the last text ({} characters)", text.chars().count());
+        Self::present_for_session(state);
+        Self::start_session_with(state, text, style, custom).await;
+    }
+
+    async fn handle_paste_clicked(state: &Rc<RefCell<AppState>>) {
+        info!("Paste button clicked, reading clipboard...");
+        Self::process_clipboard(state, |_| CorrectionStyle::Normal).await;
+    }
+
+    /// Skrót główny; styl wybiera reguła `[[app_styles]]` dla okna źródłowego,
+    /// a w oknie z regułą "skip" okno aplikacji się nie pokazuje
+    async fn handle_hotkey_pressed(state: &Rc<RefCell<AppState>>) {
+        let choice = {
+            let state_ref = state.borrow();
+            let config = state_ref.config.borrow();
+            Self::pending_source(&state_ref)
+                .and_then(|window| app_styles::choice_for(&config.app_styles, &window))
+                .map(|(rule, choice)| (app_styles::label(rule), choice))
+        };
+        let style = match choice {
+            Some((rule, AppChoice::Skip)) => {
+                info!("Hotkey ignored in this window (rule {})", rule);
+                let state_ref = state.borrow();
+                state_ref.pending_source.borrow_mut().take();
+                state_ref
+                    .status_label
+                    .set_text(&format!("⏭️ Skrót pominięty w tym oknie (reguła „{}”)", rule));
+                return;
+            }
+            Some((rule, AppChoice::Style(style))) => {
+                info!("Style {} from window rule {}", style.as_str(), rule);
+                style
+            }
+            None => CorrectionStyle::Normal,
+        };
+        Self::present_for_session(state);
+        info!("Reading clipboard after hotkey...");
+        Self::process_clipboard_from_hotkey(state, move |_| style).await;
+    }
+
+    /// Wysyła tekst ze schowka w stylu wybranym przez `choose_style` na podstawie tego tekstu
+    async fn process_clipboard<F>(state: &Rc<RefCell<AppState>>, choose_style: F)
+    where
+        F: FnOnce(&str) -> CorrectionStyle,
+    {
+        if let Some(text) = Self::read_clipboard(state) {
+            let style = choose_style(&text);
+            Self::start_session(state, text, style).await;
+        }
+    }
+
+    /// Jak `process_clipboard`, ale przy `ConfirmClipboard` tekst idzie do API dopiero po "Przetwórz"
+    async fn process_clipboard_from_hotkey<F>(state: &Rc<RefCell<AppState>>, choose_style: F)
+    where
+        F: FnOnce(&str) -> CorrectionStyle,
+    {
+        let Some(text) = Self::confirmed_clipboard(state).await else {
+            return;
+        };
+        let style = choose_style(&text);
+        Self::start_session(state, text, style).await;
+    }
+
+    /// Tekst ze schowka dla akcji ze skrótu; przy `ConfirmClipboard` `None`, gdy nie kliknięto "Przetwórz"
+    async fn confirmed_clipboard(state: &Rc<RefCell<AppState>>) -> Option<String> {
+        let text = Self::read_clipboard(state)?;
+        let confirm = state.borrow().config.borrow().settings.confirm_clipboard;
+        if confirm && !Self::confirm_clipboard_send(state, &text).await {
+            info!("Sending clipboard cancelled");
+            state.borrow().status_label.set_text("🚫 Nie wysłano schowka");
+            return None;
+        }
+        Some(text)
+    }
+
+    /// Niepusty tekst ze schowka; brak tekstu i błędy trafiają do paska stanu
+    fn read_clipboard(state: &Rc<RefCell<AppState>>) -> Option<String> {
+        match clipboard::read_text() {
+            Ok(text) => {
+                info!("Clipboard read OK, {} chars", text.len());
+                if text.is_empty() {
+                    state.borrow().status_label.set_text("⚠️ Brak tekstu w schowku");
+                    return None;
+                }
+                Some(text)
+            }
+            Err(e) => {
+                error!("Clipboard read failed: {}", e);
+                state.borrow().status_label.set_text(&format!("❌ Blad schowka: {}", e));
+                None
+            }
+        }
+    }
+
+    /// Powiadomienie z początkiem tekstu; `true` po kliknięciu "Przetwórz",
+    /// `false` gdy zniknęło bez kliknięcia
+    async fn confirm_clipboard_send(state: &Rc<RefCell<AppState>>, text: &str) -> bool {
+        let toast = adw::Toast::builder()
+            .title(format!(
+                "Wysłać schowek do API? „{}” ({} znaków)",
+                chooser::preview_line(text, chooser::PREVIEW_CHARS),
+                text.chars().count()
+            ))
+            .use_markup(false)
+            .priority(adw::ToastPriority::High)
+            .timeout(15)
+            .button_label("Przetwórz")
+            .build();
+
+        let (tx, rx) = async_channel::bounded(1);
+        let confirm_tx = tx.clone();
+        toast.connect_button_clicked(move |_| {
+            let _ = confirm_tx.try_send(true);
+        });
+        toast.connect_dismissed(move |_| {
+            let _ = tx.try_send(false);
+        });
+
+        info!("Waiting for confirmation before sending the clipboard");
+        let state_ref = state.borrow();
+        state_ref.status_label.set_text("⏸ Czekam na potwierdzenie wysłania schowka");
+        // Powiadomienie musi być widoczne, nawet gdy okno czeka na pierwsze wyniki
+        state_ref.window.set_visible(true);
+        state_ref.window.present();
+        state_ref.toasts.add_toast(toast);
+        drop(state_ref);
+
+        rx.recv().await.unwrap_or(false)
+    }
+
+    async fn start_session(state: &Rc<RefCell<AppState>>, text: String, style: CorrectionStyle) {
+        Self::start_session_with(state, text, style, None).await;
+    }
+
+    /// Nowa sesja; `custom` zastępuje polecenie i prompt systemowy stylu do końca tej sesji
+    async fn start_session_with(
+        state: &Rc<RefCell<AppState>>,
+        text: String,
+        style: CorrectionStyle,
+        custom: Option<CustomStyle>,
+    ) {
+        let text = Self::normalize_input(state, &text);
+        Self::prepare_processing_session(state, &text);
+        *state.borrow().session_style.borrow_mut() = style;
+        match &custom {
+            Some(custom) if custom.name == INSTRUCTION_STYLE => info!("Session with a custom instruction"),
+            Some(custom) => info!("Session with custom style {}", custom.name),
+            None if style != CorrectionStyle::Normal => info!("Session style: {}", style.as_str()),
+            None => {}
+        }
+        *state.borrow().session_custom.borrow_mut() = custom;
+
+        let state_ref = state.borrow();
+        let config = state_ref.config.borrow().clone();
+        let session = state_ref.session.session();
+        state_ref.metrics.record_session();
+        drop(state_ref);
+
+        let providers: Vec<usize> = (0..panel_specs().len()).collect();
+        Self::process_with_apis(state.clone(), text, config, None, session, style, &providers).await;
+    }
+
+    /// Tekst po krokach z `[normalize]` (cytowanie, łamanie wierszy, parametry śledzące).
+    /// Szerokość łamania jest wykrywana jeszcze przed sklejeniem wierszy.
+    fn normalize_input(state: &Rc<RefCell<AppState>>, text: &str) -> String {
+        let width = rewrap::detect_width(text);
+        if let Some(width) = width {
+            info!("Input is hard-wrapped at {} columns", width);
+        }
+        *state.borrow().input_wrap.borrow_mut() = width;
+        let normalized = normalize::apply(&state.borrow().config.borrow().normalize, text);
+        if normalized != text {
+            info!("Input normalized: {} -> {} characters", text.chars().count(), normalized.chars().count());
+        }
+        normalized
+    }
+
+    fn prepare_processing_session(state: &Rc<RefCell<AppState>>, text: &str) {
+        let state_ref = state.borrow();
+        
+        *state_ref.original_text.borrow_mut() = text.to_string();
+        *state_ref.session_started.borrow_mut() = now_secs();
+        
+        let session = state_ref.session.start();
+        let source = Self::pending_source(&state_ref).map(|app| app.display_name());
+        state_ref.pending_source.borrow_mut().take();
+        match &source {
+            Some(app) => state_ref
+                .session_label
+                .set_text(&format!("📝 Sesja: {} · z aplikacji: {}", session, app)),
+            None => state_ref.session_label.set_text(&format!("📝 Sesja: {}", session)),
+        }
+        let started = match &source {
+            Some(app) => format!("Sesja {}: {} znaków z aplikacji {}", session, text.chars().count(), app),
+            None => format!("Sesja {}: {} znaków", session, text.chars().count()),
+        };
+        Self::log_activity(&state_ref, ActivityKind::Session, &started);
+        *state_ref.session_source.borrow_mut() = source;
+        
+        state_ref.api_counter_label.set_text(&format!("🤖 API: 0/{}", API_NAMES.len()));
+        
+        state_ref
+            .status_label
+            .set_text(&format!("🔄 Wysyłanie do {} API równocześnie...", API_NAMES.len()));
+        state_ref.hint_label.set_text(&format!("({} znaków)", text.len()));
+        
+        for (i, panel) in state_ref.panels.iter().enumerate() {
+            state_ref.session.begin(i);
+            Self::start_panel(panel, i);
+        }
+        drop(state_ref);
+
+        Self::find_previous_choice(state, text);
+        Self::highlight_preferred_panel(state);
+        Self::update_power_state(state);
+    }
+
+    /// Szuka w historii wyniku wybranego wcześniej dla tego samego tekstu. Zaszyfrowanej
+    /// historii nie odblokowuje, żeby nie pytać o hasło przy każdej sesji.
+    fn find_previous_choice(state: &Rc<RefCell<AppState>>, text: &str) {
+        let state_ref = state.borrow();
+        *state_ref.previous_choice.borrow_mut() = None;
+        let mut history = History::new(History::default_path());
+        match state_ref.history_key.borrow().clone() {
+            Some(key) => history = history.with_key(key),
+            None if state_ref.config.borrow().history.encrypt => return,
+            None => {}
+        }
+        let previous = match history.previous_choice(text) {
+            Ok(Some(previous)) => previous,
+            Ok(None) => return,
+            Err(e) => {
+                error!("Failed to read history: {}", e);
+                return;
+            }
+        };
+        info!("Same text was corrected before, {} was chosen", previous.provider);
+        *state_ref.previous_choice.borrow_mut() = Some(previous);
+        state_ref
+            .hint_label
+            .set_text(&format!("{}{}", state_ref.hint_label.text(), Self::previous_choice_hint(&state_ref)));
+    }
+
+    /// Dopisek do podpowiedzi, np. " · poprzednio wybrano: Anthropic"
+    fn previous_choice_hint(state_ref: &AppState) -> String {
+        state_ref
+            .previous_choice
+            .borrow()
+            .as_ref()
+            .map(|previous| format!(" · poprzednio wybrano: {}", previous.provider))
+            .unwrap_or_default()
+    }
+
+    /// Czyści panel i przełącza go w stan oczekiwania na odpowiedź
+    fn start_panel(panel: &PanelState, index: usize) {
+        *panel.is_processing.borrow_mut() = true;
+        *panel.is_completed.borrow_mut() = false;
+        *panel.has_error.borrow_mut() = false;
+        *panel.start_time.borrow_mut() = Some(Instant::now());
+        *panel.latency.borrow_mut() = None;
+        *panel.result_text.borrow_mut() = String::new();
+        *panel.raw_response.borrow_mut() = String::new();
+        *panel.transfer.borrow_mut() = None;
+        *panel.usage_reported.borrow_mut() = false;
+        *panel.generation.borrow_mut() = None;
+        *panel.request_id.borrow_mut() = String::new();
+
+        panel.frame.set_visible(true);
+        if Self::animations_enabled() {
+            panel.spinner.set_visible(true);
+            panel.spinner.start();
+        }
+        panel.progress_bar.set_visible(true);
+        panel.progress_bar.set_fraction(0.0);
+        panel.cancel_button.set_sensitive(true);
+        Self::reset_stream(panel);
+        panel.pause_button.set_sensitive(true);
+        panel.warnings_label.set_visible(false);
+        panel.readability_label.set_visible(false);
+        panel.model_label.set_visible(false);
+        Self::set_panel_editable(panel, false);
+        panel.use_button.set_sensitive(false);
+        Self::set_panel_status(panel, index, PanelStatus::Processing);
+        panel.name_label.set_text(API_NAMES[index]);
+        panel.time_label.set_text("");
+        panel.text_view.buffer().set_text("🔄 Przygotowanie...");
+    }
+
+    fn rerun_provider(state: &Rc<RefCell<AppState>>, index: usize) {
+        Self::start_rerun(state, index, None);
+    }
+
+    /// Ponowne zapytanie z modelem wybranym z listy dostawcy
+    fn rerun_with_model(state: &Rc<RefCell<AppState>>, index: usize) {
+        let state_ref = state.borrow();
+        if state_ref.original_text.borrow().trim().is_empty() || *state_ref.panels[index].is_processing.borrow() {
+            return;
+        }
+        let models = panel_provider(index).model_choices(&panel_specs()[index].config(&state_ref.config.borrow()));
+        let window = state_ref.window.clone();
+        drop(state_ref);
+
+        let state = state.clone();
+        glib::spawn_future_local(async move {
+            if let Some(model) = ask_model(&window, API_NAMES[index], &models).await {
+                Self::start_rerun(&state, index, Some(model));
+            }
+        });
+    }
+
+    /// Ponawia zapytanie jednego dostawcy dla tekstu bieżącej sesji,
+    /// opcjonalnie innym modelem niż w ustawieniach
+    fn start_rerun(state: &Rc<RefCell<AppState>>, index: usize, model: Option<String>) {
+        let state_ref = state.borrow();
+        let text = state_ref.original_text.borrow().clone();
+        let panel = &state_ref.panels[index];
+        if text.trim().is_empty() || *panel.is_processing.borrow() {
+            return;
+        }
+
+        state_ref.session.begin(index);
+        state_ref
+            .api_counter_label
+            .set_text(&format!("🤖 API: {}/{}", state_ref.session.completed(), API_NAMES.len()));
+        Self::start_panel(panel, index);
+        let config = state_ref.config.borrow().clone();
+        let chosen = Self::panel_config(&config, index, model.as_deref());
+        state_ref.status_label.set_text(&format!(
+            "🔄 Ponowne zapytanie: {} ({})",
+            API_NAMES[index],
+            panel_provider(index).model(&chosen)
+        ));
+
+        let session = state_ref.session.session();
+        let style = *state_ref.session_style.borrow();
+        drop(state_ref);
+        info!("Re-running {} with model {}", API_NAMES[index], panel_provider(index).model(&chosen));
+
+        let state = state.clone();
+        glib::spawn_future_local(async move {
+            Self::process_with_apis(state, text, config, model, session, style, &[index]).await;
+        });
+    }
+
+    /// Wysyła polecenie dopracowania do dostawców z gotowym wynikiem i historią rozmowy
+    fn refine_results(state: &Rc<RefCell<AppState>>, request: &str) -> bool {
+        let state_ref = state.borrow();
+        if request.trim().is_empty() || !state_ref.config.borrow().conversation.enabled {
+            return false;
+        }
+        if state_ref.panels.iter().any(|p| *p.is_processing.borrow()) {
+            state_ref.status_label.set_text("⏳ Poczekaj na zakończenie bieżących zapytań");
+            return false;
+        }
+
+        let message = conversation::refinement_message(request);
+        let conversation = state_ref.conversation.borrow();
+        let requests: Vec<(usize, ApiRequest)> = state_ref
+            .panels
+            .iter()
+            .enumerate()
+            .filter(|(i, panel)| *panel.is_completed.borrow() && conversation.has_history(API_NAMES[*i]))
+            .map(|(i, _)| {
+                let request = ApiRequest::Refine {
+                    history: conversation.history(API_NAMES[i]),
+                    message: message.clone(),
+                };
+                (i, request)
+            })
+            .collect();
+        if requests.is_empty() {
+            state_ref.status_label.set_text("⚠️ Brak wyników do dopracowania");
+            return false;
+        }
+        let system_prompt = conversation.system_prompt().to_string();
+        drop(conversation);
+
+        for (i, _) in &requests {
+            state_ref.session.begin(*i);
+            Self::start_panel(&state_ref.panels[*i], *i);
+        }
+        state_ref
+            .api_counter_label
+            .set_text(&format!("🤖 API: {}/{}", state_ref.session.completed(), API_NAMES.len()));
+        state_ref
+            .status_label
+            .set_text(&format!("💬 Dopracowanie: {}", request.trim()));
+
+        let config = state_ref.config.borrow().clone();
+        let protected = state_ref.session_protected.borrow().clone();
+        let session = state_ref.session.session();
+        drop(state_ref);
+        info!("Refining {} results in conversation mode", requests.len());
+
+        let state = state.clone();
+        glib::spawn_future_local(async move {
+            Self::run_api_requests(state, config, None, system_prompt, protected, session, requests).await;
+        });
+        true
+    }
+
+    /// Prompt systemowy, polecenie i tekst z chronionymi fragmentami zamienionymi na znaczniki
+    fn build_prompt(
+        state: &Rc<RefCell<AppState>>,
+        config: &Config,
+        style: CorrectionStyle,
+        text: &str,
+    ) -> (String, String, ProtectedText) {
+        let custom = state.borrow().session_custom.borrow().clone();
+        let language = LanguageContext::detect(text, &config.language_prompts);
+        let base_prompt = match &custom {
+            Some(custom) => get_custom_system_prompt(custom, &language),
+            None => get_system_prompt(style, &language),
+        };
+        let system_prompt = state.borrow().session_context.borrow().system_prompt(base_prompt);
+        let system_prompt = styleguide::apply_to_prompt(&config.styleguide, &system_prompt);
+        let protected = Self::protect_text(config, text);
+        let system_prompt = protected.system_prompt(&system_prompt);
+        let base_instruction = match &custom {
+            Some(custom) => instructions::instruction_prompt(&custom.instruction),
+            None => get_instruction_prompt(style).to_string(),
+        };
+        let instruction = length::instruction(&base_instruction, &config.length, text);
+        (system_prompt, instruction, protected)
+    }
+
+    /// Podgląd promptu: co dostanie każdy panel dla stylu bieżącej sesji
+    /// (tekst sesji albo, przed pierwszą sesją, tekst ze schowka) i ile to tokenów
+    fn show_prompt_preview(state: &Rc<RefCell<AppState>>) {
+        let session_text = state.borrow().original_text.borrow().clone();
+        let text = if session_text.trim().is_empty() {
+            let Some(text) = Self::clipboard_text(state) else {
+                return;
+            };
+            Self::normalize_input(state, &text)
+        } else {
+            session_text
+        };
+        let config = state.borrow().config.borrow().clone();
+        let style = *state.borrow().session_style.borrow();
+        let (system_prompt, instruction, protected) = Self::build_prompt(state, &config, style, &text);
+        let system_prompt = if config.edit_mode.applies_to(&protected.text) {
+            edits::system_prompt(&system_prompt)
+        } else {
+            system_prompt
+        };
+        let message = conversation::user_message(&instruction, &protected.text);
+
+        let style_name = match state.borrow().session_custom.borrow().as_ref() {
+            Some(custom) => custom.name.clone(),
+            None => style.display_name_pl().to_string(),
+        };
+        let mut preview = format!("Styl: {}\n\nTokeny na panel (prompt systemowy + wiadomość):\n", style_name);
+        let mut approximate = false;
+        for (spec, name) in panel_specs().iter().zip(API_NAMES.iter()) {
+            let model = spec.model(&config);
+            let count = tokens::count_prompt(spec.provider, model, &system_prompt, &message);
+            let reply = tokens::count(spec.provider, model, &protected.text).tokens;
+            approximate |= !count.exact;
+            preview.push_str(&format!("  {} ({}): {}", name, model, count.label()));
+            if let Some(price) = config.pricing.get(model) {
+                let cost = matrix::cost_usd(price, count.tokens as u64, reply as u64);
+                preview.push_str(&format!(", ~{:.5} USD", cost));
+            }
+            if let Some(limit) = tokens::exceeds_context(spec.provider, model, count, reply) {
+                preview.push_str(&format!(" ⚠️ za długi dla modelu (okno {} {})", limit, tokens::tokens_word(limit)));
+            }
+            preview.push('\n');
+        }
+        if approximate {
+            preview.push_str("\n≈ przybliżenie: dostawca nie udostępnia tokenizera\n");
+        }
+        preview.push_str(&format!("\n── Prompt systemowy ──\n{}\n\n── Wiadomość ──\n{}", system_prompt, message));
+        info!("Prompt preview: {} characters", system_prompt.chars().count() + message.chars().count());
+
+        Self::show_text_dialog(&state.borrow().window, "Podgląd promptu", &preview);
+    }
+
+    /// Zamienia chronione fragmenty na znaczniki, jeśli ochrona jest włączona
+    fn protect_text(config: &Config, text: &str) -> ProtectedText {
+        if !config.protected_spans.enabled {
+            return ProtectedText::new(text, &[]);
+        }
+        match protect::compile_patterns(&config.protected_spans.patterns) {
+            Ok(patterns) => {
+                let protected = ProtectedText::new(text, &patterns);
+                info!("Protected {} spans", protected.spans().len());
+                protected
+            }
+            Err(e) => {
+                error!("Protected spans disabled: {}", e);
+                ProtectedText::new(text, &[])
+            }
+        }
+    }
+
+    async fn process_with_apis(
+        state: Rc<RefCell<AppState>>,
+        text: String,
+        config: Config,
+        model: Option<String>,
+        session: u64,
+        style: CorrectionStyle,
+        providers: &[usize],
+    ) {
+        let (system_prompt, instruction, protected) = Self::build_prompt(&state, &config, style, &text);
+
+        {
+            let state_ref = state.borrow();
+            let mut conversation = state_ref.conversation.borrow_mut();
+            if providers.len() == panel_specs().len() {
+                conversation.start(&system_prompt);
+            } else {
+                for &i in providers {
+                    conversation.reset(API_NAMES[i]);
+                }
+            }
+            *state_ref.session_protected.borrow_mut() = protected.clone();
+        }
+
+        let requests = providers
+            .iter()
+            .map(|&i| {
+                let request = ApiRequest::Correct {
+                    text: protected.text.clone(),
+                    instruction: instruction.clone(),
+                };
+                (i, request)
+            })
+            .collect();
+        Self::run_api_requests(state, config, model, system_prompt, protected, session, requests).await;
+    }
+
+    /// Nowy identyfikator wywołania dla panelu i span logów, w którym trzeba je wykonać
+    fn start_request_span(state: &Rc<RefCell<AppState>>, index: usize) -> (String, tracing::Span) {
+        let request_id = crate::api::new_request_id();
+        *state.borrow().panels[index].request_id.borrow_mut() = request_id.clone();
+        let span = tracing::info_span!("request", id = %request_id, provider = API_NAMES[index]);
+        (request_id, span)
+    }
+
+    /// Komunikat błędu panelu z identyfikatorem wywołania do zgłoszeń
+    fn error_with_request_id(error: impl std::fmt::Display, request_id: &str) -> String {
+        format!("{} (ID: {})", error, request_id)
+    }
+
+    /// Konfiguracja zapytania panelu: model panelu albo `model` wybrany przy ponowieniu
+    fn panel_config(config: &Config, index: usize, model: Option<&str>) -> Config {
+        let mut config = panel_specs()[index].config(config);
+        if let Some(model) = model {
+            panel_provider(index).set_model(&mut config, model);
+        }
+        config
+    }
+
+    /// Wysyła zapytania do wskazanych dostawców i wpisuje odpowiedzi do paneli;
+    /// `model` zastępuje model każdego z nich
+    async fn run_api_requests(
+        state: Rc<RefCell<AppState>>,
+        config: Config,
+        model: Option<String>,
+        system_prompt: String,
+        protected: ProtectedText,
+        session: u64,
+        requests: Vec<(usize, ApiRequest)>,
+    ) {
+        let metrics = state.borrow().metrics.clone();
+        let controller = state.borrow().session.clone();
+
+        let configs: Vec<Config> = (0..panel_specs().len())
+            .map(|i| Self::panel_config(&config, i, model.as_deref()))
+            .collect();
+        let mut prepared = Vec::new();
+        for (i, request) in requests.iter().cloned() {
+            let provider = panel_provider(i);
+            if config.circuit_breaker.enabled {
+                let remaining = state.borrow().breaker.borrow().remaining(i, Instant::now());
+                if let Some(remaining) = remaining {
+                    Self::show_circuit_open(&state, i, remaining);
+                    metrics.record_skipped(provider.name());
+                    continue;
+                }
+            }
+            if config.budget.disable_at_limit && Self::budget_status(&state, &config, i) == BudgetStatus::Exceeded {
+                Self::show_budget_exhausted(&state, i);
+                metrics.record_skipped(provider.name());
+                continue;
+            }
+            let panel_model = provider.model(&configs[i]);
+            let prompt_tokens = request.prompt_tokens(provider, panel_model, &system_prompt);
+            let reply_tokens = request.expected_reply_tokens(provider, panel_model);
+            if let Some(limit) = tokens::exceeds_context(provider, panel_model, prompt_tokens, reply_tokens) {
+                Self::show_too_long(&state, i, panel_model, prompt_tokens, limit);
+                metrics.record_skipped(provider.name());
+                continue;
+            }
+            state.borrow().prompt_tokens.borrow_mut()[i] = prompt_tokens.tokens;
+
+            let (request_id, span) = Self::start_request_span(&state, i);
+            prepared.push((i, PreparedRequest { request, request_id, span }));
+        }
+
+        let backend = Arc::new(ApiBackend {
+            configs: configs.clone(),
+            system_prompt,
+            protected: protected.clone(),
+        });
+        let rx = controller.dispatch(crate::TOKIO_RUNTIME.handle(), prepared, backend);
+
+        let state_weak = Rc::downgrade(&state);
+        glib::timeout_add_local(crate::ui::stream_bridge::MIN_FLUSH_INTERVAL, move || {
+            let Some(state) = state_weak.upgrade() else {
+                return glib::ControlFlow::Break;
+            };
+            let state_ref = state.borrow();
+            if state_ref.session.is_stale(session) {
+                return glib::ControlFlow::Break;
+            }
+
+            let mut running = false;
+            for panel in state_ref.panels.iter().filter(|p| *p.is_processing.borrow()) {
+                running = true;
+                Self::flush_stream(panel);
+            }
+
+            if running {
+                glib::ControlFlow::Continue
+            } else {
+                glib::ControlFlow::Break
+            }
+        });
+
+        let state_weak = Rc::downgrade(&state);
+        glib::timeout_add_local(std::time::Duration::from_secs(1), move || {
+            let Some(state) = state_weak.upgrade() else {
+                return glib::ControlFlow::Break;
+            };
+            let state_ref = state.borrow();
+            let running = !state_ref.session.is_stale(session)
+                && state_ref.panels.iter().any(|p| *p.is_processing.borrow());
+            drop(state_ref);
+
+            if running {
+                Self::update_tray_progress(&state);
+                glib::ControlFlow::Continue
+            } else {
+                glib::ControlFlow::Break
+            }
+        });
+
+        let summary = controller
+            .receive(session, rx, |delivery| match delivery {
+                // Po rozpoczęciu nowej sesji późne odpowiedzi nie mogą nadpisać paneli;
+                // koszt i wyłącznik nadal je liczą, bo zapytanie zostało wykonane
+                Delivery::Stale(index, result) => {
+                    info!("Dropping stale {} result from session {}", API_NAMES[index], session);
+                    Self::record_circuit_result(&state, &config, index, result.is_ok());
+                    if let Ok(raw) = &result {
+                        let tokens = Self::estimate_usage(&state, &configs[index], index, raw);
+                        Self::record_usage(&state, &configs[index], index, tokens);
+                    }
+                }
+                Delivery::Current(SessionMessage::Chunk(index, chunk)) => {
+                    Self::append_stream_chunk(&state, index, &chunk);
+                }
+                Delivery::Current(SessionMessage::Shortening(index, chars)) => {
+                    Self::show_shortening(&state, index, chars, config.length.max_chars);
+                }
+                Delivery::Current(SessionMessage::RateLimited(index, wait)) => {
+                    Self::show_rate_limited(&state, index, wait);
+                }
+                Delivery::Current(SessionMessage::Usage(index, tokens)) => {
+                    *state.borrow().panels[index].usage_reported.borrow_mut() = true;
+                    Self::record_usage(&state, &configs[index], index, tokens);
+                }
+                Delivery::Current(SessionMessage::Done(index, result)) => {
+                    Self::record_circuit_result(&state, &config, index, result.is_ok());
+                    let latency = state.borrow().panels[index]
+                        .start_time
+                        .borrow()
+                        .map(|t| t.elapsed())
+                        .unwrap_or_default();
+                    metrics.record_result(panel_provider(index).name(), result.is_ok(), latency);
+                    Self::record_audit(&state, &configs[index], index, result.as_deref().ok());
+                    if let Ok(raw) = &result {
+                        // Bez zużycia od dostawcy koszt jest liczony z lokalnie policzonych tokenów
+                        if !state.borrow().panels[index].usage_reported.replace(false) {
+                            let tokens = Self::estimate_usage(&state, &configs[index], index, raw);
+                            Self::record_usage(&state, &configs[index], index, tokens);
+                        }
+                        let state_ref = state.borrow();
+                        *state_ref.panels[index].raw_response.borrow_mut() = raw.clone();
+                        *state_ref.panels[index].transfer.borrow_mut() = last_transfer(panel_provider(index));
+                        Self::show_generation(&state_ref.panels[index], panel_provider(index).generation(&configs[index]));
+                        let request = requests.iter().find(|(i, _)| *i == index).map(|(_, r)| r);
+                        if let Some(request) = request.filter(|_| config.conversation.enabled) {
+                            state_ref.conversation.borrow_mut().record(
+                                API_NAMES[index],
+                                Exchange {
+                                    user: request.user_message(),
+                                    assistant: raw.clone(),
+                                },
+                            );
+                        }
+                    }
+                    let restored = result.map(|r| protected.restore(&r));
+                    let missing = restored.as_ref().map(|r| r.missing.clone()).unwrap_or_default();
+                    let mut length_warnings = Vec::new();
+                    if let Ok(r) = &restored {
+                        if config.length.preserve {
+                            length_warnings.extend(length::check_preserved(&state.borrow().original_text.borrow(), &r.text));
+                        }
+                        length_warnings.extend(length::check_limit(&r.text, config.length.max_chars));
+                    }
+                    let fact_check = restored
+                        .as_ref()
+                        .ok()
+                        .filter(|_| Self::checks_facts(&state, &config))
+                        .map(|r| facts::check(&state.borrow().original_text.borrow(), &r.text));
+                    Self::update_panel_result(&state, index, restored.map(|r| r.text), session);
+                    if !missing.is_empty() {
+                        Self::show_lost_spans(&state, index, &missing);
+                    }
+                    if !length_warnings.is_empty() {
+                        Self::append_warnings(&state, index, &length_warnings);
+                    }
+                    if let Some(check) = fact_check.filter(|c| !c.is_clean()) {
+                        Self::show_fact_check(&state, index, &check);
+                    }
+                    if config.settings.agreement_heatmap {
+                        Self::refresh_agreement_heatmap(&state);
+                    }
+                    Self::update_tray_progress(&state);
+                }
+            })
+            .await;
+
+        if summary.is_some() {
+            Self::finalize_processing(&state);
+        }
+    }
+
+    /// Czy sesja została już zastąpiona nową
+    fn is_stale(state: &Rc<RefCell<AppState>>, session: u64) -> bool {
+        state.borrow().session.is_stale(session)
+    }
+
+    fn update_panel_result(
+        state: &Rc<RefCell<AppState>>,
+        index: usize,
+        result: Result<String, String>,
+        session: u64,
+    ) {
+        if Self::is_stale(state, session) {
+            info!("Dropping stale {} result from session {}", API_NAMES[index], session);
+            return;
+        }
+        let state_ref = state.borrow();
+        let panel = &state_ref.panels[index];
+        
+        panel.spinner.stop();
+        panel.spinner.set_visible(false);
+        panel.progress_bar.set_visible(false);
+        panel.cancel_button.set_sensitive(false);
+        Self::reset_stream(panel);
+        *panel.is_processing.borrow_mut() = false;
+
+        let latency = panel.start_time.borrow().map(|t| t.elapsed());
+        *panel.latency.borrow_mut() = latency;
+        let elapsed = latency.map(|l| l.as_secs_f64()).unwrap_or(0.0);
+
+        match result {
+            Ok(corrected) => {
+                *panel.result_text.borrow_mut() = corrected.clone();
+                *panel.is_completed.borrow_mut() = true;
                 
-                panel.status_icon.set_text("✅");
+                Self::set_panel_status(panel, index, PanelStatus::Done);
                 panel.name_label.set_text(&format!("{} ({:.1}s)", API_NAMES[index], elapsed));
                 panel.use_button.set_sensitive(true);
                 
                 let original = state_ref.original_text.borrow().clone();
                 let highlight = state_ref.config.borrow().settings.highlight_diffs;
-                set_text_with_diff(&panel.text_view.buffer(), &original, &corrected, highlight);
+                let buffer = panel.text_view.buffer();
+                let timer = jank::measure("insert_result", corrected.chars().count());
+                buffer.begin_irreversible_action();
+                set_text_with_diff(&buffer, &original, &corrected, highlight);
+                buffer.end_irreversible_action();
+                drop(timer);
+                Self::set_panel_editable(panel, true);
+                Self::show_style_violations(panel, &state_ref.config.borrow().styleguide, &corrected);
+                Self::show_readability(panel, &corrected);
+                if *state_ref.preferred_panel.borrow() == Some(index) {
+                    panel.use_button.grab_focus();
+                }
+                
+                let count = state_ref.session.completed();
+                state_ref.api_counter_label.set_text(&format!("🤖 API: {}/{}", count, API_NAMES.len()));
+
+                if count == 1 {
+                    Self::play_sound(&state_ref, SoundEvent::FirstResult);
+                }
+                Self::log_activity(
+                    &state_ref,
+                    ActivityKind::Finished,
+                    &format!("{}: gotowe w {:.1} s", API_NAMES[index], elapsed),
+                );
+
+                if !state_ref.window.is_visible()
+                    && state_ref.config.borrow().settings.should_auto_present(count)
+                {
+                    info!("{} results ready, presenting window", count);
+                    state_ref.window.set_visible(true);
+                    state_ref.window.present();
+                }
+            }
+            Err(e) => {
+                *panel.has_error.borrow_mut() = true;
                 
-                let mut count = state_ref.completed_count.borrow_mut();
-                *count += 1;
-                state_ref.api_counter_label.set_text(&format!("🤖 API: {}/4", *count));
+                Self::set_panel_status(panel, index, PanelStatus::Error);
+                panel.name_label.set_text(API_NAMES[index]);
+                panel.text_view.buffer().set_text(&format!("❌ Błąd: {}", e));
+                panel.use_button.set_sensitive(false);
+                Self::log_activity(&state_ref, ActivityKind::Error, &format!("{}: {}", API_NAMES[index], e));
+            }
+        }
+    }
+
+    /// Lista naruszeń style guide'u pod panelem wyniku
+    fn show_style_violations(panel: &PanelState, guide: &StyleGuide, text: &str) {
+        let violations = styleguide::check(guide, text);
+        if violations.is_empty() {
+            panel.warnings_label.set_visible(false);
+            return;
+        }
+
+        let lines: Vec<String> = violations
+            .iter()
+            .map(|v| format!("⚠️ {}", v.message))
+            .collect();
+        panel.warnings_label.set_text(&lines.join("\n"));
+        panel.warnings_label.set_visible(true);
+        info!("{}: {} style guide violations", panel.name_label.text(), violations.len());
+    }
+
+    /// Stopka z modelem i parametrami, którymi powstał wynik
+    fn show_generation(panel: &PanelState, generation: Generation) {
+        panel.model_label.set_text(&format!("🏷 {}", generation.label()));
+        panel.model_label.set_visible(true);
+        *panel.generation.borrow_mut() = Some(generation);
+    }
+
+    /// Indeks FOG-PL, średnia długość zdania i czas czytania pod panelem wyniku
+    fn show_readability(panel: &PanelState, text: &str) {
+        match readability::analyze(text) {
+            Some(r) => {
+                panel.readability_label.set_text(&readability::summary(&r));
+                panel.readability_label.set_visible(true);
             }
-            Err(e) => {
-                *panel.has_error.borrow_mut() = true;
-                
-                panel.status_icon.set_text("❌");
-                panel.name_label.set_text(&format!("{} (błąd)", API_NAMES[index]));
-                panel.text_view.buffer().set_text(&format!("❌ Błąd: {}", e));
-                panel.use_button.set_sensitive(false);
+            None => panel.readability_label.set_visible(false),
+        }
+    }
+
+    /// Czyści panel przed skróconą wersją za długiego wyniku
+    fn show_shortening(state: &Rc<RefCell<AppState>>, index: usize, chars: usize, max_chars: u32) {
+        let state_ref = state.borrow();
+        let panel = &state_ref.panels[index];
+        if !*panel.is_processing.borrow() {
+            return;
+        }
+
+        Self::reset_stream(panel);
+        panel.pause_button.set_sensitive(true);
+        panel
+            .text_view
+            .buffer()
+            .set_text(&format!("✂️ Wynik ma {} znaków (limit {}), skracanie…", chars, max_chars));
+    }
+
+    /// Zapytanie czeka w kolejce po odpowiedzi 429; pierwszy fragment wyniku zastąpi komunikat
+    fn show_rate_limited(state: &Rc<RefCell<AppState>>, index: usize, wait: std::time::Duration) {
+        let state_ref = state.borrow();
+        let panel = &state_ref.panels[index];
+        if !*panel.is_processing.borrow() {
+            return;
+        }
+
+        Self::reset_stream(panel);
+        panel.pause_button.set_sensitive(true);
+        Self::set_panel_status(panel, index, PanelStatus::RateLimited);
+        let wait = wait.as_secs_f64().ceil() as u64;
+        panel.text_view.buffer().set_text(&format!(
+            "⏳ Przekroczono limit zapytań dostawcy, ponowienie za {} s…",
+            wait
+        ));
+        Self::log_activity(
+            &state_ref,
+            ActivityKind::Info,
+            &format!("{}: limit zapytań, ponowienie za {} s", API_NAMES[index], wait),
+        );
+    }
+
+    /// Ostrzeżenie o chronionych fragmentach, które model usunął z wyniku
+    fn show_lost_spans(state: &Rc<RefCell<AppState>>, index: usize, missing: &[String]) {
+        let lines: Vec<String> = missing
+            .iter()
+            .map(|span| format!("⚠️ Model usunął chroniony fragment „{}”", span))
+            .collect();
+        Self::append_warnings(state, index, &lines);
+    }
+
+    /// Czy sprawdzać liczby, daty i linki: włączone i sesja nie jest tłumaczeniem ani własnym poleceniem
+    fn checks_facts(state: &Rc<RefCell<AppState>>, config: &Config) -> bool {
+        let state_ref = state.borrow();
+        config.settings.check_facts
+            && state_ref.session_custom.borrow().is_none()
+            && facts::applies_to(*state_ref.session_style.borrow())
+    }
+
+    /// Ostrzeżenia o zmienionych liczbach, datach i linkach; nowe są podświetlane w wyniku
+    fn show_fact_check(state: &Rc<RefCell<AppState>>, index: usize, check: &FactCheck) {
+        Self::append_warnings(state, index, &check.warnings());
+
+        let ranges: Vec<(usize, usize)> = check.added.iter().map(|f| (f.start, f.end)).collect();
+        let _timer = jank::measure("fact_highlight", ranges.len());
+        highlight_char_ranges(&state.borrow().panels[index].text_view.buffer(), &ranges);
+        info!(
+            "{}: {} facts missing, {} added",
+            API_NAMES[index],
+            check.missing.len(),
+            check.added.len()
+        );
+    }
+
+    /// Przelicza zgodność zdań wszystkich gotowych paneli (każdy nowy wynik zmienia oceny pozostałych)
+    fn refresh_agreement_heatmap(state: &Rc<RefCell<AppState>>) {
+        let state_ref = state.borrow();
+        let texts: Vec<Option<String>> = state_ref
+            .panels
+            .iter()
+            .map(|panel| (*panel.is_completed.borrow()).then(|| Self::panel_text(panel)))
+            .collect();
+        let chars = texts.iter().flatten().map(|t| t.chars().count()).sum();
+        let _timer = jank::measure("agreement_heatmap", chars);
+
+        for (index, panel) in state_ref.panels.iter().enumerate() {
+            let Some(text) = &texts[index] else {
+                continue;
+            };
+            let others: Vec<&str> = texts
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i != index)
+                .filter_map(|(_, t)| t.as_deref())
+                .collect();
+            apply_agreement_heatmap(&panel.text_view.buffer(), &agreement::sentence_scores(text, &others));
+        }
+    }
+
+    /// Dopisuje ostrzeżenia pod panelem do tych, które już tam są
+    fn append_warnings(state: &Rc<RefCell<AppState>>, index: usize, warnings: &[String]) {
+        let state_ref = state.borrow();
+        let panel = &state_ref.panels[index];
+
+        let mut lines = Vec::new();
+        if panel.warnings_label.is_visible() {
+            lines.push(panel.warnings_label.text().to_string());
+        }
+        lines.extend(warnings.iter().cloned());
+
+        panel.warnings_label.set_text(&lines.join("\n"));
+        panel.warnings_label.set_visible(true);
+    }
+
+    /// Zatrzymuje oczekiwanie panelu, który nie dostanie odpowiedzi w tej sesji
+    fn stop_panel(panel: &PanelState) {
+        panel.spinner.stop();
+        panel.spinner.set_visible(false);
+        panel.progress_bar.set_visible(false);
+        panel.cancel_button.set_sensitive(false);
+        panel.pause_button.set_sensitive(false);
+        *panel.is_processing.borrow_mut() = false;
+    }
+
+    /// Ikona i tekst stanu panelu; ten sam stan dostaje opis ramki dla czytników ekranu
+    fn set_panel_status(panel: &PanelState, index: usize, status: PanelStatus) {
+        let minimal = appearance::minimal_emoji();
+        panel.status_icon.set_text(status.icon());
+        panel.status_icon.set_visible(!minimal);
+        panel.status_image.set_icon_name(Some(status.symbolic_icon()));
+        panel.status_image.set_visible(minimal && status != PanelStatus::Idle);
+        panel.status_label.set_text(status.label());
+        panel.frame.update_property(&[gtk4::accessible::Property::Label(&status.accessible_label(API_NAMES[index]))]);
+        let busy = matches!(status, PanelStatus::Processing | PanelStatus::RateLimited);
+        panel.frame.update_state(&[gtk4::accessible::State::Busy(busy)]);
+    }
+
+    /// Panel dostawcy nieużywanego przez potok stylów
+    fn show_pipeline_skipped(state: &Rc<RefCell<AppState>>, index: usize, name: &str) {
+        let state_ref = state.borrow();
+        let panel = &state_ref.panels[index];
+        Self::stop_panel(panel);
+        state_ref.session.skip(index);
+
+        Self::set_panel_status(panel, index, PanelStatus::Skipped);
+        panel.name_label.set_text(API_NAMES[index]);
+        panel.text_view.buffer().set_text(&format!("⛓ Potok „{}” używa innego dostawcy.", name));
+    }
+
+    /// Panel dostawcy pominiętego przez otwarty wyłącznik
+    fn show_circuit_open(state: &Rc<RefCell<AppState>>, index: usize, remaining: std::time::Duration) {
+        let state_ref = state.borrow();
+        let panel = &state_ref.panels[index];
+
+        Self::stop_panel(panel);
+        state_ref.session.skip(index);
+
+        let minutes = remaining.as_secs().div_ceil(60);
+        Self::set_panel_status(panel, index, PanelStatus::Disabled);
+        panel.name_label.set_text(API_NAMES[index]);
+        panel.text_view.buffer().set_text(&format!(
+            "🔌 Tymczasowo wyłączony po serii błędów. Ponowna próba za {} min.\n\
+             Aby włączyć wcześniej, kliknij prawym przyciskiem w panel i wybierz „Włącz ponownie dostawcę”.",
+            minutes
+        ));
+        info!("Skipping {}: circuit breaker open", API_NAMES[index]);
+    }
+
+    /// Stan miesięcznego budżetu dostawcy
+    fn budget_status(state: &Rc<RefCell<AppState>>, config: &Config, index: usize) -> BudgetStatus {
+        let month = usage::month_key(now_secs());
+        let provider = panel_provider(index).name();
+        let spent = state.borrow().usage.borrow().spent(provider, &month);
+        usage::budget_status(spent, config.budget.limit(provider))
+    }
+
+    /// Tokeny odpowiedzi policzone lokalnie, gdy dostawca nie podał zużycia
+    fn estimate_usage(state: &Rc<RefCell<AppState>>, config: &Config, index: usize, response: &str) -> TokenUsage {
+        let provider = panel_provider(index);
+        TokenUsage {
+            prompt_tokens: state.borrow().prompt_tokens.borrow()[index] as u64,
+            completion_tokens: tokens::count(provider, provider.model(config), response).tokens as u64,
+        }
+    }
+
+    /// Dolicza koszt odpowiedzi i ostrzega przy 80% i 100% budżetu
+    fn record_usage(state: &Rc<RefCell<AppState>>, config: &Config, index: usize, tokens: TokenUsage) {
+        let provider = panel_provider(index);
+        let Some(price) = config.pricing.get(provider.model(config)) else {
+            return;
+        };
+        let cost = matrix::cost_usd(price, tokens.prompt_tokens, tokens.completion_tokens);
+
+        let month = usage::month_key(now_secs());
+        // Budżet i zużycie są liczone dla dostawcy, wspólnie dla jego paneli
+        let limit = config.budget.limit(provider.name());
+        let state_ref = state.borrow();
+        let mut tracker = state_ref.usage.borrow_mut();
+        let before = usage::budget_status(tracker.spent(provider.name(), &month), limit);
+        if let Err(e) = tracker.record(provider.name(), cost, tokens, &month) {
+            error!("Failed to save usage: {}", e);
+        }
+        let spent = tracker.spent(provider.name(), &month);
+        let after = usage::budget_status(spent, limit);
+        drop(tracker);
+        drop(state_ref);
+        Self::update_cost_badge(state);
+
+        let (Some(limit), true) = (limit, before != after) else {
+            return;
+        };
+
+        let message = match after {
+            BudgetStatus::Warning => format!(
+                "⚠️ {}: wykorzystano {:.0}% miesięcznego budżetu ({:.2} z {:.2} USD)",
+                API_NAMES[index],
+                spent / limit * 100.0,
+                spent,
+                limit
+            ),
+            BudgetStatus::Exceeded if config.budget.disable_at_limit => format!(
+                "💰 {}: budżet {:.2} USD wyczerpany, dostawca wyłączony do końca miesiąca",
+                API_NAMES[index], limit
+            ),
+            BudgetStatus::Exceeded => format!("💰 {}: przekroczono budżet {:.2} USD", API_NAMES[index], limit),
+            _ => return,
+        };
+        warn!("{} budget: {:.4} of {:.2} USD spent", API_NAMES[index], spent, limit);
+        state.borrow().status_label.set_text(&message);
+    }
+
+    /// Panel dostawcy pominiętego po wyczerpaniu budżetu
+    fn show_budget_exhausted(state: &Rc<RefCell<AppState>>, index: usize) {
+        let state_ref = state.borrow();
+        let panel = &state_ref.panels[index];
+
+        Self::stop_panel(panel);
+        state_ref.session.skip(index);
+
+        Self::set_panel_status(panel, index, PanelStatus::OverBudget);
+        panel.name_label.set_text(API_NAMES[index]);
+        panel.text_view.buffer().set_text(
+            "💰 Miesięczny budżet tego dostawcy został wyczerpany.\n\
+             Zwiększ budżet w ustawieniach albo poczekaj do następnego miesiąca.",
+        );
+        info!("Skipping {}: monthly budget exhausted", API_NAMES[index]);
+    }
+
+    /// Panel pominięty, bo prompt z odpowiedzią nie zmieści się w oknie kontekstu modelu
+    fn show_too_long(state: &Rc<RefCell<AppState>>, index: usize, model: &str, prompt: TokenCount, limit: usize) {
+        let state_ref = state.borrow();
+        let panel = &state_ref.panels[index];
+
+        Self::stop_panel(panel);
+        state_ref.session.skip(index);
+
+        Self::set_panel_status(panel, index, PanelStatus::TooLong);
+        panel.name_label.set_text(API_NAMES[index]);
+        panel.text_view.buffer().set_text(&format!(
+            "📏 Tekst jest za długi dla modelu {}: prompt ma {}, a okno kontekstu {} {} \
+             (razem z miejscem na odpowiedź).\n\
+             Skróć tekst albo wybierz model z większym kontekstem.",
+            model,
+            prompt.label(),
+            limit,
+            tokens::tokens_word(limit)
+        ));
+        warn!(
+            "Skipping {}: {} prompt tokens exceed {} context of {}",
+            API_NAMES[index], prompt.tokens, limit, model
+        );
+    }
+
+    /// Okno kosztów: wydatki w tym miesiącu i pozostały budżet
+    fn show_usage(state: &Rc<RefCell<AppState>>) {
+        let state_ref = state.borrow();
+        Self::show_text_dialog(&state_ref.window, "Koszty dostawców", &Self::usage_summary(&state_ref));
+    }
+
+    fn usage_summary(state_ref: &AppState) -> String {
+        let month = usage::month_key(now_secs());
+        let providers: Vec<&str> = registry::panel_providers(panel_specs()).iter().map(|p| p.name()).collect();
+        usage::summary(&state_ref.usage.borrow(), &state_ref.config.borrow().budget, &providers, &month)
+    }
+
+    /// Odświeża znaczek kosztów miesiąca na pasku informacji
+    fn update_cost_badge(state: &Rc<RefCell<AppState>>) {
+        let state_ref = state.borrow();
+        let total = state_ref.usage.borrow().total(&usage::month_key(now_secs()));
+        state_ref.cost_label.set_text(&format!("💰 {:.2} USD", total));
+        state_ref.cost_label.set_tooltip_text(Some(&Self::usage_summary(&state_ref)));
+    }
+
+    /// Zlicza wynik dostawcy do wyłącznika (bez dostawców bez klucza)
+    fn record_circuit_result(state: &Rc<RefCell<AppState>>, config: &Config, index: usize, ok: bool) {
+        if !config.circuit_breaker.enabled || panel_provider(index).api_key(config).trim().is_empty() {
+            return;
+        }
+
+        let state_ref = state.borrow();
+        let mut breaker = state_ref.breaker.borrow_mut();
+        if ok {
+            breaker.record_success(index);
+        } else if breaker.record_failure(index, Instant::now()) {
+            warn!(
+                "{} disabled for {} min after {} consecutive failures",
+                API_NAMES[index], config.circuit_breaker.cooldown_minutes, config.circuit_breaker.failure_threshold
+            );
+        }
+    }
+
+    fn reset_circuit_breaker(state: &Rc<RefCell<AppState>>, index: usize) {
+        let state_ref = state.borrow();
+        let was_open = state_ref.breaker.borrow().is_open(index, Instant::now());
+        state_ref.breaker.borrow_mut().reset(index);
+        info!("Circuit breaker reset for {}", API_NAMES[index]);
+
+        let panel = &state_ref.panels[index];
+        if was_open && !*panel.is_processing.borrow() {
+            Self::set_panel_status(panel, index, PanelStatus::Idle);
+            panel.name_label.set_text(API_NAMES[index]);
+            panel.text_view.buffer().set_text("Dostawca włączony ponownie - zostanie użyty w następnej sesji.");
+        }
+    }
+
+    /// Postęp sesji w podpowiedzi zasobnika, gdy okno jest schowane
+    fn update_tray_progress(state: &Rc<RefCell<AppState>>) {
+        let state_ref = state.borrow();
+        let Some(tray) = &state_ref.tray else {
+            return;
+        };
+        if state_ref.window.is_visible() {
+            return;
+        }
+
+        let completed = state_ref.session.completed();
+        let elapsed = state_ref.panels[0]
+            .start_time
+            .borrow()
+            .map(|t| t.elapsed().as_secs())
+            .unwrap_or(0);
+        tray.borrow().set_tooltip(&format_progress(completed, API_NAMES.len() as u32, elapsed));
+    }
+
+    /// Okienko wyboru wyniku przy kursorze
+    fn show_result_popup(state: &Rc<RefCell<AppState>>) {
+        let state_ref = state.borrow();
+        // Dostawca preferowany w aplikacji źródłowej, potem najszybsi
+        let results: Vec<(usize, &str, String)> = state_ref
+            .session
+            .ranking(*state_ref.preferred_panel.borrow())
+            .into_iter()
+            .filter(|&i| *state_ref.panels[i].is_completed.borrow())
+            .map(|i| (i, API_NAMES[i], Self::panel_text(&state_ref.panels[i])))
+            .collect();
+
+        let popup = ResultPopup::new(&state_ref.window, &results);
+
+        let state_clone = state.clone();
+        popup.connect_pick(move |index| {
+            let panel = state_clone.borrow().panels[index].clone();
+            Self::use_api_result(&state_clone, index, &panel);
+        });
+
+        let window_weak = state_ref.window.downgrade();
+        popup.connect_show_window(move || {
+            if let Some(win) = window_weak.upgrade() {
+                win.set_visible(true);
+                win.present();
             }
+        });
+
+        popup.present_near_cursor();
+        info!("Result popup shown with {} results", results.len());
+    }
+
+    /// Dźwięk zdarzenia sesji, jeśli włączony i okno jest schowane
+    fn play_sound(state_ref: &AppState, event: SoundEvent) {
+        let settings = state_ref.config.borrow().settings.clone();
+        if !sound::should_play(&settings, event, state_ref.window.is_visible()) {
+            return;
+        }
+        if let Err(e) = sound::play(event) {
+            warn!("{}, using system alert", e);
+            state_ref.window.display().beep();
         }
     }
 
     fn finalize_processing(state: &Rc<RefCell<AppState>>) {
         let state_ref = state.borrow();
-        let completed = *state_ref.completed_count.borrow();
+        let completed = state_ref.session.completed();
+        if completed > 0 {
+            Self::play_sound(&state_ref, SoundEvent::LastResult);
+        }
+
+        // Próg auto-prezentacji nieosiągnięty (część API zawiodła) - pokaż to, co jest.
+        // W trybie okienka przy kursorze duże okno pojawia się tylko, gdy nie ma wyników.
+        let settings = state_ref.config.borrow().settings.clone();
+        let show_popup = settings.results_popup && completed > 0 && !state_ref.window.is_visible();
+        let present = if settings.results_popup {
+            completed == 0
+        } else {
+            settings.auto_present_results > 0 && !settings.should_auto_present(completed)
+        };
+        if present {
+            state_ref.window.set_visible(true);
+            state_ref.window.present();
+        }
         
+        if let Some(tray) = &state_ref.tray {
+            tray.borrow().set_tooltip(&format!("Gotowe: {}/{} wyników", completed, API_NAMES.len()));
+        }
+
         if completed > 0 {
             state_ref.status_label.set_text(&format!("✅ Gotowe! Otrzymano {} wyników", completed));
-            state_ref.hint_label.set_text("Wybierz najlepszy wynik i kliknij 'Użyj'");
+            state_ref.hint_label.set_text(&format!(
+                "Wybierz najlepszy wynik i kliknij 'Użyj'{}",
+                Self::previous_choice_hint(&state_ref)
+            ));
         } else {
             state_ref.status_label.set_text("❌ Wszystkie API zwróciły błędy");
             state_ref.hint_label.set_text("Sprawdź klucze API w ustawieniach");
         }
+        let summary = format!("Koniec sesji: {}/{} wyników", completed, API_NAMES.len());
+        let kind = if completed > 0 { ActivityKind::Info } else { ActivityKind::Error };
+        Self::log_activity(&state_ref, kind, &summary);
+        drop(state_ref);
+
+        if show_popup {
+            Self::show_result_popup(state);
+        }
+        Self::update_power_state(state);
     }
 
-    fn setup_tray(window: adw::ApplicationWindow) {
-        let window_weak = window.downgrade();
-        
-        if let Ok(tray) = TrayManager::new() {
-            let tray = Rc::new(RefCell::new(tray));
-            let tray_clone = tray.clone();
-            
-            glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
-                if let Some(event) = tray_clone.borrow_mut().poll_event() {
-                    match event {
-                        crate::tray::TrayEvent::Show => {
-                            if let Some(win) = window_weak.upgrade() {
-                                win.set_visible(true);
-                                win.present();
-                                info!("Window shown from tray");
-                            }
-                        }
-                        crate::tray::TrayEvent::Quit => {
-                            if let Some(win) = window_weak.upgrade() {
-                                win.application().map(|app| app.quit());
-                            }
-                        }
-                    }
+    fn setup_digest(state: Rc<RefCell<AppState>>) {
+        if !state.borrow().config.borrow().settings.learning_digest {
+            return;
+        }
+
+        let last = digest::load_last_digest(digest::default_state_path());
+        if digest::is_digest_due(last, now_secs()) {
+            glib::spawn_future_local(async move {
+                let _ = Self::run_digest(&state, false).await;
+            });
+        }
+    }
+
+    /// Zwraca opis wyniku albo błędu (do okna zadań)
+    async fn run_digest(state: &Rc<RefCell<AppState>>, manual: bool) -> Result<String, String> {
+        let config = state.borrow().config.borrow().clone();
+        let since = now_secs().saturating_sub(digest::DIGEST_PERIOD_SECS);
+
+        let Some(history) = Self::open_history(state, manual).await else {
+            warn!("History locked, digest skipped");
+            return Err("Historia jest zablokowana".to_string());
+        };
+        let entries = match history.since(since) {
+            Ok(entries) => entries,
+            Err(e) => {
+                error!("Failed to read history: {}", e);
+                return Err(format!("Nie można odczytać historii: {}", e));
+            }
+        };
+
+        info!("Generating learning digest from {} entries", entries.len());
+
+        let (tx, rx) = async_channel::bounded(1);
+        crate::TOKIO_RUNTIME.spawn(async move {
+            let result = digest::generate_digest(&config, &entries).await;
+            let _ = tx.send(result).await;
+        });
+
+        let window = state.borrow().window.clone();
+        match rx.recv().await {
+            Ok(Ok(report)) => {
+                if let Err(e) = digest::save_last_digest(digest::default_state_path(), now_secs()) {
+                    error!("Failed to save digest timestamp: {}", e);
                 }
-                glib::ControlFlow::Continue
+                Self::show_text_dialog(&window, "Raport tygodniowy", &report);
+                Ok("Raport wygenerowany".to_string())
+            }
+            Ok(Err(e)) => {
+                error!("Digest generation failed: {}", e);
+                if manual {
+                    Self::show_text_dialog(
+                        &window,
+                        "Raport tygodniowy",
+                        &format!("❌ Nie udało się wygenerować raportu: {}", e),
+                    );
+                }
+                Err(e.to_string())
+            }
+            Err(_) => Err("Przerwano".to_string()),
+        }
+    }
+
+    fn local_time() -> Option<LocalTime> {
+        let now = glib::DateTime::now_local().ok()?;
+        Some(LocalTime {
+            date: now.format("%Y-%m-%d").ok()?.to_string(),
+            weekday: now.day_of_week() as u32,
+            minutes: (now.hour() * 60 + now.minute()) as u32,
+        })
+    }
+
+    /// Co minutę uruchamia zaległe zadania z konfiguracji, gdy żaden panel nie pracuje.
+    /// Zegar działa zawsze, więc zadania dodane w ustawieniach startują bez restartu.
+    fn setup_scheduler(state: Rc<RefCell<AppState>>) {
+        let running = Rc::new(std::cell::Cell::new(false));
+        let check = move || {
+            if running.get() {
+                return;
+            }
+            let state_ref = state.borrow();
+            if state_ref.panels.iter().any(|p| *p.is_processing.borrow()) {
+                return;
+            }
+            let Some(now) = Self::local_time() else {
+                return;
+            };
+
+            let jobs = state_ref.config.borrow().jobs.clone();
+            if jobs.is_empty() {
+                return;
+            }
+            let job_state = JobState::load(JobState::default_path());
+            let due: Vec<_> = jobs
+                .into_iter()
+                .filter(|job| scheduler::is_due(job, job_state.get(&job.name), &now))
+                .collect();
+            if due.is_empty() {
+                return;
+            }
+
+            running.set(true);
+            let state = state.clone();
+            let running = running.clone();
+            glib::spawn_future_local(async move {
+                for job in due {
+                    Self::run_job(&state, &job, &now).await;
+                }
+                running.set(false);
             });
+        };
+
+        check();
+        glib::timeout_add_seconds_local(60, move || {
+            check();
+            glib::ControlFlow::Continue
+        });
+    }
+
+    async fn run_job(state: &Rc<RefCell<AppState>>, job: &Job, now: &LocalTime) {
+        info!("Running scheduled job '{}'", job.name);
+        let config = state.borrow().config.borrow().clone();
+
+        let result = match scheduler::resolve(job, &config) {
+            Ok(Task::Digest) => Self::run_digest(state, false).await,
+            Ok(Task::Folder {
+                input,
+                output,
+                provider,
+                style,
+            }) => {
+                let (tx, rx) = async_channel::bounded(1);
+                crate::TOKIO_RUNTIME.spawn(async move {
+                    let result = scheduler::run_folder(&config, &input, &output, provider, style).await;
+                    let _ = tx.send(result).await;
+                });
+                rx.recv().await.unwrap_or_else(|_| Err("Przerwano".to_string()))
+            }
+            Err(e) => Err(e),
+        };
+
+        match &result {
+            Ok(message) => info!("Scheduled job '{}' finished: {}", job.name, message),
+            Err(e) => warn!("Scheduled job '{}' failed: {}", job.name, e),
+        }
+        let (ok, message) = match result {
+            Ok(message) => (true, message),
+            Err(e) => (false, e),
+        };
+        let ran_at = glib::DateTime::now_local()
+            .ok()
+            .and_then(|t| t.format("%Y-%m-%d %H:%M").ok())
+            .map(|t| t.to_string())
+            .unwrap_or_else(|| now.date.clone());
+
+        state.borrow().status_label.set_text(&format!(
+            "🗓 {} {}: {}",
+            if ok { "✅" } else { "❌" },
+            job.name,
+            message
+        ));
+        let status = JobStatus {
+            date: now.date.clone(),
+            ran_at,
+            ok,
+            message,
+        };
+        if let Err(e) = JobState::load(JobState::default_path()).record(&job.name, status) {
+            error!("Failed to save job state: {}", e);
+        }
+    }
+
+    fn show_jobs(state: &Rc<RefCell<AppState>>) {
+        let state_ref = state.borrow();
+        let text = scheduler::summary(
+            &state_ref.config.borrow().jobs,
+            &JobState::load(JobState::default_path()),
+        );
+        Self::show_text_dialog(&state_ref.window, "Zadania", &text);
+    }
+
+    /// Przy zamykaniu przerywa trwające zapytania do API
+    fn setup_shutdown(state: &Rc<RefCell<AppState>>) {
+        let session = state.borrow().session.clone();
+        SHUTDOWN.on_shutdown("requests", move || session.cancel_all());
+    }
+
+    /// Chowa okno do zasobnika; bez działającego zasobnika tylko je minimalizuje,
+    /// żeby dało się do niego wrócić z paska zadań
+    fn hide_to_tray(state_ref: &AppState, reason: &str) {
+        state_ref.pending_source.borrow_mut().take();
+        if state_ref.tray.is_some() {
+            state_ref.window.set_visible(false);
+            info!("Window hidden to tray ({})", reason);
+        } else {
+            state_ref.window.minimize();
+            info!("Window minimized, no system tray ({})", reason);
+        }
+    }
+
+    fn setup_tray(state: Rc<RefCell<AppState>>) {
+        match TrayManager::new() {
+            Ok(tray) => {
+                tray.set_quick_mode(state.borrow().config.borrow().settings.quick_mode);
+                let tray = Rc::new(RefCell::new(tray));
+                state.borrow_mut().tray = Some(tray.clone());
+                Self::start_tray_polling(state, tray);
+            }
+            Err(e) => warn!("System tray unavailable: {}", e),
+        }
+    }
+
+    /// Odpytuje zasobnik co skonfigurowany interwał. W trybie oszczędzania
+    /// timer się kończy, a kolejne zdarzenie zasobnika wznawia odpytywanie.
+    fn start_tray_polling(state: Rc<RefCell<AppState>>, tray: Rc<RefCell<TrayManager>>) {
+        let interval = state.borrow().config.borrow().polling.tray_interval();
+
+        glib::timeout_add_local(interval, move || {
+            if let Some(event) = tray.borrow_mut().poll_event() {
+                Self::handle_tray_event(&state, event);
+            }
+
+            if state.borrow().power.is_suspended() {
+                info!("Power save: tray polling suspended");
+                let state = state.clone();
+                let tray = tray.clone();
+                glib::spawn_future_local(async move {
+                    let events = tray.borrow().events();
+                    if let Ok(event) = events.recv().await {
+                        Self::handle_tray_event(&state, event);
+                        Self::start_tray_polling(state, tray);
+                    }
+                });
+                return glib::ControlFlow::Break;
+            }
+            glib::ControlFlow::Continue
+        });
+    }
+
+    fn handle_tray_event(state: &Rc<RefCell<AppState>>, event: crate::tray::TrayEvent) {
+        let window = state.borrow().window.clone();
+        match event {
+            crate::tray::TrayEvent::Show => {
+                Self::set_window_mode(state, WindowMode::Full);
+                window.set_visible(true);
+                window.present();
+                info!("Window shown from tray");
+            }
+            crate::tray::TrayEvent::Digest => {
+                let state = state.clone();
+                glib::spawn_future_local(async move {
+                    let _ = Self::run_digest(&state, true).await;
+                });
+            }
+            crate::tray::TrayEvent::RepeatLast => {
+                let state = state.clone();
+                glib::spawn_future_local(async move {
+                    Self::repeat_last_text(&state, None).await;
+                });
+            }
+            crate::tray::TrayEvent::History => {
+                let state = state.clone();
+                glib::spawn_future_local(async move { Self::show_history_browser(&state).await });
+            }
+            crate::tray::TrayEvent::Snippet(snippet) => Self::paste_snippet(state, snippet),
+            crate::tray::TrayEvent::RecentOutput(index) => Self::copy_recent_output(state, index),
+            crate::tray::TrayEvent::ToggleQuickMode => Self::toggle_quick_mode(state),
+            crate::tray::TrayEvent::Quit => {
+                // `quit` wywołuje sygnał shutdown aplikacji, a ten koordynator zamykania
+                if let Some(app) = window.application() {
+                    app.quit();
+                }
+            }
+        }
+    }
+
+    /// Przełącza tryb szybki z zasobnika i zapisuje go w konfiguracji
+    fn toggle_quick_mode(state: &Rc<RefCell<AppState>>) {
+        let mut config = state.borrow().config.borrow().clone();
+        config.settings.quick_mode = !config.settings.quick_mode;
+        let enabled = config.settings.quick_mode;
+
+        let state = state.clone();
+        glib::spawn_future_local(async move {
+            match Self::save_settings(state.clone(), config.clone()).await {
+                Ok(()) => {
+                    info!("Quick mode {}", if enabled { "enabled" } else { "disabled" });
+                    state.borrow().status_label.set_text(if enabled {
+                        "⚡ Tryb szybki włączony"
+                    } else {
+                        "Tryb szybki wyłączony"
+                    });
+                }
+                Err(e) => {
+                    error!("Failed to toggle quick mode: {}", e);
+                    Self::show_config_save_error(&state, config, &e);
+                }
+            }
+        });
+    }
+
+    /// Przelicza stan oszczędzania energii po zmianie widoczności okna lub sesji
+    fn update_power_state(state: &Rc<RefCell<AppState>>) {
+        let state_ref = state.borrow();
+        let suspend = power::should_suspend(
+            state_ref.config.borrow().polling.power_save,
+            state_ref.window.is_visible(),
+            state_ref.panels.iter().any(|p| *p.is_processing.borrow()),
+        );
+        if state_ref.power.set_suspended(suspend) {
+            info!("Power save: polling {}", if suspend { "suspended" } else { "resumed" });
+        }
+    }
+
+    fn setup_metrics(state: &Rc<RefCell<AppState>>) {
+        let state_ref = state.borrow();
+        let settings = state_ref.config.borrow().metrics.clone();
+        if !settings.enabled {
+            return;
         }
+
+        let metrics = state_ref.metrics.clone();
+        crate::TOKIO_RUNTIME.spawn(async move {
+            if let Err(e) = crate::metrics::serve(metrics, settings.port).await {
+                error!("Metrics endpoint failed on port {}: {}", settings.port, e);
+            }
+        });
+    }
+
+    fn setup_power_save(state: Rc<RefCell<AppState>>) {
+        let window = state.borrow().window.clone();
+        let state_weak = Rc::downgrade(&state);
+        window.connect_visible_notify(move |_| {
+            if let Some(state) = state_weak.upgrade() {
+                Self::update_power_state(&state);
+            }
+        });
     }
 }
@@ -0,0 +1,133 @@
+//! Domyślny styl skrótu zależny od aplikacji źródłowej
+//!
+//! Reguły `[[app_styles]]` przypisują klasie i tytułowi okna aktywnego w chwili
+//! naciśnięcia skrótu styl, np. Thunderbird → professional. Styl "skip" każe
+//! zignorować skrót w tym oknie (np. przeglądarka na deepl.com). Pierwsza
+//! pasująca reguła wygrywa; bez pasującej obowiązuje styl "normal". Reguły z
+//! nieznanym stylem odrzuca już wczytywanie konfiguracji.
+
+use crate::config::{parse_choice, AppStyle};
+use crate::platform::SourceApp;
+use crate::prompts::CorrectionStyle;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+/// Co zrobić ze skrótem naciśniętym w danym oknie; w konfiguracji identyfikator stylu albo "skip"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum AppChoice {
+    Style(CorrectionStyle),
+    Skip,
+}
+
+/// Wszystkie style i "skip", w kolejności z komunikatu błędu
+static ALL_CHOICES: Lazy<Vec<AppChoice>> = Lazy::new(|| {
+    let mut choices: Vec<AppChoice> = CorrectionStyle::all().iter().copied().map(AppChoice::Style).collect();
+    choices.push(AppChoice::Skip);
+    choices
+});
+
+impl AppChoice {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Style(style) => style.as_str(),
+            Self::Skip => "skip",
+        }
+    }
+}
+
+impl TryFrom<String> for AppChoice {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        parse_choice("app style", &value, &ALL_CHOICES, Self::as_str)
+    }
+}
+
+impl From<AppChoice> for String {
+    fn from(value: AppChoice) -> Self {
+        value.as_str().to_string()
+    }
+}
+
+/// Reguła pasuje, gdy okno zawiera podane fragmenty klasy i tytułu (bez względu
+/// na wielkość liter); reguła bez obu fragmentów nie pasuje do niczego
+pub fn matches(rule: &AppStyle, window: &SourceApp) -> bool {
+    let contains = |pattern: &str, value: &str| value.to_lowercase().contains(&pattern.to_lowercase());
+    let (class, title) = (rule.class.trim(), rule.title.trim());
+    (!class.is_empty() || !title.is_empty()) && contains(class, &window.class) && contains(title, &window.title)
+}
+
+/// Np. "thunderbird" albo "firefox / DeepL"
+pub fn label(rule: &AppStyle) -> String {
+    match (rule.class.trim(), rule.title.trim()) {
+        (class, "") => class.to_string(),
+        ("", title) => title.to_string(),
+        (class, title) => format!("{} / {}", class, title),
+    }
+}
+
+/// Pierwsza reguła pasująca do okna
+pub fn choice_for<'a>(rules: &'a [AppStyle], window: &SourceApp) -> Option<(&'a AppStyle, AppChoice)> {
+    rules.iter().find(|rule| matches(rule, window)).map(|rule| (rule, rule.style))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(class: &str, title: &str, style: &str) -> AppStyle {
+        AppStyle {
+            class: class.to_string(),
+            title: title.to_string(),
+            style: AppChoice::try_from(style.to_string()).unwrap(),
+        }
+    }
+
+    fn window(class: &str, title: &str) -> SourceApp {
+        SourceApp {
+            class: class.to_string(),
+            title: title.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_choice_for_first_matching_rule() {
+        let rules = vec![
+            rule("firefox", "DeepL", "skip"),
+            rule("Thunderbird", "", "professional"),
+            rule("", "", "summary"),
+            rule("firefox", "", "normal"),
+        ];
+
+        let skip = choice_for(&rules, &window("firefox", "DeepL Translate – Mozilla Firefox"));
+        assert_eq!(skip.map(|(_, c)| c), Some(AppChoice::Skip));
+        let mail = choice_for(&rules, &window("thunderbird", "Nowa wiadomość"));
+        assert_eq!(
+            mail.map(|(r, c)| (label(r), c)),
+            Some(("Thunderbird".to_string(), AppChoice::Style(CorrectionStyle::Professional)))
+        );
+        let browser = choice_for(&rules, &window("firefox", "Wikipedia"));
+        assert_eq!(browser.map(|(_, c)| c), Some(AppChoice::Style(CorrectionStyle::Normal)));
+
+        // Reguła bez wzorców nie pasuje
+        assert_eq!(choice_for(&rules, &window("kitty", "")), None);
+    }
+
+    #[test]
+    fn test_parse_app_choice_and_label() {
+        assert_eq!(AppChoice::try_from(" SKIP ".to_string()), Ok(AppChoice::Skip));
+        assert_eq!(
+            AppChoice::try_from("Translate_EN".to_string()),
+            Ok(AppChoice::Style(CorrectionStyle::TranslateEn))
+        );
+        assert_eq!(
+            AppChoice::try_from("formal".to_string()),
+            Err("invalid app style \"formal\", expected one of: normal, professional, translate_en, \
+                 translate_pl, change_meaning, summary, prompt, skip"
+                .to_string())
+        );
+        assert_eq!(String::from(AppChoice::Skip), "skip");
+        assert_eq!(label(&rule("firefox", "DeepL", "skip")), "firefox / DeepL");
+    }
+}
@@ -0,0 +1,255 @@
+//! Dziennik audytu: kto, kiedy i którego dostawcę wywołał
+//!
+//! Dla firm z zasadami korzystania z AI. Każde wywołanie dostawcy to jedna
+//! linia JSON w `audit.jsonl` obok konfiguracji. Domyślnie zapisywane są tylko
+//! metadane (użytkownik, czas, dostawca, model z parametrami, styl, długość tekstu); treść
+//! tekstu i wyniku trafia do dziennika dopiero po włączeniu `IncludeContent`.
+//! Dziennik można wyeksportować jako CSV.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use crate::api::Generation;
+use crate::config::{AuditSettings, Config};
+use crate::history::now_secs;
+use crate::usage::utc_date;
+
+const CSV_HEADER: &str = "timestamp,user,provider,model,parameters,style,chars,success,original,result";
+
+/// Jedno wywołanie dostawcy
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AuditEntry {
+    /// Sekundy od epoki UNIX
+    pub timestamp: u64,
+    pub user: String,
+    pub provider: String,
+    pub model: String,
+    /// Temperatura, budżet myślenia, nakład rozumowania (wpisy sprzed tego pola go nie mają)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generation: Option<Generation>,
+    pub style: String,
+    /// Długość wysłanego tekstu w znakach
+    pub chars: usize,
+    pub success: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<String>,
+}
+
+impl AuditEntry {
+    /// Wpis bieżącego użytkownika; `result` to `None` po błędzie dostawcy.
+    /// Treść trafia do wpisu tylko przy `IncludeContent`.
+    pub fn new(
+        settings: &AuditSettings,
+        provider: &str,
+        generation: &Generation,
+        style: &str,
+        original: &str,
+        result: Option<&str>,
+    ) -> Self {
+        Self {
+            timestamp: now_secs(),
+            user: current_user(),
+            provider: provider.to_string(),
+            model: generation.model.clone(),
+            generation: Some(generation.clone()),
+            style: style.to_string(),
+            chars: original.chars().count(),
+            success: result.is_some(),
+            original: settings.include_content.then(|| original.to_string()),
+            result: result.filter(|_| settings.include_content).map(str::to_string),
+        }
+    }
+}
+
+/// Nazwa zalogowanego użytkownika systemu
+pub fn current_user() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+/// Czas w formacie ISO 8601 (UTC), np. "2026-10-18T09:05:00Z"
+pub fn format_timestamp(secs: u64) -> String {
+    let (year, month, day) = utc_date(secs);
+    let time = secs % 86_400;
+    format!(
+        "{}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+/// Pole CSV w cudzysłowie, gdy zawiera przecinek, cudzysłów albo nową linię
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Wpisy jako CSV z nagłówkiem
+pub fn to_csv(entries: &[AuditEntry]) -> String {
+    let mut csv = String::from(CSV_HEADER);
+    csv.push('\n');
+    for entry in entries {
+        let fields = [
+            format_timestamp(entry.timestamp),
+            entry.user.clone(),
+            entry.provider.clone(),
+            entry.model.clone(),
+            entry.generation.as_ref().map(|g| g.parameters().join(" · ")).unwrap_or_default(),
+            entry.style.clone(),
+            entry.chars.to_string(),
+            entry.success.to_string(),
+            entry.original.clone().unwrap_or_default(),
+            entry.result.clone().unwrap_or_default(),
+        ];
+        let line: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        csv.push_str(&line.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl AuditLog {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Plik dziennika obok `config.toml`
+    pub fn default_path() -> PathBuf {
+        Config::get_config_path().with_file_name("audit.jsonl")
+    }
+
+    pub fn append(&self, entry: &AuditEntry) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+        Ok(())
+    }
+
+    /// Wczytuje wszystkie wpisy; uszkodzone linie są pomijane
+    pub fn load(&self) -> Result<Vec<AuditEntry>, Box<dyn std::error::Error>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let file = fs::File::open(&self.path)?;
+        let entries = BufReader::new(file)
+            .lines()
+            .map_while(Result::ok)
+            .filter_map(|line| serde_json::from_str(&line).ok())
+            .collect();
+        Ok(entries)
+    }
+
+    /// Zapisuje cały dziennik jako CSV; zwraca liczbę wpisów
+    pub fn export_csv<P: AsRef<Path>>(&self, path: P) -> Result<usize, Box<dyn std::error::Error>> {
+        let entries = self.load()?;
+        fs::write(path, to_csv(&entries))?;
+        Ok(entries.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ReasoningEffort;
+    use tempfile::TempDir;
+
+    fn entry(provider: &str, success: bool) -> AuditEntry {
+        AuditEntry {
+            timestamp: 1_792_314_300,
+            user: "jan".to_string(),
+            provider: provider.to_string(),
+            model: "gpt-4o-mini".to_string(),
+            generation: None,
+            style: "normal".to_string(),
+            chars: 42,
+            success,
+            original: None,
+            result: None,
+        }
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_timestamp(1_792_314_300), "2026-10-18T09:05:00Z");
+    }
+
+    #[test]
+    fn test_append_and_load_without_content() {
+        let dir = TempDir::new().unwrap();
+        let log = AuditLog::new(dir.path().join("audit.jsonl"));
+        assert!(log.load().unwrap().is_empty());
+
+        log.append(&entry("OpenAI", true)).unwrap();
+        log.append(&entry("Gemini", false)).unwrap();
+
+        let content = fs::read_to_string(dir.path().join("audit.jsonl")).unwrap();
+        assert!(!content.contains("original"));
+        assert_eq!(log.load().unwrap(), vec![entry("OpenAI", true), entry("Gemini", false)]);
+    }
+
+    fn generation() -> Generation {
+        Generation {
+            model: "gpt-4o".to_string(),
+            temperature: Some(0.7),
+            thinking_budget: None,
+            reasoning_effort: Some(ReasoningEffort::Low),
+        }
+    }
+
+    #[test]
+    fn test_new_stores_content_only_when_enabled() {
+        let mut settings = AuditSettings::default();
+        let entry = AuditEntry::new(&settings, "OpenAI", &generation(), "normal", "Zażółć", Some("Zażółć."));
+        assert_eq!(entry.model, "gpt-4o");
+        assert_eq!(entry.generation, Some(generation()));
+        assert_eq!(entry.chars, 6);
+        assert!(entry.success);
+        assert_eq!((entry.original, entry.result), (None, None));
+
+        settings.include_content = true;
+        let entry = AuditEntry::new(&settings, "OpenAI", &generation(), "normal", "Zażółć", None);
+        assert!(!entry.success);
+        assert_eq!(entry.original.as_deref(), Some("Zażółć"));
+        assert_eq!(entry.result, None);
+    }
+
+    #[test]
+    fn test_csv_export_escapes_content() {
+        let with_content = AuditEntry {
+            generation: Some(generation()),
+            original: Some("Ala, \"kot\"\ni pies".to_string()),
+            result: Some("Ala ma kota.".to_string()),
+            ..entry("Anthropic", true)
+        };
+        let csv = to_csv(&[entry("OpenAI", true), with_content]);
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(lines[1], "2026-10-18T09:05:00Z,jan,OpenAI,gpt-4o-mini,,normal,42,true,,");
+        assert!(csv.contains("gpt-4o-mini,temp. 0.7 · rozumowanie low,normal,42,true,\"Ala, \"\"kot\"\"\ni pies\",Ala ma kota.\n"));
+    }
+}
@@ -0,0 +1,119 @@
+//! Wyłącznik awaryjny dostawców
+//!
+//! Po `threshold` kolejnych błędach dostawca jest pomijany przez `cooldown`,
+//! żeby każda sesja nie czekała na API, które i tak nie odpowiada.
+
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Default)]
+struct ProviderCircuit {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Duration,
+    circuits: Vec<ProviderCircuit>,
+}
+
+impl CircuitBreaker {
+    pub fn new(providers: usize, threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold: threshold.max(1),
+            cooldown,
+            circuits: vec![ProviderCircuit::default(); providers],
+        }
+    }
+
+    /// Czas do ponownego włączenia dostawcy; `None` gdy wyłącznik jest zamknięty
+    pub fn remaining(&self, provider: usize, now: Instant) -> Option<Duration> {
+        let until = self.circuits.get(provider)?.open_until?;
+        (until > now).then(|| until - now)
+    }
+
+    pub fn is_open(&self, provider: usize, now: Instant) -> bool {
+        self.remaining(provider, now).is_some()
+    }
+
+    pub fn record_success(&mut self, provider: usize) {
+        if let Some(circuit) = self.circuits.get_mut(provider) {
+            *circuit = ProviderCircuit::default();
+        }
+    }
+
+    /// Zlicza błąd; zwraca `true`, jeśli właśnie otworzył wyłącznik
+    pub fn record_failure(&mut self, provider: usize, now: Instant) -> bool {
+        let Some(circuit) = self.circuits.get_mut(provider) else {
+            return false;
+        };
+        circuit.consecutive_failures += 1;
+        if circuit.consecutive_failures >= self.threshold {
+            circuit.consecutive_failures = 0;
+            circuit.open_until = Some(now + self.cooldown);
+            return true;
+        }
+        false
+    }
+
+    /// Ręczne włączenie dostawcy z powrotem
+    pub fn reset(&mut self, provider: usize) {
+        self.record_success(provider);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COOLDOWN: Duration = Duration::from_secs(300);
+
+    #[test]
+    fn test_opens_after_threshold_failures() {
+        let now = Instant::now();
+        let mut breaker = CircuitBreaker::new(4, 3, COOLDOWN);
+
+        assert!(!breaker.record_failure(1, now));
+        assert!(!breaker.record_failure(1, now));
+        assert!(!breaker.is_open(1, now));
+        assert!(breaker.record_failure(1, now));
+
+        assert!(breaker.is_open(1, now));
+        assert!(!breaker.is_open(0, now));
+        assert_eq!(breaker.remaining(1, now + Duration::from_secs(60)), Some(Duration::from_secs(240)));
+    }
+
+    #[test]
+    fn test_success_resets_failure_count() {
+        let now = Instant::now();
+        let mut breaker = CircuitBreaker::new(4, 2, COOLDOWN);
+
+        breaker.record_failure(0, now);
+        breaker.record_success(0);
+        assert!(!breaker.record_failure(0, now));
+        assert!(!breaker.is_open(0, now));
+    }
+
+    #[test]
+    fn test_closes_after_cooldown_and_on_reset() {
+        let now = Instant::now();
+        let mut breaker = CircuitBreaker::new(4, 1, COOLDOWN);
+
+        assert!(breaker.record_failure(2, now));
+        assert!(!breaker.is_open(2, now + COOLDOWN));
+
+        breaker.record_failure(2, now);
+        breaker.reset(2);
+        assert!(!breaker.is_open(2, now));
+    }
+
+    #[test]
+    fn test_unknown_provider_is_ignored() {
+        let now = Instant::now();
+        let mut breaker = CircuitBreaker::new(1, 0, COOLDOWN);
+        assert!(!breaker.record_failure(5, now));
+        assert!(!breaker.is_open(5, now));
+        assert!(breaker.record_failure(0, now));
+    }
+}
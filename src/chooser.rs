@@ -0,0 +1,100 @@
+//! Mały wybierak wyników przy kursorze
+//!
+//! Zamiast wywoływać duże okno, po zakończeniu sesji przy kursorze myszy
+//! pojawia się lista pierwszych linii wyników z przyciskami wklejenia.
+
+/// Najdłuższy podgląd pierwszej linii wyniku (w znakach)
+pub const PREVIEW_CHARS: usize = 60;
+
+/// Pierwsza niepusta linia tekstu, skrócona do `max_chars` znaków
+pub fn preview_line(text: &str, max_chars: usize) -> String {
+    let line = text.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or("");
+    if line.chars().count() <= max_chars {
+        return line.to_string();
+    }
+    let mut preview: String = line.chars().take(max_chars.saturating_sub(1)).collect();
+    preview.push('…');
+    preview
+}
+
+/// Prostokąt ekranu (x, y, szerokość, wysokość)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Area {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Area {
+    pub fn contains(&self, (x, y): (i32, i32)) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+}
+
+/// Lewy górny róg okienka przy kursorze, przesunięty tak, by mieściło się na ekranie
+pub fn place_near(cursor: (i32, i32), size: (i32, i32), screen: Area) -> (i32, i32) {
+    let max_x = (screen.x + screen.width - size.0).max(screen.x);
+    let max_y = (screen.y + screen.height - size.1).max(screen.y);
+    (cursor.0.clamp(screen.x, max_x), cursor.1.clamp(screen.y, max_y))
+}
+
+/// Lewy górny róg okna wyśrodkowanego na ekranie (przy za dużym oknie: róg ekranu)
+pub fn center_in(size: (i32, i32), screen: Area) -> (i32, i32) {
+    (
+        screen.x + ((screen.width - size.0) / 2).max(0),
+        screen.y + ((screen.height - size.1) / 2).max(0),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCREEN: Area = Area {
+        x: 0,
+        y: 0,
+        width: 1920,
+        height: 1080,
+    };
+
+    #[test]
+    fn test_preview_line() {
+        assert_eq!(preview_line("\n  Pierwsza linia \nDruga", 60), "Pierwsza linia");
+        assert_eq!(preview_line("", 60), "");
+        assert_eq!(preview_line("ąęśćżźółń", 5), "ąęść…");
+    }
+
+    #[test]
+    fn test_place_near_keeps_popup_on_screen() {
+        assert_eq!(place_near((100, 200), (400, 300), SCREEN), (100, 200));
+        assert_eq!(place_near((1900, 1000), (400, 300), SCREEN), (1520, 780));
+        assert_eq!(place_near((-50, 10), (400, 300), SCREEN), (0, 10));
+    }
+
+    #[test]
+    fn test_place_near_second_monitor() {
+        let right = Area {
+            x: 1920,
+            y: 0,
+            width: 1280,
+            height: 1024,
+        };
+        assert!(right.contains((2000, 500)));
+        assert!(!right.contains((1919, 500)));
+        assert_eq!(place_near((3100, 1000), (400, 300), right), (2800, 724));
+    }
+
+    #[test]
+    fn test_center_in() {
+        assert_eq!(center_in((400, 300), SCREEN), (760, 390));
+        assert_eq!(center_in((2000, 1200), SCREEN), (0, 0));
+        let right = Area {
+            x: 1920,
+            y: 0,
+            width: 1280,
+            height: 1024,
+        };
+        assert_eq!(center_in((400, 300), right), (2360, 362));
+    }
+}
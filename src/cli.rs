@@ -0,0 +1,372 @@
+//! Tryb tekstowy: korekta tekstu ze standardowego wejścia bez GUI
+//!
+//! Używany z `--cli` albo automatycznie, gdy nie ma ekranu (np. sesja SSH).
+//! Tekst trafia równocześnie do wszystkich dostawców z uzupełnionym kluczem,
+//! a wyniki są drukowane po kolei.
+
+use std::io::{IsTerminal, Read};
+use std::time::{Duration, Instant};
+
+use futures::future::join_all;
+
+use crate::api::{correct_text, correct_text_structured, correct_text_with_edits, Provider};
+use crate::audit::{self, AuditEntry, AuditLog};
+use crate::config::Config;
+use crate::error::ApiError;
+use crate::prompts::{get_instruction_prompt, get_system_prompt, CorrectionStyle, LanguageContext};
+use crate::structured::StructuredResult;
+
+pub const NO_DISPLAY_MESSAGE: &str = "Brak ekranu (nie ustawiono DISPLAY ani WAYLAND_DISPLAY) - uruchamiam tryb tekstowy.\n\
+    Tekst do korekty podaj na standardowym wejściu, np.: echo \"tekst\" | poprawiacz-tekstu-rs --cli";
+
+/// Czy zmienne środowiska wskazują na dostępny ekran (X11 lub Wayland)
+pub fn display_env_present(display: Option<&str>, wayland_display: Option<&str>) -> bool {
+    [display, wayland_display]
+        .iter()
+        .any(|v| v.is_some_and(|v| !v.trim().is_empty()))
+}
+
+/// Wczytuje tekst ze standardowego wejścia
+pub fn read_input() -> Result<String, String> {
+    let mut stdin = std::io::stdin();
+    if stdin.is_terminal() {
+        eprintln!("Wpisz tekst do korekty i zakończ Ctrl+D:");
+    }
+
+    let mut text = String::new();
+    stdin
+        .read_to_string(&mut text)
+        .map_err(|e| format!("Nie można odczytać wejścia: {}", e))?;
+    Ok(text)
+}
+
+/// Wartość opcji podanej jako `--opcja wartość` albo `--opcja=wartość`
+pub fn arg_value(args: &[String], name: &str) -> Option<String> {
+    let prefix = format!("{}=", name);
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == name {
+            return args.next().cloned();
+        }
+        if let Some(value) = arg.strip_prefix(&prefix) {
+            return Some(value.to_string());
+        }
+    }
+    None
+}
+
+/// Styl z `--style`; bez opcji `None`, nieznana nazwa to błąd z listą stylów
+pub fn style_arg(args: &[String]) -> Result<Option<CorrectionStyle>, String> {
+    arg_value(args, "--style")
+        .map(|name| crate::config::parse_choice("--style", &name, CorrectionStyle::all(), CorrectionStyle::as_str))
+        .transpose()
+}
+
+/// Tekst z `--text`, a bez tej opcji ze standardowego wejścia
+pub fn input(args: &[String]) -> Result<String, String> {
+    match arg_value(args, "--text") {
+        Some(text) => Ok(text),
+        None => read_input(),
+    }
+}
+
+/// Sformatowany wynik jednego dostawcy
+pub fn format_result(provider: Provider, result: &Result<String, ApiError>, elapsed: Duration) -> String {
+    match result {
+        Ok(text) => format!(
+            "=== {} ({:.1} s) ===\n{}\n",
+            provider.name(),
+            elapsed.as_secs_f64(),
+            text.trim_end()
+        ),
+        Err(e) => format!("=== {} (błąd) ===\n{}\n", provider.name(), e),
+    }
+}
+
+/// Wysyła tekst do dostawców z kluczem; zwraca wyniki w kolejności `Provider::all()`
+pub async fn correct_all(
+    config: &Config,
+    text: &str,
+    style: CorrectionStyle,
+) -> Vec<(Provider, Result<String, ApiError>, Duration)> {
+    let language = LanguageContext::detect(text, &config.language_prompts);
+    let system = get_system_prompt(style, &language);
+    let instruction = get_instruction_prompt(style);
+
+    let tasks = Provider::all()
+        .iter()
+        .copied()
+        .filter(|p| p.is_available(config))
+        .map(|provider| async move {
+            let start = Instant::now();
+            let result = if config.edit_mode.applies_to(text) {
+                correct_text_with_edits::<fn(&str)>(provider, config, text, instruction, system, None).await
+            } else {
+                correct_text(provider, config, text, instruction, system).await
+            };
+            record_audit(config, provider, style, text, result.as_deref().ok());
+            (provider, result, start.elapsed())
+        });
+
+    join_all(tasks).await
+}
+
+/// Jak `correct_all`, ale każdy dostawca zwraca wynik w formacie JSON
+pub async fn correct_all_structured(
+    config: &Config,
+    text: &str,
+    style: CorrectionStyle,
+) -> Vec<(Provider, Result<StructuredResult, ApiError>, Duration)> {
+    let language = LanguageContext::detect(text, &config.language_prompts);
+    let system = get_system_prompt(style, &language);
+    let instruction = get_instruction_prompt(style);
+
+    let tasks = Provider::all()
+        .iter()
+        .copied()
+        .filter(|p| p.is_available(config))
+        .map(|provider| async move {
+            let start = Instant::now();
+            let result = correct_text_structured(provider, config, text, instruction, system).await;
+            let corrected = result.as_ref().ok().map(|r| r.corrected.as_str());
+            record_audit(config, provider, style, text, corrected);
+            (provider, result, start.elapsed())
+        });
+
+    join_all(tasks).await
+}
+
+/// Zapisuje wywołanie dostawcy w dzienniku audytu, jeśli jest włączony
+fn record_audit(config: &Config, provider: Provider, style: CorrectionStyle, text: &str, result: Option<&str>) {
+    if !config.audit.enabled {
+        return;
+    }
+    let entry = AuditEntry::new(&config.audit, provider.name(), &provider.generation(config), style.as_str(), text, result);
+    if let Err(e) = AuditLog::new(AuditLog::default_path()).append(&entry) {
+        eprintln!("Nie można zapisać dziennika audytu: {}", e);
+    }
+}
+
+/// Tryb `--audit-csv`: cały dziennik audytu jako CSV na stdout
+pub fn run_audit_csv() -> Result<(), String> {
+    let entries = AuditLog::new(AuditLog::default_path())
+        .load()
+        .map_err(|e| format!("Nie można odczytać dziennika audytu: {}", e))?;
+    print!("{}", audit::to_csv(&entries));
+    Ok(())
+}
+
+/// Wpis jednego dostawcy w wyjściu `--json`
+pub fn json_entry(
+    provider: Provider,
+    config: &Config,
+    result: &Result<StructuredResult, ApiError>,
+    elapsed: Duration,
+) -> serde_json::Value {
+    let mut entry = serde_json::json!({
+        "provider": provider.name(),
+        "model": provider.model(config),
+        "elapsed_ms": elapsed.as_millis() as u64,
+    });
+    match result {
+        Ok(result) => entry["result"] = serde_json::to_value(result).expect("result is always serializable"),
+        Err(e) => entry["error"] = serde_json::Value::String(e.to_string()),
+    }
+    entry
+}
+
+fn validate(config: &Config, text: &str) -> Result<(), String> {
+    if text.trim().is_empty() {
+        return Err("Brak tekstu do korekty".to_string());
+    }
+    if !Provider::all().iter().any(|p| p.is_available(config)) {
+        return Err(format!(
+            "Brak kluczy API w {}",
+            Config::get_config_path().display()
+        ));
+    }
+    Ok(())
+}
+
+/// Linia w formacie dmenu: "Dostawca: tekst" z nowymi liniami zapisanymi jako `\n`
+pub fn format_pick_line(provider: Provider, text: &str) -> String {
+    let escaped = text.trim_end().replace('\\', "\\\\").replace('\n', "\\n");
+    format!("{}: {}", provider.name(), escaped)
+}
+
+/// Odwraca `format_pick_line`: tekst wybranej linii bez nazwy dostawcy
+pub fn parse_pick_line(line: &str) -> String {
+    let line = line.trim_end_matches(['\r', '\n']);
+    let escaped = Provider::all()
+        .iter()
+        .find_map(|p| line.strip_prefix(p.name())?.strip_prefix(": "))
+        .unwrap_or(line);
+
+    let mut text = String::with_capacity(escaped.len());
+    let mut chars = escaped.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => text.push('\n'),
+            Some(other) => text.push(other),
+            None => text.push('\\'),
+        }
+    }
+    text
+}
+
+/// Tryb `--pick`: wyniki jako linie dmenu na stdout, błędy na stderr
+pub fn run_pick(config: &Config, text: &str) -> Result<bool, String> {
+    validate(config, text)?;
+
+    let style = config.settings.default_style;
+    let results = crate::TOKIO_RUNTIME.block_on(correct_all(config, text, style));
+
+    let mut any = false;
+    for (provider, result, _) in &results {
+        match result {
+            Ok(corrected) => {
+                any = true;
+                println!("{}", format_pick_line(*provider, corrected));
+            }
+            Err(e) => eprintln!("{}: {}", provider.name(), e),
+        }
+    }
+    Ok(any)
+}
+
+/// Tryb `--json`: tablica wyników wszystkich dostawców na stdout
+pub fn run_json(config: &Config, text: &str) -> Result<bool, String> {
+    validate(config, text)?;
+
+    let style = config.settings.default_style;
+    let results = crate::TOKIO_RUNTIME.block_on(correct_all_structured(config, text, style));
+
+    let entries: Vec<serde_json::Value> = results
+        .iter()
+        .map(|(provider, result, elapsed)| json_entry(*provider, config, result, *elapsed))
+        .collect();
+    let output = serde_json::to_string_pretty(&entries).map_err(|e| e.to_string())?;
+    println!("{}", output);
+
+    Ok(results.iter().any(|(_, r, _)| r.is_ok()))
+}
+
+/// Uruchamia tryb tekstowy; zwraca `true`, jeśli choć jeden dostawca zwrócił wynik
+pub fn run(config: &Config, text: &str) -> Result<bool, String> {
+    validate(config, text)?;
+
+    let style = config.settings.default_style;
+    let results = crate::TOKIO_RUNTIME.block_on(correct_all(config, text, style));
+
+    for (provider, result, elapsed) in &results {
+        println!("{}", format_result(*provider, result, *elapsed));
+    }
+
+    Ok(results.iter().any(|(_, r, _)| r.is_ok()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_env_present() {
+        assert!(!display_env_present(None, None));
+        assert!(!display_env_present(Some(""), Some("  ")));
+        assert!(display_env_present(Some(":0"), None));
+        assert!(display_env_present(None, Some("wayland-0")));
+    }
+
+    #[test]
+    fn test_text_and_style_args() {
+        let args: Vec<String> = ["poprawiacz-tekstu-rs", "--text", "ala ma kta", "--style=Professional"]
+            .iter()
+            .map(|a| a.to_string())
+            .collect();
+        assert_eq!(arg_value(&args, "--text").as_deref(), Some("ala ma kta"));
+        assert_eq!(style_arg(&args), Ok(Some(CorrectionStyle::Professional)));
+        assert_eq!(input(&args).as_deref(), Ok("ala ma kta"));
+
+        assert_eq!(arg_value(&args[..2], "--text"), None);
+        assert_eq!(style_arg(&args[..3]), Ok(None));
+        let err = style_arg(&["--style".to_string(), "formal".to_string()]).unwrap_err();
+        assert!(err.contains("formal") && err.contains("translate_en"), "{}", err);
+    }
+
+    #[test]
+    fn test_format_result() {
+        let ok = format_result(Provider::OpenAI, &Ok("Poprawiony tekst\n".to_string()), Duration::from_millis(1500));
+        assert_eq!(ok, "=== OpenAI (1.5 s) ===\nPoprawiony tekst\n");
+
+        let err = format_result(
+            Provider::Gemini,
+            &Err(ApiError::Response("API key is empty".to_string())),
+            Duration::ZERO,
+        );
+        assert!(err.starts_with("=== Gemini (błąd) ===\n"));
+        assert!(err.contains("API key is empty"));
+    }
+
+    #[test]
+    fn test_pick_line_roundtrip() {
+        let text = "Pierwsza linia\nDruga z \\n dosłownie\n";
+        let line = format_pick_line(Provider::Anthropic, text);
+        assert_eq!(line, "Anthropic: Pierwsza linia\\nDruga z \\\\n dosłownie");
+        assert!(!line.contains('\n'));
+
+        assert_eq!(parse_pick_line(&format!("{}\n", line)), text.trim_end());
+    }
+
+    #[test]
+    fn test_parse_pick_line_without_provider() {
+        assert_eq!(parse_pick_line("zwykły tekst"), "zwykły tekst");
+        assert_eq!(parse_pick_line("Inny: a\\nb"), "Inny: a\nb");
+        assert_eq!(parse_pick_line("koniec\\"), "koniec\\");
+    }
+
+    #[test]
+    fn test_run_requires_text_and_keys() {
+        let mut config = Config::default();
+        assert!(run(&config, "  \n").is_err());
+        assert!(run(&config, "tekst").unwrap_err().contains("Brak kluczy API"));
+
+        assert!(run_pick(&config, "tekst").unwrap_err().contains("Brak kluczy API"));
+        assert!(run_json(&config, "tekst").unwrap_err().contains("Brak kluczy API"));
+
+        config.api_keys.openai = "sk-test".to_string();
+        assert!(run(&config, "").is_err());
+        assert!(run_pick(&config, " ").is_err());
+    }
+
+    #[test]
+    fn test_json_entry() {
+        let config = Config::default();
+        let result = Ok(crate::structured::from_diff("kta", "kota", 0.9));
+        let ok = json_entry(Provider::OpenAI, &config, &result, Duration::from_millis(1200));
+        assert_eq!(ok["provider"], "OpenAI");
+        assert_eq!(ok["model"], "gpt-5-mini");
+        assert_eq!(ok["elapsed_ms"], 1200);
+        assert_eq!(ok["result"]["corrected"], "kota");
+        assert!(ok.get("error").is_none());
+
+        let err = json_entry(
+            Provider::Gemini,
+            &config,
+            &Err(ApiError::Response("API key is empty".to_string())),
+            Duration::ZERO,
+        );
+        assert!(err["error"].as_str().unwrap().contains("API key is empty"));
+        assert!(err.get("result").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_correct_all_skips_providers_without_key() {
+        let config = Config::default();
+        let results = correct_all(&config, "tekst", CorrectionStyle::Normal).await;
+        assert!(results.is_empty());
+    }
+}
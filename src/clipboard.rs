@@ -1,6 +1,9 @@
+use crate::config::RichClipboard;
 use arboard::Clipboard;
+use pulldown_cmark::{html, Event, Parser, Tag};
 use std::fmt;
 use std::process::Command;
+use tracing::warn;
 
 #[derive(Debug, Clone)]
 pub enum ClipboardError {
@@ -106,6 +109,68 @@ pub fn write_text(text: &str) -> Result<(), ClipboardError> {
         .map_err(|e| ClipboardError::WriteFailed(e.to_string()))
 }
 
+/// Postać, w jakiej wynik trafia do schowka
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasteFormat {
+    /// Tylko text/plain;charset=utf-8
+    Plain,
+    /// text/html z Markdownu przekonwertowanego na HTML oraz text/plain
+    Rich,
+}
+
+/// Czy tekst zawiera formatowanie Markdown (nagłówki, listy, pogrubienia, kod, linki...)
+pub fn looks_like_markdown(text: &str) -> bool {
+    Parser::new(text).any(|event| match event {
+        Event::Start(Tag::Paragraph) => false,
+        Event::Start(_) | Event::Code(_) => true,
+        _ => false,
+    })
+}
+
+pub fn markdown_to_html(text: &str) -> String {
+    let mut out = String::new();
+    html::push_html(&mut out, Parser::new(text));
+    out
+}
+
+/// Wybiera format dla aplikacji docelowej (klasa okna aktywnego przy skrócie)
+pub fn choose_format(settings: &RichClipboard, target_app: Option<&str>, text: &str) -> PasteFormat {
+    let wants_rich = match settings.mode.as_str() {
+        "plain" => false,
+        "rich" => true,
+        _ => target_app.is_some_and(|app| {
+            let app = app.to_lowercase();
+            settings
+                .rich_apps
+                .iter()
+                .any(|rich| !rich.is_empty() && app.contains(&rich.to_lowercase()))
+        }),
+    };
+
+    if wants_rich && looks_like_markdown(text) {
+        PasteFormat::Rich
+    } else {
+        PasteFormat::Plain
+    }
+}
+
+/// Zapisuje tekst w wybranym formacie; HTML niedostępny w schowku kończy się zwykłym tekstem
+pub fn write_formatted(text: &str, format: PasteFormat) -> Result<(), ClipboardError> {
+    if format == PasteFormat::Plain {
+        return write_text(text);
+    }
+
+    let html = markdown_to_html(text);
+    let result = Clipboard::new().and_then(|mut clipboard| clipboard.set_html(html, Some(text.to_string())));
+    match result {
+        Ok(()) => Ok(()),
+        Err(e) => {
+            warn!("Rich clipboard unavailable ({}), writing plain text", e);
+            write_text(text)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -116,6 +181,38 @@ mod tests {
         assert!(result == true || result == false);
     }
 
+    #[test]
+    fn test_looks_like_markdown() {
+        assert!(!looks_like_markdown("Zwykły tekst bez formatowania.\n\nDrugi akapit."));
+        assert!(looks_like_markdown("To jest **ważne**."));
+        assert!(looks_like_markdown("- punkt pierwszy\n- punkt drugi"));
+        assert!(looks_like_markdown("Uruchom `cargo test`."));
+        assert!(looks_like_markdown("# Nagłówek"));
+    }
+
+    #[test]
+    fn test_markdown_to_html() {
+        assert_eq!(markdown_to_html("To jest **ważne**."), "<p>To jest <strong>ważne</strong>.</p>\n");
+    }
+
+    #[test]
+    fn test_choose_format() {
+        let mut settings = RichClipboard::default();
+        let markdown = "- **punkt**";
+
+        settings.rich_apps = vec!["Thunderbird".to_string()];
+        assert_eq!(choose_format(&settings, Some("thunderbird"), markdown), PasteFormat::Rich);
+        assert_eq!(choose_format(&settings, Some("thunderbird"), "zwykły tekst"), PasteFormat::Plain);
+        assert_eq!(choose_format(&settings, Some("kitty"), markdown), PasteFormat::Plain);
+        assert_eq!(choose_format(&settings, None, markdown), PasteFormat::Plain);
+
+        settings.mode = "rich".to_string();
+        assert_eq!(choose_format(&settings, None, markdown), PasteFormat::Rich);
+
+        settings.mode = "plain".to_string();
+        assert_eq!(choose_format(&settings, Some("thunderbird"), markdown), PasteFormat::Plain);
+    }
+
     #[test]
     fn test_clipboard_error_display() {
         let err = ClipboardError::AccessFailed("No display".to_string());
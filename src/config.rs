@@ -1,6 +1,12 @@
-use serde::{Deserialize, Serialize};
+use crate::api::registry::PROVIDERS;
+use crate::app_styles::AppChoice;
+use crate::prompts::CorrectionStyle;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Config {
@@ -8,6 +14,65 @@ pub struct Config {
     pub models: Models,
     pub settings: Settings,
     pub ai_settings: AiSettings,
+    #[serde(default)]
+    pub styleguide: StyleGuide,
+    #[serde(default)]
+    pub protected_spans: ProtectedSpans,
+    #[serde(default)]
+    pub normalize: Normalize,
+    #[serde(default)]
+    pub polling: Polling,
+    #[serde(default)]
+    pub circuit_breaker: CircuitBreakerSettings,
+    #[serde(default)]
+    pub global_hotkey: GlobalHotkey,
+    #[serde(default)]
+    pub double_press: DoublePress,
+    #[serde(default)]
+    pub history: HistorySettings,
+    #[serde(default)]
+    pub conversation: ConversationSettings,
+    #[serde(default)]
+    pub edit_mode: EditModeSettings,
+    #[serde(default)]
+    pub length: LengthSettings,
+    #[serde(default)]
+    pub budget: BudgetSettings,
+    #[serde(default)]
+    pub audit: AuditSettings,
+    #[serde(default)]
+    pub metrics: MetricsEndpoint,
+    #[serde(default)]
+    pub rich_clipboard: RichClipboard,
+    #[serde(default)]
+    pub paste_guard: PasteGuard,
+    #[serde(default)]
+    pub network: Network,
+    #[serde(default)]
+    pub appearance: Appearance,
+    #[serde(default)]
+    pub snippets: Vec<Snippet>,
+    /// Potoki stylów dostępne w palecie stylów
+    #[serde(default)]
+    pub pipelines: Vec<Pipeline>,
+    /// Zadania wsadowe uruchamiane o ustalonych godzinach
+    #[serde(default)]
+    pub jobs: Vec<Job>,
+    /// Domyślny styl skrótu dla aplikacji źródłowych (pierwsza pasująca reguła)
+    #[serde(default, deserialize_with = "lenient_app_styles")]
+    pub app_styles: Vec<AppStyle>,
+    /// Własne style w palecie stylów, obok wbudowanych
+    #[serde(default)]
+    pub custom_styles: Vec<CustomStyle>,
+    /// Ceny modeli (USD za milion tokenów) do szacowania kosztu w porównaniu dostawców
+    #[serde(default = "default_pricing")]
+    pub pricing: BTreeMap<String, ModelPrice>,
+    /// Kod języka wejścia (np. "en", "de") → system prompt zastępujący wbudowany
+    #[serde(default)]
+    pub language_prompts: BTreeMap<String, String>,
+    /// Akcja okna → akcelerator różny od domyślnego (pusty wyłącza skrót)
+    #[serde(default)]
+    pub shortcuts: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -20,6 +85,10 @@ pub struct ApiKeys {
     pub gemini: String,
     #[serde(rename = "DeepSeek")]
     pub deepseek: String,
+    #[serde(rename = "Mistral", default)]
+    pub mistral: String,
+    #[serde(rename = "Groq", default)]
+    pub groq: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -32,24 +101,339 @@ pub struct Models {
     pub gemini: String,
     #[serde(rename = "DeepSeek")]
     pub deepseek: String,
+    #[serde(rename = "Mistral", default = "default_mistral_model")]
+    pub mistral: String,
+    #[serde(rename = "Groq", default = "default_groq_model")]
+    pub groq: String,
+}
+
+fn default_mistral_model() -> String {
+    PROVIDERS[4].default_model.to_string()
+}
+
+fn default_groq_model() -> String {
+    PROVIDERS[5].default_model.to_string()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Settings {
     #[serde(rename = "AutoStartup")]
     pub auto_startup: bool,
-    #[serde(rename = "DefaultStyle")]
-    pub default_style: String,
+    #[serde(rename = "DefaultStyle", deserialize_with = "lenient_choice")]
+    pub default_style: CorrectionStyle,
     #[serde(rename = "HighlightDiffs")]
     pub highlight_diffs: bool,
+    #[serde(rename = "LearningDigest", default)]
+    pub learning_digest: bool,
+    /// Ukryj okno po wyborze wyniku dopiero gdy nadeszło tyle wyników (0 = zawsze)
+    #[serde(rename = "AutoHideMinResults", default)]
+    pub auto_hide_min_results: u32,
+    /// Pokaż okno dopiero gdy gotowych jest tyle wyników (0 = od razu)
+    #[serde(rename = "AutoPresentResults", default)]
+    pub auto_present_results: u32,
+    /// Zamiast okna pokaż przy kursorze małe okienko wyboru wyniku
+    #[serde(rename = "ResultsPopup", default)]
+    pub results_popup: bool,
+    /// Skrót globalny pokazuje okno bez ramki, nagłówka i paska narzędzi
+    #[serde(rename = "CompactHotkeyWindow", default)]
+    pub compact_hotkey_window: bool,
+    /// Po skrócie wysyłaj schowek do API dopiero po potwierdzeniu w powiadomieniu
+    #[serde(rename = "ConfirmClipboard", default)]
+    pub confirm_clipboard: bool,
+    /// Dźwięk po pierwszym wyniku, gdy okno jest schowane
+    #[serde(rename = "SoundFirstResult", default)]
+    pub sound_first_result: bool,
+    /// Dźwięk po ostatnim wyniku, gdy okno jest schowane
+    #[serde(rename = "SoundLastResult", default)]
+    pub sound_last_result: bool,
+    /// Zamiast prawdziwych API używaj dostawcy demonstracyjnego (bez kluczy i sieci)
+    #[serde(rename = "DemoMode", default)]
+    pub demo_mode: bool,
+    /// Ostrzeżenia o liczbach, datach i linkach zmienionych przez model
+    #[serde(rename = "CheckFacts", default = "default_true")]
+    pub check_facts: bool,
+    /// Tło zdań, co do których dostawcy się nie zgadzają
+    #[serde(rename = "AgreementHeatmap", default)]
+    pub agreement_heatmap: bool,
+    /// Krótszy limit czasu i mniej ponowień dla wszystkich dostawców
+    #[serde(rename = "QuickMode", default)]
+    pub quick_mode: bool,
+    /// Dostawcy w kolejności komórek siatki paneli (od lewego górnego)
+    #[serde(rename = "PanelOrder", default)]
+    pub panel_order: Vec<String>,
+    /// Panele jako `"Dostawca"` albo `"Dostawca:model"`, dostawcy mogą się powtarzać
+    /// (pusta lista: pierwsi czterej); zmiana po ponownym uruchomieniu
+    #[serde(rename = "PanelProviders", default)]
+    pub panel_providers: Vec<String>,
+    /// Samouczek pierwszego uruchomienia ukończony albo pominięty
+    #[serde(rename = "TutorialDone", default)]
+    pub tutorial_done: bool,
+    /// Łam wklejany wynik do szerokości wierszy oryginału, jeśli był łamany ręcznie
+    #[serde(rename = "RewrapOutput", default = "default_true")]
+    pub rewrap_output: bool,
+}
+
+/// Co się dzieje z wybranym wynikiem poza skopiowaniem go do schowka
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delivery {
+    /// Okno się chowa, a Ctrl+V wkleja wynik w oknie docelowym
+    HideAndPaste,
+    /// Okno zostaje; Ctrl+V trafiłby do niego, więc wynik jest tylko w schowku
+    CopyOnly,
+}
+
+impl Settings {
+    pub fn should_auto_hide(&self, completed: u32) -> bool {
+        completed >= self.auto_hide_min_results
+    }
+
+    /// Wklejenie wymaga schowania okna, bo Ctrl+V trafia do okna z fokusem
+    pub fn delivery(&self, completed: u32) -> Delivery {
+        if self.should_auto_hide(completed) {
+            Delivery::HideAndPaste
+        } else {
+            Delivery::CopyOnly
+        }
+    }
+
+    /// Duże okno nigdy nie pojawia się samo w trybie okienka przy kursorze
+    pub fn should_auto_present(&self, completed: u32) -> bool {
+        !self.results_popup && completed >= self.auto_present_results
+    }
+
+    /// Numery dostawców z `names` w kolejności komórek; nieznane i powtórzone
+    /// nazwy są pomijane, brakujący dostawcy trafiają na koniec
+    pub fn panel_order(&self, names: &[&str]) -> Vec<usize> {
+        let mut order: Vec<usize> = Vec::with_capacity(names.len());
+        for entry in &self.panel_order {
+            if let Some(index) = names.iter().position(|n| n.eq_ignore_ascii_case(entry.trim())) {
+                if !order.contains(&index) {
+                    order.push(index);
+                }
+            }
+        }
+        let missing: Vec<usize> = (0..names.len()).filter(|i| !order.contains(i)).collect();
+        order.extend(missing);
+        order
+    }
+
+    /// Zamienia miejscami panele dostawców `a` i `b`
+    pub fn swap_panels(&mut self, names: &[&str], a: usize, b: usize) {
+        let mut order = self.panel_order(names);
+        if let (Some(pa), Some(pb)) = (order.iter().position(|&i| i == a), order.iter().position(|&i| i == b)) {
+            order.swap(pa, pb);
+        }
+        self.panel_order = order.into_iter().map(|i| names[i].to_string()).collect();
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct AiSettings {
-    #[serde(rename = "ReasoningEffort")]
-    pub reasoning_effort: String,
-    #[serde(rename = "Verbosity")]
-    pub verbosity: String,
+    #[serde(rename = "ReasoningEffort", deserialize_with = "lenient_choice")]
+    pub reasoning_effort: ReasoningEffort,
+    #[serde(rename = "Verbosity", deserialize_with = "lenient_choice")]
+    pub verbosity: Verbosity,
+}
+
+/// Wartość z listy dozwolonych (bez rozróżniania wielkości liter); błąd wymienia dozwolone
+pub fn parse_choice<T: Copy>(kind: &str, value: &str, all: &[T], name: fn(&T) -> &'static str) -> Result<T, String> {
+    let wanted = value.trim();
+    all.iter().copied().find(|choice| name(choice).eq_ignore_ascii_case(wanted)).ok_or_else(|| {
+        let allowed: Vec<&str> = all.iter().map(name).collect();
+        format!("invalid {} \"{}\", expected one of: {}", kind, value, allowed.join(", "))
+    })
+}
+
+thread_local! {
+    /// Wartości pominięte przy wczytywaniu konfiguracji w tym wątku
+    static LOAD_WARNINGS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+fn warn_on_load(message: String) {
+    tracing::warn!("Config: {}", message);
+    LOAD_WARNINGS.with(|warnings| warnings.borrow_mut().push(message));
+}
+
+fn choice_from_value<T: TryFrom<String, Error = String>>(value: toml::Value) -> Result<T, String> {
+    match value {
+        toml::Value::String(text) => T::try_from(text),
+        other => Err(format!("expected a string, got {}", other)),
+    }
+}
+
+/// Wybór z pliku; nieznana wartość daje ostrzeżenie i wartość domyślną zamiast błędu całego pliku
+fn lenient_choice<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: TryFrom<String, Error = String> + Default + Into<String> + Copy,
+{
+    let value = toml::Value::deserialize(deserializer)?;
+    Ok(choice_from_value(value).unwrap_or_else(|e| {
+        let fallback = T::default();
+        warn_on_load(format!("{}; using \"{}\"", e, fallback.into()));
+        fallback
+    }))
+}
+
+/// Skróty stylów; wpis z nieznanym stylem jest pomijany z ostrzeżeniem
+fn lenient_styles<'de, D>(deserializer: D) -> Result<BTreeMap<String, CorrectionStyle>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let entries = BTreeMap::<String, toml::Value>::deserialize(deserializer)?;
+    let mut styles = BTreeMap::new();
+    for (combination, value) in entries {
+        match choice_from_value(value) {
+            Ok(style) => {
+                styles.insert(combination, style);
+            }
+            Err(e) => warn_on_load(format!("shortcut {}: {}; skipped", combination, e)),
+        }
+    }
+    Ok(styles)
+}
+
+/// Reguły okien; reguła z nieznanym stylem jest pomijana z ostrzeżeniem
+fn lenient_app_styles<'de, D>(deserializer: D) -> Result<Vec<AppStyle>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let rules = Vec::<toml::Value>::deserialize(deserializer)?;
+    Ok(rules
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, rule)| {
+            rule.try_into()
+                .map_err(|e| warn_on_load(format!("app_styles rule {}: {}; rule skipped", i + 1, e.message())))
+                .ok()
+        })
+        .collect())
+}
+
+/// Nakład rozumowania modeli, które go obsługują
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(try_from = "String", into = "String")]
+pub enum ReasoningEffort {
+    Minimal,
+    Low,
+    Medium,
+    #[default]
+    High,
+}
+
+impl ReasoningEffort {
+    pub fn all() -> &'static [ReasoningEffort] {
+        &[Self::Minimal, Self::Low, Self::Medium, Self::High]
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Minimal => "minimal",
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        }
+    }
+}
+
+impl TryFrom<String> for ReasoningEffort {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        parse_choice("ReasoningEffort", &value, Self::all(), Self::as_str)
+    }
+}
+
+impl From<ReasoningEffort> for String {
+    fn from(value: ReasoningEffort) -> Self {
+        value.as_str().to_string()
+    }
+}
+
+/// Szczegółowość odpowiedzi modeli, które ją obsługują
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(try_from = "String", into = "String")]
+pub enum Verbosity {
+    Low,
+    #[default]
+    Medium,
+    High,
+}
+
+impl Verbosity {
+    pub fn all() -> &'static [Verbosity] {
+        &[Self::Low, Self::Medium, Self::High]
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        }
+    }
+}
+
+impl TryFrom<String> for Verbosity {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        parse_choice("Verbosity", &value, Self::all(), Self::as_str)
+    }
+}
+
+impl From<Verbosity> for String {
+    fn from(value: Verbosity) -> Self {
+        value.as_str().to_string()
+    }
+}
+
+/// Firmowy style guide: reguły doklejane do promptu i sprawdzane lokalnie w wynikach
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct StyleGuide {
+    #[serde(rename = "Enabled", default)]
+    pub enabled: bool,
+    /// Zwroty, które nie mogą pojawić się w tekście
+    #[serde(rename = "ForbiddenPhrases", default)]
+    pub forbidden_phrases: Vec<String>,
+    /// Niepożądana pisownia → pisownia preferowana
+    #[serde(rename = "PreferredSpellings", default)]
+    pub preferred_spellings: BTreeMap<String, String>,
+    /// Nazwy zapisywane zawsze dokładnie w tej postaci (np. "GitHub", "PoprawiaczTekstu")
+    #[serde(rename = "Capitalization", default)]
+    pub capitalization: Vec<String>,
+}
+
+impl StyleGuide {
+    pub fn is_active(&self) -> bool {
+        self.enabled
+            && !(self.forbidden_phrases.is_empty()
+                && self.preferred_spellings.is_empty()
+                && self.capitalization.is_empty())
+    }
+}
+
+/// Wyrażenia regularne, których dopasowania nie są wysyłane do modeli
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProtectedSpans {
+    #[serde(rename = "Enabled", default)]
+    pub enabled: bool,
+    #[serde(rename = "Patterns", default)]
+    pub patterns: Vec<String>,
+}
+
+impl Default for ProtectedSpans {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            patterns: vec![
+                // Identyfikatory zgłoszeń, np. ABC-123
+                r"\b[A-Z][A-Z0-9]+-\d+\b".to_string(),
+                r#"https?://[^\s<>"]+"#.to_string(),
+                r"`[^`\n]+`".to_string(),
+            ],
+        }
+    }
 }
 
 impl Default for Config {
@@ -60,31 +444,664 @@ impl Default for Config {
                 anthropic: String::new(),
                 gemini: String::new(),
                 deepseek: String::new(),
+                mistral: String::new(),
+                groq: String::new(),
             },
             models: Models {
-                openai: "gpt-5-mini".to_string(),
-                anthropic: "claude-3-7-sonnet-latest".to_string(),
-                gemini: "gemini-2.5-flash".to_string(),
-                deepseek: "deepseek-chat".to_string(),
+                openai: PROVIDERS[0].default_model.to_string(),
+                anthropic: PROVIDERS[1].default_model.to_string(),
+                gemini: PROVIDERS[2].default_model.to_string(),
+                deepseek: PROVIDERS[3].default_model.to_string(),
+                mistral: default_mistral_model(),
+                groq: default_groq_model(),
             },
             settings: Settings {
                 auto_startup: false,
-                default_style: "normal".to_string(),
+                default_style: CorrectionStyle::default(),
                 highlight_diffs: false,
+                learning_digest: false,
+                auto_hide_min_results: 0,
+                auto_present_results: 0,
+                results_popup: false,
+                compact_hotkey_window: false,
+                confirm_clipboard: false,
+                sound_first_result: false,
+                sound_last_result: false,
+                demo_mode: false,
+                check_facts: true,
+                agreement_heatmap: false,
+                quick_mode: false,
+                panel_order: Vec::new(),
+                panel_providers: Vec::new(),
+                tutorial_done: false,
+                rewrap_output: true,
             },
             ai_settings: AiSettings {
-                reasoning_effort: "high".to_string(),
-                verbosity: "medium".to_string(),
+                reasoning_effort: ReasoningEffort::default(),
+                verbosity: Verbosity::default(),
             },
+            styleguide: StyleGuide::default(),
+            protected_spans: ProtectedSpans::default(),
+            normalize: Normalize::default(),
+            polling: Polling::default(),
+            circuit_breaker: CircuitBreakerSettings::default(),
+            global_hotkey: GlobalHotkey::default(),
+            double_press: DoublePress::default(),
+            history: HistorySettings::default(),
+            conversation: ConversationSettings::default(),
+            edit_mode: EditModeSettings::default(),
+            length: LengthSettings::default(),
+            budget: BudgetSettings::default(),
+            audit: AuditSettings::default(),
+            metrics: MetricsEndpoint::default(),
+            rich_clipboard: RichClipboard::default(),
+            paste_guard: PasteGuard::default(),
+            network: Network::default(),
+            appearance: Appearance::default(),
+            snippets: Vec::new(),
+            pipelines: Vec::new(),
+            jobs: Vec::new(),
+            app_styles: Vec::new(),
+            custom_styles: Vec::new(),
+            pricing: default_pricing(),
+            language_prompts: BTreeMap::new(),
+            shortcuts: BTreeMap::new(),
         }
     }
 }
 
+/// Najkrótszy i najdłuższy dopuszczalny interwał odpytywania (ms)
+pub const MIN_POLL_INTERVAL_MS: u64 = 10;
+pub const MAX_POLL_INTERVAL_MS: u64 = 5000;
+
+/// Interwały odpytywania skrótu i zasobnika oraz tryb oszczędzania energii
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Polling {
+    #[serde(rename = "HotkeyIntervalMs", default = "default_hotkey_interval_ms")]
+    pub hotkey_interval_ms: u64,
+    #[serde(rename = "TrayIntervalMs", default = "default_tray_interval_ms")]
+    pub tray_interval_ms: u64,
+    /// Wstrzymuj odpytywanie, gdy okno jest schowane i nie trwa sesja
+    #[serde(rename = "PowerSave", default)]
+    pub power_save: bool,
+}
+
+fn default_hotkey_interval_ms() -> u64 {
+    50
+}
+
+fn default_tray_interval_ms() -> u64 {
+    100
+}
+
+impl Default for Polling {
+    fn default() -> Self {
+        Self {
+            hotkey_interval_ms: default_hotkey_interval_ms(),
+            tray_interval_ms: default_tray_interval_ms(),
+            power_save: false,
+        }
+    }
+}
+
+impl Polling {
+    pub fn hotkey_interval(&self) -> Duration {
+        clamp_interval(self.hotkey_interval_ms)
+    }
+
+    pub fn tray_interval(&self) -> Duration {
+        clamp_interval(self.tray_interval_ms)
+    }
+}
+
+fn clamp_interval(ms: u64) -> Duration {
+    Duration::from_millis(ms.clamp(MIN_POLL_INTERVAL_MS, MAX_POLL_INTERVAL_MS))
+}
+
+/// Pomijanie dostawcy po serii błędów
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CircuitBreakerSettings {
+    #[serde(rename = "Enabled", default = "default_true")]
+    pub enabled: bool,
+    /// Liczba kolejnych błędów, po której dostawca jest wyłączany
+    #[serde(rename = "FailureThreshold", default = "default_failure_threshold")]
+    pub failure_threshold: u32,
+    #[serde(rename = "CooldownMinutes", default = "default_cooldown_minutes")]
+    pub cooldown_minutes: u64,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_failure_threshold() -> u32 {
+    3
+}
+
+fn default_cooldown_minutes() -> u64 {
+    5
+}
+
+/// Najdłuższa przerwa dostawcy (tydzień); większe wartości są przycinane
+pub const MAX_COOLDOWN_MINUTES: u64 = 7 * 24 * 60;
+
+impl Default for CircuitBreakerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            failure_threshold: default_failure_threshold(),
+            cooldown_minutes: default_cooldown_minutes(),
+        }
+    }
+}
+
+impl CircuitBreakerSettings {
+    pub fn cooldown(&self) -> Duration {
+        Duration::from_secs(self.cooldown_minutes.min(MAX_COOLDOWN_MINUTES) * 60)
+    }
+}
+
+/// Najkrótszy i najdłuższy odstęp podwójnego naciśnięcia skrótu (ms)
+pub const MIN_DOUBLE_PRESS_MS: u64 = 150;
+pub const MAX_DOUBLE_PRESS_MS: u64 = 1000;
+
+/// Skrót globalny przechwytujący zaznaczony tekst
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GlobalHotkey {
+    /// Kombinacja w zapisie "Ctrl+Shift+C" (modyfikatory Ctrl, Shift, Alt, Super i jeden klawisz)
+    #[serde(rename = "Combination", default = "default_hotkey_combination")]
+    pub combination: String,
+    /// Dodatkowe skróty: kombinacja → styl, w którym od razu idzie tekst
+    #[serde(rename = "Styles", default, deserialize_with = "lenient_styles")]
+    pub styles: BTreeMap<String, CorrectionStyle>,
+}
+
+fn default_hotkey_combination() -> String {
+    "Ctrl+Shift+C".to_string()
+}
+
+impl Default for GlobalHotkey {
+    fn default() -> Self {
+        Self {
+            combination: default_hotkey_combination(),
+            styles: BTreeMap::new(),
+        }
+    }
+}
+
+/// Druga akcja skrótu globalnego wywoływana podwójnym naciśnięciem
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DoublePress {
+    /// "none", "translate", "rerun_last" albo "palette"
+    #[serde(rename = "Action", default = "default_double_press_action")]
+    pub action: String,
+    #[serde(rename = "WindowMs", default = "default_double_press_ms")]
+    pub window_ms: u64,
+}
+
+fn default_double_press_action() -> String {
+    "none".to_string()
+}
+
+fn default_double_press_ms() -> u64 {
+    500
+}
+
+impl Default for DoublePress {
+    fn default() -> Self {
+        Self {
+            action: default_double_press_action(),
+            window_ms: default_double_press_ms(),
+        }
+    }
+}
+
+impl DoublePress {
+    pub fn window(&self) -> Duration {
+        Duration::from_millis(self.window_ms.clamp(MIN_DOUBLE_PRESS_MS, MAX_DOUBLE_PRESS_MS))
+    }
+}
+
+/// Przechowywanie historii poprawek
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HistorySettings {
+    /// Zapisuj wybrane wyniki; bez tego klucza w pliku przejmuje wartość `LearningDigest`
+    #[serde(rename = "Enabled", default)]
+    pub enabled: bool,
+    /// Szyfruj wpisy hasłem podawanym raz na uruchomienie
+    #[serde(rename = "Encrypt", default)]
+    pub encrypt: bool,
+    /// "all", "days", "entries" albo "none" (nic nie zapisuj)
+    #[serde(rename = "Retention", default = "default_retention")]
+    pub retention: String,
+    #[serde(rename = "RetentionDays", default = "default_retention_days")]
+    pub retention_days: u32,
+    #[serde(rename = "RetentionEntries", default = "default_retention_entries")]
+    pub retention_entries: u32,
+}
+
+fn default_retention() -> String {
+    "all".to_string()
+}
+
+fn default_retention_days() -> u32 {
+    30
+}
+
+fn default_retention_entries() -> u32 {
+    500
+}
+
+impl Default for HistorySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            encrypt: false,
+            retention: default_retention(),
+            retention_days: default_retention_days(),
+            retention_entries: default_retention_entries(),
+        }
+    }
+}
+
+/// Tryb rozmowy: dopracowywanie wyników kolejnymi poleceniami w obrębie sesji
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConversationSettings {
+    #[serde(rename = "Enabled", default)]
+    pub enabled: bool,
+    /// Ile ostatnich wymian z każdym dostawcą wysyłać jako kontekst
+    #[serde(rename = "Exchanges", default = "default_conversation_exchanges")]
+    pub exchanges: u32,
+}
+
+fn default_conversation_exchanges() -> u32 {
+    3
+}
+
+impl ConversationSettings {
+    /// Co najmniej jedna wymiana, inaczej nie byłoby czego dopracować
+    pub fn limit(&self) -> usize {
+        self.exchanges.max(1) as usize
+    }
+}
+
+impl Default for ConversationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            exchanges: default_conversation_exchanges(),
+        }
+    }
+}
+
+/// Oszczędny tryb poprawek: dla długich tekstów model zwraca tylko listę zmian
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EditModeSettings {
+    #[serde(rename = "Enabled", default)]
+    pub enabled: bool,
+    /// Od ilu znaków tekst jest uznawany za długi
+    #[serde(rename = "MinChars", default = "default_edit_mode_min_chars")]
+    pub min_chars: u32,
+}
+
+fn default_edit_mode_min_chars() -> u32 {
+    1500
+}
+
+impl EditModeSettings {
+    /// Czy tekst wysłać w trybie listy zmian
+    pub fn applies_to(&self, text: &str) -> bool {
+        self.enabled && text.chars().count() >= self.min_chars as usize
+    }
+}
+
+impl Default for EditModeSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_chars: default_edit_mode_min_chars(),
+        }
+    }
+}
+
+/// Ograniczenia długości wyniku
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct LengthSettings {
+    /// Zachowanie liczby słów w granicach ±10% z ostrzeżeniem przy większej zmianie
+    #[serde(rename = "Preserve", default)]
+    pub preserve: bool,
+    /// Limit znaków wyniku; 0 wyłącza
+    #[serde(rename = "MaxChars", default)]
+    pub max_chars: u32,
+}
+
+/// Miesięczne budżety kosztów dostawców
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct BudgetSettings {
+    /// Nazwa dostawcy → budżet w USD na miesiąc (0 lub brak = bez budżetu)
+    #[serde(rename = "Limits", default)]
+    pub limits: BTreeMap<String, f64>,
+    /// Pomijanie dostawcy do końca miesiąca po przekroczeniu budżetu
+    #[serde(rename = "DisableAtLimit", default)]
+    pub disable_at_limit: bool,
+}
+
+impl BudgetSettings {
+    pub fn limit(&self, provider: &str) -> Option<f64> {
+        self.limits.get(provider).copied().filter(|limit| *limit > 0.0)
+    }
+}
+
+/// Dziennik audytu wywołań dostawców (`audit.jsonl`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct AuditSettings {
+    #[serde(rename = "Enabled", default)]
+    pub enabled: bool,
+    /// Zapisywanie treści tekstu i wyniku; domyślnie tylko metadane
+    #[serde(rename = "IncludeContent", default)]
+    pub include_content: bool,
+}
+
+/// Lokalny endpoint z metrykami w formacie Prometheusa (tylko 127.0.0.1)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MetricsEndpoint {
+    #[serde(rename = "Enabled", default)]
+    pub enabled: bool,
+    #[serde(rename = "Port", default = "default_metrics_port")]
+    pub port: u16,
+}
+
+fn default_metrics_port() -> u16 {
+    9464
+}
+
+impl Default for MetricsEndpoint {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: default_metrics_port(),
+        }
+    }
+}
+
+/// Porządkowanie tekstu przed wysłaniem do dostawców
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct Normalize {
+    /// Usuń znaki cytowania (`>`) z początku wierszy
+    #[serde(rename = "StripQuotes", default)]
+    pub strip_quotes: bool,
+    /// Sklej ręcznie łamane wiersze w akapity
+    #[serde(rename = "UnwrapLines", default)]
+    pub unwrap_lines: bool,
+    /// Usuń parametry śledzące (utm_*, fbclid…) z adresów URL
+    #[serde(rename = "StripTracking", default)]
+    pub strip_tracking: bool,
+}
+
+/// Wygląd okna dla osób, którym przeszkadza ruch albo emoji
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct Appearance {
+    /// Bez animacji i wskaźników obrotowych
+    #[serde(rename = "ReducedMotion", default)]
+    pub reduced_motion: bool,
+    /// Zwykłe ikony stanu zamiast emoji, bez emoji na początku komunikatów i przycisków
+    #[serde(rename = "MinimalEmoji", default)]
+    pub minimal_emoji: bool,
+}
+
+/// Format schowka przy wklejaniu wyniku
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RichClipboard {
+    /// "auto" (HTML dla aplikacji z `RichApps`), "plain" albo "rich"
+    #[serde(rename = "Mode", default = "default_rich_clipboard_mode")]
+    pub mode: String,
+    /// Fragmenty klas okien aplikacji przyjmujących tekst sformatowany
+    #[serde(rename = "RichApps", default)]
+    pub rich_apps: Vec<String>,
+}
+
+fn default_rich_clipboard_mode() -> String {
+    "auto".to_string()
+}
+
+impl Default for RichClipboard {
+    fn default() -> Self {
+        Self {
+            mode: default_rich_clipboard_mode(),
+            rich_apps: ["thunderbird", "evolution", "libreoffice", "soffice", "outlook", "winword"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// Okna, do których wynik nie jest wklejany automatycznie (tylko kopiowany)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PasteGuard {
+    #[serde(rename = "Enabled", default = "default_true")]
+    pub enabled: bool,
+    /// Fragmenty klas okien (np. menedżery haseł, okna uwierzytelniania)
+    #[serde(rename = "BlockedClasses", default = "default_blocked_classes")]
+    pub blocked_classes: Vec<String>,
+    /// Fragmenty tytułów okien (np. terminal z pytaniem sudo)
+    #[serde(rename = "BlockedTitles", default = "default_blocked_titles")]
+    pub blocked_titles: Vec<String>,
+    /// Wklejanie, gdy nie da się odczytać okna z fokusem (np. GNOME na Waylandzie)
+    #[serde(rename = "AllowUnknownWindow", default)]
+    pub allow_unknown_window: bool,
+}
+
+fn default_blocked_classes() -> Vec<String> {
+    ["pinentry", "keepass", "1password", "bitwarden", "gcr-prompter", "polkit", "ksshaskpass", "ssh-askpass"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+fn default_blocked_titles() -> Vec<String> {
+    ["sudo", "password", "passphrase", "hasło", "haslo", "authentication", "uwierzytelnianie"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+impl Default for PasteGuard {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            blocked_classes: default_blocked_classes(),
+            blocked_titles: default_blocked_titles(),
+            allow_unknown_window: false,
+        }
+    }
+}
+
+/// Połączenia z dostawcami
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Network {
+    /// Kompresuj gzipem duże treści żądań (tylko u dostawców, którzy to przyjmują)
+    #[serde(rename = "CompressRequests", default)]
+    pub compress_requests: bool,
+    /// "auto" (IPv4 i IPv6, szybszy wygrywa), "ipv4" albo "ipv6"
+    #[serde(rename = "IpVersion", default = "default_ip_version")]
+    pub ip_version: String,
+    /// Host API → adresy IP (po przecinku) używane zamiast zapytania DNS
+    #[serde(rename = "DnsOverrides", default)]
+    pub dns_overrides: BTreeMap<String, String>,
+}
+
+fn default_ip_version() -> String {
+    "auto".to_string()
+}
+
+impl Default for Network {
+    fn default() -> Self {
+        Self {
+            compress_requests: false,
+            ip_version: default_ip_version(),
+            dns_overrides: BTreeMap::new(),
+        }
+    }
+}
+
+/// Gotowy zwrot wstawiany do wyniku albo wklejany z zasobnika
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Snippet {
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "Text")]
+    pub text: String,
+}
+
+/// Styl skrótu dla okna o pasującej klasie i tytule
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AppStyle {
+    /// Fragment klasy okna, np. "thunderbird"; pusty pasuje do każdej
+    #[serde(rename = "Class", default)]
+    pub class: String,
+    /// Fragment tytułu okna, np. "DeepL"; pusty pasuje do każdego
+    #[serde(rename = "Title", default)]
+    pub title: String,
+    /// Identyfikator stylu albo "skip" (skrót w tym oknie nic nie wysyła)
+    #[serde(rename = "Style")]
+    pub style: AppChoice,
+}
+
+/// Styl zdefiniowany przez użytkownika
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CustomStyle {
+    /// Nazwa w palecie stylów, historii i dzienniku audytu
+    #[serde(rename = "Name")]
+    pub name: String,
+    /// Polecenie dla modelu, np. "Zamień tekst na listę punktów"
+    #[serde(rename = "Instruction")]
+    pub instruction: String,
+    /// Pusty: wbudowany prompt korekty (albo prompt języka wejścia)
+    #[serde(rename = "SystemPrompt", default)]
+    pub system_prompt: String,
+}
+
+/// Style wykonywane po kolei przez jednego dostawcę
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Pipeline {
+    #[serde(rename = "Name")]
+    pub name: String,
+    /// Nazwa dostawcy, np. "OpenAI"
+    #[serde(rename = "Provider")]
+    pub provider: String,
+    /// Identyfikatory stylów, np. ["normal", "professional", "translate_en"]
+    #[serde(rename = "Styles")]
+    pub styles: Vec<String>,
+}
+
+/// Zadanie wsadowe uruchamiane codziennie o podanej godzinie
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Job {
+    #[serde(rename = "Name")]
+    pub name: String,
+    /// "folder" (korekta plików z katalogu) albo "digest" (raport tygodniowy)
+    #[serde(rename = "Kind")]
+    pub kind: String,
+    /// Godzina czasu lokalnego, np. "02:30"
+    #[serde(rename = "At")]
+    pub at: String,
+    /// Dni tygodnia 1–7 (poniedziałek = 1); pusta lista = codziennie
+    #[serde(rename = "Days", default)]
+    pub days: Vec<u32>,
+    /// Katalog z plikami .txt/.md do korekty
+    #[serde(rename = "Folder", default)]
+    pub folder: String,
+    /// Katalog wyników; pusty = podkatalog "poprawione"
+    #[serde(rename = "Output", default)]
+    pub output: String,
+    /// Nazwa dostawcy; pusta = pierwszy z kluczem API
+    #[serde(rename = "Provider", default)]
+    pub provider: String,
+    /// Identyfikator stylu; pusty = standardowa korekta
+    #[serde(rename = "Style", default)]
+    pub style: String,
+}
+
+/// Cena modelu w USD za milion tokenów
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct ModelPrice {
+    #[serde(rename = "Input")]
+    pub input: f64,
+    #[serde(rename = "Output")]
+    pub output: f64,
+}
+
+fn default_pricing() -> BTreeMap<String, ModelPrice> {
+    [
+        ("gpt-5-mini", 0.25, 2.0),
+        ("claude-3-7-sonnet-latest", 3.0, 15.0),
+        ("gemini-2.5-flash", 0.30, 2.50),
+        ("deepseek-chat", 0.27, 1.10),
+        ("mistral-small-latest", 0.10, 0.30),
+        ("llama-3.3-70b-versatile", 0.59, 0.79),
+    ]
+    .into_iter()
+    .map(|(model, input, output)| (model.to_string(), ModelPrice { input, output }))
+    .collect()
+}
+
+const EXPORT_HEADER: &str = "# Ustawienia PoprawiaczTekstuRs bez kluczy API.\n\
+# Przy imporcie klucze zapisane na komputerze docelowym zostają zachowane.\n\n";
+
 impl Config {
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self::load_with_warnings(path)?.0)
+    }
+
+    /// Konfiguracja z pliku i ostrzeżenia o wartościach zastąpionych domyślnymi
+    pub fn load_with_warnings<P: AsRef<Path>>(path: P) -> Result<(Self, Vec<String>), Box<dyn std::error::Error>> {
         let content = fs::read_to_string(path)?;
-        let config: Config = toml::from_str(&content)?;
-        Ok(config)
+        LOAD_WARNINGS.with(|warnings| warnings.borrow_mut().clear());
+        let parsed = toml::from_str::<Config>(&content);
+        let warnings = LOAD_WARNINGS.with(RefCell::take);
+        Ok((parsed?.migrate(&content), warnings))
+    }
+
+    /// Uzupełnia ustawienia dodane później na podstawie starszych pól z `content`
+    fn migrate(mut self, content: &str) -> Self {
+        // Historia zapisywała się dawniej tylko przy włączonym raporcie nauki
+        let has_history_switch = toml::from_str::<toml::Table>(content)
+            .ok()
+            .is_some_and(|table| table.get("history").and_then(|history| history.get("Enabled")).is_some());
+        if !has_history_switch {
+            self.history.enabled = self.settings.learning_digest;
+        }
+        self
+    }
+
+    /// Konfiguracja z pliku albo domyślna, z problemami do pokazania użytkownikowi;
+    /// niewczytywalny plik zostaje skopiowany obok, żeby autozapis nie nadpisał kluczy API
+    pub fn load_reporting<P: AsRef<Path>>(path: P) -> (Self, Vec<String>) {
+        let path = path.as_ref();
+        if !path.exists() {
+            return (Self::default(), Vec::new());
+        }
+        match Self::load_with_warnings(path) {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                tracing::error!("Invalid config {}: {}", path.display(), e);
+                let backup = path.with_extension("toml.invalid");
+                let problem = match fs::copy(path, &backup) {
+                    Ok(_) => format!("{}: {}; kopia pliku: {}", path.display(), e, backup.display()),
+                    Err(copy_error) => {
+                        tracing::error!("Could not back up config to {}: {}", backup.display(), copy_error);
+                        format!("{}: {}", path.display(), e)
+                    }
+                };
+                (Self::default(), vec![problem])
+            }
+        }
+    }
+
+    /// Konfiguracja z pliku albo domyślna; problemy trafiają tylko do logu
+    pub fn load_or_default<P: AsRef<Path>>(path: P) -> Self {
+        Self::load_reporting(path).0
     }
 
     pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn std::error::Error>> {
@@ -93,10 +1110,46 @@ impl Config {
             fs::create_dir_all(parent)?;
         }
         let toml_string = toml::to_string_pretty(self)?;
-        fs::write(path, toml_string)?;
+
+        // Zapis przez plik tymczasowy: przerwany proces zostawia stary albo nowy plik, nigdy ucięty
+        let temp_path = path.with_extension("toml.tmp");
+        fs::write(&temp_path, toml_string)?;
+        fs::rename(&temp_path, path)?;
         Ok(())
     }
 
+    /// TOML do przeniesienia na inny komputer, bez kluczy API
+    pub fn export_without_secrets(&self) -> Result<String, Box<dyn std::error::Error>> {
+        let mut sanitized = self.clone();
+        sanitized.api_keys = ApiKeys {
+            openai: String::new(),
+            anthropic: String::new(),
+            gemini: String::new(),
+            deepseek: String::new(),
+            mistral: String::new(),
+            groq: String::new(),
+        };
+        Ok(format!("{}{}", EXPORT_HEADER, toml::to_string_pretty(&sanitized)?))
+    }
+
+    /// Konfiguracja z eksportu; puste klucze API zostają zastąpione kluczami z `self`
+    pub fn import_keeping_secrets(&self, content: &str) -> Result<Config, Box<dyn std::error::Error>> {
+        let mut imported = toml::from_str::<Config>(content)?.migrate(content);
+        for (key, current) in [
+            (&mut imported.api_keys.openai, &self.api_keys.openai),
+            (&mut imported.api_keys.anthropic, &self.api_keys.anthropic),
+            (&mut imported.api_keys.gemini, &self.api_keys.gemini),
+            (&mut imported.api_keys.deepseek, &self.api_keys.deepseek),
+            (&mut imported.api_keys.mistral, &self.api_keys.mistral),
+            (&mut imported.api_keys.groq, &self.api_keys.groq),
+        ] {
+            if key.trim().is_empty() {
+                key.clone_from(current);
+            }
+        }
+        Ok(imported)
+    }
+
     pub fn get_config_path() -> PathBuf {
         if let Some(config_dir) = dirs::config_dir() {
             let app_config_dir = config_dir.join("poprawiacz-tekstu-rs");
@@ -120,8 +1173,100 @@ mod tests {
         assert_eq!(config.models.anthropic, "claude-3-7-sonnet-latest");
         assert_eq!(config.models.gemini, "gemini-2.5-flash");
         assert_eq!(config.models.deepseek, "deepseek-chat");
+        assert_eq!(config.models.mistral, "mistral-small-latest");
+        assert_eq!(config.models.groq, "llama-3.3-70b-versatile");
         assert_eq!(config.settings.auto_startup, false);
-        assert_eq!(config.ai_settings.reasoning_effort, "high");
+        assert_eq!(config.ai_settings.reasoning_effort, ReasoningEffort::High);
+    }
+
+    #[test]
+    fn test_sections_without_mistral_load() {
+        let keys: ApiKeys = toml::from_str("OpenAI = \"a\"\nAnthropic = \"b\"\nGemini = \"c\"\nDeepSeek = \"d\"").unwrap();
+        assert!(keys.mistral.is_empty());
+        assert!(keys.groq.is_empty());
+        let models: Models = toml::from_str("OpenAI = \"a\"\nAnthropic = \"b\"\nGemini = \"c\"\nDeepSeek = \"d\"").unwrap();
+        assert_eq!(models.mistral, "mistral-small-latest");
+        assert_eq!(models.groq, "llama-3.3-70b-versatile");
+    }
+
+    #[test]
+    fn test_typed_choices() {
+        let ai: AiSettings = toml::from_str("ReasoningEffort = \"Medium\"\nVerbosity = \" low \"").unwrap();
+        assert_eq!(ai.reasoning_effort, ReasoningEffort::Medium);
+        assert_eq!(ai.verbosity, Verbosity::Low);
+        assert!(toml::to_string(&ai).unwrap().contains("ReasoningEffort = \"medium\""));
+
+        let ai: AiSettings = toml::from_str("ReasoningEffort = \"extreme\"\nVerbosity = 3").unwrap();
+        assert_eq!(ai.reasoning_effort, ReasoningEffort::High);
+        assert_eq!(ai.verbosity, Verbosity::Medium);
+
+        let mut content = toml::to_string(&Config::default()).unwrap();
+        assert!(content.contains("DefaultStyle = \"normal\""));
+        content = content.replace("DefaultStyle = \"normal\"", "DefaultStyle = \"Translate_EN\"");
+        let config: Config = toml::from_str(&content).unwrap();
+        assert_eq!(config.settings.default_style, CorrectionStyle::TranslateEn);
+    }
+
+    #[test]
+    fn test_invalid_choices_fall_back_per_field() {
+        let mut config = Config::default();
+        config.api_keys.openai = "sk-test".to_string();
+        config.global_hotkey.styles.insert("Ctrl+Alt+P".to_string(), CorrectionStyle::Professional);
+        config.global_hotkey.styles.insert("Ctrl+Alt+S".to_string(), CorrectionStyle::Summary);
+        let content = toml::to_string(&config)
+            .unwrap()
+            .replace("DefaultStyle = \"normal\"", "DefaultStyle = \"poetic\"")
+            .replace("ReasoningEffort = \"high\"", "ReasoningEffort = \"extreme\"")
+            .replace("= \"summary\"", "= \"concise\"");
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+
+        let (loaded, warnings) = Config::load_with_warnings(temp_file.path()).unwrap();
+        assert_eq!(loaded.api_keys.openai, "sk-test");
+        assert_eq!(loaded.settings.default_style, CorrectionStyle::Normal);
+        assert_eq!(loaded.ai_settings.reasoning_effort, ReasoningEffort::High);
+        assert_eq!(loaded.global_hotkey.styles.len(), 1);
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings.iter().any(|w| w.starts_with("invalid style \"poetic\"")));
+        assert!(warnings.iter().any(|w| w.starts_with("invalid ReasoningEffort \"extreme\"")));
+        assert!(warnings.iter().any(|w| w.starts_with("shortcut Ctrl+Alt+S: invalid style \"concise\"")));
+
+        assert_eq!(Config::load_with_warnings(temp_file.path()).unwrap().1.len(), 3);
+    }
+
+    #[test]
+    fn test_app_styles_skip_rules_with_unknown_style() {
+        let mut content = toml::to_string(&Config::default()).unwrap().replace("app_styles = []\n", "");
+        content.push_str(concat!(
+            "\n[[app_styles]]\nClass = \"thunderbird\"\nStyle = \"Professional\"\n",
+            "\n[[app_styles]]\nClass = \"slack\"\nStyle = \"formal\"\n",
+            "\n[[app_styles]]\nTitle = \"DeepL\"\nStyle = \"skip\"\n",
+        ));
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), content).unwrap();
+
+        let (config, warnings) = Config::load_with_warnings(temp_file.path()).unwrap();
+        let styles: Vec<AppChoice> = config.app_styles.iter().map(|rule| rule.style).collect();
+        assert_eq!(styles, [AppChoice::Style(CorrectionStyle::Professional), AppChoice::Skip]);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("app_styles rule 2: invalid app style \"formal\""));
+        assert!(toml::to_string(&config).unwrap().contains("Style = \"professional\""));
+    }
+
+    #[test]
+    fn test_load_reporting_keeps_copy_of_unreadable_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "[api_keys\nOpenAI = \"sk-test\"").unwrap();
+
+        let (config, problems) = Config::load_reporting(&path);
+        assert_eq!(config, Config::default());
+        assert_eq!(problems.len(), 1);
+        let backup = dir.path().join("config.toml.invalid");
+        assert!(problems[0].contains(&backup.display().to_string()));
+        assert_eq!(fs::read_to_string(backup).unwrap(), "[api_keys\nOpenAI = \"sk-test\"");
+
+        assert_eq!(Config::load_reporting(dir.path().join("missing.toml")), (Config::default(), Vec::new()));
     }
 
     #[test]
@@ -136,6 +1281,18 @@ mod tests {
         assert_eq!(original_config, loaded_config);
     }
 
+    #[test]
+    fn test_config_save_replaces_file_without_leftovers() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "stara zawartość").unwrap();
+
+        Config::default().save(&path).unwrap();
+
+        assert_eq!(Config::load(&path).unwrap(), Config::default());
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
     #[test]
     fn test_config_serialization() {
         let config = Config::default();
@@ -147,6 +1304,305 @@ mod tests {
         assert!(toml_str.contains("DeepSeek"));
     }
 
+    #[test]
+    fn test_auto_hide_and_present_thresholds() {
+        let mut settings = Config::default().settings;
+        assert!(settings.should_auto_hide(0));
+        assert!(settings.should_auto_present(0));
+
+        settings.auto_hide_min_results = 3;
+        settings.auto_present_results = 2;
+        assert!(!settings.should_auto_hide(2));
+        assert!(settings.should_auto_hide(3));
+        assert!(!settings.should_auto_present(1));
+        assert!(settings.should_auto_present(2));
+
+        settings.results_popup = true;
+        assert!(!settings.should_auto_present(4));
+
+        // Bez schowania okna wynik jest tylko kopiowany, nie wklejany
+        assert_eq!(settings.delivery(2), Delivery::CopyOnly);
+        assert_eq!(settings.delivery(3), Delivery::HideAndPaste);
+    }
+
+    #[test]
+    fn test_panel_order() {
+        let names = ["OpenAI", "Anthropic", "Gemini", "DeepSeek"];
+        let mut settings = Config::default().settings;
+        assert_eq!(settings.panel_order(&names), vec![0, 1, 2, 3]);
+
+        settings.panel_order = vec!["deepseek".into(), "Mistral".into(), "DeepSeek".into(), "Gemini".into()];
+        assert_eq!(settings.panel_order(&names), vec![3, 2, 0, 1]);
+
+        settings.swap_panels(&names, 0, 3);
+        assert_eq!(settings.panel_order, vec!["OpenAI", "Gemini", "DeepSeek", "Anthropic"]);
+    }
+
+    #[test]
+    fn test_styleguide_roundtrip() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let mut config = Config::default();
+        config.styleguide.enabled = true;
+        config.styleguide.forbidden_phrases = vec!["na dzień dzisiejszy".to_string()];
+        config
+            .styleguide
+            .preferred_spellings
+            .insert("mail".to_string(), "e-mail".to_string());
+        config.styleguide.capitalization = vec!["GitHub".to_string()];
+
+        config.save(temp_file.path()).unwrap();
+        let loaded = Config::load(temp_file.path()).unwrap();
+        assert_eq!(loaded.styleguide, config.styleguide);
+        assert!(loaded.styleguide.is_active());
+    }
+
+    #[test]
+    fn test_polling_intervals_are_clamped() {
+        let mut polling = Polling::default();
+        assert_eq!(polling.hotkey_interval(), Duration::from_millis(50));
+        assert_eq!(polling.tray_interval(), Duration::from_millis(100));
+
+        polling.hotkey_interval_ms = 0;
+        polling.tray_interval_ms = 60_000;
+        assert_eq!(polling.hotkey_interval(), Duration::from_millis(MIN_POLL_INTERVAL_MS));
+        assert_eq!(polling.tray_interval(), Duration::from_millis(MAX_POLL_INTERVAL_MS));
+
+        let partial: Polling = toml::from_str("PowerSave = true").unwrap();
+        assert!(partial.power_save);
+        assert_eq!(partial.hotkey_interval_ms, 50);
+    }
+
+    #[test]
+    fn test_circuit_breaker_defaults() {
+        let settings: CircuitBreakerSettings = toml::from_str("CooldownMinutes = 10").unwrap();
+        assert!(settings.enabled);
+        assert_eq!(settings.failure_threshold, 3);
+        assert_eq!(settings.cooldown(), Duration::from_secs(600));
+
+        let settings = CircuitBreakerSettings {
+            cooldown_minutes: u64::MAX,
+            ..CircuitBreakerSettings::default()
+        };
+        assert_eq!(settings.cooldown(), Duration::from_secs(MAX_COOLDOWN_MINUTES * 60));
+    }
+
+    #[test]
+    fn test_double_press_defaults_and_clamp() {
+        let mut double_press: DoublePress = toml::from_str("Action = \"palette\"").unwrap();
+        assert_eq!(double_press.action, "palette");
+        assert_eq!(double_press.window(), Duration::from_millis(500));
+
+        double_press.window_ms = 5;
+        assert_eq!(double_press.window(), Duration::from_millis(MIN_DOUBLE_PRESS_MS));
+        assert_eq!(Config::default().double_press.action, "none");
+    }
+
+    #[test]
+    fn test_global_hotkey_default() {
+        let hotkey: GlobalHotkey = toml::from_str("").unwrap();
+        assert_eq!(hotkey.combination, "Ctrl+Shift+C");
+        assert_eq!(Config::default().global_hotkey, hotkey);
+
+        let hotkey: GlobalHotkey = toml::from_str("[Styles]\n\"Ctrl+Shift+T\" = \"translate_en\"").unwrap();
+        assert_eq!(hotkey.styles["Ctrl+Shift+T"], CorrectionStyle::TranslateEn);
+    }
+
+    #[test]
+    fn test_conversation_defaults() {
+        let conversation: ConversationSettings = toml::from_str("Enabled = true").unwrap();
+        assert!(conversation.enabled);
+        assert_eq!(conversation.exchanges, 3);
+        assert!(!Config::default().conversation.enabled);
+
+        let zero: ConversationSettings = toml::from_str("Exchanges = 0").unwrap();
+        assert_eq!(zero.limit(), 1);
+    }
+
+    #[test]
+    fn test_budget_limits() {
+        let budget: BudgetSettings = toml::from_str("[Limits]\nOpenAI = 5.0\nGemini = 0.0").unwrap();
+        assert_eq!(budget.limit("OpenAI"), Some(5.0));
+        assert_eq!(budget.limit("Gemini"), None);
+        assert_eq!(budget.limit("DeepSeek"), None);
+        assert!(!budget.disable_at_limit);
+    }
+
+    #[test]
+    fn test_history_switch_migrates_from_learning_digest() {
+        assert!(!Config::default().history.enabled);
+
+        let mut old = Config::default();
+        old.settings.learning_digest = true;
+        let mut table = toml::Table::try_from(&old).unwrap();
+        table["history"].as_table_mut().unwrap().remove("Enabled");
+        let content = toml::to_string(&table).unwrap();
+        let migrated = toml::from_str::<Config>(&content).unwrap().migrate(&content);
+        assert!(migrated.history.enabled, "Old configs with the digest on keep recording history");
+
+        let mut explicit = old.clone();
+        explicit.history.enabled = false;
+        let content = toml::to_string(&explicit).unwrap();
+        assert!(!toml::from_str::<Config>(&content).unwrap().migrate(&content).history.enabled);
+    }
+
+    #[test]
+    fn test_audit_defaults_to_metadata_only() {
+        let audit: AuditSettings = toml::from_str("Enabled = true").unwrap();
+        assert!(audit.enabled);
+        assert!(!audit.include_content);
+        assert!(!Config::default().audit.enabled);
+    }
+
+    #[test]
+    fn test_edit_mode_applies_to_long_texts() {
+        let mut edit_mode: EditModeSettings = toml::from_str("MinChars = 5").unwrap();
+        assert!(!edit_mode.applies_to("długi tekst"));
+
+        edit_mode.enabled = true;
+        assert!(edit_mode.applies_to("żółty"));
+        assert!(!edit_mode.applies_to("żółw"));
+        assert_eq!(Config::default().edit_mode.min_chars, 1500);
+    }
+
+    #[test]
+    fn test_pipelines_roundtrip() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let config = Config {
+            pipelines: vec![Pipeline {
+                name: "Do klienta".to_string(),
+                provider: "OpenAI".to_string(),
+                styles: vec!["normal".to_string(), "professional".to_string()],
+            }],
+            ..Config::default()
+        };
+
+        config.save(temp_file.path()).unwrap();
+        let content = fs::read_to_string(temp_file.path()).unwrap();
+        assert!(content.contains("[[pipelines]]"));
+        assert_eq!(Config::load(temp_file.path()).unwrap().pipelines, config.pipelines);
+        assert!(Config::default().pipelines.is_empty());
+    }
+
+    #[test]
+    fn test_custom_styles_roundtrip() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let config = Config {
+            custom_styles: vec![CustomStyle {
+                name: "Lista".to_string(),
+                instruction: "Zamień na listę punktów".to_string(),
+                system_prompt: String::new(),
+            }],
+            ..Config::default()
+        };
+
+        config.save(temp_file.path()).unwrap();
+        let content = fs::read_to_string(temp_file.path()).unwrap();
+        assert!(content.contains("[[custom_styles]]"));
+        assert_eq!(Config::load(temp_file.path()).unwrap().custom_styles, config.custom_styles);
+
+        // Bez SystemPrompt obowiązuje wbudowany
+        let style: CustomStyle = toml::from_str("Name = \"Lista\"\nInstruction = \"x\"").unwrap();
+        assert!(style.system_prompt.is_empty());
+    }
+
+    #[test]
+    fn test_jobs_optional_fields() {
+        let job: Job = toml::from_str("Name = \"Raport\"\nKind = \"digest\"\nAt = \"08:00\"").unwrap();
+
+        assert_eq!(job.at, "08:00");
+        assert!(job.days.is_empty());
+        assert!(job.folder.is_empty() && job.provider.is_empty());
+        assert!(Config::default().jobs.is_empty());
+    }
+
+    #[test]
+    fn test_network_roundtrip() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let mut config = Config::default();
+        config.network.ip_version = "ipv4".to_string();
+        config
+            .network
+            .dns_overrides
+            .insert("api.openai.com".to_string(), "162.159.140.245".to_string());
+
+        config.save(temp_file.path()).unwrap();
+        let content = fs::read_to_string(temp_file.path()).unwrap();
+        assert!(content.contains("[network.DnsOverrides]"));
+        assert_eq!(Config::load(temp_file.path()).unwrap().network, config.network);
+        assert_eq!(toml::from_str::<Network>("").unwrap(), Network::default());
+    }
+
+    #[test]
+    fn test_snippets_roundtrip() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let config = Config {
+            snippets: vec![Snippet {
+                name: "Podpis".to_string(),
+                text: "Pozdrawiam,\nJan".to_string(),
+            }],
+            ..Config::default()
+        };
+
+        config.save(temp_file.path()).unwrap();
+        let content = fs::read_to_string(temp_file.path()).unwrap();
+        assert!(content.contains("[[snippets]]"));
+        assert_eq!(Config::load(temp_file.path()).unwrap().snippets, config.snippets);
+    }
+
+    #[test]
+    fn test_export_omits_keys_and_import_keeps_local_ones() {
+        let mut source = Config::default();
+        source.api_keys.openai = "sk-source-secret".to_string();
+        source.models.gemini = "gemini-2.5-pro".to_string();
+        source.settings.highlight_diffs = true;
+
+        let exported = source.export_without_secrets().unwrap();
+        assert!(!exported.contains("sk-source-secret"));
+        assert!(exported.starts_with("# "));
+
+        let mut target = Config::default();
+        target.api_keys.openai = "sk-target".to_string();
+        target.api_keys.deepseek = "sk-target-deepseek".to_string();
+        let imported = target.import_keeping_secrets(&exported).unwrap();
+
+        assert_eq!(imported.api_keys.openai, "sk-target");
+        assert_eq!(imported.api_keys.deepseek, "sk-target-deepseek");
+        assert_eq!(imported.api_keys.anthropic, "");
+        assert_eq!(imported.models.gemini, "gemini-2.5-pro");
+        assert!(imported.settings.highlight_diffs);
+    }
+
+    #[test]
+    fn test_import_prefers_keys_present_in_file() {
+        let mut source = Config::default();
+        source.api_keys.gemini = "AIza-file".to_string();
+        let content = toml::to_string_pretty(&source).unwrap();
+
+        let mut target = Config::default();
+        target.api_keys.gemini = "AIza-local".to_string();
+        assert_eq!(target.import_keeping_secrets(&content).unwrap().api_keys.gemini, "AIza-file");
+        assert!(target.import_keeping_secrets("not = [valid").is_err());
+    }
+
+    #[test]
+    fn test_pricing_defaults_cover_default_models() {
+        let config = Config::default();
+        for model in [
+            &config.models.openai,
+            &config.models.anthropic,
+            &config.models.gemini,
+            &config.models.deepseek,
+            &config.models.mistral,
+            &config.models.groq,
+        ] {
+            assert!(config.pricing.contains_key(model), "no price for {}", model);
+        }
+
+        let temp_file = NamedTempFile::new().unwrap();
+        config.save(temp_file.path()).unwrap();
+        assert_eq!(Config::load(temp_file.path()).unwrap().pricing, config.pricing);
+    }
+
     #[test]
     fn test_config_fields_exist() {
         let config = Config::default();
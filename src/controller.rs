@@ -0,0 +1,466 @@
+//! Przebieg sesji poprawiania bez GTK i bez sieci
+//!
+//! `SessionController` pilnuje numeru sesji, flag anulowania i stanu każdego
+//! dostawcy: rozsyła zapytania do wstrzykniętej implementacji
+//! `SessionBackend`, liczy gotowe wyniki, odrzuca odpowiedzi anulowanych
+//! zapytań i zastąpionych sesji oraz układa wyniki w kolejności wyboru.
+//! Okno główne podaje mu prawdziwe API, testy - atrapy.
+
+use crate::api::token_usage::TokenUsage;
+use futures::future::BoxFuture;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::runtime::Handle;
+
+/// Komunikaty z zadań dostawców do wątku okna
+#[derive(Debug, Clone, PartialEq)]
+pub enum SessionMessage {
+    Chunk(usize, String),
+    /// Wynik przekroczył limit znaków; model dostał prośbę o skrócenie
+    Shortening(usize, usize),
+    /// Dostawca odrzucił zapytanie limitem; ponowienie po podanym czasie
+    RateLimited(usize, Duration),
+    /// Zużycie tokenów podane przez dostawcę (przy skracaniu wyniku więcej niż raz)
+    Usage(usize, TokenUsage),
+    Done(usize, Result<String, String>),
+}
+
+/// Komunikat przekazany odbiorcy sesji
+#[derive(Debug, Clone, PartialEq)]
+pub enum Delivery {
+    Current(SessionMessage),
+    /// Wynik, który przyszedł po rozpoczęciu nowej sesji (do kosztów i wyłącznika)
+    Stale(usize, Result<String, String>),
+}
+
+/// Kanał zadania jednego dostawcy: fragmenty strumienia, prośba o skrócenie i oczekiwanie na limit
+#[derive(Clone)]
+pub struct ProviderEvents {
+    index: usize,
+    tx: async_channel::Sender<SessionMessage>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl ProviderEvents {
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::SeqCst)
+    }
+
+    /// Fragment odpowiedzi; po anulowaniu nic nie wysyła
+    pub fn chunk(&self, chunk: &str) {
+        if !self.is_cancelled() {
+            let _ = self.tx.try_send(SessionMessage::Chunk(self.index, chunk.to_string()));
+        }
+    }
+
+    pub async fn shortening(&self, chars: usize) {
+        let _ = self.tx.send(SessionMessage::Shortening(self.index, chars)).await;
+    }
+
+    /// Zapytanie czeka w kolejce limitów dostawcy; po anulowaniu nic nie wysyła
+    pub fn rate_limited(&self, wait: Duration) {
+        if !self.is_cancelled() {
+            let _ = self.tx.try_send(SessionMessage::RateLimited(self.index, wait));
+        }
+    }
+
+    /// Zużycie tokenów; wysyłane także po anulowaniu, bo zapytanie i tak kosztowało
+    pub fn usage(&self, usage: TokenUsage) {
+        let _ = self.tx.try_send(SessionMessage::Usage(self.index, usage));
+    }
+}
+
+/// Wykonuje zapytania sesji; `R` to zapytanie w postaci ustalonej przez wywołującego
+pub trait SessionBackend<R>: Send + Sync {
+    fn send(&self, index: usize, request: R, events: ProviderEvents) -> BoxFuture<'static, Result<String, String>>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotState {
+    Idle,
+    Running,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Slot {
+    state: SlotState,
+    started: Option<Instant>,
+    latency: Option<Duration>,
+}
+
+impl Default for Slot {
+    fn default() -> Self {
+        Self {
+            state: SlotState::Idle,
+            started: None,
+            latency: None,
+        }
+    }
+}
+
+/// Wynik zakończonej sesji
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionSummary {
+    pub completed: u32,
+    pub failed: u32,
+    pub cancelled: u32,
+}
+
+/// Stan sesji współdzielony przez okno i zadania dostawców
+#[derive(Clone)]
+pub struct SessionController {
+    session: Arc<AtomicU64>,
+    cancel_flags: Vec<Arc<AtomicBool>>,
+    slots: Arc<Mutex<Vec<Slot>>>,
+}
+
+impl SessionController {
+    pub fn new(providers: usize) -> Self {
+        Self {
+            session: Arc::new(AtomicU64::new(0)),
+            cancel_flags: (0..providers).map(|_| Arc::new(AtomicBool::new(false))).collect(),
+            slots: Arc::new(Mutex::new(vec![Slot::default(); providers])),
+        }
+    }
+
+    pub fn session(&self) -> u64 {
+        self.session.load(Ordering::SeqCst)
+    }
+
+    /// Czy sesja została już zastąpiona nową
+    pub fn is_stale(&self, session: u64) -> bool {
+        self.session() != session
+    }
+
+    /// Nowa sesja: wszyscy dostawcy czekają, flagi anulowania są zdjęte
+    pub fn start(&self) -> u64 {
+        let session = self.session.fetch_add(1, Ordering::SeqCst) + 1;
+        for flag in &self.cancel_flags {
+            flag.store(false, Ordering::SeqCst);
+        }
+        self.slots.lock().unwrap().fill(Slot::default());
+        session
+    }
+
+    /// Zapytanie dostawcy rusza (także ponownie); jego poprzedni wynik przestaje się liczyć
+    pub fn begin(&self, index: usize) {
+        self.begin_at(index, Instant::now());
+    }
+
+    fn begin_at(&self, index: usize, now: Instant) {
+        self.cancel_flags[index].store(false, Ordering::SeqCst);
+        self.slots.lock().unwrap()[index] = Slot {
+            state: SlotState::Running,
+            started: Some(now),
+            latency: None,
+        };
+    }
+
+    /// Dostawca nie dostał zapytania (wyłącznik, budżet, potok innego dostawcy)
+    pub fn skip(&self, index: usize) {
+        self.slots.lock().unwrap()[index] = Slot::default();
+    }
+
+    /// Zapisuje wynik trwającego zapytania i zwraca liczbę gotowych wyników
+    pub fn finish(&self, index: usize, ok: bool) -> u32 {
+        self.finish_at(index, ok, Instant::now())
+    }
+
+    fn finish_at(&self, index: usize, ok: bool, now: Instant) -> u32 {
+        let mut slots = self.slots.lock().unwrap();
+        let slot = &mut slots[index];
+        if slot.state == SlotState::Running {
+            slot.state = if ok { SlotState::Done } else { SlotState::Failed };
+            slot.latency = slot.started.map(|started| now.saturating_duration_since(started));
+        }
+        drop(slots);
+        self.completed()
+    }
+
+    /// Anuluje zapytanie dostawcy; zwraca `true`, jeśli jeszcze trwało
+    pub fn cancel(&self, index: usize) -> bool {
+        self.cancel_flags[index].store(true, Ordering::SeqCst);
+        let mut slots = self.slots.lock().unwrap();
+        let running = slots[index].state == SlotState::Running;
+        if running {
+            slots[index].state = SlotState::Cancelled;
+        }
+        running
+    }
+
+    pub fn cancel_all(&self) {
+        for index in 0..self.cancel_flags.len() {
+            self.cancel(index);
+        }
+    }
+
+    pub fn is_cancelled(&self, index: usize) -> bool {
+        self.cancel_flags[index].load(Ordering::SeqCst)
+    }
+
+    pub fn state(&self, index: usize) -> SlotState {
+        self.slots.lock().unwrap()[index].state
+    }
+
+    pub fn completed(&self) -> u32 {
+        self.count(SlotState::Done)
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.count(SlotState::Running) > 0
+    }
+
+    fn count(&self, state: SlotState) -> u32 {
+        self.slots.lock().unwrap().iter().filter(|s| s.state == state).count() as u32
+    }
+
+    pub fn summary(&self) -> SessionSummary {
+        SessionSummary {
+            completed: self.completed(),
+            failed: self.count(SlotState::Failed),
+            cancelled: self.count(SlotState::Cancelled),
+        }
+    }
+
+    /// Gotowe wyniki w kolejności wyboru: najpierw dostawca preferowany, potem najszybsi
+    pub fn ranking(&self, preferred: Option<usize>) -> Vec<usize> {
+        let slots = self.slots.lock().unwrap();
+        let mut ranked: Vec<usize> = (0..slots.len()).filter(|&i| slots[i].state == SlotState::Done).collect();
+        ranked.sort_by_key(|&i| (Some(i) != preferred, slots[i].latency.unwrap_or(Duration::MAX)));
+        ranked
+    }
+
+    /// Uruchamia zapytania na `runtime`; wyniki anulowanych zapytań nie są wysyłane
+    pub fn dispatch<R, B>(
+        &self,
+        runtime: &Handle,
+        requests: Vec<(usize, R)>,
+        backend: Arc<B>,
+    ) -> async_channel::Receiver<SessionMessage>
+    where
+        R: Send + 'static,
+        B: SessionBackend<R> + ?Sized + 'static,
+    {
+        let (tx, rx) = async_channel::unbounded();
+        for (index, request) in requests {
+            let events = ProviderEvents {
+                index,
+                tx: tx.clone(),
+                cancel: self.cancel_flags[index].clone(),
+            };
+            let backend = backend.clone();
+            runtime.spawn(async move {
+                let result = backend.send(index, request, events.clone()).await;
+                if !events.is_cancelled() {
+                    let _ = events.tx.send(SessionMessage::Done(index, result)).await;
+                }
+            });
+        }
+        rx
+    }
+
+    /// Przekazuje komunikaty sesji `on_message` do zakończenia wszystkich zapytań.
+    /// Wyniki liczy przed przekazaniem; dla zastąpionej sesji zwraca `None`.
+    pub async fn receive<F>(
+        &self,
+        session: u64,
+        rx: async_channel::Receiver<SessionMessage>,
+        mut on_message: F,
+    ) -> Option<SessionSummary>
+    where
+        F: FnMut(Delivery),
+    {
+        while let Ok(message) = rx.recv().await {
+            if self.is_stale(session) {
+                // Fragmenty zastąpionej sesji nikogo nie interesują
+                if let SessionMessage::Done(index, result) = message {
+                    on_message(Delivery::Stale(index, result));
+                }
+                continue;
+            }
+            if let SessionMessage::Done(index, result) = &message {
+                self.finish(*index, result.is_ok());
+            }
+            on_message(Delivery::Current(message));
+        }
+        (!self.is_stale(session)).then(|| self.summary())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::FutureExt;
+
+    /// Atrapa dostawcy: odpowiedź i opóźnienie dla każdego indeksu
+    struct MockBackend {
+        replies: Vec<Result<&'static str, &'static str>>,
+        delays_ms: Vec<u64>,
+    }
+
+    impl MockBackend {
+        fn new(replies: Vec<Result<&'static str, &'static str>>) -> Arc<Self> {
+            let delays_ms = vec![0; replies.len()];
+            Arc::new(Self { replies, delays_ms })
+        }
+
+        fn with_delays(replies: Vec<Result<&'static str, &'static str>>, delays_ms: Vec<u64>) -> Arc<Self> {
+            Arc::new(Self { replies, delays_ms })
+        }
+    }
+
+    impl SessionBackend<String> for MockBackend {
+        fn send(&self, index: usize, request: String, events: ProviderEvents) -> BoxFuture<'static, Result<String, String>> {
+            let reply = self.replies[index].map(|r| format!("{} {}", r, request)).map_err(String::from);
+            let delay = Duration::from_millis(self.delays_ms[index]);
+            async move {
+                events.chunk("…");
+                tokio::time::sleep(delay).await;
+                reply
+            }
+            .boxed()
+        }
+    }
+
+    fn start_all(controller: &SessionController, providers: usize) -> (u64, Vec<(usize, String)>) {
+        let session = controller.start();
+        for i in 0..providers {
+            controller.begin(i);
+        }
+        (session, (0..providers).map(|i| (i, "tekst".to_string())).collect())
+    }
+
+    async fn collect(controller: &SessionController, session: u64, rx: async_channel::Receiver<SessionMessage>) -> (Vec<Delivery>, Option<SessionSummary>) {
+        let mut deliveries = Vec::new();
+        let summary = controller.receive(session, rx, |d| deliveries.push(d)).await;
+        (deliveries, summary)
+    }
+
+    fn done(deliveries: &[Delivery]) -> Vec<(usize, Result<String, String>)> {
+        let mut done: Vec<_> = deliveries
+            .iter()
+            .filter_map(|d| match d {
+                Delivery::Current(SessionMessage::Done(i, r)) => Some((*i, r.clone())),
+                _ => None,
+            })
+            .collect();
+        done.sort_by_key(|(i, _)| *i);
+        done
+    }
+
+    #[tokio::test]
+    async fn test_counts_results_and_finalizes() {
+        let controller = SessionController::new(4);
+        let backend = MockBackend::new(vec![Ok("A"), Err("błąd"), Ok("C"), Ok("D")]);
+        let (session, requests) = start_all(&controller, 4);
+
+        let rx = controller.dispatch(&Handle::current(), requests, backend);
+        let (deliveries, summary) = collect(&controller, session, rx).await;
+
+        assert_eq!(
+            summary,
+            Some(SessionSummary {
+                completed: 3,
+                failed: 1,
+                cancelled: 0
+            })
+        );
+        assert_eq!(
+            done(&deliveries),
+            vec![
+                (0, Ok("A tekst".to_string())),
+                (1, Err("błąd".to_string())),
+                (2, Ok("C tekst".to_string())),
+                (3, Ok("D tekst".to_string())),
+            ]
+        );
+        assert!(deliveries.contains(&Delivery::Current(SessionMessage::Chunk(2, "…".to_string()))));
+        assert!(!controller.is_running());
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_result_is_dropped() {
+        let controller = SessionController::new(2);
+        let backend = MockBackend::with_delays(vec![Ok("A"), Ok("B")], vec![0, 200]);
+        let (session, requests) = start_all(&controller, 2);
+
+        let rx = controller.dispatch(&Handle::current(), requests, backend);
+        assert!(controller.cancel(1));
+        let (deliveries, summary) = collect(&controller, session, rx).await;
+
+        assert_eq!(done(&deliveries), vec![(0, Ok("A tekst".to_string()))]);
+        assert_eq!(
+            summary,
+            Some(SessionSummary {
+                completed: 1,
+                failed: 0,
+                cancelled: 1
+            })
+        );
+        assert!(!controller.cancel(1));
+    }
+
+    #[tokio::test]
+    async fn test_new_session_makes_results_stale() {
+        let controller = SessionController::new(2);
+        let backend = MockBackend::with_delays(vec![Ok("A"), Err("błąd")], vec![50, 50]);
+        let (session, requests) = start_all(&controller, 2);
+
+        let rx = controller.dispatch(&Handle::current(), requests, backend);
+        let next = controller.start();
+        assert!(controller.is_stale(session));
+        assert!(!controller.is_stale(next));
+        let (deliveries, summary) = collect(&controller, session, rx).await;
+
+        assert_eq!(summary, None);
+        assert_eq!(deliveries.len(), 2);
+        assert!(deliveries.iter().all(|d| matches!(d, Delivery::Stale(..))));
+        assert_eq!(controller.completed(), 0);
+    }
+
+    #[test]
+    fn test_rerun_recounts() {
+        let controller = SessionController::new(4);
+        controller.start();
+        for i in 0..3 {
+            controller.begin(i);
+        }
+        controller.skip(3);
+        assert_eq!(controller.finish(0, true), 1);
+        assert_eq!(controller.finish(1, true), 2);
+        assert_eq!(controller.finish(2, false), 2);
+
+        // Ponowne zapytanie zdejmuje gotowy wynik z licznika do czasu odpowiedzi
+        controller.begin(1);
+        assert_eq!(controller.completed(), 1);
+        assert!(controller.is_running());
+        assert_eq!(controller.finish(1, true), 2);
+
+        // Wynik niezaczętego zapytania nic nie zmienia
+        assert_eq!(controller.finish(3, true), 2);
+        assert_eq!(controller.state(3), SlotState::Idle);
+
+        controller.start();
+        assert_eq!(controller.completed(), 0);
+    }
+
+    #[test]
+    fn test_ranking() {
+        let controller = SessionController::new(4);
+        controller.start();
+        let t0 = Instant::now();
+        for (i, ms) in [(0, 900), (1, 300), (2, 500), (3, 100)] {
+            controller.begin_at(i, t0);
+            controller.finish_at(i, i != 3, t0 + Duration::from_millis(ms));
+        }
+
+        assert_eq!(controller.ranking(None), vec![1, 2, 0]);
+        assert_eq!(controller.ranking(Some(0)), vec![0, 1, 2]);
+        // Preferowany bez wyniku nie zmienia kolejności
+        assert_eq!(controller.ranking(Some(3)), vec![1, 2, 0]);
+    }
+}
@@ -0,0 +1,155 @@
+//! Tryb rozmowy: kolejne polecenia w obrębie sesji jako wieloturowa rozmowa
+//!
+//! Po pierwszej poprawce użytkownik może dopracować wyniki krótkim poleceniem
+//! („krócej”, „bardziej formalnie”). Każdy dostawca dostaje wtedy swoje
+//! ostatnie K wymian (wiadomość użytkownika i własną odpowiedź), więc
+//! poprawia swoją poprzednią wersję zamiast zaczynać od oryginału.
+
+use std::collections::{HashMap, VecDeque};
+
+/// Jedna wymiana: wiadomość użytkownika i odpowiedź modelu
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exchange {
+    pub user: String,
+    pub assistant: String,
+}
+
+/// Wiadomość użytkownika z poleceniem i tekstem do poprawy
+pub fn user_message(instruction_prompt: &str, text: &str) -> String {
+    format!("{}\n\n---\n{}\n---", instruction_prompt, text)
+}
+
+/// Wiadomość z poleceniem dopracowania poprzedniej odpowiedzi
+pub fn refinement_message(request: &str) -> String {
+    format!(
+        "{}\n\nApply this to your previous version of the text, keeping everything else unchanged. \
+         Return ONLY the revised text, without any additional headers, separators, or comments.",
+        request.trim()
+    )
+}
+
+/// Ostatnie wymiany z każdym dostawcą w bieżącej sesji
+#[derive(Debug, Clone, Default)]
+pub struct Conversation {
+    limit: usize,
+    system_prompt: String,
+    exchanges: HashMap<String, VecDeque<Exchange>>,
+}
+
+impl Conversation {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            ..Self::default()
+        }
+    }
+
+    /// Nowa sesja: zapomina poprzednie wymiany
+    pub fn start(&mut self, system_prompt: &str) {
+        self.system_prompt = system_prompt.to_string();
+        self.exchanges.clear();
+    }
+
+    /// System prompt sesji, wysyłany ponownie z każdym dopracowaniem
+    pub fn system_prompt(&self) -> &str {
+        &self.system_prompt
+    }
+
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        for exchanges in self.exchanges.values_mut() {
+            trim_front(exchanges, limit);
+        }
+    }
+
+    /// Dopisuje wymianę; najstarsze ponad limit wypadają
+    pub fn record(&mut self, provider: &str, exchange: Exchange) {
+        let exchanges = self.exchanges.entry(provider.to_string()).or_default();
+        exchanges.push_back(exchange);
+        trim_front(exchanges, self.limit);
+    }
+
+    /// Zapomina wymiany dostawcy (np. po ponownym zapytaniu od zera)
+    pub fn reset(&mut self, provider: &str) {
+        self.exchanges.remove(provider);
+    }
+
+    pub fn history(&self, provider: &str) -> Vec<Exchange> {
+        self.exchanges
+            .get(provider)
+            .map(|e| e.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn has_history(&self, provider: &str) -> bool {
+        self.exchanges.get(provider).is_some_and(|e| !e.is_empty())
+    }
+}
+
+fn trim_front(exchanges: &mut VecDeque<Exchange>, limit: usize) {
+    while exchanges.len() > limit {
+        exchanges.pop_front();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exchange(n: usize) -> Exchange {
+        Exchange {
+            user: format!("pytanie {}", n),
+            assistant: format!("odpowiedź {}", n),
+        }
+    }
+
+    #[test]
+    fn test_keeps_last_exchanges_per_provider() {
+        let mut conversation = Conversation::new(2);
+        conversation.start("System");
+        for n in 1..=3 {
+            conversation.record("OpenAI", exchange(n));
+        }
+        conversation.record("Gemini", exchange(9));
+
+        assert_eq!(conversation.history("OpenAI"), vec![exchange(2), exchange(3)]);
+        assert_eq!(conversation.history("Gemini"), vec![exchange(9)]);
+        assert!(conversation.history("DeepSeek").is_empty());
+        assert_eq!(conversation.system_prompt(), "System");
+    }
+
+    #[test]
+    fn test_start_and_reset_forget_exchanges() {
+        let mut conversation = Conversation::new(3);
+        conversation.record("OpenAI", exchange(1));
+        conversation.record("Anthropic", exchange(1));
+
+        conversation.reset("OpenAI");
+        assert!(!conversation.has_history("OpenAI"));
+        assert!(conversation.has_history("Anthropic"));
+
+        conversation.start("Nowy");
+        assert!(!conversation.has_history("Anthropic"));
+    }
+
+    #[test]
+    fn test_set_limit_trims_and_zero_keeps_nothing() {
+        let mut conversation = Conversation::new(3);
+        for n in 1..=3 {
+            conversation.record("OpenAI", exchange(n));
+        }
+
+        conversation.set_limit(1);
+        assert_eq!(conversation.history("OpenAI"), vec![exchange(3)]);
+
+        conversation.set_limit(0);
+        conversation.record("OpenAI", exchange(4));
+        assert!(!conversation.has_history("OpenAI"));
+    }
+
+    #[test]
+    fn test_messages() {
+        assert_eq!(user_message("Correct", "tekst"), "Correct\n\n---\ntekst\n---");
+        assert!(refinement_message("  krócej \n").starts_with("krócej\n\n"));
+    }
+}
@@ -35,6 +35,60 @@ pub fn compute_diff(original: &str, corrected: &str) -> Vec<DiffChange> {
     changes
 }
 
+/// Renders the corrected text as Markdown with tracked changes:
+/// removed words as `~~strikethrough~~`, added words in `**bold**`
+pub fn to_markdown(original: &str, corrected: &str) -> String {
+    let mut out = String::new();
+    let mut deleted = String::new();
+    let mut inserted = String::new();
+
+    for change in compute_diff(original, corrected) {
+        match change {
+            DiffChange::Delete(text) => deleted.push_str(&text),
+            DiffChange::Insert(text) => inserted.push_str(&text),
+            DiffChange::Equal(text) => {
+                flush_markdown_run(&mut out, &mut deleted, &mut inserted);
+                out.push_str(&text);
+            }
+        }
+    }
+    flush_markdown_run(&mut out, &mut deleted, &mut inserted);
+    out
+}
+
+/// Emits one run of changes; whitespace of the corrected text stays outside the markers
+fn flush_markdown_run(out: &mut String, deleted: &mut String, inserted: &mut String) {
+    let removed = deleted.trim();
+    let added = inserted.trim();
+
+    if added.is_empty() && !removed.is_empty() {
+        // Removed words keep their own spacing so neighbours don't run together
+        out.push_str(leading_whitespace(deleted));
+        out.push_str(&format!("~~{}~~", removed));
+        out.push_str(trailing_whitespace(deleted));
+    } else if added.is_empty() {
+        out.push_str(inserted);
+    } else {
+        out.push_str(leading_whitespace(inserted));
+        if !removed.is_empty() {
+            out.push_str(&format!("~~{}~~ ", removed));
+        }
+        out.push_str(&format!("**{}**", added));
+        out.push_str(trailing_whitespace(inserted));
+    }
+
+    deleted.clear();
+    inserted.clear();
+}
+
+fn leading_whitespace(text: &str) -> &str {
+    &text[..text.len() - text.trim_start().len()]
+}
+
+fn trailing_whitespace(text: &str) -> &str {
+    &text[text.trim_end().len()..]
+}
+
 /// Cached diff result to avoid recomputation
 #[derive(Debug, Clone)]
 pub struct CachedDiff {
@@ -195,4 +249,23 @@ mod tests {
 
         assert!(changes.iter().any(|c| matches!(c, DiffChange::Insert(_))));
     }
+
+    #[test]
+    fn test_to_markdown_tracks_changes() {
+        assert_eq!(
+            to_markdown("ala ma kota", "Ala ma kota."),
+            "~~ala~~ **Ala** ma ~~kota~~ **kota.**"
+        );
+        assert_eq!(
+            to_markdown("Witam serdecznie wszystkich", "Witam bardzo serdecznie wszystkich"),
+            "Witam **bardzo** serdecznie wszystkich"
+        );
+        assert_eq!(to_markdown("Hello beautiful world", "Hello world"), "Hello ~~beautiful~~ world");
+    }
+
+    #[test]
+    fn test_to_markdown_unchanged_text() {
+        assert_eq!(to_markdown("Bez zmian.\nDruga linia", "Bez zmian.\nDruga linia"), "Bez zmian.\nDruga linia");
+        assert_eq!(to_markdown("", ""), "");
+    }
 }
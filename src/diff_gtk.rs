@@ -4,6 +4,8 @@ use regex::Regex;
 use similar::{DiffTag, TextDiff};
 use std::sync::LazyLock;
 
+use crate::agreement::{Level, SentenceScore};
+
 static WORD_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\S+").unwrap());
 
 pub fn set_text_with_diff(buffer: &TextBuffer, original: &str, corrected: &str, highlight: bool) {
@@ -14,6 +16,49 @@ pub fn set_text_with_diff(buffer: &TextBuffer, original: &str, corrected: &str,
     }
 }
 
+/// Highlights character ranges (not byte ranges) with a yellow background
+pub fn highlight_char_ranges(buffer: &TextBuffer, ranges: &[(usize, usize)]) {
+    let tag_table = buffer.tag_table();
+
+    if tag_table.lookup("fact_highlight").is_none() {
+        let tag = gtk4::TextTag::builder()
+            .name("fact_highlight")
+            .background("#fce8b2")
+            .build();
+        tag_table.add(&tag);
+    }
+
+    for &(start, end) in ranges {
+        let start_iter = buffer.iter_at_offset(start as i32);
+        let end_iter = buffer.iter_at_offset(end as i32);
+        buffer.apply_tag_by_name("fact_highlight", &start_iter, &end_iter);
+    }
+}
+
+/// Tints sentences the providers disagree on; earlier tints are cleared first
+pub fn apply_agreement_heatmap(buffer: &TextBuffer, scores: &[SentenceScore]) {
+    let tag_table = buffer.tag_table();
+
+    for (name, color) in [("agreement_medium", "#fff6d5"), ("agreement_low", "#fde0dc")] {
+        if tag_table.lookup(name).is_none() {
+            let tag = gtk4::TextTag::builder().name(name).background(color).build();
+            tag_table.add(&tag);
+        }
+        buffer.remove_tag_by_name(name, &buffer.start_iter(), &buffer.end_iter());
+    }
+
+    for score in scores {
+        let name = match score.level() {
+            Level::High => continue,
+            Level::Medium => "agreement_medium",
+            Level::Low => "agreement_low",
+        };
+        let start_iter = buffer.iter_at_offset(score.start as i32);
+        let end_iter = buffer.iter_at_offset(score.end as i32);
+        buffer.apply_tag_by_name(name, &start_iter, &end_iter);
+    }
+}
+
 fn apply_diff_highlighting(buffer: &TextBuffer, original: &str, corrected: &str) {
     let tag_table = buffer.tag_table();
 
@@ -0,0 +1,176 @@
+//! Tygodniowy raport "trybu nauki"
+//!
+//! Na podstawie historii wybranych korekt prosi model o podsumowanie
+//! najczęstszych kategorii błędów (interpunkcja, szyk zdania, kalki z angielskiego).
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::api::{correct_text, Provider};
+use crate::config::Config;
+use crate::error::ApiError;
+use crate::history::HistoryEntry;
+
+/// Raport generowany raz na tydzień
+pub const DIGEST_PERIOD_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// Limit znaków wejścia wysyłanego do modelu
+pub const MAX_DIGEST_INPUT_CHARS: usize = 12_000;
+
+pub const DIGEST_SYSTEM_PROMPT: &str = r#"You are a Polish-language teacher reviewing a week of a user's writing. You receive pairs of texts: the user's original and the corrected version they accepted. Identify recurring error categories (e.g. interpunkcja, szyk zdania, kalki z angielskiego, ortografia, odmiana, styl). Write the report in Polish. Return ONLY the report, without introductions or closing remarks."#;
+
+pub const DIGEST_INSTRUCTION: &str = "Analyze the following pairs (ORIGINAL → CORRECTED). \
+    Produce a short report listing the most frequent error categories, ordered by frequency. \
+    For each category give a one-sentence explanation and one or two examples taken from the texts. \
+    Finish with three concrete tips for the next week.";
+
+/// Plik z czasem ostatniego raportu (obok `config.toml`)
+pub fn default_state_path() -> PathBuf {
+    Config::get_config_path().with_file_name("last_digest")
+}
+
+pub fn load_last_digest<P: AsRef<Path>>(path: P) -> Option<u64> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+pub fn save_last_digest<P: AsRef<Path>>(path: P, timestamp: u64) -> std::io::Result<()> {
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, timestamp.to_string())
+}
+
+pub fn is_digest_due(last: Option<u64>, now: u64) -> bool {
+    match last {
+        Some(last) => now.saturating_sub(last) >= DIGEST_PERIOD_SECS,
+        None => true,
+    }
+}
+
+/// Składa pary oryginał → poprawka, od najnowszych, z limitem długości;
+/// para, która się nie mieści, jest pomijana, a krótsze starsze mogą wejść
+pub fn build_digest_input(entries: &[HistoryEntry]) -> String {
+    let mut input = String::new();
+    let mut chars = 0;
+
+    for entry in entries.iter().rev() {
+        let pair = format!(
+            "ORIGINAL:\n{}\nCORRECTED:\n{}\n\n",
+            entry.original.trim(),
+            entry.result.trim()
+        );
+        let pair_chars = pair.chars().count();
+        if chars + pair_chars > MAX_DIGEST_INPUT_CHARS {
+            continue;
+        }
+        chars += pair_chars;
+        input.push_str(&pair);
+    }
+
+    input.trim_end().to_string()
+}
+
+/// Pierwszy dostawca z uzupełnionym kluczem API (w trybie demo po prostu pierwszy)
+pub fn pick_provider(config: &Config) -> Option<Provider> {
+    Provider::all()
+        .iter()
+        .copied()
+        .find(|p| p.is_available(config))
+}
+
+pub async fn generate_digest(config: &Config, entries: &[HistoryEntry]) -> Result<String, ApiError> {
+    if entries.is_empty() {
+        return Err(ApiError::Response("Brak wpisów w historii z ostatniego tygodnia".to_string()));
+    }
+
+    let provider = pick_provider(config)
+        .ok_or_else(|| ApiError::Response("Brak skonfigurowanego klucza API".to_string()))?;
+
+    let input = build_digest_input(entries);
+    correct_text(provider, config, &input, DIGEST_INSTRUCTION, DIGEST_SYSTEM_PROMPT).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn entry(original: &str, result: &str) -> HistoryEntry {
+        HistoryEntry {
+            timestamp: 0,
+            started: None,
+            style: "normal".to_string(),
+            provider: "OpenAI".to_string(),
+            original: original.to_string(),
+            result: result.to_string(),
+            generation: None,
+            source_app: None,
+        }
+    }
+
+    #[test]
+    fn test_is_digest_due() {
+        assert!(is_digest_due(None, 0));
+        assert!(!is_digest_due(Some(1000), 1000 + DIGEST_PERIOD_SECS - 1));
+        assert!(is_digest_due(Some(1000), 1000 + DIGEST_PERIOD_SECS));
+    }
+
+    #[test]
+    fn test_last_digest_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("last_digest");
+
+        assert_eq!(load_last_digest(&path), None);
+        save_last_digest(&path, 12345).unwrap();
+        assert_eq!(load_last_digest(&path), Some(12345));
+    }
+
+    #[test]
+    fn test_build_digest_input_contains_pairs() {
+        let entries = vec![entry("Witam,wszystkich", "Witam wszystkich")];
+        let input = build_digest_input(&entries);
+
+        assert!(input.contains("ORIGINAL:\nWitam,wszystkich"));
+        assert!(input.contains("CORRECTED:\nWitam wszystkich"));
+    }
+
+    #[test]
+    fn test_build_digest_input_respects_limit() {
+        let long = "a".repeat(MAX_DIGEST_INPUT_CHARS / 3);
+        let entries: Vec<_> = (0..10).map(|_| entry(&long, &long)).collect();
+
+        assert!(build_digest_input(&entries).len() <= MAX_DIGEST_INPUT_CHARS);
+    }
+
+    #[test]
+    fn test_build_digest_input_counts_chars_and_skips_long_pairs() {
+        // Polskie znaki zajmują po dwa bajty, ale limit dotyczy znaków
+        let polish = "ż".repeat(MAX_DIGEST_INPUT_CHARS / 3);
+        let input = build_digest_input(&[entry(&polish, &polish)]);
+        assert!(input.len() > MAX_DIGEST_INPUT_CHARS);
+        assert!(input.chars().count() <= MAX_DIGEST_INPUT_CHARS);
+
+        // Za długa najnowsza para nie zeruje raportu
+        let long = "a".repeat(MAX_DIGEST_INPUT_CHARS);
+        let entries = vec![entry("Witam,wszystkich", "Witam wszystkich"), entry(&long, &long)];
+        let input = build_digest_input(&entries);
+        assert!(input.contains("Witam,wszystkich"));
+        assert!(!input.contains(&long));
+    }
+
+    #[test]
+    fn test_pick_provider_skips_empty_keys() {
+        let mut config = Config::default();
+        assert_eq!(pick_provider(&config), None);
+
+        config.api_keys.gemini = "AIza-test".to_string();
+        assert_eq!(pick_provider(&config), Some(Provider::Gemini));
+    }
+
+    #[tokio::test]
+    async fn test_generate_digest_requires_entries() {
+        let result = generate_digest(&Config::default(), &[]).await;
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,396 @@
+//! Diagnostyka środowiska uruchamiana przez `--doctor`
+//!
+//! Każde sprawdzenie zwraca status i wskazówkę naprawy; wynik drukowany jest
+//! jako tabela. Kod wyjścia jest niezerowy, jeśli któreś sprawdzenie zawiodło.
+
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+use crate::api::registry;
+use crate::config::Config;
+use crate::hotkey::{HotkeyCombo, HotkeyManager};
+use crate::hotkey_portal::is_wayland;
+
+const NETWORK_TIMEOUT: Duration = Duration::from_secs(3);
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+    /// Sprawdzenie nie dotyczy tej platformy lub konfiguracji
+    Skip,
+}
+
+impl CheckStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "OK",
+            CheckStatus::Warn => "UWAGA",
+            CheckStatus::Fail => "BŁĄD",
+            CheckStatus::Skip => "POMINIĘTO",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// Co zrobić, gdy status nie jest `Pass`
+    pub hint: Option<String>,
+}
+
+impl CheckResult {
+    fn new(name: &'static str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+}
+
+/// Uruchamia wszystkie sprawdzenia po kolei
+pub fn run_checks() -> Vec<CheckResult> {
+    let config_path = Config::get_config_path();
+    let (config_check, config) = check_config(&config_path);
+
+    vec![
+        config_check,
+        check_keys(&config),
+        check_keyring(),
+        check_hotkey(&config),
+        check_keyboard_tool(std::env::var("PATH").unwrap_or_default().as_str(), is_wayland()),
+        check_dbus_name(
+            "Zasobnik systemowy",
+            "org.kde.StatusNotifierWatcher",
+            "Zainstaluj rozszerzenie AppIndicator/KStatusNotifierItem dla swojego środowiska",
+        ),
+        check_dbus_name(
+            "Portal D-Bus",
+            "org.freedesktop.portal.Desktop",
+            "Zainstaluj xdg-desktop-portal oraz backend dla swojego środowiska (np. xdg-desktop-portal-gnome)",
+        ),
+        check_network(&config),
+    ]
+}
+
+/// Wczytuje konfigurację; przy braku pliku zwraca domyślną
+pub fn check_config(path: &Path) -> (CheckResult, Config) {
+    const NAME: &str = "Konfiguracja";
+
+    if !path.exists() {
+        let result = CheckResult::new(NAME, CheckStatus::Warn, format!("brak pliku {}", path.display()))
+            .with_hint("Uruchom aplikację i zapisz ustawienia, żeby utworzyć plik");
+        return (result, Config::default());
+    }
+
+    match Config::load_with_warnings(path) {
+        Ok((config, warnings)) if warnings.is_empty() => (
+            CheckResult::new(NAME, CheckStatus::Pass, path.display().to_string()),
+            config,
+        ),
+        Ok((config, warnings)) => (
+            CheckResult::new(NAME, CheckStatus::Warn, format!("pominięte wartości: {}", warnings.join("; ")))
+                .with_hint(format!("Popraw {}; do tego czasu obowiązują wartości domyślne", path.display())),
+            config,
+        ),
+        Err(e) => (
+            CheckResult::new(NAME, CheckStatus::Fail, format!("niepoprawny plik: {}", e))
+                .with_hint(format!("Popraw lub usuń {}", path.display())),
+            Config::default(),
+        ),
+    }
+}
+
+pub fn check_keys(config: &Config) -> CheckResult {
+    const NAME: &str = "Klucze API";
+
+    let panels = registry::panel_providers(&registry::panel_specs(&config.settings.panel_providers));
+    let missing: Vec<&str> = panels
+        .iter()
+        .filter(|p| p.api_key(config).trim().is_empty())
+        .map(|p| p.name())
+        .collect();
+
+    match missing.len() {
+        0 => CheckResult::new(NAME, CheckStatus::Pass, format!("wszystkie {} uzupełnione", panels.len())),
+        n if n == panels.len() => CheckResult::new(NAME, CheckStatus::Fail, "brak kluczy")
+            .with_hint("Wpisz co najmniej jeden klucz w Ustawieniach"),
+        _ => CheckResult::new(NAME, CheckStatus::Warn, format!("brak: {}", missing.join(", ")))
+            .with_hint("Panele bez klucza zakończą się błędem"),
+    }
+}
+
+/// Secret Service (GNOME Keyring, KWallet) w sesji D-Bus; klucze API leżą w
+/// config.toml, więc jego brak jest tylko ostrzeżeniem
+pub fn check_keyring() -> CheckResult {
+    let mut result = check_dbus_name(
+        "Keyring",
+        "org.freedesktop.secrets",
+        "Zainstaluj gnome-keyring albo włącz Secret Service w KWallet",
+    );
+    if result.status == CheckStatus::Fail {
+        result.status = CheckStatus::Warn;
+    }
+    result
+}
+
+fn check_hotkey(config: &Config) -> CheckResult {
+    const NAME: &str = "Skrót globalny";
+
+    if is_wayland() {
+        return CheckResult::new(NAME, CheckStatus::Skip, "Wayland: skrót rejestruje portal GlobalShortcuts");
+    }
+
+    let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+    let configured = match HotkeyCombo::from_config(&config.global_hotkey.combination) {
+        Ok(combo) => combo,
+        Err(e) => {
+            return CheckResult::new(NAME, CheckStatus::Fail, format!("{}: {}", config.global_hotkey.combination, e))
+                .with_hint("Popraw Combination w sekcji [global_hotkey], np. \"Ctrl+Shift+C\"")
+        }
+    };
+    let description = configured.description().to_string();
+    let (conflicts, conflict) = tokio::sync::watch::channel(None);
+    match HotkeyManager::new(tx, configured, conflicts) {
+        Ok(manager) => match (manager.active_combo(), conflict.borrow().as_ref()) {
+            (Some(combo), _) => CheckResult::new(NAME, CheckStatus::Pass, combo.description()),
+            (None, Some(conflict)) if !conflict.alternatives.is_empty() => {
+                CheckResult::new(NAME, CheckStatus::Fail, format!("{} zajęty: {}", description, conflict.error))
+                    .with_hint(format!("Wolne kombinacje: {}", conflict.alternatives.join(", ")))
+            }
+            _ => CheckResult::new(NAME, CheckStatus::Fail, format!("{} zajęty", description)).with_hint(format!(
+                "Zamknij program, który zajął {}, albo wybierz inny skrót w ustawieniach",
+                description
+            )),
+        },
+        Err(e) => CheckResult::new(NAME, CheckStatus::Fail, e).with_hint(format!(
+            "Zamknij program, który zajął {}, wybierz inny skrót w ustawieniach albo użyj `--paste` przypisanego do własnego skrótu",
+            description
+        )),
+    }
+}
+
+/// Szuka pliku wykonywalnego w katalogach z `path_var` (format zmiennej PATH)
+pub fn find_in_path(name: &str, path_var: &str) -> Option<PathBuf> {
+    std::env::split_paths(path_var)
+        .map(|dir| dir.join(name))
+        .find(|candidate| candidate.is_file())
+}
+
+pub fn check_keyboard_tool(path_var: &str, wayland: bool) -> CheckResult {
+    const NAME: &str = "Symulacja klawiatury";
+
+    if cfg!(target_os = "windows") {
+        return CheckResult::new(NAME, CheckStatus::Skip, "Windows: SendKeys");
+    }
+
+    let (tool, package) = if wayland { ("wtype", "wtype") } else { ("xdotool", "xdotool") };
+    match find_in_path(tool, path_var) {
+        Some(path) => CheckResult::new(NAME, CheckStatus::Pass, path.display().to_string()),
+        None => CheckResult::new(NAME, CheckStatus::Fail, format!("brak {}", tool))
+            .with_hint(format!("sudo apt install {}", package)),
+    }
+}
+
+/// Interpretuje odpowiedź `dbus-send` na `NameHasOwner`
+pub fn parse_name_has_owner(output: &str) -> bool {
+    output.contains("boolean true")
+}
+
+fn check_dbus_name(name: &'static str, bus_name: &str, hint: &str) -> CheckResult {
+    if cfg!(target_os = "windows") {
+        return CheckResult::new(name, CheckStatus::Skip, "nie dotyczy Windows");
+    }
+
+    let output = Command::new("dbus-send")
+        .args([
+            "--session",
+            "--print-reply",
+            "--dest=org.freedesktop.DBus",
+            "/org/freedesktop/DBus",
+            "org.freedesktop.DBus.NameHasOwner",
+            &format!("string:{}", bus_name),
+        ])
+        .output();
+
+    match output {
+        Ok(out) if out.status.success() => {
+            if parse_name_has_owner(&String::from_utf8_lossy(&out.stdout)) {
+                CheckResult::new(name, CheckStatus::Pass, bus_name.to_string())
+            } else {
+                CheckResult::new(name, CheckStatus::Fail, format!("brak usługi {}", bus_name)).with_hint(hint)
+            }
+        }
+        Ok(out) => CheckResult::new(
+            name,
+            CheckStatus::Fail,
+            format!("brak sesji D-Bus: {}", String::from_utf8_lossy(&out.stderr).trim()),
+        )
+        .with_hint("Uruchom program w sesji graficznej (zmienna DBUS_SESSION_BUS_ADDRESS)"),
+        Err(_) => CheckResult::new(name, CheckStatus::Warn, "brak dbus-send, nie można sprawdzić")
+            .with_hint("sudo apt install dbus"),
+    }
+}
+
+/// Osiągalność hostów API dostawców paneli
+fn check_network(config: &Config) -> CheckResult {
+    const NAME: &str = "Sieć";
+
+    let panels = registry::panel_specs(&config.settings.panel_providers);
+    let hosts: Vec<&str> = registry::panel_providers(&panels).iter().map(|p| p.host()).collect();
+    let unreachable: Vec<&str> = hosts.iter().copied().filter(|host| !is_reachable(host)).collect();
+
+    if unreachable.is_empty() {
+        CheckResult::new(NAME, CheckStatus::Pass, "wszystkie API osiągalne")
+    } else if unreachable.len() == hosts.len() {
+        CheckResult::new(NAME, CheckStatus::Fail, "brak połączenia z API")
+            .with_hint("Sprawdź połączenie z internetem, proxy i DNS")
+    } else {
+        CheckResult::new(NAME, CheckStatus::Warn, format!("nieosiągalne: {}", unreachable.join(", ")))
+            .with_hint("Sprawdź firewall lub proxy dla tych hostów")
+    }
+}
+
+fn is_reachable(host: &str) -> bool {
+    let Ok(addrs) = (host, 443).to_socket_addrs() else {
+        return false;
+    };
+    addrs
+        .into_iter()
+        .any(|addr| TcpStream::connect_timeout(&addr, NETWORK_TIMEOUT).is_ok())
+}
+
+/// Czy któreś sprawdzenie zakończyło się błędem
+pub fn has_failures(results: &[CheckResult]) -> bool {
+    results.iter().any(|r| r.status == CheckStatus::Fail)
+}
+
+/// Tabela wyników z wyrównanymi kolumnami i wskazówkami pod błędami
+pub fn format_table(results: &[CheckResult]) -> String {
+    let name_width = results.iter().map(|r| r.name.chars().count()).max().unwrap_or(0);
+    let status_width = results
+        .iter()
+        .map(|r| r.status.label().chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut out = String::new();
+    for result in results {
+        out.push_str(&format!(
+            "{:<nw$}  {:<sw$}  {}\n",
+            result.name,
+            result.status.label(),
+            result.detail,
+            nw = name_width,
+            sw = status_width,
+        ));
+        if result.status != CheckStatus::Pass {
+            if let Some(hint) = &result.hint {
+                out.push_str(&format!("{:<nw$}  → {}\n", "", hint, nw = name_width));
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_check_config_states() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+
+        assert_eq!(check_config(&path).0.status, CheckStatus::Warn);
+
+        Config::default().save(&path).unwrap();
+        assert_eq!(check_config(&path).0.status, CheckStatus::Pass);
+
+        let content = fs::read_to_string(&path).unwrap().replace("Verbosity = \"medium\"", "Verbosity = \"loud\"");
+        fs::write(&path, content).unwrap();
+        let (result, _) = check_config(&path);
+        assert_eq!(result.status, CheckStatus::Warn);
+        assert!(result.detail.contains("invalid Verbosity \"loud\""));
+
+        fs::write(&path, "[api_keys\n").unwrap();
+        let (result, _) = check_config(&path);
+        assert_eq!(result.status, CheckStatus::Fail);
+        assert!(result.hint.is_some());
+    }
+
+    #[test]
+    fn test_check_keys() {
+        let mut config = Config::default();
+        assert_eq!(check_keys(&config).status, CheckStatus::Fail);
+
+        config.api_keys.openai = "sk-test".to_string();
+        let partial = check_keys(&config);
+        assert_eq!(partial.status, CheckStatus::Warn);
+        assert!(partial.detail.contains("Anthropic"));
+
+        config.api_keys.anthropic = "a".to_string();
+        config.api_keys.gemini = "b".to_string();
+        config.api_keys.deepseek = "c".to_string();
+        assert_eq!(check_keys(&config).status, CheckStatus::Pass);
+
+        config.settings.panel_providers = vec!["Mistral".to_string(), "OpenAI:gpt-4o-mini".to_string(), "OpenAI".to_string()];
+        let mistral = check_keys(&config);
+        assert_eq!(mistral.status, CheckStatus::Warn);
+        assert_eq!(mistral.detail, "brak: Mistral");
+
+        config.settings.panel_providers = vec!["Mistral".to_string()];
+        assert_eq!(check_keys(&config).status, CheckStatus::Fail);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_keyboard_tool_lookup() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("xdotool"), "").unwrap();
+        let path_var = dir.path().display().to_string();
+
+        assert_eq!(check_keyboard_tool(&path_var, false).status, CheckStatus::Pass);
+
+        let missing = check_keyboard_tool(&path_var, true);
+        assert_eq!(missing.status, CheckStatus::Fail);
+        assert_eq!(missing.hint.as_deref(), Some("sudo apt install wtype"));
+    }
+
+    #[test]
+    fn test_parse_name_has_owner() {
+        assert!(parse_name_has_owner("method return time=1.0\n   boolean true\n"));
+        assert!(!parse_name_has_owner("method return time=1.0\n   boolean false\n"));
+    }
+
+    #[test]
+    fn test_format_table_and_failures() {
+        let results = vec![
+            CheckResult::new("Konfiguracja", CheckStatus::Pass, "ok").with_hint("ukryta"),
+            CheckResult::new("Sieć", CheckStatus::Fail, "brak").with_hint("Sprawdź połączenie"),
+        ];
+
+        let table = format_table(&results);
+        assert!(table.contains("Konfiguracja  OK    ok\n"));
+        assert!(table.contains("Sieć          BŁĄD  brak\n"));
+        assert!(table.contains("→ Sprawdź połączenie"));
+        assert!(!table.contains("ukryta"));
+        assert!(has_failures(&results));
+        assert!(!has_failures(&results[..1]));
+    }
+}
@@ -0,0 +1,160 @@
+//! Oszczędny tryb poprawek: model zwraca tylko listę zmian
+//!
+//! Przy długim tekście z kilkoma błędami przepisywanie całości marnuje tokeny
+//! wyjściowe. Model odsyła wtedy bloki SEARCH/REPLACE, które aplikacja nanosi
+//! lokalnie. Jeśli odpowiedź nie daje się odczytać albo któryś fragment nie
+//! pasuje dokładnie raz, wywołujący wraca do zwykłego przepisania tekstu.
+
+const SEARCH_MARKER: &str = "<<<<<<< SEARCH";
+const DIVIDER: &str = "=======";
+const REPLACE_MARKER: &str = ">>>>>>> REPLACE";
+/// Odpowiedź modelu, gdy tekst nie wymaga zmian
+pub const NO_CHANGES: &str = "NO_CHANGES";
+
+/// Dopisywane do system promptu w trybie listy zmian
+pub const EDITS_INSTRUCTION: &str = "Do NOT return the whole text. Return ONLY the edits needed, \
+each as a block in exactly this format:\n\
+<<<<<<< SEARCH\n\
+exact fragment copied from the text\n\
+=======\n\
+corrected fragment\n\
+>>>>>>> REPLACE\n\
+Each SEARCH fragment must appear exactly once in the text; include enough surrounding words to make it unique. \
+Keep blocks short and list them in text order. If nothing needs to change, return only NO_CHANGES.";
+
+/// Jedna zmiana: dokładny fragment tekstu i jego zamiennik
+#[derive(Debug, Clone, PartialEq)]
+pub struct Edit {
+    pub search: String,
+    pub replace: String,
+}
+
+/// System prompt z dopisanym opisem formatu zmian
+pub fn system_prompt(base: &str) -> String {
+    format!("{}\n\n{}", base.trim_end(), EDITS_INSTRUCTION)
+}
+
+/// Odczytuje bloki SEARCH/REPLACE; tekst poza blokami jest pomijany
+pub fn parse(response: &str) -> Result<Vec<Edit>, String> {
+    enum Part {
+        Outside,
+        Search,
+        Replace,
+    }
+
+    let mut edits = Vec::new();
+    let mut part = Part::Outside;
+    let mut search: Vec<&str> = Vec::new();
+    let mut replace: Vec<&str> = Vec::new();
+
+    for line in response.lines() {
+        let marker = line.trim_end();
+        match part {
+            Part::Outside if marker == SEARCH_MARKER => part = Part::Search,
+            Part::Outside => {}
+            Part::Search if marker == DIVIDER => part = Part::Replace,
+            Part::Search => search.push(line),
+            Part::Replace if marker == REPLACE_MARKER => {
+                edits.push(Edit {
+                    search: search.join("\n"),
+                    replace: replace.join("\n"),
+                });
+                search.clear();
+                replace.clear();
+                part = Part::Outside;
+            }
+            Part::Replace => replace.push(line),
+        }
+    }
+
+    if !matches!(part, Part::Outside) {
+        return Err("Unterminated edit block".to_string());
+    }
+    if edits.is_empty() && response.trim() != NO_CHANGES {
+        return Err("Response contains no edit blocks".to_string());
+    }
+    Ok(edits)
+}
+
+/// Nanosi zmiany po kolei; każdy fragment musi wystąpić w tekście dokładnie raz
+pub fn apply(text: &str, edits: &[Edit]) -> Result<String, String> {
+    let mut result = text.to_string();
+
+    for (n, edit) in edits.iter().enumerate() {
+        if edit.search.is_empty() {
+            return Err(format!("Edit {} has an empty search fragment", n + 1));
+        }
+        let mut matches = result.match_indices(&edit.search);
+        let Some((start, _)) = matches.next() else {
+            return Err(format!("Edit {} does not match the text", n + 1));
+        };
+        if matches.next().is_some() {
+            return Err(format!("Edit {} matches the text more than once", n + 1));
+        }
+        result.replace_range(start..start + edit.search.len(), &edit.replace);
+    }
+
+    Ok(result)
+}
+
+/// Poprawiony tekst z odpowiedzi modelu albo powód, dla którego trzeba przepisać całość
+pub fn apply_response(text: &str, response: &str) -> Result<String, String> {
+    apply(text, &parse(response)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(search: &str, replace: &str) -> String {
+        format!("{}\n{}\n{}\n{}\n{}\n", SEARCH_MARKER, search, DIVIDER, replace, REPLACE_MARKER)
+    }
+
+    #[test]
+    fn test_parse_blocks_and_ignore_surrounding_text() {
+        let response = format!("```\n{}{}```", block("ala ma kta", "Ala ma kota"), block("linia\ndruga", ""));
+        let edits = parse(&response).unwrap();
+
+        assert_eq!(
+            edits,
+            vec![
+                Edit {
+                    search: "ala ma kta".to_string(),
+                    replace: "Ala ma kota".to_string(),
+                },
+                Edit {
+                    search: "linia\ndruga".to_string(),
+                    replace: String::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("Ala ma kota.").is_err());
+        assert!(parse("<<<<<<< SEARCH\nala\n=======\nAla\n").is_err());
+        assert_eq!(parse("  NO_CHANGES\n").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn test_apply_edits_in_order() {
+        let text = "Wczoraj poszłem do sklepu. Kupiłem chleb i mleko napewno.";
+        let response = block("poszłem", "poszedłem") + &block("mleko napewno", "mleko, na pewno");
+
+        assert_eq!(
+            apply_response(text, &response).unwrap(),
+            "Wczoraj poszedłem do sklepu. Kupiłem chleb i mleko, na pewno."
+        );
+        assert_eq!(apply_response(text, NO_CHANGES).unwrap(), text);
+    }
+
+    #[test]
+    fn test_apply_rejects_missing_and_ambiguous_fragments() {
+        let text = "kot i kot";
+        assert!(apply_response(text, &block("pies", "Pies")).unwrap_err().contains("does not match"));
+        assert!(apply_response(text, &block("kot", "Kot")).unwrap_err().contains("more than once"));
+        assert!(apply(text, &[Edit { search: String::new(), replace: "x".to_string() }]).is_err());
+        assert_eq!(apply_response(text, &block("kot i", "Kot i")).unwrap(), "Kot i kot");
+    }
+}
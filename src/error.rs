@@ -5,6 +5,8 @@ pub enum ApiError {
     Connection(String),
     Response(String),
     Timeout(String),
+    /// HTTP 429; czas z nagłówka `Retry-After`, jeśli dostawca go podał, i treść odpowiedzi
+    RateLimited(Option<std::time::Duration>, String),
 }
 
 impl fmt::Display for ApiError {
@@ -13,6 +15,16 @@ impl fmt::Display for ApiError {
             ApiError::Connection(msg) => write!(f, "Connection error: {}", msg),
             ApiError::Response(msg) => write!(f, "Response error: {}", msg),
             ApiError::Timeout(msg) => write!(f, "Timeout error: {}", msg),
+            ApiError::RateLimited(retry_after, body) => {
+                write!(f, "Rate limited")?;
+                if let Some(wait) = retry_after {
+                    write!(f, ", retry after {}s", wait.as_secs())?;
+                }
+                if !body.is_empty() {
+                    write!(f, ": {}", body)?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -67,6 +79,15 @@ mod tests {
         assert_eq!(err.to_string(), "Timeout error: Request exceeded 25s");
     }
 
+    #[test]
+    fn test_rate_limited_display() {
+        let err = ApiError::RateLimited(Some(std::time::Duration::from_secs(20)), String::new());
+        assert_eq!(err.to_string(), "Rate limited, retry after 20s");
+        assert_eq!(ApiError::RateLimited(None, String::new()).to_string(), "Rate limited");
+        let err = ApiError::RateLimited(None, r#"{"error":{"code":"insufficient_quota"}}"#.to_string());
+        assert_eq!(err.to_string(), r#"Rate limited: {"error":{"code":"insufficient_quota"}}"#);
+    }
+
     #[test]
     fn test_timeout_constants() {
         assert_eq!(DEFAULT_TIMEOUT, 25);
@@ -0,0 +1,189 @@
+//! Sprawdzanie, czy liczby, daty i adresy URL przetrwały korektę
+//!
+//! Model potrafi po cichu zmienić kwotę, przestawić datę albo „poprawić” link.
+//! Po odebraniu wyniku każda liczba, data i URL z oryginału musi wystąpić
+//! w wyniku bez zmian (tyle samo razy). Brakujące trafiają jako ostrzeżenia pod
+//! panel, a nowe, których nie było w oryginale, są dodatkowo podświetlane.
+//! Przy tłumaczeniu formaty dat i liczb mogą się zmieniać, więc sprawdzenie
+//! jest pomijane.
+
+use regex::Regex;
+use std::sync::LazyLock;
+
+use crate::prompts::CorrectionStyle;
+
+static URL_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?:https?://|www\.)[^\s<>"„”]+"#).unwrap());
+static DATE_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(?:\d{4}-\d{1,2}-\d{1,2}|\d{1,2}[./-]\d{1,2}[./-]\d{2,4})\b").unwrap()
+});
+static NUMBER_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\d+(?:[.,]\d+)*").unwrap());
+
+/// Znaki interpunkcji, które kończą zdanie, a nie adres
+const URL_TRAILING: &[char] = &['.', ',', ';', ':', '!', '?', ')', ']', '}', '»', '\''];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FactKind {
+    Number,
+    Date,
+    Url,
+}
+
+impl FactKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Number => "liczba",
+            Self::Date => "data",
+            Self::Url => "link",
+        }
+    }
+}
+
+/// Liczba, data albo URL znaleziony w tekście
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fact {
+    pub kind: FactKind,
+    pub text: String,
+    /// Zakres w znakach (nie bajtach), do podświetlenia w buforze GTK
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Różnice między faktami oryginału i wyniku
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FactCheck {
+    /// Z oryginału, nieobecne w wyniku
+    pub missing: Vec<Fact>,
+    /// Z wyniku, nieobecne w oryginale (pozycje w wyniku)
+    pub added: Vec<Fact>,
+}
+
+impl FactCheck {
+    pub fn is_clean(&self) -> bool {
+        self.missing.is_empty() && self.added.is_empty()
+    }
+
+    /// Ostrzeżenia pod panelem
+    pub fn warnings(&self) -> Vec<String> {
+        let missing = self
+            .missing
+            .iter()
+            .map(|f| format!("⚠️ Brak w wyniku: {} „{}”", f.kind.label(), f.text));
+        let added = self
+            .added
+            .iter()
+            .map(|f| format!("⚠️ Nowe w wyniku: {} „{}”", f.kind.label(), f.text));
+        missing.chain(added).collect()
+    }
+}
+
+/// Czy sprawdzenie ma sens dla stylu (tłumaczenia są pomijane)
+pub fn applies_to(style: CorrectionStyle) -> bool {
+    !matches!(style, CorrectionStyle::TranslateEn | CorrectionStyle::TranslatePl)
+}
+
+/// Fakty w kolejności występowania; liczby wewnątrz dat i adresów nie są liczone osobno
+pub fn extract(text: &str) -> Vec<Fact> {
+    let mut spans: Vec<(FactKind, usize, usize)> = Vec::new();
+    let overlaps = |spans: &[(FactKind, usize, usize)], start: usize, end: usize| {
+        spans.iter().any(|&(_, s, e)| start < e && s < end)
+    };
+
+    for m in URL_PATTERN.find_iter(text) {
+        let url = m.as_str().trim_end_matches(URL_TRAILING);
+        spans.push((FactKind::Url, m.start(), m.start() + url.len()));
+    }
+    for (kind, pattern) in [(FactKind::Date, &DATE_PATTERN), (FactKind::Number, &NUMBER_PATTERN)] {
+        for m in pattern.find_iter(text) {
+            if !overlaps(&spans, m.start(), m.end()) {
+                spans.push((kind, m.start(), m.end()));
+            }
+        }
+    }
+    spans.sort_by_key(|&(_, start, _)| start);
+
+    spans
+        .into_iter()
+        .map(|(kind, start, end)| Fact {
+            kind,
+            text: text[start..end].to_string(),
+            start: text[..start].chars().count(),
+            end: text[..end].chars().count(),
+        })
+        .collect()
+}
+
+/// Porównuje fakty jak wielozbiory: każde wystąpienie w oryginale musi mieć swoją parę w wyniku
+pub fn check(original: &str, result: &str) -> FactCheck {
+    let mut added = extract(result);
+    let mut missing = Vec::new();
+
+    for fact in extract(original) {
+        match added.iter().position(|f| f.kind == fact.kind && f.text == fact.text) {
+            Some(i) => {
+                added.remove(i);
+            }
+            None => missing.push(fact),
+        }
+    }
+
+    FactCheck { missing, added }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(facts: &[Fact]) -> Vec<(FactKind, &str)> {
+        facts.iter().map(|f| (f.kind, f.text.as_str())).collect()
+    }
+
+    #[test]
+    fn test_extract_kinds_without_overlap() {
+        let facts = extract("Spotkanie 12.05.2024 o 15:30, koszt 1500,50 zł, szczegóły: https://example.com/a?id=7.");
+        assert_eq!(
+            texts(&facts),
+            vec![
+                (FactKind::Date, "12.05.2024"),
+                (FactKind::Number, "15"),
+                (FactKind::Number, "30"),
+                (FactKind::Number, "1500,50"),
+                (FactKind::Url, "https://example.com/a?id=7"),
+            ]
+        );
+        assert_eq!(extract("ISO 2026-10-18.")[0].text, "2026-10-18");
+    }
+
+    #[test]
+    fn test_char_offsets_with_polish_letters() {
+        let text = "Zażółć 42 razy";
+        let fact = &extract(text)[0];
+        assert_eq!((fact.start, fact.end), (7, 9));
+        let chars: String = text.chars().skip(fact.start).take(fact.end - fact.start).collect();
+        assert_eq!(chars, "42");
+    }
+
+    #[test]
+    fn test_check_finds_missing_and_added() {
+        let original = "Zamówienie 15 sztuk na 2024-05-01, patrz www.firma.pl";
+        assert!(check(original, "Zamówienie: 15 sztuk na 2024-05-01. Patrz www.firma.pl.").is_clean());
+
+        let result = check(original, "Zamówienie 16 sztuk na 2024-05-01, patrz www.firma.pl");
+        assert_eq!(texts(&result.missing), vec![(FactKind::Number, "15")]);
+        assert_eq!(texts(&result.added), vec![(FactKind::Number, "16")]);
+        assert_eq!(
+            result.warnings(),
+            vec!["⚠️ Brak w wyniku: liczba „15”", "⚠️ Nowe w wyniku: liczba „16”"]
+        );
+    }
+
+    #[test]
+    fn test_check_counts_repeated_facts() {
+        let result = check("5 plus 5", "5 plus pięć");
+        assert_eq!(texts(&result.missing), vec![(FactKind::Number, "5")]);
+        assert!(result.added.is_empty());
+
+        assert!(!applies_to(CorrectionStyle::TranslateEn));
+        assert!(applies_to(CorrectionStyle::Professional));
+    }
+}
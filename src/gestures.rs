@@ -0,0 +1,78 @@
+//! Gesty touchpada i ekranu dotykowego
+//!
+//! Przesunięcie panelu w lewo chowa go do końca sesji, przesunięcie okna w dół
+//! chowa je do zasobnika, a szczypanie zmienia rozmiar tekstu w panelach.
+//! Tu jest tylko rozpoznawanie kierunku i skala tekstu; kontrolery GTK
+//! podpina okno główne.
+
+/// Najmniejsza prędkość (px/s), przy której przesunięcie liczy się jako gest
+pub const MIN_SWIPE_VELOCITY: f64 = 600.0;
+
+/// Rozmiar tekstu w panelach przy skali 1.0 (jak w arkuszu stylów okna)
+pub const BASE_FONT_PX: f64 = 13.0;
+
+pub const MIN_ZOOM: f64 = 0.6;
+pub const MAX_ZOOM: f64 = 2.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Swipe {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// Kierunek przesunięcia według przeważającej osi; `None` przy zbyt wolnym ruchu
+pub fn classify(velocity_x: f64, velocity_y: f64) -> Option<Swipe> {
+    if velocity_x.abs().max(velocity_y.abs()) < MIN_SWIPE_VELOCITY {
+        return None;
+    }
+    Some(if velocity_x.abs() >= velocity_y.abs() {
+        if velocity_x < 0.0 {
+            Swipe::Left
+        } else {
+            Swipe::Right
+        }
+    } else if velocity_y < 0.0 {
+        Swipe::Up
+    } else {
+        Swipe::Down
+    })
+}
+
+pub fn clamp_zoom(zoom: f64) -> f64 {
+    if zoom.is_finite() {
+        zoom.clamp(MIN_ZOOM, MAX_ZOOM)
+    } else {
+        1.0
+    }
+}
+
+/// Arkusz stylów z rozmiarem tekstu paneli dla danej skali; dotyczy tylko okna
+/// głównego (klasa `main-container`), nie dialogów
+pub fn zoom_css(zoom: f64) -> String {
+    format!(".main-container textview {{ font-size: {:.1}px; }}", BASE_FONT_PX * clamp_zoom(zoom))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify(-900.0, 100.0), Some(Swipe::Left));
+        assert_eq!(classify(900.0, -300.0), Some(Swipe::Right));
+        assert_eq!(classify(200.0, 1200.0), Some(Swipe::Down));
+        assert_eq!(classify(0.0, -700.0), Some(Swipe::Up));
+        assert_eq!(classify(-300.0, 200.0), None);
+    }
+
+    #[test]
+    fn test_zoom() {
+        assert_eq!(clamp_zoom(10.0), MAX_ZOOM);
+        assert_eq!(clamp_zoom(0.1), MIN_ZOOM);
+        assert_eq!(clamp_zoom(f64::NAN), 1.0);
+        assert_eq!(zoom_css(1.0), ".main-container textview { font-size: 13.0px; }");
+        assert_eq!(zoom_css(1.5), ".main-container textview { font-size: 19.5px; }");
+    }
+}
@@ -0,0 +1,492 @@
+//! Historia wybranych korekt zapisywana jako JSON-lines w katalogu konfiguracji
+//!
+//! Każda linia pliku to jedna sesja zakończona wyborem wyniku: oryginalny
+//! tekst, wybrany wynik, dostawca, styl i czasy. Uszkodzone linie są pomijane przy odczycie. Z kluczem
+//! (`history_cipher`) linie są zapisywane zaszyfrowane; zaszyfrowanych linii
+//! nie da się odczytać bez klucza, więc są wtedy pomijane.
+
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::fs::{self, OpenOptions};
+use std::hash::{Hash, Hasher};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::api::Generation;
+use crate::config::{Config, HistorySettings};
+use crate::history_cipher::{self, HistoryKey};
+
+/// Pojedynczy wpis historii
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HistoryEntry {
+    /// Czas wyboru wyniku (sekundy od epoki UNIX)
+    pub timestamp: u64,
+    /// Początek sesji (brak w starszych wpisach)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started: Option<u64>,
+    pub style: String,
+    pub provider: String,
+    pub original: String,
+    pub result: String,
+    /// Model i parametry wybranego wyniku (brak w starszych wpisach)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generation: Option<Generation>,
+    /// Aplikacja, z której skopiowano tekst (np. "Thunderbird")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_app: Option<String>,
+}
+
+/// Jak długo trzymać wpisy historii
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Retention {
+    All,
+    Days(u32),
+    Entries(usize),
+    /// Nie zapisuj historii wcale
+    Nothing,
+}
+
+impl Retention {
+    pub fn from_settings(settings: &HistorySettings) -> Self {
+        match settings.retention.to_lowercase().as_str() {
+            "days" => Self::Days(settings.retention_days.max(1)),
+            "entries" => Self::Entries(settings.retention_entries.max(1) as usize),
+            "none" => Self::Nothing,
+            _ => Self::All,
+        }
+    }
+
+    pub fn keeps_entries(&self) -> bool {
+        *self != Self::Nothing
+    }
+
+    /// Wpisy pozostające po przycięciu; kolejność zachowana
+    pub fn apply(&self, entries: Vec<HistoryEntry>, now: u64) -> Vec<HistoryEntry> {
+        match *self {
+            Self::All => entries,
+            Self::Days(days) => {
+                let cutoff = now.saturating_sub(days as u64 * 24 * 60 * 60);
+                entries.into_iter().filter(|e| e.timestamp >= cutoff).collect()
+            }
+            Self::Entries(max) => {
+                let skip = entries.len().saturating_sub(max);
+                entries.into_iter().skip(skip).collect()
+            }
+            Self::Nothing => Vec::new(),
+        }
+    }
+}
+
+pub struct History {
+    path: PathBuf,
+    key: Option<HistoryKey>,
+}
+
+impl History {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            key: None,
+        }
+    }
+
+    /// Historia szyfrowana podanym kluczem
+    pub fn with_key(mut self, key: HistoryKey) -> Self {
+        self.key = Some(key);
+        self
+    }
+
+    /// Plik historii obok `config.toml`
+    pub fn default_path() -> PathBuf {
+        Config::get_config_path().with_file_name("history.jsonl")
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn append(&self, entry: &HistoryEntry) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", self.encode(entry)?)?;
+        Ok(())
+    }
+
+    /// Zastępuje całą historię podanymi wpisami (np. po włączeniu szyfrowania)
+    pub fn save_all(&self, entries: &[HistoryEntry]) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut content = String::new();
+        for entry in entries {
+            content.push_str(&self.encode(entry)?);
+            content.push('\n');
+        }
+
+        let temp_path = self.path.with_extension("jsonl.tmp");
+        fs::write(&temp_path, content)?;
+        fs::rename(&temp_path, &self.path)?;
+        Ok(())
+    }
+
+    fn encode(&self, entry: &HistoryEntry) -> Result<String, Box<dyn std::error::Error>> {
+        let line = serde_json::to_string(entry)?;
+        match &self.key {
+            Some(key) => Ok(key.encrypt_line(&line)?),
+            None => Ok(line),
+        }
+    }
+
+    fn decode(&self, line: &str) -> Option<HistoryEntry> {
+        if history_cipher::is_encrypted_line(line) {
+            let line = self.key.as_ref()?.decrypt_line(line).ok()?;
+            serde_json::from_str(&line).ok()
+        } else {
+            serde_json::from_str(line).ok()
+        }
+    }
+
+    /// Wczytuje wszystkie wpisy; brak pliku oznacza pustą historię
+    pub fn load(&self) -> Result<Vec<HistoryEntry>, Box<dyn std::error::Error>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let file = fs::File::open(&self.path)?;
+        let entries = BufReader::new(file)
+            .lines()
+            .map_while(Result::ok)
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| self.decode(&line))
+            .collect();
+        Ok(entries)
+    }
+
+    /// Usuwa plik historii (klucz szyfrowania zostaje)
+    pub fn clear(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if self.path.exists() {
+            fs::remove_file(&self.path)?;
+        }
+        Ok(())
+    }
+
+    /// Przycina historię według `retention`; zwraca liczbę usuniętych wpisów.
+    /// Bez klucza zaszyfrowanej historii nie da się przyciąć (poza usunięciem całej).
+    pub fn prune(&self, retention: Retention, now: u64) -> Result<usize, Box<dyn std::error::Error>> {
+        if retention == Retention::Nothing {
+            let count = self.load()?.len();
+            self.clear()?;
+            return Ok(count);
+        }
+        if self.key.is_none() && self.has_encrypted_lines()? {
+            return Err("History is encrypted, unlock it before pruning".into());
+        }
+
+        let entries = self.load()?;
+        let before = entries.len();
+        let kept = retention.apply(entries, now);
+        let removed = before - kept.len();
+        if removed > 0 {
+            self.save_all(&kept)?;
+        }
+        Ok(removed)
+    }
+
+    fn has_encrypted_lines(&self) -> Result<bool, Box<dyn std::error::Error>> {
+        if !self.path.exists() {
+            return Ok(false);
+        }
+        let file = fs::File::open(&self.path)?;
+        Ok(BufReader::new(file)
+            .lines()
+            .map_while(Result::ok)
+            .any(|line| history_cipher::is_encrypted_line(&line)))
+    }
+
+    /// Ostatni wpis z tym samym oryginałem (zgodny skrót `text_hash`)
+    pub fn previous_choice(&self, original: &str) -> Result<Option<HistoryEntry>, Box<dyn std::error::Error>> {
+        let hash = text_hash(original);
+        Ok(self.load()?.into_iter().rev().find(|e| text_hash(&e.original) == hash))
+    }
+
+    /// Wpisy nie starsze niż podany znacznik czasu
+    pub fn since(&self, timestamp: u64) -> Result<Vec<HistoryEntry>, Box<dyn std::error::Error>> {
+        Ok(self
+            .load()?
+            .into_iter()
+            .filter(|e| e.timestamp >= timestamp)
+            .collect())
+    }
+}
+
+/// Wpisy pasujące do zapytania, od najnowszego. Każde słowo zapytania musi
+/// wystąpić (bez względu na wielkość liter) w oryginale, wyniku, dostawcy,
+/// stylu albo aplikacji źródłowej.
+pub fn search<'a>(entries: &'a [HistoryEntry], query: &str) -> Vec<&'a HistoryEntry> {
+    let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    entries
+        .iter()
+        .rev()
+        .filter(|entry| {
+            let haystack = [
+                entry.original.as_str(),
+                &entry.result,
+                &entry.provider,
+                &entry.style,
+                entry.source_app.as_deref().unwrap_or_default(),
+            ]
+            .join("\n")
+            .to_lowercase();
+            words.iter().all(|word| haystack.contains(word))
+        })
+        .collect()
+}
+
+/// Skrót tekstu do rozpoznania tego samego oryginału; układ białych znaków nie ma znaczenia
+pub fn text_hash(text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for word in text.split_whitespace() {
+        word.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Aktualny czas w sekundach od epoki UNIX
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ReasoningEffort;
+    use tempfile::TempDir;
+
+    fn entry(timestamp: u64, original: &str) -> HistoryEntry {
+        HistoryEntry {
+            timestamp,
+            started: None,
+            style: "normal".to_string(),
+            provider: "OpenAI".to_string(),
+            original: original.to_string(),
+            result: format!("{} (poprawione)", original),
+            generation: None,
+            source_app: None,
+        }
+    }
+
+    #[test]
+    fn test_load_missing_file_is_empty() {
+        let dir = TempDir::new().unwrap();
+        let history = History::new(dir.path().join("history.jsonl"));
+        assert!(history.load().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_append_and_load() {
+        let dir = TempDir::new().unwrap();
+        let history = History::new(dir.path().join("nested").join("history.jsonl"));
+
+        history.append(&entry(1, "pierwszy")).unwrap();
+        history.append(&entry(2, "drugi")).unwrap();
+
+        let entries = history.load().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0], entry(1, "pierwszy"));
+        assert_eq!(entries[1].original, "drugi");
+    }
+
+    #[test]
+    fn test_generation_is_optional() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("history.jsonl");
+        fs::write(
+            &path,
+            "{\"timestamp\":1,\"style\":\"normal\",\"provider\":\"OpenAI\",\"original\":\"a\",\"result\":\"A\"}\n",
+        )
+        .unwrap();
+        let history = History::new(&path);
+        assert_eq!(history.load().unwrap()[0].generation, None);
+        assert_eq!(history.load().unwrap()[0].source_app, None);
+
+        let mut with_model = entry(2, "b");
+        with_model.generation = Some(Generation {
+            model: "gpt-5-mini".to_string(),
+            temperature: Some(0.7),
+            thinking_budget: None,
+            reasoning_effort: Some(ReasoningEffort::Medium),
+        });
+        with_model.source_app = Some("Thunderbird".to_string());
+        history.append(&with_model).unwrap();
+        assert_eq!(history.load().unwrap()[1], with_model);
+    }
+
+    #[test]
+    fn test_malformed_lines_are_skipped() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("history.jsonl");
+        let history = History::new(&path);
+
+        history.append(&entry(1, "ok")).unwrap();
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        writeln!(file, "{{not json").unwrap();
+        history.append(&entry(2, "też ok")).unwrap();
+
+        assert_eq!(history.load().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_encrypted_history() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("history.jsonl");
+        let (_, key) = crate::history_cipher::KeyFile::create("hasło").unwrap();
+
+        History::new(&path).append(&entry(1, "jawny")).unwrap();
+        let encrypted = History::new(&path).with_key(key.clone());
+        encrypted.append(&entry(2, "tajny")).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("jawny"));
+        assert!(!content.contains("tajny"));
+
+        assert_eq!(encrypted.load().unwrap().len(), 2);
+        assert_eq!(History::new(&path).load().unwrap(), vec![entry(1, "jawny")]);
+    }
+
+    #[test]
+    fn test_save_all_rewrites_encrypted() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("history.jsonl");
+        let (_, key) = crate::history_cipher::KeyFile::create("hasło").unwrap();
+
+        let plain = History::new(&path);
+        plain.append(&entry(1, "pierwszy")).unwrap();
+        plain.append(&entry(2, "drugi")).unwrap();
+
+        let encrypted = History::new(&path).with_key(key);
+        encrypted.save_all(&plain.load().unwrap()).unwrap();
+
+        assert!(!fs::read_to_string(&path).unwrap().contains("pierwszy"));
+        assert!(plain.load().unwrap().is_empty());
+        assert_eq!(encrypted.load().unwrap(), vec![entry(1, "pierwszy"), entry(2, "drugi")]);
+    }
+
+    #[test]
+    fn test_retention_from_settings() {
+        let mut settings = HistorySettings::default();
+        assert_eq!(Retention::from_settings(&settings), Retention::All);
+
+        settings.retention = "days".to_string();
+        settings.retention_days = 7;
+        assert_eq!(Retention::from_settings(&settings), Retention::Days(7));
+
+        settings.retention = "Entries".to_string();
+        settings.retention_entries = 0;
+        assert_eq!(Retention::from_settings(&settings), Retention::Entries(1));
+
+        settings.retention = "none".to_string();
+        assert!(!Retention::from_settings(&settings).keeps_entries());
+    }
+
+    #[test]
+    fn test_retention_apply() {
+        let day = 24 * 60 * 60;
+        let entries = vec![entry(0, "a"), entry(5 * day, "b"), entry(9 * day, "c")];
+
+        assert_eq!(Retention::All.apply(entries.clone(), 10 * day).len(), 3);
+        assert_eq!(
+            Retention::Days(5).apply(entries.clone(), 10 * day),
+            vec![entry(5 * day, "b"), entry(9 * day, "c")]
+        );
+        assert_eq!(Retention::Entries(1).apply(entries.clone(), 0), vec![entry(9 * day, "c")]);
+        assert!(Retention::Nothing.apply(entries, 0).is_empty());
+    }
+
+    #[test]
+    fn test_prune_and_clear() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("history.jsonl");
+        let history = History::new(&path);
+        for i in 0..5 {
+            history.append(&entry(i, "wpis")).unwrap();
+        }
+
+        assert_eq!(history.prune(Retention::All, 10).unwrap(), 0);
+        assert_eq!(history.prune(Retention::Entries(2), 10).unwrap(), 3);
+        assert_eq!(history.load().unwrap(), vec![entry(3, "wpis"), entry(4, "wpis")]);
+
+        assert_eq!(history.prune(Retention::Nothing, 10).unwrap(), 2);
+        assert!(!path.exists());
+        history.clear().unwrap();
+    }
+
+    #[test]
+    fn test_prune_encrypted_needs_key() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("history.jsonl");
+        let (_, key) = crate::history_cipher::KeyFile::create("hasło").unwrap();
+        let encrypted = History::new(&path).with_key(key);
+        encrypted.append(&entry(1, "a")).unwrap();
+        encrypted.append(&entry(2, "b")).unwrap();
+
+        assert!(History::new(&path).prune(Retention::Entries(1), 10).is_err());
+        assert_eq!(encrypted.prune(Retention::Entries(1), 10).unwrap(), 1);
+        assert_eq!(encrypted.load().unwrap(), vec![entry(2, "b")]);
+    }
+
+    #[test]
+    fn test_since_filters_by_timestamp() {
+        let dir = TempDir::new().unwrap();
+        let history = History::new(dir.path().join("history.jsonl"));
+
+        history.append(&entry(100, "stary")).unwrap();
+        history.append(&entry(200, "nowy")).unwrap();
+
+        let recent = history.since(150).unwrap();
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].original, "nowy");
+    }
+
+    #[test]
+    fn test_search() {
+        let mut thunderbird = entry(300, "Spotkanie w piątek");
+        thunderbird.source_app = Some("Thunderbird".to_string());
+        thunderbird.provider = "Anthropic".to_string();
+        let entries = vec![entry(100, "Ala ma kota"), entry(200, "ala ma psa"), thunderbird];
+
+        let found: Vec<u64> = search(&entries, "ALA").iter().map(|e| e.timestamp).collect();
+        assert_eq!(found, [200, 100]);
+        assert_eq!(search(&entries, "ala psa").len(), 1);
+        assert_eq!(search(&entries, "anthropic thunderbird")[0].timestamp, 300);
+        assert_eq!(search(&entries, "poprawione").len(), 3);
+        assert_eq!(search(&entries, "  ").len(), 3);
+        assert!(search(&entries, "kot pies").is_empty());
+    }
+
+    #[test]
+    fn test_previous_choice_matches_same_text() {
+        let dir = TempDir::new().unwrap();
+        let history = History::new(dir.path().join("history.jsonl"));
+        assert_eq!(history.previous_choice("Ala ma kota").unwrap(), None);
+
+        history.append(&entry(100, "Ala ma kota")).unwrap();
+        history.append(&entry(200, "Inny tekst")).unwrap();
+        let mut later = entry(300, "Ala ma kota");
+        later.provider = "Anthropic".to_string();
+        history.append(&later).unwrap();
+
+        let found = history.previous_choice("  Ala  ma\nkota ").unwrap().unwrap();
+        assert_eq!(found.timestamp, 300);
+        assert_eq!(found.provider, "Anthropic");
+        assert_eq!(history.previous_choice("Ala ma psa").unwrap(), None);
+        assert_ne!(text_hash("Ala ma kota"), text_hash("Ala makota"));
+    }
+}
@@ -0,0 +1,178 @@
+//! Szyfrowanie historii hasłem użytkownika
+//!
+//! Klucz powstaje z hasła przez Argon2id z losową solą; każda linia historii
+//! jest szyfrowana osobno ChaCha20-Poly1305 z własnym nonce, więc dopisywanie
+//! wpisów nie wymaga przepisywania pliku. Sól i zaszyfrowany tekst kontrolny
+//! (do sprawdzenia hasła) leżą w `history.key` obok pliku historii.
+
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Prefiks zaszyfrowanej linii historii
+pub const ENCRYPTED_PREFIX: &str = "enc1:";
+const NONCE_LEN: usize = 12;
+const SALT_LEN: usize = 16;
+/// Tekst kontrolny; jego odszyfrowanie potwierdza poprawność hasła
+const CHECK_TEXT: &str = "poprawiacz-tekstu-rs";
+
+/// Klucz wyprowadzony z hasła
+#[derive(Clone)]
+pub struct HistoryKey {
+    cipher: ChaCha20Poly1305,
+}
+
+impl HistoryKey {
+    pub fn derive(passphrase: &str, salt: &[u8]) -> Result<Self, String> {
+        let mut key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+            .map_err(|e| format!("Key derivation failed: {}", e))?;
+        Ok(Self {
+            cipher: ChaCha20Poly1305::new(Key::from_slice(&key)),
+        })
+    }
+
+    /// Linia `enc1:<base64(nonce || szyfrogram)>`
+    pub fn encrypt_line(&self, plaintext: &str) -> Result<String, String> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|e| format!("Encryption failed: {}", e))?;
+
+        let mut data = nonce.to_vec();
+        data.extend_from_slice(&ciphertext);
+        Ok(format!("{}{}", ENCRYPTED_PREFIX, STANDARD.encode(data)))
+    }
+
+    pub fn decrypt_line(&self, line: &str) -> Result<String, String> {
+        let encoded = line
+            .strip_prefix(ENCRYPTED_PREFIX)
+            .ok_or("Line is not encrypted")?;
+        let data = STANDARD
+            .decode(encoded.trim())
+            .map_err(|e| format!("Invalid base64: {}", e))?;
+        if data.len() < NONCE_LEN {
+            return Err("Encrypted line too short".to_string());
+        }
+
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        let plaintext = self
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| "Wrong passphrase or corrupted entry".to_string())?;
+        String::from_utf8(plaintext).map_err(|e| format!("Invalid UTF-8: {}", e))
+    }
+}
+
+pub fn is_encrypted_line(line: &str) -> bool {
+    line.starts_with(ENCRYPTED_PREFIX)
+}
+
+/// Zawartość `history.key`: sól i tekst kontrolny
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct KeyFile {
+    salt: String,
+    check: String,
+}
+
+impl KeyFile {
+    /// Plik klucza obok pliku historii
+    pub fn path_for(history_path: &Path) -> PathBuf {
+        history_path.with_file_name("history.key")
+    }
+
+    /// Nowa sól dla hasła; zwraca też gotowy klucz
+    pub fn create(passphrase: &str) -> Result<(Self, HistoryKey), String> {
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+
+        let key = HistoryKey::derive(passphrase, &salt)?;
+        let check = key.encrypt_line(CHECK_TEXT)?;
+        Ok((
+            Self {
+                salt: STANDARD.encode(salt),
+                check,
+            },
+            key,
+        ))
+    }
+
+    /// Klucz z hasła; błąd przy złym haśle
+    pub fn unlock(&self, passphrase: &str) -> Result<HistoryKey, String> {
+        let salt = STANDARD
+            .decode(&self.salt)
+            .map_err(|e| format!("Invalid salt: {}", e))?;
+        let key = HistoryKey::derive(passphrase, &salt)?;
+        match key.decrypt_line(&self.check) {
+            Ok(check) if check == CHECK_TEXT => Ok(key),
+            _ => Err("Nieprawidłowe hasło".to_string()),
+        }
+    }
+
+    /// `None`, gdy hasło nie zostało jeszcze ustawione
+    pub fn load(path: &Path) -> Result<Option<Self>, Box<dyn std::error::Error>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(path)?;
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_encrypt_decrypt_line() {
+        let (_, key) = KeyFile::create("hasło").unwrap();
+        let line = key.encrypt_line("{\"original\":\"Zażółć\"}").unwrap();
+
+        assert!(is_encrypted_line(&line));
+        assert!(!line.contains("Zażółć"));
+        assert_eq!(key.decrypt_line(&line).unwrap(), "{\"original\":\"Zażółć\"}");
+        // Każda linia ma własny nonce
+        assert_ne!(line, key.encrypt_line("{\"original\":\"Zażółć\"}").unwrap());
+    }
+
+    #[test]
+    fn test_unlock_checks_passphrase() {
+        let dir = TempDir::new().unwrap();
+        let path = KeyFile::path_for(&dir.path().join("history.jsonl"));
+        assert!(KeyFile::load(&path).unwrap().is_none());
+
+        let (key_file, key) = KeyFile::create("tajne").unwrap();
+        key_file.save(&path).unwrap();
+        let line = key.encrypt_line("wpis").unwrap();
+
+        let loaded = KeyFile::load(&path).unwrap().unwrap();
+        assert_eq!(loaded, key_file);
+        assert!(loaded.unlock("inne").is_err());
+        assert_eq!(loaded.unlock("tajne").unwrap().decrypt_line(&line).unwrap(), "wpis");
+    }
+
+    #[test]
+    fn test_decrypt_rejects_garbage() {
+        let (_, key) = KeyFile::create("hasło").unwrap();
+        assert!(key.decrypt_line("{\"plain\":1}").is_err());
+        assert!(key.decrypt_line("enc1:!!!").is_err());
+        assert!(key.decrypt_line("enc1:AAAA").is_err());
+    }
+}
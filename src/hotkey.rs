@@ -2,26 +2,46 @@ use global_hotkey::{
     hotkey::{Code, HotKey, Modifiers},
     GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState,
 };
+use crate::config::GlobalHotkey;
+use crate::power::PowerState;
+use crate::prompts::CorrectionStyle;
+use std::str::FromStr;
 use std::sync::Arc;
-use tokio::sync::mpsc;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, watch};
 use tracing::{error, info, warn};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum HotkeyEvent {
     Triggered,
+    /// Skrót przypisany do stylu: tekst idzie od razu w tym stylu
+    Style(CorrectionStyle),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum HotkeyCombo {
     Primary,
     Fallback,
+    /// Kombinacja z ustawień w postaci z `normalize_combination`
+    Custom(String, HotKey),
 }
 
 impl HotkeyCombo {
-    pub fn description(&self) -> &'static str {
+    /// Skrót z konfiguracji; domyślna kombinacja to `Primary`
+    pub fn from_config(combination: &str) -> Result<Self, String> {
+        let description = normalize_combination(combination)?;
+        if description == HotkeyCombo::Primary.description() {
+            return Ok(HotkeyCombo::Primary);
+        }
+        let hotkey = HotKey::from_str(&description).map_err(|e| e.to_string())?;
+        Ok(HotkeyCombo::Custom(description, hotkey))
+    }
+
+    pub fn description(&self) -> &str {
         match self {
             HotkeyCombo::Primary => "Ctrl+Shift+C",
             HotkeyCombo::Fallback => "Ctrl+Shift+Alt+C",
+            HotkeyCombo::Custom(description, _) => description,
         }
     }
 
@@ -35,6 +55,245 @@ impl HotkeyCombo {
                 Some(Modifiers::CONTROL | Modifiers::SHIFT | Modifiers::ALT),
                 Code::KeyC,
             ),
+            HotkeyCombo::Custom(_, hotkey) => *hotkey,
+        }
+    }
+}
+
+/// Kombinacje proponowane, gdy skrót główny zajął inny program (kolejność to kolejność propozycji)
+pub const ALTERNATIVES: &[&str] = &[
+    "Ctrl+Shift+Alt+C",
+    "Ctrl+Alt+C",
+    "Alt+Super+C",
+    "Ctrl+Shift+K",
+    "Ctrl+Alt+K",
+    "Ctrl+Shift+Space",
+];
+
+/// Najwięcej wolnych kombinacji pokazywanych w oknie konfliktu
+pub const MAX_SUGGESTIONS: usize = 3;
+
+/// Co ile pętla skrótu wstrzymana przez oszczędzanie energii sprawdza, czy ją wznowiono
+pub const SUSPENDED_RECHECK: Duration = Duration::from_secs(1);
+
+/// Skrót główny, którego nie udało się zarejestrować, i wolne kombinacje w zamian
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HotkeyConflict {
+    pub combination: String,
+    pub error: String,
+    pub alternatives: Vec<String>,
+    /// Skrót, który nadal działa (poprzedni po nieudanej zmianie)
+    pub active: Option<String>,
+}
+
+/// Kandydaci z `ALTERNATIVES` poza zajętym skrótem i kombinacjami z `taken` (identyfikatory `HotKey`)
+pub fn alternative_candidates(requested: &HotkeyCombo, taken: &[u32]) -> Vec<HotkeyCombo> {
+    let requested = requested.to_hotkey().id();
+    ALTERNATIVES
+        .iter()
+        .filter_map(|combination| HotkeyCombo::from_config(combination).ok())
+        .filter(|combo| {
+            let id = combo.to_hotkey().id();
+            id != requested && !taken.contains(&id)
+        })
+        .collect()
+}
+
+/// Skrót główny i skróty stylów przekazywane wątkowi skrótu
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HotkeyBindings {
+    pub main: HotkeyCombo,
+    pub styles: Vec<(HotkeyCombo, CorrectionStyle)>,
+}
+
+impl Default for HotkeyBindings {
+    fn default() -> Self {
+        Self {
+            main: HotkeyCombo::Primary,
+            styles: Vec::new(),
+        }
+    }
+}
+
+impl HotkeyBindings {
+    /// Skróty z konfiguracji; nieprawidłowe i powtarzające skrót główny są pomijane z ostrzeżeniem
+    pub fn from_config(config: &GlobalHotkey) -> Self {
+        let main = HotkeyCombo::from_config(&config.combination).unwrap_or_else(|e| {
+            warn!("Invalid hotkey '{}': {}", config.combination, e);
+            HotkeyCombo::Primary
+        });
+        let styles = config
+            .styles
+            .iter()
+            .filter_map(|(combination, style)| match HotkeyCombo::from_config(combination) {
+                Ok(combo) if combo.to_hotkey().id() == main.to_hotkey().id() => {
+                    warn!("Style hotkey {} is already the main hotkey", combo.description());
+                    None
+                }
+                Ok(combo) => Some((combo, *style)),
+                Err(e) => {
+                    warn!("Invalid style hotkey '{}': {}", combination, e);
+                    None
+                }
+            })
+            .collect();
+        Self { main, styles }
+    }
+}
+
+/// Błędy skrótów stylów z formularza: nieprawidłowe kombinacje i powtórzenia
+/// (skrótu głównego albo innego stylu); puste pola są pomijane
+pub fn style_binding_errors(main: &str, styles: &[(CorrectionStyle, String)]) -> Vec<(CorrectionStyle, String)> {
+    let main = normalize_combination(main).ok();
+    let mut seen: Vec<(String, CorrectionStyle)> = Vec::new();
+    let mut errors = Vec::new();
+    for (style, combination) in styles.iter().filter(|(_, c)| !c.trim().is_empty()) {
+        match normalize_combination(combination) {
+            Err(e) => errors.push((*style, e)),
+            Ok(normalized) if main.as_deref() == Some(normalized.as_str()) => {
+                errors.push((*style, "ten sam skrót co główny".to_string()))
+            }
+            Ok(normalized) => match seen.iter().find(|(c, _)| *c == normalized) {
+                Some((_, other)) => errors.push((*style, format!("ten sam skrót co „{}”", other.display_name_pl()))),
+                None => seen.push((normalized, *style)),
+            },
+        }
+    }
+    errors
+}
+
+/// Sprowadza kombinację do zapisu "Ctrl+Shift+Alt+Super+Klawisz"; wymaga modyfikatora,
+/// żeby sam klawisz nie został przechwycony we wszystkich programach
+pub fn normalize_combination(combination: &str) -> Result<String, String> {
+    let mut modifiers = [false; 4];
+    let mut key: Option<String> = None;
+    for token in combination.split('+').map(str::trim) {
+        let slot = match token.to_ascii_lowercase().as_str() {
+            "ctrl" | "control" => Some(0),
+            "shift" => Some(1),
+            "alt" | "option" => Some(2),
+            "super" | "meta" | "cmd" | "win" => Some(3),
+            _ => None,
+        };
+        match (slot, &key) {
+            (Some(i), None) => modifiers[i] = true,
+            (None, None) if !token.is_empty() => {
+                key = Some(if token.chars().count() == 1 { token.to_uppercase() } else { token.to_string() })
+            }
+            (_, Some(_)) => return Err("klawisz musi być ostatni".to_string()),
+            (None, None) => return Err("pusty element kombinacji".to_string()),
+        }
+    }
+    let key = key.ok_or("brak klawisza")?;
+    if !modifiers.iter().any(|m| *m) {
+        return Err("dodaj Ctrl, Alt albo Super".to_string());
+    }
+
+    let mut parts: Vec<&str> = ["Ctrl", "Shift", "Alt", "Super"]
+        .iter()
+        .zip(modifiers)
+        .filter_map(|(name, on)| on.then_some(*name))
+        .collect();
+    parts.push(&key);
+    let description = parts.join("+");
+    HotKey::from_str(&description).map_err(|_| format!("nieznany klawisz „{}”", key))?;
+    Ok(description)
+}
+
+/// Kombinacja z klawisza GDK (np. "c", "F5", "space") i wciśniętych modyfikatorów
+pub fn combination_from_key(key_name: &str, ctrl: bool, shift: bool, alt: bool, super_key: bool) -> Option<String> {
+    let key = match key_name {
+        "space" => "Space".to_string(),
+        "Return" => "Enter".to_string(),
+        "Delete" | "Insert" | "Home" | "End" | "Tab" => key_name.to_string(),
+        "Page_Up" => "PageUp".to_string(),
+        "Page_Down" => "PageDown".to_string(),
+        name if name.chars().count() == 1 => name.to_uppercase(),
+        name if name.starts_with('F') && name[1..].parse::<u8>().is_ok() => name.to_string(),
+        _ => return None,
+    };
+    let mut parts: Vec<&str> = ["Ctrl", "Shift", "Alt", "Super"]
+        .iter()
+        .zip([ctrl, shift, alt, super_key])
+        .filter_map(|(name, on)| on.then_some(*name))
+        .collect();
+    parts.push(&key);
+    normalize_combination(&parts.join("+")).ok()
+}
+
+/// Akcja po dwukrotnym naciśnięciu skrótu w krótkim odstępie
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DoublePressAction {
+    /// Podwójne naciśnięcie działa jak dwa pojedyncze
+    None,
+    /// Tłumaczenie schowka (na polski, gdy tekst jest po angielsku)
+    Translate,
+    /// Ponowne wysłanie tekstu z ostatniej sesji
+    RerunLast,
+    /// Okienko wyboru stylu przy kursorze
+    Palette,
+}
+
+impl DoublePressAction {
+    pub fn from_config(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "translate" => Self::Translate,
+            "rerun_last" => Self::RerunLast,
+            "palette" => Self::Palette,
+            _ => Self::None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Translate => "translate",
+            Self::RerunLast => "rerun_last",
+            Self::Palette => "palette",
+        }
+    }
+
+    pub fn all() -> &'static [DoublePressAction] {
+        &[Self::None, Self::Translate, Self::RerunLast, Self::Palette]
+    }
+
+    pub fn display_name_pl(&self) -> &'static str {
+        match self {
+            Self::None => "Brak",
+            Self::Translate => "Tłumaczenie",
+            Self::RerunLast => "Powtórz ostatnią sesję",
+            Self::Palette => "Szybka paleta stylów",
+        }
+    }
+}
+
+/// Rozpoznaje drugie naciśnięcie skrótu w oknie czasowym po pierwszym
+#[derive(Debug, Clone)]
+pub struct DoublePressDetector {
+    window: Duration,
+    last_press: Option<Instant>,
+}
+
+impl DoublePressDetector {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            last_press: None,
+        }
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// `true`, gdy naciśnięcie kończy podwójne; trzecie naciśnięcie zaczyna od nowa
+    pub fn press(&mut self, now: Instant) -> bool {
+        match self.last_press.take() {
+            Some(last) if now.saturating_duration_since(last) <= self.window => true,
+            _ => {
+                self.last_press = Some(now);
+                false
+            }
         }
     }
 }
@@ -43,11 +302,20 @@ pub struct HotkeyManager {
     manager: Arc<GlobalHotKeyManager>,
     registered_hotkey: Option<HotKey>,
     active_combo: Option<HotkeyCombo>,
+    style_hotkeys: Vec<(HotKey, CorrectionStyle)>,
     tx: mpsc::UnboundedSender<HotkeyEvent>,
+    /// Ostatni konflikt skrótu głównego; `None` po udanej rejestracji
+    conflicts: watch::Sender<Option<HotkeyConflict>>,
 }
 
 impl HotkeyManager {
-    pub fn new(tx: mpsc::UnboundedSender<HotkeyEvent>) -> Result<Self, String> {
+    /// Rejestruje `combo`. Zajęty skrót nie jest zastępowany zapasowym: konflikt
+    /// z wolnymi kombinacjami trafia do `conflicts`, a wybór należy do użytkownika.
+    pub fn new(
+        tx: mpsc::UnboundedSender<HotkeyEvent>,
+        combo: HotkeyCombo,
+        conflicts: watch::Sender<Option<HotkeyConflict>>,
+    ) -> Result<Self, String> {
         let manager = GlobalHotKeyManager::new().map_err(|e| {
             error!("Failed to create GlobalHotKeyManager: {}", e);
             format!("Failed to create hotkey manager: {}", e)
@@ -57,16 +325,19 @@ impl HotkeyManager {
             manager: Arc::new(manager),
             registered_hotkey: None,
             active_combo: None,
+            style_hotkeys: Vec::new(),
             tx,
+            conflicts,
         };
 
-        hotkey_manager.register_with_fallback()?;
+        if let Err(e) = hotkey_manager.try_register(combo.clone()) {
+            hotkey_manager.report_conflict(&combo, e);
+        }
 
         Ok(hotkey_manager)
     }
 
-    fn try_register_primary_hotkey(&mut self) -> Result<(), String> {
-        let combo = HotkeyCombo::Primary;
+    fn try_register(&mut self, combo: HotkeyCombo) -> Result<(), String> {
         let hotkey = combo.to_hotkey();
 
         self.manager.register(hotkey).map_err(|e| {
@@ -74,75 +345,187 @@ impl HotkeyManager {
             format!("Failed to register {}: {}", combo.description(), e)
         })?;
 
-        self.registered_hotkey = Some(hotkey);
-        self.active_combo = Some(combo.clone());
         info!(
             "Global hotkey {} registered successfully",
             combo.description()
         );
+        self.registered_hotkey = Some(hotkey);
+        self.active_combo = Some(combo);
+        self.conflicts.send_if_modified(|conflict| conflict.take().is_some());
 
         Ok(())
     }
 
-    fn try_register_fallback_hotkey(&mut self) -> Result<(), String> {
-        let combo = HotkeyCombo::Fallback;
+    /// Czy kombinację da się teraz zarejestrować (rejestruje ją na chwilę)
+    fn is_free(&self, combo: &HotkeyCombo) -> bool {
         let hotkey = combo.to_hotkey();
+        if self.manager.register(hotkey).is_err() {
+            return false;
+        }
+        if let Err(e) = self.manager.unregister(hotkey) {
+            error!("Failed to unregister probed hotkey {}: {}", combo.description(), e);
+        }
+        true
+    }
 
-        self.manager.register(hotkey).map_err(|e| {
-            error!("Failed to register {}: {}", combo.description(), e);
-            format!("Failed to register {}: {}", combo.description(), e)
-        })?;
-
-        self.registered_hotkey = Some(hotkey);
-        self.active_combo = Some(combo.clone());
-        info!(
-            "Fallback hotkey {} registered successfully",
-            combo.description()
+    /// Publikuje konflikt skrótu głównego z wolnymi kombinacjami z `ALTERNATIVES`
+    fn report_conflict(&self, combo: &HotkeyCombo, error: String) {
+        let mut taken: Vec<u32> = self.style_hotkeys.iter().map(|(hotkey, _)| hotkey.id()).collect();
+        taken.extend(self.registered_hotkey.map(|hotkey| hotkey.id()));
+        let alternatives: Vec<String> = alternative_candidates(combo, &taken)
+            .into_iter()
+            .filter(|candidate| self.is_free(candidate))
+            .take(MAX_SUGGESTIONS)
+            .map(|candidate| candidate.description().to_string())
+            .collect();
+        warn!(
+            "Hotkey {} is taken by another application, free alternatives: {:?}",
+            combo.description(),
+            alternatives
         );
+        let conflict = HotkeyConflict {
+            combination: combo.description().to_string(),
+            error,
+            alternatives,
+            active: self.active_combo.as_ref().map(|combo| combo.description().to_string()),
+        };
+        // Ponowna nieudana próba tego samego skrótu (np. pierwszy przebieg pętli) nie otwiera drugiego okna
+        self.conflicts.send_if_modified(|current| {
+            let repeated = current.as_ref().is_some_and(|c| c.combination == conflict.combination);
+            *current = Some(conflict);
+            !repeated
+        });
+    }
 
-        Ok(())
+    fn unregister(&mut self) {
+        if let Some(hotkey) = self.registered_hotkey.take() {
+            if let Err(e) = self.manager.unregister(hotkey) {
+                error!("Failed to unregister hotkey: {}", e);
+            } else {
+                info!("Hotkey unregistered successfully");
+            }
+        }
+        self.active_combo = None;
     }
 
-    fn register_with_fallback(&mut self) -> Result<(), String> {
-        if self.try_register_primary_hotkey().is_ok() {
+    /// Zamienia zarejestrowany skrót na `combo`; gdy nowy jest zajęty, wraca do poprzedniego
+    pub fn rebind(&mut self, combo: HotkeyCombo) -> Result<(), String> {
+        if self.active_combo.as_ref() == Some(&combo) {
             return Ok(());
         }
+        let previous = self.active_combo.clone();
+        self.unregister();
+        match self.try_register(combo) {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                if let Some(previous) = previous {
+                    let _ = self.try_register(previous);
+                }
+                Err(e)
+            }
+        }
+    }
 
-        warn!("Primary hotkey registration failed, trying fallback...");
+    pub fn active_combo(&self) -> Option<&HotkeyCombo> {
+        self.active_combo.as_ref()
+    }
 
-        if self.try_register_fallback_hotkey().is_ok() {
-            return Ok(());
+    /// Rejestruje skróty stylów w miejsce poprzednich; zajęte kombinacje są pomijane
+    pub fn register_styles(&mut self, styles: &[(HotkeyCombo, CorrectionStyle)]) {
+        self.unregister_styles();
+        for (combo, style) in styles {
+            let hotkey = combo.to_hotkey();
+            match self.manager.register(hotkey) {
+                Ok(()) => {
+                    info!("Style hotkey {} registered for {}", combo.description(), style.as_str());
+                    self.style_hotkeys.push((hotkey, *style));
+                }
+                Err(e) => warn!("Failed to register style hotkey {}: {}", combo.description(), e),
+            }
+        }
+    }
+
+    fn unregister_styles(&mut self) {
+        for (hotkey, _) in self.style_hotkeys.drain(..) {
+            if let Err(e) = self.manager.unregister(hotkey) {
+                error!("Failed to unregister style hotkey: {}", e);
+            }
         }
+    }
 
-        error!("Failed to register any hotkey - manual mode required");
-        Err("Failed to register any hotkey".to_string())
+    /// Przerejestrowuje skrót główny i skróty stylów
+    pub fn apply(&mut self, bindings: &HotkeyBindings) -> Result<(), String> {
+        // Skrót stylu może stać się głównym, więc najpierw zwalniamy style
+        self.unregister_styles();
+        let result = self.rebind(bindings.main.clone());
+        self.register_styles(&bindings.styles);
+        if let Err(e) = &result {
+            self.report_conflict(&bindings.main, e.clone());
+        }
+        result
     }
 
-    pub fn active_combo(&self) -> Option<&HotkeyCombo> {
-        self.active_combo.as_ref()
+    /// Zdarzenie dla naciśniętego skrótu o danym identyfikatorze
+    fn event_for(&self, id: u32) -> Option<HotkeyEvent> {
+        if self.registered_hotkey.is_some_and(|hotkey| hotkey.id() == id) {
+            return Some(HotkeyEvent::Triggered);
+        }
+        self.style_hotkeys
+            .iter()
+            .find(|(hotkey, _)| hotkey.id() == id)
+            .map(|(_, style)| HotkeyEvent::Style(*style))
     }
 
-    pub fn start_event_loop(self) -> tokio::task::JoinHandle<()> {
+    /// Odpytuje zdarzenia skrótu co `interval`; przy wstrzymaniu przez `power`
+    /// czeka na zdarzenie, budząc się tylko co `SUSPENDED_RECHECK`, żeby
+    /// zauważyć wznowienie. Nowe skróty z `bindings` od razu zastępują zarejestrowane.
+    pub fn start_event_loop(
+        mut self,
+        interval: Duration,
+        power: PowerState,
+        mut bindings: watch::Receiver<HotkeyBindings>,
+    ) -> tokio::task::JoinHandle<()> {
         tokio::spawn(async move {
             let receiver = GlobalHotKeyEvent::receiver();
-            info!("Hotkey event loop started");
+            info!("Hotkey event loop started ({} ms)", interval.as_millis());
 
             loop {
-                if let Ok(event) = receiver.try_recv() {
+                if bindings.has_changed().unwrap_or(false) {
+                    let changed = bindings.borrow_and_update().clone();
+                    if let Err(e) = self.apply(&changed) {
+                        error!("Hotkey not changed: {}", e);
+                    }
+                }
+
+                let event = if power.is_suspended() {
+                    let waited = tokio::task::spawn_blocking(|| {
+                        GlobalHotKeyEvent::receiver().recv_timeout(SUSPENDED_RECHECK)
+                    })
+                    .await;
+                    match waited {
+                        Ok(Ok(event)) => Some(event),
+                        Ok(Err(e)) if e.is_timeout() => None,
+                        _ => break,
+                    }
+                } else {
+                    let event = receiver.try_recv().ok();
+                    if event.is_none() {
+                        tokio::time::sleep(interval).await;
+                    }
+                    event
+                };
+
+                if let Some(event) = event {
                     if event.state == HotKeyState::Pressed {
-                        if let Some(registered) = self.registered_hotkey {
-                            if event.id == registered.id() {
-                                info!("Hotkey triggered: {:?}", self.active_combo);
-                                if let Err(e) = self.tx.send(HotkeyEvent::Triggered) {
-                                    error!("Failed to send hotkey event: {}", e);
-                                    break;
-                                }
+                        if let Some(hotkey_event) = self.event_for(event.id) {
+                            info!("Hotkey triggered: {:?} ({:?})", hotkey_event, self.active_combo);
+                            if let Err(e) = self.tx.send(hotkey_event) {
+                                error!("Failed to send hotkey event: {}", e);
+                                break;
                             }
                         }
                     }
                 }
-
-                tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
             }
 
             warn!("Hotkey event loop terminated");
@@ -152,13 +535,8 @@ impl HotkeyManager {
 
 impl Drop for HotkeyManager {
     fn drop(&mut self) {
-        if let Some(hotkey) = self.registered_hotkey {
-            if let Err(e) = self.manager.unregister(hotkey) {
-                error!("Failed to unregister hotkey: {}", e);
-            } else {
-                info!("Hotkey unregistered successfully");
-            }
-        }
+        self.unregister_styles();
+        self.unregister();
     }
 }
 
@@ -172,24 +550,24 @@ mod tests {
     async fn test_hotkey_registration_succeeds() {
         let (tx, _rx) = mpsc::unbounded_channel();
         
-        let result = HotkeyManager::new(tx);
+        let (conflicts, conflict) = watch::channel(None);
+        
+        let result = HotkeyManager::new(tx, HotkeyCombo::Primary, conflicts);
         
         assert!(
             result.is_ok(),
-            "Hotkey registration should succeed with primary or fallback"
+            "Manager creation should succeed even when the hotkey is taken"
         );
         
         let manager = result.unwrap();
-        assert!(
-            manager.active_combo().is_some(),
-            "Active combo should be set"
-        );
-        
-        let combo = manager.active_combo().unwrap();
-        assert!(
-            *combo == HotkeyCombo::Primary || *combo == HotkeyCombo::Fallback,
-            "Active combo should be Primary or Fallback"
-        );
+        match manager.active_combo() {
+            Some(combo) => assert_eq!(*combo, HotkeyCombo::Primary, "No silent fallback"),
+            None => assert_eq!(
+                conflict.borrow().as_ref().map(|c| c.combination.as_str()),
+                Some("Ctrl+Shift+C"),
+                "A taken hotkey should be reported as a conflict"
+            ),
+        }
     }
 
     #[tokio::test]
@@ -198,6 +576,62 @@ mod tests {
         assert_eq!(HotkeyCombo::Fallback.description(), "Ctrl+Shift+Alt+C");
     }
 
+    #[test]
+    fn test_normalize_combination() {
+        assert_eq!(normalize_combination("shift + ctrl + c").unwrap(), "Ctrl+Shift+C");
+        assert_eq!(normalize_combination("Super+Alt+F5").unwrap(), "Alt+Super+F5");
+        assert!(normalize_combination("C").is_err());
+        assert!(normalize_combination("Ctrl+Shift").is_err());
+        assert!(normalize_combination("Ctrl+C+Shift").is_err());
+        assert!(normalize_combination("Ctrl+Nope").is_err());
+
+        assert_eq!(HotkeyCombo::from_config("ctrl+shift+c").unwrap(), HotkeyCombo::Primary);
+        let custom = HotkeyCombo::from_config("Alt+Space").unwrap();
+        assert_eq!(custom.description(), "Alt+Space");
+        assert_ne!(custom.to_hotkey().id(), HotkeyCombo::Primary.to_hotkey().id());
+    }
+
+    #[test]
+    fn test_bindings_from_config() {
+        let mut config = GlobalHotkey::default();
+        config.styles.insert("ctrl+shift+t".to_string(), CorrectionStyle::TranslateEn);
+        config.styles.insert("Ctrl+Shift+C".to_string(), CorrectionStyle::Summary);
+        config.styles.insert("Ctrl+".to_string(), CorrectionStyle::Prompt);
+
+        let bindings = HotkeyBindings::from_config(&config);
+        assert_eq!(bindings.main, HotkeyCombo::Primary);
+        assert_eq!(bindings.styles.len(), 1);
+        assert_eq!(bindings.styles[0].0.description(), "Ctrl+Shift+T");
+        assert_eq!(bindings.styles[0].1, CorrectionStyle::TranslateEn);
+    }
+
+    #[test]
+    fn test_style_binding_errors() {
+        let styles = vec![
+            (CorrectionStyle::TranslateEn, "Ctrl+Shift+T".to_string()),
+            (CorrectionStyle::Professional, "shift+ctrl+t".to_string()),
+            (CorrectionStyle::Summary, "Ctrl+Shift+C".to_string()),
+            (CorrectionStyle::Prompt, "P".to_string()),
+            (CorrectionStyle::ChangeMeaning, " ".to_string()),
+        ];
+        let errors = style_binding_errors("Ctrl+Shift+C", &styles);
+        let styles: Vec<CorrectionStyle> = errors.iter().map(|(s, _)| *s).collect();
+        assert_eq!(
+            styles,
+            vec![CorrectionStyle::Professional, CorrectionStyle::Summary, CorrectionStyle::Prompt]
+        );
+        assert_eq!(errors[0].1, "ten sam skrót co „Przetłumacz na angielski”");
+    }
+
+    #[test]
+    fn test_combination_from_key() {
+        assert_eq!(combination_from_key("c", true, true, false, false).as_deref(), Some("Ctrl+Shift+C"));
+        assert_eq!(combination_from_key("space", false, false, true, false).as_deref(), Some("Alt+Space"));
+        assert_eq!(combination_from_key("F9", false, false, false, true).as_deref(), Some("Super+F9"));
+        assert_eq!(combination_from_key("c", false, false, false, false), None);
+        assert_eq!(combination_from_key("Shift_L", true, true, false, false), None);
+    }
+
     #[tokio::test]
     async fn test_hotkey_combos_generate_different_hotkeys() {
         let primary = HotkeyCombo::Primary.to_hotkey();
@@ -211,11 +645,15 @@ mod tests {
     async fn test_event_forwarding_via_channel() {
         let (tx, mut rx) = mpsc::unbounded_channel();
         
-        let manager = HotkeyManager::new(tx);
+        let manager = HotkeyManager::new(tx, HotkeyCombo::Primary, watch::channel(None).0);
         assert!(manager.is_ok(), "Manager creation should succeed");
         
         let manager = manager.unwrap();
-        let _handle = manager.start_event_loop();
+        let _handle = manager.start_event_loop(
+            Duration::from_millis(50),
+            PowerState::new(),
+            watch::channel(HotkeyBindings::default()).1,
+        );
         
         sleep(Duration::from_millis(100)).await;
         assert!(!rx.is_closed(), "Channel should remain open");
@@ -226,14 +664,32 @@ mod tests {
 
     #[test]
     #[ignore] // Requires X11 GrabKey
-    fn test_fallback_registration_logic() {
+    fn test_conflict_is_cleared_after_registration() {
         let (tx, _rx) = mpsc::unbounded_channel();
-        let manager = HotkeyManager::new(tx);
+        let (conflicts, conflict) = watch::channel(None);
+        let mut manager = HotkeyManager::new(tx, HotkeyCombo::Primary, conflicts).expect("Manager creation should succeed");
         
-        assert!(
-            manager.is_ok(),
-            "Should register at least one hotkey (primary or fallback)"
-        );
+        let free = HotkeyCombo::from_config("Ctrl+Shift+Alt+F12").unwrap();
+        assert!(manager.rebind(free).is_ok(), "Free combination should register");
+        assert!(conflict.borrow().is_none(), "Successful registration clears the conflict");
+    }
+
+    #[test]
+    #[ignore] // Requires X11 GrabKey
+    fn test_failed_rebind_reports_previous_combo() {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let taken = HotkeyCombo::from_config("Ctrl+Shift+Alt+F11").unwrap();
+        let _holder = HotkeyManager::new(tx.clone(), taken.clone(), watch::channel(None).0).expect("Manager creation should succeed");
+
+        let (conflicts, conflict) = watch::channel(None);
+        let free = HotkeyCombo::from_config("Ctrl+Shift+Alt+F12").unwrap();
+        let mut manager = HotkeyManager::new(tx, free.clone(), conflicts).expect("Manager creation should succeed");
+        let bindings = HotkeyBindings { main: taken, styles: Vec::new() };
+        assert!(manager.apply(&bindings).is_err(), "Taken combination should not register");
+
+        assert_eq!(manager.active_combo(), Some(&free), "Previous hotkey stays registered");
+        let active = conflict.borrow().as_ref().and_then(|c| c.active.clone());
+        assert_eq!(active.as_deref(), Some("Ctrl+Shift+Alt+F12"));
     }
 
     #[tokio::test]
@@ -242,11 +698,11 @@ mod tests {
         let (tx, _rx) = mpsc::unbounded_channel();
         
         {
-            let manager = HotkeyManager::new(tx.clone());
+            let manager = HotkeyManager::new(tx.clone(), HotkeyCombo::Primary, watch::channel(None).0);
             assert!(manager.is_ok(), "Manager creation should succeed");
         }
         
-        let manager2 = HotkeyManager::new(tx);
+        let manager2 = HotkeyManager::new(tx, HotkeyCombo::Primary, watch::channel(None).0);
         assert!(
             manager2.is_ok(),
             "Should be able to create new manager after previous one was dropped"
@@ -257,7 +713,7 @@ mod tests {
     #[ignore] // Requires X11 GrabKey
     fn test_active_combo_is_set_after_registration() {
         let (tx, _rx) = mpsc::unbounded_channel();
-        let manager = HotkeyManager::new(tx).expect("Manager creation should succeed");
+        let manager = HotkeyManager::new(tx, HotkeyCombo::Primary, watch::channel(None).0).expect("Manager creation should succeed");
         
         assert!(
             manager.active_combo().is_some(),
@@ -265,6 +721,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_alternative_candidates() {
+        let names = |combos: Vec<HotkeyCombo>| -> Vec<String> {
+            combos.iter().map(|c| c.description().to_string()).collect()
+        };
+        let all = names(alternative_candidates(&HotkeyCombo::Primary, &[]));
+        assert_eq!(all, ALTERNATIVES);
+
+        let taken = [HotkeyCombo::Fallback.to_hotkey().id()];
+        let custom = HotkeyCombo::from_config("ctrl+alt+c").unwrap();
+        let rest = names(alternative_candidates(&custom, &taken));
+        assert_eq!(rest[0], "Alt+Super+C");
+        assert!(!rest.iter().any(|c| c == "Ctrl+Alt+C" || c == "Ctrl+Shift+Alt+C"));
+    }
+
+    #[test]
+    fn test_double_press_detector() {
+        let start = Instant::now();
+        let mut detector = DoublePressDetector::new(Duration::from_millis(500));
+
+        assert!(!detector.press(start));
+        assert!(detector.press(start + Duration::from_millis(300)));
+        // Po podwójnym naciśnięciu liczenie zaczyna się od nowa
+        assert!(!detector.press(start + Duration::from_millis(400)));
+        assert!(!detector.press(start + Duration::from_millis(1000)));
+        assert!(detector.press(start + Duration::from_millis(1500)));
+    }
+
+    #[test]
+    fn test_double_press_action_roundtrip() {
+        for action in DoublePressAction::all() {
+            assert_eq!(DoublePressAction::from_config(action.as_str()), *action);
+        }
+        assert_eq!(DoublePressAction::from_config("Palette"), DoublePressAction::Palette);
+        assert_eq!(DoublePressAction::from_config("unknown"), DoublePressAction::None);
+    }
+
     #[test]
     fn test_hotkey_event_derives() {
         let event1 = HotkeyEvent::Triggered;
@@ -1,39 +1,326 @@
-use ashpd::desktop::global_shortcuts::{GlobalShortcuts, NewShortcut};
+//! Skrót globalny przez portal XDG GlobalShortcuts (Wayland)
+//!
+//! Portal pamięta skróty aplikacji po swojej stronie, ale sesja kończy się
+//! razem z programem. Po pierwszej zgodzie opis skrótu trafia do
+//! `portal_hotkey.json`; przy kolejnym uruchomieniu nowa sesja najpierw pyta
+//! portal o istniejące skróty i wiąże je od nowa (z oknem zgody) tylko wtedy,
+//! gdy ich nie ma albo zmienił się proponowany skrót. Skróty stylów są
+//! wiązane w tej samej sesji, każdy pod własnym identyfikatorem.
+
+use ashpd::desktop::global_shortcuts::{GlobalShortcuts, NewShortcut, Shortcut};
+use ashpd::desktop::Session;
 use ashpd::WindowIdentifier;
 use futures_util::StreamExt;
-use tokio::sync::mpsc;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tokio::sync::{mpsc, oneshot, watch};
 use tracing::{error, info, warn};
 
+use crate::config::Config;
+use crate::hotkey::HotkeyBindings;
+use crate::prompts::CorrectionStyle;
+use crate::shutdown::ShutdownSignal;
+
+const SHORTCUT_ID: &str = "capture-text";
+/// Przedrostek identyfikatorów skrótów stylów (`style-professional`)
+const STYLE_ID_PREFIX: &str = "style-";
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PortalHotkeyEvent {
     Triggered,
+    /// Skrót stylu z `[global_hotkey.Styles]`
+    Style(CorrectionStyle),
+}
+
+/// Polecenia z UI do sesji portalu
+#[derive(Debug)]
+pub enum PortalCommand {
+    /// Nowe powiązanie z oknem wyboru skrótu; odpowiedź to opis skrótu
+    Rebind(oneshot::Sender<Result<String, String>>),
+}
+
+/// Powiązanie zapamiętane między uruchomieniami
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PortalBinding {
+    /// Skrót zaproponowany portalowi, w zapisie portalu (np. `CTRL+SHIFT+c`)
+    #[serde(rename = "PreferredTrigger")]
+    pub preferred_trigger: String,
+    /// Opis skrótu podany przez portal (ten, który naprawdę działa)
+    #[serde(rename = "TriggerDescription")]
+    pub trigger_description: String,
+    /// Skróty stylów zaproponowane portalowi, według identyfikatora skrótu
+    #[serde(rename = "StyleTriggers", default)]
+    pub style_triggers: BTreeMap<String, String>,
+}
+
+impl PortalBinding {
+    /// Plik powiązania obok `config.toml`
+    pub fn default_path() -> PathBuf {
+        Config::get_config_path().with_file_name("portal_hotkey.json")
+    }
+
+    /// Brak lub uszkodzenie pliku daje `None`
+    pub fn load(path: &Path) -> Option<Self> {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Zgoda dotyczyła tych samych proponowanych skrótów, więc wystarczy odczytać powiązanie
+    pub fn can_restore(&self, preferred_trigger: &str, style_triggers: &BTreeMap<String, String>) -> bool {
+        self.preferred_trigger == preferred_trigger
+            && &self.style_triggers == style_triggers
+            && !self.trigger_description.is_empty()
+    }
+}
+
+/// Kombinacja z ustawień w zapisie portalu (`Ctrl+Shift+C` → `CTRL+SHIFT+c`)
+pub fn portal_trigger(combination: &str) -> String {
+    combination
+        .split('+')
+        .map(|token| match token {
+            "Ctrl" => "CTRL".to_string(),
+            "Shift" => "SHIFT".to_string(),
+            "Alt" => "ALT".to_string(),
+            "Super" => "LOGO".to_string(),
+            key if key.chars().count() == 1 => key.to_lowercase(),
+            key => key.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("+")
+}
+
+/// Skróty stylów w zapisie portalu, według identyfikatora skrótu
+pub fn style_triggers(bindings: &HotkeyBindings) -> BTreeMap<String, String> {
+    bindings
+        .styles
+        .iter()
+        .map(|(combo, style)| (style_shortcut_id(*style), portal_trigger(combo.description())))
+        .collect()
+}
+
+fn style_shortcut_id(style: CorrectionStyle) -> String {
+    format!("{}{}", STYLE_ID_PREFIX, style.as_str())
+}
+
+/// Zdarzenie dla skrótu portalu o danym identyfikatorze
+fn event_for(shortcut_id: &str) -> Option<PortalHotkeyEvent> {
+    if shortcut_id == SHORTCUT_ID {
+        return Some(PortalHotkeyEvent::Triggered);
+    }
+    let name = shortcut_id.strip_prefix(STYLE_ID_PREFIX)?;
+    CorrectionStyle::all()
+        .iter()
+        .find(|style| style.as_str() == name)
+        .map(|style| PortalHotkeyEvent::Style(*style))
+}
+
+fn find_shortcut(shortcuts: &[Shortcut]) -> Option<&Shortcut> {
+    shortcuts.iter().find(|shortcut| shortcut.id() == SHORTCUT_ID)
+}
+
+/// Strona UI: opis działającego skrótu i ponowne powiązanie
+#[derive(Clone)]
+pub struct PortalHotkeyHandle {
+    commands: mpsc::UnboundedSender<PortalCommand>,
+    trigger: watch::Receiver<String>,
+}
+
+impl PortalHotkeyHandle {
+    /// Opis skrótu od portalu; `None` przed powiązaniem
+    pub fn trigger(&self) -> Option<String> {
+        Some(self.trigger.borrow().clone()).filter(|t| !t.is_empty())
+    }
+
+    /// Kolejne opisy skrótu (po powiązaniu, zmianie w ustawieniach systemu)
+    pub fn subscribe(&self) -> watch::Receiver<String> {
+        self.trigger.clone()
+    }
+
+    pub async fn rebind(&self) -> Result<String, String> {
+        let (reply, response) = oneshot::channel();
+        self.commands
+            .send(PortalCommand::Rebind(reply))
+            .map_err(|_| "portal skrótów nie działa".to_string())?;
+        response.await.map_err(|_| "portal skrótów nie działa".to_string())?
+    }
 }
 
 pub struct PortalHotkeyManager {
     tx: mpsc::UnboundedSender<PortalHotkeyEvent>,
+    commands: mpsc::UnboundedReceiver<PortalCommand>,
+    trigger: watch::Sender<String>,
+    preferred_trigger: String,
+    style_triggers: BTreeMap<String, String>,
+    binding_path: PathBuf,
 }
 
 impl PortalHotkeyManager {
-    pub fn new(tx: mpsc::UnboundedSender<PortalHotkeyEvent>) -> Self {
-        Self { tx }
+    /// Skrót główny i skróty stylów z ustawień
+    pub fn new(tx: mpsc::UnboundedSender<PortalHotkeyEvent>, bindings: &HotkeyBindings) -> (Self, PortalHotkeyHandle) {
+        let (command_tx, commands) = mpsc::unbounded_channel();
+        let (trigger, trigger_rx) = watch::channel(String::new());
+        let manager = Self {
+            tx,
+            commands,
+            trigger,
+            preferred_trigger: portal_trigger(bindings.main.description()),
+            style_triggers: style_triggers(bindings),
+            binding_path: PortalBinding::default_path(),
+        };
+        let handle = PortalHotkeyHandle {
+            commands: command_tx,
+            trigger: trigger_rx,
+        };
+        (manager, handle)
     }
 
-    pub async fn run(self) -> Result<(), String> {
+    /// Nasłuchuje skrótu do sygnału zamknięcia, po którym zamyka sesję portalu
+    pub async fn run(mut self, mut shutdown: ShutdownSignal) -> Result<(), String> {
         let shortcuts = GlobalShortcuts::new().await.map_err(|e| {
             error!("Failed to create GlobalShortcuts portal: {}", e);
             format!("Portal unavailable: {}", e)
         })?;
 
-        let session = shortcuts.create_session().await.map_err(|e| {
+        let mut session = Self::create_session(&shortcuts).await?;
+
+        // Okno zgody na skrót może czekać na użytkownika; zamknięcie aplikacji na nie nie czeka
+        let started = tokio::select! {
+            trigger = self.start(&shortcuts, &session) => Some(trigger),
+            _ = shutdown.wait() => None,
+        };
+        let trigger = match started {
+            Some(Ok(trigger)) => trigger,
+            Some(Err(e)) => {
+                Self::close_session(session).await;
+                return Err(e);
+            }
+            None => {
+                Self::close_session(session).await;
+                return Ok(());
+            }
+        };
+        self.publish(&trigger);
+
+        let streams = tokio::try_join!(shortcuts.receive_activated(), shortcuts.receive_shortcuts_changed());
+        let (mut activated_stream, mut changed_stream) = match streams {
+            Ok(streams) => streams,
+            Err(e) => {
+                error!("Failed to subscribe to portal signals: {}", e);
+                Self::close_session(session).await;
+                return Err(format!("Signal subscription failed: {}", e));
+            }
+        };
+
+        info!("Portal hotkey manager started, listening for {}", trigger);
+
+        loop {
+            tokio::select! {
+                activated = activated_stream.next() => {
+                    let Some(activated) = activated else {
+                        warn!("Portal hotkey event loop terminated");
+                        break;
+                    };
+                    if let Some(event) = event_for(activated.shortcut_id()) {
+                        info!("Portal hotkey triggered: {}", activated.shortcut_id());
+                        if let Err(e) = self.tx.send(event) {
+                            error!("Failed to send hotkey event: {}", e);
+                            break;
+                        }
+                    }
+                }
+                Some(changed) = changed_stream.next() => {
+                    if let Some(shortcut) = find_shortcut(changed.shortcuts()) {
+                        info!("Portal shortcut changed in system settings: {}", shortcut.trigger_description());
+                        self.publish(shortcut.trigger_description());
+                    }
+                }
+                Some(command) = self.commands.recv() => match command {
+                    PortalCommand::Rebind(reply) => {
+                        let result = self.rebind(&shortcuts, &mut session).await;
+                        if let Ok(trigger) = &result {
+                            self.publish(trigger);
+                        }
+                        let _ = reply.send(result);
+                    }
+                },
+                _ = shutdown.wait() => break,
+            }
+        }
+
+        Self::close_session(session).await;
+        Ok(())
+    }
+
+    /// Skrót przywrócony z poprzedniej sesji albo powiązany od nowa
+    async fn start(&self, shortcuts: &GlobalShortcuts<'_>, session: &Session<'_, GlobalShortcuts<'_>>) -> Result<String, String> {
+        let saved = PortalBinding::load(&self.binding_path).filter(|b| b.can_restore(&self.preferred_trigger, &self.style_triggers));
+        let restored = match saved {
+            Some(_) => self.restore(shortcuts, session).await,
+            None => None,
+        };
+        match restored {
+            Some(trigger) => {
+                info!("Portal shortcut restored without approval: {}", trigger);
+                Ok(trigger)
+            }
+            None => self.bind(shortcuts, session).await,
+        }
+    }
+
+    async fn close_session(session: Session<'_, GlobalShortcuts<'_>>) {
+        if let Err(e) = session.close().await {
+            warn!("Failed to close shortcuts session: {}", e);
+        } else {
+            info!("Portal shortcuts session closed");
+        }
+    }
+
+    async fn create_session<'a>(shortcuts: &GlobalShortcuts<'a>) -> Result<Session<'a, GlobalShortcuts<'a>>, String> {
+        shortcuts.create_session().await.map_err(|e| {
             error!("Failed to create shortcuts session: {}", e);
             format!("Session creation failed: {}", e)
-        })?;
+        })
+    }
 
-        let shortcut = NewShortcut::new("capture-text", "Przechwytywanie tekstu")
-            .preferred_trigger("CTRL+SHIFT+C");
+    /// Skrót, który portal już zna z poprzednich uruchomień
+    async fn restore(&self, shortcuts: &GlobalShortcuts<'_>, session: &Session<'_, GlobalShortcuts<'_>>) -> Option<String> {
+        let response = match shortcuts.list_shortcuts(session).await.and_then(|request| request.response()) {
+            Ok(response) => response,
+            Err(e) => {
+                warn!("Failed to list portal shortcuts: {}", e);
+                return None;
+            }
+        };
+        find_shortcut(response.shortcuts())
+            .map(|shortcut| shortcut.trigger_description().to_string())
+            .filter(|trigger| !trigger.is_empty())
+    }
+
+    /// Wiąże skrót główny i skróty stylów; portal może pokazać okno zgody i wyboru kombinacji
+    async fn bind(&self, shortcuts: &GlobalShortcuts<'_>, session: &Session<'_, GlobalShortcuts<'_>>) -> Result<String, String> {
+        let mut requested = vec![NewShortcut::new(SHORTCUT_ID, "Przechwytywanie tekstu")
+            .preferred_trigger(self.preferred_trigger.as_str())];
+        for (id, trigger) in &self.style_triggers {
+            let name = match event_for(id) {
+                Some(PortalHotkeyEvent::Style(style)) => style.display_name_pl(),
+                _ => continue,
+            };
+            requested.push(NewShortcut::new(id.as_str(), format!("Styl: {}", name)).preferred_trigger(trigger.as_str()));
+        }
 
         let request = shortcuts
-            .bind_shortcuts(&session, &[shortcut], &WindowIdentifier::default())
+            .bind_shortcuts(session, &requested, &WindowIdentifier::default())
             .await
             .map_err(|e| {
                 error!("Failed to bind shortcuts: {}", e);
@@ -45,36 +332,54 @@ impl PortalHotkeyManager {
             format!("Binding rejected: {}", e)
         })?;
 
-        if response.shortcuts().is_empty() {
-            warn!("No shortcuts were bound - user may need to configure in system settings");
-        } else {
-            for shortcut in response.shortcuts() {
-                info!(
-                    "Shortcut bound: {}",
-                    shortcut.id(),
-                );
+        match find_shortcut(response.shortcuts()) {
+            Some(shortcut) => {
+                info!("Shortcut bound: {} ({})", shortcut.id(), shortcut.trigger_description());
+                Ok(shortcut.trigger_description().to_string())
+            }
+            None => {
+                warn!("No shortcuts were bound - user may need to configure in system settings");
+                Ok(String::new())
             }
         }
+    }
 
-        let mut activated_stream = shortcuts.receive_activated().await.map_err(|e| {
-            error!("Failed to receive activated signal: {}", e);
-            format!("Signal subscription failed: {}", e)
-        })?;
-
-        info!("Portal hotkey manager started, listening for Ctrl+Shift+C");
-
-        while let Some(activated) = activated_stream.next().await {
-            if activated.shortcut_id() == "capture-text" {
-                info!("Portal hotkey triggered: capture-text");
-                if let Err(e) = self.tx.send(PortalHotkeyEvent::Triggered) {
-                    error!("Failed to send hotkey event: {}", e);
-                    break;
+    /// Wiąże skrót w nowej sesji; stara zostaje, dopóki nowa się nie powiedzie
+    async fn rebind<'a>(
+        &self,
+        shortcuts: &GlobalShortcuts<'a>,
+        session: &mut Session<'a, GlobalShortcuts<'a>>,
+    ) -> Result<String, String> {
+        let new_session = Self::create_session(shortcuts).await?;
+        match self.bind(shortcuts, &new_session).await {
+            Ok(trigger) => {
+                let old = std::mem::replace(session, new_session);
+                if let Err(e) = old.close().await {
+                    warn!("Failed to close previous shortcuts session: {}", e);
                 }
+                Ok(trigger)
+            }
+            Err(e) => {
+                let _ = new_session.close().await;
+                Err(e)
             }
         }
+    }
 
-        warn!("Portal hotkey event loop terminated");
-        Ok(())
+    /// Przekazuje opis skrótu do UI i zapamiętuje powiązanie na następne uruchomienie
+    fn publish(&self, trigger: &str) {
+        self.trigger.send_replace(trigger.to_string());
+        if trigger.is_empty() {
+            return;
+        }
+        let binding = PortalBinding {
+            preferred_trigger: self.preferred_trigger.clone(),
+            trigger_description: trigger.to_string(),
+            style_triggers: self.style_triggers.clone(),
+        };
+        if let Err(e) = binding.save(&self.binding_path) {
+            warn!("Failed to save portal shortcut binding: {}", e);
+        }
     }
 }
 
@@ -88,6 +393,8 @@ pub fn is_wayland() -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::GlobalHotkey;
+    use tempfile::TempDir;
 
     #[test]
     fn test_portal_hotkey_event_derives() {
@@ -104,4 +411,50 @@ mod tests {
         let result = is_wayland();
         assert!(result == true || result == false);
     }
+
+    #[test]
+    fn test_portal_trigger() {
+        assert_eq!(portal_trigger("Ctrl+Shift+C"), "CTRL+SHIFT+c");
+        assert_eq!(portal_trigger("Alt+Super+F5"), "ALT+LOGO+F5");
+    }
+
+    #[test]
+    fn test_style_shortcuts() {
+        let mut config = GlobalHotkey::default();
+        config.styles.insert("Ctrl+Shift+P".to_string(), CorrectionStyle::Professional);
+        let bindings = HotkeyBindings::from_config(&config);
+        let triggers = style_triggers(&bindings);
+        assert_eq!(triggers.get("style-professional").map(String::as_str), Some("CTRL+SHIFT+p"));
+
+        assert_eq!(event_for(SHORTCUT_ID), Some(PortalHotkeyEvent::Triggered));
+        assert_eq!(event_for("style-professional"), Some(PortalHotkeyEvent::Style(CorrectionStyle::Professional)));
+        assert_eq!(event_for("style-unknown"), None);
+        assert_eq!(event_for("other"), None);
+    }
+
+    #[test]
+    fn test_binding_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("portal_hotkey.json");
+        assert_eq!(PortalBinding::load(&path), None);
+
+        let binding = PortalBinding {
+            preferred_trigger: "CTRL+SHIFT+c".to_string(),
+            trigger_description: "Ctrl+Shift+C".to_string(),
+            style_triggers: BTreeMap::from([("style-professional".to_string(), "CTRL+SHIFT+p".to_string())]),
+        };
+        binding.save(&path).unwrap();
+        let loaded = PortalBinding::load(&path).unwrap();
+        assert_eq!(loaded, binding);
+        assert!(loaded.can_restore("CTRL+SHIFT+c", &binding.style_triggers));
+        assert!(!loaded.can_restore("CTRL+ALT+c", &binding.style_triggers));
+        assert!(!loaded.can_restore("CTRL+SHIFT+c", &BTreeMap::new()), "Changed style hotkeys need a new approval");
+
+        // Powiązanie zapisane przed skrótami stylów nie ma ich w pliku
+        fs::write(&path, r#"{"PreferredTrigger":"CTRL+SHIFT+c","TriggerDescription":"Ctrl+Shift+C"}"#).unwrap();
+        assert!(PortalBinding::load(&path).unwrap().can_restore("CTRL+SHIFT+c", &BTreeMap::new()));
+
+        fs::write(&path, "nie json").unwrap();
+        assert_eq!(PortalBinding::load(&path), None);
+    }
 }
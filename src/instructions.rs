@@ -0,0 +1,110 @@
+//! Własne polecenia: instrukcja wpisana ręcznie zamiast stylu
+//!
+//! „Inna instrukcja…” w palecie stylów zastępuje polecenie stylu tylko
+//! w bieżącej sesji. Ostatnio użyte polecenia są zapamiętywane w pliku obok
+//! konfiguracji, żeby dało się je szybko wybrać ponownie.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+
+/// Ile ostatnich poleceń jest pamiętanych
+pub const MAX_RECENT: usize = 10;
+
+pub struct RecentInstructions {
+    path: PathBuf,
+    items: Vec<String>,
+}
+
+impl RecentInstructions {
+    /// Wczytuje listę; brak lub uszkodzenie pliku daje pustą listę
+    pub fn load<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let items = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self { path, items }
+    }
+
+    /// Plik poleceń obok `config.toml`
+    pub fn default_path() -> PathBuf {
+        Config::get_config_path().with_file_name("recent_instructions.json")
+    }
+
+    /// Polecenia od najnowszego
+    pub fn items(&self) -> &[String] {
+        &self.items
+    }
+
+    /// Przesuwa polecenie na początek listy i zapisuje plik
+    pub fn remember(&mut self, instruction: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let instruction = instruction.trim();
+        if instruction.is_empty() || self.items.first().map(String::as_str) == Some(instruction) {
+            return Ok(());
+        }
+        self.items.retain(|item| item != instruction);
+        self.items.insert(0, instruction.to_string());
+        self.items.truncate(MAX_RECENT);
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(&self.items)?)?;
+        Ok(())
+    }
+}
+
+/// Polecenie dla modelu z instrukcją użytkownika
+pub fn instruction_prompt(instruction: &str) -> String {
+    format!(
+        "{}\n\nReturn ONLY the resulting text, without any additional headers, separators, or comments.",
+        instruction.trim()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_remember_and_reload() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("recent_instructions.json");
+
+        let mut recent = RecentInstructions::load(&path);
+        assert!(recent.items().is_empty());
+
+        recent.remember("Zamień na listę punktów").unwrap();
+        recent.remember("  Skróć do jednego zdania \n").unwrap();
+        recent.remember("Zamień na listę punktów").unwrap();
+        recent.remember("   ").unwrap();
+
+        let reloaded = RecentInstructions::load(&path);
+        assert_eq!(reloaded.items(), ["Zamień na listę punktów", "Skróć do jednego zdania"]);
+    }
+
+    #[test]
+    fn test_keeps_at_most_max_recent() {
+        let dir = TempDir::new().unwrap();
+        let mut recent = RecentInstructions::load(dir.path().join("recent.json"));
+        for n in 0..MAX_RECENT + 3 {
+            recent.remember(&format!("polecenie {}", n)).unwrap();
+        }
+
+        assert_eq!(recent.items().len(), MAX_RECENT);
+        assert_eq!(recent.items()[0], format!("polecenie {}", MAX_RECENT + 2));
+    }
+
+    #[test]
+    fn test_corrupted_file_gives_empty_list() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("recent_instructions.json");
+        fs::write(&path, "{nie json").unwrap();
+
+        assert!(RecentInstructions::load(&path).items().is_empty());
+        assert!(instruction_prompt(" Skróć ").starts_with("Skróć\n\nReturn ONLY"));
+    }
+}
@@ -0,0 +1,166 @@
+//! Pomiar czasu wywołań w pętli głównej GTK
+//!
+//! Włączany flagą `--jank`. Wywołanie dłuższe niż klatka (16 ms), np.
+//! wstawienie wyniku z podświetleniem zmian albo mapa zgodności, trafia do
+//! logu z nazwą miejsca i długością tekstu. Przy zamykaniu log dostaje
+//! podsumowanie: ile razy każde miejsce przekroczyło budżet i najdłuższy czas.
+
+use once_cell::sync::Lazy;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Czas jednej klatki przy 60 Hz
+pub const FRAME_BUDGET: Duration = Duration::from_millis(16);
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+static REPORT: Lazy<Mutex<JankReport>> = Lazy::new(|| Mutex::new(JankReport::default()));
+
+/// Włącza pomiar (flaga `--jank`)
+pub fn enable() {
+    ENABLED.store(true, Ordering::SeqCst);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::SeqCst)
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JankStats {
+    pub calls: u64,
+    pub over_budget: u64,
+    pub worst: Duration,
+}
+
+/// Statystyki mierzonych miejsc w kolejności pierwszego pomiaru
+#[derive(Debug, Default)]
+pub struct JankReport {
+    entries: Vec<(&'static str, JankStats)>,
+}
+
+impl JankReport {
+    /// Zapisuje pomiar; zwraca `true`, gdy przekroczył budżet klatki
+    pub fn record(&mut self, name: &'static str, elapsed: Duration) -> bool {
+        let index = match self.entries.iter().position(|(n, _)| *n == name) {
+            Some(index) => index,
+            None => {
+                self.entries.push((name, JankStats::default()));
+                self.entries.len() - 1
+            }
+        };
+        let stats = &mut self.entries[index].1;
+        let over = elapsed > FRAME_BUDGET;
+        stats.calls += 1;
+        stats.over_budget += u64::from(over);
+        stats.worst = stats.worst.max(elapsed);
+        over
+    }
+
+    pub fn stats(&self, name: &str) -> Option<JankStats> {
+        self.entries.iter().find(|(n, _)| *n == name).map(|(_, s)| *s)
+    }
+
+    /// Miejsca, które przekroczyły budżet, od najczęściej przekraczającego
+    pub fn summary(&self) -> Option<String> {
+        let mut over: Vec<&(&str, JankStats)> = self.entries.iter().filter(|(_, s)| s.over_budget > 0).collect();
+        if over.is_empty() {
+            return None;
+        }
+        over.sort_by(|a, b| b.1.over_budget.cmp(&a.1.over_budget).then(b.1.worst.cmp(&a.1.worst)));
+        let lines: Vec<String> = over
+            .iter()
+            .map(|(name, s)| {
+                format!(
+                    "{}: {}/{} over budget, worst {:.1} ms",
+                    name,
+                    s.over_budget,
+                    s.calls,
+                    s.worst.as_secs_f64() * 1000.0
+                )
+            })
+            .collect();
+        Some(lines.join("; "))
+    }
+}
+
+/// Mierzy czas do końca zasięgu; bez `--jank` nic nie robi
+pub struct FrameTimer {
+    name: &'static str,
+    chars: usize,
+    started: Option<Instant>,
+}
+
+/// Początek pomiaru miejsca `name` dla tekstu o `chars` znakach
+pub fn measure(name: &'static str, chars: usize) -> FrameTimer {
+    FrameTimer {
+        name,
+        chars,
+        started: is_enabled().then(Instant::now),
+    }
+}
+
+impl Drop for FrameTimer {
+    fn drop(&mut self) {
+        let Some(started) = self.started else {
+            return;
+        };
+        let elapsed = started.elapsed();
+        if REPORT.lock().unwrap().record(self.name, elapsed) {
+            warn!(
+                "Main loop callback {} took {:.1} ms for {} characters (frame budget {} ms)",
+                self.name,
+                elapsed.as_secs_f64() * 1000.0,
+                self.chars,
+                FRAME_BUDGET.as_millis()
+            );
+        }
+    }
+}
+
+/// Podsumowanie do logu przy zamykaniu
+pub fn log_summary() {
+    if !is_enabled() {
+        return;
+    }
+    match REPORT.lock().unwrap().summary() {
+        Some(summary) => info!("Jank report: {}", summary),
+        None => info!("Jank report: no callback exceeded the frame budget"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let mut report = JankReport::default();
+        assert!(!report.record("diff", Duration::from_millis(3)));
+        assert!(report.record("diff", Duration::from_millis(40)));
+        assert!(report.record("heatmap", Duration::from_millis(20)));
+        assert!(report.record("heatmap", Duration::from_millis(17)));
+        assert!(!report.record("stream", FRAME_BUDGET));
+
+        assert_eq!(
+            report.stats("diff"),
+            Some(JankStats {
+                calls: 2,
+                over_budget: 1,
+                worst: Duration::from_millis(40)
+            })
+        );
+        assert_eq!(
+            report.summary().as_deref(),
+            Some("heatmap: 2/2 over budget, worst 20.0 ms; diff: 1/2 over budget, worst 40.0 ms")
+        );
+        assert_eq!(JankReport::default().summary(), None);
+    }
+
+    #[test]
+    fn test_timer_is_noop_when_disabled() {
+        let timer = measure("test", 10);
+        assert!(timer.started.is_none());
+    }
+}
@@ -0,0 +1,99 @@
+//! Rozpoznawanie języka tekstu wejściowego
+//!
+//! Prosta heurystyka bez zależności: liczy częste słowa funkcyjne i litery
+//! charakterystyczne dla danego języka. Wystarcza do wyboru system promptu,
+//! nie jest ogólnym detektorem języka.
+
+/// Obsługiwane języki (kody ISO 639-1)
+pub const LANGUAGES: &[&str] = &["pl", "en", "de", "fr", "es"];
+
+/// Najmniejsza liczba trafień, poniżej której wynik jest uznawany za niepewny
+const MIN_SCORE: usize = 2;
+
+fn stopwords(language: &str) -> &'static [&'static str] {
+    match language {
+        "pl" => &[
+            "i", "w", "z", "na", "nie", "się", "to", "jest", "że", "do", "o", "jak", "ale", "po",
+            "co", "tak", "za", "od", "czy", "oraz", "jako", "dla", "już", "tylko",
+        ],
+        "en" => &[
+            "the", "and", "of", "to", "is", "in", "that", "it", "for", "with", "as", "was", "on",
+            "are", "be", "this", "have", "not", "you", "we", "will", "can", "at", "by",
+        ],
+        "de" => &[
+            "der", "die", "das", "und", "ist", "nicht", "ich", "sie", "es", "mit", "den", "ein",
+            "eine", "zu", "von", "auf", "für", "dem", "wir", "auch", "sich", "wird", "sind", "bitte",
+        ],
+        "fr" => &[
+            "le", "la", "les", "et", "est", "des", "une", "un", "du", "que", "pour", "dans", "pas",
+            "en", "nous", "vous", "sur", "avec", "ce", "qui", "sont", "au", "mais", "merci",
+        ],
+        "es" => &[
+            "el", "la", "los", "las", "y", "es", "que", "de", "en", "un", "una", "por", "con",
+            "para", "del", "se", "no", "su", "al", "lo", "como", "pero", "está", "gracias",
+        ],
+        _ => &[],
+    }
+}
+
+fn special_letters(language: &str) -> &'static [char] {
+    match language {
+        "pl" => &['ą', 'ę', 'ś', 'ć', 'ż', 'ź', 'ł', 'ń'],
+        "de" => &['ä', 'ö', 'ü', 'ß'],
+        "fr" => &['è', 'ê', 'à', 'ç', 'ù', 'œ'],
+        "es" => &['ñ', '¿', '¡', 'á', 'í'],
+        _ => &[],
+    }
+}
+
+/// Kod języka tekstu albo `None`, gdy tekst jest za krótki lub niejednoznaczny
+pub fn detect(text: &str) -> Option<&'static str> {
+    let lower = text.to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    let mut scores: Vec<(&'static str, usize)> = LANGUAGES
+        .iter()
+        .map(|&language| {
+            let stop = stopwords(language);
+            let letters = special_letters(language);
+            let word_hits = words.iter().filter(|w| stop.contains(w)).count();
+            let letter_hits = lower.chars().filter(|c| letters.contains(c)).count();
+            (language, word_hits + letter_hits)
+        })
+        .collect();
+    scores.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+
+    match scores.as_slice() {
+        [(language, best), (_, second), ..] if *best >= MIN_SCORE && best > second => Some(language),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_languages() {
+        assert_eq!(detect("To jest tekst, który trzeba poprawić i wysłać do klienta."), Some("pl"));
+        assert_eq!(detect("This is the text that we need to send to the client."), Some("en"));
+        assert_eq!(detect("Das ist der Text, den wir an den Kunden schicken müssen."), Some("de"));
+        assert_eq!(detect("Voici le texte que nous devons envoyer pour le client."), Some("fr"));
+        assert_eq!(detect("Este es el texto que tenemos que enviar al cliente."), Some("es"));
+    }
+
+    #[test]
+    fn test_detect_polish_without_stopwords() {
+        assert_eq!(detect("Zażółć gęślą jaźń"), Some("pl"));
+    }
+
+    #[test]
+    fn test_detect_uncertain() {
+        assert_eq!(detect(""), None);
+        assert_eq!(detect("OK"), None);
+        assert_eq!(detect("12345 ???"), None);
+    }
+}
@@ -0,0 +1,157 @@
+//! Ograniczenia długości wyniku
+//!
+//! Przy tekstach o sztywnym limicie (tweety, streszczenia, napisy w interfejsie)
+//! model dostaje polecenie zachowania długości, a wynik jest potem sprawdzany:
+//! odchylenie liczby słów ponad tolerancję trafia jako ostrzeżenie pod panel.
+//! Przy limicie znaków (SMS, meta description) za długi wynik jest raz
+//! odsyłany do modelu z prośbą o skrócenie.
+
+use crate::config::LengthSettings;
+
+/// Dopuszczalna zmiana liczby słów względem oryginału
+pub const TOLERANCE: f64 = 0.10;
+
+pub fn word_count(text: &str) -> usize {
+    text.split_whitespace().count()
+}
+
+/// Polecenie dla modelu z docelową liczbą słów
+pub fn preserve_instruction(original: &str) -> String {
+    let words = word_count(original);
+    format!(
+        "Keep the length of the text within ±{:.0}% of the original ({} words, so between {} and {} words).",
+        TOLERANCE * 100.0,
+        words,
+        (words as f64 * (1.0 - TOLERANCE)).floor() as usize,
+        (words as f64 * (1.0 + TOLERANCE)).ceil() as usize,
+    )
+}
+
+/// Polecenie dla modelu z limitem znaków
+pub fn limit_instruction(max_chars: u32) -> String {
+    format!(
+        "The result must fit in {} characters, including spaces. Shorten it if needed, keeping the meaning.",
+        max_chars
+    )
+}
+
+/// Polecenie stylu uzupełnione o ograniczenia długości z ustawień
+pub fn instruction(base: &str, settings: &LengthSettings, original: &str) -> String {
+    let mut instruction = base.to_string();
+    if settings.preserve && word_count(original) > 0 {
+        instruction.push('\n');
+        instruction.push_str(&preserve_instruction(original));
+    }
+    if settings.max_chars > 0 {
+        instruction.push('\n');
+        instruction.push_str(&limit_instruction(settings.max_chars));
+    }
+    instruction
+}
+
+/// Liczba znaków wyniku, jeśli przekracza limit (0 = bez limitu)
+pub fn exceeds_limit(text: &str, max_chars: u32) -> Option<usize> {
+    let chars = text.trim().chars().count();
+    (max_chars > 0 && chars > max_chars as usize).then_some(chars)
+}
+
+/// Prośba o skrócenie za długiej odpowiedzi
+pub fn shorten_message(max_chars: u32, actual: usize) -> String {
+    format!(
+        "Your version has {} characters, but it must fit in {} characters, including spaces. \
+         Shorten it, keeping the meaning. Return ONLY the shortened text, without any comments.",
+        actual, max_chars
+    )
+}
+
+/// Ostrzeżenie, gdy wynik nadal nie mieści się w limicie znaków
+pub fn check_limit(text: &str, max_chars: u32) -> Option<String> {
+    exceeds_limit(text, max_chars).map(|chars| format!("⚠️ Wynik ma {} znaków, limit to {}", chars, max_chars))
+}
+
+/// Etykieta przycisku limitu znaków
+pub fn limit_label(max_chars: u32) -> String {
+    if max_chars == 0 {
+        "📏 Limit znaków".to_string()
+    } else {
+        format!("📏 ≤ {} znaków", max_chars)
+    }
+}
+
+/// Względna zmiana liczby słów; `None` dla pustego oryginału
+pub fn deviation(original: &str, result: &str) -> Option<f64> {
+    let before = word_count(original);
+    if before == 0 {
+        return None;
+    }
+    Some((word_count(result) as f64 - before as f64) / before as f64)
+}
+
+/// Ostrzeżenie, gdy wynik odbiega od oryginału bardziej niż o `TOLERANCE`
+pub fn check_preserved(original: &str, result: &str) -> Option<String> {
+    let deviation = deviation(original, result)?;
+    // Drobny zapas na błąd zaokrąglenia przy krótkich tekstach
+    if deviation.abs() <= TOLERANCE + 1e-9 {
+        return None;
+    }
+    Some(format!(
+        "⚠️ Długość zmieniła się o {:+.0}% ({} → {} słów)",
+        deviation * 100.0,
+        word_count(original),
+        word_count(result)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preserve_instruction_bounds() {
+        let text = "jeden dwa trzy cztery pięć sześć siedem osiem dziewięć dziesięć";
+        let instruction = preserve_instruction(text);
+        assert!(instruction.contains("±10%"));
+        assert!(instruction.contains("(10 words, so between 9 and 11 words)"));
+    }
+
+    #[test]
+    fn test_instruction_only_when_enabled() {
+        let mut settings = LengthSettings::default();
+        assert_eq!(instruction("Correct", &settings, "ala ma kota"), "Correct");
+
+        settings.preserve = true;
+        assert!(instruction("Correct", &settings, "ala ma kota").starts_with("Correct\nKeep the length"));
+        assert_eq!(instruction("Correct", &settings, "   "), "Correct");
+
+        settings.max_chars = 160;
+        let both = instruction("Correct", &settings, "ala ma kota");
+        assert!(both.contains("Keep the length"));
+        assert!(both.ends_with(&limit_instruction(160)));
+    }
+
+    #[test]
+    fn test_character_limit() {
+        assert_eq!(exceeds_limit("Zażółć gęślą", 12), None);
+        assert_eq!(exceeds_limit(" Zażółć gęślą jaźń \n", 12), Some(17));
+        assert_eq!(exceeds_limit("dowolnie długi tekst", 0), None);
+
+        assert!(shorten_message(12, 17).starts_with("Your version has 17 characters, but it must fit in 12"));
+        assert_eq!(check_limit("Zażółć gęślą jaźń", 12).unwrap(), "⚠️ Wynik ma 17 znaków, limit to 12");
+        assert!(check_limit("krótko", 12).is_none());
+
+        assert_eq!(limit_label(0), "📏 Limit znaków");
+        assert_eq!(limit_label(160), "📏 ≤ 160 znaków");
+    }
+
+    #[test]
+    fn test_check_preserved() {
+        let original = "a b c d e f g h i j";
+        assert!(check_preserved(original, "a b c d e f g h i j k").is_none());
+        assert!(check_preserved(original, "a b c d e f g h i").is_none());
+
+        let warning = check_preserved(original, "a b c d e f g h i j k l m").unwrap();
+        assert_eq!(warning, "⚠️ Długość zmieniła się o +30% (10 → 13 słów)");
+        assert!(check_preserved(original, "a b c").unwrap().contains("-70%"));
+        assert!(check_preserved("", "cokolwiek").is_none());
+    }
+}
@@ -6,9 +6,54 @@ pub mod platform;
 pub mod hotkey;
 pub mod hotkey_portal;
 pub mod clipboard;
+pub mod paste_guard;
 pub mod diff;
 pub mod diff_gtk;
 pub mod prompts;
+pub mod history;
+pub mod history_cipher;
+pub mod digest;
+pub mod session;
+pub mod controller;
+pub mod instructions;
+pub mod conversation;
+pub mod edits;
+pub mod pipeline;
+pub mod scheduler;
+pub mod structured;
+pub mod styleguide;
+pub mod protect;
+pub mod normalize;
+pub mod rewrap;
+pub mod subject;
+pub mod merge;
+pub mod agreement;
+pub mod align;
+pub mod review;
+pub mod doctor;
+pub mod cli;
+pub mod power;
+pub mod shutdown;
+pub mod breaker;
+pub mod metrics;
+pub mod jank;
+pub mod usage;
+pub mod audit;
+pub mod window_rules;
+pub mod app_styles;
+pub mod activity;
+pub mod chooser;
+pub mod snippets;
+pub mod sound;
+pub mod readability;
+pub mod facts;
+pub mod gestures;
+pub mod tutorial;
+pub mod length;
+pub mod matrix;
+pub mod tokens;
+pub mod language;
+pub mod shortcuts;
 pub mod app;
 pub mod tray;
 
@@ -6,6 +6,10 @@ use once_cell::sync::Lazy;
 use tracing_subscriber::{self, EnvFilter};
 
 use poprawiacz_tekstu_rs::app::MainWindow;
+use poprawiacz_tekstu_rs::config::Config;
+use poprawiacz_tekstu_rs::prompts::CorrectionStyle;
+use poprawiacz_tekstu_rs::shutdown::{JOIN_TIMEOUT, SHUTDOWN};
+use poprawiacz_tekstu_rs::{api, cli, doctor, jank};
 use poprawiacz_tekstu_rs::TOKIO_RUNTIME;
 
 const APP_ID: &str = "io.github.jarx88.poprawiacz-tekstu-rs";
@@ -18,23 +22,67 @@ fn main() -> glib::ExitCode {
         )
         .init();
 
+    if std::env::args().any(|a| a == "--demo") {
+        api::demo::enable();
+        tracing::info!("Demo mode: using the offline demo provider instead of real APIs");
+    }
+    if std::env::args().any(|a| a == "--jank") {
+        jank::enable();
+        tracing::info!("Logging main loop callbacks longer than {} ms", jank::FRAME_BUDGET.as_millis());
+    }
+
+    if std::env::args().any(|a| a == "--doctor") {
+        return run_doctor();
+    }
+    if std::env::args().any(|a| a == "--audit-csv") {
+        return run_audit_csv();
+    }
+
     Lazy::force(&TOKIO_RUNTIME);
 
+    if std::env::args().any(|a| a == "--pick") {
+        return run_pick();
+    }
+    if std::env::args().any(|a| a == "--json") {
+        return run_json();
+    }
+    if std::env::args().any(|a| a == "--unpick") {
+        return run_unpick();
+    }
+
+    let cli_requested = std::env::args().any(|a| a == "--cli");
+    if cli_requested || !display_available() {
+        if !cli_requested {
+            eprintln!("{}", cli::NO_DISPLAY_MESSAGE);
+        }
+        return run_cli();
+    }
+
     let app = adw::Application::builder()
         .application_id(APP_ID)
         .flags(gio::ApplicationFlags::HANDLES_COMMAND_LINE)
         .build();
 
+    // Uruchomienie ręczne zawsze pokazuje pełne okno, także po skrócie w trybie kompaktowym
     app.connect_activate(|app| {
         if let Some(window) = app.active_window() {
-            window.set_visible(true);
-            window.present();
+            if window.activate_action("win.show-full", None).is_err() {
+                window.set_visible(true);
+                window.present();
+            }
         }
     });
 
     app.connect_startup(|app| {
         let window = MainWindow::new(app);
         window.present();
+        #[cfg(unix)]
+        handle_termination_signals(app);
+    });
+
+    app.connect_shutdown(|_| {
+        SHUTDOWN.run(JOIN_TIMEOUT);
+        jank::log_summary();
     });
 
     app.connect_command_line(|app, cmd| {
@@ -44,7 +92,17 @@ fn main() -> glib::ExitCode {
             .map(|s| s.to_string_lossy().to_string())
             .collect();
 
-        if args.contains(&"--paste".to_string()) || args.contains(&"-p".to_string()) {
+        if let Some(text) = cli::arg_value(&args, "--text") {
+            let style = match cli::style_arg(&args) {
+                Ok(style) => style,
+                Err(e) => {
+                    cmd.printerr_literal(&format!("{}\n", e));
+                    return 1;
+                }
+            };
+            app.activate();
+            process_text_in_window(app, &text, style);
+        } else if args.contains(&"--paste".to_string()) || args.contains(&"-p".to_string()) {
             if let Some(window) = app.active_window() {
                 window.set_visible(true);
                 window.present();
@@ -69,6 +127,140 @@ fn main() -> glib::ExitCode {
     app.run()
 }
 
+/// SIGTERM/SIGINT chowają okno i zamykają aplikację przez koordynator zamykania
+/// (przerwanie zapytań, wyrejestrowanie skrótu) zamiast ubijać ją w trakcie zapisu
+#[cfg(unix)]
+fn handle_termination_signals(app: &adw::Application) {
+    const SIGINT: i32 = 2;
+    const SIGTERM: i32 = 15;
+
+    for (signum, name) in [(SIGINT, "SIGINT"), (SIGTERM, "SIGTERM")] {
+        let app_weak = app.downgrade();
+        glib::unix_signal_add_local(signum, move || {
+            tracing::info!("{} received, shutting down", name);
+            if let Some(app) = app_weak.upgrade() {
+                for window in app.windows() {
+                    window.set_visible(false);
+                }
+                app.quit();
+            }
+            glib::ControlFlow::Continue
+        });
+    }
+}
+
+/// Wysyła tekst z `--text` do przetworzenia w oknie (także już działającej instancji)
+fn process_text_in_window(app: &adw::Application, text: &str, style: Option<CorrectionStyle>) {
+    let Some(window) = app.active_window() else {
+        return;
+    };
+    let style = style.map(|s| s.as_str()).unwrap_or_default();
+    if let Err(e) = window.activate_action("win.process-text", Some(&(text, style).to_variant())) {
+        tracing::error!("Cannot start processing text from the command line: {}", e);
+    }
+}
+
+fn run_doctor() -> glib::ExitCode {
+    let results = doctor::run_checks();
+    print!("{}", doctor::format_table(&results));
+
+    if doctor::has_failures(&results) {
+        glib::ExitCode::FAILURE
+    } else {
+        glib::ExitCode::SUCCESS
+    }
+}
+
+fn run_audit_csv() -> glib::ExitCode {
+    match cli::run_audit_csv() {
+        Ok(()) => glib::ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            glib::ExitCode::FAILURE
+        }
+    }
+}
+
+/// Sprawdza, czy GTK może otworzyć ekran, zanim powstanie aplikacja
+fn display_available() -> bool {
+    if cfg!(target_os = "linux")
+        && !cli::display_env_present(
+            std::env::var("DISPLAY").ok().as_deref(),
+            std::env::var("WAYLAND_DISPLAY").ok().as_deref(),
+        )
+    {
+        return false;
+    }
+    gtk4::init().is_ok()
+}
+
+/// Konfiguracja trybów tekstowych; `--style` zastępuje `DefaultStyle`
+fn load_cli_config() -> Result<Config, String> {
+    let mut config = Config::load_or_default(Config::get_config_path());
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(style) = cli::style_arg(&args)? {
+        config.settings.default_style = style;
+    }
+    Ok(config)
+}
+
+/// Tekst z `--text` albo ze standardowego wejścia
+fn cli_input() -> Result<String, String> {
+    cli::input(&std::env::args().collect::<Vec<_>>())
+}
+
+fn run_cli() -> glib::ExitCode {
+    let result = load_cli_config().and_then(|config| cli_input().and_then(|text| cli::run(&config, &text)));
+    match result {
+        Ok(true) => glib::ExitCode::SUCCESS,
+        Ok(false) => glib::ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("{}", e);
+            eprintln!("Diagnostyka: poprawiacz-tekstu-rs --doctor");
+            glib::ExitCode::FAILURE
+        }
+    }
+}
+
+fn run_pick() -> glib::ExitCode {
+    match load_cli_config().and_then(|config| cli_input().and_then(|text| cli::run_pick(&config, &text))) {
+        Ok(true) => glib::ExitCode::SUCCESS,
+        Ok(false) => glib::ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("{}", e);
+            glib::ExitCode::FAILURE
+        }
+    }
+}
+
+fn run_json() -> glib::ExitCode {
+    match load_cli_config().and_then(|config| cli_input().and_then(|text| cli::run_json(&config, &text))) {
+        Ok(true) => glib::ExitCode::SUCCESS,
+        Ok(false) => glib::ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("{}", e);
+            glib::ExitCode::FAILURE
+        }
+    }
+}
+
+/// Zamienia linię wybraną w rofi/wofi z powrotem na tekst do wpisania
+fn run_unpick() -> glib::ExitCode {
+    let line = match cli::read_input() {
+        Ok(line) => line,
+        Err(e) => {
+            eprintln!("{}", e);
+            return glib::ExitCode::FAILURE;
+        }
+    };
+    let Some(line) = line.lines().next().filter(|l| !l.trim().is_empty()) else {
+        return glib::ExitCode::FAILURE;
+    };
+
+    print!("{}", cli::parse_pick_line(line));
+    glib::ExitCode::SUCCESS
+}
+
 fn find_paste_button(widget: &glib::Object) -> Option<gtk4::Button> {
     if let Some(btn) = widget.downcast_ref::<gtk4::Button>() {
         if let Some(label) = btn.label() {
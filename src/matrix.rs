@@ -0,0 +1,226 @@
+//! Macierz porównania dostawców do eksportu jako HTML
+//!
+//! Dla każdego dostawcy bieżącej sesji: czas odpowiedzi, odsetek zmienionych
+//! słów, czytelność (FOG-PL) i szacowany koszt. Tokeny liczy lokalny tokenizer
+//! (dla części dostawców w przybliżeniu), więc koszt służy do porównań, nie jest rachunkiem.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use similar::TextDiff;
+
+use crate::config::ModelPrice;
+use crate::readability;
+
+/// Wynik jednego dostawcy w sesji
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatrixRow {
+    pub provider: String,
+    pub model: String,
+    /// Model z parametrami, którymi powstał wynik (pusty, gdy nieznane)
+    pub parameters: String,
+    pub latency: Option<Duration>,
+    /// Tokeny promptu i odpowiedzi (do kosztu)
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// `None`, gdy dostawca zwrócił błąd albo został pominięty
+    pub result: Option<String>,
+}
+
+/// Wiersz z policzonymi miarami
+struct Measured<'a> {
+    row: &'a MatrixRow,
+    diff_pct: Option<f64>,
+    fog_pl: Option<f64>,
+    cost_usd: Option<f64>,
+}
+
+/// Odsetek słów oryginału zmienionych w wyniku (0–100)
+pub fn diff_percent(original: &str, corrected: &str) -> f64 {
+    let ratio = TextDiff::from_words(original, corrected).ratio() as f64;
+    ((1.0 - ratio) * 100.0).clamp(0.0, 100.0)
+}
+
+/// Szacowany koszt wywołania w USD
+pub fn cost_usd(price: &ModelPrice, input_tokens: u64, output_tokens: u64) -> f64 {
+    (input_tokens as f64 * price.input + output_tokens as f64 * price.output) / 1_000_000.0
+}
+
+fn measure<'a>(
+    original: &str,
+    row: &'a MatrixRow,
+    pricing: &BTreeMap<String, ModelPrice>,
+) -> Measured<'a> {
+    let Some(result) = row.result.as_deref() else {
+        return Measured {
+            row,
+            diff_pct: None,
+            fog_pl: None,
+            cost_usd: None,
+        };
+    };
+    Measured {
+        row,
+        diff_pct: Some(diff_percent(original, result)),
+        fog_pl: readability::analyze(result).map(|r| r.fog_pl),
+        cost_usd: pricing
+            .get(&row.model)
+            .map(|price| cost_usd(price, row.input_tokens, row.output_tokens)),
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Najmniejsza wartość kolumny; to ona jest wyróżniana (krócej, mniej zmian, prościej, taniej)
+fn best(values: impl Iterator<Item = Option<f64>>) -> Option<f64> {
+    values.flatten().min_by(|a, b| a.total_cmp(b))
+}
+
+fn cell(value: Option<f64>, best: Option<f64>, format: impl Fn(f64) -> String) -> String {
+    match value {
+        Some(v) if Some(v) == best => format!("<td class=\"best\">{}</td>", format(v)),
+        Some(v) => format!("<td>{}</td>", format(v)),
+        None => "<td class=\"missing\">—</td>".to_string(),
+    }
+}
+
+/// Dokument HTML z macierzą porównania
+pub fn render_html(original: &str, rows: &[MatrixRow], pricing: &BTreeMap<String, ModelPrice>) -> String {
+    let measured: Vec<Measured> = rows.iter().map(|row| measure(original, row, pricing)).collect();
+
+    let latencies = |m: &Measured| m.row.result.as_ref().and(m.row.latency).map(|l| l.as_secs_f64());
+    let best_latency = best(measured.iter().map(latencies));
+    let best_diff = best(measured.iter().map(|m| m.diff_pct));
+    let best_fog = best(measured.iter().map(|m| m.fog_pl));
+    let best_cost = best(measured.iter().map(|m| m.cost_usd));
+
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html lang=\"pl\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Porównanie dostawców</title>\n<style>\n\
+         body { font-family: sans-serif; margin: 2em; }\n\
+         table { border-collapse: collapse; }\n\
+         th, td { border: 1px solid #ccc; padding: 6px 12px; text-align: right; }\n\
+         th:first-child, td:first-child { text-align: left; }\n\
+         td.best { background: #d4f4dd; font-weight: bold; }\n\
+         td.missing { color: #999; }\n\
+         pre { white-space: pre-wrap; background: #f6f6f6; padding: 8px; }\n\
+         </style>\n</head>\n<body>\n<h1>Porównanie dostawców</h1>\n",
+    );
+
+    html.push_str(
+        "<table>\n<tr><th>Dostawca</th><th>Model</th><th>Czas</th><th>Zmiany</th>\
+         <th>FOG-PL</th><th>Koszt (USD)</th></tr>\n",
+    );
+    for m in &measured {
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td>{}{}{}{}</tr>\n",
+            escape_html(&m.row.provider),
+            escape_html(&m.row.model),
+            cell(latencies(m), best_latency, |v| format!("{:.1} s", v)),
+            cell(m.diff_pct, best_diff, |v| format!("{:.1}%", v)),
+            cell(m.fog_pl, best_fog, |v| format!("{:.1}", v)),
+            cell(m.cost_usd, best_cost, |v| format!("{:.5}", v)),
+        ));
+    }
+    html.push_str("</table>\n");
+    html.push_str("<p>Koszt szacowany z liczby tokenów policzonej lokalnie; wyróżniono najniższe wartości.</p>\n");
+
+    html.push_str(&format!("<h2>Oryginał</h2>\n<pre>{}</pre>\n", escape_html(original)));
+    for m in &measured {
+        let body = match &m.row.result {
+            Some(text) => escape_html(text),
+            None => "(brak wyniku)".to_string(),
+        };
+        html.push_str(&format!("<h2>{}</h2>\n", escape_html(&m.row.provider)));
+        if !m.row.parameters.is_empty() {
+            html.push_str(&format!("<p><small>{}</small></p>\n", escape_html(&m.row.parameters)));
+        }
+        html.push_str(&format!("<pre>{}</pre>\n", body));
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(provider: &str, model: &str, secs: f64, result: Option<&str>) -> MatrixRow {
+        MatrixRow {
+            provider: provider.to_string(),
+            model: model.to_string(),
+            parameters: format!("{} · temp. 0.7", model),
+            latency: Some(Duration::from_secs_f64(secs)),
+            input_tokens: 25,
+            output_tokens: 5,
+            result: result.map(str::to_string),
+        }
+    }
+
+    fn pricing() -> BTreeMap<String, ModelPrice> {
+        BTreeMap::from([(
+            "cheap".to_string(),
+            ModelPrice {
+                input: 1.0,
+                output: 2.0,
+            },
+        )])
+    }
+
+    #[test]
+    fn test_cost() {
+        let price = ModelPrice {
+            input: 1.0,
+            output: 2.0,
+        };
+        assert!((cost_usd(&price, 1_000_000, 500_000) - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_diff_percent() {
+        assert_eq!(diff_percent("Ala ma kota.", "Ala ma kota."), 0.0);
+        let changed = diff_percent("Ala ma kota.", "Ala ma psa.");
+        assert!(changed > 0.0 && changed < 100.0);
+        assert_eq!(diff_percent("", "Nowy tekst"), 100.0);
+    }
+
+    #[test]
+    fn test_render_html_marks_best_and_missing() {
+        let rows = vec![
+            row("OpenAI", "cheap", 1.0, Some("Ala ma kota.")),
+            row("Gemini", "unknown", 3.0, Some("Ala ma bardzo dużego kota.")),
+            row("DeepSeek", "cheap", 0.5, None),
+        ];
+        let html = render_html("Ala ma kota", &rows, &pricing());
+
+        assert!(html.contains("<td class=\"best\">1.0 s</td>"));
+        // Błąd nie wygrywa czasem odpowiedzi
+        assert!(!html.contains("0.5 s"));
+        assert!(html.contains("<tr><td>Gemini</td><td>unknown</td><td>3.0 s</td>"));
+        assert!(html.contains("(brak wyniku)"));
+        assert!(html.contains("<h2>Gemini</h2>\n<p><small>unknown · temp. 0.7</small></p>"));
+        assert_eq!(html.matches("<td class=\"missing\">").count(), 5);
+    }
+
+    #[test]
+    fn test_render_html_escapes_text() {
+        let rows = vec![row("OpenAI", "cheap", 1.0, Some("<b>a & b</b>"))];
+        let html = render_html("x < y", &rows, &pricing());
+        assert!(html.contains("&lt;b&gt;a &amp; b&lt;/b&gt;"));
+        assert!(html.contains("<pre>x &lt; y</pre>"));
+    }
+}
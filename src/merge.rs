@@ -0,0 +1,234 @@
+//! Składanie wyniku z fragmentów różnych paneli
+//!
+//! Wyniki dzielone są na akapity (albo zdania, gdy tekst ma jeden akapit)
+//! i ustawiane wierszami według pozycji. Użytkownik zaznacza fragmenty,
+//! a złożony tekst zachowuje kolejność wierszy.
+
+use std::collections::BTreeSet;
+
+/// Jednostka podziału wyników
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Granularity {
+    Paragraph,
+    Sentence,
+}
+
+impl Granularity {
+    /// Akapity, chyba że żaden wynik nie ma więcej niż jednego
+    pub fn detect(texts: &[&str]) -> Self {
+        if texts.iter().any(|t| split_paragraphs(t).len() > 1) {
+            Self::Paragraph
+        } else {
+            Self::Sentence
+        }
+    }
+
+    pub fn split(&self, text: &str) -> Vec<String> {
+        match self {
+            Self::Paragraph => split_paragraphs(text),
+            Self::Sentence => split_sentences(text),
+        }
+    }
+
+    fn separator(&self) -> &'static str {
+        match self {
+            Self::Paragraph => "\n\n",
+            Self::Sentence => " ",
+        }
+    }
+}
+
+/// Dzieli tekst na akapity rozdzielone pustą linią
+pub fn split_paragraphs(text: &str) -> Vec<String> {
+    let mut paragraphs = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                paragraphs.push(current.join("\n"));
+                current.clear();
+            }
+        } else {
+            current.push(line.trim_end());
+        }
+    }
+    if !current.is_empty() {
+        paragraphs.push(current.join("\n"));
+    }
+
+    paragraphs
+}
+
+/// Dzieli tekst na zdania po `.`, `!`, `?` i `…` zakończonych białym znakiem
+pub fn split_sentences(text: &str) -> Vec<String> {
+    sentence_spans(text)
+        .into_iter()
+        .map(|(start, end)| text[start..end].to_string())
+        .collect()
+}
+
+/// Zakresy bajtów zdań jak w `split_sentences`, bez otaczających białych znaków
+pub fn sentence_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    let mut push = |from: usize, to: usize| {
+        let slice = &text[from..to];
+        let trimmed = slice.trim_start();
+        let begin = from + slice.len() - trimmed.len();
+        let trimmed = trimmed.trim_end();
+        if !trimmed.is_empty() {
+            spans.push((begin, begin + trimmed.len()));
+        }
+    };
+
+    while let Some((_, c)) = chars.next() {
+        if !matches!(c, '.' | '!' | '?' | '…') {
+            continue;
+        }
+        if let Some(&(next_idx, next)) = chars.peek() {
+            if next.is_whitespace() {
+                push(start, next_idx);
+                start = next_idx;
+            }
+        }
+    }
+    push(start, text.len());
+
+    spans
+}
+
+/// Fragmenty wyników ułożone w wiersze i bieżące zaznaczenie
+#[derive(Debug, Clone)]
+pub struct MergePlan {
+    granularity: Granularity,
+    /// `segments[źródło][wiersz]`
+    segments: Vec<Vec<String>>,
+    selected: BTreeSet<(usize, usize)>,
+}
+
+impl MergePlan {
+    /// Źródła bez wyniku należy przekazać jako pusty tekst
+    pub fn new(sources: &[&str]) -> Self {
+        let granularity = Granularity::detect(sources);
+        Self::with_granularity(sources, granularity)
+    }
+
+    pub fn with_granularity(sources: &[&str], granularity: Granularity) -> Self {
+        Self {
+            granularity,
+            segments: sources.iter().map(|s| granularity.split(s)).collect(),
+            selected: BTreeSet::new(),
+        }
+    }
+
+    pub fn granularity(&self) -> Granularity {
+        self.granularity
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.segments.iter().map(Vec::len).max().unwrap_or(0)
+    }
+
+    pub fn segment(&self, source: usize, row: usize) -> Option<&str> {
+        self.segments.get(source)?.get(row).map(String::as_str)
+    }
+
+    pub fn set_selected(&mut self, source: usize, row: usize, selected: bool) {
+        if self.segment(source, row).is_none() {
+            return;
+        }
+        if selected {
+            self.selected.insert((row, source));
+        } else {
+            self.selected.remove(&(row, source));
+        }
+    }
+
+    pub fn is_selected(&self, source: usize, row: usize) -> bool {
+        self.selected.contains(&(row, source))
+    }
+
+    /// Zaznacza w każdym wierszu fragment danego źródła (punkt wyjścia do poprawek)
+    pub fn select_source(&mut self, source: usize) {
+        self.selected.clear();
+        for row in 0..self.row_count() {
+            self.set_selected(source, row, true);
+        }
+    }
+
+    /// Złożony tekst: wiersze po kolei, w wierszu źródła według numeru
+    pub fn compose(&self) -> String {
+        self.selected
+            .iter()
+            .filter_map(|&(row, source)| self.segment(source, row))
+            .collect::<Vec<_>>()
+            .join(self.granularity.separator())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_paragraphs() {
+        let text = "Pierwszy akapit.\nDruga linia.\n\n\n  \nDrugi akapit.\n";
+        assert_eq!(
+            split_paragraphs(text),
+            vec!["Pierwszy akapit.\nDruga linia.", "Drugi akapit."]
+        );
+    }
+
+    #[test]
+    fn test_split_sentences() {
+        assert_eq!(
+            split_sentences("Ala ma kota. Kot ma Alę! Czy to prawda? Tak…"),
+            vec!["Ala ma kota.", "Kot ma Alę!", "Czy to prawda?", "Tak…"]
+        );
+        assert_eq!(split_sentences("Wersja 1.5 działa"), vec!["Wersja 1.5 działa"]);
+    }
+
+    #[test]
+    fn test_granularity_detection() {
+        assert_eq!(Granularity::detect(&["a\n\nb", "c"]), Granularity::Paragraph);
+        assert_eq!(Granularity::detect(&["Jedno. Dwa.", ""]), Granularity::Sentence);
+    }
+
+    #[test]
+    fn test_compose_keeps_row_order() {
+        let mut plan = MergePlan::new(&["A1\n\nA2\n\nA3", "B1\n\nB2\n\nB3"]);
+        assert_eq!(plan.row_count(), 3);
+
+        plan.set_selected(1, 2, true);
+        plan.set_selected(0, 0, true);
+        plan.set_selected(1, 1, true);
+
+        assert_eq!(plan.compose(), "A1\n\nB2\n\nB3");
+    }
+
+    #[test]
+    fn test_select_source_and_override() {
+        let mut plan = MergePlan::new(&["Raz. Dwa.", "Jeden. Dwa!", ""]);
+        assert_eq!(plan.granularity(), Granularity::Sentence);
+
+        plan.select_source(0);
+        plan.set_selected(0, 0, false);
+        plan.set_selected(1, 0, true);
+
+        assert_eq!(plan.compose(), "Jeden. Dwa.");
+        assert!(plan.is_selected(1, 0));
+        assert!(!plan.is_selected(0, 0));
+    }
+
+    #[test]
+    fn test_missing_segments_are_ignored() {
+        let mut plan = MergePlan::new(&["A1\n\nA2", ""]);
+        plan.set_selected(1, 0, true);
+        plan.set_selected(5, 0, true);
+
+        assert_eq!(plan.row_count(), 2);
+        assert_eq!(plan.compose(), "");
+    }
+}
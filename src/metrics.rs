@@ -0,0 +1,260 @@
+//! Lokalne metryki w formacie Prometheusa
+//!
+//! Nic nie jest nigdzie wysyłane: włączony w konfiguracji serwer nasłuchuje
+//! wyłącznie na 127.0.0.1 i odpowiada na `GET /metrics`.
+
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tracing::{error, info};
+
+/// Granice kubełków histogramu czasu odpowiedzi (sekundy)
+pub const LATENCY_BUCKETS: [f64; 8] = [0.5, 1.0, 2.0, 5.0, 10.0, 20.0, 30.0, 60.0];
+
+#[derive(Debug, Clone, Default)]
+struct ProviderMetrics {
+    successes: u64,
+    errors: u64,
+    skipped: u64,
+    /// Liczniki kumulatywne dla `LATENCY_BUCKETS`
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    latency_sum: f64,
+    latency_count: u64,
+}
+
+#[derive(Debug, Default)]
+struct Registry {
+    sessions: u64,
+    providers: Vec<(String, ProviderMetrics)>,
+}
+
+impl Registry {
+    fn provider(&mut self, name: &str) -> &mut ProviderMetrics {
+        let index = match self.providers.iter().position(|(n, _)| n == name) {
+            Some(index) => index,
+            None => {
+                self.providers.push((name.to_string(), ProviderMetrics::default()));
+                self.providers.len() - 1
+            }
+        };
+        &mut self.providers[index].1
+    }
+}
+
+/// Współdzielony rejestr metryk
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    registry: Arc<Mutex<Registry>>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_session(&self) {
+        self.registry.lock().unwrap().sessions += 1;
+    }
+
+    /// Wynik zapytania do dostawcy wraz z czasem odpowiedzi
+    pub fn record_result(&self, provider: &str, ok: bool, latency: Duration) {
+        let mut registry = self.registry.lock().unwrap();
+        let metrics = registry.provider(provider);
+        if ok {
+            metrics.successes += 1;
+        } else {
+            metrics.errors += 1;
+        }
+
+        let secs = latency.as_secs_f64();
+        for (bucket, bound) in metrics.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if secs <= bound {
+                *bucket += 1;
+            }
+        }
+        metrics.latency_sum += secs;
+        metrics.latency_count += 1;
+    }
+
+    /// Dostawca pominięty (np. otwarty wyłącznik)
+    pub fn record_skipped(&self, provider: &str) {
+        self.registry.lock().unwrap().provider(provider).skipped += 1;
+    }
+
+    /// Tekst w formacie ekspozycji Prometheusa
+    pub fn render(&self) -> String {
+        let registry = self.registry.lock().unwrap();
+        let mut out = String::new();
+
+        out.push_str("# HELP poprawiacz_sessions_total Correction sessions started.\n");
+        out.push_str("# TYPE poprawiacz_sessions_total counter\n");
+        let _ = writeln!(out, "poprawiacz_sessions_total {}", registry.sessions);
+
+        out.push_str("# HELP poprawiacz_requests_total Provider requests by outcome.\n");
+        out.push_str("# TYPE poprawiacz_requests_total counter\n");
+        for (name, m) in &registry.providers {
+            for (status, value) in [("ok", m.successes), ("error", m.errors), ("skipped", m.skipped)] {
+                let _ = writeln!(
+                    out,
+                    "poprawiacz_requests_total{{provider=\"{}\",status=\"{}\"}} {}",
+                    name, status, value
+                );
+            }
+        }
+
+        out.push_str("# HELP poprawiacz_request_duration_seconds Provider response time.\n");
+        out.push_str("# TYPE poprawiacz_request_duration_seconds histogram\n");
+        for (name, m) in &registry.providers {
+            for (bound, count) in LATENCY_BUCKETS.iter().zip(m.buckets) {
+                let _ = writeln!(
+                    out,
+                    "poprawiacz_request_duration_seconds_bucket{{provider=\"{}\",le=\"{}\"}} {}",
+                    name, bound, count
+                );
+            }
+            let _ = writeln!(
+                out,
+                "poprawiacz_request_duration_seconds_bucket{{provider=\"{}\",le=\"+Inf\"}} {}",
+                name, m.latency_count
+            );
+            let _ = writeln!(
+                out,
+                "poprawiacz_request_duration_seconds_sum{{provider=\"{}\"}} {}",
+                name, m.latency_sum
+            );
+            let _ = writeln!(
+                out,
+                "poprawiacz_request_duration_seconds_count{{provider=\"{}\"}} {}",
+                name, m.latency_count
+            );
+        }
+
+        out
+    }
+}
+
+/// Ścieżka z pierwszej linii żądania HTTP, np. "GET /metrics HTTP/1.1"
+pub fn request_path(request: &str) -> Option<&str> {
+    let mut parts = request.lines().next()?.split_whitespace();
+    match (parts.next(), parts.next()) {
+        (Some("GET"), Some(path)) => Some(path),
+        _ => None,
+    }
+}
+
+fn http_response(status: &str, content_type: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )
+}
+
+/// Nasłuchuje na 127.0.0.1:`port` i serwuje metryki do zakończenia procesu
+pub async fn serve(metrics: Metrics, port: u16) -> std::io::Result<()> {
+    let listener = TcpListener::bind(("127.0.0.1", port)).await?;
+    info!("Metrics endpoint at http://{}/metrics", listener.local_addr()?);
+    serve_listener(metrics, listener).await
+}
+
+/// Przerwa po nieudanym `accept`; chwilowe błędy (np. EMFILE, ECONNABORTED) nie kończą serwera
+const ACCEPT_RETRY_DELAY: std::time::Duration = std::time::Duration::from_millis(100);
+
+async fn serve_listener(metrics: Metrics, listener: TcpListener) -> std::io::Result<()> {
+    loop {
+        let mut stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                error!("Metrics accept failed: {}", e);
+                // Bez przerwy brak deskryptorów zamieniłby pętlę w aktywne czekanie
+                tokio::time::sleep(ACCEPT_RETRY_DELAY).await;
+                continue;
+            }
+        };
+        let metrics = metrics.clone();
+
+        tokio::spawn(async move {
+            let mut buf = [0u8; 1024];
+            let n = match stream.read(&mut buf).await {
+                Ok(n) => n,
+                Err(e) => {
+                    error!("Metrics request read failed: {}", e);
+                    return;
+                }
+            };
+
+            let request = String::from_utf8_lossy(&buf[..n]);
+            let response = match request_path(&request) {
+                Some("/metrics") => http_response(
+                    "200 OK",
+                    "text/plain; version=0.0.4; charset=utf-8",
+                    &metrics.render(),
+                ),
+                _ => http_response("404 Not Found", "text/plain", "Not Found\n"),
+            };
+            let _ = stream.write_all(response.as_bytes()).await;
+            let _ = stream.shutdown().await;
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpStream;
+
+    #[test]
+    fn test_render_counts_and_histogram() {
+        let metrics = Metrics::new();
+        metrics.record_session();
+        metrics.record_result("OpenAI", true, Duration::from_millis(800));
+        metrics.record_result("OpenAI", false, Duration::from_secs(15));
+        metrics.record_skipped("Gemini");
+
+        let text = metrics.render();
+        assert!(text.contains("poprawiacz_sessions_total 1\n"));
+        assert!(text.contains("poprawiacz_requests_total{provider=\"OpenAI\",status=\"ok\"} 1\n"));
+        assert!(text.contains("poprawiacz_requests_total{provider=\"OpenAI\",status=\"error\"} 1\n"));
+        assert!(text.contains("poprawiacz_requests_total{provider=\"Gemini\",status=\"skipped\"} 1\n"));
+        assert!(text.contains("poprawiacz_request_duration_seconds_bucket{provider=\"OpenAI\",le=\"0.5\"} 0\n"));
+        assert!(text.contains("poprawiacz_request_duration_seconds_bucket{provider=\"OpenAI\",le=\"1\"} 1\n"));
+        assert!(text.contains("poprawiacz_request_duration_seconds_bucket{provider=\"OpenAI\",le=\"20\"} 2\n"));
+        assert!(text.contains("poprawiacz_request_duration_seconds_bucket{provider=\"OpenAI\",le=\"+Inf\"} 2\n"));
+        assert!(text.contains("poprawiacz_request_duration_seconds_count{provider=\"OpenAI\"} 2\n"));
+    }
+
+    #[test]
+    fn test_request_path() {
+        assert_eq!(request_path("GET /metrics HTTP/1.1\r\nHost: x\r\n\r\n"), Some("/metrics"));
+        assert_eq!(request_path("POST /metrics HTTP/1.1\r\n"), None);
+        assert_eq!(request_path(""), None);
+    }
+
+    #[tokio::test]
+    async fn test_serves_metrics_over_http() {
+        let metrics = Metrics::new();
+        metrics.record_session();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve_listener(metrics, listener));
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(b"GET /metrics HTTP/1.1\r\n\r\n").await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("poprawiacz_sessions_total 1"));
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(b"GET / HTTP/1.1\r\n\r\n").await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 404"));
+    }
+}
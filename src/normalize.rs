@@ -0,0 +1,167 @@
+//! Porządkowanie tekstu przed wysłaniem
+//!
+//! Każdy krok włącza się osobno w sekcji `[normalize]` konfiguracji:
+//! usuwanie znaków cytowania z maili (`>` na początku wiersza), sklejanie
+//! ręcznie łamanych wierszy w akapity oraz usuwanie parametrów śledzących
+//! z adresów URL. Kroki są idempotentne - ponowne przejście nic nie zmienia.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::config::Normalize;
+
+static URL: Lazy<Regex> = Lazy::new(|| Regex::new(r#"https?://[^\s<>"]+"#).expect("valid regex"));
+
+/// Parametry zapytania dodawane przez newslettery, reklamy i serwisy społecznościowe
+const TRACKING_PARAMS: &[&str] = &[
+    "fbclid", "gclid", "dclid", "gbraid", "wbraid", "msclkid", "yclid", "igshid", "mc_cid", "mc_eid", "_hsenc",
+    "_hsmi", "mkt_tok",
+];
+
+/// Stosuje włączone kroki w stałej kolejności
+pub fn apply(settings: &Normalize, text: &str) -> String {
+    let mut text = text.to_string();
+    if settings.strip_quotes {
+        text = strip_quote_marks(&text);
+    }
+    if settings.unwrap_lines {
+        text = unwrap_lines(&text);
+    }
+    if settings.strip_tracking {
+        text = strip_tracking(&text);
+    }
+    text
+}
+
+/// Usuwa znaki cytowania (także zagnieżdżone, np. "> > ") z początku wierszy
+pub fn strip_quote_marks(text: &str) -> String {
+    text.split('\n')
+        .map(|line| {
+            let rest = line.trim_start_matches(['>', ' ', '\t']);
+            if rest.len() < line.len() && line.trim_start().starts_with('>') {
+                rest
+            } else {
+                line
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Wiersz, który zaczyna nowy element i nie jest doklejany do poprzedniego
+pub fn starts_block(line: &str) -> bool {
+    let line = line.trim_start();
+    let numbered = line
+        .split_once(". ")
+        .is_some_and(|(n, _)| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+    numbered || ["- ", "* ", "• ", "#", ">", "|"].iter().any(|p| line.starts_with(p))
+}
+
+/// Skleja wiersze akapitu w jeden; puste wiersze, listy i nagłówki zostają
+pub fn unwrap_lines(text: &str) -> String {
+    let mut out: Vec<String> = Vec::new();
+    let mut joinable = false;
+    for line in text.split('\n') {
+        let trimmed = line.trim_end();
+        if trimmed.is_empty() {
+            out.push(String::new());
+            joinable = false;
+        } else if joinable && !starts_block(trimmed) {
+            let last = out.last_mut().expect("joinable after a line");
+            last.push(' ');
+            last.push_str(trimmed.trim_start());
+        } else {
+            out.push(trimmed.to_string());
+            joinable = !trimmed.trim_start().starts_with('#');
+        }
+    }
+    out.join("\n")
+}
+
+fn is_tracking(param: &str) -> bool {
+    let key = param.split('=').next().unwrap_or_default().to_ascii_lowercase();
+    key.starts_with("utm_") || TRACKING_PARAMS.contains(&key.as_str())
+}
+
+fn clean_url(url: &str) -> String {
+    let (rest, fragment) = match url.split_once('#') {
+        Some((rest, fragment)) => (rest, Some(fragment)),
+        None => (url, None),
+    };
+    let Some((base, query)) = rest.split_once('?') else {
+        return url.to_string();
+    };
+
+    let kept: Vec<&str> = query.split('&').filter(|p| !p.is_empty() && !is_tracking(p)).collect();
+    let mut cleaned = base.to_string();
+    if !kept.is_empty() {
+        cleaned.push('?');
+        cleaned.push_str(&kept.join("&"));
+    }
+    if let Some(fragment) = fragment {
+        cleaned.push('#');
+        cleaned.push_str(fragment);
+    }
+    cleaned
+}
+
+/// Usuwa z adresów URL parametry śledzące (utm_*, fbclid, gclid…)
+pub fn strip_tracking(text: &str) -> String {
+    URL.replace_all(text, |caps: &regex::Captures| clean_url(&caps[0])).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EMAIL: &str = "> Dzień dobry,\n> czy spotkanie\n> jest aktualne?\n>> Tak, w piątek.\n\n\
+                         Szczegóły: https://example.com/a?id=7&utm_source=news&utm_medium=mail#top\n\
+                         - punkt pierwszy\n- punkt drugi";
+
+    #[test]
+    fn test_strip_quote_marks() {
+        assert_eq!(strip_quote_marks("> a\n> > b\n>>c\nd > e"), "a\nb\nc\nd > e");
+    }
+
+    #[test]
+    fn test_unwrap_lines() {
+        assert_eq!(
+            unwrap_lines("Pierwszy wiersz\nciąg dalszy.\n\n# Nagłówek\n- a\n  b\n- c\n1. d"),
+            "Pierwszy wiersz ciąg dalszy.\n\n# Nagłówek\n- a b\n- c\n1. d"
+        );
+    }
+
+    #[test]
+    fn test_strip_tracking() {
+        assert_eq!(
+            strip_tracking("Zobacz https://example.com/x?utm_source=a&fbclid=b oraz https://ex.pl/?q=1&gclid=2#s."),
+            "Zobacz https://example.com/x oraz https://ex.pl/?q=1#s."
+        );
+        assert_eq!(strip_tracking("https://example.com/?q=utm_source"), "https://example.com/?q=utm_source");
+    }
+
+    #[test]
+    fn test_apply_is_idempotent() {
+        for bits in 0..8u8 {
+            let settings = Normalize {
+                strip_quotes: bits & 1 != 0,
+                unwrap_lines: bits & 2 != 0,
+                strip_tracking: bits & 4 != 0,
+            };
+            let once = apply(&settings, EMAIL);
+            assert_eq!(apply(&settings, &once), once, "settings {:?}", settings);
+        }
+
+        let all = Normalize {
+            strip_quotes: true,
+            unwrap_lines: true,
+            strip_tracking: true,
+        };
+        assert_eq!(
+            apply(&all, EMAIL),
+            "Dzień dobry, czy spotkanie jest aktualne? Tak, w piątek.\n\n\
+             Szczegóły: https://example.com/a?id=7#top\n- punkt pierwszy\n- punkt drugi"
+        );
+        assert_eq!(apply(&Normalize::default(), EMAIL), EMAIL);
+    }
+}
@@ -0,0 +1,107 @@
+//! Sprawdzanie okna przed symulowanym Ctrl+V
+//!
+//! Wynik nie jest wklejany do okna, którego klasa albo tytuł zawiera fragment
+//! z `[paste_guard]` (menedżery haseł, okna uwierzytelniania, terminal z
+//! pytaniem sudo). Zostaje wtedy tylko w schowku. Gdy okna z fokusem nie da
+//! się odczytać, wynik też zostaje tylko w schowku, chyba że
+//! `AllowUnknownWindow` na to pozwala.
+
+use crate::config::PasteGuard;
+use crate::platform::SourceApp;
+
+/// Wynik sprawdzenia okna z fokusem
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict<'a> {
+    Paste,
+    /// Okno pasuje do fragmentu z listy
+    Blocked(&'a str),
+    /// Nie udało się odczytać okna z fokusem
+    UnknownWindow,
+}
+
+/// Czy wkleić do okna `focused` (`None`, gdy nie udało się go odczytać)
+pub fn check<'a>(settings: &'a PasteGuard, focused: Option<&SourceApp>) -> Verdict<'a> {
+    if !settings.enabled {
+        return Verdict::Paste;
+    }
+    match focused {
+        Some(window) => blocked_by(settings, window).map_or(Verdict::Paste, Verdict::Blocked),
+        None if settings.allow_unknown_window => Verdict::Paste,
+        None => Verdict::UnknownWindow,
+    }
+}
+
+/// Fragment listy, przez który wklejenie do `window` jest zablokowane
+pub fn blocked_by<'a>(settings: &'a PasteGuard, window: &SourceApp) -> Option<&'a str> {
+    if !settings.enabled {
+        return None;
+    }
+    let matches = |patterns: &'a [String], value: &str| {
+        let value = value.to_lowercase();
+        patterns
+            .iter()
+            .map(|p| p.trim())
+            .find(|p| !p.is_empty() && value.contains(&p.to_lowercase()))
+    };
+    matches(&settings.blocked_classes, &window.class).or_else(|| matches(&settings.blocked_titles, &window.title))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(class: &str, title: &str) -> SourceApp {
+        SourceApp {
+            class: class.to_string(),
+            title: title.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_blocked_by_default_lists() {
+        let guard = PasteGuard::default();
+        assert_eq!(blocked_by(&guard, &window("org.keepassxc.KeePassXC", "Baza.kdbx")), Some("keepass"));
+        assert_eq!(blocked_by(&guard, &window("kitty", "[sudo] Hasło użytkownika jan:")), Some("sudo"));
+        assert_eq!(blocked_by(&guard, &window("Gnome-terminal", "Enter passphrase for key")), Some("passphrase"));
+        assert_eq!(blocked_by(&guard, &window("thunderbird", "Odebrane - Poczta")), None);
+        assert_eq!(blocked_by(&guard, &window("", "")), None);
+    }
+
+    #[test]
+    fn test_blocked_by_respects_settings() {
+        let guard = PasteGuard {
+            enabled: true,
+            blocked_classes: vec![" ".to_string(), "Alacritty".to_string()],
+            blocked_titles: Vec::new(),
+            allow_unknown_window: false,
+        };
+        assert_eq!(blocked_by(&guard, &window("alacritty", "vim notatki.txt")), Some("Alacritty"));
+        assert_eq!(blocked_by(&guard, &window("kitty", "sudo apt upgrade")), None);
+
+        let disabled = PasteGuard {
+            enabled: false,
+            ..PasteGuard::default()
+        };
+        assert_eq!(blocked_by(&disabled, &window("pinentry-gtk", "")), None);
+    }
+
+    #[test]
+    fn test_check_unknown_window() {
+        let guard = PasteGuard::default();
+        assert_eq!(check(&guard, None), Verdict::UnknownWindow);
+        assert_eq!(check(&guard, Some(&window("thunderbird", "Poczta"))), Verdict::Paste);
+        assert_eq!(check(&guard, Some(&window("pinentry-gtk", ""))), Verdict::Blocked("pinentry"));
+
+        let allow = PasteGuard {
+            allow_unknown_window: true,
+            ..PasteGuard::default()
+        };
+        assert_eq!(check(&allow, None), Verdict::Paste);
+
+        let disabled = PasteGuard {
+            enabled: false,
+            ..PasteGuard::default()
+        };
+        assert_eq!(check(&disabled, None), Verdict::Paste);
+    }
+}
@@ -0,0 +1,162 @@
+//! Potoki stylów: kilka stylów po kolei przez jednego dostawcę
+//!
+//! Potok z konfiguracji (np. korekta → profesjonalny ton → tłumaczenie na
+//! angielski) wysyła tekst do wybranego dostawcy w pierwszym stylu, a jego
+//! wynik w kolejnym. Wyniki pośrednie są zachowywane, żeby dało się sprawdzić,
+//! na którym kroku coś poszło nie tak.
+
+use crate::api::{correct_text, Provider};
+use crate::config::{Config, Pipeline};
+use crate::error::ApiError;
+use crate::prompts::{get_instruction_prompt, get_system_prompt, CorrectionStyle, LanguageContext};
+
+/// Wynik jednego kroku potoku
+#[derive(Debug, Clone, PartialEq)]
+pub struct Step {
+    pub style: CorrectionStyle,
+    pub output: String,
+}
+
+/// Dostawca i style potoku; błąd opisuje pierwszy niepoprawny wpis
+pub fn resolve(pipeline: &Pipeline) -> Result<(Provider, Vec<CorrectionStyle>), String> {
+    let provider = Provider::all()
+        .iter()
+        .copied()
+        .find(|p| p.name().eq_ignore_ascii_case(pipeline.provider.trim()))
+        .ok_or_else(|| format!("Potok „{}”: nieznany dostawca „{}”", pipeline.name, pipeline.provider))?;
+
+    let styles = pipeline
+        .styles
+        .iter()
+        .map(|name| {
+            CorrectionStyle::all()
+                .iter()
+                .copied()
+                .find(|s| s.as_str().eq_ignore_ascii_case(name.trim()))
+                .ok_or_else(|| format!("Potok „{}”: nieznany styl „{}”", pipeline.name, name))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if styles.is_empty() {
+        return Err(format!("Potok „{}” nie ma żadnego stylu", pipeline.name));
+    }
+
+    Ok((provider, styles))
+}
+
+fn steps_word(count: usize) -> &'static str {
+    match (count % 10, count % 100) {
+        _ if count == 1 => "krok",
+        (2..=4, n) if !(12..=14).contains(&n) => "kroki",
+        _ => "kroków",
+    }
+}
+
+/// Etykieta potoku w palecie stylów, np. "⛓ Do klienta (OpenAI: 3 kroki)"
+pub fn label(pipeline: &Pipeline) -> String {
+    let count = pipeline.styles.len();
+    format!("⛓ {} ({}: {} {})", pipeline.name, pipeline.provider, count, steps_word(count))
+}
+
+/// Tekst okna z wynikami pośrednimi
+pub fn format_steps(original: &str, steps: &[Step]) -> String {
+    let mut sections = vec![format!("=== Oryginał ===\n{}", original.trim_end())];
+    for (n, step) in steps.iter().enumerate() {
+        sections.push(format!(
+            "=== {}. {} {} ===\n{}",
+            n + 1,
+            step.style.emoji(),
+            step.style.display_name_pl(),
+            step.output.trim_end()
+        ));
+    }
+    sections.join("\n\n")
+}
+
+/// Wykonuje kroki po kolei; `on_step` dostaje numer zaczynanego kroku (od 0).
+/// Błąd zwraca numer kroku, który się nie udał, i wyniki kroków wcześniejszych.
+pub async fn run<F: Fn(usize)>(
+    provider: Provider,
+    config: &Config,
+    text: &str,
+    styles: &[CorrectionStyle],
+    on_step: F,
+) -> Result<Vec<Step>, (usize, ApiError, Vec<Step>)> {
+    let mut steps: Vec<Step> = Vec::with_capacity(styles.len());
+    for (n, &style) in styles.iter().enumerate() {
+        on_step(n);
+        let input = steps.last().map_or(text, |s| s.output.as_str());
+        let system = get_system_prompt(style, &LanguageContext::detect(input, &config.language_prompts));
+        match correct_text(provider, config, input, get_instruction_prompt(style), system).await {
+            Ok(output) => steps.push(Step { style, output }),
+            Err(e) => return Err((n, e, steps)),
+        }
+    }
+    Ok(steps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pipeline(provider: &str, styles: &[&str]) -> Pipeline {
+        Pipeline {
+            name: "Do klienta".to_string(),
+            provider: provider.to_string(),
+            styles: styles.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_resolve() {
+        let (provider, styles) = resolve(&pipeline("openai", &["normal", "Professional", "translate_en"])).unwrap();
+        assert_eq!(provider, Provider::OpenAI);
+        assert_eq!(
+            styles,
+            vec![CorrectionStyle::Normal, CorrectionStyle::Professional, CorrectionStyle::TranslateEn]
+        );
+    }
+
+    #[test]
+    fn test_resolve_errors() {
+        assert!(resolve(&pipeline("Cohere", &["normal"])).unwrap_err().contains("nieznany dostawca"));
+        assert!(resolve(&pipeline("Gemini", &["normal", "poetic"])).unwrap_err().contains("„poetic”"));
+        assert!(resolve(&pipeline("Gemini", &[])).is_err());
+    }
+
+    #[test]
+    fn test_label_and_steps() {
+        assert_eq!(label(&pipeline("OpenAI", &["normal", "summary"])), "⛓ Do klienta (OpenAI: 2 kroki)");
+        assert_eq!(label(&pipeline("OpenAI", &["normal"; 5])), "⛓ Do klienta (OpenAI: 5 kroków)");
+        assert_eq!(label(&pipeline("OpenAI", &["normal"])), "⛓ Do klienta (OpenAI: 1 krok)");
+
+        let steps = [
+            Step {
+                style: CorrectionStyle::Normal,
+                output: "Ala ma kota.".to_string(),
+            },
+            Step {
+                style: CorrectionStyle::TranslateEn,
+                output: "Ala has a cat.\n".to_string(),
+            },
+        ];
+        assert_eq!(
+            format_steps("ala ma kota", &steps),
+            "=== Oryginał ===\nala ma kota\n\n=== 1. ✏️ Standardowa korekta ===\nAla ma kota.\n\n\
+             === 2. 🇺🇸 Przetłumacz na angielski ===\nAla has a cat."
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_stops_at_failed_step() {
+        // Pusty tekst kończy pierwszy krok błędem, zanim cokolwiek trafi do sieci
+        let config = Config::default();
+        let started = std::cell::Cell::new(0);
+        let result = run(Provider::OpenAI, &config, "", &[CorrectionStyle::Normal, CorrectionStyle::Summary], |n| {
+            started.set(n + 1)
+        })
+        .await;
+
+        let (step, _, done) = result.unwrap_err();
+        assert_eq!((step, done.len(), started.get()), (0, 0, 1));
+    }
+}
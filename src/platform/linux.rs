@@ -1,4 +1,4 @@
-use super::KeyboardSimulator;
+use super::{KeyboardSimulator, SourceApp};
 use crate::error::PlatformError;
 use std::process::Command;
 use tracing::{debug, warn};
@@ -55,6 +55,145 @@ impl LinuxKeyboardSimulator {
     }
 }
 
+/// Skąd odczytać okno aktywne w tej sesji
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WindowSource {
+    Hyprland,
+    Sway,
+    /// KDE Plasma na Wayland: kdotool (składnia jak xdotool)
+    Kde,
+    X11,
+    /// Pozostałe kompozytory Wayland (m.in. GNOME) nie udostępniają okna aktywnego
+    Unsupported,
+}
+
+fn window_source(var: impl Fn(&str) -> Option<String>) -> WindowSource {
+    let wayland = var("WAYLAND_DISPLAY").is_some() || var("XDG_SESSION_TYPE").as_deref() == Some("wayland");
+    if var("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
+        WindowSource::Hyprland
+    } else if var("SWAYSOCK").is_some() {
+        WindowSource::Sway
+    } else if !wayland {
+        WindowSource::X11
+    } else if var("XDG_CURRENT_DESKTOP").is_some_and(|desktop| desktop.split(':').any(|d| d.eq_ignore_ascii_case("KDE"))) {
+        WindowSource::Kde
+    } else {
+        WindowSource::Unsupported
+    }
+}
+
+/// Okno aktywne: na Hyprland przez hyprctl, na Sway przez swaymsg, na KDE
+/// Wayland przez kdotool, a na X11 przez xdotool. Na innych kompozytorach
+/// Wayland (np. GNOME) brak, bo xdotool widziałby tam tylko okna XWayland.
+pub fn active_window() -> Option<SourceApp> {
+    let app = match window_source(|name| std::env::var(name).ok()) {
+        WindowSource::Hyprland => command_output("hyprctl", &["activewindow", "-j"]).and_then(|out| parse_hyprctl(&out)),
+        WindowSource::Sway => command_output("swaymsg", &["-t", "get_tree"]).and_then(|out| parse_sway_tree(&out)),
+        WindowSource::Kde => dotool_window("kdotool"),
+        WindowSource::X11 => dotool_window("xdotool"),
+        WindowSource::Unsupported => {
+            debug!("This Wayland compositor doesn't expose the active window");
+            return None;
+        }
+    };
+    if app.is_none() {
+        debug!("Could not read the active window");
+    }
+    app.filter(|a| !a.class.is_empty() || !a.title.is_empty())
+}
+
+/// Klasa i tytuł okna aktywnego z xdotool albo kdotool
+fn dotool_window(program: &str) -> Option<SourceApp> {
+    let class = command_output(program, &["getactivewindow", "getwindowclassname"])?;
+    let title = command_output(program, &["getactivewindow", "getwindowname"]).unwrap_or_default();
+    Some(SourceApp {
+        class: class.trim().to_string(),
+        title: title.trim().to_string(),
+    })
+}
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Wynik `hyprctl activewindow -j`
+fn parse_hyprctl(json: &str) -> Option<SourceApp> {
+    let window: serde_json::Value = serde_json::from_str(json).ok()?;
+    Some(SourceApp {
+        class: window["class"].as_str()?.to_string(),
+        title: window["title"].as_str().unwrap_or_default().to_string(),
+    })
+}
+
+/// Okno z `"focused": true` w drzewie z `swaymsg -t get_tree`
+fn parse_sway_tree(json: &str) -> Option<SourceApp> {
+    fn focused(node: &serde_json::Value) -> Option<&serde_json::Value> {
+        if node["focused"].as_bool() == Some(true) {
+            return Some(node);
+        }
+        ["nodes", "floating_nodes"]
+            .iter()
+            .filter_map(|key| node[*key].as_array())
+            .flatten()
+            .find_map(focused)
+    }
+
+    let tree: serde_json::Value = serde_json::from_str(json).ok()?;
+    let node = focused(&tree)?;
+    // Okna XWayland nie mają app_id, tylko klasę X11
+    let class = node["app_id"]
+        .as_str()
+        .or_else(|| node["window_properties"]["class"].as_str())?;
+    Some(SourceApp {
+        class: class.to_string(),
+        title: node["name"].as_str().unwrap_or_default().to_string(),
+    })
+}
+
+/// Położenie kursora myszy; tylko X11, przez xdotool
+pub fn cursor_position() -> Option<(i32, i32)> {
+    let output = Command::new("xdotool")
+        .args(["getmouselocation", "--shell"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_mouse_location(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parsuje wynik `xdotool getmouselocation --shell` ("X=..", "Y=..")
+fn parse_mouse_location(output: &str) -> Option<(i32, i32)> {
+    let value = |key: &str| {
+        output
+            .lines()
+            .find_map(|line| line.strip_prefix(key)?.strip_prefix('=')?.trim().parse().ok())
+    };
+    Some((value("X")?, value("Y")?))
+}
+
+/// Przesuwa okno o podanym tytule; tylko X11, przez xdotool
+pub fn move_window(title: &str, x: i32, y: i32) -> Result<(), PlatformError> {
+    let pattern = format!("^{}$", regex::escape(title));
+    let output = Command::new("xdotool")
+        .args(["search", "--name", &pattern, "windowmove", &x.to_string(), &y.to_string()])
+        .output()
+        .map_err(|e| PlatformError::ToolNotFound(format!("Failed to execute xdotool: {}", e)))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(PlatformError::CommandFailed(format!(
+            "xdotool windowmove failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        )))
+    }
+}
+
 impl KeyboardSimulator for LinuxKeyboardSimulator {
     fn simulate_copy(&self) -> Result<(), PlatformError> {
         self.execute_xdotool("ctrl+c")
@@ -75,6 +214,50 @@ impl Default for LinuxKeyboardSimulator {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_window_source() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| vars.iter().find(|(k, _)| *k == name).map(|(_, v)| v.to_string())
+        };
+        assert_eq!(window_source(env(&[("DISPLAY", ":0")])), WindowSource::X11);
+        assert_eq!(window_source(env(&[("XDG_SESSION_TYPE", "x11"), ("XDG_CURRENT_DESKTOP", "KDE")])), WindowSource::X11);
+        assert_eq!(window_source(env(&[("WAYLAND_DISPLAY", "wayland-0"), ("SWAYSOCK", "/run/sway")])), WindowSource::Sway);
+        assert_eq!(window_source(env(&[("HYPRLAND_INSTANCE_SIGNATURE", "abc")])), WindowSource::Hyprland);
+        assert_eq!(window_source(env(&[("XDG_SESSION_TYPE", "wayland"), ("XDG_CURRENT_DESKTOP", "KDE")])), WindowSource::Kde);
+        assert_eq!(window_source(env(&[("WAYLAND_DISPLAY", "wayland-0"), ("XDG_CURRENT_DESKTOP", "ubuntu:GNOME")])), WindowSource::Unsupported);
+    }
+
+    #[test]
+    fn test_parse_compositor_windows() {
+        let hypr = r#"{"address":"0x1","class":"thunderbird","title":"Odebrane - Thunderbird"}"#;
+        assert_eq!(
+            parse_hyprctl(hypr),
+            Some(SourceApp { class: "thunderbird".to_string(), title: "Odebrane - Thunderbird".to_string() })
+        );
+        assert_eq!(parse_hyprctl("{}"), None);
+
+        let sway = r#"{"focused":false,"nodes":[{"focused":false,"nodes":[
+            {"focused":false,"app_id":"foot","name":"~","nodes":[]},
+            {"focused":true,"app_id":null,"window_properties":{"class":"Slack"},"name":"Slack | general","nodes":[]}
+        ]}]}"#;
+        assert_eq!(
+            parse_sway_tree(sway),
+            Some(SourceApp { class: "Slack".to_string(), title: "Slack | general".to_string() })
+        );
+        // Fokus na obszarze roboczym, nie na oknie
+        assert_eq!(parse_sway_tree(r#"{"focused":false,"nodes":[{"focused":true,"nodes":[]}]}"#), None);
+    }
+
+    #[test]
+    fn test_parse_mouse_location() {
+        assert_eq!(
+            parse_mouse_location("X=812\nY=430\nSCREEN=0\nWINDOW=62914567\n"),
+            Some((812, 430))
+        );
+        assert_eq!(parse_mouse_location("SCREEN=0\n"), None);
+        assert_eq!(parse_mouse_location("X=abc\nY=1\n"), None);
+    }
+
     #[test]
     fn test_new_simulator() {
         let simulator = LinuxKeyboardSimulator::new();
@@ -3,12 +3,33 @@ use crate::error::PlatformError;
 #[cfg(target_os = "linux")]
 mod linux;
 #[cfg(target_os = "linux")]
-pub use linux::LinuxKeyboardSimulator;
+pub use linux::{active_window, cursor_position, move_window, LinuxKeyboardSimulator};
 
 #[cfg(target_os = "windows")]
 mod windows;
 #[cfg(target_os = "windows")]
-pub use windows::WindowsKeyboardSimulator;
+pub use windows::{active_window, cursor_position, move_window, WindowsKeyboardSimulator};
+
+/// Okno aktywne w chwili naciśnięcia skrótu, czyli źródło tekstu
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SourceApp {
+    /// Klasa okna albo app_id (np. "thunderbird"), klucz reguł okien
+    pub class: String,
+    pub title: String,
+}
+
+impl SourceApp {
+    /// Nazwa do pokazania, np. "Thunderbird"; bez klasy - tytuł okna
+    pub fn display_name(&self) -> String {
+        // app_id bywa w odwrotnej notacji domenowej ("org.gnome.TextEditor")
+        let class = self.class.rsplit('.').next().unwrap_or_default().trim();
+        let mut chars = class.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => self.title.trim().to_string(),
+        }
+    }
+}
 
 pub trait KeyboardSimulator {
     fn simulate_copy(&self) -> Result<(), PlatformError>;
@@ -37,6 +58,14 @@ pub fn simulate_paste() -> Result<(), PlatformError> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_source_app_display_name() {
+        let app = |class: &str, title: &str| SourceApp { class: class.to_string(), title: title.to_string() };
+        assert_eq!(app("thunderbird", "Odebrane").display_name(), "Thunderbird");
+        assert_eq!(app("org.gnome.TextEditor", "notatki.txt").display_name(), "TextEditor");
+        assert_eq!(app("", "Dokument - Edytor").display_name(), "Dokument - Edytor");
+    }
+
     #[test]
     #[cfg(target_os = "linux")]
     fn test_create_linux_simulator() {
@@ -1,4 +1,4 @@
-use super::KeyboardSimulator;
+use super::{KeyboardSimulator, SourceApp};
 use crate::error::PlatformError;
 use tracing::warn;
 
@@ -11,6 +11,22 @@ impl WindowsKeyboardSimulator {
     }
 }
 
+/// Okno aktywne - jeszcze niezaimplementowane na Windows
+pub fn active_window() -> Option<SourceApp> {
+    None
+}
+
+/// Położenie kursora - jeszcze niezaimplementowane na Windows
+pub fn cursor_position() -> Option<(i32, i32)> {
+    None
+}
+
+pub fn move_window(_title: &str, _x: i32, _y: i32) -> Result<(), PlatformError> {
+    Err(PlatformError::NotSupported(
+        "Window positioning not yet implemented on Windows".to_string(),
+    ))
+}
+
 impl KeyboardSimulator for WindowsKeyboardSimulator {
     fn simulate_copy(&self) -> Result<(), PlatformError> {
         Err(PlatformError::NotSupported(
@@ -0,0 +1,60 @@
+//! Tryb oszczędzania energii
+//!
+//! Gdy okno jest schowane i nie trwa żadna sesja, pętle odpytujące skrót
+//! i zasobnik przestają się budzić co kilkadziesiąt milisekund i czekają
+//! wyłącznie na zdarzenia.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Czy odpytywanie należy wstrzymać
+pub fn should_suspend(power_save: bool, window_visible: bool, session_active: bool) -> bool {
+    power_save && !window_visible && !session_active
+}
+
+/// Flaga wstrzymania współdzielona przez UI i wątki odpytujące
+#[derive(Debug, Clone, Default)]
+pub struct PowerState {
+    suspended: Arc<AtomicBool>,
+}
+
+impl PowerState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_suspended(&self) -> bool {
+        self.suspended.load(Ordering::SeqCst)
+    }
+
+    /// Zwraca `true`, jeśli stan się zmienił
+    pub fn set_suspended(&self, suspended: bool) -> bool {
+        self.suspended.swap(suspended, Ordering::SeqCst) != suspended
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_suspend() {
+        assert!(should_suspend(true, false, false));
+        assert!(!should_suspend(false, false, false));
+        assert!(!should_suspend(true, true, false));
+        assert!(!should_suspend(true, false, true));
+    }
+
+    #[test]
+    fn test_power_state_is_shared() {
+        let state = PowerState::new();
+        let clone = state.clone();
+        assert!(!clone.is_suspended());
+
+        assert!(state.set_suspended(true));
+        assert!(clone.is_suspended());
+        assert!(!state.set_suspended(true));
+        assert!(clone.set_suspended(false));
+        assert!(!state.is_suspended());
+    }
+}
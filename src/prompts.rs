@@ -2,15 +2,22 @@
 //!
 //! Port z Python: utils/prompts.py
 //! Obsługuje 7 różnych stylów: normal, professional, translate_en, translate_pl,
-//! change_meaning, summary, prompt
+//! change_meaning, summary, prompt. Własne style użytkownika pochodzą
+//! z `[[custom_styles]]` w konfiguracji.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
 
-/// Style korekty tekstu
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+use crate::config::CustomStyle;
+use crate::language;
+
+/// Style korekty tekstu; w konfiguracji zapisywane jako identyfikator z `as_str`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[serde(try_from = "String", into = "String")]
 pub enum CorrectionStyle {
     /// Standardowa korekta gramatyczna i ortograficzna
+    #[default]
     Normal,
     /// Profesjonalny, formalny ton
     Professional,
@@ -40,6 +47,19 @@ impl CorrectionStyle {
         }
     }
 
+    /// Zwraca identyfikator stylu (odwrotność `from_str`)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::Professional => "professional",
+            Self::TranslateEn => "translate_en",
+            Self::TranslatePl => "translate_pl",
+            Self::ChangeMeaning => "change_meaning",
+            Self::Summary => "summary",
+            Self::Prompt => "prompt",
+        }
+    }
+
     /// Zwraca wszystkie dostępne style
     pub fn all() -> &'static [CorrectionStyle] {
         &[
@@ -78,6 +98,28 @@ impl CorrectionStyle {
             Self::Prompt => "💡",
         }
     }
+
+    /// Styl tłumaczenia dla tekstu: angielski na polski, wszystko inne na angielski
+    pub fn translation_for(text: &str) -> Self {
+        match language::detect(text) {
+            Some("en") => Self::TranslatePl,
+            _ => Self::TranslateEn,
+        }
+    }
+}
+
+impl TryFrom<String> for CorrectionStyle {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        crate::config::parse_choice("style", &value, Self::all(), Self::as_str)
+    }
+}
+
+impl From<CorrectionStyle> for String {
+    fn from(value: CorrectionStyle) -> Self {
+        value.as_str().to_string()
+    }
 }
 
 /// Instrukcje dla różnych stylów korekty
@@ -173,8 +215,46 @@ Tłumaczenie: Usuń przycisk Anuluj
 Add a new feature
 Tłumaczenie: Dodaj nową funkcję"#;
 
-/// Zwraca system prompt dla danego stylu
-pub fn get_system_prompt(style: CorrectionStyle) -> &'static str {
+/// Język tekstu wejściowego i system prompty przypisane językom w konfiguracji
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LanguageContext<'a> {
+    /// Kod wykrytego języka (np. "en")
+    pub language: Option<&'a str>,
+    /// Język → system prompt (`[language_prompts]`)
+    pub prompts: Option<&'a BTreeMap<String, String>>,
+}
+
+impl<'a> LanguageContext<'a> {
+    /// Rozpoznaje język tekstu; przy pustym mapowaniu detekcja jest pomijana
+    pub fn detect(text: &str, prompts: &'a BTreeMap<String, String>) -> Self {
+        if prompts.is_empty() {
+            return Self::default();
+        }
+        Self {
+            language: language::detect(text),
+            prompts: Some(prompts),
+        }
+    }
+
+    fn prompt(&self) -> Option<&'a str> {
+        let prompt = self.prompts?.get(self.language?)?;
+        (!prompt.trim().is_empty()).then_some(prompt.as_str())
+    }
+}
+
+/// Zwraca system prompt dla danego stylu; prompt przypisany językowi wejścia
+/// zastępuje wbudowany, z wyjątkiem tłumaczeń i trybu instrukcji
+pub fn get_system_prompt<'a>(style: CorrectionStyle, language: &LanguageContext<'a>) -> &'a str {
+    let keeps_language = !matches!(
+        style,
+        CorrectionStyle::TranslateEn | CorrectionStyle::TranslatePl | CorrectionStyle::Prompt
+    );
+    if keeps_language {
+        if let Some(prompt) = language.prompt() {
+            return prompt;
+        }
+    }
+
     match style {
         CorrectionStyle::Prompt => PROMPT_SYSTEM_PROMPT,
         CorrectionStyle::Professional => PROFESSIONAL_SYSTEM_PROMPT,
@@ -190,6 +270,64 @@ pub fn get_instruction_prompt(style: CorrectionStyle) -> &'static str {
         .unwrap_or(INSTRUCTIONS.get(&CorrectionStyle::Normal).unwrap())
 }
 
+/// Nazwa stylu sesji z poleceniem wpisanym ręcznie („Inna instrukcja…”)
+pub const INSTRUCTION_STYLE: &str = "custom";
+
+/// Jednorazowy styl z poleceniem wpisanym ręcznie
+pub fn instruction_style(instruction: &str) -> CustomStyle {
+    CustomStyle {
+        name: INSTRUCTION_STYLE.to_string(),
+        instruction: instruction.to_string(),
+        system_prompt: String::new(),
+    }
+}
+
+/// System prompt własnego stylu; bez własnego jak dla standardowej korekty
+pub fn get_custom_system_prompt<'a>(style: &'a CustomStyle, language: &LanguageContext<'a>) -> &'a str {
+    match style.system_prompt.trim() {
+        "" => get_system_prompt(CorrectionStyle::Normal, language),
+        prompt => prompt,
+    }
+}
+
+/// Błędy własnych stylów jako (indeks, opis): brak nazwy lub polecenia,
+/// nazwa wbudowanego stylu i powtórzona nazwa
+pub fn custom_style_errors(styles: &[CustomStyle]) -> Vec<(usize, String)> {
+    let mut errors = Vec::new();
+    for (i, style) in styles.iter().enumerate() {
+        let name = style.name.trim();
+        let reserved = name.eq_ignore_ascii_case(INSTRUCTION_STYLE)
+            || CorrectionStyle::all().iter().any(|s| s.as_str().eq_ignore_ascii_case(name));
+        let error = if name.is_empty() {
+            Some("brak nazwy stylu".to_string())
+        } else if reserved {
+            Some(format!("nazwa „{}” należy do wbudowanego stylu", name))
+        } else if styles[..i].iter().any(|other| other.name.trim().eq_ignore_ascii_case(name)) {
+            Some(format!("powtórzona nazwa „{}”", name))
+        } else if style.instruction.trim().is_empty() {
+            Some(format!("styl „{}” nie ma polecenia", name))
+        } else {
+            None
+        };
+        errors.extend(error.map(|e| (i, e)));
+    }
+    errors
+}
+
+/// Własne style bez błędów, w kolejności z konfiguracji; błędne trafiają do logu
+pub fn usable_custom_styles(styles: &[CustomStyle]) -> Vec<&CustomStyle> {
+    let errors = custom_style_errors(styles);
+    for (_, error) in &errors {
+        tracing::warn!("Custom style skipped: {}", error);
+    }
+    styles
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !errors.iter().any(|(index, _)| index == i))
+        .map(|(_, style)| style)
+        .collect()
+}
+
 /// Buduje pełny prompt do wysłania do API
 pub fn build_full_prompt(style: CorrectionStyle, text: &str) -> String {
     format!("{}\n\n{}", get_instruction_prompt(style), text)
@@ -209,6 +347,26 @@ mod tests {
         assert_eq!(CorrectionStyle::from_str("unknown"), CorrectionStyle::Normal);
     }
 
+    #[test]
+    fn test_correction_style_as_str_roundtrip() {
+        for style in CorrectionStyle::all() {
+            assert_eq!(CorrectionStyle::from_str(style.as_str()), *style);
+        }
+    }
+
+    #[test]
+    fn test_translation_for() {
+        assert_eq!(
+            CorrectionStyle::translation_for("This is the text that we need to send."),
+            CorrectionStyle::TranslatePl
+        );
+        assert_eq!(
+            CorrectionStyle::translation_for("To jest tekst, który trzeba wysłać."),
+            CorrectionStyle::TranslateEn
+        );
+        assert_eq!(CorrectionStyle::translation_for("OK"), CorrectionStyle::TranslateEn);
+    }
+
     #[test]
     fn test_instruction_prompts_exist() {
         for style in CorrectionStyle::all() {
@@ -220,11 +378,68 @@ mod tests {
     #[test]
     fn test_system_prompts_exist() {
         for style in CorrectionStyle::all() {
-            let prompt = get_system_prompt(*style);
+            let prompt = get_system_prompt(*style, &LanguageContext::default());
             assert!(!prompt.is_empty(), "System prompt for {:?} should not be empty", style);
         }
     }
 
+    #[test]
+    fn test_custom_style_prompts_and_errors() {
+        let custom = |name: &str, instruction: &str, system_prompt: &str| CustomStyle {
+            name: name.to_string(),
+            instruction: instruction.to_string(),
+            system_prompt: system_prompt.to_string(),
+        };
+        let styles = vec![
+            custom("Lista", "Zamień na listę punktów", "You write bullet lists."),
+            custom("Tweet", "Skróć do 280 znaków", ""),
+            custom("lista", "Inna lista", ""),
+            custom(" ", "x", ""),
+            custom("Summary", "x", ""),
+            custom("Pusty", "  ", ""),
+        ];
+
+        let language = LanguageContext::default();
+        assert_eq!(get_custom_system_prompt(&styles[0], &language), "You write bullet lists.");
+        assert_eq!(get_custom_system_prompt(&styles[1], &language), SYSTEM_PROMPT);
+        assert_eq!(get_custom_system_prompt(&instruction_style("x"), &language), SYSTEM_PROMPT);
+
+        let errors = custom_style_errors(&styles);
+        assert_eq!(errors.iter().map(|(i, _)| *i).collect::<Vec<_>>(), vec![2, 3, 4, 5]);
+        assert_eq!(errors[0].1, "powtórzona nazwa „lista”");
+        let usable: Vec<&str> = usable_custom_styles(&styles).iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(usable, vec!["Lista", "Tweet"]);
+    }
+
+    #[test]
+    fn test_system_prompt_per_language() {
+        let prompts = BTreeMap::from([
+            ("en".to_string(), "You are an English editor.".to_string()),
+            ("de".to_string(), "  ".to_string()),
+        ]);
+
+        let english = LanguageContext::detect("This is the text that we need to send.", &prompts);
+        assert_eq!(english.language, Some("en"));
+        assert_eq!(get_system_prompt(CorrectionStyle::Normal, &english), "You are an English editor.");
+        assert_eq!(get_system_prompt(CorrectionStyle::Summary, &english), "You are an English editor.");
+        assert_eq!(get_system_prompt(CorrectionStyle::TranslatePl, &english), SYSTEM_PROMPT);
+        assert_eq!(get_system_prompt(CorrectionStyle::Prompt, &english), PROMPT_SYSTEM_PROMPT);
+
+        // Pusty prompt i język bez wpisu zostawiają prompt wbudowany
+        let german = LanguageContext::detect("Das ist der Text, den wir schicken müssen.", &prompts);
+        assert_eq!(get_system_prompt(CorrectionStyle::Normal, &german), SYSTEM_PROMPT);
+        let polish = LanguageContext::detect("To jest tekst, który trzeba wysłać.", &prompts);
+        assert_eq!(get_system_prompt(CorrectionStyle::Professional, &polish), PROFESSIONAL_SYSTEM_PROMPT);
+    }
+
+    #[test]
+    fn test_language_context_skips_detection_without_prompts() {
+        let prompts = BTreeMap::new();
+        let context = LanguageContext::detect("This is the text that we need to send.", &prompts);
+        assert_eq!(context.language, None);
+        assert_eq!(get_system_prompt(CorrectionStyle::Normal, &context), SYSTEM_PROMPT);
+    }
+
     #[test]
     fn test_build_full_prompt() {
         let prompt = build_full_prompt(CorrectionStyle::Normal, "Test text");
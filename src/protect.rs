@@ -0,0 +1,169 @@
+//! Ochrona fragmentów tekstu przed zmianą przez modele
+//!
+//! Dopasowania skonfigurowanych wyrażeń regularnych (identyfikatory zgłoszeń,
+//! adresy URL, fragmenty kodu) są przed wysłaniem zamieniane na znaczniki
+//! `[[KEEP_n]]`, a po odebraniu wyniku podstawiane z powrotem.
+
+use regex::Regex;
+
+/// Dopisywane do system promptu, gdy w tekście są chronione fragmenty
+pub const PLACEHOLDER_PROMPT: &str = "\n\nThe text contains placeholders like [[KEEP_0]]. \
+    They stand for content that must not change. Copy every placeholder into the output exactly as it is, \
+    in the same position, and never translate, split or remove it.";
+
+/// Kompiluje wzorce z konfiguracji; błąd wskazuje pierwszy niepoprawny wzorzec
+pub fn compile_patterns(patterns: &[String]) -> Result<Vec<Regex>, String> {
+    patterns
+        .iter()
+        .filter(|p| !p.trim().is_empty())
+        .map(|p| Regex::new(p).map_err(|e| format!("Niepoprawny wzorzec '{}': {}", p, e)))
+        .collect()
+}
+
+fn placeholder(index: usize) -> String {
+    format!("[[KEEP_{}]]", index)
+}
+
+/// Tekst z podmienionymi fragmentami i ich oryginałami
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProtectedText {
+    pub text: String,
+    spans: Vec<String>,
+}
+
+/// Wynik przywrócenia fragmentów w odpowiedzi modelu
+#[derive(Debug, Clone, PartialEq)]
+pub struct Restored {
+    pub text: String,
+    /// Fragmenty, których znaczniki model zgubił
+    pub missing: Vec<String>,
+}
+
+impl ProtectedText {
+    /// Zamienia dopasowania na znaczniki; przy nakładaniu wygrywa wcześniejsze dopasowanie
+    pub fn new(text: &str, patterns: &[Regex]) -> Self {
+        let mut matches: Vec<(usize, usize)> = patterns
+            .iter()
+            .flat_map(|re| re.find_iter(text).map(|m| (m.start(), m.end())))
+            .filter(|(start, end)| start < end)
+            .collect();
+        matches.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+
+        let mut result = String::with_capacity(text.len());
+        let mut spans = Vec::new();
+        let mut pos = 0;
+        for (start, end) in matches {
+            if start < pos {
+                continue;
+            }
+            result.push_str(&text[pos..start]);
+            result.push_str(&placeholder(spans.len()));
+            spans.push(text[start..end].to_string());
+            pos = end;
+        }
+        result.push_str(&text[pos..]);
+
+        Self { text: result, spans }
+    }
+
+    pub fn has_spans(&self) -> bool {
+        !self.spans.is_empty()
+    }
+
+    pub fn spans(&self) -> &[String] {
+        &self.spans
+    }
+
+    /// System prompt z instrukcją o znacznikach, jeśli są potrzebne
+    pub fn system_prompt(&self, base: &str) -> String {
+        if self.has_spans() {
+            format!("{}{}", base, PLACEHOLDER_PROMPT)
+        } else {
+            base.to_string()
+        }
+    }
+
+    /// Podstawia oryginalne fragmenty w miejsce znaczników
+    pub fn restore(&self, output: &str) -> Restored {
+        let mut text = output.to_string();
+        let mut missing = Vec::new();
+
+        for (index, span) in self.spans.iter().enumerate() {
+            let marker = placeholder(index);
+            if text.contains(&marker) {
+                text = text.replace(&marker, span);
+            } else {
+                missing.push(span.clone());
+            }
+        }
+
+        Restored { text, missing }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ProtectedSpans;
+
+    fn default_patterns() -> Vec<Regex> {
+        compile_patterns(&ProtectedSpans::default().patterns).unwrap()
+    }
+
+    #[test]
+    fn test_protect_and_restore_roundtrip() {
+        let text = "Zgłoszenie ABC-123 opisano na https://example.com/a?b=1 , patrz `cargo test`.";
+        let protected = ProtectedText::new(text, &default_patterns());
+
+        assert_eq!(protected.spans(), ["ABC-123", "https://example.com/a?b=1", "`cargo test`"]);
+        assert!(!protected.text.contains("ABC-123"));
+        assert!(protected.text.contains("[[KEEP_0]]"));
+
+        let restored = protected.restore(&protected.text);
+        assert_eq!(restored.text, text);
+        assert!(restored.missing.is_empty());
+    }
+
+    #[test]
+    fn test_restore_reports_missing_placeholders() {
+        let protected = ProtectedText::new("Napraw ABC-1 i XYZ-2", &default_patterns());
+        let restored = protected.restore("Napraw [[KEEP_1]]");
+
+        assert_eq!(restored.text, "Napraw XYZ-2");
+        assert_eq!(restored.missing, vec!["ABC-1".to_string()]);
+    }
+
+    #[test]
+    fn test_restore_many_placeholders() {
+        let text: Vec<String> = (1..=12).map(|i| format!("TK-{}", i)).collect();
+        let text = text.join(" ");
+        let protected = ProtectedText::new(&text, &default_patterns());
+
+        assert_eq!(protected.spans().len(), 12);
+        assert_eq!(protected.restore(&protected.text).text, text);
+    }
+
+    #[test]
+    fn test_overlapping_matches_keep_first() {
+        let patterns = compile_patterns(&[r"https?://\S+".to_string(), r"[A-Z]+-\d+".to_string()]).unwrap();
+        let protected = ProtectedText::new("https://jira.example.com/browse/ABC-7", &patterns);
+
+        assert_eq!(protected.spans().len(), 1);
+        assert_eq!(protected.text, "[[KEEP_0]]");
+    }
+
+    #[test]
+    fn test_no_matches_keeps_prompt() {
+        let protected = ProtectedText::new("Zwykły tekst", &default_patterns());
+
+        assert!(!protected.has_spans());
+        assert_eq!(protected.text, "Zwykły tekst");
+        assert_eq!(protected.system_prompt("BASE"), "BASE");
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_reported() {
+        let result = compile_patterns(&["(".to_string()]);
+        assert!(result.unwrap_err().contains("'('"));
+    }
+}
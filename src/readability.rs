@@ -0,0 +1,142 @@
+//! Miary czytelności wyników: indeks FOG-PL, średnia długość zdania, czas czytania
+//!
+//! FOG-PL to polska odmiana indeksu mglistości Gunninga:
+//! `0.4 × (słowa / zdania + 100 × trudne słowa / słowa)`, gdzie trudne słowo
+//! ma co najmniej cztery sylaby. Sylaby liczone są jako grupy samogłosek,
+//! a „i” przed samogłoską (np. „nie”, „się”) nie tworzy osobnej sylaby.
+
+use crate::merge::split_sentences;
+
+/// Liczba sylab, od której słowo jest trudne
+pub const HARD_WORD_SYLLABLES: usize = 4;
+/// Tempo czytania używane do szacowania czasu (słowa na minutę)
+pub const WORDS_PER_MINUTE: f64 = 200.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Readability {
+    pub words: usize,
+    pub sentences: usize,
+    pub hard_words: usize,
+    pub avg_sentence_len: f64,
+    pub fog_pl: f64,
+    pub reading_secs: u64,
+}
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'ą' | 'e' | 'ę' | 'i' | 'o' | 'ó' | 'u' | 'y')
+}
+
+/// Liczba sylab polskiego słowa (co najmniej 1 dla słów z literami)
+pub fn syllables(word: &str) -> usize {
+    let chars: Vec<char> = word.to_lowercase().chars().filter(|c| c.is_alphabetic()).collect();
+    if chars.is_empty() {
+        return 0;
+    }
+
+    let mut count = 0;
+    let mut in_vowels = false;
+    for (i, &c) in chars.iter().enumerate() {
+        let softening = c == 'i' && chars.get(i + 1).is_some_and(|&n| is_vowel(n));
+        if is_vowel(c) && !softening {
+            if !in_vowels {
+                count += 1;
+            }
+            in_vowels = true;
+        } else {
+            in_vowels = false;
+        }
+    }
+    count.max(1)
+}
+
+fn words(text: &str) -> impl Iterator<Item = &str> {
+    text.split_whitespace()
+        .filter(|w| w.chars().any(char::is_alphanumeric))
+}
+
+/// Miary tekstu; `None` dla tekstu bez słów
+pub fn analyze(text: &str) -> Option<Readability> {
+    let word_count = words(text).count();
+    if word_count == 0 {
+        return None;
+    }
+    let hard_words = words(text)
+        .filter(|w| syllables(w) >= HARD_WORD_SYLLABLES)
+        .count();
+    let sentences = split_sentences(text).len().max(1);
+
+    let avg_sentence_len = word_count as f64 / sentences as f64;
+    let fog_pl = 0.4 * (avg_sentence_len + 100.0 * hard_words as f64 / word_count as f64);
+    let reading_secs = (word_count as f64 / WORDS_PER_MINUTE * 60.0).ceil() as u64;
+
+    Some(Readability {
+        words: word_count,
+        sentences,
+        hard_words,
+        avg_sentence_len,
+        fog_pl,
+        reading_secs,
+    })
+}
+
+/// Krótki opis pod panelem, np. "FOG-PL 8.4 · zdanie śr. 12.0 słów · czytanie ~30 s"
+pub fn summary(r: &Readability) -> String {
+    let reading = if r.reading_secs < 60 {
+        format!("~{} s", r.reading_secs)
+    } else {
+        format!("~{} min", r.reading_secs.div_ceil(60))
+    };
+    format!(
+        "FOG-PL {:.1} · zdanie śr. {:.1} słów · czytanie {}",
+        r.fog_pl, r.avg_sentence_len, reading
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_syllables() {
+        assert_eq!(syllables("kot"), 1);
+        assert_eq!(syllables("nie"), 1);
+        assert_eq!(syllables("się"), 1);
+        assert_eq!(syllables("mama"), 2);
+        assert_eq!(syllables("Polska"), 2);
+        assert_eq!(syllables("kolejność"), 3);
+        assert_eq!(syllables("niebezpieczeństwo"), 5);
+        assert_eq!(syllables("informacja"), 4);
+        assert_eq!(syllables("w"), 1);
+        assert_eq!(syllables("123,"), 0);
+    }
+
+    #[test]
+    fn test_analyze() {
+        let r = analyze("Ala ma kota. Kot ma niebezpieczne zainteresowania.").unwrap();
+        assert_eq!(r.words, 7);
+        assert_eq!(r.sentences, 2);
+        assert_eq!(r.hard_words, 2);
+        assert!((r.avg_sentence_len - 3.5).abs() < 1e-9);
+        assert!((r.fog_pl - 0.4 * (3.5 + 200.0 / 7.0)).abs() < 1e-9);
+        assert_eq!(r.reading_secs, 3);
+    }
+
+    #[test]
+    fn test_analyze_empty_and_no_terminator() {
+        assert_eq!(analyze("  \n "), None);
+        assert_eq!(analyze("— ... —"), None);
+        assert_eq!(analyze("bez kropki na końcu").unwrap().sentences, 1);
+    }
+
+    #[test]
+    fn test_summary() {
+        let r = analyze("Ala ma kota.").unwrap();
+        assert_eq!(summary(&r), "FOG-PL 1.2 · zdanie śr. 3.0 słów · czytanie ~1 s");
+
+        let long = Readability {
+            reading_secs: 90,
+            ..r
+        };
+        assert!(summary(&long).ends_with("czytanie ~2 min"));
+    }
+}
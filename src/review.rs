@@ -0,0 +1,319 @@
+//! Tryb przeglądu: porównanie wyników zmiana po zmianie
+//!
+//! Zmiany wszystkich dostawców względem oryginału (diff po słowach) są
+//! grupowane w fragmenty obejmujące te same miejsca tekstu. Dla każdego
+//! fragmentu wybiera się wersję jednego dostawcy albo zostawia oryginał,
+//! a wynik złożony powstaje z oryginału z podstawionymi wyborami.
+
+use similar::{DiffTag, TextDiff};
+
+/// Liczba znaków kontekstu pokazywanego wokół fragmentu
+pub const CONTEXT_CHARS: usize = 40;
+
+/// Jedno miejsce w tekście, które zmienił co najmniej jeden dostawca
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReviewHunk {
+    pub original: String,
+    /// Wersja każdego dostawcy; `None` gdy dostawca nie zwrócił wyniku
+    pub versions: Vec<Option<String>>,
+    /// Wybrany dostawca; `None` oznacza pozostawienie oryginału
+    pub choice: Option<usize>,
+    /// Zakres tokenów oryginału
+    start: usize,
+    end: usize,
+}
+
+impl ReviewHunk {
+    pub fn chosen_text(&self) -> &str {
+        self.choice
+            .and_then(|i| self.versions.get(i)?.as_deref())
+            .unwrap_or(&self.original)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Review {
+    tokens: Vec<String>,
+    hunks: Vec<ReviewHunk>,
+    current: usize,
+}
+
+impl Review {
+    /// `results[i]` to wynik i-tego dostawcy (`None` przy błędzie lub braku wyniku)
+    pub fn new(original: &str, results: &[Option<&str>]) -> Self {
+        let tokens: Vec<String> = TextDiff::from_words(original, original)
+            .old_slices()
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        let diffs: Vec<Option<ProviderDiff>> = results
+            .iter()
+            .map(|r| r.map(|text| ProviderDiff::new(original, text, tokens.len())))
+            .collect();
+
+        let mut ranges: Vec<(usize, usize)> = diffs
+            .iter()
+            .flatten()
+            .flat_map(|d| d.changes.iter().copied())
+            .collect();
+        ranges.sort();
+
+        let mut clusters: Vec<(usize, usize)> = Vec::new();
+        for (start, end) in ranges {
+            match clusters.last_mut() {
+                Some(last) if start <= last.1 => last.1 = last.1.max(end),
+                _ => clusters.push((start, end)),
+            }
+        }
+
+        let hunks = clusters
+            .into_iter()
+            .map(|(start, end)| ReviewHunk {
+                original: tokens[start..end].concat(),
+                versions: diffs
+                    .iter()
+                    .map(|d| d.as_ref().map(|d| d.version(start, end)))
+                    .collect(),
+                choice: None,
+                start,
+                end,
+            })
+            .collect();
+
+        Self {
+            tokens,
+            hunks,
+            current: 0,
+        }
+    }
+
+    pub fn hunks(&self) -> &[ReviewHunk] {
+        &self.hunks
+    }
+
+    pub fn hunk_count(&self) -> usize {
+        self.hunks.len()
+    }
+
+    pub fn current_index(&self) -> usize {
+        self.current
+    }
+
+    pub fn current(&self) -> Option<&ReviewHunk> {
+        self.hunks.get(self.current)
+    }
+
+    pub fn next(&mut self) {
+        if self.current + 1 < self.hunks.len() {
+            self.current += 1;
+        }
+    }
+
+    pub fn prev(&mut self) {
+        self.current = self.current.saturating_sub(1);
+    }
+
+    /// Przyjmuje wersję dostawcy dla bieżącego fragmentu i przechodzi dalej.
+    /// Dostawca bez wyniku jest ignorowany.
+    pub fn accept(&mut self, provider: usize) -> bool {
+        let Some(hunk) = self.hunks.get_mut(self.current) else {
+            return false;
+        };
+        if !matches!(hunk.versions.get(provider), Some(Some(_))) {
+            return false;
+        }
+        hunk.choice = Some(provider);
+        self.next();
+        true
+    }
+
+    /// Przywraca oryginał w bieżącym fragmencie
+    pub fn keep_original(&mut self) {
+        if let Some(hunk) = self.hunks.get_mut(self.current) {
+            hunk.choice = None;
+            self.next();
+        }
+    }
+
+    /// Tekst oryginału przed i po bieżącym fragmencie (do `CONTEXT_CHARS` znaków)
+    pub fn context(&self) -> (String, String) {
+        let Some(hunk) = self.current() else {
+            return (String::new(), String::new());
+        };
+        let before = self.tokens[..hunk.start].concat();
+        let after = self.tokens[hunk.end..].concat();
+
+        let skip = before.chars().count().saturating_sub(CONTEXT_CHARS);
+        (
+            before.chars().skip(skip).collect(),
+            after.chars().take(CONTEXT_CHARS).collect(),
+        )
+    }
+
+    /// Oryginał z podstawionymi wybranymi wersjami fragmentów
+    pub fn composite(&self) -> String {
+        let mut result = String::new();
+        let mut pos = 0;
+        for hunk in &self.hunks {
+            result.push_str(&self.tokens[pos..hunk.start].concat());
+            result.push_str(hunk.chosen_text());
+            pos = hunk.end;
+        }
+        result.push_str(&self.tokens[pos..].concat());
+        result
+    }
+}
+
+/// Diff jednego dostawcy z mapowaniem pozycji oryginału na jego tokeny
+struct ProviderDiff {
+    new_tokens: Vec<String>,
+    /// Zakresy tokenów oryginału objęte zmianami (wstawienie to zakres pusty)
+    changes: Vec<(usize, usize)>,
+    /// Pozycja w nowym tekście przed ewentualnym wstawieniem w danym miejscu
+    before: Vec<usize>,
+    /// Pozycja w nowym tekście po ewentualnym wstawieniu w danym miejscu
+    after: Vec<usize>,
+}
+
+impl ProviderDiff {
+    fn new(original: &str, result: &str, old_len: usize) -> Self {
+        let diff = TextDiff::from_words(original, result);
+        let new_tokens: Vec<String> = diff.new_slices().iter().map(|s| s.to_string()).collect();
+
+        let mut changes = Vec::new();
+        let mut before = vec![usize::MAX; old_len + 1];
+        let mut after = vec![0; old_len + 1];
+
+        for op in diff.ops() {
+            let (tag, old, new) = op.as_tag_tuple();
+            match tag {
+                DiffTag::Equal => {
+                    for k in 0..=(old.end - old.start) {
+                        let pos = old.start + k;
+                        before[pos] = before[pos].min(new.start + k);
+                        after[pos] = after[pos].max(new.start + k);
+                    }
+                }
+                _ => {
+                    changes.push((old.start, old.end));
+                    before[old.start] = before[old.start].min(new.start);
+                    after[old.end] = after[old.end].max(new.end);
+                }
+            }
+        }
+
+        Self {
+            new_tokens,
+            changes,
+            before,
+            after,
+        }
+    }
+
+    fn version(&self, start: usize, end: usize) -> String {
+        let from = self.before[start];
+        let to = self.after[end];
+        if from >= to {
+            return String::new();
+        }
+        self.new_tokens[from..to].concat()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORIGINAL: &str = "Ala ma kota i psa, ktory szczeka.";
+
+    #[test]
+    fn test_no_changes_has_no_hunks() {
+        let review = Review::new(ORIGINAL, &[Some(ORIGINAL), None]);
+        assert_eq!(review.hunk_count(), 0);
+        assert_eq!(review.composite(), ORIGINAL);
+    }
+
+    #[test]
+    fn test_hunks_group_changes_across_providers() {
+        let review = Review::new(
+            ORIGINAL,
+            &[
+                Some("Ala ma kota i psa, który szczeka."),
+                Some("Ala ma kota oraz psa, który szczeka."),
+                None,
+            ],
+        );
+
+        assert_eq!(review.hunk_count(), 2);
+        let first = &review.hunks()[0];
+        assert_eq!(first.original, "i");
+        assert_eq!(first.versions, vec![Some("i".to_string()), Some("oraz".to_string()), None]);
+
+        let second = &review.hunks()[1];
+        assert_eq!(second.original, "ktory");
+        assert_eq!(second.versions[0].as_deref(), Some("który"));
+        assert_eq!(second.versions[1].as_deref(), Some("który"));
+    }
+
+    #[test]
+    fn test_composite_uses_choices() {
+        let mut review = Review::new(
+            ORIGINAL,
+            &[
+                Some("Ala ma kota i psa, który szczeka."),
+                Some("Ala ma kota oraz psa, który szczeka."),
+            ],
+        );
+        assert_eq!(review.composite(), ORIGINAL);
+
+        assert!(review.accept(1));
+        assert_eq!(review.current_index(), 1);
+        assert!(review.accept(0));
+
+        assert_eq!(review.composite(), "Ala ma kota oraz psa, który szczeka.");
+    }
+
+    #[test]
+    fn test_accept_ignores_missing_provider() {
+        let mut review = Review::new(ORIGINAL, &[Some("Ala ma kota."), None]);
+        assert!(!review.accept(1));
+        assert!(!review.accept(7));
+        assert_eq!(review.current_index(), 0);
+    }
+
+    #[test]
+    fn test_insertions_and_deletions() {
+        let mut review = Review::new("Idę do domu", &[Some("Idę teraz do domu"), Some("Idę domu")]);
+        for i in 0..review.hunk_count() {
+            let hunk = &review.hunks()[i];
+            assert!(hunk.versions.iter().all(Option::is_some));
+        }
+
+        review.accept(0);
+        assert_eq!(review.composite(), "Idę teraz do domu");
+    }
+
+    #[test]
+    fn test_navigation_and_context() {
+        let mut review = Review::new(
+            "Pierwsze zdanie ma blad. Drugie zdanie tez ma blad.",
+            &[Some("Pierwsze zdanie ma błąd. Drugie zdanie też ma błąd.")],
+        );
+        assert_eq!(review.hunk_count(), 3);
+
+        review.prev();
+        assert_eq!(review.current_index(), 0);
+        let (before, after) = review.context();
+        assert_eq!(before, "Pierwsze zdanie ma ");
+        assert!(after.starts_with(" Drugie"));
+
+        review.next();
+        review.next();
+        review.next();
+        assert_eq!(review.current_index(), 2);
+
+        review.keep_original();
+        assert_eq!(review.current_index(), 2);
+    }
+}
@@ -0,0 +1,153 @@
+//! Łamanie wyniku do szerokości wierszy oryginału
+//!
+//! Gdy tekst wejściowy był łamany ręcznie (opis commita, mail w zwykłym
+//! tekście na 72 kolumnach), modele i tak zwracają długie akapity. Szerokość
+//! oryginału jest wykrywana z wierszy przerwanych w środku akapitu: przy
+//! łamaniu "do szerokości" pierwsze słowo następnego wiersza nie mieściłoby
+//! się w poprzednim. Wynik jest łamany tak samo przed wklejeniem.
+
+use crate::normalize::starts_block;
+
+/// Zakres szerokości uznawanych za łamanie do kolumny
+pub const MIN_WIDTH: usize = 30;
+pub const MAX_WIDTH: usize = 120;
+
+/// Jaka część przerwanych wierszy musi wyglądać na łamane do szerokości
+const MIN_WRAPPED_SHARE: f64 = 0.8;
+
+fn width_of(line: &str) -> usize {
+    line.chars().count()
+}
+
+/// Szerokość, do której łamano tekst (najdłuższy przerwany wiersz);
+/// `None`, gdy wiersze nie były łamane ręcznie
+pub fn detect_width(text: &str) -> Option<usize> {
+    let lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+    let broken: Vec<(&str, &str)> = lines
+        .windows(2)
+        .map(|pair| (pair[0], pair[1]))
+        .filter(|(line, next)| !line.is_empty() && !next.trim().is_empty() && !starts_block(next))
+        .collect();
+    if broken.len() < 2 {
+        return None;
+    }
+
+    let width = broken.iter().map(|(line, _)| width_of(line)).max()?;
+    if !(MIN_WIDTH..=MAX_WIDTH).contains(&width) {
+        return None;
+    }
+
+    // Następne słowo nie zmieściłoby się w wierszu, więc złamano go z braku miejsca
+    let wrapped = broken
+        .iter()
+        .filter(|(line, next)| {
+            let first_word = next.split_whitespace().next().unwrap_or_default();
+            width_of(line) + 1 + width_of(first_word) > width
+        })
+        .count();
+    (wrapped as f64 / broken.len() as f64 >= MIN_WRAPPED_SHARE).then_some(width)
+}
+
+/// Wcięcie kolejnych wierszy elementu listy ("- ", "12. ")
+fn hanging_indent(line: &str) -> Option<usize> {
+    let indent = width_of(line) - width_of(line.trim_start());
+    let body = line.trim_start();
+    let marker = ["- ", "* ", "• "]
+        .iter()
+        .find(|m| body.starts_with(*m))
+        .map(|m| width_of(m))
+        .or_else(|| {
+            let (n, _) = body.split_once(". ")?;
+            (!n.is_empty() && n.chars().all(|c| c.is_ascii_digit())).then(|| n.len() + 2)
+        })?;
+    Some(indent + marker)
+}
+
+fn wrap_line(line: &str, width: usize, out: &mut Vec<String>) {
+    let continuation = " ".repeat(hanging_indent(line).unwrap_or(0));
+    let leading = &line[..line.len() - line.trim_start().len()];
+    let mut current = leading.to_string();
+    let mut empty = true;
+    for word in line.split_whitespace() {
+        if !empty && width_of(&current) + 1 + width_of(word) > width {
+            out.push(std::mem::replace(&mut current, continuation.clone()));
+            empty = true;
+        }
+        if !empty {
+            current.push(' ');
+        }
+        current.push_str(word);
+        empty = false;
+    }
+    out.push(current);
+}
+
+/// Łamie akapity do `width` znaków; nagłówki, cytaty i tabele zostają bez zmian
+pub fn wrap(text: &str, width: usize) -> String {
+    // Wiersze logiczne: akapit albo element listy sklejony z kontynuacją
+    let mut logical: Vec<String> = Vec::new();
+    let mut joinable = false;
+    for line in text.lines().map(str::trim_end) {
+        if line.is_empty() {
+            logical.push(String::new());
+            joinable = false;
+        } else if joinable && !starts_block(line) {
+            let last = logical.last_mut().expect("joinable after a line");
+            last.push(' ');
+            last.push_str(line.trim_start());
+        } else {
+            logical.push(line.to_string());
+            let body = line.trim_start();
+            joinable = !["#", ">", "|"].iter().any(|p| body.starts_with(p));
+        }
+    }
+
+    let mut out = Vec::new();
+    for line in &logical {
+        let body = line.trim_start();
+        if line.is_empty() || ["#", ">", "|"].iter().any(|p| body.starts_with(p)) {
+            out.push(line.clone());
+        } else {
+            wrap_line(line, width, &mut out);
+        }
+    }
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMMIT: &str = "Fix race condition in file watcher initialization\n\n\
+        The watcher could emit events before the handler map was filled, so the\n\
+        first change after startup was silently dropped. Register handlers\n\
+        before starting the watcher thread and add a regression test that\n\
+        touches a file immediately after start.";
+
+    #[test]
+    fn test_detect_width() {
+        // Najdłuższy przerwany wiersz ma 71 znaków (łamane do 72 kolumn)
+        assert_eq!(detect_width(COMMIT), Some(71));
+        assert_eq!(detect_width("Jedno długie zdanie bez łamania wierszy."), None);
+        // Krótkie wiersze wiersza (poezji) nie są łamaniem do szerokości
+        assert_eq!(detect_width("Litwo! Ojczyzno moja!\nty jesteś jak zdrowie.\nIle cię trzeba cenić,\nten tylko się dowie"), None);
+    }
+
+    #[test]
+    fn test_wrap() {
+        let long = "The watcher could emit events before the handler map was filled, so the first change \
+                    after startup was silently dropped. Register handlers before starting the watcher thread \
+                    and add a regression test that touches a file immediately after start.";
+        let wrapped = wrap(&format!("Subject line\n\n{}\n\n- one item that is long enough to need a second line here", long), 40);
+        assert!(wrapped.lines().all(|l| l.chars().count() <= 40), "{}", wrapped);
+        assert!(wrapped.starts_with("Subject line\n\nThe watcher could emit events before the\n"));
+        assert!(wrapped.ends_with("- one item that is long enough to need a\n  second line here"));
+        assert_eq!(wrap(&wrapped, 40), wrapped);
+    }
+
+    #[test]
+    fn test_wrap_keeps_headings_and_long_words() {
+        let text = "# A heading that is longer than the width\nhttps://example.com/a/very/long/path/that/cannot/be/split";
+        assert_eq!(wrap(text, 20), text);
+    }
+}
@@ -0,0 +1,386 @@
+//! Harmonogram zadań wsadowych
+//!
+//! Zadania z konfiguracji (korekta plików z katalogu, raport tygodniowy) są
+//! uruchamiane raz dziennie o podanej godzinie, dopóki aplikacja działa
+//! w zasobniku. Zadanie, którego godzina minęła przy wyłączonej aplikacji,
+//! rusza przy najbliższym sprawdzeniu tego samego dnia. Wynik ostatniego
+//! uruchomienia każdego zadania jest zapisywany w `jobs_state.json`.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::api::{correct_text, Provider};
+use crate::config::{Config, Job};
+use crate::digest::pick_provider;
+use crate::prompts::{get_instruction_prompt, get_system_prompt, CorrectionStyle, LanguageContext};
+
+/// Rozszerzenia plików poprawianych przez zadanie "folder"
+const FOLDER_EXTENSIONS: &[&str] = &["txt", "md"];
+
+/// Podkatalog wyników, gdy zadanie nie podaje własnego
+const DEFAULT_OUTPUT_DIR: &str = "poprawione";
+
+const DAY_NAMES: [&str; 7] = ["pn", "wt", "śr", "cz", "pt", "sb", "nd"];
+
+/// Bieżący czas lokalny (z GLib po stronie UI)
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalTime {
+    /// Data "RRRR-MM-DD"
+    pub date: String,
+    /// 1–7, poniedziałek = 1
+    pub weekday: u32,
+    /// Minuty od północy
+    pub minutes: u32,
+}
+
+/// Co zadanie ma zrobić, po sprawdzeniu konfiguracji
+#[derive(Debug, Clone, PartialEq)]
+pub enum Task {
+    Folder {
+        input: PathBuf,
+        output: PathBuf,
+        provider: Provider,
+        style: CorrectionStyle,
+    },
+    Digest,
+}
+
+/// "HH:MM" → minuty od północy
+pub fn parse_time(at: &str) -> Option<u32> {
+    let (hours, minutes) = at.trim().split_once(':')?;
+    let (hours, minutes): (u32, u32) = (hours.parse().ok()?, minutes.parse().ok()?);
+    (hours < 24 && minutes < 60).then_some(hours * 60 + minutes)
+}
+
+/// Zadanie gotowe do uruchomienia; błąd opisuje pierwszy niepoprawny wpis
+pub fn resolve(job: &Job, config: &Config) -> Result<Task, String> {
+    if parse_time(&job.at).is_none() {
+        return Err(format!("Zadanie „{}”: niepoprawna godzina „{}”", job.name, job.at));
+    }
+
+    match job.kind.trim().to_lowercase().as_str() {
+        "digest" => Ok(Task::Digest),
+        "folder" => {
+            if job.folder.trim().is_empty() {
+                return Err(format!("Zadanie „{}”: brak katalogu", job.name));
+            }
+            let input = PathBuf::from(job.folder.trim());
+            let output = match job.output.trim() {
+                "" => input.join(DEFAULT_OUTPUT_DIR),
+                output => PathBuf::from(output),
+            };
+
+            let provider = match job.provider.trim() {
+                "" => pick_provider(config).ok_or_else(|| format!("Zadanie „{}”: brak klucza API", job.name))?,
+                name => Provider::all()
+                    .iter()
+                    .copied()
+                    .find(|p| p.name().eq_ignore_ascii_case(name))
+                    .ok_or_else(|| format!("Zadanie „{}”: nieznany dostawca „{}”", job.name, name))?,
+            };
+            let style = match job.style.trim() {
+                "" => CorrectionStyle::Normal,
+                name => CorrectionStyle::all()
+                    .iter()
+                    .copied()
+                    .find(|s| s.as_str().eq_ignore_ascii_case(name))
+                    .ok_or_else(|| format!("Zadanie „{}”: nieznany styl „{}”", job.name, name))?,
+            };
+
+            Ok(Task::Folder {
+                input,
+                output,
+                provider,
+                style,
+            })
+        }
+        kind => Err(format!("Zadanie „{}”: nieznany rodzaj „{}”", job.name, kind)),
+    }
+}
+
+/// Wynik ostatniego uruchomienia zadania
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JobStatus {
+    /// Data lokalna uruchomienia "RRRR-MM-DD"
+    pub date: String,
+    /// Czas lokalny do wyświetlenia, np. "2026-10-18 02:30"
+    pub ran_at: String,
+    pub ok: bool,
+    pub message: String,
+}
+
+/// Czy zadanie powinno ruszyć teraz: właściwy dzień, godzina minęła, dziś jeszcze nie działało
+pub fn is_due(job: &Job, last: Option<&JobStatus>, now: &LocalTime) -> bool {
+    let Some(at) = parse_time(&job.at) else {
+        return false;
+    };
+    let right_day = job.days.is_empty() || job.days.contains(&now.weekday);
+    right_day && now.minutes >= at && last.is_none_or(|s| s.date != now.date)
+}
+
+/// Stan zadań w `jobs_state.json`: nazwa zadania → ostatnie uruchomienie
+pub struct JobState {
+    path: PathBuf,
+    jobs: BTreeMap<String, JobStatus>,
+}
+
+impl JobState {
+    /// Wczytuje stan; brak lub uszkodzenie pliku daje pusty stan
+    pub fn load<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let jobs = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self { path, jobs }
+    }
+
+    /// Plik stanu obok `config.toml`
+    pub fn default_path() -> PathBuf {
+        Config::get_config_path().with_file_name("jobs_state.json")
+    }
+
+    pub fn get(&self, name: &str) -> Option<&JobStatus> {
+        self.jobs.get(name)
+    }
+
+    /// Zapisuje wynik uruchomienia zadania
+    pub fn record(&mut self, name: &str, status: JobStatus) -> Result<(), Box<dyn std::error::Error>> {
+        self.jobs.insert(name.to_string(), status);
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(&self.jobs)?)?;
+        Ok(())
+    }
+}
+
+/// Pliki do poprawienia: bez wyniku albo zmienione po ostatnim wyniku
+pub fn pending_files(input: &Path, output: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = fs::read_dir(input)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| FOLDER_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        })
+        .filter(|path| {
+            let Some(name) = path.file_name() else {
+                return false;
+            };
+            let modified = |p: &Path| fs::metadata(p).and_then(|m| m.modified()).ok();
+            match (modified(path), modified(&output.join(name))) {
+                (Some(source), Some(result)) => source > result,
+                (_, None) => true,
+                (None, Some(_)) => false,
+            }
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+fn files_word(count: usize) -> &'static str {
+    if count == 1 {
+        "pliku"
+    } else {
+        "plików"
+    }
+}
+
+/// Poprawia pliki z katalogu jednym dostawcą; zwraca podsumowanie do okna zadań
+pub async fn run_folder(
+    config: &Config,
+    input: &Path,
+    output: &Path,
+    provider: Provider,
+    style: CorrectionStyle,
+) -> Result<String, String> {
+    let files = pending_files(input, output).map_err(|e| format!("Nie można odczytać {}: {}", input.display(), e))?;
+    if files.is_empty() {
+        return Ok("Brak nowych plików".to_string());
+    }
+    fs::create_dir_all(output).map_err(|e| format!("Nie można utworzyć {}: {}", output.display(), e))?;
+
+    let mut failed = Vec::new();
+    for path in &files {
+        let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let result = match fs::read_to_string(path) {
+            Ok(text) if text.trim().is_empty() => Ok(text),
+            Ok(text) => {
+                let system = get_system_prompt(style, &LanguageContext::detect(&text, &config.language_prompts));
+                correct_text(provider, config, &text, get_instruction_prompt(style), system)
+                    .await
+                    .map_err(|e| e.to_string())
+            }
+            Err(e) => Err(e.to_string()),
+        };
+        let written = result.and_then(|corrected| fs::write(output.join(&name), corrected).map_err(|e| e.to_string()));
+        if let Err(e) = written {
+            tracing::warn!("Scheduled correction of {} failed: {}", path.display(), e);
+            failed.push(format!("{} ({})", name, e));
+        }
+    }
+
+    let done = files.len() - failed.len();
+    let summary = format!("Poprawiono {} z {} {} ({})", done, files.len(), files_word(files.len()), provider.name());
+    if failed.is_empty() {
+        Ok(summary)
+    } else {
+        Err(format!("{}; błędy: {}", summary, failed.join(", ")))
+    }
+}
+
+fn days_label(days: &[u32]) -> String {
+    if days.is_empty() {
+        return "codziennie".to_string();
+    }
+    days.iter()
+        .filter_map(|&d| DAY_NAMES.get((d as usize).wrapping_sub(1)))
+        .copied()
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Tekst okna zadań: harmonogram i wynik ostatniego uruchomienia
+pub fn summary(jobs: &[Job], state: &JobState) -> String {
+    if jobs.is_empty() {
+        return "Brak zadań. Dodaj sekcję [[jobs]] w config.toml.".to_string();
+    }
+
+    let sections: Vec<String> = jobs
+        .iter()
+        .map(|job| {
+            let schedule = format!("🗓 {} ({}, {} o {})", job.name, job.kind, days_label(&job.days), job.at.trim());
+            let last = match state.get(&job.name) {
+                Some(status) => format!(
+                    "{} {}: {}",
+                    if status.ok { "✅" } else { "❌" },
+                    status.ran_at,
+                    status.message
+                ),
+                None => "Jeszcze nie uruchamiane".to_string(),
+            };
+            format!("{}\n   {}", schedule, last)
+        })
+        .collect();
+    sections.join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn job(kind: &str, at: &str, days: &[u32]) -> Job {
+        Job {
+            name: "Nocna korekta".to_string(),
+            kind: kind.to_string(),
+            at: at.to_string(),
+            days: days.to_vec(),
+            folder: "/tmp/teksty".to_string(),
+            output: String::new(),
+            provider: "Gemini".to_string(),
+            style: String::new(),
+        }
+    }
+
+    fn now(date: &str, weekday: u32, at: &str) -> LocalTime {
+        LocalTime {
+            date: date.to_string(),
+            weekday,
+            minutes: parse_time(at).unwrap(),
+        }
+    }
+
+    fn status(date: &str) -> JobStatus {
+        JobStatus {
+            date: date.to_string(),
+            ran_at: format!("{} 02:30", date),
+            ok: true,
+            message: "Poprawiono 2 z 2 plików (Gemini)".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_time() {
+        assert_eq!(parse_time("02:30"), Some(150));
+        assert_eq!(parse_time(" 23:59 "), Some(1439));
+        assert_eq!(parse_time("24:00"), None);
+        assert_eq!(parse_time("7"), None);
+    }
+
+    #[test]
+    fn test_is_due_once_per_day() {
+        let job = job("folder", "02:30", &[]);
+        assert!(!is_due(&job, None, &now("2026-10-18", 7, "02:29")));
+        assert!(is_due(&job, None, &now("2026-10-18", 7, "02:30")));
+        assert!(is_due(&job, None, &now("2026-10-18", 7, "14:00")));
+        assert!(!is_due(&job, Some(&status("2026-10-18")), &now("2026-10-18", 7, "14:00")));
+        assert!(is_due(&job, Some(&status("2026-10-17")), &now("2026-10-18", 7, "14:00")));
+
+        let weekdays = self::job("folder", "02:30", &[1, 2, 3, 4, 5]);
+        assert!(!is_due(&weekdays, None, &now("2026-10-18", 7, "03:00")));
+        assert!(!is_due(&self::job("folder", "później", &[]), None, &now("2026-10-18", 7, "03:00")));
+    }
+
+    #[test]
+    fn test_resolve() {
+        let config = Config::default();
+        assert_eq!(
+            resolve(&job("folder", "02:30", &[]), &config).unwrap(),
+            Task::Folder {
+                input: PathBuf::from("/tmp/teksty"),
+                output: PathBuf::from("/tmp/teksty/poprawione"),
+                provider: Provider::Gemini,
+                style: CorrectionStyle::Normal,
+            }
+        );
+        assert_eq!(resolve(&job("Digest", "08:00", &[]), &config).unwrap(), Task::Digest);
+        assert!(resolve(&job("backup", "08:00", &[]), &config).unwrap_err().contains("nieznany rodzaj"));
+
+        let mut no_folder = job("folder", "02:30", &[]);
+        no_folder.folder.clear();
+        assert!(resolve(&no_folder, &config).unwrap_err().contains("brak katalogu"));
+    }
+
+    #[test]
+    fn test_pending_files_skips_up_to_date_results() {
+        let dir = TempDir::new().unwrap();
+        let output = dir.path().join(DEFAULT_OUTPUT_DIR);
+        fs::create_dir_all(&output).unwrap();
+        fs::write(dir.path().join("a.txt"), "ala ma kota").unwrap();
+        fs::write(dir.path().join("b.md"), "kot ma ale").unwrap();
+        fs::write(dir.path().join("c.pdf"), "binarne").unwrap();
+        fs::write(output.join("b.md"), "Kot ma Alę.").unwrap();
+
+        let files = pending_files(dir.path(), &output).unwrap();
+        assert_eq!(files, vec![dir.path().join("a.txt")]);
+    }
+
+    #[test]
+    fn test_state_roundtrip_and_summary() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("jobs_state.json");
+
+        let mut state = JobState::load(&path);
+        assert!(state.get("Nocna korekta").is_none());
+        state.record("Nocna korekta", status("2026-10-18")).unwrap();
+
+        let state = JobState::load(&path);
+        assert_eq!(state.get("Nocna korekta"), Some(&status("2026-10-18")));
+
+        let jobs = [job("folder", "02:30", &[1, 3, 5])];
+        assert_eq!(
+            summary(&jobs, &state),
+            "🗓 Nocna korekta (folder, pn, śr, pt o 02:30)\n   ✅ 2026-10-18 02:30: Poprawiono 2 z 2 plików (Gemini)"
+        );
+        assert!(summary(&[], &state).contains("[[jobs]]"));
+    }
+}
@@ -0,0 +1,228 @@
+//! Kontekst sesji roboczej: załączniki doklejane do system promptu
+//!
+//! Użytkownik może dołączyć plik (np. style guide firmy, glosariusz), którego
+//! treść trafia do system promptu każdego zapytania aż do wyczyszczenia.
+//! Długość pojedynczego załącznika i całego kontekstu jest ograniczona,
+//! żeby nie przekroczyć okna kontekstu modeli.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Limit znaków pojedynczego załącznika
+pub const MAX_ATTACHMENT_CHARS: usize = 8_000;
+
+/// Limit znaków wszystkich załączników łącznie
+pub const MAX_CONTEXT_CHARS: usize = 16_000;
+
+/// Pliki większe od tego nie są w ogóle wczytywane
+pub const MAX_ATTACHMENT_FILE_BYTES: u64 = 1024 * 1024;
+
+const TRUNCATION_MARKER: &str = "\n[...obcięto...]";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attachment {
+    pub name: String,
+    pub content: String,
+    /// Czy treść została skrócona do `MAX_ATTACHMENT_CHARS`
+    pub truncated: bool,
+}
+
+impl Attachment {
+    pub fn new(name: &str, content: &str) -> Self {
+        let content = content.trim();
+        let truncated = content.chars().count() > MAX_ATTACHMENT_CHARS;
+        Self {
+            name: name.to_string(),
+            content: truncate_chars(content, MAX_ATTACHMENT_CHARS).to_string(),
+            truncated,
+        }
+    }
+
+    /// Wczytuje plik tekstowy (UTF-8)
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.display().to_string());
+
+        let size = fs::metadata(path)
+            .map_err(|e| format!("Nie można odczytać {}: {}", name, e))?
+            .len();
+        if size > MAX_ATTACHMENT_FILE_BYTES {
+            return Err(format!("Plik {} jest za duży ({} KiB)", name, size / 1024));
+        }
+
+        let bytes = fs::read(path).map_err(|e| format!("Nie można odczytać {}: {}", name, e))?;
+        let content = String::from_utf8(bytes)
+            .map_err(|_| format!("Plik {} nie jest plikiem tekstowym UTF-8", name))?;
+        if content.trim().is_empty() {
+            return Err(format!("Plik {} jest pusty", name));
+        }
+
+        Ok(Self::new(&name, &content))
+    }
+}
+
+/// Załączniki i katalog roboczy bieżącej sesji
+#[derive(Debug, Default, Clone)]
+pub struct SessionContext {
+    attachments: Vec<Attachment>,
+    working_dir: Option<PathBuf>,
+}
+
+impl SessionContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Dołącza plik; ponowne dołączenie pliku o tej samej nazwie podmienia treść
+    pub fn attach_file<P: AsRef<Path>>(&mut self, path: P) -> Result<&Attachment, String> {
+        let path = path.as_ref();
+        let attachment = Attachment::from_file(path)?;
+        self.working_dir = path.parent().map(Path::to_path_buf);
+        Ok(self.attach(attachment))
+    }
+
+    pub fn attach(&mut self, attachment: Attachment) -> &Attachment {
+        self.attachments.retain(|a| a.name != attachment.name);
+        self.attachments.push(attachment);
+        self.attachments.last().unwrap()
+    }
+
+    pub fn clear(&mut self) {
+        self.attachments.clear();
+    }
+
+    pub fn attachments(&self) -> &[Attachment] {
+        &self.attachments
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.attachments.is_empty()
+    }
+
+    /// Katalog ostatnio dołączonego pliku (punkt startowy wyboru kolejnych)
+    pub fn working_dir(&self) -> Option<&Path> {
+        self.working_dir.as_deref()
+    }
+
+    /// System prompt uzupełniony o treść załączników
+    pub fn system_prompt(&self, base: &str) -> String {
+        if self.attachments.is_empty() {
+            return base.to_string();
+        }
+
+        let mut prompt = base.to_string();
+        prompt.push_str(
+            "\n\nThe user attached the reference documents below (style guide, glossary). \
+             Follow their rules and terminology when correcting the text. \
+             Never copy the documents into the output.",
+        );
+
+        let mut remaining = MAX_CONTEXT_CHARS;
+        for attachment in &self.attachments {
+            if remaining == 0 {
+                break;
+            }
+            let content = truncate_chars(&attachment.content, remaining);
+            remaining -= content.chars().count();
+
+            prompt.push_str(&format!("\n\n<document name=\"{}\">\n{}", attachment.name, content));
+            if attachment.truncated || content.len() < attachment.content.len() {
+                prompt.push_str(TRUNCATION_MARKER);
+            }
+            prompt.push_str("\n</document>");
+        }
+
+        prompt
+    }
+}
+
+fn truncate_chars(text: &str, max_chars: usize) -> &str {
+    match text.char_indices().nth(max_chars) {
+        Some((idx, _)) => &text[..idx],
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_empty_context_keeps_prompt() {
+        let context = SessionContext::new();
+        assert_eq!(context.system_prompt("BASE"), "BASE");
+    }
+
+    #[test]
+    fn test_attachment_is_injected() {
+        let mut context = SessionContext::new();
+        context.attach(Attachment::new("glosariusz.md", "faktura → rachunek"));
+
+        let prompt = context.system_prompt("BASE");
+        assert!(prompt.starts_with("BASE"));
+        assert!(prompt.contains("<document name=\"glosariusz.md\">\nfaktura → rachunek\n</document>"));
+    }
+
+    #[test]
+    fn test_attachment_is_truncated() {
+        let attachment = Attachment::new("duzy.txt", &"ż".repeat(MAX_ATTACHMENT_CHARS + 10));
+        assert!(attachment.truncated);
+        assert_eq!(attachment.content.chars().count(), MAX_ATTACHMENT_CHARS);
+    }
+
+    #[test]
+    fn test_total_context_is_limited() {
+        let mut context = SessionContext::new();
+        for i in 0..3 {
+            context.attach(Attachment::new(&format!("{}.txt", i), &"ą".repeat(MAX_ATTACHMENT_CHARS - 1)));
+        }
+
+        let prompt = context.system_prompt("");
+        assert!(prompt.matches('ą').count() <= MAX_CONTEXT_CHARS);
+        assert!(prompt.contains(TRUNCATION_MARKER));
+    }
+
+    #[test]
+    fn test_reattach_replaces_same_name() {
+        let mut context = SessionContext::new();
+        context.attach(Attachment::new("styl.md", "stara"));
+        context.attach(Attachment::new("styl.md", "nowa"));
+
+        assert_eq!(context.attachments().len(), 1);
+        assert_eq!(context.attachments()[0].content, "nowa");
+    }
+
+    #[test]
+    fn test_attach_file_sets_working_dir() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("styleguide.md");
+        fs::write(&path, "Piszemy \"e-mail\", nie \"mail\".").unwrap();
+
+        let mut context = SessionContext::new();
+        context.attach_file(&path).unwrap();
+
+        assert_eq!(context.working_dir(), Some(dir.path()));
+        assert_eq!(context.attachments()[0].name, "styleguide.md");
+
+        context.clear();
+        assert!(context.is_empty());
+    }
+
+    #[test]
+    fn test_attach_rejects_binary_and_empty() {
+        let dir = TempDir::new().unwrap();
+        let binary = dir.path().join("obraz.png");
+        fs::write(&binary, [0xff, 0xfe, 0x00, 0x89]).unwrap();
+        let empty = dir.path().join("pusty.txt");
+        fs::write(&empty, "  \n").unwrap();
+
+        let mut context = SessionContext::new();
+        assert!(context.attach_file(&binary).is_err());
+        assert!(context.attach_file(&empty).is_err());
+        assert!(context.is_empty());
+    }
+}
@@ -0,0 +1,133 @@
+//! Skróty klawiszowe okna głównego
+//!
+//! Każdy skrót to akcja okna (`win.*`) z domyślnym akceleratorem GTK.
+//! Użytkownik może go zmienić w ustawieniach; w konfiguracji zapisywane są
+//! tylko akceleratory różne od domyślnych, a pusty tekst wyłącza skrót.
+
+use std::collections::BTreeMap;
+
+/// Akcja okna głównego dostępna ze skrótu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShortcutAction {
+    /// Nazwa akcji bez prefiksu `win.`
+    pub name: &'static str,
+    pub label: &'static str,
+    pub default_accel: &'static str,
+}
+
+pub const ACTIONS: &[ShortcutAction] = &[
+    ShortcutAction { name: "paste", label: "Wklej tekst i popraw", default_accel: "<Control><Shift>v" },
+    ShortcutAction { name: "use-result-1", label: "Użyj wyniku panelu 1", default_accel: "<Alt>1" },
+    ShortcutAction { name: "use-result-2", label: "Użyj wyniku panelu 2", default_accel: "<Alt>2" },
+    ShortcutAction { name: "use-result-3", label: "Użyj wyniku panelu 3", default_accel: "<Alt>3" },
+    ShortcutAction { name: "use-result-4", label: "Użyj wyniku panelu 4", default_accel: "<Alt>4" },
+    ShortcutAction { name: "cancel-all", label: "Anuluj wszystko", default_accel: "<Control>period" },
+    ShortcutAction { name: "repeat-last", label: "Powtórz ostatni tekst", default_accel: "<Control><Shift>r" },
+    ShortcutAction { name: "merge", label: "Złóż wynik", default_accel: "<Control>m" },
+    ShortcutAction { name: "review", label: "Przegląd zmian", default_accel: "<Control>r" },
+    ShortcutAction { name: "settings", label: "Ustawienia", default_accel: "<Control>comma" },
+    ShortcutAction { name: "hide", label: "Minimalizuj do zasobnika", default_accel: "<Control>w" },
+];
+
+/// Pełna nazwa akcji dla `set_accels_for_action`
+pub fn detailed_name(action: &ShortcutAction) -> String {
+    format!("win.{}", action.name)
+}
+
+/// Akcelerator akcji po uwzględnieniu zmian użytkownika; `None` gdy skrót jest wyłączony
+pub fn effective_accel<'a>(action: &'a ShortcutAction, overrides: &'a BTreeMap<String, String>) -> Option<&'a str> {
+    let accel = overrides
+        .get(action.name)
+        .map(|a| a.trim())
+        .unwrap_or(action.default_accel);
+    (!accel.is_empty()).then_some(accel)
+}
+
+/// Zmiany względem domyślnych akceleratorów do zapisania w konfiguracji
+pub fn overrides_from(accels: &[(&str, String)]) -> BTreeMap<String, String> {
+    accels
+        .iter()
+        .filter_map(|(name, accel)| {
+            let action = ACTIONS.iter().find(|a| a.name == *name)?;
+            let accel = accel.trim();
+            (accel != action.default_accel).then(|| (name.to_string(), accel.to_string()))
+        })
+        .collect()
+}
+
+/// Pary akcji z tym samym akceleratorem
+pub fn conflicts<'a>(accels: &[(&'a str, String)]) -> Vec<(&'a str, &'a str)> {
+    let mut found = Vec::new();
+    for (i, (first, accel)) in accels.iter().enumerate() {
+        let accel = accel.trim();
+        if accel.is_empty() {
+            continue;
+        }
+        for (second, other) in &accels[i + 1..] {
+            if other.trim().eq_ignore_ascii_case(accel) {
+                found.push((*first, *second));
+            }
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_action_names_are_unique() {
+        for (i, action) in ACTIONS.iter().enumerate() {
+            assert!(ACTIONS[i + 1..].iter().all(|a| a.name != action.name));
+        }
+        assert_eq!(detailed_name(&ACTIONS[0]), "win.paste");
+    }
+
+    #[test]
+    fn test_effective_accel() {
+        let merge = ACTIONS.iter().find(|a| a.name == "merge").unwrap();
+        let mut overrides = BTreeMap::new();
+        assert_eq!(effective_accel(merge, &overrides), Some("<Control>m"));
+
+        overrides.insert("merge".to_string(), " <Control><Shift>m ".to_string());
+        assert_eq!(effective_accel(merge, &overrides), Some("<Control><Shift>m"));
+
+        overrides.insert("merge".to_string(), String::new());
+        assert_eq!(effective_accel(merge, &overrides), None);
+    }
+
+    #[test]
+    fn test_overrides_keep_only_changes() {
+        let accels = vec![
+            ("merge", "<Control>m".to_string()),
+            ("review", "<Control>e".to_string()),
+            ("hide", "".to_string()),
+            ("unknown", "<Control>x".to_string()),
+        ];
+        let overrides = overrides_from(&accels);
+        assert_eq!(overrides.len(), 2);
+        assert_eq!(overrides["review"], "<Control>e");
+        assert_eq!(overrides["hide"], "");
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let accels = vec![
+            ("merge", "<Control>m".to_string()),
+            ("review", "<control>M".to_string()),
+            ("hide", "".to_string()),
+            ("settings", "".to_string()),
+        ];
+        assert_eq!(conflicts(&accels), vec![("merge", "review")]);
+    }
+
+    #[test]
+    fn test_default_accels_do_not_conflict() {
+        let accels: Vec<(&str, String)> = ACTIONS
+            .iter()
+            .map(|a| (a.name, a.default_accel.to_string()))
+            .collect();
+        assert!(conflicts(&accels).is_empty());
+    }
+}
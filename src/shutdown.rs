@@ -0,0 +1,170 @@
+//! Porządne zamykanie aplikacji
+//!
+//! Wątki i zadania działające w tle (skrót globalny, sesja portalu, usługa
+//! zasobnika, zapytania do API) rejestrują się tutaj. `run` wywołuje ich
+//! procedury zamknięcia, wysyła sygnał oczekującym pętlom i czeka chwilę na
+//! zakończenie wątków, żeby skrót został wyrejestrowany, a sesja portalu
+//! zamknięta, zanim proces się skończy.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+/// Ile najdłużej czekać na wątki przy wyjściu
+pub const JOIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Wspólny koordynator dla całej aplikacji
+pub static SHUTDOWN: Lazy<Shutdown> = Lazy::new(Shutdown::new);
+
+type Hook = Box<dyn FnOnce() + Send>;
+
+pub struct Shutdown {
+    triggered: AtomicBool,
+    tx: watch::Sender<bool>,
+    hooks: Mutex<Vec<(&'static str, Hook)>>,
+    threads: Mutex<Vec<(&'static str, JoinHandle<()>)>>,
+}
+
+/// Odbiornik sygnału zamknięcia dla pętli działających w tle
+#[derive(Clone)]
+pub struct ShutdownSignal {
+    rx: watch::Receiver<bool>,
+}
+
+impl ShutdownSignal {
+    pub fn is_triggered(&self) -> bool {
+        *self.rx.borrow()
+    }
+
+    /// Czeka na zamknięcie (od razu wraca, jeśli już nastąpiło)
+    pub async fn wait(&mut self) {
+        let _ = self.rx.wait_for(|triggered| *triggered).await;
+    }
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        Self {
+            triggered: AtomicBool::new(false),
+            tx: watch::channel(false).0,
+            hooks: Mutex::new(Vec::new()),
+            threads: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn signal(&self) -> ShutdownSignal {
+        ShutdownSignal { rx: self.tx.subscribe() }
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        self.triggered.load(Ordering::SeqCst)
+    }
+
+    /// Procedura wywoływana przy zamknięciu, w kolejności rejestracji
+    pub fn on_shutdown<F: FnOnce() + Send + 'static>(&self, name: &'static str, hook: F) {
+        self.hooks.lock().unwrap().push((name, Box::new(hook)));
+    }
+
+    /// Wątek, na którego zakończenie `run` czeka
+    pub fn track(&self, name: &'static str, handle: JoinHandle<()>) {
+        self.threads.lock().unwrap().push((name, handle));
+    }
+
+    /// Zamyka wszystko raz; zwraca nazwy wątków, które nie skończyły się w `timeout`
+    pub fn run(&self, timeout: Duration) -> Vec<&'static str> {
+        if self.triggered.swap(true, Ordering::SeqCst) {
+            return Vec::new();
+        }
+        info!("Shutting down");
+
+        let hooks = std::mem::take(&mut *self.hooks.lock().unwrap());
+        for (name, hook) in hooks {
+            info!("Shutdown: {}", name);
+            hook();
+        }
+        self.tx.send_replace(true);
+
+        let deadline = Instant::now() + timeout;
+        let mut threads = std::mem::take(&mut *self.threads.lock().unwrap());
+        while !threads.is_empty() && Instant::now() < deadline {
+            threads.retain(|(_, handle)| !handle.is_finished());
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        let stuck: Vec<&'static str> = threads.into_iter().map(|(name, _)| name).collect();
+        if !stuck.is_empty() {
+            warn!("Shutdown timed out waiting for: {}", stuck.join(", "));
+        }
+        stuck
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_hooks_run_once_in_order() {
+        let shutdown = Shutdown::new();
+        let order = Arc::new(Mutex::new(Vec::new()));
+        for name in ["hotkey", "tray"] {
+            let order = order.clone();
+            shutdown.on_shutdown(name, move || order.lock().unwrap().push(name));
+        }
+
+        assert!(!shutdown.is_triggered());
+        assert!(shutdown.run(JOIN_TIMEOUT).is_empty());
+        assert!(shutdown.run(JOIN_TIMEOUT).is_empty());
+        assert!(shutdown.is_triggered());
+        assert_eq!(*order.lock().unwrap(), vec!["hotkey", "tray"]);
+    }
+
+    #[test]
+    fn test_threads_stop_on_signal() {
+        let shutdown = Shutdown::new();
+        let stopped = Arc::new(AtomicUsize::new(0));
+
+        let signal = shutdown.signal();
+        let stopped_clone = stopped.clone();
+        shutdown.track(
+            "worker",
+            std::thread::spawn(move || {
+                while !signal.is_triggered() {
+                    std::thread::sleep(Duration::from_millis(5));
+                }
+                stopped_clone.fetch_add(1, Ordering::SeqCst);
+            }),
+        );
+        shutdown.track("stuck", std::thread::spawn(|| std::thread::sleep(Duration::from_secs(5))));
+
+        assert_eq!(shutdown.run(Duration::from_millis(200)), vec!["stuck"]);
+        assert_eq!(stopped.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_signal_wait() {
+        let shutdown = Shutdown::new();
+        let mut signal = shutdown.signal();
+        let waiter = tokio::spawn(async move { signal.wait().await });
+
+        shutdown.run(JOIN_TIMEOUT);
+        tokio::time::timeout(Duration::from_secs(1), waiter).await.unwrap().unwrap();
+
+        let mut late = shutdown.signal();
+        late.wait().await;
+        assert!(late.is_triggered());
+    }
+}
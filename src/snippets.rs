@@ -0,0 +1,73 @@
+//! Biblioteka wstawek: gotowe zwroty, powitania i podpisy
+//!
+//! Wstawki są przechowywane w konfiguracji jako `[[snippets]]`. W ustawieniach
+//! edytuje się je jako zwykły tekst: linia `### Nazwa` zaczyna wstawkę,
+//! a kolejne linie (do następnego nagłówka) są jej treścią.
+
+use crate::config::Snippet;
+
+const HEADER: &str = "### ";
+
+/// Tekst edytora ustawień z listy wstawek
+pub fn format_snippets(snippets: &[Snippet]) -> String {
+    snippets
+        .iter()
+        .map(|s| format!("{}{}\n{}", HEADER, s.name.trim(), s.text.trim_end()))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Lista wstawek z tekstu edytora; tekst przed pierwszym nagłówkiem i wstawki bez nazwy są pomijane
+pub fn parse_snippets(text: &str) -> Vec<Snippet> {
+    let mut snippets = Vec::new();
+    let mut current: Option<(String, Vec<&str>)> = None;
+
+    for line in text.lines() {
+        if let Some(name) = line.strip_prefix(HEADER) {
+            snippets.extend(current.take().and_then(finish));
+            current = Some((name.trim().to_string(), Vec::new()));
+        } else if let Some((_, body)) = current.as_mut() {
+            body.push(line);
+        }
+    }
+    snippets.extend(current.and_then(finish));
+    snippets
+}
+
+fn finish((name, body): (String, Vec<&str>)) -> Option<Snippet> {
+    let text = body.join("\n").trim_matches('\n').trim_end().to_string();
+    (!name.is_empty() && !text.is_empty()).then_some(Snippet { name, text })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snippet(name: &str, text: &str) -> Snippet {
+        Snippet {
+            name: name.to_string(),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let snippets = vec![
+            snippet("Podpis", "Pozdrawiam,\nJan Kowalski\n  Dział IT"),
+            snippet("Powitanie", "Dzień dobry,"),
+        ];
+        let text = format_snippets(&snippets);
+        assert_eq!(
+            text,
+            "### Podpis\nPozdrawiam,\nJan Kowalski\n  Dział IT\n\n### Powitanie\nDzień dobry,"
+        );
+        assert_eq!(parse_snippets(&text), snippets);
+    }
+
+    #[test]
+    fn test_parse_skips_invalid_entries() {
+        let text = "luźny tekst\n### \nbez nazwy\n### Pusta\n\n### Ok\n\ntreść\n\n";
+        assert_eq!(parse_snippets(text), vec![snippet("Ok", "treść")]);
+        assert!(parse_snippets("").is_empty());
+    }
+}
@@ -0,0 +1,76 @@
+//! Dźwięk po nadejściu wyników, gdy okno jest schowane
+//!
+//! Odtwarzanie przez `canberra-gtk-play` (libcanberra, motyw dźwiękowy
+//! pulpitu). Gdy narzędzia brak, wywołujący może użyć systemowego sygnału.
+
+use std::process::{Command, Stdio};
+
+use crate::config::Settings;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoundEvent {
+    FirstResult,
+    LastResult,
+}
+
+/// Czy zagrać dźwięk dla zdarzenia; przy widocznym oknie dźwięk jest zbędny
+pub fn should_play(settings: &Settings, event: SoundEvent, window_visible: bool) -> bool {
+    if window_visible {
+        return false;
+    }
+    match event {
+        SoundEvent::FirstResult => settings.sound_first_result,
+        SoundEvent::LastResult => settings.sound_last_result,
+    }
+}
+
+/// Identyfikator dźwięku z motywu freedesktop
+pub fn sound_id(event: SoundEvent) -> &'static str {
+    match event {
+        SoundEvent::FirstResult => "message-new-instant",
+        SoundEvent::LastResult => "complete",
+    }
+}
+
+/// Odtwarza dźwięk w tle; błąd oznacza brak `canberra-gtk-play`.
+/// Proces jest odbierany w osobnym wątku, żeby nie zostawał po nim zombie.
+pub fn play(event: SoundEvent) -> Result<(), String> {
+    let mut child = Command::new("canberra-gtk-play")
+        .args(["-i", sound_id(event), "-d", "poprawiacz-tekstu-rs"])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("canberra-gtk-play unavailable: {}", e))?;
+    std::thread::spawn(move || {
+        if let Err(e) = child.wait() {
+            tracing::warn!("Waiting for canberra-gtk-play failed: {}", e);
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn test_should_play_respects_toggles_and_visibility() {
+        let mut settings = Config::default().settings;
+        assert!(!should_play(&settings, SoundEvent::FirstResult, false));
+        assert!(!should_play(&settings, SoundEvent::LastResult, false));
+
+        settings.sound_first_result = true;
+        assert!(should_play(&settings, SoundEvent::FirstResult, false));
+        assert!(!should_play(&settings, SoundEvent::FirstResult, true));
+        assert!(!should_play(&settings, SoundEvent::LastResult, false));
+
+        settings.sound_last_result = true;
+        assert!(should_play(&settings, SoundEvent::LastResult, false));
+    }
+
+    #[test]
+    fn test_sound_ids_differ() {
+        assert_ne!(sound_id(SoundEvent::FirstResult), sound_id(SoundEvent::LastResult));
+    }
+}
@@ -0,0 +1,164 @@
+//! Wynik w formacie JSON dla automatyzacji
+//!
+//! Zamiast samego tekstu model zwraca obiekt `{corrected, changes[], confidence}`,
+//! który narzędzia mogą przetwarzać bez porównywania tekstów. Dostawcy z trybem
+//! JSON (OpenAI, DeepSeek, Mistral, Groq, Gemini) dostają go w żądaniu, Anthropic tylko w prompcie.
+
+use serde::{Deserialize, Serialize};
+
+use crate::diff::{compute_diff, DiffChange};
+
+/// Dopisywane do system promptu, gdy wynik ma być obiektem JSON
+pub const JSON_FORMAT_INSTRUCTION: &str = "Respond with a single JSON object and nothing else, using this schema: \
+{\"corrected\": string (the full corrected text), \
+\"changes\": [{\"original\": string, \"corrected\": string, \"reason\": string}] (every edit you made, in order), \
+\"confidence\": number between 0 and 1 (how sure you are the correction is right)}.";
+
+/// Jedna poprawka: fragment przed i po zmianie
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Change {
+    pub original: String,
+    pub corrected: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StructuredResult {
+    pub corrected: String,
+    #[serde(default)]
+    pub changes: Vec<Change>,
+    #[serde(default = "default_confidence")]
+    pub confidence: f64,
+}
+
+fn default_confidence() -> f64 {
+    1.0
+}
+
+/// System prompt z dopisanym opisem formatu JSON
+pub fn system_prompt(base: &str) -> String {
+    format!("{}\n\n{}", base.trim_end(), JSON_FORMAT_INSTRUCTION)
+}
+
+/// Odczytuje odpowiedź modelu; znosi blok kodu wokół JSON-a
+pub fn parse(response: &str) -> Result<StructuredResult, String> {
+    let json = strip_code_fence(response.trim());
+    let mut result: StructuredResult =
+        serde_json::from_str(json).map_err(|e| format!("Invalid JSON response: {}", e))?;
+
+    if !result.confidence.is_finite() {
+        result.confidence = default_confidence();
+    }
+    result.confidence = result.confidence.clamp(0.0, 1.0);
+    Ok(result)
+}
+
+fn strip_code_fence(text: &str) -> &str {
+    let Some(rest) = text.strip_prefix("```") else {
+        return text;
+    };
+    // Pomija oznaczenie języka (```json)
+    let body = rest.split_once('\n').map_or(rest, |(_, body)| body);
+    body.trim_end().strip_suffix("```").unwrap_or(body).trim()
+}
+
+/// Wynik złożony z różnic słów, gdy model zwrócił sam tekst (np. tryb demo)
+pub fn from_diff(original: &str, corrected: &str, confidence: f64) -> StructuredResult {
+    let mut changes = Vec::new();
+    let mut removed = String::new();
+    let mut added = String::new();
+
+    for change in compute_diff(original, corrected) {
+        match change {
+            DiffChange::Delete(text) => removed.push_str(&text),
+            DiffChange::Insert(text) => added.push_str(&text),
+            DiffChange::Equal(_) => flush_change(&mut changes, &mut removed, &mut added),
+        }
+    }
+    flush_change(&mut changes, &mut removed, &mut added);
+
+    StructuredResult {
+        corrected: corrected.to_string(),
+        changes,
+        confidence,
+    }
+}
+
+fn flush_change(changes: &mut Vec<Change>, removed: &mut String, added: &mut String) {
+    if !removed.trim().is_empty() || !added.trim().is_empty() {
+        changes.push(Change {
+            original: removed.trim().to_string(),
+            corrected: added.trim().to_string(),
+            reason: String::new(),
+        });
+    }
+    removed.clear();
+    added.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_full_response() {
+        let response = r#"{"corrected": "Ala ma kota.", "changes": [{"original": "kta", "corrected": "kota", "reason": "literówka"}], "confidence": 0.92}"#;
+        let result = parse(response).unwrap();
+
+        assert_eq!(result.corrected, "Ala ma kota.");
+        assert_eq!(result.changes.len(), 1);
+        assert_eq!(result.changes[0].reason, "literówka");
+        assert!((result.confidence - 0.92).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_parse_tolerates_code_fence_and_clamps_confidence() {
+        let response = "```json\n{\"corrected\": \"Tekst.\", \"confidence\": 3}\n```";
+        let result = parse(response).unwrap();
+
+        assert_eq!(result.corrected, "Tekst.");
+        assert!(result.changes.is_empty());
+        assert_eq!(result.confidence, 1.0);
+    }
+
+    #[test]
+    fn test_parse_rejects_plain_text() {
+        assert!(parse("Ala ma kota.").is_err());
+        assert!(parse(r#"{"changes": []}"#).is_err());
+    }
+
+    #[test]
+    fn test_from_diff_collects_changed_runs() {
+        let result = from_diff("ala ma kta i psa", "Ala ma kota i psa.", 1.0);
+
+        assert_eq!(result.corrected, "Ala ma kota i psa.");
+        assert_eq!(
+            result.changes,
+            vec![
+                Change {
+                    original: "ala".to_string(),
+                    corrected: "Ala".to_string(),
+                    reason: String::new(),
+                },
+                Change {
+                    original: "kta".to_string(),
+                    corrected: "kota".to_string(),
+                    reason: String::new(),
+                },
+                Change {
+                    original: "psa".to_string(),
+                    corrected: "psa.".to_string(),
+                    reason: String::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_serialized_change_omits_empty_reason() {
+        let json = serde_json::to_value(from_diff("kta", "kota", 0.5)).unwrap();
+        assert_eq!(json["changes"][0], serde_json::json!({"original": "kta", "corrected": "kota"}));
+        assert_eq!(json["confidence"], 0.5);
+    }
+}
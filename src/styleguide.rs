@@ -0,0 +1,190 @@
+//! Tryb egzekwowania firmowego style guide'u
+//!
+//! Reguły z sekcji `[styleguide]` konfiguracji są doklejane do system promptu,
+//! a gotowe wyniki przechodzą lokalne sprawdzenie, bo modele nie zawsze
+//! stosują się do instrukcji.
+
+use regex::{Regex, RegexBuilder};
+
+use crate::config::StyleGuide;
+
+/// Pojedyncze naruszenie reguły w wyniku
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    /// Fragment tekstu, który złamał regułę
+    pub found: String,
+    pub message: String,
+}
+
+/// Reguły w postaci do wklejenia do system promptu; `None` gdy tryb jest wyłączony
+pub fn prompt_section(guide: &StyleGuide) -> Option<String> {
+    if !guide.is_active() {
+        return None;
+    }
+
+    let mut section = String::from(
+        "\n\nCOMPANY STYLE GUIDE (mandatory, applies to the corrected text):",
+    );
+    for phrase in &guide.forbidden_phrases {
+        section.push_str(&format!("\n- Never use the phrase \"{}\".", phrase));
+    }
+    for (wrong, preferred) in &guide.preferred_spellings {
+        section.push_str(&format!("\n- Write \"{}\" instead of \"{}\".", preferred, wrong));
+    }
+    for term in &guide.capitalization {
+        section.push_str(&format!("\n- Always write \"{}\" with exactly this capitalization.", term));
+    }
+
+    Some(section)
+}
+
+/// System prompt uzupełniony o reguły style guide'u
+pub fn apply_to_prompt(guide: &StyleGuide, base: &str) -> String {
+    match prompt_section(guide) {
+        Some(section) => format!("{}{}", base, section),
+        None => base.to_string(),
+    }
+}
+
+/// Sprawdza wynik pod kątem reguł; zwraca listę naruszeń bez powtórzeń
+pub fn check(guide: &StyleGuide, text: &str) -> Vec<Violation> {
+    let mut violations: Vec<Violation> = Vec::new();
+    if !guide.is_active() {
+        return violations;
+    }
+
+    let mut push = |found: &str, message: String| {
+        if !violations.iter().any(|v| v.message == message) {
+            violations.push(Violation {
+                found: found.to_string(),
+                message,
+            });
+        }
+    };
+
+    for phrase in &guide.forbidden_phrases {
+        if let Some(m) = phrase_regex(phrase).and_then(|re| re.find(text)) {
+            push(m.as_str(), format!("Zakazany zwrot „{}”", m.as_str()));
+        }
+    }
+
+    for (wrong, preferred) in &guide.preferred_spellings {
+        let Some(wrong_re) = phrase_regex(wrong) else {
+            continue;
+        };
+        // "mail" nie może być zgłaszany wewnątrz poprawnego "e-mail"
+        let allowed: Vec<_> = phrase_regex(preferred)
+            .map(|re| re.find_iter(text).map(|m| m.range()).collect())
+            .unwrap_or_default();
+
+        let hit = wrong_re.find_iter(text).find(|m| {
+            !allowed
+                .iter()
+                .any(|r| r.start <= m.start() && m.end() <= r.end)
+        });
+        if let Some(m) = hit {
+            push(m.as_str(), format!("„{}” zamiast „{}”", m.as_str(), preferred));
+        }
+    }
+
+    for term in &guide.capitalization {
+        let hit = phrase_regex(term).and_then(|re| re.find_iter(text).find(|m| m.as_str() != term));
+        if let Some(m) = hit {
+            push(m.as_str(), format!("„{}” powinno być zapisane jako „{}”", m.as_str(), term));
+        }
+    }
+
+    violations
+}
+
+/// Dopasowanie całych słów bez rozróżniania wielkości liter; granica słowa jest
+/// sprawdzana tylko po stronie, na której zwrot zaczyna się lub kończy literą
+/// albo cyfrą ("C++", "(sic)", "np.")
+fn phrase_regex(phrase: &str) -> Option<Regex> {
+    let phrase = phrase.trim();
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let first = phrase.chars().next()?;
+    let last = phrase.chars().next_back()?;
+    let edge = |c: char| if is_word(c) { r"\b" } else { "" };
+    RegexBuilder::new(&format!("{}{}{}", edge(first), regex::escape(phrase), edge(last)))
+        .case_insensitive(true)
+        .build()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guide() -> StyleGuide {
+        let mut guide = StyleGuide {
+            enabled: true,
+            forbidden_phrases: vec!["na dzień dzisiejszy".to_string()],
+            capitalization: vec!["GitHub".to_string()],
+            ..Default::default()
+        };
+        guide
+            .preferred_spellings
+            .insert("mail".to_string(), "e-mail".to_string());
+        guide
+    }
+
+    #[test]
+    fn test_disabled_guide_is_ignored() {
+        let mut guide = guide();
+        guide.enabled = false;
+
+        assert_eq!(prompt_section(&guide), None);
+        assert_eq!(apply_to_prompt(&guide, "BASE"), "BASE");
+        assert!(check(&guide, "Na dzień dzisiejszy wysyłam mail.").is_empty());
+    }
+
+    #[test]
+    fn test_prompt_contains_all_rules() {
+        let prompt = apply_to_prompt(&guide(), "BASE");
+
+        assert!(prompt.starts_with("BASE"));
+        assert!(prompt.contains("\"na dzień dzisiejszy\""));
+        assert!(prompt.contains("Write \"e-mail\" instead of \"mail\""));
+        assert!(prompt.contains("\"GitHub\""));
+    }
+
+    #[test]
+    fn test_check_flags_violations() {
+        let violations = check(&guide(), "Na dzień dzisiejszy wysyłam mail z repozytorium na github.");
+        let found: Vec<_> = violations.iter().map(|v| v.found.as_str()).collect();
+
+        assert_eq!(found, vec!["Na dzień dzisiejszy", "mail", "github"]);
+    }
+
+    #[test]
+    fn test_check_accepts_compliant_text() {
+        assert!(check(&guide(), "Dziś wysyłam e-mail z repozytorium na GitHub.").is_empty());
+    }
+
+    #[test]
+    fn test_check_requires_whole_words() {
+        assert!(check(&guide(), "Mailingowa lista").is_empty());
+    }
+
+    #[test]
+    fn test_check_phrases_with_punctuation_at_edges() {
+        let guide = StyleGuide {
+            enabled: true,
+            forbidden_phrases: vec!["(sic)".to_string(), "np.".to_string()],
+            capitalization: vec!["C++".to_string()],
+            ..Default::default()
+        };
+        let violations = check(&guide, "Kod w c++ (sic) działa, np. tak.");
+        let found: Vec<_> = violations.iter().map(|v| v.found.as_str()).collect();
+        assert_eq!(found, vec!["(sic)", "np.", "c++"]);
+
+        // Granica słowa nadal obowiązuje po stronie litery
+        assert!(check(&guide, "Tanp. abc++").is_empty());
+    }
+
+    #[test]
+    fn test_check_reports_each_rule_once() {
+        assert_eq!(check(&guide(), "mail, mail i jeszcze mail").len(), 1);
+    }
+}
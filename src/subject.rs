@@ -0,0 +1,99 @@
+//! Propozycje tematu maila
+//!
+//! Poprawiona treść maila trafia jeszcze raz do wybranego dostawcy z prośbą
+//! o trzy tematy, po jednym w wierszu. Modele lubią je numerować, ujmować
+//! w cudzysłów albo poprzedzać słowem „Temat:”, więc odpowiedź jest tu
+//! sprowadzana do samych tematów.
+
+/// Ile tematów proponować
+pub const COUNT: usize = 3;
+
+pub const SYSTEM_PROMPT: &str = "You write concise, specific email subject lines. \
+Reply in the language of the email. Output only the subject lines, one per line, without numbering, quotes or comments.";
+
+pub fn instruction() -> String {
+    format!("Suggest {} alternative subject lines for this email:", COUNT)
+}
+
+/// Wiersz odpowiedzi bez numeracji, punktorów, cudzysłowów i przedrostka „Temat:”
+fn clean_line(line: &str) -> &str {
+    let mut line = line.trim();
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits > 0 && line[digits..].starts_with(['.', ')', ':']) {
+        line = &line[digits + 1..];
+    }
+    line = line.trim_start_matches(['-', '*', '•', ' ']);
+    for prefix in ["Temat:", "Subject:", "Betreff:"] {
+        if line.get(..prefix.len()).is_some_and(|p| p.eq_ignore_ascii_case(prefix)) {
+            line = &line[prefix.len()..];
+        }
+    }
+    line.trim()
+        .trim_matches(['"', '\'', '„', '”', '“', '«', '»', '*'])
+        .trim()
+}
+
+/// Do `COUNT` różnych tematów z odpowiedzi modelu
+pub fn parse(response: &str) -> Vec<String> {
+    let mut subjects: Vec<String> = Vec::new();
+    for line in response.lines().map(clean_line) {
+        // Wstęp w rodzaju "Oto propozycje:" to nie temat
+        if !line.is_empty() && !line.ends_with(':') && !subjects.iter().any(|s| s.eq_ignore_ascii_case(line)) {
+            subjects.push(line.to_string());
+        }
+        if subjects.len() == COUNT {
+            break;
+        }
+    }
+    subjects
+}
+
+/// Tematy bez sieci (tryb demo): początek pierwszego zdania w trzech wariantach
+pub fn demo_suggestions(body: &str) -> Vec<String> {
+    let first = body
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty() && !l.ends_with(','))
+        .unwrap_or_default();
+    let sentence = first.split(['.', '!', '?']).next().unwrap_or_default();
+    let words: Vec<&str> = sentence.split_whitespace().take(6).collect();
+    if words.is_empty() {
+        return Vec::new();
+    }
+    let short = words.join(" ");
+    vec![short.clone(), format!("Pytanie: {}", short), format!("W sprawie: {}", short)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let response = "Oto propozycje:\n\n1. \"Spotkanie w piątek\"\n2) Temat: Zmiana terminu spotkania\n- **Piątek: potwierdzenie**\n4. Czwarty";
+        assert_eq!(
+            parse(response),
+            vec!["Spotkanie w piątek", "Zmiana terminu spotkania", "Piątek: potwierdzenie"]
+        );
+        assert_eq!(parse("A\na\n\nB"), vec!["A", "B"]);
+        assert_eq!(clean_line("2024 budżet"), "2024 budżet");
+    }
+
+    #[test]
+    fn test_clean_line_with_non_ascii_start() {
+        assert_eq!(clean_line("Łódź: nowe biuro"), "Łódź: nowe biuro");
+        assert_eq!(clean_line("1. Żółw w ogrodzie"), "Żółw w ogrodzie");
+        assert_eq!(clean_line("Żółwie"), "Żółwie");
+        assert_eq!(clean_line("TEMAT: Łódź"), "Łódź");
+    }
+
+    #[test]
+    fn test_demo_suggestions() {
+        let body = "Dzień dobry,\n\nprzesyłam raport za marzec. Proszę o uwagi.";
+        assert_eq!(
+            demo_suggestions(body),
+            vec!["przesyłam raport za marzec", "Pytanie: przesyłam raport za marzec", "W sprawie: przesyłam raport za marzec"]
+        );
+        assert!(demo_suggestions("  \n").is_empty());
+    }
+}
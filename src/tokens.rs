@@ -0,0 +1,171 @@
+//! Lokalne liczenie tokenów promptu, bez wywołań API.
+//!
+//! Modele OpenAI są liczone ich własnym tokenizerem z tiktoken. Pozostali
+//! dostawcy nie udostępniają tokenizera, więc dla nich liczba z o200k_base
+//! jest tylko przybliżeniem. Na tej podstawie szacowany jest koszt i sprawdzane,
+//! czy prompt zmieści się w oknie kontekstu modelu.
+
+use crate::api::Provider;
+use tiktoken_rs::tokenizer::{get_tokenizer, Tokenizer};
+use tiktoken_rs::CoreBPE;
+
+/// Liczba tokenów; `exact` tylko gdy policzył ją tokenizer modelu
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TokenCount {
+    pub tokens: usize,
+    pub exact: bool,
+}
+
+impl TokenCount {
+    /// Np. "1234 tokeny" albo "≈1234 tokenów" dla przybliżenia
+    pub fn label(&self) -> String {
+        let prefix = if self.exact { "" } else { "≈" };
+        format!("{}{} {}", prefix, self.tokens, tokens_word(self.tokens))
+    }
+}
+
+impl std::ops::Add for TokenCount {
+    type Output = TokenCount;
+
+    fn add(self, other: TokenCount) -> TokenCount {
+        TokenCount {
+            tokens: self.tokens + other.tokens,
+            exact: self.exact && other.exact,
+        }
+    }
+}
+
+/// Odmiana słowa "token" po liczebniku
+pub fn tokens_word(n: usize) -> &'static str {
+    match (n % 10, n % 100) {
+        _ if n == 1 => "token",
+        (2..=4, rem) if !(12..=14).contains(&rem) => "tokeny",
+        _ => "tokenów",
+    }
+}
+
+fn bpe(tokenizer: Tokenizer) -> &'static CoreBPE {
+    use tiktoken_rs::*;
+    match tokenizer {
+        Tokenizer::O200kBase => o200k_base_singleton(),
+        Tokenizer::Cl100kBase => cl100k_base_singleton(),
+        Tokenizer::P50kBase => p50k_base_singleton(),
+        Tokenizer::P50kEdit => p50k_edit_singleton(),
+        Tokenizer::R50kBase | Tokenizer::Gpt2 => r50k_base_singleton(),
+    }
+}
+
+/// Tokeny tekstu dla modelu `model` dostawcy `provider`
+pub fn count(provider: Provider, model: &str, text: &str) -> TokenCount {
+    let tokenizer = match provider {
+        Provider::OpenAI => get_tokenizer(model),
+        _ => None,
+    };
+    TokenCount {
+        tokens: bpe(tokenizer.unwrap_or(Tokenizer::O200kBase)).encode_ordinary(text).len(),
+        exact: tokenizer.is_some(),
+    }
+}
+
+/// Tokeny promptu systemowego i wiadomości użytkownika razem
+pub fn count_prompt(provider: Provider, model: &str, system: &str, message: &str) -> TokenCount {
+    count(provider, model, system) + count(provider, model, message)
+}
+
+/// Okna kontekstu nowszych modeli, których nie zna tiktoken (pierwszy pasujący prefiks)
+const CONTEXT_SIZES: &[(Provider, &str, usize)] = &[
+    (Provider::OpenAI, "gpt-5", 400_000),
+    (Provider::OpenAI, "gpt-4.1", 1_047_576),
+    (Provider::OpenAI, "o1", 200_000),
+    (Provider::OpenAI, "o3", 200_000),
+    (Provider::OpenAI, "o4", 200_000),
+    (Provider::Gemini, "gemini-1.5-pro", 2_097_152),
+    (Provider::Mistral, "mistral-large", 128_000),
+    (Provider::Mistral, "mistral-medium", 128_000),
+    (Provider::Mistral, "mistral-small", 128_000),
+    (Provider::Mistral, "codestral", 256_000),
+    (Provider::Groq, "llama-3.1", 131_072),
+    (Provider::Groq, "llama-3.3", 131_072),
+];
+
+/// Okno kontekstu modelu w tokenach; dla nieznanych modeli ostrożna wartość dostawcy
+pub fn context_size(provider: Provider, model: &str) -> usize {
+    if let Some(&(_, _, size)) = CONTEXT_SIZES
+        .iter()
+        .find(|(p, prefix, _)| *p == provider && model.starts_with(prefix))
+    {
+        return size;
+    }
+    match provider {
+        Provider::OpenAI => tiktoken_rs::model::get_context_size(model),
+        Provider::Anthropic => 200_000,
+        Provider::Gemini => 1_048_576,
+        Provider::DeepSeek => 128_000,
+        Provider::Mistral => 32_000,
+        Provider::Groq => 8_192,
+    }
+}
+
+/// Okno kontekstu, gdy prompt razem ze spodziewaną odpowiedzią się w nim nie mieści
+pub fn exceeds_context(provider: Provider, model: &str, prompt: TokenCount, reply: usize) -> Option<usize> {
+    let limit = context_size(provider, model);
+    (prompt.tokens + reply > limit).then_some(limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_openai_is_exact() {
+        let hello = count(Provider::OpenAI, "gpt-4o", "Hello world");
+        assert_eq!(hello, TokenCount { tokens: 2, exact: true });
+        assert_eq!(count(Provider::OpenAI, "gpt-4o", "").tokens, 0);
+
+        let polish = "Zażółć gęślą jaźń, proszę poprawić ten tekst.";
+        let gemini = count(Provider::Gemini, "gemini-2.5-flash", polish);
+        assert!(!gemini.exact);
+        assert!(gemini.tokens > 5 && gemini.tokens < polish.chars().count());
+        assert!(!count(Provider::OpenAI, "unknown-model", polish).exact);
+    }
+
+    #[test]
+    fn test_count_prompt_sums_parts() {
+        let system = count(Provider::OpenAI, "gpt-4o", "Jesteś korektorem.");
+        let message = count(Provider::OpenAI, "gpt-4o", "Popraw: ala ma kota");
+        let total = count_prompt(Provider::OpenAI, "gpt-4o", "Jesteś korektorem.", "Popraw: ala ma kota");
+        assert_eq!(total.tokens, system.tokens + message.tokens);
+        assert!(total.exact);
+        assert!(!count_prompt(Provider::Anthropic, "claude", "a", "b").exact);
+    }
+
+    #[test]
+    fn test_context_size() {
+        assert_eq!(context_size(Provider::OpenAI, "gpt-5-mini"), 400_000);
+        assert_eq!(context_size(Provider::OpenAI, "gpt-4o-mini"), 128_000);
+        assert_eq!(context_size(Provider::OpenAI, "gpt-4"), 8192);
+        assert_eq!(context_size(Provider::Anthropic, "claude-3-7-sonnet-latest"), 200_000);
+        assert_eq!(context_size(Provider::Mistral, "mistral-small-latest"), 128_000);
+        assert_eq!(context_size(Provider::Mistral, "open-mistral-7b"), 32_000);
+        assert_eq!(context_size(Provider::Groq, "llama-3.3-70b-versatile"), 131_072);
+        assert_eq!(context_size(Provider::Groq, "gemma2-9b-it"), 8_192);
+    }
+
+    #[test]
+    fn test_exceeds_context() {
+        let prompt = TokenCount { tokens: 6000, exact: true };
+        assert_eq!(exceeds_context(Provider::OpenAI, "gpt-4", prompt, 1000), None);
+        assert_eq!(exceeds_context(Provider::OpenAI, "gpt-4", prompt, 3000), Some(8192));
+        assert_eq!(exceeds_context(Provider::Groq, "gemma2-9b-it", prompt, 3000), Some(8_192));
+        assert_eq!(exceeds_context(Provider::Anthropic, "claude-3-7-sonnet-latest", prompt, 3000), None);
+    }
+
+    #[test]
+    fn test_label() {
+        assert_eq!(TokenCount { tokens: 1, exact: true }.label(), "1 token");
+        assert_eq!(TokenCount { tokens: 3, exact: true }.label(), "3 tokeny");
+        assert_eq!(TokenCount { tokens: 13, exact: false }.label(), "≈13 tokenów");
+        assert_eq!(TokenCount { tokens: 22, exact: true }.label(), "22 tokeny");
+        assert_eq!(TokenCount { tokens: 105, exact: true }.label(), "105 tokenów");
+    }
+}
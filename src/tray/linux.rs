@@ -0,0 +1,110 @@
+use std::sync::mpsc::Receiver;
+use tracing::{error, info};
+
+use ksni::{Tray, TrayService};
+
+use super::{MenuEntry, MenuState, TrayCommand, TrayEvent};
+
+struct PoprawiaczTray {
+    tx: async_channel::Sender<TrayEvent>,
+    state: MenuState,
+}
+
+impl Tray for PoprawiaczTray {
+    fn id(&self) -> String {
+        "poprawiacz-tekstu-rs".into()
+    }
+
+    fn icon_name(&self) -> String {
+        Self::get_icon_path()
+    }
+
+    fn title(&self) -> String {
+        "PoprawiaczTekstuRs".into()
+    }
+
+    fn tool_tip(&self) -> ksni::ToolTip {
+        ksni::ToolTip {
+            title: "PoprawiaczTekstuRs".into(),
+            description: self.state.tooltip.clone(),
+            ..Default::default()
+        }
+    }
+
+    fn menu(&self) -> Vec<ksni::MenuItem<Self>> {
+        self.state.entries().into_iter().map(menu_item).collect()
+    }
+}
+
+fn menu_item(entry: MenuEntry) -> ksni::MenuItem<PoprawiaczTray> {
+    use ksni::menu::*;
+    match entry {
+        MenuEntry::Item(label, event) => StandardItem {
+            label,
+            activate: Box::new(move |tray: &mut PoprawiaczTray| {
+                let _ = tray.tx.try_send(event);
+            }),
+            ..Default::default()
+        }
+        .into(),
+        MenuEntry::Check(label, checked, event) => CheckmarkItem {
+            label,
+            checked,
+            activate: Box::new(move |tray: &mut PoprawiaczTray| {
+                let _ = tray.tx.try_send(event);
+            }),
+            ..Default::default()
+        }
+        .into(),
+        MenuEntry::Submenu(label, items) => SubMenu {
+            label,
+            submenu: items.into_iter().map(menu_item).collect(),
+            ..Default::default()
+        }
+        .into(),
+        MenuEntry::Separator => MenuItem::Separator,
+    }
+}
+
+impl PoprawiaczTray {
+    fn get_icon_path() -> String {
+        if let Ok(exe) = std::env::current_exe() {
+            if let Some(dir) = exe.parent() {
+                let icon = dir.join("assets").join("icon_24.png");
+                if icon.exists() {
+                    return icon.to_string_lossy().to_string();
+                }
+                let icon = dir.join("icon_24.png");
+                if icon.exists() {
+                    return icon.to_string_lossy().to_string();
+                }
+            }
+        }
+        "text-editor".into()
+    }
+}
+
+/// Usługa StatusNotifierItem; działa do polecenia `Shutdown`
+pub fn run(tx: async_channel::Sender<TrayEvent>, commands: Receiver<TrayCommand>) -> Result<(), String> {
+    let service = TrayService::new(PoprawiaczTray {
+        tx,
+        state: MenuState::default(),
+    });
+    let handle = service.handle();
+    let service_thread = std::thread::spawn(move || {
+        if let Err(e) = service.run() {
+            error!("Tray D-Bus service stopped: {}", e);
+        }
+    });
+
+    while let Ok(command) = commands.recv() {
+        if !handle.update(|tray| tray.state.apply(command)) {
+            handle.shutdown();
+            let _ = service_thread.join();
+            info!("Tray service stopped");
+            break;
+        }
+    }
+
+    Ok(())
+}
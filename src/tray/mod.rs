@@ -0,0 +1,353 @@
+//! Ikona w zasobniku systemowym
+//!
+//! Menu i jego stan są wspólne, a usługę wybiera `TrayBackend::detect` przy
+//! starcie: StatusNotifierItem przez D-Bus na Linuksie (`linux.rs`), o ile w
+//! sesji działa StatusNotifierWatcher, albo ikona obszaru powiadomień Windows
+//! (`windows.rs`). Bez usługi `TrayManager::new` zwraca błąd, a okno zamiast
+//! chować się minimalizuje.
+
+use gio::prelude::*;
+use std::collections::VecDeque;
+use std::sync::mpsc::{self, Receiver, Sender};
+use tracing::{error, info};
+
+use crate::shutdown::SHUTDOWN;
+
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "windows")]
+mod windows;
+
+/// Ile ostatnich wyników trzyma podmenu zasobnika
+pub const RECENT_OUTPUTS: usize = 5;
+/// Maksymalna długość etykiety wyniku w menu (znaki)
+const RECENT_LABEL_CHARS: usize = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayEvent {
+    Show,
+    Digest,
+    /// Okno historii poprawek
+    History,
+    /// Ponowne wysłanie tekstu ostatniej sesji
+    RepeatLast,
+    /// Wklejenie wstawki o podanym indeksie
+    Snippet(usize),
+    /// Ponowne skopiowanie ostatniego wyniku o podanym indeksie (0 = najnowszy)
+    RecentOutput(usize),
+    /// Przełączenie trybu szybkiego
+    ToggleQuickMode,
+    Quit,
+}
+
+/// Polecenia wysyłane z UI do usługi zasobnika
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TrayCommand {
+    SetTooltip(String),
+    SetSnippets(Vec<String>),
+    SetRecentOutputs(Vec<String>),
+    SetQuickMode(bool),
+    /// Zatrzymuje usługę zasobnika przy zamykaniu aplikacji
+    Shutdown,
+}
+
+/// Usługa D-Bus, u której rejestrują się ikony StatusNotifierItem; bez niej ikona nigdzie się nie pojawi
+const STATUS_NOTIFIER_WATCHER: &str = "org.kde.StatusNotifierWatcher";
+/// Limit czasu zapytania o właściciela nazwy D-Bus (ms)
+const DBUS_PROBE_TIMEOUT_MS: i32 = 1000;
+
+/// Czy ktoś w sesji D-Bus jest właścicielem nazwy StatusNotifierWatcher
+fn status_notifier_watcher_running() -> Result<bool, String> {
+    let bus = gio::bus_get_sync(gio::BusType::Session, gio::Cancellable::NONE)
+        .map_err(|e| format!("no D-Bus session bus: {}", e))?;
+    let reply = bus
+        .call_sync(
+            Some("org.freedesktop.DBus"),
+            "/org/freedesktop/DBus",
+            "org.freedesktop.DBus",
+            "NameHasOwner",
+            Some(&(STATUS_NOTIFIER_WATCHER,).to_variant()),
+            None,
+            gio::DBusCallFlags::NONE,
+            DBUS_PROBE_TIMEOUT_MS,
+            gio::Cancellable::NONE,
+        )
+        .map_err(|e| format!("NameHasOwner({}) failed: {}", STATUS_NOTIFIER_WATCHER, e))?;
+    reply
+        .get::<(bool,)>()
+        .map(|(running,)| running)
+        .ok_or_else(|| format!("unexpected NameHasOwner reply: {}", reply))
+}
+
+/// Usługa pokazująca ikonę w zasobniku
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayBackend {
+    /// StatusNotifierItem przez D-Bus (KDE, GNOME z rozszerzeniem AppIndicator)
+    StatusNotifier,
+    /// Ikona obszaru powiadomień Windows
+    Windows,
+}
+
+impl TrayBackend {
+    /// Usługa dostępna w bieżącej sesji; błąd mówi, czego brakuje
+    pub fn detect() -> Result<Self, String> {
+        if cfg!(target_os = "windows") {
+            Ok(TrayBackend::Windows)
+        } else if cfg!(target_os = "linux") {
+            if status_notifier_watcher_running()? {
+                Ok(TrayBackend::StatusNotifier)
+            } else {
+                Err(format!("no {} on the session bus", STATUS_NOTIFIER_WATCHER))
+            }
+        } else {
+            Err("no system tray on this platform".to_string())
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TrayBackend::StatusNotifier => "StatusNotifierItem",
+            TrayBackend::Windows => "Windows",
+        }
+    }
+
+    fn run(self, tx: async_channel::Sender<TrayEvent>, commands: Receiver<TrayCommand>) -> Result<(), String> {
+        match self {
+            #[cfg(target_os = "linux")]
+            TrayBackend::StatusNotifier => linux::run(tx, commands),
+            #[cfg(target_os = "windows")]
+            TrayBackend::Windows => windows::run(tx, commands),
+            _ => {
+                let _ = (tx, commands);
+                Err(format!("{} tray is not available on this platform", self.name()))
+            }
+        }
+    }
+}
+
+/// Stan menu zmieniany poleceniami z okna
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct MenuState {
+    tooltip: String,
+    snippets: Vec<String>,
+    recent_outputs: Vec<String>,
+    quick_mode: bool,
+}
+
+/// Pozycja menu niezależna od usługi zasobnika
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum MenuEntry {
+    Item(String, TrayEvent),
+    Check(String, bool, TrayEvent),
+    Submenu(String, Vec<MenuEntry>),
+    Separator,
+}
+
+impl MenuState {
+    /// Nakłada polecenie na stan; `false` oznacza zamknięcie usługi
+    fn apply(&mut self, command: TrayCommand) -> bool {
+        match command {
+            TrayCommand::SetTooltip(text) => self.tooltip = text,
+            TrayCommand::SetSnippets(names) => self.snippets = names,
+            TrayCommand::SetRecentOutputs(labels) => self.recent_outputs = labels,
+            TrayCommand::SetQuickMode(enabled) => self.quick_mode = enabled,
+            TrayCommand::Shutdown => return false,
+        }
+        true
+    }
+
+    /// Pozycje menu; puste podmenu wstawek i ostatnich wyników są pomijane
+    fn entries(&self) -> Vec<MenuEntry> {
+        let mut entries = vec![
+            MenuEntry::Item("Pokaż".into(), TrayEvent::Show),
+            MenuEntry::Item("Powtórz ostatni tekst".into(), TrayEvent::RepeatLast),
+            MenuEntry::Item("Raport nauki".into(), TrayEvent::Digest),
+            MenuEntry::Item("Historia poprawek".into(), TrayEvent::History),
+        ];
+        if !self.snippets.is_empty() {
+            let items = self
+                .snippets
+                .iter()
+                .enumerate()
+                .map(|(i, name)| MenuEntry::Item(name.clone(), TrayEvent::Snippet(i)))
+                .collect();
+            entries.push(MenuEntry::Submenu("Wstawki".into(), items));
+        }
+        if !self.recent_outputs.is_empty() {
+            let items = self
+                .recent_outputs
+                .iter()
+                .enumerate()
+                .map(|(i, label)| MenuEntry::Item(label.clone(), TrayEvent::RecentOutput(i)))
+                .collect();
+            entries.push(MenuEntry::Submenu("Ostatnie wyniki".into(), items));
+        }
+        entries.extend([
+            MenuEntry::Separator,
+            MenuEntry::Check("Tryb szybki".into(), self.quick_mode, TrayEvent::ToggleQuickMode),
+            MenuEntry::Item("Zakończ".into(), TrayEvent::Quit),
+        ]);
+        entries
+    }
+}
+
+pub struct TrayManager {
+    event_rx: async_channel::Receiver<TrayEvent>,
+    #[allow(dead_code)]
+    event_tx: async_channel::Sender<TrayEvent>,
+    command_tx: Sender<TrayCommand>,
+}
+
+/// Tekst postępu sesji do podpowiedzi zasobnika, np. "2/4 gotowe, 12 s"
+pub fn format_progress(completed: u32, total: u32, elapsed_secs: u64) -> String {
+    format!("{}/{} gotowe, {} s", completed, total, elapsed_secs)
+}
+
+/// Ostatnie użyte wyniki, najnowszy pierwszy; niezależne od historii poprawek
+#[derive(Debug, Clone, Default)]
+pub struct RecentOutputs {
+    outputs: VecDeque<String>,
+}
+
+impl RecentOutputs {
+    /// Dodaje wynik na początek; powtórzony tekst przesuwa się na górę
+    pub fn push(&mut self, text: &str) {
+        if text.trim().is_empty() {
+            return;
+        }
+        self.outputs.retain(|t| t != text);
+        self.outputs.push_front(text.to_string());
+        self.outputs.truncate(RECENT_OUTPUTS);
+    }
+
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.outputs.get(index).map(String::as_str)
+    }
+
+    /// Etykiety do podmenu zasobnika
+    pub fn labels(&self) -> Vec<String> {
+        self.outputs.iter().map(|t| recent_label(t)).collect()
+    }
+}
+
+/// Pierwsza linia wyniku skrócona do długości pozycji menu
+pub fn recent_label(text: &str) -> String {
+    let line = text.trim().lines().next().unwrap_or_default();
+    if line.chars().count() <= RECENT_LABEL_CHARS {
+        return line.to_string();
+    }
+    let short: String = line.chars().take(RECENT_LABEL_CHARS - 1).collect();
+    format!("{}…", short.trim_end())
+}
+
+impl TrayManager {
+    pub fn new() -> Result<Self, String> {
+        let backend = TrayBackend::detect()?;
+        let (event_tx, event_rx) = async_channel::unbounded();
+        let (command_tx, command_rx) = mpsc::channel();
+
+        let tx_clone = event_tx.clone();
+
+        let thread = std::thread::spawn(move || {
+            if let Err(e) = backend.run(tx_clone, command_rx) {
+                error!("Tray service error: {}", e);
+            }
+        });
+        let shutdown_tx = command_tx.clone();
+        SHUTDOWN.on_shutdown("tray", move || {
+            let _ = shutdown_tx.send(TrayCommand::Shutdown);
+        });
+        SHUTDOWN.track("tray", thread);
+
+        info!("TrayManager initialized ({})", backend.name());
+
+        Ok(Self {
+            event_rx,
+            event_tx,
+            command_tx,
+        })
+    }
+
+    /// Nazwy wstawek w podmenu "Wstawki" (pusta lista ukrywa podmenu)
+    pub fn set_snippets(&self, names: Vec<String>) {
+        let _ = self.command_tx.send(TrayCommand::SetSnippets(names));
+    }
+
+    /// Etykiety w podmenu "Ostatnie wyniki" (pusta lista ukrywa podmenu)
+    pub fn set_recent_outputs(&self, labels: Vec<String>) {
+        let _ = self.command_tx.send(TrayCommand::SetRecentOutputs(labels));
+    }
+
+    /// Zaznaczenie pozycji "Tryb szybki"
+    pub fn set_quick_mode(&self, enabled: bool) {
+        let _ = self.command_tx.send(TrayCommand::SetQuickMode(enabled));
+    }
+
+    /// Ustawia opis podpowiedzi ikony (pusty tekst przywraca domyślną)
+    pub fn set_tooltip(&self, text: &str) {
+        let _ = self
+            .command_tx
+            .send(TrayCommand::SetTooltip(text.to_string()));
+    }
+
+    pub fn poll_event(&mut self) -> Option<TrayEvent> {
+        self.event_rx.try_recv().ok()
+    }
+
+    /// Kanał zdarzeń do oczekiwania bez odpytywania
+    pub fn events(&self) -> async_channel::Receiver<TrayEvent> {
+        self.event_rx.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_progress() {
+        assert_eq!(format_progress(2, 4, 12), "2/4 gotowe, 12 s");
+        assert_eq!(format_progress(0, 4, 0), "0/4 gotowe, 0 s");
+    }
+
+    #[test]
+    fn test_recent_outputs_keep_last_five() {
+        let mut recent = RecentOutputs::default();
+        for n in 1..=6 {
+            recent.push(&format!("Wynik {}", n));
+        }
+        recent.push("  ");
+        recent.push("Wynik 3");
+
+        assert_eq!(recent.labels(), ["Wynik 3", "Wynik 6", "Wynik 5", "Wynik 4", "Wynik 2"]);
+        assert_eq!(recent.get(1), Some("Wynik 6"));
+        assert_eq!(recent.get(5), None);
+    }
+
+    #[test]
+    fn test_menu_entries_follow_state() {
+        let mut state = MenuState::default();
+        let entries = state.entries();
+        assert_eq!(entries.len(), 7);
+        assert!(!entries.iter().any(|e| matches!(e, MenuEntry::Submenu(..))));
+
+        assert!(state.apply(TrayCommand::SetSnippets(vec!["Podpis".into()])));
+        assert!(state.apply(TrayCommand::SetQuickMode(true)));
+        let entries = state.entries();
+        assert_eq!(
+            entries[4],
+            MenuEntry::Submenu("Wstawki".into(), vec![MenuEntry::Item("Podpis".into(), TrayEvent::Snippet(0))])
+        );
+        assert!(entries.contains(&MenuEntry::Check("Tryb szybki".into(), true, TrayEvent::ToggleQuickMode)));
+        assert!(!state.apply(TrayCommand::Shutdown));
+    }
+
+    #[test]
+    fn test_recent_label_uses_first_line() {
+        assert_eq!(recent_label("\n Krótki wynik\nDruga linia"), "Krótki wynik");
+
+        let label = recent_label(&"żółw ".repeat(20));
+        assert_eq!(label.chars().count(), RECENT_LABEL_CHARS);
+        assert!(label.ends_with("żółw…"));
+    }
+}
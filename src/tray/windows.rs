@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::time::Duration;
+use tracing::info;
+
+use tray_icon::menu::{CheckMenuItem, IsMenuItem, Menu, MenuEvent, MenuId, MenuItem, PredefinedMenuItem, Submenu};
+use tray_icon::{Icon, MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent};
+use windows_sys::Win32::UI::WindowsAndMessaging::{DispatchMessageW, PeekMessageW, TranslateMessage, MSG, PM_REMOVE};
+
+use super::{MenuEntry, MenuState, TrayCommand, TrayEvent};
+
+/// Co ile wątek zasobnika obsługuje komunikaty okna ikony i polecenia z UI
+const PUMP_INTERVAL: Duration = Duration::from_millis(50);
+
+const ICON_PNG: &[u8] = include_bytes!("../../assets/icon_32.png");
+
+fn load_icon() -> Result<Icon, String> {
+    let image = image::load_from_memory(ICON_PNG).map_err(|e| e.to_string())?.into_rgba8();
+    let (width, height) = image.dimensions();
+    Icon::from_rgba(image.into_raw(), width, height).map_err(|e| e.to_string())
+}
+
+/// Menu z `tray-icon` i zdarzenia przypisane identyfikatorom jego pozycji
+fn build_menu(state: &MenuState) -> Result<(Menu, HashMap<MenuId, TrayEvent>), String> {
+    let menu = Menu::new();
+    let mut events = HashMap::new();
+    for entry in state.entries() {
+        let item = menu_item(entry, &mut events)?;
+        menu.append(item.as_ref()).map_err(|e| e.to_string())?;
+    }
+    Ok((menu, events))
+}
+
+fn menu_item(entry: MenuEntry, events: &mut HashMap<MenuId, TrayEvent>) -> Result<Box<dyn IsMenuItem>, String> {
+    let id = MenuId::new(events.len().to_string());
+    Ok(match entry {
+        MenuEntry::Item(label, event) => {
+            events.insert(id.clone(), event);
+            Box::new(MenuItem::with_id(id, label, true, None))
+        }
+        MenuEntry::Check(label, checked, event) => {
+            events.insert(id.clone(), event);
+            Box::new(CheckMenuItem::with_id(id, label, true, checked, None))
+        }
+        MenuEntry::Submenu(label, items) => {
+            let submenu = Submenu::new(label, true);
+            for entry in items {
+                let item = menu_item(entry, events)?;
+                submenu.append(item.as_ref()).map_err(|e| e.to_string())?;
+            }
+            Box::new(submenu)
+        }
+        MenuEntry::Separator => Box::new(PredefinedMenuItem::separator()),
+    })
+}
+
+/// Przekazuje komunikaty okna ikony; bez tego Windows nie dostarcza kliknięć
+fn pump_messages() {
+    // SAFETY: MSG to zwykła struktura C, a wywołania dotyczą kolejki bieżącego wątku
+    unsafe {
+        let mut msg: MSG = std::mem::zeroed();
+        while PeekMessageW(&mut msg, std::ptr::null_mut(), 0, 0, PM_REMOVE) != 0 {
+            TranslateMessage(&msg);
+            DispatchMessageW(&msg);
+        }
+    }
+}
+
+fn set_tooltip(tray: &TrayIcon, text: &str) {
+    let tooltip = match text {
+        "" => "PoprawiaczTekstuRs".to_string(),
+        text => format!("PoprawiaczTekstuRs\n{}", text),
+    };
+    let _ = tray.set_tooltip(Some(tooltip));
+}
+
+/// Ikona obszaru powiadomień; kliknięcie lewym przyciskiem pokazuje okno.
+/// Ikona należy do wątku, który ją utworzył, więc ten wątek obsługuje też jej komunikaty.
+pub fn run(tx: async_channel::Sender<TrayEvent>, commands: Receiver<TrayCommand>) -> Result<(), String> {
+    let mut state = MenuState::default();
+    let (menu, mut events) = build_menu(&state)?;
+    let tray = TrayIconBuilder::new()
+        .with_icon(load_icon()?)
+        .with_tooltip("PoprawiaczTekstuRs")
+        .with_menu(Box::new(menu))
+        .with_menu_on_left_click(false)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    loop {
+        pump_messages();
+
+        while let Ok(event) = MenuEvent::receiver().try_recv() {
+            if let Some(event) = events.get(&event.id) {
+                let _ = tx.try_send(*event);
+            }
+        }
+        while let Ok(event) = TrayIconEvent::receiver().try_recv() {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                let _ = tx.try_send(TrayEvent::Show);
+            }
+        }
+
+        match commands.recv_timeout(PUMP_INTERVAL) {
+            Ok(command) => {
+                let tooltip_only = matches!(command, TrayCommand::SetTooltip(_));
+                if !state.apply(command) {
+                    break;
+                }
+                if tooltip_only {
+                    set_tooltip(&tray, &state.tooltip);
+                } else {
+                    let (menu, ids) = build_menu(&state)?;
+                    tray.set_menu(Some(Box::new(menu)));
+                    events = ids;
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+
+    info!("Tray service stopped");
+    Ok(())
+}
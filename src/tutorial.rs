@@ -0,0 +1,110 @@
+//! Samouczek pierwszego uruchomienia
+//!
+//! Kilka dymków wskazujących elementy okna prowadzi przez jedną przykładową
+//! korektę. Przykład idzie przez dostawcę demonstracyjnego, więc działa bez
+//! kluczy API. Samouczek pojawia się, dopóki nie zostanie ukończony albo
+//! pominięty, i tylko wtedy, gdy nie wpisano jeszcze żadnego klucza.
+
+use crate::config::Config;
+
+/// Przykład z błędami, które poprawia dostawca demonstracyjny
+pub const SAMPLE_TEXT: &str = "narazie wogóle nie wiem czy napewno dobrze to napisałem";
+
+/// Element okna, na który wskazuje dymek
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    PasteButton,
+    FirstPanel,
+    StatusBar,
+    SettingsButton,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub target: Target,
+    pub title: &'static str,
+    pub body: &'static str,
+    /// Przycisk przechodzący dalej
+    pub button: &'static str,
+}
+
+/// Miejsce w treści kroku na skrót globalny z ustawień
+const HOTKEY: &str = "{skrót}";
+
+impl Step {
+    /// Treść kroku ze skrótem globalnym z ustawień w miejscu `{skrót}`
+    pub fn text(&self, hotkey: &str) -> String {
+        self.body.replace(HOTKEY, hotkey)
+    }
+}
+
+pub const STEPS: &[Step] = &[
+    Step {
+        target: Target::PasteButton,
+        title: "Witaj!",
+        body: "Zwykle zaznaczasz tekst i klikasz „Wklej” albo naciskasz skrót. \
+               Spróbuj na przykładzie: poprawią go dostawcy demonstracyjni, bez kluczy API.",
+        button: "Popraw przykład",
+    },
+    Step {
+        target: Target::FirstPanel,
+        title: "Wyniki dostawców",
+        body: "Każdy panel to inny dostawca. Najlepszy wynik wybierasz przyciskiem „Użyj” \
+               - trafia do schowka i jest wklejany tam, gdzie był tekst.",
+        button: "Dalej",
+    },
+    Step {
+        target: Target::StatusBar,
+        title: "Skrót globalny",
+        body: "W dowolnym programie zaznacz tekst i naciśnij {skrót} - okno pokaże poprawki.",
+        button: "Dalej",
+    },
+    Step {
+        target: Target::SettingsButton,
+        title: "Klucze API",
+        body: "Na koniec wpisz w ustawieniach klucze dostawców, z których chcesz korzystać.",
+        button: "Zakończ",
+    },
+];
+
+/// Czy pokazać samouczek przy starcie
+pub fn should_show(config: &Config) -> bool {
+    let keys = &config.api_keys;
+    !config.settings.tutorial_done
+        && [&keys.openai, &keys.anthropic, &keys.gemini, &keys.deepseek, &keys.mistral, &keys.groq]
+            .iter()
+            .all(|key| key.trim().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_show() {
+        let mut config = Config::default();
+        assert!(should_show(&config));
+
+        config.api_keys.gemini = "AIza-test".to_string();
+        assert!(!should_show(&config));
+
+        config.api_keys.gemini = "  ".to_string();
+        config.settings.tutorial_done = true;
+        assert!(!should_show(&config));
+    }
+
+    #[test]
+    fn test_steps_start_with_sample_and_end_in_settings() {
+        assert_eq!(STEPS.first().map(|s| s.target), Some(Target::PasteButton));
+        assert_eq!(STEPS.last().map(|s| s.target), Some(Target::SettingsButton));
+    }
+
+    #[test]
+    fn test_step_text_uses_configured_hotkey() {
+        let step = STEPS.iter().find(|s| s.target == Target::StatusBar).unwrap();
+        let text = step.text("Ctrl+Alt+K");
+        assert!(text.contains("Ctrl+Alt+K"));
+        assert!(!text.contains("Ctrl+Shift+C"));
+        assert!(!text.contains(HOTKEY));
+    }
+}
@@ -0,0 +1,58 @@
+//! Ograniczony ruch i oszczędne emoji (`[appearance]`)
+//!
+//! Przy `MinimalEmoji` stan panelu pokazuje zwykła ikona symboliczna, a
+//! komunikaty paska stanu i napisy przycisków tracą emoji z początku. Samo
+//! okno czyta ustawienie przez `minimal_emoji()`, bo stan paneli jest
+//! ustawiany w wielu miejscach bez dostępu do konfiguracji.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static MINIMAL_EMOJI: AtomicBool = AtomicBool::new(false);
+
+pub fn set_minimal_emoji(enabled: bool) {
+    MINIMAL_EMOJI.store(enabled, Ordering::SeqCst);
+}
+
+pub fn minimal_emoji() -> bool {
+    MINIMAL_EMOJI.load(Ordering::SeqCst)
+}
+
+/// Emoji, symbole ozdobne (strzałki, dingbaty) i ich modyfikatory
+fn is_decoration(c: char) -> bool {
+    matches!(c as u32, 0x2190..=0x2BFF | 0x1F000..=0x1FAFF | 0xFE0F | 0x200D | 0x20E3)
+}
+
+/// Tekst bez emoji na początku; sam symbol (np. „⏸”) zostaje bez zmian
+pub fn strip_leading_emoji(text: &str) -> &str {
+    let rest = text.trim_start_matches(is_decoration).trim_start();
+    if rest.is_empty() {
+        text
+    } else {
+        rest
+    }
+}
+
+/// Tekst do pokazania: bez emoji z początku, gdy włączono `MinimalEmoji`
+pub fn plain(text: &str) -> &str {
+    if minimal_emoji() {
+        strip_leading_emoji(text)
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_leading_emoji() {
+        assert_eq!(strip_leading_emoji("📋 Skopiowano wynik: OpenAI"), "Skopiowano wynik: OpenAI");
+        assert_eq!(strip_leading_emoji("⚠️ Brak tekstu w schowku"), "Brak tekstu w schowku");
+        assert_eq!(strip_leading_emoji("⛓ Krok 1/2"), "Krok 1/2");
+        assert_eq!(strip_leading_emoji("❌ Anulowano"), "Anulowano");
+        assert_eq!(strip_leading_emoji("⏸"), "⏸");
+        assert_eq!(strip_leading_emoji("„Cytat” 📋"), "„Cytat” 📋");
+        assert_eq!(strip_leading_emoji("Gotowe"), "Gotowe");
+    }
+}
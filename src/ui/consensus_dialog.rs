@@ -0,0 +1,125 @@
+use crate::agreement::Level;
+use crate::align::{contested_count, AlignedRow};
+use gtk4::prelude::*;
+use libadwaita as adw;
+
+/// Okno "Zgodność": wyniki wyrównane zdanie po zdaniu, sporne wiersze wyróżnione
+pub struct ConsensusDialog {
+    window: gtk4::Window,
+}
+
+impl ConsensusDialog {
+    /// `names` to nazwy dostawców w kolejności kolumn `rows`
+    pub fn new(parent: &adw::ApplicationWindow, names: &[&str], rows: &[AlignedRow]) -> Self {
+        let window = gtk4::Window::builder()
+            .title("Zgodność dostawców")
+            .transient_for(parent)
+            .modal(true)
+            .default_width(1000)
+            .default_height(700)
+            .build();
+
+        let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 12);
+        vbox.set_margin_start(12);
+        vbox.set_margin_end(12);
+        vbox.set_margin_top(12);
+        vbox.set_margin_bottom(12);
+
+        let header = gtk4::Box::new(gtk4::Orientation::Horizontal, 12);
+        let summary = gtk4::Label::new(Some(&format!(
+            "Sporne zdania: {} z {}. Przejrzyj je przed wyborem wyniku.",
+            contested_count(rows),
+            rows.len()
+        )));
+        summary.set_halign(gtk4::Align::Start);
+        summary.set_hexpand(true);
+        summary.add_css_class("dim-label");
+        header.append(&summary);
+
+        let only_contested = gtk4::CheckButton::with_label("Tylko sporne");
+        header.append(&only_contested);
+        vbox.append(&header);
+
+        let grid = gtk4::Grid::builder()
+            .row_spacing(8)
+            .column_spacing(12)
+            .build();
+
+        for (col, name) in names.iter().enumerate() {
+            let label = gtk4::Label::new(Some(name));
+            label.add_css_class("panel-title");
+            label.set_hexpand(true);
+            grid.attach(&label, col as i32 + 1, 0, 1, 1);
+        }
+
+        // Widżety wierszy zgodnych, chowane przy "Tylko sporne"
+        let mut agreed_widgets: Vec<gtk4::Widget> = Vec::new();
+        for (row_index, row) in rows.iter().enumerate() {
+            let grid_row = row_index as i32 + 1;
+            let (marker, class) = match (row.is_contested(), row.level()) {
+                (false, _) => ("✓", None),
+                (true, Level::High) => ("≈", Some("warning")),
+                (true, _) => ("⚠️", Some("error")),
+            };
+
+            let marker_label = gtk4::Label::new(Some(marker));
+            marker_label.set_valign(gtk4::Align::Start);
+            marker_label.set_tooltip_text(Some(&format!("Zgodność {:.0}%", row.agreement * 100.0)));
+            grid.attach(&marker_label, 0, grid_row, 1, 1);
+
+            let mut widgets = vec![marker_label.upcast::<gtk4::Widget>()];
+            for (col, cell) in row.cells.iter().enumerate() {
+                let label = gtk4::Label::new(Some(cell.as_deref().unwrap_or("—")));
+                label.set_wrap(true);
+                label.set_xalign(0.0);
+                label.set_valign(gtk4::Align::Start);
+                label.set_selectable(true);
+                if cell.is_none() {
+                    label.add_css_class("dim-label");
+                } else if let Some(class) = class {
+                    label.add_css_class(class);
+                }
+                grid.attach(&label, col as i32 + 1, grid_row, 1, 1);
+                widgets.push(label.upcast());
+            }
+
+            if !row.is_contested() {
+                agreed_widgets.extend(widgets);
+            }
+        }
+
+        only_contested.connect_toggled(move |check| {
+            for widget in &agreed_widgets {
+                widget.set_visible(!check.is_active());
+            }
+        });
+
+        let scrolled = gtk4::ScrolledWindow::builder()
+            .hscrollbar_policy(gtk4::PolicyType::Never)
+            .vexpand(true)
+            .child(&grid)
+            .build();
+        vbox.append(&scrolled);
+
+        let button_box = gtk4::Box::new(gtk4::Orientation::Horizontal, 6);
+        button_box.set_halign(gtk4::Align::End);
+
+        let window_weak = window.downgrade();
+        let close_button = gtk4::Button::with_label("Zamknij");
+        close_button.connect_clicked(move |_| {
+            if let Some(w) = window_weak.upgrade() {
+                w.close();
+            }
+        });
+        button_box.append(&close_button);
+        vbox.append(&button_box);
+
+        window.set_child(Some(&vbox));
+
+        Self { window }
+    }
+
+    pub fn present(&self) {
+        self.window.present();
+    }
+}
@@ -0,0 +1,144 @@
+use crate::audit::format_timestamp;
+use crate::history::{search, HistoryEntry};
+use gtk4::prelude::*;
+use libadwaita as adw;
+use std::rc::Rc;
+
+/// Najwięcej wierszy na liście; dłuższą historię zawęża wyszukiwanie
+const MAX_ROWS: usize = 200;
+
+type CopyHandler = Rc<dyn Fn(&str) -> Result<(), String>>;
+
+/// Okno historii poprawek: wyszukiwanie i ponowne kopiowanie wyników
+pub struct HistoryBrowser {
+    window: gtk4::Window,
+}
+
+impl HistoryBrowser {
+    /// `on_copy` kopiuje tekst do schowka; błąd jest pokazywany w oknie
+    pub fn new(
+        parent: &adw::ApplicationWindow,
+        entries: Vec<HistoryEntry>,
+        on_copy: impl Fn(&str) -> Result<(), String> + 'static,
+    ) -> Self {
+        let window = gtk4::Window::builder()
+            .title("Historia poprawek")
+            .transient_for(parent)
+            .modal(true)
+            .default_width(800)
+            .default_height(600)
+            .build();
+
+        let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 12);
+        vbox.set_margin_start(12);
+        vbox.set_margin_end(12);
+        vbox.set_margin_top(12);
+        vbox.set_margin_bottom(12);
+
+        let search_entry = gtk4::SearchEntry::builder()
+            .placeholder_text("Szukaj w tekście, wyniku, dostawcy lub stylu")
+            .key_capture_widget(&window)
+            .build();
+        vbox.append(&search_entry);
+
+        let summary = gtk4::Label::new(None);
+        summary.set_xalign(0.0);
+        summary.add_css_class("dim-label");
+        vbox.append(&summary);
+
+        let list = gtk4::ListBox::new();
+        list.set_selection_mode(gtk4::SelectionMode::None);
+        list.add_css_class("boxed-list");
+        let scrolled = gtk4::ScrolledWindow::builder()
+            .hscrollbar_policy(gtk4::PolicyType::Never)
+            .vexpand(true)
+            .child(&list)
+            .build();
+        vbox.append(&scrolled);
+
+        let button_box = gtk4::Box::new(gtk4::Orientation::Horizontal, 6);
+        button_box.set_halign(gtk4::Align::End);
+        let window_weak = window.downgrade();
+        let close_button = gtk4::Button::with_label("Zamknij");
+        close_button.connect_clicked(move |_| {
+            if let Some(w) = window_weak.upgrade() {
+                w.close();
+            }
+        });
+        button_box.append(&close_button);
+        vbox.append(&button_box);
+
+        let entries = Rc::new(entries);
+        let on_copy: CopyHandler = Rc::new(on_copy);
+        fill_list(&list, &summary, &entries, "", &on_copy);
+        search_entry.connect_search_changed(move |entry| {
+            fill_list(&list, &summary, &entries, &entry.text(), &on_copy);
+        });
+
+        window.set_child(Some(&vbox));
+        Self { window }
+    }
+
+    pub fn present(&self) {
+        self.window.present();
+    }
+}
+
+/// Wypełnia listę wpisami pasującymi do zapytania (najnowsze na górze)
+fn fill_list(list: &gtk4::ListBox, summary: &gtk4::Label, entries: &[HistoryEntry], query: &str, on_copy: &CopyHandler) {
+    while let Some(child) = list.first_child() {
+        list.remove(&child);
+    }
+
+    let found = search(entries, query);
+    summary.set_text(&match (entries.len(), found.len()) {
+        (0, _) => "Historia jest pusta".to_string(),
+        (total, shown) if shown > MAX_ROWS => {
+            format!("Znaleziono {} z {} wpisów; pokazano {} najnowszych", shown, total, MAX_ROWS)
+        }
+        (total, shown) => format!("Znaleziono {} z {} wpisów", shown, total),
+    });
+
+    for entry in found.into_iter().take(MAX_ROWS) {
+        list.append(&entry_row(entry, summary, on_copy));
+    }
+}
+
+fn entry_row(entry: &HistoryEntry, summary: &gtk4::Label, on_copy: &CopyHandler) -> gtk4::Box {
+    let row = gtk4::Box::new(gtk4::Orientation::Vertical, 6);
+    row.set_margin_start(8);
+    row.set_margin_end(8);
+    row.set_margin_top(8);
+    row.set_margin_bottom(8);
+
+    // "2026-10-18T09:05:00Z" → "2026-10-18 09:05"
+    let time = format_timestamp(entry.timestamp).replace('T', " ");
+    let mut details = vec![time[..16].to_string(), entry.provider.clone(), entry.style.clone()];
+    details.extend(entry.source_app.clone());
+    let header = gtk4::Label::new(Some(&details.join(" · ")));
+    header.set_xalign(0.0);
+    header.add_css_class("dim-label");
+    row.append(&header);
+
+    let result = gtk4::Label::new(Some(&entry.result));
+    result.set_xalign(0.0);
+    result.set_wrap(true);
+    result.set_lines(3);
+    result.set_ellipsize(gtk4::pango::EllipsizeMode::End);
+    result.set_tooltip_text(Some(&format!("Oryginał:\n{}", entry.original)));
+    row.append(&result);
+
+    let buttons = gtk4::Box::new(gtk4::Orientation::Horizontal, 6);
+    buttons.set_halign(gtk4::Align::End);
+    for (label, text) in [("📋 Kopiuj wynik", &entry.result), ("Kopiuj oryginał", &entry.original)] {
+        let button = gtk4::Button::with_label(label);
+        let (text, summary, on_copy) = (text.clone(), summary.clone(), on_copy.clone());
+        button.connect_clicked(move |_| match on_copy(&text) {
+            Ok(()) => summary.set_text("📋 Skopiowano do schowka"),
+            Err(e) => summary.set_text(&format!("❌ Nie udało się skopiować: {}", e)),
+        });
+        buttons.append(&button);
+    }
+    row.append(&buttons);
+    row
+}
@@ -0,0 +1,61 @@
+use crate::hotkey::HotkeyConflict;
+use gtk4::prelude::*;
+use libadwaita as adw;
+use libadwaita::prelude::*;
+
+/// Wybór w oknie konfliktu skrótu
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConflictChoice {
+    /// Przełącz na wolną kombinację
+    Use(String),
+    /// Otwórz ustawienia i wpisz własną kombinację
+    Settings,
+    Skip,
+}
+
+/// Informuje, że skrót główny jest zajęty, i proponuje wolne kombinacje do wybrania jednym kliknięciem
+pub async fn ask_hotkey_alternative(parent: &adw::ApplicationWindow, conflict: &HotkeyConflict) -> ConflictChoice {
+    let effect = match &conflict.active {
+        Some(active) => format!("więc nadal działa poprzedni skrót {}", active),
+        None => "więc poprawianie skrótem nie zadziała".to_string(),
+    };
+    let body = if conflict.alternatives.is_empty() {
+        format!(
+            "{} używa już inny program, {}. Nie znaleziono wolnej kombinacji; ustaw inną w ustawieniach.",
+            conflict.combination, effect
+        )
+    } else {
+        format!("{} używa już inny program, {}. Wybierz wolną kombinację:", conflict.combination, effect)
+    };
+    let dialog = adw::MessageDialog::builder()
+        .transient_for(parent)
+        .modal(true)
+        .heading("Skrót jest zajęty")
+        .body(body)
+        .build();
+    dialog.add_responses(&[("skip", "Pomiń"), ("settings", "Ustawienia…")]);
+    for (i, combination) in conflict.alternatives.iter().enumerate() {
+        dialog.add_response(&format!("use-{}", i), combination);
+    }
+    if !conflict.alternatives.is_empty() {
+        dialog.set_response_appearance("use-0", adw::ResponseAppearance::Suggested);
+        dialog.set_default_response(Some("use-0"));
+    }
+    dialog.set_close_response("skip");
+
+    let (tx, rx) = async_channel::bounded(1);
+    dialog.connect_response(None, move |_, response| {
+        let _ = tx.try_send(response.to_string());
+    });
+    dialog.present();
+
+    let response = rx.recv().await.unwrap_or_default();
+    match response.as_str() {
+        "settings" => ConflictChoice::Settings,
+        response => response
+            .strip_prefix("use-")
+            .and_then(|i| i.parse::<usize>().ok())
+            .and_then(|i| conflict.alternatives.get(i).cloned())
+            .map_or(ConflictChoice::Skip, ConflictChoice::Use),
+    }
+}
@@ -0,0 +1,80 @@
+use gtk4::prelude::*;
+use libadwaita as adw;
+use libadwaita::prelude::*;
+
+/// Pyta o własne polecenie dla bieżącej sesji; `recent` można wybrać jednym kliknięciem.
+/// `None` po anulowaniu.
+pub async fn ask_instruction(parent: &adw::ApplicationWindow, recent: &[String]) -> Option<String> {
+    let dialog = adw::MessageDialog::builder()
+        .transient_for(parent)
+        .modal(true)
+        .heading("Inna instrukcja")
+        .body("Napisz, co zrobić z tekstem ze schowka. Polecenie dotyczy tylko tej sesji.")
+        .build();
+    dialog.add_responses(&[("cancel", "Anuluj"), ("ok", "Wyślij")]);
+    dialog.set_response_appearance("ok", adw::ResponseAppearance::Suggested);
+    dialog.set_close_response("cancel");
+
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 6);
+
+    let text_view = gtk4::TextView::builder()
+        .wrap_mode(gtk4::WrapMode::WordChar)
+        .top_margin(6)
+        .bottom_margin(6)
+        .left_margin(6)
+        .right_margin(6)
+        .build();
+    let scrolled = gtk4::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk4::PolicyType::Never)
+        .min_content_height(100)
+        .min_content_width(360)
+        .child(&text_view)
+        .build();
+    scrolled.add_css_class("frame");
+    vbox.append(&scrolled);
+
+    if !recent.is_empty() {
+        let label = gtk4::Label::new(Some("Ostatnio użyte:"));
+        label.set_halign(gtk4::Align::Start);
+        vbox.append(&label);
+
+        for instruction in recent {
+            let label = gtk4::Label::builder()
+                .label(instruction.lines().next().unwrap_or_default())
+                .xalign(0.0)
+                .ellipsize(gtk4::pango::EllipsizeMode::End)
+                .max_width_chars(50)
+                .build();
+            let button = gtk4::Button::builder().child(&label).build();
+            button.add_css_class("flat");
+            button.set_tooltip_text(Some(instruction));
+            let buffer = text_view.buffer();
+            let instruction = instruction.clone();
+            button.connect_clicked(move |_| buffer.set_text(&instruction));
+            vbox.append(&button);
+        }
+    }
+    dialog.set_extra_child(Some(&vbox));
+
+    let buffer = text_view.buffer();
+    let update = {
+        let dialog = dialog.clone();
+        move |buffer: &gtk4::TextBuffer| {
+            let text = buffer.text(&buffer.start_iter(), &buffer.end_iter(), false);
+            dialog.set_response_enabled("ok", !text.trim().is_empty());
+        }
+    };
+    update(&buffer);
+    buffer.connect_changed(update);
+
+    let (tx, rx) = async_channel::bounded(1);
+    dialog.connect_response(None, move |_, response| {
+        let _ = tx.try_send(response == "ok");
+    });
+    dialog.present();
+    text_view.grab_focus();
+
+    let accepted = rx.recv().await.unwrap_or(false);
+    let text = buffer.text(&buffer.start_iter(), &buffer.end_iter(), false).trim().to_string();
+    (accepted && !text.is_empty()).then_some(text)
+}
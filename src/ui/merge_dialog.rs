@@ -0,0 +1,162 @@
+use crate::merge::{Granularity, MergePlan};
+use gtk4::prelude::*;
+use libadwaita as adw;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Okno "Złóż wynik": fragmenty wyników w kolumnach, zaznaczane checkboxami
+pub struct MergeDialog {
+    window: gtk4::Window,
+    preview: gtk4::TextView,
+    compose_button: gtk4::Button,
+}
+
+impl MergeDialog {
+    /// `sources` to pary (nazwa dostawcy, tekst wyniku); pusty tekst oznacza brak wyniku
+    pub fn new(parent: &adw::ApplicationWindow, sources: &[(&str, String)]) -> Self {
+        let window = gtk4::Window::builder()
+            .title("Złóż wynik")
+            .transient_for(parent)
+            .modal(true)
+            .default_width(1000)
+            .default_height(700)
+            .build();
+
+        let texts: Vec<&str> = sources.iter().map(|(_, t)| t.as_str()).collect();
+        let plan = Rc::new(RefCell::new(MergePlan::new(&texts)));
+        if let Some(first) = texts.iter().position(|t| !t.trim().is_empty()) {
+            plan.borrow_mut().select_source(first);
+        }
+
+        let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 12);
+        vbox.set_margin_start(12);
+        vbox.set_margin_end(12);
+        vbox.set_margin_top(12);
+        vbox.set_margin_bottom(12);
+
+        let unit = match plan.borrow().granularity() {
+            Granularity::Paragraph => "akapity",
+            Granularity::Sentence => "zdania",
+        };
+        let hint = gtk4::Label::new(Some(&format!(
+            "Zaznacz {} do złożonego wyniku. Kolejność wynika z pozycji w tekście.",
+            unit
+        )));
+        hint.set_halign(gtk4::Align::Start);
+        hint.add_css_class("dim-label");
+        vbox.append(&hint);
+
+        let grid = gtk4::Grid::builder()
+            .row_spacing(8)
+            .column_spacing(12)
+            .column_homogeneous(true)
+            .build();
+
+        for (col, (name, _)) in sources.iter().enumerate() {
+            let label = gtk4::Label::new(Some(name));
+            label.add_css_class("panel-title");
+            grid.attach(&label, col as i32, 0, 1, 1);
+        }
+
+        let preview = gtk4::TextView::builder()
+            .wrap_mode(gtk4::WrapMode::Word)
+            .left_margin(8)
+            .right_margin(8)
+            .top_margin(8)
+            .bottom_margin(8)
+            .build();
+        preview.buffer().set_text(&plan.borrow().compose());
+
+        let row_count = plan.borrow().row_count();
+        for row in 0..row_count {
+            for source in 0..sources.len() {
+                let Some(segment) = plan.borrow().segment(source, row).map(str::to_string) else {
+                    continue;
+                };
+
+                let label = gtk4::Label::new(Some(&segment));
+                label.set_wrap(true);
+                label.set_xalign(0.0);
+
+                let check = gtk4::CheckButton::new();
+                check.set_child(Some(&label));
+                check.set_valign(gtk4::Align::Start);
+                check.set_active(plan.borrow().is_selected(source, row));
+
+                let plan_clone = plan.clone();
+                let preview_clone = preview.clone();
+                check.connect_toggled(move |check| {
+                    let mut plan = plan_clone.borrow_mut();
+                    plan.set_selected(source, row, check.is_active());
+                    preview_clone.buffer().set_text(&plan.compose());
+                });
+
+                grid.attach(&check, source as i32, row as i32 + 1, 1, 1);
+            }
+        }
+
+        let scrolled = gtk4::ScrolledWindow::builder()
+            .hscrollbar_policy(gtk4::PolicyType::Never)
+            .vexpand(true)
+            .child(&grid)
+            .build();
+        vbox.append(&scrolled);
+
+        let preview_label = gtk4::Label::new(Some("Podgląd (można poprawić ręcznie):"));
+        preview_label.set_halign(gtk4::Align::Start);
+        vbox.append(&preview_label);
+
+        let preview_scrolled = gtk4::ScrolledWindow::builder()
+            .hscrollbar_policy(gtk4::PolicyType::Never)
+            .min_content_height(150)
+            .child(&preview)
+            .build();
+        vbox.append(&preview_scrolled);
+
+        let button_box = gtk4::Box::new(gtk4::Orientation::Horizontal, 6);
+        button_box.set_halign(gtk4::Align::End);
+
+        let window_weak = window.downgrade();
+        let cancel_button = gtk4::Button::with_label("Anuluj");
+        cancel_button.connect_clicked(move |_| {
+            if let Some(w) = window_weak.upgrade() {
+                w.close();
+            }
+        });
+        button_box.append(&cancel_button);
+
+        let compose_button = gtk4::Button::with_label("🧩 Złóż wynik");
+        compose_button.add_css_class("suggested-action");
+        button_box.append(&compose_button);
+
+        vbox.append(&button_box);
+        window.set_child(Some(&vbox));
+
+        Self {
+            window,
+            preview,
+            compose_button,
+        }
+    }
+
+    pub fn present(&self) {
+        self.window.present();
+    }
+
+    /// Wywoływane ze złożonym tekstem (po ręcznych poprawkach w podglądzie)
+    pub fn connect_compose<F: Fn(String) + 'static>(&self, callback: F) {
+        let preview = self.preview.clone();
+        let window_weak = self.window.downgrade();
+        self.compose_button.connect_clicked(move |_| {
+            let buffer = preview.buffer();
+            let text = buffer.text(&buffer.start_iter(), &buffer.end_iter(), false);
+            if text.trim().is_empty() {
+                return;
+            }
+            callback(text.to_string());
+            if let Some(w) = window_weak.upgrade() {
+                w.close();
+            }
+        });
+    }
+}
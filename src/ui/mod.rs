@@ -1,3 +1,30 @@
+pub mod appearance;
+pub mod consensus_dialog;
+pub mod history_browser;
+pub mod hotkey_conflict_dialog;
+pub mod instruction_dialog;
+pub mod merge_dialog;
+pub mod model_dialog;
+pub mod panel_status;
+pub mod passphrase_dialog;
+pub mod result_popup;
+pub mod review_dialog;
+pub mod settings;
 pub mod settings_gtk;
+pub mod stream_bridge;
+pub mod style_palette;
 
+pub use consensus_dialog::ConsensusDialog;
+pub use history_browser::HistoryBrowser;
+pub use hotkey_conflict_dialog::{ask_hotkey_alternative, ConflictChoice};
+pub use instruction_dialog::ask_instruction;
+pub use merge_dialog::MergeDialog;
+pub use model_dialog::ask_model;
+pub use panel_status::PanelStatus;
+pub use passphrase_dialog::ask_passphrase;
+pub use result_popup::ResultPopup;
+pub use review_dialog::ReviewDialog;
+pub use settings::{SettingsField, SettingsForm};
 pub use settings_gtk::SettingsDialog;
+pub use stream_bridge::StreamBridge;
+pub use style_palette::StylePalette;
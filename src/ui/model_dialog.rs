@@ -0,0 +1,34 @@
+use gtk4::prelude::*;
+use libadwaita as adw;
+use libadwaita::prelude::*;
+
+/// Pyta o model dla ponownego zapytania dostawcy. `None` po anulowaniu.
+pub async fn ask_model(parent: &adw::ApplicationWindow, provider: &str, models: &[String]) -> Option<String> {
+    let dialog = adw::MessageDialog::builder()
+        .transient_for(parent)
+        .modal(true)
+        .heading(format!("Uruchom ponownie: {}", provider))
+        .body("Wybierz model. Zmiana dotyczy tylko tego zapytania, ustawienia pozostają bez zmian.")
+        .build();
+    dialog.add_responses(&[("cancel", "Anuluj"), ("ok", "Uruchom")]);
+    dialog.set_response_appearance("ok", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("ok"));
+    dialog.set_close_response("cancel");
+
+    let names: Vec<&str> = models.iter().map(String::as_str).collect();
+    let dropdown = gtk4::DropDown::from_strings(&names);
+    dialog.set_extra_child(Some(&dropdown));
+
+    let (tx, rx) = async_channel::bounded(1);
+    dialog.connect_response(None, move |_, response| {
+        let _ = tx.try_send(response == "ok");
+    });
+    dialog.present();
+    dropdown.grab_focus();
+
+    let accepted = rx.recv().await.unwrap_or(false);
+    if !accepted {
+        return None;
+    }
+    models.get(dropdown.selected() as usize).cloned()
+}
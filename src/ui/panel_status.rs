@@ -0,0 +1,109 @@
+//! Stan panelu dostawcy
+//!
+//! Stan jest pokazywany ikoną i obok niej tekstem, żeby nie zależał tylko od
+//! emoji i koloru. Ten sam tekst trafia do opisu panelu dla technologii
+//! asystujących (czytników ekranu). W trybie `MinimalEmoji` emoji zastępuje
+//! ikona symboliczna motywu.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanelStatus {
+    Idle,
+    Processing,
+    /// Czeka w kolejce po przekroczeniu limitu zapytań dostawcy
+    RateLimited,
+    Done,
+    Error,
+    Cancelled,
+    Skipped,
+    Disabled,
+    OverBudget,
+    /// Tekst nie mieści się w oknie kontekstu modelu
+    TooLong,
+}
+
+impl PanelStatus {
+    pub fn icon(&self) -> &'static str {
+        match self {
+            PanelStatus::Idle => "",
+            PanelStatus::Processing => "🤖",
+            PanelStatus::RateLimited => "⏳",
+            PanelStatus::Done => "✅",
+            PanelStatus::Error => "❌",
+            PanelStatus::Cancelled => "❌",
+            PanelStatus::Skipped => "⛓",
+            PanelStatus::Disabled => "🔌",
+            PanelStatus::OverBudget => "💰",
+            PanelStatus::TooLong => "📏",
+        }
+    }
+
+    /// Ikona symboliczna motywu zamiast emoji (tryb `MinimalEmoji`)
+    pub fn symbolic_icon(&self) -> &'static str {
+        match self {
+            PanelStatus::Idle => "",
+            PanelStatus::Processing => "content-loading-symbolic",
+            PanelStatus::RateLimited => "alarm-symbolic",
+            PanelStatus::Done => "object-select-symbolic",
+            PanelStatus::Error => "dialog-error-symbolic",
+            PanelStatus::Cancelled => "process-stop-symbolic",
+            PanelStatus::Skipped => "media-skip-forward-symbolic",
+            PanelStatus::Disabled => "network-offline-symbolic",
+            PanelStatus::OverBudget => "dialog-warning-symbolic",
+            PanelStatus::TooLong => "dialog-warning-symbolic",
+        }
+    }
+
+    /// Tekst stanu obok ikony (pusty, gdy panel czeka na tekst)
+    pub fn label(&self) -> &'static str {
+        match self {
+            PanelStatus::Idle => "",
+            PanelStatus::Processing => "Przetwarzanie",
+            PanelStatus::RateLimited => "Limit zapytań",
+            PanelStatus::Done => "Gotowe",
+            PanelStatus::Error => "Błąd",
+            PanelStatus::Cancelled => "Anulowano",
+            PanelStatus::Skipped => "Pominięty",
+            PanelStatus::Disabled => "Wyłączony",
+            PanelStatus::OverBudget => "Budżet wyczerpany",
+            PanelStatus::TooLong => "Tekst za długi",
+        }
+    }
+
+    /// Opis panelu dla czytników ekranu, np. "OpenAI: Gotowe"
+    pub fn accessible_label(&self, name: &str) -> String {
+        match self {
+            PanelStatus::Idle => format!("{}: Oczekiwanie", name),
+            _ => format!("{}: {}", name, self.label()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_icon_has_text() {
+        let all = [
+            PanelStatus::Processing,
+            PanelStatus::RateLimited,
+            PanelStatus::Done,
+            PanelStatus::Error,
+            PanelStatus::Cancelled,
+            PanelStatus::Skipped,
+            PanelStatus::Disabled,
+            PanelStatus::OverBudget,
+            PanelStatus::TooLong,
+        ];
+        for status in all {
+            assert!(!status.icon().is_empty());
+            assert!(status.symbolic_icon().ends_with("-symbolic"));
+            assert!(!status.label().is_empty());
+        }
+        // Ta sama ikona, ale różny tekst
+        assert_ne!(PanelStatus::Error.label(), PanelStatus::Cancelled.label());
+
+        assert_eq!(PanelStatus::Done.accessible_label("OpenAI"), "OpenAI: Gotowe");
+        assert_eq!(PanelStatus::Idle.accessible_label("Gemini"), "Gemini: Oczekiwanie");
+    }
+}
@@ -0,0 +1,77 @@
+use gtk4::prelude::*;
+use libadwaita as adw;
+use libadwaita::prelude::*;
+
+/// Pyta o hasło historii; `creating` dodaje pole potwierdzenia. `None` po anulowaniu.
+pub async fn ask_passphrase(parent: &adw::ApplicationWindow, creating: bool, error: Option<&str>) -> Option<String> {
+    let (heading, body) = if creating {
+        (
+            "Ustaw hasło historii",
+            "Historia poprawek będzie szyfrowana tym hasłem. Bez niego nie da się jej odczytać.",
+        )
+    } else {
+        ("Odblokuj historię", "Podaj hasło, którym zaszyfrowano historię poprawek.")
+    };
+    let body = match error {
+        Some(error) => format!("{}\n\n❌ {}", body, error),
+        None => body.to_string(),
+    };
+
+    let dialog = adw::MessageDialog::builder()
+        .transient_for(parent)
+        .modal(true)
+        .heading(heading)
+        .body(body)
+        .build();
+    dialog.add_responses(&[("cancel", "Anuluj"), ("ok", if creating { "Ustaw" } else { "Odblokuj" })]);
+    dialog.set_response_appearance("ok", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("ok"));
+    dialog.set_close_response("cancel");
+
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 6);
+    let passphrase = gtk4::PasswordEntry::builder()
+        .placeholder_text("Hasło")
+        .show_peek_icon(true)
+        .activates_default(true)
+        .build();
+    vbox.append(&passphrase);
+
+    let confirm = gtk4::PasswordEntry::builder()
+        .placeholder_text("Powtórz hasło")
+        .show_peek_icon(true)
+        .activates_default(true)
+        .visible(creating)
+        .build();
+    vbox.append(&confirm);
+    dialog.set_extra_child(Some(&vbox));
+
+    let update = {
+        let dialog = dialog.clone();
+        let passphrase = passphrase.clone();
+        let confirm = confirm.clone();
+        move || {
+            let text = passphrase.text();
+            let valid = !text.is_empty() && (!creating || text == confirm.text());
+            dialog.set_response_enabled("ok", valid);
+        }
+    };
+    update();
+    passphrase.connect_changed({
+        let update = update.clone();
+        move |_| update()
+    });
+    confirm.connect_changed(move |_| update());
+
+    let (tx, rx) = async_channel::bounded(1);
+    dialog.connect_response(None, move |_, response| {
+        let _ = tx.try_send(response == "ok");
+    });
+    dialog.present();
+    passphrase.grab_focus();
+
+    let accepted = rx.recv().await.unwrap_or(false);
+    let text = passphrase.text().to_string();
+    passphrase.set_text("");
+    confirm.set_text("");
+    accepted.then_some(text)
+}
@@ -0,0 +1,179 @@
+use crate::chooser::{self, Area};
+use gtk4::prelude::*;
+use gtk4::{gdk, glib};
+use libadwaita as adw;
+use std::rc::Rc;
+use tracing::{info, warn};
+
+/// Tytuł okienka; po nim okno jest odnajdywane przy przesuwaniu pod kursor
+const POPUP_TITLE: &str = "Poprawiacz - wybór wyniku";
+const POPUP_WIDTH: i32 = 460;
+const ROW_HEIGHT: i32 = 56;
+
+/// Okienko przy kursorze z pierwszymi liniami wyników i przyciskami wklejenia
+pub struct ResultPopup {
+    window: gtk4::Window,
+    pick_buttons: Vec<(usize, gtk4::Button)>,
+    show_button: gtk4::Button,
+    height: i32,
+}
+
+impl ResultPopup {
+    /// `results` to trójki (indeks panelu, nazwa dostawcy, tekst wyniku)
+    pub fn new(parent: &adw::ApplicationWindow, results: &[(usize, &str, String)]) -> Self {
+        let height = ROW_HEIGHT * (results.len() as i32 + 1);
+        let window = gtk4::Window::builder()
+            .title(POPUP_TITLE)
+            .decorated(false)
+            .resizable(false)
+            .default_width(POPUP_WIDTH)
+            .default_height(height)
+            .build();
+        if let Some(app) = parent.application() {
+            window.set_application(Some(&app));
+        }
+
+        let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 6);
+        vbox.set_margin_start(8);
+        vbox.set_margin_end(8);
+        vbox.set_margin_top(8);
+        vbox.set_margin_bottom(8);
+
+        let mut pick_buttons = Vec::new();
+        for (n, (index, name, text)) in results.iter().enumerate() {
+            let row = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+
+            let label = gtk4::Label::new(None);
+            label.set_markup(&format!(
+                "<b>{} {}</b>\n{}",
+                n + 1,
+                glib::markup_escape_text(name),
+                glib::markup_escape_text(&chooser::preview_line(text, chooser::PREVIEW_CHARS))
+            ));
+            label.set_xalign(0.0);
+            label.set_hexpand(true);
+            label.set_ellipsize(gtk4::pango::EllipsizeMode::End);
+            row.append(&label);
+
+            let button = gtk4::Button::with_label("📋 Wklej");
+            button.add_css_class(&format!("use-button-{}", index));
+            row.append(&button);
+
+            vbox.append(&row);
+            pick_buttons.push((*index, button));
+        }
+
+        let footer = gtk4::Box::new(gtk4::Orientation::Horizontal, 6);
+        footer.set_halign(gtk4::Align::End);
+
+        let show_button = gtk4::Button::with_label("🪟 Pokaż okno");
+        footer.append(&show_button);
+
+        let window_weak = window.downgrade();
+        let close_button = gtk4::Button::with_label("✕");
+        close_button.set_tooltip_text(Some("Zamknij (Esc)"));
+        close_button.connect_clicked(move |_| {
+            if let Some(w) = window_weak.upgrade() {
+                w.close();
+            }
+        });
+        footer.append(&close_button);
+        vbox.append(&footer);
+
+        window.set_child(Some(&vbox));
+
+        let key_controller = gtk4::EventControllerKey::new();
+        let window_weak = window.downgrade();
+        let buttons: Vec<gtk4::Button> = pick_buttons.iter().map(|(_, b)| b.clone()).collect();
+        key_controller.connect_key_pressed(move |_, key, _, _| {
+            if key == gdk::Key::Escape {
+                if let Some(w) = window_weak.upgrade() {
+                    w.close();
+                }
+                return glib::Propagation::Stop;
+            }
+            match key.to_unicode().and_then(|c| c.to_digit(10)) {
+                Some(n @ 1..=9) => {
+                    if let Some(button) = buttons.get(n as usize - 1) {
+                        button.emit_clicked();
+                    }
+                    glib::Propagation::Stop
+                }
+                _ => glib::Propagation::Proceed,
+            }
+        });
+        window.add_controller(key_controller);
+
+        Self {
+            window,
+            pick_buttons,
+            show_button,
+            height,
+        }
+    }
+
+    /// Pokazuje okienko i przesuwa je pod kursor (jeśli platforma na to pozwala)
+    pub fn present_near_cursor(&self) {
+        self.window.present();
+
+        let Some(cursor) = crate::platform::cursor_position() else {
+            info!("Cursor position unavailable, popup placed by the window manager");
+            return;
+        };
+        let size = (POPUP_WIDTH, self.height);
+        let (x, y) = match monitor_at(cursor) {
+            Some(screen) => chooser::place_near(cursor, size, screen),
+            None => cursor,
+        };
+
+        // Okno musi być już zmapowane, żeby dało się je znaleźć po tytule
+        glib::timeout_add_local_once(std::time::Duration::from_millis(100), move || {
+            if let Err(e) = crate::platform::move_window(POPUP_TITLE, x, y) {
+                warn!("Could not move result popup: {}", e);
+            }
+        });
+    }
+
+    /// Wywoływane z indeksem panelu wybranego wyniku; okienko zamyka się przed wklejeniem
+    pub fn connect_pick<F: Fn(usize) + 'static>(&self, callback: F) {
+        let callback = Rc::new(callback);
+        for (index, button) in &self.pick_buttons {
+            let index = *index;
+            let callback = callback.clone();
+            let window_weak = self.window.downgrade();
+            button.connect_clicked(move |_| {
+                if let Some(w) = window_weak.upgrade() {
+                    w.close();
+                }
+                callback(index);
+            });
+        }
+    }
+
+    pub fn connect_show_window<F: Fn() + 'static>(&self, callback: F) {
+        let window_weak = self.window.downgrade();
+        self.show_button.connect_clicked(move |_| {
+            if let Some(w) = window_weak.upgrade() {
+                w.close();
+            }
+            callback();
+        });
+    }
+}
+
+/// Geometria monitora, na którym jest kursor
+pub(crate) fn monitor_at(point: (i32, i32)) -> Option<Area> {
+    let monitors = gdk::Display::default()?.monitors();
+    (0..monitors.n_items())
+        .filter_map(|i| monitors.item(i)?.downcast::<gdk::Monitor>().ok())
+        .map(|monitor| {
+            let geometry = monitor.geometry();
+            Area {
+                x: geometry.x(),
+                y: geometry.y(),
+                width: geometry.width(),
+                height: geometry.height(),
+            }
+        })
+        .find(|area| area.contains(point))
+}
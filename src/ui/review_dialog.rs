@@ -0,0 +1,227 @@
+use crate::review::Review;
+use gtk4::prelude::*;
+use gtk4::{gdk, glib};
+use libadwaita as adw;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Podpowiedź klawiszy; cyfry wyboru wersji sięgają liczby paneli (najwyżej 9)
+fn help_text(versions: usize) -> String {
+    format!(
+        "n / p – następna / poprzednia zmiana · 1–{} – wybierz wersję · 0 – zostaw oryginał · Enter – zatwierdź · Esc – zamknij",
+        versions.clamp(1, 9)
+    )
+}
+
+/// Widżety odświeżane po każdym ruchu w przeglądzie
+#[derive(Clone)]
+struct ReviewWidgets {
+    position: gtk4::Label,
+    context: gtk4::Label,
+    versions: Vec<(gtk4::Frame, gtk4::Label)>,
+    preview: gtk4::TextView,
+}
+
+/// Przegląd zmian sterowany klawiaturą: wersje wszystkich dostawców obok siebie
+pub struct ReviewDialog {
+    window: gtk4::Window,
+    review: Rc<RefCell<Review>>,
+    accept_button: gtk4::Button,
+}
+
+impl ReviewDialog {
+    pub fn new(parent: &adw::ApplicationWindow, names: &[&str], review: Review) -> Self {
+        let window = gtk4::Window::builder()
+            .title("Przegląd zmian")
+            .transient_for(parent)
+            .modal(true)
+            .default_width(1000)
+            .default_height(600)
+            .build();
+
+        let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 12);
+        vbox.set_margin_start(12);
+        vbox.set_margin_end(12);
+        vbox.set_margin_top(12);
+        vbox.set_margin_bottom(12);
+
+        let position = gtk4::Label::new(None);
+        position.add_css_class("panel-title");
+        position.set_halign(gtk4::Align::Start);
+        vbox.append(&position);
+
+        let context = gtk4::Label::new(None);
+        context.set_wrap(true);
+        context.set_xalign(0.0);
+        vbox.append(&context);
+
+        let grid = gtk4::Grid::builder()
+            .column_spacing(12)
+            .column_homogeneous(true)
+            .build();
+        let versions: Vec<_> = names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let frame = gtk4::Frame::new(Some(&format!("{} {}", i + 1, name)));
+                let label = gtk4::Label::new(None);
+                label.set_wrap(true);
+                label.set_xalign(0.0);
+                label.set_margin_start(8);
+                label.set_margin_end(8);
+                label.set_margin_top(8);
+                label.set_margin_bottom(8);
+                frame.set_child(Some(&label));
+                grid.attach(&frame, i as i32, 0, 1, 1);
+                (frame, label)
+            })
+            .collect();
+        vbox.append(&grid);
+
+        let help = gtk4::Label::new(Some(&help_text(names.len())));
+        help.add_css_class("dim-label");
+        help.set_halign(gtk4::Align::Start);
+        vbox.append(&help);
+
+        let preview = gtk4::TextView::builder()
+            .editable(false)
+            .wrap_mode(gtk4::WrapMode::Word)
+            .left_margin(8)
+            .right_margin(8)
+            .top_margin(8)
+            .bottom_margin(8)
+            .build();
+        let scrolled = gtk4::ScrolledWindow::builder()
+            .hscrollbar_policy(gtk4::PolicyType::Never)
+            .vexpand(true)
+            .child(&preview)
+            .build();
+        vbox.append(&scrolled);
+
+        let button_box = gtk4::Box::new(gtk4::Orientation::Horizontal, 6);
+        button_box.set_halign(gtk4::Align::End);
+
+        let window_weak = window.downgrade();
+        let close_button = gtk4::Button::with_label("Zamknij");
+        close_button.connect_clicked(move |_| {
+            if let Some(w) = window_weak.upgrade() {
+                w.close();
+            }
+        });
+        button_box.append(&close_button);
+
+        let accept_button = gtk4::Button::with_label("✅ Zatwierdź");
+        accept_button.add_css_class("suggested-action");
+        button_box.append(&accept_button);
+
+        vbox.append(&button_box);
+        window.set_child(Some(&vbox));
+
+        let widgets = ReviewWidgets {
+            position,
+            context,
+            versions,
+            preview,
+        };
+        let review = Rc::new(RefCell::new(review));
+        refresh(&widgets, &review.borrow());
+
+        let key_controller = gtk4::EventControllerKey::new();
+        key_controller.set_propagation_phase(gtk4::PropagationPhase::Capture);
+        let review_clone = review.clone();
+        let window_weak = window.downgrade();
+        let accept_clone = accept_button.clone();
+        key_controller.connect_key_pressed(move |_, key, _, _| {
+            match key {
+                gdk::Key::Return | gdk::Key::KP_Enter => {
+                    accept_clone.emit_clicked();
+                    return glib::Propagation::Stop;
+                }
+                gdk::Key::Escape => {
+                    if let Some(w) = window_weak.upgrade() {
+                        w.close();
+                    }
+                    return glib::Propagation::Stop;
+                }
+                _ => {}
+            }
+
+            let mut review = review_clone.borrow_mut();
+            match key.to_unicode() {
+                Some('n') => review.next(),
+                Some('p') => review.prev(),
+                Some('0') => review.keep_original(),
+                Some(c @ '1'..='9') => {
+                    review.accept(c as usize - '1' as usize);
+                }
+                _ => return glib::Propagation::Proceed,
+            }
+            refresh(&widgets, &review);
+            glib::Propagation::Stop
+        });
+        window.add_controller(key_controller);
+
+        Self {
+            window,
+            review,
+            accept_button,
+        }
+    }
+
+    pub fn present(&self) {
+        self.window.present();
+    }
+
+    /// Wywoływane z wynikiem złożonym z wybranych wersji
+    pub fn connect_accept<F: Fn(String) + 'static>(&self, callback: F) {
+        let review = self.review.clone();
+        let window_weak = self.window.downgrade();
+        self.accept_button.connect_clicked(move |_| {
+            callback(review.borrow().composite());
+            if let Some(w) = window_weak.upgrade() {
+                w.close();
+            }
+        });
+    }
+}
+
+fn refresh(widgets: &ReviewWidgets, review: &Review) {
+    widgets.preview.buffer().set_text(&review.composite());
+
+    let Some(hunk) = review.current() else {
+        widgets.position.set_text("Brak różnic między wynikami a oryginałem");
+        widgets.context.set_text("");
+        for (frame, label) in &widgets.versions {
+            frame.remove_css_class("review-chosen");
+            label.set_text("");
+        }
+        return;
+    };
+
+    widgets.position.set_text(&format!(
+        "Zmiana {}/{}",
+        review.current_index() + 1,
+        review.hunk_count()
+    ));
+
+    let (before, after) = review.context();
+    widgets.context.set_markup(&format!(
+        "…{}<b><s>{}</s></b>{}…",
+        glib::markup_escape_text(&before),
+        glib::markup_escape_text(&hunk.original),
+        glib::markup_escape_text(&after)
+    ));
+
+    for (i, (frame, label)) in widgets.versions.iter().enumerate() {
+        match hunk.versions.get(i) {
+            Some(Some(text)) if text.is_empty() => label.set_text("(usunięte)"),
+            Some(Some(text)) => label.set_text(text),
+            _ => label.set_text("—"),
+        }
+        if hunk.choice == Some(i) {
+            frame.add_css_class("review-chosen");
+        } else {
+            frame.remove_css_class("review-chosen");
+        }
+    }
+}
@@ -1,42 +1,96 @@
-use crate::config::{AiSettings, ApiKeys, Config, Models};
+use crate::api::{registry, Provider};
+use crate::config::{AiSettings, ApiKeys, Config, Models, ReasoningEffort, Verbosity};
 use std::path::PathBuf;
 
+/// Pole formularza, którego dotyczy błąd walidacji
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsField {
+    OpenAiKey,
+    AnthropicKey,
+    GeminiKey,
+    DeepSeekKey,
+    MistralKey,
+    GroqKey,
+    OpenAiModel,
+    AnthropicModel,
+    GeminiModel,
+    DeepSeekModel,
+    MistralModel,
+    GroqModel,
+}
+
+impl SettingsField {
+    /// Pole klucza API dostawcy
+    pub fn api_key(provider: Provider) -> Self {
+        match provider {
+            Provider::OpenAI => SettingsField::OpenAiKey,
+            Provider::Anthropic => SettingsField::AnthropicKey,
+            Provider::Gemini => SettingsField::GeminiKey,
+            Provider::DeepSeek => SettingsField::DeepSeekKey,
+            Provider::Mistral => SettingsField::MistralKey,
+            Provider::Groq => SettingsField::GroqKey,
+        }
+    }
+
+    /// Pole modelu dostawcy
+    pub fn model(provider: Provider) -> Self {
+        match provider {
+            Provider::OpenAI => SettingsField::OpenAiModel,
+            Provider::Anthropic => SettingsField::AnthropicModel,
+            Provider::Gemini => SettingsField::GeminiModel,
+            Provider::DeepSeek => SettingsField::DeepSeekModel,
+            Provider::Mistral => SettingsField::MistralModel,
+            Provider::Groq => SettingsField::GroqModel,
+        }
+    }
+}
+
+/// Stan formularza ustawień niezależny od widżetów: walidacja i zapis do `Config`
 #[derive(Debug, Clone)]
-pub struct SettingsDialog {
-    pub visible: bool,
+pub struct SettingsForm {
     pub temp_openai_key: String,
     pub temp_anthropic_key: String,
     pub temp_gemini_key: String,
     pub temp_deepseek_key: String,
+    pub temp_mistral_key: String,
+    pub temp_groq_key: String,
     pub temp_openai_model: String,
     pub temp_anthropic_model: String,
     pub temp_gemini_model: String,
     pub temp_deepseek_model: String,
-    pub temp_reasoning_effort: String,
-    pub temp_verbosity: String,
+    pub temp_mistral_model: String,
+    pub temp_groq_model: String,
+    /// Dostawcy paneli (`PanelProviders`); tylko oni muszą mieć klucz i model
+    pub temp_panel_providers: Vec<String>,
+    pub temp_reasoning_effort: ReasoningEffort,
+    pub temp_verbosity: Verbosity,
     pub validation_error: Option<String>,
 }
 
-impl Default for SettingsDialog {
+impl Default for SettingsForm {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl SettingsDialog {
+impl SettingsForm {
     pub fn new() -> Self {
         Self {
-            visible: false,
             temp_openai_key: String::new(),
             temp_anthropic_key: String::new(),
             temp_gemini_key: String::new(),
             temp_deepseek_key: String::new(),
+            temp_mistral_key: String::new(),
+            temp_groq_key: String::new(),
             temp_openai_model: String::new(),
             temp_anthropic_model: String::new(),
             temp_gemini_model: String::new(),
             temp_deepseek_model: String::new(),
-            temp_reasoning_effort: "high".to_string(),
-            temp_verbosity: "medium".to_string(),
+            temp_mistral_model: String::new(),
+            temp_groq_model: String::new(),
+            temp_panel_providers: Vec::new(),
+            temp_reasoning_effort: ReasoningEffort::High,
+            temp_verbosity: Verbosity::Medium,
             validation_error: None,
         }
     }
@@ -46,47 +100,66 @@ impl SettingsDialog {
         self.temp_anthropic_key = config.api_keys.anthropic.clone();
         self.temp_gemini_key = config.api_keys.gemini.clone();
         self.temp_deepseek_key = config.api_keys.deepseek.clone();
+        self.temp_mistral_key = config.api_keys.mistral.clone();
+        self.temp_groq_key = config.api_keys.groq.clone();
         self.temp_openai_model = config.models.openai.clone();
         self.temp_anthropic_model = config.models.anthropic.clone();
         self.temp_gemini_model = config.models.gemini.clone();
         self.temp_deepseek_model = config.models.deepseek.clone();
-        self.temp_reasoning_effort = config.ai_settings.reasoning_effort.clone();
-        self.temp_verbosity = config.ai_settings.verbosity.clone();
+        self.temp_mistral_model = config.models.mistral.clone();
+        self.temp_groq_model = config.models.groq.clone();
+        self.temp_panel_providers = config.settings.panel_providers.clone();
+        self.temp_reasoning_effort = config.ai_settings.reasoning_effort;
+        self.temp_verbosity = config.ai_settings.verbosity;
         self.validation_error = None;
     }
 
     /// Validate settings - ensure no empty API keys for enabled providers
     pub fn validate(&mut self) -> Result<(), String> {
-        // Check if any API key is empty
-        if self.temp_openai_key.trim().is_empty() {
-            return Err("OpenAI API key cannot be empty".to_string());
-        }
-        if self.temp_anthropic_key.trim().is_empty() {
-            return Err("Anthropic API key cannot be empty".to_string());
-        }
-        if self.temp_gemini_key.trim().is_empty() {
-            return Err("Gemini API key cannot be empty".to_string());
-        }
-        if self.temp_deepseek_key.trim().is_empty() {
-            return Err("DeepSeek API key cannot be empty".to_string());
+        if let Some((_, error)) = self.field_errors().into_iter().next() {
+            return Err(error);
         }
 
-        // Check if any model is empty
-        if self.temp_openai_model.trim().is_empty() {
-            return Err("OpenAI model cannot be empty".to_string());
-        }
-        if self.temp_anthropic_model.trim().is_empty() {
-            return Err("Anthropic model cannot be empty".to_string());
-        }
-        if self.temp_gemini_model.trim().is_empty() {
-            return Err("Gemini model cannot be empty".to_string());
+        self.validation_error = None;
+        Ok(())
+    }
+
+    fn key(&self, provider: Provider) -> &str {
+        match provider {
+            Provider::OpenAI => &self.temp_openai_key,
+            Provider::Anthropic => &self.temp_anthropic_key,
+            Provider::Gemini => &self.temp_gemini_key,
+            Provider::DeepSeek => &self.temp_deepseek_key,
+            Provider::Mistral => &self.temp_mistral_key,
+            Provider::Groq => &self.temp_groq_key,
         }
-        if self.temp_deepseek_model.trim().is_empty() {
-            return Err("DeepSeek model cannot be empty".to_string());
+    }
+
+    fn model(&self, provider: Provider) -> &str {
+        match provider {
+            Provider::OpenAI => &self.temp_openai_model,
+            Provider::Anthropic => &self.temp_anthropic_model,
+            Provider::Gemini => &self.temp_gemini_model,
+            Provider::DeepSeek => &self.temp_deepseek_model,
+            Provider::Mistral => &self.temp_mistral_model,
+            Provider::Groq => &self.temp_groq_model,
         }
+    }
 
-        self.validation_error = None;
-        Ok(())
+    /// Wszystkie błędy walidacji razem z polami, których dotyczą (do oznaczenia wierszy formularza)
+    pub fn field_errors(&self) -> Vec<(SettingsField, String)> {
+        let panels = registry::panel_specs(&self.temp_panel_providers);
+        let providers = registry::panel_providers(&panels);
+        let keys = providers.iter().filter(|p| self.key(**p).trim().is_empty()).map(|p| {
+            (SettingsField::api_key(*p), format!("{} API key cannot be empty", p.name()))
+        });
+        // Model dostawcy jest potrzebny tylko panelom bez własnego modelu
+        let models = providers
+            .iter()
+            .filter(|p| panels.iter().any(|panel| panel.provider == **p && panel.model.is_none()))
+            .filter(|p| self.model(**p).trim().is_empty())
+            .map(|p| (SettingsField::model(*p), format!("{} model cannot be empty", p.name())));
+        keys.chain(models).collect()
     }
 
     pub fn save_to_config(&mut self, config: &mut Config, path: &PathBuf) -> Result<(), String> {
@@ -97,6 +170,8 @@ impl SettingsDialog {
             anthropic: self.temp_anthropic_key.trim().to_string(),
             gemini: self.temp_gemini_key.trim().to_string(),
             deepseek: self.temp_deepseek_key.trim().to_string(),
+            mistral: self.temp_mistral_key.trim().to_string(),
+            groq: self.temp_groq_key.trim().to_string(),
         };
 
         config.models = Models {
@@ -104,12 +179,15 @@ impl SettingsDialog {
             anthropic: self.temp_anthropic_model.trim().to_string(),
             gemini: self.temp_gemini_model.trim().to_string(),
             deepseek: self.temp_deepseek_model.trim().to_string(),
+            mistral: self.temp_mistral_model.trim().to_string(),
+            groq: self.temp_groq_model.trim().to_string(),
         };
 
         config.ai_settings = AiSettings {
-            reasoning_effort: self.temp_reasoning_effort.clone(),
-            verbosity: self.temp_verbosity.clone(),
+            reasoning_effort: self.temp_reasoning_effort,
+            verbosity: self.temp_verbosity,
         };
+        config.settings.panel_providers = self.temp_panel_providers.clone();
 
         config
             .save(path)
@@ -124,286 +202,44 @@ impl SettingsDialog {
         self.temp_anthropic_key.clear();
         self.temp_gemini_key.clear();
         self.temp_deepseek_key.clear();
+        self.temp_mistral_key.clear();
+        self.temp_groq_key.clear();
         self.temp_openai_model.clear();
         self.temp_anthropic_model.clear();
         self.temp_gemini_model.clear();
         self.temp_deepseek_model.clear();
-        self.temp_reasoning_effort = "high".to_string();
-        self.temp_verbosity = "medium".to_string();
+        self.temp_mistral_model.clear();
+        self.temp_groq_model.clear();
+        self.temp_panel_providers.clear();
+        self.temp_reasoning_effort = ReasoningEffort::High;
+        self.temp_verbosity = Verbosity::Medium;
         self.validation_error = None;
     }
+}
 
-    /// Show the settings dialog
-    pub fn show(&mut self) {
-        self.visible = true;
-    }
-
-    /// Hide the settings dialog
-    pub fn hide(&mut self) {
-        self.visible = false;
-    }
-
-    pub fn show_window_content(&mut self, ctx: &egui::Context) -> Option<SettingsAction> {
-        let mut action = None;
-
-        egui::CentralPanel::default().show(ctx, |ui| {
-            ui.vertical_centered(|ui| {
-                ui.add_space(10.0);
-                action = self.render_content(ui);
-            });
-        });
-
-        action
-    }
-
-    pub fn show_window(&mut self, ctx: &egui::Context) -> Option<SettingsAction> {
-        let mut action = None;
-        let mut visible = self.visible;
-
-        egui::Window::new("⚙️ Ustawienia")
-            .open(&mut visible)
-            .resizable(true)
-            .default_width(620.0)
-            .default_height(850.0)
-            .collapsible(false)
-            .show(ctx, |ui| {
-                action = self.render_content(ui);
-            });
-
-        self.visible = visible;
-        action
-    }
-
-    fn render_content(&mut self, ui: &mut egui::Ui) -> Option<SettingsAction> {
-        let mut action = None;
-
-        egui::ScrollArea::vertical()
-            .auto_shrink(false)
-            .show(ui, |ui| {
-                ui.set_max_width(580.0);
-
-                if let Some(error) = &self.validation_error {
-                    egui::Frame::NONE
-                        .fill(egui::Color32::from_rgb(80, 20, 20))
-                        .stroke(egui::Stroke::new(1.0, egui::Color32::from_rgb(220, 60, 60)))
-                        .corner_radius(egui::CornerRadius::same(8))
-                        .inner_margin(egui::Margin::same(12))
-                        .show(ui, |ui| {
-                            ui.colored_label(
-                                egui::Color32::from_rgb(248, 113, 113),
-                                format!("❌ {}", error),
-                            );
-                        });
-                    ui.add_space(12.0);
-                }
-
-                let api_section = |ui: &mut egui::Ui,
-                                   icon: &str,
-                                   name: &str,
-                                   color: egui::Color32,
-                                   key: &mut String,
-                                   model: &mut String| {
-                    egui::Frame::NONE
-                        .fill(egui::Color32::from_rgb(38, 38, 46))
-                        .stroke(egui::Stroke::new(1.0, egui::Color32::from_rgb(55, 55, 65)))
-                        .corner_radius(egui::CornerRadius::same(10))
-                        .inner_margin(egui::Margin::same(16))
-                        .show(ui, |ui| {
-                            ui.horizontal(|ui| {
-                                ui.label(
-                                    egui::RichText::new(format!("{} {}", icon, name))
-                                        .size(15.0)
-                                        .strong()
-                                        .color(color),
-                                );
-                            });
-
-                            ui.add_space(8.0);
-
-                            ui.label(
-                                egui::RichText::new("API Key")
-                                    .size(12.0)
-                                    .color(egui::Color32::from_rgb(160, 160, 170)),
-                            );
-                            ui.add(
-                                egui::TextEdit::singleline(key)
-                                    .password(true)
-                                    .desired_width(f32::INFINITY),
-                            );
-
-                            ui.add_space(8.0);
-
-                            ui.label(
-                                egui::RichText::new("Model")
-                                    .size(12.0)
-                                    .color(egui::Color32::from_rgb(160, 160, 170)),
-                            );
-                            ui.add(egui::TextEdit::singleline(model).desired_width(f32::INFINITY));
-                        });
-                };
-
-                api_section(
-                    ui,
-                    "●",
-                    "OpenAI",
-                    egui::Color32::from_rgb(16, 163, 127),
-                    &mut self.temp_openai_key,
-                    &mut self.temp_openai_model,
-                );
-                ui.add_space(12.0);
-
-                api_section(
-                    ui,
-                    "●",
-                    "Anthropic",
-                    egui::Color32::from_rgb(217, 119, 6),
-                    &mut self.temp_anthropic_key,
-                    &mut self.temp_anthropic_model,
-                );
-                ui.add_space(12.0);
-
-                api_section(
-                    ui,
-                    "●",
-                    "Gemini",
-                    egui::Color32::from_rgb(66, 133, 244),
-                    &mut self.temp_gemini_key,
-                    &mut self.temp_gemini_model,
-                );
-                ui.add_space(12.0);
-
-                api_section(
-                    ui,
-                    "●",
-                    "DeepSeek",
-                    egui::Color32::from_rgb(124, 58, 237),
-                    &mut self.temp_deepseek_key,
-                    &mut self.temp_deepseek_model,
-                );
-                ui.add_space(12.0);
-
-                egui::Frame::NONE
-                    .fill(egui::Color32::from_rgb(38, 38, 46))
-                    .stroke(egui::Stroke::new(1.0, egui::Color32::from_rgb(55, 55, 65)))
-                    .corner_radius(egui::CornerRadius::same(10))
-                    .inner_margin(egui::Margin::same(16))
-                    .show(ui, |ui| {
-                        ui.label(
-                            egui::RichText::new("🤖 AI Settings")
-                                .size(15.0)
-                                .strong()
-                                .color(egui::Color32::from_rgb(140, 140, 200)),
-                        );
-
-                        ui.add_space(12.0);
-
-                        ui.horizontal(|ui| {
-                            ui.label(
-                                egui::RichText::new("Reasoning Effort:")
-                                    .size(12.0)
-                                    .color(egui::Color32::from_rgb(160, 160, 170)),
-                            );
-                            egui::ComboBox::from_id_salt("reasoning_effort")
-                                .selected_text(&self.temp_reasoning_effort)
-                                .width(150.0)
-                                .show_ui(ui, |ui| {
-                                    ui.selectable_value(
-                                        &mut self.temp_reasoning_effort,
-                                        "minimal".to_string(),
-                                        "Minimal",
-                                    );
-                                    ui.selectable_value(
-                                        &mut self.temp_reasoning_effort,
-                                        "low".to_string(),
-                                        "Low",
-                                    );
-                                    ui.selectable_value(
-                                        &mut self.temp_reasoning_effort,
-                                        "medium".to_string(),
-                                        "Medium",
-                                    );
-                                    ui.selectable_value(
-                                        &mut self.temp_reasoning_effort,
-                                        "high".to_string(),
-                                        "High",
-                                    );
-                                });
-                        });
-
-                        ui.add_space(8.0);
-
-                        ui.horizontal(|ui| {
-                            ui.label(
-                                egui::RichText::new("Verbosity:")
-                                    .size(12.0)
-                                    .color(egui::Color32::from_rgb(160, 160, 170)),
-                            );
-                            ui.add_space(40.0);
-                            egui::ComboBox::from_id_salt("verbosity")
-                                .selected_text(&self.temp_verbosity)
-                                .width(150.0)
-                                .show_ui(ui, |ui| {
-                                    ui.selectable_value(
-                                        &mut self.temp_verbosity,
-                                        "low".to_string(),
-                                        "Low",
-                                    );
-                                    ui.selectable_value(
-                                        &mut self.temp_verbosity,
-                                        "medium".to_string(),
-                                        "Medium",
-                                    );
-                                    ui.selectable_value(
-                                        &mut self.temp_verbosity,
-                                        "high".to_string(),
-                                        "High",
-                                    );
-                                });
-                        });
-                    });
-
-                ui.add_space(20.0);
-
-                ui.horizontal(|ui| {
-                    ui.spacing_mut().item_spacing.x = 12.0;
-
-                    let save_btn = egui::Button::new(
-                        egui::RichText::new("💾 Zapisz")
-                            .color(egui::Color32::WHITE)
-                            .size(14.0),
-                    )
-                    .fill(egui::Color32::from_rgb(34, 197, 94))
-                    .corner_radius(egui::CornerRadius::same(8))
-                    .min_size(egui::vec2(120.0, 38.0));
-
-                    if ui.add(save_btn).clicked() {
-                        action = Some(SettingsAction::Save);
-                    }
-
-                    let cancel_btn = egui::Button::new(
-                        egui::RichText::new("Anuluj")
-                            .color(egui::Color32::WHITE)
-                            .size(14.0),
-                    )
-                    .fill(egui::Color32::from_rgb(70, 70, 85))
-                    .corner_radius(egui::CornerRadius::same(8))
-                    .min_size(egui::vec2(120.0, 38.0));
-
-                    if ui.add(cancel_btn).clicked() {
-                        action = Some(SettingsAction::Cancel);
-                    }
-                });
-            });
-
-        action
-    }
+/// Zwija polskie znaki diakrytyczne, żeby „roznice” znajdowało „różnice” i odwrotnie
+fn fold(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .map(|c| match c {
+            'ą' => 'a',
+            'ć' => 'c',
+            'ę' => 'e',
+            'ł' => 'l',
+            'ń' => 'n',
+            'ó' => 'o',
+            'ś' => 's',
+            'ź' | 'ż' => 'z',
+            _ => c,
+        })
+        .collect()
 }
 
-/// Actions that can be triggered from the settings dialog
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum SettingsAction {
-    Save,
-    Cancel,
+/// Czy wiersz ustawień o podanych tekstach (tytuł, podtytuł) pasuje do wyszukiwania;
+/// każde słowo zapytania musi wystąpić w którymś z tekstów
+pub fn matches_search(texts: &[&str], query: &str) -> bool {
+    let haystack = fold(&texts.join(" "));
+    fold(query).split_whitespace().all(|word| haystack.contains(word))
 }
 
 #[cfg(test)]
@@ -413,8 +249,7 @@ mod tests {
 
     #[test]
     fn test_settings_dialog_new() {
-        let dialog = SettingsDialog::new();
-        assert!(!dialog.visible);
+        let dialog = SettingsForm::new();
         assert!(dialog.temp_openai_key.is_empty());
         assert!(dialog.temp_anthropic_key.is_empty());
         assert!(dialog.temp_gemini_key.is_empty());
@@ -424,7 +259,7 @@ mod tests {
 
     #[test]
     fn test_settings_load_from_config() {
-        let mut dialog = SettingsDialog::new();
+        let mut dialog = SettingsForm::new();
         let config = Config::default();
 
         dialog.load_from_config(&config);
@@ -437,7 +272,7 @@ mod tests {
 
     #[test]
     fn test_settings_validation_empty_openai_key() {
-        let mut dialog = SettingsDialog::new();
+        let mut dialog = SettingsForm::new();
         dialog.temp_openai_key = String::new();
         dialog.temp_anthropic_key = "test-key".to_string();
         dialog.temp_gemini_key = "test-key".to_string();
@@ -454,7 +289,7 @@ mod tests {
 
     #[test]
     fn test_settings_validation_empty_anthropic_key() {
-        let mut dialog = SettingsDialog::new();
+        let mut dialog = SettingsForm::new();
         dialog.temp_openai_key = "test-key".to_string();
         dialog.temp_anthropic_key = String::new();
         dialog.temp_gemini_key = "test-key".to_string();
@@ -471,7 +306,7 @@ mod tests {
 
     #[test]
     fn test_settings_validation_empty_gemini_key() {
-        let mut dialog = SettingsDialog::new();
+        let mut dialog = SettingsForm::new();
         dialog.temp_openai_key = "test-key".to_string();
         dialog.temp_anthropic_key = "test-key".to_string();
         dialog.temp_gemini_key = String::new();
@@ -488,7 +323,7 @@ mod tests {
 
     #[test]
     fn test_settings_validation_empty_deepseek_key() {
-        let mut dialog = SettingsDialog::new();
+        let mut dialog = SettingsForm::new();
         dialog.temp_openai_key = "test-key".to_string();
         dialog.temp_anthropic_key = "test-key".to_string();
         dialog.temp_gemini_key = "test-key".to_string();
@@ -505,7 +340,7 @@ mod tests {
 
     #[test]
     fn test_settings_validation_empty_model() {
-        let mut dialog = SettingsDialog::new();
+        let mut dialog = SettingsForm::new();
         dialog.temp_openai_key = "test-key".to_string();
         dialog.temp_anthropic_key = "test-key".to_string();
         dialog.temp_gemini_key = "test-key".to_string();
@@ -522,7 +357,7 @@ mod tests {
 
     #[test]
     fn test_settings_validation_success() {
-        let mut dialog = SettingsDialog::new();
+        let mut dialog = SettingsForm::new();
         dialog.temp_openai_key = "sk-test".to_string();
         dialog.temp_anthropic_key = "sk-ant-test".to_string();
         dialog.temp_gemini_key = "AIza-test".to_string();
@@ -539,7 +374,7 @@ mod tests {
 
     #[test]
     fn test_settings_validation_whitespace_only() {
-        let mut dialog = SettingsDialog::new();
+        let mut dialog = SettingsForm::new();
         dialog.temp_openai_key = "   ".to_string();
         dialog.temp_anthropic_key = "test-key".to_string();
         dialog.temp_gemini_key = "test-key".to_string();
@@ -553,12 +388,54 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_settings_field_errors_lists_every_empty_field() {
+        let mut dialog = SettingsForm::new();
+        dialog.load_from_config(&Config::default());
+        dialog.temp_openai_key = "sk-test".to_string();
+        dialog.temp_gemini_model = " ".to_string();
+
+        let fields: Vec<SettingsField> = dialog.field_errors().into_iter().map(|(f, _)| f).collect();
+        assert_eq!(
+            fields,
+            vec![
+                SettingsField::AnthropicKey,
+                SettingsField::GeminiKey,
+                SettingsField::DeepSeekKey,
+                SettingsField::GeminiModel,
+            ]
+        );
+        assert_eq!(dialog.validate().unwrap_err(), "Anthropic API key cannot be empty");
+    }
+
+    #[test]
+    fn test_settings_field_errors_follow_panel_providers() {
+        let mut dialog = SettingsForm::new();
+        dialog.load_from_config(&Config::default());
+        for key in [&mut dialog.temp_openai_key, &mut dialog.temp_anthropic_key, &mut dialog.temp_gemini_key] {
+            *key = "test-key".to_string();
+        }
+        assert_eq!(dialog.field_errors()[0].0, SettingsField::DeepSeekKey);
+
+        dialog.temp_panel_providers = vec!["Mistral".to_string()];
+        dialog.temp_mistral_model.clear();
+        let fields: Vec<SettingsField> = dialog.field_errors().into_iter().map(|(f, _)| f).collect();
+        assert_eq!(fields, vec![SettingsField::MistralKey, SettingsField::MistralModel]);
+
+        dialog.temp_mistral_key = "mistral-key".to_string();
+        dialog.temp_panel_providers = vec!["Mistral:ministral-8b-latest".to_string(), "Mistral".to_string()];
+        assert_eq!(dialog.field_errors()[0].0, SettingsField::MistralModel);
+
+        dialog.temp_panel_providers.pop();
+        assert!(dialog.validate().is_ok());
+    }
+
     #[test]
     fn test_settings_save_to_config() {
         let temp_file = NamedTempFile::new().unwrap();
         let temp_path = temp_file.path().to_path_buf();
 
-        let mut dialog = SettingsDialog::new();
+        let mut dialog = SettingsForm::new();
         dialog.temp_openai_key = "sk-openai-test".to_string();
         dialog.temp_anthropic_key = "sk-ant-test".to_string();
         dialog.temp_gemini_key = "AIza-test".to_string();
@@ -587,7 +464,7 @@ mod tests {
         let temp_file = NamedTempFile::new().unwrap();
         let temp_path = temp_file.path().to_path_buf();
 
-        let mut dialog = SettingsDialog::new();
+        let mut dialog = SettingsForm::new();
         dialog.temp_openai_key = "sk-openai-test".to_string();
         dialog.temp_anthropic_key = "sk-ant-test".to_string();
         dialog.temp_gemini_key = "AIza-test".to_string();
@@ -611,7 +488,7 @@ mod tests {
         let temp_file = NamedTempFile::new().unwrap();
         let temp_path = temp_file.path().to_path_buf();
 
-        let mut dialog = SettingsDialog::new();
+        let mut dialog = SettingsForm::new();
         dialog.temp_openai_key = String::new(); // Empty key
         dialog.temp_anthropic_key = "sk-ant-test".to_string();
         dialog.temp_gemini_key = "AIza-test".to_string();
@@ -628,9 +505,19 @@ mod tests {
         assert!(result.unwrap_err().contains("OpenAI"));
     }
 
+    #[test]
+    fn test_matches_search() {
+        let row = ["Podswietlaj roznice", "Zaznacz zmiany miedzy oryginalem a poprawionym tekstem"];
+        assert!(matches_search(&row, "różnice"));
+        assert!(matches_search(&row, "ZMIANY tekst"));
+        assert!(matches_search(&row, ""));
+        assert!(!matches_search(&row, "dźwięk"));
+        assert!(matches_search(&["Dzwiek po pierwszym wyniku"], "dźwięk"));
+    }
+
     #[test]
     fn test_settings_clear() {
-        let mut dialog = SettingsDialog::new();
+        let mut dialog = SettingsForm::new();
         dialog.temp_openai_key = "test".to_string();
         dialog.temp_anthropic_key = "test".to_string();
         dialog.temp_gemini_key = "test".to_string();
@@ -647,26 +534,4 @@ mod tests {
         assert!(dialog.temp_openai_model.is_empty());
         assert!(dialog.validation_error.is_none());
     }
-
-    #[test]
-    fn test_settings_show_hide() {
-        let mut dialog = SettingsDialog::new();
-        assert!(!dialog.visible);
-
-        dialog.show();
-        assert!(dialog.visible);
-
-        dialog.hide();
-        assert!(!dialog.visible);
-    }
-
-    #[test]
-    fn test_settings_action_enum() {
-        let save_action = SettingsAction::Save;
-        let cancel_action = SettingsAction::Cancel;
-
-        assert_eq!(save_action, SettingsAction::Save);
-        assert_eq!(cancel_action, SettingsAction::Cancel);
-        assert_ne!(save_action, cancel_action);
-    }
 }
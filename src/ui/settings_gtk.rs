@@ -1,44 +1,816 @@
-use crate::config::Config;
-use gtk4::glib;
+use crate::api::http_client::COMPRESS_MIN_BYTES;
+use crate::api::registry::{self, PanelSpec};
+use crate::api::Provider;
+use crate::audit::AuditLog;
+use crate::config::{Appearance, AuditSettings, BudgetSettings, Config, ConversationSettings, CustomStyle, DoublePress, EditModeSettings, GlobalHotkey, HistorySettings, LengthSettings, Network, Normalize, PasteGuard, MAX_DOUBLE_PRESS_MS};
+use crate::error::{QUICK_RETRIES, QUICK_TIMEOUT};
+use crate::history::History;
+use crate::hotkey::{self, DoublePressAction};
+use crate::prompts::{custom_style_errors, CorrectionStyle};
+use crate::shortcuts;
+use crate::usage::UsageTracker;
+use crate::ui::settings::{matches_search, SettingsField, SettingsForm};
 use gtk4::prelude::*;
+use gtk4::{gdk, glib};
 use libadwaita as adw;
 use libadwaita::prelude::*;
-use tracing::info;
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+use std::path::Path;
+use std::rc::Rc;
+use tracing::{info, warn};
 
+/// Okno ustawień; konfiguracja jest zapisywana tylko po kliknięciu „Zapisz”
 pub struct SettingsDialog {
-    dialog: adw::PreferencesWindow,
-    openai_key: gtk4::Entry,
-    openai_model: gtk4::Entry,
-    anthropic_key: gtk4::Entry,
-    anthropic_model: gtk4::Entry,
-    gemini_key: gtk4::Entry,
-    gemini_model: gtk4::Entry,
-    deepseek_key: gtk4::Entry,
-    deepseek_model: gtk4::Entry,
+    dialog: adw::Window,
+    toasts: adw::ToastOverlay,
+    save_button: gtk4::Button,
+    fields: SettingsFields,
+    /// Ponowne powiązanie skrótu portalu; ukryte bez portalu
+    portal_row: (adw::ActionRow, gtk4::Button),
+    /// Wiersze budżetów w kolejności `Provider::all()`, z wydatkami miesiąca
+    budget_rows: Vec<adw::ActionRow>,
+}
+
+#[derive(Clone)]
+struct SettingsFields {
+    /// Konfiguracja, na którą nakładane są pola formularza (podmieniana przy imporcie)
+    base: Rc<RefCell<Config>>,
+    /// Klucze API w kolejności `Provider::all()`
+    api_keys: Vec<adw::PasswordEntryRow>,
+    /// Modele w kolejności `Provider::all()`
+    models: Vec<adw::EntryRow>,
+    panels: PanelRows,
     highlight_diffs: gtk4::Switch,
+    learning_digest: gtk4::Switch,
+    record_history: gtk4::Switch,
+    encrypt_history: gtk4::Switch,
+    retention: adw::ComboRow,
+    retention_days: gtk4::SpinButton,
+    retention_entries: gtk4::SpinButton,
+    auto_hide_min_results: gtk4::SpinButton,
+    auto_present_results: gtk4::SpinButton,
+    results_popup: gtk4::Switch,
+    compact_hotkey_window: gtk4::Switch,
+    confirm_clipboard: gtk4::Switch,
+    rewrap_output: gtk4::Switch,
+    sound_first_result: gtk4::Switch,
+    sound_last_result: gtk4::Switch,
+    demo_mode: gtk4::Switch,
+    quick_mode: gtk4::Switch,
+    compress_requests: gtk4::Switch,
+    check_facts: gtk4::Switch,
+    agreement_heatmap: gtk4::Switch,
+    reduced_motion: gtk4::Switch,
+    minimal_emoji: gtk4::Switch,
+    conversation_enabled: gtk4::Switch,
+    conversation_exchanges: gtk4::SpinButton,
+    edit_mode_enabled: gtk4::Switch,
+    strip_quotes: gtk4::Switch,
+    unwrap_lines: gtk4::Switch,
+    strip_tracking: gtk4::Switch,
+    edit_mode_min_chars: gtk4::SpinButton,
+    preserve_length: gtk4::Switch,
+    /// Budżety w kolejności `Provider::all()`
+    budget_limits: Vec<gtk4::SpinButton>,
+    disable_at_limit: gtk4::Switch,
+    audit_enabled: gtk4::Switch,
+    audit_include_content: gtk4::Switch,
+    paste_guard_enabled: gtk4::Switch,
+    /// Fragmenty klas i tytułów okien oddzielone przecinkami
+    paste_guard_classes: adw::EntryRow,
+    paste_guard_titles: adw::EntryRow,
+    paste_guard_unknown: gtk4::Switch,
+    snippets: gtk4::TextView,
+    custom_styles: CustomStyleRows,
+    global_hotkey: adw::EntryRow,
+    /// Skróty stylów (styl, wiersz, tytuł); pusty wiersz to brak skrótu
+    style_hotkeys: Vec<(CorrectionStyle, adw::EntryRow, String)>,
+    double_press_action: adw::ComboRow,
+    double_press_ms: gtk4::SpinButton,
+    /// Wiersze pól sprawdzanych przez `SettingsForm::field_errors` z ich pierwotnymi tytułami
+    validated_rows: Vec<(SettingsField, adw::EntryRow, String)>,
+    /// Akceleratory akcji okna głównego (nazwa akcji, wiersz, tytuł)
+    shortcut_rows: Vec<(&'static str, adw::EntryRow, String)>,
 }
 
-fn create_entry_row(title: &str, value: &str, is_password: bool) -> (adw::ActionRow, gtk4::Entry) {
-    let row = adw::ActionRow::builder().title(title).build();
+impl SettingsFields {
+    fn field_errors(&self) -> Vec<(SettingsField, String)> {
+        let mut form = SettingsForm::new();
+        form.load_from_config(&self.to_config());
+        form.field_errors()
+    }
+
+    fn shortcut_accels(&self) -> Vec<(&'static str, String)> {
+        self.shortcut_rows
+            .iter()
+            .map(|(name, row, _)| (*name, row.text().to_string()))
+            .collect()
+    }
+
+    /// Nieparsowalne akceleratory i powtórzenia (błąd trafia do drugiego z pary)
+    fn shortcut_errors(&self) -> Vec<(&'static str, String)> {
+        let accels = self.shortcut_accels();
+        let mut errors: Vec<(&'static str, String)> = accels
+            .iter()
+            .filter(|(_, accel)| !accel.trim().is_empty() && gtk4::accelerator_parse(accel.trim()).is_none())
+            .map(|(name, _)| (*name, "nieprawidłowy skrót".to_string()))
+            .collect();
+        for (first, second) in shortcuts::conflicts(&accels) {
+            let label = shortcuts::ACTIONS
+                .iter()
+                .find(|a| a.name == first)
+                .map_or(first, |a| a.label);
+            errors.push((second, format!("ten sam skrót co „{}”", label)));
+        }
+        errors
+    }
+
+    fn global_hotkey_error(&self) -> Option<String> {
+        hotkey::normalize_combination(&self.global_hotkey.text()).err()
+    }
+
+    fn style_hotkey_errors(&self) -> Vec<(CorrectionStyle, String)> {
+        let styles: Vec<(CorrectionStyle, String)> = self
+            .style_hotkeys
+            .iter()
+            .map(|(style, row, _)| (*style, row.text().to_string()))
+            .collect();
+        hotkey::style_binding_errors(&self.global_hotkey.text(), &styles)
+    }
+
+    /// Zaznacza błędne wiersze i dopisuje komunikat do tytułu; poprawne wiersze są czyszczone
+    fn show_errors(&self, errors: &[(SettingsField, String)], shortcut_errors: &[(&str, String)]) {
+        set_row_error(&self.global_hotkey, GLOBAL_HOTKEY_TITLE, self.global_hotkey_error().as_deref());
+        let style_errors = self.style_hotkey_errors();
+        for (style, row, title) in &self.style_hotkeys {
+            let message = style_errors.iter().find(|(s, _)| s == style).map(|(_, m)| m.as_str());
+            set_row_error(row, title, message);
+        }
+        for (field, row, title) in &self.validated_rows {
+            let message = errors.iter().find(|(f, _)| f == field).map(|(_, m)| m.as_str());
+            set_row_error(row, title, message);
+        }
+        for (name, row, title) in &self.shortcut_rows {
+            let message = shortcut_errors.iter().find(|(n, _)| n == name).map(|(_, m)| m.as_str());
+            set_row_error(row, title, message);
+        }
+    }
+
+    fn to_config(&self) -> Config {
+        let mut config = Config {
+            settings: crate::config::Settings {
+                highlight_diffs: self.highlight_diffs.is_active(),
+                learning_digest: self.learning_digest.is_active(),
+                auto_hide_min_results: self.auto_hide_min_results.value() as u32,
+                auto_present_results: self.auto_present_results.value() as u32,
+                results_popup: self.results_popup.is_active(),
+                compact_hotkey_window: self.compact_hotkey_window.is_active(),
+                confirm_clipboard: self.confirm_clipboard.is_active(),
+                rewrap_output: self.rewrap_output.is_active(),
+                sound_first_result: self.sound_first_result.is_active(),
+                sound_last_result: self.sound_last_result.is_active(),
+                demo_mode: self.demo_mode.is_active(),
+                quick_mode: self.quick_mode.is_active(),
+                check_facts: self.check_facts.is_active(),
+                agreement_heatmap: self.agreement_heatmap.is_active(),
+                panel_providers: self.panels.entries(),
+                ..self.base.borrow().settings.clone()
+            },
+            network: Network {
+                compress_requests: self.compress_requests.is_active(),
+                ..self.base.borrow().network.clone()
+            },
+            snippets: {
+                let buffer = self.snippets.buffer();
+                crate::snippets::parse_snippets(&buffer.text(&buffer.start_iter(), &buffer.end_iter(), false))
+            },
+            custom_styles: self.custom_styles.styles(),
+            shortcuts: shortcuts::overrides_from(&self.shortcut_accels()),
+            history: HistorySettings {
+                enabled: self.record_history.is_active(),
+                encrypt: self.encrypt_history.is_active(),
+                retention: RETENTION_MODES
+                    .get(self.retention.selected() as usize)
+                    .map_or("all", |(mode, _)| *mode)
+                    .to_string(),
+                retention_days: self.retention_days.value() as u32,
+                retention_entries: self.retention_entries.value() as u32,
+            },
+            appearance: Appearance {
+                reduced_motion: self.reduced_motion.is_active(),
+                minimal_emoji: self.minimal_emoji.is_active(),
+            },
+            conversation: ConversationSettings {
+                enabled: self.conversation_enabled.is_active(),
+                exchanges: self.conversation_exchanges.value() as u32,
+            },
+            edit_mode: EditModeSettings {
+                enabled: self.edit_mode_enabled.is_active(),
+                min_chars: self.edit_mode_min_chars.value() as u32,
+            },
+            normalize: Normalize {
+                strip_quotes: self.strip_quotes.is_active(),
+                unwrap_lines: self.unwrap_lines.is_active(),
+                strip_tracking: self.strip_tracking.is_active(),
+            },
+            length: LengthSettings {
+                preserve: self.preserve_length.is_active(),
+                ..self.base.borrow().length.clone()
+            },
+            budget: BudgetSettings {
+                limits: Provider::all()
+                    .iter()
+                    .zip(&self.budget_limits)
+                    .filter(|(_, spin)| spin.value() > 0.0)
+                    .map(|(provider, spin)| (provider.name().to_string(), spin.value()))
+                    .collect(),
+                disable_at_limit: self.disable_at_limit.is_active(),
+            },
+            audit: AuditSettings {
+                enabled: self.audit_enabled.is_active(),
+                include_content: self.audit_include_content.is_active(),
+            },
+            paste_guard: PasteGuard {
+                enabled: self.paste_guard_enabled.is_active(),
+                blocked_classes: split_list(&self.paste_guard_classes.text()),
+                blocked_titles: split_list(&self.paste_guard_titles.text()),
+                allow_unknown_window: self.paste_guard_unknown.is_active(),
+            },
+            global_hotkey: {
+                let text = self.global_hotkey.text().to_string();
+                GlobalHotkey {
+                    combination: hotkey::normalize_combination(&text).unwrap_or(text),
+                    styles: self
+                        .style_hotkeys
+                        .iter()
+                        .map(|(style, row, _)| (row.text().trim().to_string(), *style))
+                        .filter(|(combination, _)| !combination.is_empty())
+                        .map(|(combination, style)| {
+                            (hotkey::normalize_combination(&combination).unwrap_or(combination), style)
+                        })
+                        .collect(),
+                }
+            },
+            double_press: DoublePress {
+                action: DoublePressAction::all()
+                    .get(self.double_press_action.selected() as usize)
+                    .unwrap_or(&DoublePressAction::None)
+                    .as_str()
+                    .to_string(),
+                window_ms: self.double_press_ms.value() as u64,
+            },
+            ..self.base.borrow().clone()
+        };
+        for ((provider, key), model) in Provider::all().iter().zip(&self.api_keys).zip(&self.models) {
+            provider.set_api_key(&mut config, &key.text());
+            provider.set_model(&mut config, &model.text());
+        }
+        config
+    }
+
+    /// Wpisuje konfigurację do wszystkich pól (po imporcie ustawień)
+    fn set_values(&self, config: &Config) {
+        *self.base.borrow_mut() = config.clone();
+        for ((provider, key), model) in Provider::all().iter().zip(&self.api_keys).zip(&self.models) {
+            key.set_text(provider.api_key(config));
+            model.set_text(provider.model(config));
+        }
+        self.panels.set(&registry::panel_specs(&config.settings.panel_providers));
+        self.highlight_diffs.set_active(config.settings.highlight_diffs);
+        self.learning_digest.set_active(config.settings.learning_digest);
+        self.record_history.set_active(config.history.enabled);
+        self.encrypt_history.set_active(config.history.encrypt);
+        self.retention.set_selected(retention_index(&config.history));
+        self.retention_days.set_value(config.history.retention_days as f64);
+        self.retention_entries.set_value(config.history.retention_entries as f64);
+        self.auto_hide_min_results.set_value(config.settings.auto_hide_min_results as f64);
+        self.auto_present_results.set_value(config.settings.auto_present_results as f64);
+        self.results_popup.set_active(config.settings.results_popup);
+        self.compact_hotkey_window.set_active(config.settings.compact_hotkey_window);
+        self.confirm_clipboard.set_active(config.settings.confirm_clipboard);
+        self.rewrap_output.set_active(config.settings.rewrap_output);
+        self.sound_first_result.set_active(config.settings.sound_first_result);
+        self.sound_last_result.set_active(config.settings.sound_last_result);
+        self.demo_mode.set_active(config.settings.demo_mode);
+        self.quick_mode.set_active(config.settings.quick_mode);
+        self.compress_requests.set_active(config.network.compress_requests);
+        self.check_facts.set_active(config.settings.check_facts);
+        self.agreement_heatmap.set_active(config.settings.agreement_heatmap);
+        self.reduced_motion.set_active(config.appearance.reduced_motion);
+        self.minimal_emoji.set_active(config.appearance.minimal_emoji);
+        self.conversation_enabled.set_active(config.conversation.enabled);
+        self.conversation_exchanges
+            .set_value(config.conversation.exchanges as f64);
+        self.edit_mode_enabled.set_active(config.edit_mode.enabled);
+        self.edit_mode_min_chars.set_value(config.edit_mode.min_chars as f64);
+        self.strip_quotes.set_active(config.normalize.strip_quotes);
+        self.unwrap_lines.set_active(config.normalize.unwrap_lines);
+        self.strip_tracking.set_active(config.normalize.strip_tracking);
+        self.preserve_length.set_active(config.length.preserve);
+        for (provider, spin) in Provider::all().iter().zip(&self.budget_limits) {
+            spin.set_value(config.budget.limit(provider.name()).unwrap_or(0.0));
+        }
+        self.disable_at_limit.set_active(config.budget.disable_at_limit);
+        self.audit_enabled.set_active(config.audit.enabled);
+        self.audit_include_content.set_active(config.audit.include_content);
+        self.paste_guard_enabled.set_active(config.paste_guard.enabled);
+        self.paste_guard_classes.set_text(&config.paste_guard.blocked_classes.join(", "));
+        self.paste_guard_titles.set_text(&config.paste_guard.blocked_titles.join(", "));
+        self.paste_guard_unknown.set_active(config.paste_guard.allow_unknown_window);
+        self.snippets
+            .buffer()
+            .set_text(&crate::snippets::format_snippets(&config.snippets));
+        self.custom_styles.set(&config.custom_styles);
+        self.global_hotkey.set_text(&config.global_hotkey.combination);
+        for (style, row, _) in &self.style_hotkeys {
+            row.set_text(style_hotkey(&config.global_hotkey, *style));
+        }
+        self.double_press_action
+            .set_selected(double_press_index(&config.double_press));
+        self.double_press_ms.set_value(config.double_press.window_ms as f64);
+        for (name, row, _) in &self.shortcut_rows {
+            let accel = shortcuts::ACTIONS
+                .iter()
+                .find(|a| a.name == *name)
+                .and_then(|a| shortcuts::effective_accel(a, &config.shortcuts));
+            row.set_text(accel.unwrap_or(""));
+        }
+    }
+}
+
+/// Lista paneli: wiersz na panel z dostawcą, własnym modelem i przyciskiem usuwania
+#[derive(Clone)]
+struct PanelRows {
+    group: adw::PreferencesGroup,
+    add_button: gtk4::Button,
+    rows: Rc<RefCell<Vec<PanelRow>>>,
+}
+
+#[derive(Clone)]
+struct PanelRow {
+    provider: adw::ComboRow,
+    model: gtk4::Entry,
+    remove_button: gtk4::Button,
+}
+
+impl PanelRows {
+    fn new(panels: &[PanelSpec]) -> Self {
+        let add_button = gtk4::Button::builder()
+            .icon_name("list-add-symbolic")
+            .tooltip_text("Dodaj panel")
+            .valign(gtk4::Align::Center)
+            .build();
+        add_button.add_css_class("flat");
+        let group = adw::PreferencesGroup::builder()
+            .title("Panele")
+            .description("Dostawcy odpytywani równolegle; ten sam dostawca może mieć kilka paneli z różnymi modelami. Zmiana działa po ponownym uruchomieniu")
+            .header_suffix(&add_button)
+            .build();
+        let rows = Self {
+            group,
+            add_button,
+            rows: Rc::new(RefCell::new(Vec::new())),
+        };
+        rows.set(panels);
+
+        let rows_clone = rows.clone();
+        rows.add_button.connect_clicked(move |_| {
+            rows_clone.push(&PanelSpec::new(Provider::all()[0]));
+        });
+        rows
+    }
 
-    let entry = gtk4::Entry::builder()
-        .text(value)
+    /// Podmienia wszystkie wiersze (po imporcie ustawień)
+    fn set(&self, panels: &[PanelSpec]) {
+        for row in self.rows.borrow_mut().drain(..) {
+            self.group.remove(&row.provider);
+        }
+        for panel in panels {
+            self.push(panel);
+        }
+    }
+
+    fn push(&self, panel: &PanelSpec) {
+        if self.rows.borrow().len() >= registry::MAX_PANELS {
+            return;
+        }
+        let selected = Provider::all().iter().position(|p| *p == panel.provider).unwrap_or(0);
+        let provider = adw::ComboRow::builder()
+            .model(&gtk4::StringList::new(&registry::PROVIDER_NAMES))
+            .selected(selected as u32)
+            .build();
+        let model = gtk4::Entry::builder()
+            .text(panel.model.as_deref().unwrap_or(""))
+            .placeholder_text("Model dostawcy")
+            .tooltip_text("Pusty: model z sekcji dostawcy")
+            .valign(gtk4::Align::Center)
+            .build();
+        let remove_button = gtk4::Button::builder()
+            .icon_name("list-remove-symbolic")
+            .tooltip_text("Usuń panel")
+            .valign(gtk4::Align::Center)
+            .build();
+        remove_button.add_css_class("flat");
+        provider.add_suffix(&model);
+        provider.add_suffix(&remove_button);
+        self.group.add(&provider);
+
+        let rows = self.clone();
+        let row = provider.clone();
+        remove_button.connect_clicked(move |_| rows.remove(&row));
+
+        self.rows.borrow_mut().push(PanelRow {
+            provider,
+            model,
+            remove_button,
+        });
+        self.refresh();
+    }
+
+    /// Usuwa panel; ostatniego nie da się usunąć
+    fn remove(&self, provider: &adw::ComboRow) {
+        let mut rows = self.rows.borrow_mut();
+        if rows.len() <= 1 {
+            return;
+        }
+        if let Some(index) = rows.iter().position(|row| row.provider == *provider) {
+            self.group.remove(&rows.remove(index).provider);
+        }
+        drop(rows);
+        self.refresh();
+    }
+
+    /// Numeruje panele i blokuje przyciski na granicach liczby paneli
+    fn refresh(&self) {
+        let rows = self.rows.borrow();
+        for (i, row) in rows.iter().enumerate() {
+            row.provider.set_title(&format!("Panel {}", i + 1));
+            row.remove_button.set_sensitive(rows.len() > 1);
+        }
+        self.add_button.set_sensitive(rows.len() < registry::MAX_PANELS);
+    }
+
+    /// Wpisy `PanelProviders` w kolejności wierszy
+    fn entries(&self) -> Vec<String> {
+        self.rows
+            .borrow()
+            .iter()
+            .filter_map(|row| {
+                let provider = *Provider::all().get(row.provider.selected() as usize)?;
+                let model = row.model.text().trim().to_string();
+                let panel = PanelSpec {
+                    provider,
+                    model: Some(model).filter(|m| !m.is_empty()),
+                };
+                Some(panel.to_entry())
+            })
+            .collect()
+    }
+}
+
+/// Własne style: rozwijany wiersz na styl z nazwą, poleceniem i promptem systemowym
+#[derive(Clone)]
+struct CustomStyleRows {
+    group: adw::PreferencesGroup,
+    rows: Rc<RefCell<Vec<CustomStyleRow>>>,
+}
+
+#[derive(Clone)]
+struct CustomStyleRow {
+    expander: adw::ExpanderRow,
+    name: adw::EntryRow,
+    instruction: adw::EntryRow,
+    system_prompt: adw::EntryRow,
+}
+
+impl CustomStyleRow {
+    fn style(&self) -> CustomStyle {
+        CustomStyle {
+            name: self.name.text().trim().to_string(),
+            instruction: self.instruction.text().trim().to_string(),
+            system_prompt: self.system_prompt.text().trim().to_string(),
+        }
+    }
+}
+
+impl CustomStyleRows {
+    fn new(styles: &[CustomStyle]) -> Self {
+        let add_button = gtk4::Button::builder()
+            .icon_name("list-add-symbolic")
+            .tooltip_text("Dodaj styl")
+            .valign(gtk4::Align::Center)
+            .build();
+        add_button.add_css_class("flat");
+        let group = adw::PreferencesGroup::builder()
+            .title("Wlasne style")
+            .description("Style w palecie stylow, pod wbudowanymi. Polecenie mowi modelowi, co zrobic z tekstem; pusty prompt systemowy to prompt standardowej korekty.")
+            .header_suffix(&add_button)
+            .build();
+        let rows = Self {
+            group,
+            rows: Rc::new(RefCell::new(Vec::new())),
+        };
+        rows.set(styles);
+
+        let rows_clone = rows.clone();
+        add_button.connect_clicked(move |_| {
+            let style = CustomStyle {
+                name: String::new(),
+                instruction: String::new(),
+                system_prompt: String::new(),
+            };
+            rows_clone.push(&style).set_expanded(true);
+        });
+        rows
+    }
+
+    /// Podmienia wszystkie wiersze (po imporcie ustawień)
+    fn set(&self, styles: &[CustomStyle]) {
+        for row in self.rows.borrow_mut().drain(..) {
+            self.group.remove(&row.expander);
+        }
+        for style in styles {
+            self.push(style);
+        }
+    }
+
+    fn push(&self, style: &CustomStyle) -> adw::ExpanderRow {
+        let expander = adw::ExpanderRow::builder().use_markup(false).build();
+        let name = create_entry_row("Nazwa", &style.name);
+        let instruction = create_entry_row("Polecenie", &style.instruction);
+        let system_prompt = create_entry_row("Prompt systemowy (opcjonalny)", &style.system_prompt);
+        expander.add_row(&name);
+        expander.add_row(&instruction);
+        expander.add_row(&system_prompt);
+
+        let remove_button = gtk4::Button::builder()
+            .icon_name("list-remove-symbolic")
+            .tooltip_text("Usun styl")
+            .valign(gtk4::Align::Center)
+            .build();
+        remove_button.add_css_class("flat");
+        expander.add_suffix(&remove_button);
+        self.group.add(&expander);
+
+        let rows = self.clone();
+        let row = expander.clone();
+        remove_button.connect_clicked(move |_| rows.remove(&row));
+        let row = expander.clone();
+        name.connect_changed(move |name| row.set_title(&style_title(&name.text())));
+        expander.set_title(&style_title(&style.name));
+
+        self.rows.borrow_mut().push(CustomStyleRow {
+            expander: expander.clone(),
+            name,
+            instruction,
+            system_prompt,
+        });
+        expander
+    }
+
+    fn remove(&self, expander: &adw::ExpanderRow) {
+        let mut rows = self.rows.borrow_mut();
+        if let Some(index) = rows.iter().position(|row| row.expander == *expander) {
+            self.group.remove(&rows.remove(index).expander);
+        }
+    }
+
+    /// Style w kolejności wierszy
+    fn styles(&self) -> Vec<CustomStyle> {
+        self.rows.borrow().iter().map(CustomStyleRow::style).collect()
+    }
+
+    /// Wpisuje błąd w podtytuł błędnych wierszy i czyści pozostałe; zwraca liczbę błędów
+    fn show_errors(&self) -> usize {
+        let errors = custom_style_errors(&self.styles());
+        for (i, row) in self.rows.borrow().iter().enumerate() {
+            match errors.iter().find(|(index, _)| *index == i) {
+                Some((_, message)) => {
+                    row.expander.add_css_class("error");
+                    row.expander.set_subtitle(message);
+                }
+                None => {
+                    row.expander.remove_css_class("error");
+                    row.expander.set_subtitle("");
+                }
+            }
+        }
+        errors.len()
+    }
+}
+
+/// Tytuł wiersza stylu; nowy styl nie ma jeszcze nazwy
+fn style_title(name: &str) -> String {
+    match name.trim() {
+        "" => "Nowy styl".to_string(),
+        name => name.to_string(),
+    }
+}
+
+/// Tryby przechowywania historii (wartość w konfiguracji, etykieta)
+const RETENTION_MODES: &[(&str, &str)] = &[
+    ("all", "Wszystko"),
+    ("days", "Ostatnie N dni"),
+    ("entries", "Ostatnie N wpisow"),
+    ("none", "Nie zapisuj"),
+];
+
+fn retention_index(history: &HistorySettings) -> u32 {
+    RETENTION_MODES
+        .iter()
+        .position(|(mode, _)| mode.eq_ignore_ascii_case(&history.retention))
+        .unwrap_or(0) as u32
+}
+
+fn double_press_index(double_press: &DoublePress) -> u32 {
+    let action = DoublePressAction::from_config(&double_press.action);
+    DoublePressAction::all()
+        .iter()
+        .position(|a| *a == action)
+        .unwrap_or(0) as u32
+}
+
+const GLOBAL_HOTKEY_TITLE: &str = "Popraw zaznaczony tekst (w dowolnej aplikacji)";
+
+/// Kombinacja przypisana do stylu albo pusty napis
+fn style_hotkey(config: &GlobalHotkey, style: CorrectionStyle) -> &str {
+    config
+        .styles
+        .iter()
+        .find(|(_, s)| **s == style)
+        .map_or("", |(combination, _)| combination.as_str())
+}
+
+/// Wiersz skrótu z przyciskiem nagrywania
+fn create_hotkey_row(title: &str, combination: &str) -> adw::EntryRow {
+    let row = create_entry_row(title, combination);
+    let record_button = gtk4::Button::builder()
+        .icon_name("media-record-symbolic")
+        .tooltip_text("Nagraj kombinację")
         .valign(gtk4::Align::Center)
-        .hexpand(true)
-        .visibility(!is_password)
         .build();
+    record_button.add_css_class("flat");
+    row.add_suffix(&record_button);
+    connect_hotkey_recorder(&row, &record_button, title);
+    let title = title.to_string();
+    row.connect_changed(move |row| set_row_error(row, &title, None));
+    row
+}
+
+/// Przycisk nagrywania: następna kombinacja z modyfikatorem trafia do wiersza, Esc anuluje
+fn connect_hotkey_recorder(row: &adw::EntryRow, button: &gtk4::Button, title: &str) {
+    let title = Rc::new(title.to_string());
+    let recording = Rc::new(Cell::new(false));
+    let previous = Rc::new(RefCell::new(String::new()));
 
-    if is_password {
-        entry.add_css_class("monospace");
+    let recording_clone = recording.clone();
+    let previous_clone = previous.clone();
+    let row_clone = row.clone();
+    let title_clone = title.clone();
+    button.connect_clicked(move |_| {
+        recording_clone.set(true);
+        *previous_clone.borrow_mut() = row_clone.text().to_string();
+        row_clone.set_text("");
+        row_clone.set_title(&format!("{} – naciśnij kombinację (Esc anuluje)", title_clone));
+        row_clone.grab_focus();
+    });
+
+    let controller = gtk4::EventControllerKey::new();
+    controller.set_propagation_phase(gtk4::PropagationPhase::Capture);
+    let row_clone = row.clone();
+    controller.connect_key_pressed(move |_, keyval, _, modifiers| {
+        if !recording.get() {
+            return glib::Propagation::Proceed;
+        }
+        if keyval == gdk::Key::Escape {
+            recording.set(false);
+            row_clone.set_text(&previous.borrow());
+            set_row_error(&row_clone, &title, None);
+            return glib::Propagation::Stop;
+        }
+        let name = keyval.to_lower().name().map(|n| n.to_string()).unwrap_or_default();
+        // Sam modyfikator - czekamy na klawisz
+        if let Some(combination) = hotkey::combination_from_key(
+            &name,
+            modifiers.contains(gdk::ModifierType::CONTROL_MASK),
+            modifiers.contains(gdk::ModifierType::SHIFT_MASK),
+            modifiers.contains(gdk::ModifierType::ALT_MASK),
+            modifiers.contains(gdk::ModifierType::SUPER_MASK),
+        ) {
+            recording.set(false);
+            row_clone.set_text(&combination);
+            set_row_error(&row_clone, &title, None);
+        }
+        glib::Propagation::Stop
+    });
+    row.add_controller(controller);
+}
+
+fn set_row_error(row: &adw::EntryRow, title: &str, message: Option<&str>) {
+    match message {
+        Some(message) => {
+            row.add_css_class("error");
+            row.set_title(&format!("{} – {}", title, message));
+        }
+        None => {
+            row.remove_css_class("error");
+            row.set_title(title);
+        }
     }
+}
+
+fn create_switch_row(title: &str, subtitle: &str, active: bool) -> (adw::ActionRow, gtk4::Switch) {
+    let row = adw::ActionRow::builder()
+        .title(title)
+        .subtitle(subtitle)
+        .build();
+
+    let switch = gtk4::Switch::builder()
+        .valign(gtk4::Align::Center)
+        .active(active)
+        .build();
+    row.add_suffix(&switch);
+    row.set_activatable_widget(Some(&switch));
+
+    (row, switch)
+}
+
+fn create_budget_row(provider: &str, value: f64) -> (adw::ActionRow, gtk4::SpinButton) {
+    let row = adw::ActionRow::builder()
+        .title(format!("Budzet {}", provider))
+        .subtitle("USD na miesiac")
+        .build();
+
+    let spin = gtk4::SpinButton::with_range(0.0, 10_000.0, 0.5);
+    spin.set_digits(2);
+    spin.set_value(value);
+    spin.set_valign(gtk4::Align::Center);
+    row.add_suffix(&spin);
+
+    (row, spin)
+}
+
+fn create_spin_row(title: &str, subtitle: &str, value: u32, max: u32) -> (adw::ActionRow, gtk4::SpinButton) {
+    let row = adw::ActionRow::builder()
+        .title(title)
+        .subtitle(subtitle)
+        .build();
+
+    let spin = gtk4::SpinButton::with_range(0.0, max as f64, 1.0);
+    spin.set_value(value as f64);
+    spin.set_valign(gtk4::Align::Center);
+    row.add_suffix(&spin);
+
+    (row, spin)
+}
+
+/// Elementy listy wpisanej jako "a, b, c"; puste są pomijane
+fn split_list(text: &str) -> Vec<String> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
 
-    row.add_suffix(&entry);
-    (row, entry)
+fn create_entry_row(title: &str, value: &str) -> adw::EntryRow {
+    let row = adw::EntryRow::builder().title(title).build();
+    row.set_text(value);
+    row
+}
+
+/// Zamaskowany klucz z wbudowanym przełącznikiem podglądu i przyciskiem kopiowania
+fn create_key_row(title: &str, value: &str, toasts: &adw::ToastOverlay) -> adw::PasswordEntryRow {
+    let row = adw::PasswordEntryRow::builder().title(title).build();
+    row.set_text(value);
+
+    let copy_button = gtk4::Button::builder()
+        .icon_name("edit-copy-symbolic")
+        .tooltip_text("Kopiuj klucz do schowka")
+        .valign(gtk4::Align::Center)
+        .build();
+    copy_button.add_css_class("flat");
+    let row_weak = row.downgrade();
+    let toasts = toasts.clone();
+    copy_button.connect_clicked(move |_| {
+        let Some(row) = row_weak.upgrade() else {
+            return;
+        };
+        if row.text().is_empty() {
+            return;
+        }
+        row.clipboard().set_text(&row.text());
+        toasts.add_toast(adw::Toast::new("Skopiowano klucz do schowka"));
+    });
+    row.add_suffix(&copy_button);
+
+    row
 }
 
 impl SettingsDialog {
     pub fn new(parent: &adw::ApplicationWindow, config: &Config) -> Self {
-        let dialog = adw::PreferencesWindow::builder()
+        let dialog = adw::Window::builder()
             .title("Ustawienia")
             .transient_for(parent)
             .modal(true)
@@ -46,98 +818,721 @@ impl SettingsDialog {
             .default_height(700)
             .build();
 
+        let stack = adw::ViewStack::new();
+        let toasts = adw::ToastOverlay::new();
+
         let api_page = adw::PreferencesPage::builder()
             .title("API")
             .icon_name("network-server-symbolic")
             .build();
 
-        let openai_group = adw::PreferencesGroup::builder().title("OpenAI").build();
+        let mut api_keys = Vec::new();
+        let mut models = Vec::new();
+        for provider in Provider::all() {
+            let info = provider.info();
+            let group = adw::PreferencesGroup::builder().title(info.name).build();
 
-        let (openai_key_row, openai_key) =
-            create_entry_row("Klucz API", &config.api_keys.openai, true);
-        openai_group.add(&openai_key_row);
+            let key = create_key_row("Klucz API", provider.api_key(config), &toasts);
+            group.add(&key);
 
-        let (openai_model_row, openai_model) =
-            create_entry_row("Model", &config.models.openai, false);
-        openai_group.add(&openai_model_row);
+            let model = create_entry_row("Model", provider.model(config));
+            model.set_tooltip_text(Some(&format!("Domyślnie: {}", info.default_model)));
+            group.add(&model);
 
-        api_page.add(&openai_group);
+            api_page.add(&group);
+            api_keys.push(key);
+            models.push(model);
+        }
 
-        let anthropic_group = adw::PreferencesGroup::builder().title("Anthropic").build();
+        let panels = PanelRows::new(&registry::panel_specs(&config.settings.panel_providers));
+        api_page.add(&panels.group);
 
-        let (anthropic_key_row, anthropic_key) =
-            create_entry_row("Klucz API", &config.api_keys.anthropic, true);
-        anthropic_group.add(&anthropic_key_row);
+        add_page(&stack, &api_page, "api");
 
-        let (anthropic_model_row, anthropic_model) =
-            create_entry_row("Model", &config.models.anthropic, false);
-        anthropic_group.add(&anthropic_model_row);
+        let settings_page = adw::PreferencesPage::builder()
+            .title("Ustawienia")
+            .icon_name("emblem-system-symbolic")
+            .build();
 
-        api_page.add(&anthropic_group);
+        let display_group = adw::PreferencesGroup::builder()
+            .title("Wyswietlanie")
+            .build();
 
-        let gemini_group = adw::PreferencesGroup::builder().title("Gemini").build();
+        let (highlight_row, highlight_diffs) = create_switch_row(
+            "Podswietlaj roznice",
+            "Zaznacz zmiany miedzy oryginalem a poprawionym tekstem",
+            config.settings.highlight_diffs,
+        );
 
-        let (gemini_key_row, gemini_key) =
-            create_entry_row("Klucz API", &config.api_keys.gemini, true);
-        gemini_group.add(&gemini_key_row);
+        display_group.add(&highlight_row);
 
-        let (gemini_model_row, gemini_model) =
-            create_entry_row("Model", &config.models.gemini, false);
-        gemini_group.add(&gemini_model_row);
+        let (check_facts_row, check_facts) = create_switch_row(
+            "Sprawdzaj liczby, daty i linki",
+            "Ostrzegaj, gdy w wyniku brakuje liczby, daty lub adresu z oryginalu albo pojawia sie nowy (poza tlumaczeniem)",
+            config.settings.check_facts,
+        );
+        display_group.add(&check_facts_row);
 
-        api_page.add(&gemini_group);
+        let (heatmap_row, agreement_heatmap) = create_switch_row(
+            "Mapa zgodnosci zdan",
+            "Tlo zdan, co do ktorych modele sie roznia: zolte - czesciowo, czerwone - wyraznie",
+            config.settings.agreement_heatmap,
+        );
+        display_group.add(&heatmap_row);
 
-        let deepseek_group = adw::PreferencesGroup::builder().title("DeepSeek").build();
+        let (demo_row, demo_mode) = create_switch_row(
+            "Tryb demonstracyjny",
+            "Sztuczne poprawki bez kluczy API i sieci (do wyprobowania okna i zrzutow ekranu)",
+            config.settings.demo_mode,
+        );
+        display_group.add(&demo_row);
 
-        let (deepseek_key_row, deepseek_key) =
-            create_entry_row("Klucz API", &config.api_keys.deepseek, true);
-        deepseek_group.add(&deepseek_key_row);
+        let (quick_row, quick_mode) = create_switch_row(
+            "Tryb szybki",
+            &format!(
+                "Krotszy limit czasu ({} s) i ponowienia po bledzie polaczenia (maks. {}) - do krotkich poprawek na biezaco",
+                QUICK_TIMEOUT, QUICK_RETRIES
+            ),
+            config.settings.quick_mode,
+        );
+        display_group.add(&quick_row);
 
-        let (deepseek_model_row, deepseek_model) =
-            create_entry_row("Model", &config.models.deepseek, false);
-        deepseek_group.add(&deepseek_model_row);
+        let (compress_row, compress_requests) = create_switch_row(
+            "Kompresja duzych zapytan",
+            &format!(
+                "Zapytania od {} kB wysylane jako gzip, mniej danych na laczach z limitem (tylko dostawcy, ktorzy to przyjmuja)",
+                COMPRESS_MIN_BYTES / 1024
+            ),
+            config.network.compress_requests,
+        );
+        display_group.add(&compress_row);
+        settings_page.add(&display_group);
 
-        api_page.add(&deepseek_group);
+        let appearance_group = adw::PreferencesGroup::builder().title("Wygląd").build();
 
-        dialog.add(&api_page);
+        let (reduced_motion_row, reduced_motion) = create_switch_row(
+            "Ogranicz ruch",
+            "Bez animacji i kręcących się wskaźników; stan paneli pokazuje tekst",
+            config.appearance.reduced_motion,
+        );
+        appearance_group.add(&reduced_motion_row);
 
-        let settings_page = adw::PreferencesPage::builder()
-            .title("Ustawienia")
-            .icon_name("emblem-system-symbolic")
+        let (minimal_emoji_row, minimal_emoji) = create_switch_row(
+            "Bez emoji",
+            "Zwykłe ikony stanu paneli, komunikaty i przyciski bez emoji (przywrócenie emoji przycisków po ponownym uruchomieniu)",
+            config.appearance.minimal_emoji,
+        );
+        appearance_group.add(&minimal_emoji_row);
+        settings_page.add(&appearance_group);
+
+        let window_group = adw::PreferencesGroup::builder()
+            .title("Okno")
             .build();
 
-        let display_group = adw::PreferencesGroup::builder()
-            .title("Wyswietlanie")
+        let (auto_present_row, auto_present_results) = create_spin_row(
+            "Pokaz okno po wynikach",
+            "Liczba gotowych wynikow, po ktorej okno pojawia sie samo (0 = od razu)",
+            config.settings.auto_present_results,
+            4,
+        );
+        window_group.add(&auto_present_row);
+
+        let (auto_hide_row, auto_hide_min_results) = create_spin_row(
+            "Ukryj okno po wyborze",
+            "Od tej liczby wynikow wybor chowa okno i wkleja tekst; wczesniej wynik trafia tylko do schowka (0 = zawsze)",
+            config.settings.auto_hide_min_results,
+            4,
+        );
+        window_group.add(&auto_hide_row);
+
+        let (results_popup_row, results_popup) = create_switch_row(
+            "Wybor wyniku przy kursorze",
+            "Po zakonczeniu pokaz male okienko z wynikami przy myszy zamiast duzego okna",
+            config.settings.results_popup,
+        );
+        window_group.add(&results_popup_row);
+
+        let (compact_row, compact_hotkey_window) = create_switch_row(
+            "Kompaktowe okno po skrocie",
+            "Skrot globalny pokazuje same panele w oknie bez ramki i paska narzedzi; uruchomienie z menu lub zasobnika pokazuje pelne okno",
+            config.settings.compact_hotkey_window,
+        );
+        window_group.add(&compact_row);
+
+        let (confirm_clipboard_row, confirm_clipboard) = create_switch_row(
+            "Potwierdzaj wyslanie schowka",
+            "Po skrocie pokaz poczatek tekstu i wyslij go do API dopiero po kliknieciu Przetworz",
+            config.settings.confirm_clipboard,
+        );
+        window_group.add(&confirm_clipboard_row);
+
+        let (rewrap_row, rewrap_output) = create_switch_row(
+            "Lam wynik jak oryginal",
+            "Gdy tekst byl lamany recznie (np. opis commita na 72 kolumnach), wklejany wynik jest lamany do tej samej szerokosci",
+            config.settings.rewrap_output,
+        );
+        window_group.add(&rewrap_row);
+
+        let (sound_first_row, sound_first_result) = create_switch_row(
+            "Dzwiek po pierwszym wyniku",
+            "Gdy okno jest schowane (canberra-gtk-play lub sygnal systemowy)",
+            config.settings.sound_first_result,
+        );
+        window_group.add(&sound_first_row);
+
+        let (sound_last_row, sound_last_result) = create_switch_row(
+            "Dzwiek po ostatnim wyniku",
+            "Gdy okno jest schowane i wszystkie API skonczyly",
+            config.settings.sound_last_result,
+        );
+        window_group.add(&sound_last_row);
+
+        settings_page.add(&window_group);
+
+        let conversation_group = adw::PreferencesGroup::builder()
+            .title("Tryb rozmowy")
             .build();
 
-        let highlight_row = adw::ActionRow::builder()
-            .title("Podswietlaj roznice")
-            .subtitle("Zaznacz zmiany miedzy oryginalem a poprawionym tekstem")
+        let (conversation_row, conversation_enabled) = create_switch_row(
+            "Dopracowywanie wynikow",
+            "Pole pod panelami wysyla polecenie (np. \"krocej\") do kazdego dostawcy razem z jego poprzednimi odpowiedziami",
+            config.conversation.enabled,
+        );
+        conversation_group.add(&conversation_row);
+
+        let (exchanges_row, conversation_exchanges) = create_spin_row(
+            "Pamietane wymiany",
+            "Ile ostatnich wymian z dostawca wysylac jako kontekst",
+            config.conversation.exchanges,
+            10,
+        );
+        conversation_group.add(&exchanges_row);
+        settings_page.add(&conversation_group);
+
+        let edit_mode_group = adw::PreferencesGroup::builder()
+            .title("Oszczedny tryb")
             .build();
 
-        let highlight_diffs = gtk4::Switch::builder()
-            .valign(gtk4::Align::Center)
-            .active(config.settings.highlight_diffs)
+        let (edit_mode_row, edit_mode_enabled) = create_switch_row(
+            "Tylko lista zmian dla dlugich tekstow",
+            "Model odsyla same poprawki, a aplikacja nanosi je lokalnie; gdy nie pasuja, tekst jest przepisywany w calosci",
+            config.edit_mode.enabled,
+        );
+        edit_mode_group.add(&edit_mode_row);
+
+        let (min_chars_row, edit_mode_min_chars) = create_spin_row(
+            "Dlugi tekst od",
+            "Liczba znakow, od ktorej wlacza sie lista zmian",
+            config.edit_mode.min_chars,
+            100_000,
+        );
+        edit_mode_group.add(&min_chars_row);
+        settings_page.add(&edit_mode_group);
+
+        let normalize_group = adw::PreferencesGroup::builder()
+            .title("Przygotowanie tekstu")
+            .description("Porzadkowanie tekstu przed wyslaniem do dostawcow")
             .build();
-        highlight_row.add_suffix(&highlight_diffs);
-        highlight_row.set_activatable_widget(Some(&highlight_diffs));
 
-        display_group.add(&highlight_row);
-        settings_page.add(&display_group);
+        let (strip_quotes_row, strip_quotes) = create_switch_row(
+            "Usun znaki cytowania",
+            "Znaki \">\" z poczatku wierszy cytowanego maila",
+            config.normalize.strip_quotes,
+        );
+        normalize_group.add(&strip_quotes_row);
+
+        let (unwrap_lines_row, unwrap_lines) = create_switch_row(
+            "Sklejaj lamane wiersze",
+            "Wiersze akapitu lamane recznie (np. na 72 znakach) laczone w jeden; listy i naglowki zostaja",
+            config.normalize.unwrap_lines,
+        );
+        normalize_group.add(&unwrap_lines_row);
+
+        let (strip_tracking_row, strip_tracking) = create_switch_row(
+            "Usun parametry sledzace z linkow",
+            "utm_*, fbclid, gclid i podobne z adresow URL",
+            config.normalize.strip_tracking,
+        );
+        normalize_group.add(&strip_tracking_row);
+        settings_page.add(&normalize_group);
+
+        let length_group = adw::PreferencesGroup::builder()
+            .title("Dlugosc wyniku")
+            .build();
+
+        let (preserve_length_row, preserve_length) = create_switch_row(
+            "Zachowaj dlugosc (+-10%)",
+            "Model ma zachowac liczbe slow; wieksza zmiana jest oznaczana ostrzezeniem pod panelem",
+            config.length.preserve,
+        );
+        length_group.add(&preserve_length_row);
+        settings_page.add(&length_group);
+
+        let budget_group = adw::PreferencesGroup::builder()
+            .title("Budzet miesieczny")
+            .description("Koszt jest liczony z cennika modeli i liczby tokenow podanej przez dostawce (bez niej policzonej lokalnie); 0 oznacza brak budzetu")
+            .build();
+
+        let mut budget_limits = Vec::new();
+        let mut budget_rows = Vec::new();
+        for provider in Provider::all() {
+            let (row, spin) = create_budget_row(provider.name(), config.budget.limit(provider.name()).unwrap_or(0.0));
+            budget_group.add(&row);
+            budget_limits.push(spin);
+            budget_rows.push(row);
+        }
+
+        let (disable_at_limit_row, disable_at_limit) = create_switch_row(
+            "Wylacz dostawce po przekroczeniu",
+            "Do konca miesiaca dostawca jest pomijany; ostrzezenie pojawia sie juz przy 80% budzetu",
+            config.budget.disable_at_limit,
+        );
+        budget_group.add(&disable_at_limit_row);
+        settings_page.add(&budget_group);
+
+        let audit_group = adw::PreferencesGroup::builder()
+            .title("Dziennik audytu")
+            .description("Kto, kiedy i ktorego dostawce wywolal - zapisywane w audit.jsonl obok konfiguracji")
+            .build();
+
+        let (audit_enabled_row, audit_enabled) = create_switch_row(
+            "Zapisuj wywolania dostawcow",
+            "Uzytkownik, czas, dostawca, model, styl i dlugosc tekstu",
+            config.audit.enabled,
+        );
+        audit_group.add(&audit_enabled_row);
+
+        let (audit_content_row, audit_include_content) = create_switch_row(
+            "Zapisuj tresc",
+            "Dolacza do dziennika tekst i wynik; domyslnie tresc nie jest zapisywana",
+            config.audit.include_content,
+        );
+        audit_group.add(&audit_content_row);
+
+        let audit_export_row = create_button_row("Eksportuj dziennik (CSV)", "document-save-symbolic");
+        audit_group.add(&audit_export_row.0);
+        settings_page.add(&audit_group);
+
+        let paste_guard_group = adw::PreferencesGroup::builder()
+            .title("Bezpieczne wklejanie")
+            .description("Do pasujacych okien wynik jest tylko kopiowany, bez symulowanego Ctrl+V")
+            .build();
+        let (paste_guard_row, paste_guard_enabled) = create_switch_row(
+            "Nie wklejaj do okien hasel",
+            "Sprawdza klase i tytul okna z fokusem przed wklejeniem",
+            config.paste_guard.enabled,
+        );
+        paste_guard_group.add(&paste_guard_row);
+        let paste_guard_classes =
+            create_entry_row("Klasy okien (po przecinku)", &config.paste_guard.blocked_classes.join(", "));
+        paste_guard_group.add(&paste_guard_classes);
+        let paste_guard_titles =
+            create_entry_row("Tytuly okien (po przecinku)", &config.paste_guard.blocked_titles.join(", "));
+        paste_guard_group.add(&paste_guard_titles);
+        let (paste_guard_unknown_row, paste_guard_unknown) = create_switch_row(
+            "Wklejaj do nieznanych okien",
+            "Gdy nie da sie odczytac okna z fokusem (np. GNOME na Waylandzie); bez tego wynik jest tylko kopiowany",
+            config.paste_guard.allow_unknown_window,
+        );
+        paste_guard_group.add(&paste_guard_unknown_row);
+        settings_page.add(&paste_guard_group);
+
+        let learning_group = adw::PreferencesGroup::builder()
+            .title("Tryb nauki")
+            .build();
+
+        let (learning_row, learning_digest) = create_switch_row(
+            "Cotygodniowy raport bledow",
+            "Raz w tygodniu podsumuj najczestsze bledy z historii poprawek",
+            config.settings.learning_digest,
+        );
+        learning_group.add(&learning_row);
+
+        let (record_row, record_history) = create_switch_row(
+            "Zapisuj historie poprawek",
+            "Wybrane wyniki trafiaja do historii; z niej korzystaja raport, przegladarka historii i podpowiedz poprzedniego wyboru",
+            config.history.enabled,
+        );
+        learning_group.add(&record_row);
+
+        let (encrypt_row, encrypt_history) = create_switch_row(
+            "Szyfruj historie",
+            "Haslo jest wymagane raz po uruchomieniu, przy pierwszym dostepie do historii",
+            config.history.encrypt,
+        );
+        learning_group.add(&encrypt_row);
+
+        let retention_names: Vec<&str> = RETENTION_MODES.iter().map(|(_, name)| *name).collect();
+        let retention = adw::ComboRow::builder()
+            .title("Przechowywanie historii")
+            .subtitle("Starsze wpisy sa usuwane przy uruchomieniu")
+            .model(&gtk4::StringList::new(&retention_names))
+            .selected(retention_index(&config.history))
+            .build();
+        learning_group.add(&retention);
+
+        let (retention_days_row, retention_days) = create_spin_row(
+            "Liczba dni",
+            "Dla trybu „Ostatnie N dni”",
+            config.history.retention_days,
+            3650,
+        );
+        learning_group.add(&retention_days_row);
+
+        let (retention_entries_row, retention_entries) = create_spin_row(
+            "Liczba wpisow",
+            "Dla trybu „Ostatnie N wpisow”",
+            config.history.retention_entries,
+            100_000,
+        );
+        learning_group.add(&retention_entries_row);
+
+        let clear_history_row = create_button_row("Wyczysc historie", "user-trash-symbolic");
+        clear_history_row.1.add_css_class("destructive-action");
+        learning_group.add(&clear_history_row.0);
+        settings_page.add(&learning_group);
+
+        let transfer_group = adw::PreferencesGroup::builder()
+            .title("Przenoszenie ustawien")
+            .description("Plik TOML bez kluczy API; przy imporcie klucze z tego komputera zostaja zachowane")
+            .build();
+        let export_row = create_button_row("Eksportuj ustawienia", "document-save-symbolic");
+        transfer_group.add(&export_row.0);
+        let import_row = create_button_row("Importuj ustawienia", "document-open-symbolic");
+        transfer_group.add(&import_row.0);
+        settings_page.add(&transfer_group);
+
+        add_page(&stack, &settings_page, "settings");
+
+        let snippets_page = adw::PreferencesPage::builder()
+            .title("Wstawki")
+            .icon_name("edit-paste-symbolic")
+            .build();
+
+        let snippets_group = adw::PreferencesGroup::builder()
+            .title("Wstawki")
+            .description("Linia \"### Nazwa\" zaczyna wstawke, kolejne linie sa jej trescia. Wstawki sa dostepne w menu kontekstowym paneli i w zasobniku.")
+            .build();
+
+        let snippets = gtk4::TextView::builder()
+            .wrap_mode(gtk4::WrapMode::Word)
+            .monospace(true)
+            .left_margin(8)
+            .right_margin(8)
+            .top_margin(8)
+            .bottom_margin(8)
+            .build();
+        snippets
+            .buffer()
+            .set_text(&crate::snippets::format_snippets(&config.snippets));
+        let snippets_scrolled = gtk4::ScrolledWindow::builder()
+            .hscrollbar_policy(gtk4::PolicyType::Never)
+            .min_content_height(300)
+            .child(&snippets)
+            .build();
+        snippets_group.add(&snippets_scrolled);
+        snippets_page.add(&snippets_group);
+
+        add_page(&stack, &snippets_page, "snippets");
+
+        let styles_page = adw::PreferencesPage::builder()
+            .title("Style")
+            .icon_name("document-edit-symbolic")
+            .build();
+        let custom_styles = CustomStyleRows::new(&config.custom_styles);
+        styles_page.add(&custom_styles.group);
+
+        add_page(&stack, &styles_page, "styles");
+
+        let shortcuts_page = adw::PreferencesPage::builder()
+            .title("Skróty")
+            .icon_name("preferences-desktop-keyboard-shortcuts-symbolic")
+            .build();
+
+        let global_group = adw::PreferencesGroup::builder()
+            .title("Globalne")
+            .build();
+        let global_hotkey = create_hotkey_row(GLOBAL_HOTKEY_TITLE, &config.global_hotkey.combination);
+        global_group.add(&global_hotkey);
+
+        let portal_row = create_button_row("Zmień skrót (portal)", "view-refresh-symbolic");
+        portal_row.0.set_visible(false);
+        global_group.add(&portal_row.0);
+
+        for (title, accel) in [
+            ("Cofnij / ponów edycję w panelu wyniku", "Ctrl+Z / Ctrl+Shift+Z"),
+            ("Szukaj w ustawieniach", "Ctrl+F"),
+        ] {
+            let row = adw::ActionRow::builder().title(title).subtitle(accel).build();
+            global_group.add(&row);
+        }
+
+        let action_names: Vec<&str> = DoublePressAction::all()
+            .iter()
+            .map(|a| a.display_name_pl())
+            .collect();
+        let double_press_action = adw::ComboRow::builder()
+            .title("Podwójne naciśnięcie skrótu globalnego")
+            .subtitle("Przy włączonej akcji pojedyncze naciśnięcie startuje po upływie odstępu")
+            .model(&gtk4::StringList::new(&action_names))
+            .selected(double_press_index(&config.double_press))
+            .build();
+        global_group.add(&double_press_action);
+
+        let (double_press_row, double_press_ms) = create_spin_row(
+            "Odstęp podwójnego naciśnięcia (ms)",
+            "Drugie naciśnięcie w tym czasie wywołuje akcję",
+            config.double_press.window_ms as u32,
+            MAX_DOUBLE_PRESS_MS as u32,
+        );
+        global_group.add(&double_press_row);
+        shortcuts_page.add(&global_group);
+
+        let style_group = adw::PreferencesGroup::builder()
+            .title("Skróty stylów")
+            .description("Tekst ze schowka idzie od razu w wybranym stylu. Pusty wiersz wyłącza skrót.")
+            .build();
+        let mut style_hotkeys = Vec::new();
+        for style in CorrectionStyle::all().iter().filter(|s| **s != CorrectionStyle::Normal) {
+            let title = style.display_name_pl().to_string();
+            let row = create_hotkey_row(&title, style_hotkey(&config.global_hotkey, *style));
+            style_group.add(&row);
+            style_hotkeys.push((*style, row, title));
+        }
+        shortcuts_page.add(&style_group);
+
+        let window_group = adw::PreferencesGroup::builder()
+            .title("Okno główne")
+            .description("Zapis GTK, np. <Control>m, <Alt>1, <Control><Shift>v. Pusty wiersz wyłącza skrót.")
+            .build();
+        let mut shortcut_rows = Vec::new();
+        for action in shortcuts::ACTIONS {
+            let accel = shortcuts::effective_accel(action, &config.shortcuts).unwrap_or("");
+            let row = create_entry_row(action.label, accel);
+            window_group.add(&row);
+            shortcut_rows.push((action.name, row, action.label.to_string()));
+        }
+        shortcuts_page.add(&window_group);
+
+        add_page(&stack, &shortcuts_page, "shortcuts");
+
+        let pages = [api_page, settings_page, snippets_page, styles_page, shortcuts_page];
+
+        let switcher = adw::ViewSwitcherTitle::builder()
+            .title("Ustawienia")
+            .stack(&stack)
+            .build();
+
+        let search_button = gtk4::ToggleButton::builder()
+            .icon_name("system-search-symbolic")
+            .tooltip_text("Szukaj (Ctrl+F)")
+            .build();
+        let search_entry = gtk4::SearchEntry::new();
+        search_entry.set_hexpand(true);
+        let search_bar = gtk4::SearchBar::builder()
+            .child(&search_entry)
+            .key_capture_widget(&dialog)
+            .build();
+        search_bar.connect_entry(&search_entry);
+        search_button
+            .bind_property("active", &search_bar, "search-mode-enabled")
+            .bidirectional()
+            .build();
+
+        let stack_weak = stack.downgrade();
+        search_entry.connect_search_changed(move |entry| {
+            if let Some(stack) = stack_weak.upgrade() {
+                filter_pages(&stack, &pages, &entry.text());
+            }
+        });
+
+        let cancel_button = gtk4::Button::with_label("Anuluj");
+        let save_button = gtk4::Button::with_label("Zapisz");
+        save_button.add_css_class("suggested-action");
+
+        let header = adw::HeaderBar::new();
+        header.set_title_widget(Some(&switcher));
+        header.set_show_end_title_buttons(false);
+        header.pack_start(&cancel_button);
+        header.pack_end(&save_button);
+        header.pack_end(&search_button);
+
+        toasts.set_child(Some(&stack));
 
-        dialog.add(&settings_page);
+        let content = gtk4::Box::new(gtk4::Orientation::Vertical, 0);
+        content.append(&header);
+        content.append(&search_bar);
+        content.append(&toasts);
+        dialog.set_content(Some(&content));
+
+        let dialog_weak = dialog.downgrade();
+        cancel_button.connect_clicked(move |_| {
+            if let Some(d) = dialog_weak.upgrade() {
+                info!("Settings dialog cancelled");
+                d.close();
+            }
+        });
+
+        let key_controller = gtk4::EventControllerKey::new();
+        let dialog_weak = dialog.downgrade();
+        let search_bar_weak = search_bar.downgrade();
+        key_controller.connect_key_pressed(move |_, key, _, modifiers| {
+            if key == gdk::Key::f && modifiers.contains(gdk::ModifierType::CONTROL_MASK) {
+                if let Some(bar) = search_bar_weak.upgrade() {
+                    bar.set_search_mode(true);
+                }
+                return glib::Propagation::Stop;
+            }
+            if key != gdk::Key::Escape {
+                return glib::Propagation::Proceed;
+            }
+            // Esc w polu wyszukiwania zamyka je już w SearchBar, tu zamyka okno
+            if let Some(d) = dialog_weak.upgrade() {
+                d.close();
+            }
+            glib::Propagation::Stop
+        });
+        dialog.add_controller(key_controller);
+
+        let validated_rows: Vec<(SettingsField, adw::EntryRow, String)> = Provider::all()
+            .iter()
+            .zip(api_keys.iter().zip(&models))
+            .flat_map(|(provider, (key, model))| {
+                [
+                    (SettingsField::api_key(*provider), key.clone().upcast()),
+                    (SettingsField::model(*provider), model.clone()),
+                ]
+            })
+            .map(|(field, row): (SettingsField, adw::EntryRow)| {
+                let title = row.title().to_string();
+                (field, row, title)
+            })
+            .collect();
+        let editable_rows = validated_rows
+            .iter()
+            .map(|(_, row, title)| (row, title))
+            .chain(shortcut_rows.iter().map(|(_, row, title)| (row, title)));
+        for (row, title) in editable_rows {
+            let title = title.clone();
+            row.connect_changed(move |row| set_row_error(row, &title, None));
+        }
+
+        let fields = SettingsFields {
+            base: Rc::new(RefCell::new(config.clone())),
+            api_keys,
+            models,
+            panels,
+            highlight_diffs,
+            learning_digest,
+            record_history,
+            encrypt_history,
+            retention,
+            retention_days,
+            retention_entries,
+            auto_hide_min_results,
+            auto_present_results,
+            results_popup,
+            compact_hotkey_window,
+            confirm_clipboard,
+            rewrap_output,
+            sound_first_result,
+            sound_last_result,
+            demo_mode,
+            quick_mode,
+            compress_requests,
+            check_facts,
+            agreement_heatmap,
+            reduced_motion,
+            minimal_emoji,
+            conversation_enabled,
+            conversation_exchanges,
+            edit_mode_enabled,
+            strip_quotes,
+            unwrap_lines,
+            strip_tracking,
+            edit_mode_min_chars,
+            preserve_length,
+            budget_limits,
+            disable_at_limit,
+            audit_enabled,
+            audit_include_content,
+            paste_guard_enabled,
+            paste_guard_classes,
+            paste_guard_titles,
+            paste_guard_unknown,
+            snippets,
+            custom_styles,
+            global_hotkey,
+            style_hotkeys,
+            double_press_action,
+            double_press_ms,
+            validated_rows,
+            shortcut_rows,
+        };
+
+        let dialog_weak = dialog.downgrade();
+        let export_fields = fields.clone();
+        let export_toasts = toasts.clone();
+        export_row.1.connect_clicked(move |_| {
+            if let Some(d) = dialog_weak.upgrade() {
+                choose_transfer_file(&d, true, {
+                    let fields = export_fields.clone();
+                    let toasts = export_toasts.clone();
+                    move |path| export_settings(&fields, &toasts, path)
+                });
+            }
+        });
+
+        let dialog_weak = dialog.downgrade();
+        let import_fields = fields.clone();
+        let import_toasts = toasts.clone();
+        import_row.1.connect_clicked(move |_| {
+            if let Some(d) = dialog_weak.upgrade() {
+                choose_transfer_file(&d, false, {
+                    let fields = import_fields.clone();
+                    let toasts = import_toasts.clone();
+                    move |path| import_settings(&fields, &toasts, path)
+                });
+            }
+        });
+
+        let dialog_weak = dialog.downgrade();
+        let audit_toasts = toasts.clone();
+        audit_export_row.1.connect_clicked(move |_| {
+            if let Some(d) = dialog_weak.upgrade() {
+                export_audit_log(&d, &audit_toasts);
+            }
+        });
+
+        let dialog_weak = dialog.downgrade();
+        let clear_toasts = toasts.clone();
+        clear_history_row.1.connect_clicked(move |_| {
+            if let Some(d) = dialog_weak.upgrade() {
+                confirm_clear_history(&d, &clear_toasts);
+            }
+        });
 
         Self {
             dialog,
-            openai_key,
-            openai_model,
-            anthropic_key,
-            anthropic_model,
-            gemini_key,
-            gemini_model,
-            deepseek_key,
-            deepseek_model,
-            highlight_diffs,
+            toasts,
+            save_button,
+            fields,
+            portal_row,
+            budget_rows,
+        }
+    }
+
+    /// Pokazuje przy budżetach wydatki i tokeny dostawców w miesiącu `month`
+    pub fn show_usage(&self, tracker: &UsageTracker, month: &str) {
+        for (provider, row) in Provider::all().iter().zip(&self.budget_rows) {
+            let tokens = tracker.tokens(provider.name(), month);
+            row.set_subtitle(&format!(
+                "USD na miesiac; w {} wydano {:.4} USD ({} tokenow wejscia, {} wyjscia)",
+                month,
+                tracker.spent(provider.name(), month),
+                tokens.prompt_tokens,
+                tokens.completion_tokens
+            ));
         }
     }
 
@@ -146,71 +1541,315 @@ impl SettingsDialog {
     }
 
     pub fn to_config(&self) -> Config {
-        Config {
-            api_keys: crate::config::ApiKeys {
-                openai: self.openai_key.text().to_string(),
-                anthropic: self.anthropic_key.text().to_string(),
-                gemini: self.gemini_key.text().to_string(),
-                deepseek: self.deepseek_key.text().to_string(),
-            },
-            models: crate::config::Models {
-                openai: self.openai_model.text().to_string(),
-                anthropic: self.anthropic_model.text().to_string(),
-                gemini: self.gemini_model.text().to_string(),
-                deepseek: self.deepseek_model.text().to_string(),
-            },
-            settings: crate::config::Settings {
-                auto_startup: false,
-                default_style: "normal".to_string(),
-                highlight_diffs: self.highlight_diffs.is_active(),
-            },
-            ai_settings: crate::config::AiSettings {
-                reasoning_effort: "high".to_string(),
-                verbosity: "medium".to_string(),
-            },
-        }
+        self.fields.to_config()
     }
 
-    pub fn connect_save<F: Fn(Config) + 'static>(&self, callback: F) {
-        let openai_key = self.openai_key.clone();
-        let openai_model = self.openai_model.clone();
-        let anthropic_key = self.anthropic_key.clone();
-        let anthropic_model = self.anthropic_model.clone();
-        let gemini_key = self.gemini_key.clone();
-        let gemini_model = self.gemini_model.clone();
-        let deepseek_key = self.deepseek_key.clone();
-        let deepseek_model = self.deepseek_model.clone();
-        let highlight_diffs = self.highlight_diffs.clone();
-
-        self.dialog.connect_close_request(move |_| {
-            let config = Config {
-                api_keys: crate::config::ApiKeys {
-                    openai: openai_key.text().to_string(),
-                    anthropic: anthropic_key.text().to_string(),
-                    gemini: gemini_key.text().to_string(),
-                    deepseek: deepseek_key.text().to_string(),
-                },
-                models: crate::config::Models {
-                    openai: openai_model.text().to_string(),
-                    anthropic: anthropic_model.text().to_string(),
-                    gemini: gemini_model.text().to_string(),
-                    deepseek: deepseek_model.text().to_string(),
-                },
-                settings: crate::config::Settings {
-                    auto_startup: false,
-                    default_style: "normal".to_string(),
-                    highlight_diffs: highlight_diffs.is_active(),
-                },
-                ai_settings: crate::config::AiSettings {
-                    reasoning_effort: "high".to_string(),
-                    verbosity: "medium".to_string(),
-                },
-            };
+    /// `callback` zapisuje konfigurację; okno zamyka się dopiero po udanym zapisie,
+    /// a przy błędzie pokazuje komunikat i pozwala spróbować ponownie
+    pub fn connect_save<F, Fut>(&self, callback: F)
+    where
+        F: Fn(Config) -> Fut + 'static,
+        Fut: Future<Output = Result<(), String>> + 'static,
+    {
+        let callback = Rc::new(callback);
+        let fields = self.fields.clone();
+        let toasts = self.toasts.clone();
+        let dialog_weak = self.dialog.downgrade();
 
-            callback(config);
-            info!("Settings saved");
+        self.save_button.connect_clicked(move |button| {
+            let errors = fields.field_errors();
+            let shortcut_errors = fields.shortcut_errors();
+            let hotkey_error = fields.global_hotkey_error();
+            let style_hotkey_errors = fields.style_hotkey_errors();
+            fields.show_errors(&errors, &shortcut_errors);
+            let custom_style_errors = fields.custom_styles.show_errors();
+            if !errors.is_empty()
+                || !shortcut_errors.is_empty()
+                || hotkey_error.is_some()
+                || !style_hotkey_errors.is_empty()
+                || custom_style_errors > 0
+            {
+                let invalid = errors.len()
+                    + shortcut_errors.len()
+                    + usize::from(hotkey_error.is_some())
+                    + style_hotkey_errors.len()
+                    + custom_style_errors;
+                warn!("Settings not saved: {} invalid fields", invalid);
+                toasts.add_toast(adw::Toast::new("Popraw zaznaczone pola"));
+                return;
+            }
 
-            glib::Propagation::Proceed
+            button.set_sensitive(false);
+            let save = callback(fields.to_config());
+            let button = button.clone();
+            let toasts = toasts.clone();
+            let dialog_weak = dialog_weak.clone();
+            glib::spawn_future_local(async move {
+                match save.await {
+                    Ok(()) => {
+                        info!("Settings saved");
+                        if let Some(d) = dialog_weak.upgrade() {
+                            d.close();
+                        }
+                    }
+                    Err(e) => {
+                        toasts.add_toast(adw::Toast::new(&format!("Nie udało się zapisać: {}", e)));
+                        button.set_sensitive(true);
+                    }
+                }
+            });
         });
     }
+
+    /// Pokazuje wiersz skrótu portalu; `callback` wiąże skrót od nowa i zwraca jego opis
+    pub fn connect_portal_rebind<F, Fut>(&self, trigger: Option<String>, callback: F)
+    where
+        F: Fn() -> Fut + 'static,
+        Fut: Future<Output = Result<String, String>> + 'static,
+    {
+        let (row, button) = &self.portal_row;
+        row.set_subtitle(&portal_subtitle(trigger.as_deref()));
+        row.set_visible(true);
+
+        let row = row.clone();
+        let toasts = self.toasts.clone();
+        button.connect_clicked(move |button| {
+            button.set_sensitive(false);
+            let rebind = callback();
+            let button = button.clone();
+            let row = row.clone();
+            let toasts = toasts.clone();
+            glib::spawn_future_local(async move {
+                match rebind.await {
+                    Ok(trigger) => {
+                        info!("Portal shortcut rebound: {}", trigger);
+                        row.set_subtitle(&portal_subtitle(Some(&trigger)));
+                    }
+                    Err(e) => {
+                        warn!("Portal shortcut rebind failed: {}", e);
+                        toasts.add_toast(adw::Toast::new(&format!("Nie udało się zmienić skrótu: {}", e)));
+                    }
+                }
+                button.set_sensitive(true);
+            });
+        });
+    }
+}
+
+fn portal_subtitle(trigger: Option<&str>) -> String {
+    match trigger.filter(|t| !t.is_empty()) {
+        Some(trigger) => format!("Portal przypisał: {}", trigger),
+        None => "Portal nie przypisał jeszcze skrótu".to_string(),
+    }
+}
+
+fn create_button_row(title: &str, icon: &str) -> (adw::ActionRow, gtk4::Button) {
+    let row = adw::ActionRow::builder().title(title).build();
+    let button = gtk4::Button::builder()
+        .icon_name(icon)
+        .valign(gtk4::Align::Center)
+        .build();
+    button.add_css_class("flat");
+    row.add_suffix(&button);
+    row.set_activatable_widget(Some(&button));
+    (row, button)
+}
+
+/// Usuwa plik historii po potwierdzeniu
+fn confirm_clear_history(parent: &adw::Window, toasts: &adw::ToastOverlay) {
+    let confirm = adw::MessageDialog::builder()
+        .transient_for(parent)
+        .modal(true)
+        .heading("Wyczyścić historię?")
+        .body("Wszystkie zapisane poprawki zostaną usunięte. Tej operacji nie można cofnąć.")
+        .build();
+    confirm.add_responses(&[("cancel", "Anuluj"), ("clear", "Wyczyść")]);
+    confirm.set_response_appearance("clear", adw::ResponseAppearance::Destructive);
+    confirm.set_close_response("cancel");
+
+    let toasts = toasts.clone();
+    confirm.connect_response(None, move |_, response| {
+        if response != "clear" {
+            return;
+        }
+        match History::new(History::default_path()).clear() {
+            Ok(()) => {
+                info!("History cleared");
+                toasts.add_toast(adw::Toast::new("Historia wyczyszczona"));
+            }
+            Err(e) => {
+                warn!("Failed to clear history: {}", e);
+                toasts.add_toast(adw::Toast::new(&format!("Nie udało się wyczyścić historii: {}", e)));
+            }
+        }
+    });
+    confirm.present();
+}
+
+fn choose_transfer_file<F: Fn(&Path) + 'static>(parent: &adw::Window, save: bool, on_chosen: F) {
+    let (title, action, accept) = if save {
+        ("Eksportuj ustawienia", gtk4::FileChooserAction::Save, "Zapisz")
+    } else {
+        ("Importuj ustawienia", gtk4::FileChooserAction::Open, "Importuj")
+    };
+    let chooser = gtk4::FileChooserNative::new(Some(title), Some(parent), action, Some(accept), Some("Anuluj"));
+    if save {
+        chooser.set_current_name("poprawiacz-ustawienia.toml");
+    }
+
+    let filter = gtk4::FileFilter::new();
+    filter.set_name(Some("Pliki TOML"));
+    filter.add_pattern("*.toml");
+    chooser.add_filter(&filter);
+
+    chooser.connect_response(move |chooser, response| {
+        if response == gtk4::ResponseType::Accept {
+            if let Some(path) = chooser.file().and_then(|f| f.path()) {
+                on_chosen(&path);
+            }
+        }
+        chooser.destroy();
+    });
+    chooser.show();
+}
+
+/// Eksportuje bieżący stan formularza, także niezapisane zmiany
+fn export_settings(fields: &SettingsFields, toasts: &adw::ToastOverlay, path: &Path) {
+    let result = fields
+        .to_config()
+        .export_without_secrets()
+        .and_then(|content| std::fs::write(path, content).map_err(Into::into));
+    match result {
+        Ok(()) => {
+            info!("Settings exported to {}", path.display());
+            toasts.add_toast(adw::Toast::new("Wyeksportowano ustawienia (bez kluczy API)"));
+        }
+        Err(e) => {
+            warn!("Settings export failed: {}", e);
+            toasts.add_toast(adw::Toast::new(&format!("Nie udało się wyeksportować: {}", e)));
+        }
+    }
+}
+
+/// Zapisuje dziennik audytu jako CSV w wybranym pliku
+fn export_audit_log(parent: &adw::Window, toasts: &adw::ToastOverlay) {
+    let chooser = gtk4::FileChooserNative::new(
+        Some("Eksportuj dziennik audytu"),
+        Some(parent),
+        gtk4::FileChooserAction::Save,
+        Some("Zapisz"),
+        Some("Anuluj"),
+    );
+    chooser.set_current_name("poprawiacz-audyt.csv");
+
+    let filter = gtk4::FileFilter::new();
+    filter.set_name(Some("Pliki CSV"));
+    filter.add_pattern("*.csv");
+    chooser.add_filter(&filter);
+
+    let toasts = toasts.clone();
+    chooser.connect_response(move |chooser, response| {
+        if response == gtk4::ResponseType::Accept {
+            if let Some(path) = chooser.file().and_then(|f| f.path()) {
+                match AuditLog::new(AuditLog::default_path()).export_csv(&path) {
+                    Ok(count) => {
+                        info!("Audit log exported to {} ({} entries)", path.display(), count);
+                        toasts.add_toast(adw::Toast::new(&format!("Wyeksportowano dziennik audytu ({} wpisów)", count)));
+                    }
+                    Err(e) => {
+                        warn!("Audit log export failed: {}", e);
+                        toasts.add_toast(adw::Toast::new(&format!("Nie udało się wyeksportować: {}", e)));
+                    }
+                }
+            }
+        }
+        chooser.destroy();
+    });
+    chooser.show();
+}
+
+/// Wczytuje ustawienia do formularza; trafiają do pliku konfiguracji dopiero po „Zapisz”
+fn import_settings(fields: &SettingsFields, toasts: &adw::ToastOverlay, path: &Path) {
+    let result = std::fs::read_to_string(path)
+        .map_err(Into::into)
+        .and_then(|content| fields.to_config().import_keeping_secrets(&content));
+    match result {
+        Ok(config) => {
+            fields.set_values(&config);
+            info!("Settings imported from {}", path.display());
+            toasts.add_toast(adw::Toast::new("Zaimportowano ustawienia – kliknij „Zapisz”, aby je zachować"));
+        }
+        Err(e) => {
+            warn!("Settings import failed: {}", e);
+            toasts.add_toast(adw::Toast::new(&format!("Nie udało się zaimportować: {}", e)));
+        }
+    }
+}
+
+fn add_page(stack: &adw::ViewStack, page: &adw::PreferencesPage, name: &str) {
+    let title = page.title();
+    let stack_page = stack.add_titled(page, Some(name), &title);
+    stack_page.set_icon_name(page.icon_name().as_deref());
+}
+
+fn descendants<T: IsA<gtk4::Widget>>(root: &gtk4::Widget, found: &mut Vec<T>) {
+    let mut child = root.first_child();
+    while let Some(widget) = child {
+        if let Some(matching) = widget.downcast_ref::<T>() {
+            found.push(matching.clone());
+        }
+        descendants(&widget, found);
+        child = widget.next_sibling();
+    }
+}
+
+fn row_matches(row: &adw::PreferencesRow, query: &str) -> bool {
+    let subtitle = row
+        .downcast_ref::<adw::ActionRow>()
+        .and_then(|r| r.subtitle())
+        .unwrap_or_default();
+    matches_search(&[row.title().as_str(), subtitle.as_str()], query)
+}
+
+/// Ukrywa wiersze, grupy i strony niepasujące do wyszukiwania; trafienie w tytule grupy pokazuje całą grupę
+fn filter_pages(stack: &adw::ViewStack, pages: &[adw::PreferencesPage], query: &str) {
+    let mut first_match = None;
+    let mut current_matches = false;
+
+    for page in pages {
+        let mut groups: Vec<adw::PreferencesGroup> = Vec::new();
+        descendants(page.upcast_ref(), &mut groups);
+
+        let mut page_matches = false;
+        for group in groups {
+            let description = group.description().unwrap_or_default();
+            let group_matches = matches_search(&[group.title().as_str(), description.as_str()], query);
+
+            let mut rows: Vec<adw::PreferencesRow> = Vec::new();
+            descendants(group.upcast_ref(), &mut rows);
+            let mut any_row = false;
+            for row in rows {
+                let visible = group_matches || row_matches(&row, query);
+                row.set_visible(visible);
+                any_row |= visible;
+            }
+
+            group.set_visible(group_matches || any_row);
+            page_matches |= group_matches || any_row;
+        }
+
+        stack.page(page).set_visible(page_matches);
+        if page_matches && first_match.is_none() {
+            first_match = Some(page.clone());
+        }
+        if page_matches && stack.visible_child().as_ref() == Some(page.upcast_ref()) {
+            current_matches = true;
+        }
+    }
+
+    if !current_matches {
+        if let Some(page) = first_match {
+            stack.set_visible_child(&page);
+        }
+    }
 }
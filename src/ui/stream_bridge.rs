@@ -0,0 +1,206 @@
+//! Warstwa pośrednia między strumieniem fragmentów z API a widokiem panelu
+//!
+//! Fragmenty są zbierane i oddawane do widoku najwyżej raz na `MIN_FLUSH_INTERVAL`,
+//! żeby szybcy dostawcy nie zalewali UI odświeżeniami. Wyświetlanie można
+//! wstrzymać (fragmenty czekają w buforze) i wznowić, nadrabiając zaległości.
+//! Z tej logiki korzystają panele wyników GTK.
+
+use std::time::{Duration, Instant};
+
+/// Minimalny odstęp między kolejnymi dopisaniami do widoku (~20 klatek/s)
+pub const MIN_FLUSH_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Po przekroczeniu tej wielkości bufor jest opróżniany natychmiast,
+/// także gdy wyświetlanie wstrzymano
+pub const MAX_PENDING_BYTES: usize = 64 * 1024;
+
+#[derive(Debug)]
+pub struct StreamBridge {
+    paused: bool,
+    received: bool,
+    pending: String,
+    last_flush: Option<Instant>,
+    min_interval: Duration,
+    max_pending: usize,
+}
+
+impl Default for StreamBridge {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StreamBridge {
+    pub fn new() -> Self {
+        Self::with_limits(MIN_FLUSH_INTERVAL, MAX_PENDING_BYTES)
+    }
+
+    pub fn with_limits(min_interval: Duration, max_pending: usize) -> Self {
+        Self {
+            paused: false,
+            received: false,
+            pending: String::new(),
+            last_flush: None,
+            min_interval,
+            max_pending,
+        }
+    }
+
+    /// Dokłada fragment do bufora
+    pub fn push(&mut self, chunk: &str) {
+        self.received = true;
+        self.pending.push_str(chunk);
+    }
+
+    /// Zwraca zebrany tekst, jeśli minął odstęp odświeżania albo bufor jest pełny.
+    ///
+    /// Przepełnienie bufora w czasie pauzy wznawia wyświetlanie.
+    pub fn take_ready(&mut self, now: Instant) -> Option<String> {
+        if self.pending.is_empty() {
+            return None;
+        }
+
+        let overflow = self.pending.len() >= self.max_pending;
+        if self.paused && !overflow {
+            return None;
+        }
+
+        let due = self
+            .last_flush
+            .map(|t| now.saturating_duration_since(t) >= self.min_interval)
+            .unwrap_or(true);
+
+        if due || overflow {
+            self.paused = false;
+            self.last_flush = Some(now);
+            Some(std::mem::take(&mut self.pending))
+        } else {
+            None
+        }
+    }
+
+    /// Ile zostało do następnego możliwego opróżnienia bufora
+    pub fn time_until_ready(&self, now: Instant) -> Option<Duration> {
+        if self.pending.is_empty() || self.paused {
+            return None;
+        }
+        let next = self.last_flush? + self.min_interval;
+        Some(next.saturating_duration_since(now))
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Wznawia wyświetlanie i zwraca zbuforowany tekst do dopisania
+    pub fn resume(&mut self) -> String {
+        self.paused = false;
+        self.last_flush = Some(Instant::now());
+        std::mem::take(&mut self.pending)
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Czy w tej sesji dotarł już jakikolwiek fragment
+    pub fn has_received(&self) -> bool {
+        self.received
+    }
+
+    pub fn pending_len(&self) -> usize {
+        self.pending.chars().count()
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::with_limits(self.min_interval, self.max_pending);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_chunk_is_flushed_immediately() {
+        let mut bridge = StreamBridge::new();
+        assert!(!bridge.has_received());
+
+        bridge.push("Ala ");
+        assert!(bridge.has_received());
+        assert_eq!(bridge.take_ready(Instant::now()), Some("Ala ".to_string()));
+        assert_eq!(bridge.take_ready(Instant::now()), None);
+    }
+
+    #[test]
+    fn test_chunks_are_coalesced_within_interval() {
+        let mut bridge = StreamBridge::with_limits(Duration::from_millis(50), 1024);
+        let start = Instant::now();
+
+        bridge.push("a");
+        assert_eq!(bridge.take_ready(start), Some("a".to_string()));
+
+        bridge.push("b");
+        bridge.push("c");
+        assert_eq!(bridge.take_ready(start + Duration::from_millis(10)), None);
+        assert_eq!(
+            bridge.time_until_ready(start + Duration::from_millis(10)),
+            Some(Duration::from_millis(40))
+        );
+        assert_eq!(
+            bridge.take_ready(start + Duration::from_millis(50)),
+            Some("bc".to_string())
+        );
+    }
+
+    #[test]
+    fn test_overflow_flushes_before_interval() {
+        let mut bridge = StreamBridge::with_limits(Duration::from_secs(10), 4);
+        let start = Instant::now();
+
+        bridge.push("ab");
+        bridge.take_ready(start);
+        bridge.push("cdef");
+        assert_eq!(bridge.take_ready(start), Some("cdef".to_string()));
+    }
+
+    #[test]
+    fn test_pause_buffers_and_resume_flushes() {
+        let mut bridge = StreamBridge::new();
+        bridge.push("Ala ");
+        bridge.take_ready(Instant::now());
+        bridge.pause();
+
+        bridge.push("ma ");
+        bridge.push("kota");
+        assert_eq!(bridge.take_ready(Instant::now() + Duration::from_secs(1)), None);
+        assert_eq!(bridge.time_until_ready(Instant::now()), None);
+        assert_eq!(bridge.pending_len(), 7);
+
+        assert_eq!(bridge.resume(), "ma kota");
+        assert!(!bridge.is_paused());
+        assert_eq!(bridge.pending_len(), 0);
+    }
+
+    #[test]
+    fn test_overflow_while_paused_resumes() {
+        let mut bridge = StreamBridge::with_limits(Duration::from_millis(50), 4);
+        bridge.pause();
+        bridge.push("abcd");
+
+        assert_eq!(bridge.take_ready(Instant::now()), Some("abcd".to_string()));
+        assert!(!bridge.is_paused());
+    }
+
+    #[test]
+    fn test_reset_clears_state() {
+        let mut bridge = StreamBridge::new();
+        bridge.pause();
+        bridge.push("x");
+        bridge.reset();
+
+        assert!(!bridge.is_paused());
+        assert!(!bridge.has_received());
+        assert_eq!(bridge.resume(), "");
+    }
+}
@@ -0,0 +1,217 @@
+use crate::chooser;
+use crate::prompts::CorrectionStyle;
+use crate::ui::result_popup::monitor_at;
+use gtk4::prelude::*;
+use gtk4::{gdk, glib};
+use libadwaita as adw;
+use std::rc::Rc;
+use tracing::{info, warn};
+
+/// Tytuł okienka; po nim okno jest odnajdywane przy przesuwaniu pod kursor
+const PALETTE_TITLE: &str = "Poprawiacz - szybka paleta";
+const PALETTE_WIDTH: i32 = 320;
+const ROW_HEIGHT: i32 = 40;
+
+/// Okienko przy kursorze z listą stylów; wybór wysyła schowek w danym stylu
+pub struct StylePalette {
+    window: gtk4::Window,
+    style_buttons: Vec<(CorrectionStyle, gtk4::Button)>,
+    /// Własne style z konfiguracji, pod wbudowanymi
+    custom_style_buttons: Vec<gtk4::Button>,
+    custom_button: gtk4::Button,
+    pipeline_buttons: Vec<gtk4::Button>,
+    height: i32,
+}
+
+impl StylePalette {
+    /// `custom_styles` to nazwy własnych stylów, a `pipelines` etykiety potoków
+    /// stylów z konfiguracji, pokazywane pod wbudowanymi stylami
+    pub fn new(parent: &adw::ApplicationWindow, custom_styles: &[String], pipelines: &[String]) -> Self {
+        let styles = CorrectionStyle::all();
+        // Style, własne style, „Inna instrukcja…” i potoki
+        let height = ROW_HEIGHT * (styles.len() + custom_styles.len() + 1 + pipelines.len()) as i32 + 16;
+        let window = gtk4::Window::builder()
+            .title(PALETTE_TITLE)
+            .decorated(false)
+            .resizable(false)
+            .default_width(PALETTE_WIDTH)
+            .default_height(height)
+            .build();
+        if let Some(app) = parent.application() {
+            window.set_application(Some(&app));
+        }
+
+        let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 4);
+        vbox.set_margin_start(8);
+        vbox.set_margin_end(8);
+        vbox.set_margin_top(8);
+        vbox.set_margin_bottom(8);
+
+        let mut style_buttons = Vec::new();
+        for (n, style) in styles.iter().enumerate() {
+            let button = gtk4::Button::with_label(&format!("{} {}", n + 1, style.display_name_pl()));
+            button.add_css_class("flat");
+            if let Some(label) = button.child().and_downcast::<gtk4::Label>() {
+                label.set_xalign(0.0);
+            }
+            vbox.append(&button);
+            style_buttons.push((*style, button));
+        }
+
+        // Cyfry 1-9 wybierają kolejne style; dalsze własne style tylko myszą
+        let mut custom_style_buttons = Vec::new();
+        for (n, name) in custom_styles.iter().enumerate() {
+            let number = styles.len() + n + 1;
+            let label = if number <= 9 {
+                format!("{} ⭐ {}", number, name)
+            } else {
+                format!("⭐ {}", name)
+            };
+            let button = gtk4::Button::with_label(&label);
+            button.add_css_class("flat");
+            if let Some(label) = button.child().and_downcast::<gtk4::Label>() {
+                label.set_xalign(0.0);
+            }
+            vbox.append(&button);
+            custom_style_buttons.push(button);
+        }
+
+        let custom_button = gtk4::Button::with_label("0 Inna instrukcja…");
+        custom_button.add_css_class("flat");
+        if let Some(label) = custom_button.child().and_downcast::<gtk4::Label>() {
+            label.set_xalign(0.0);
+        }
+        vbox.append(&custom_button);
+
+        let mut pipeline_buttons = Vec::new();
+        for label in pipelines {
+            let button = gtk4::Button::with_label(label);
+            button.add_css_class("flat");
+            if let Some(label) = button.child().and_downcast::<gtk4::Label>() {
+                label.set_xalign(0.0);
+            }
+            vbox.append(&button);
+            pipeline_buttons.push(button);
+        }
+
+        window.set_child(Some(&vbox));
+
+        let key_controller = gtk4::EventControllerKey::new();
+        let window_weak = window.downgrade();
+        let buttons: Vec<gtk4::Button> = style_buttons
+            .iter()
+            .map(|(_, b)| b.clone())
+            .chain(custom_style_buttons.iter().cloned())
+            .collect();
+        let custom = custom_button.clone();
+        key_controller.connect_key_pressed(move |_, key, _, _| {
+            if key == gdk::Key::Escape {
+                if let Some(w) = window_weak.upgrade() {
+                    w.close();
+                }
+                return glib::Propagation::Stop;
+            }
+            match key.to_unicode().and_then(|c| c.to_digit(10)) {
+                Some(0) => {
+                    custom.emit_clicked();
+                    glib::Propagation::Stop
+                }
+                Some(n @ 1..=9) => {
+                    if let Some(button) = buttons.get(n as usize - 1) {
+                        button.emit_clicked();
+                    }
+                    glib::Propagation::Stop
+                }
+                _ => glib::Propagation::Proceed,
+            }
+        });
+        window.add_controller(key_controller);
+
+        Self {
+            window,
+            style_buttons,
+            custom_style_buttons,
+            custom_button,
+            pipeline_buttons,
+            height,
+        }
+    }
+
+    /// Pokazuje okienko i przesuwa je pod kursor (jeśli platforma na to pozwala)
+    pub fn present_near_cursor(&self) {
+        self.window.present();
+
+        let Some(cursor) = crate::platform::cursor_position() else {
+            info!("Cursor position unavailable, palette placed by the window manager");
+            return;
+        };
+        let size = (PALETTE_WIDTH, self.height);
+        let (x, y) = match monitor_at(cursor) {
+            Some(screen) => chooser::place_near(cursor, size, screen),
+            None => cursor,
+        };
+
+        glib::timeout_add_local_once(std::time::Duration::from_millis(100), move || {
+            if let Err(e) = crate::platform::move_window(PALETTE_TITLE, x, y) {
+                warn!("Could not move style palette: {}", e);
+            }
+        });
+    }
+
+    /// Wywoływane z wybranym stylem; okienko zamyka się przed wysłaniem tekstu
+    pub fn connect_pick<F: Fn(CorrectionStyle) + 'static>(&self, callback: F) {
+        let callback = Rc::new(callback);
+        for (style, button) in &self.style_buttons {
+            let style = *style;
+            let callback = callback.clone();
+            let window_weak = self.window.downgrade();
+            button.connect_clicked(move |_| {
+                if let Some(w) = window_weak.upgrade() {
+                    w.close();
+                }
+                callback(style);
+            });
+        }
+    }
+
+    /// Wywoływane z numerem wybranego własnego stylu (kolejność z `new`)
+    pub fn connect_custom_style<F: Fn(usize) + 'static>(&self, callback: F) {
+        let callback = Rc::new(callback);
+        for (index, button) in self.custom_style_buttons.iter().enumerate() {
+            let callback = callback.clone();
+            let window_weak = self.window.downgrade();
+            button.connect_clicked(move |_| {
+                if let Some(w) = window_weak.upgrade() {
+                    w.close();
+                }
+                callback(index);
+            });
+        }
+    }
+
+    /// Wywoływane po wybraniu „Inna instrukcja…”; okienko zamyka się wcześniej
+    pub fn connect_custom<F: Fn() + 'static>(&self, callback: F) {
+        let window_weak = self.window.downgrade();
+        self.custom_button.connect_clicked(move |_| {
+            if let Some(w) = window_weak.upgrade() {
+                w.close();
+            }
+            callback();
+        });
+    }
+
+    /// Wywoływane z numerem wybranego potoku (kolejność z konfiguracji)
+    pub fn connect_pipeline<F: Fn(usize) + 'static>(&self, callback: F) {
+        let callback = Rc::new(callback);
+        for (index, button) in self.pipeline_buttons.iter().enumerate() {
+            let callback = callback.clone();
+            let window_weak = self.window.downgrade();
+            button.connect_clicked(move |_| {
+                if let Some(w) = window_weak.upgrade() {
+                    w.close();
+                }
+                callback(index);
+            });
+        }
+    }
+}
@@ -0,0 +1,247 @@
+//! Miesięczne koszty dostawców i budżety
+//!
+//! Koszt każdej odpowiedzi jest liczony z cennika modeli (`[pricing]`) i liczby
+//! tokenów podanej przez dostawcę, a gdy jej brak, policzonej lokalnie. Koszty
+//! i tokeny są sumowane w `usage.json` obok konfiguracji.
+//! Na początku miesiąca (UTC) liczniki zaczynają się od zera. Po przekroczeniu
+//! 80% budżetu pojawia się ostrzeżenie, a po 100% dostawca może być pomijany.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::api::token_usage::TokenUsage;
+use crate::config::{BudgetSettings, Config};
+
+/// Część budżetu, po której pojawia się ostrzeżenie
+pub const WARN_FRACTION: f64 = 0.8;
+
+const SECS_PER_DAY: u64 = 86_400;
+
+/// Data UTC (rok, miesiąc, dzień) dla sekund od epoki
+pub fn utc_date(secs: u64) -> (i64, u32, u32) {
+    // Algorytm „civil from days” Howarda Hinnanta
+    let days = (secs / SECS_PER_DAY) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Klucz miesiąca rozliczeniowego, np. "2026-10"
+pub fn month_key(secs: u64) -> String {
+    let (year, month, _) = utc_date(secs);
+    format!("{}-{:02}", year, month)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BudgetStatus {
+    /// Brak budżetu dla dostawcy
+    Unlimited,
+    Ok,
+    /// Wykorzystano co najmniej `WARN_FRACTION` budżetu
+    Warning,
+    Exceeded,
+}
+
+pub fn budget_status(spent: f64, limit: Option<f64>) -> BudgetStatus {
+    match limit {
+        None => BudgetStatus::Unlimited,
+        Some(limit) if spent >= limit => BudgetStatus::Exceeded,
+        Some(limit) if spent >= limit * WARN_FRACTION => BudgetStatus::Warning,
+        Some(_) => BudgetStatus::Ok,
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+struct UsageFile {
+    month: String,
+    /// Dostawca → wydane USD w miesiącu
+    spent: BTreeMap<String, f64>,
+    /// Dostawca → tokeny w miesiącu (brak w plikach starszych wersji)
+    #[serde(default)]
+    tokens: BTreeMap<String, TokenUsage>,
+}
+
+pub struct UsageTracker {
+    path: PathBuf,
+    data: UsageFile,
+}
+
+impl UsageTracker {
+    /// Wczytuje liczniki; brak lub uszkodzenie pliku daje zera
+    pub fn load<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let data = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self { path, data }
+    }
+
+    /// Plik liczników obok `config.toml`
+    pub fn default_path() -> PathBuf {
+        Config::get_config_path().with_file_name("usage.json")
+    }
+
+    /// Wydatki dostawcy w podanym miesiącu
+    pub fn spent(&self, provider: &str, month: &str) -> f64 {
+        if self.data.month != month {
+            return 0.0;
+        }
+        self.data.spent.get(provider).copied().unwrap_or(0.0)
+    }
+
+    /// Tokeny dostawcy w podanym miesiącu
+    pub fn tokens(&self, provider: &str, month: &str) -> TokenUsage {
+        if self.data.month != month {
+            return TokenUsage::default();
+        }
+        self.data.tokens.get(provider).copied().unwrap_or_default()
+    }
+
+    /// Wydatki wszystkich dostawców w podanym miesiącu
+    pub fn total(&self, month: &str) -> f64 {
+        if self.data.month != month {
+            return 0.0;
+        }
+        self.data.spent.values().sum()
+    }
+
+    /// Dolicza koszt i tokeny, zapisuje plik; nowy miesiąc zeruje liczniki
+    pub fn record(
+        &mut self,
+        provider: &str,
+        cost_usd: f64,
+        tokens: TokenUsage,
+        month: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if self.data.month != month {
+            self.data = UsageFile {
+                month: month.to_string(),
+                ..Default::default()
+            };
+        }
+        *self.data.spent.entry(provider.to_string()).or_insert(0.0) += cost_usd;
+        let total = self.data.tokens.entry(provider.to_string()).or_default();
+        *total = *total + tokens;
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(&self.data)?)?;
+        Ok(())
+    }
+}
+
+/// Tekst okna kosztów: wydatki, budżet i pozostała kwota każdego dostawcy
+pub fn summary(tracker: &UsageTracker, budget: &BudgetSettings, providers: &[&str], month: &str) -> String {
+    let mut lines = vec![format!("Koszty w miesiącu {} (z liczby tokenów i cennika modeli)", month), String::new()];
+    for provider in providers {
+        let spent = tracker.spent(provider, month);
+        let limit = budget.limit(provider);
+        let line = match (limit, budget_status(spent, limit)) {
+            (None, _) => format!("{}: {:.4} USD (bez budżetu)", provider, spent),
+            (Some(limit), status) => {
+                let marker = match status {
+                    BudgetStatus::Exceeded => "💰 ",
+                    BudgetStatus::Warning => "⚠️ ",
+                    _ => "",
+                };
+                format!(
+                    "{}{}: {:.4} z {:.2} USD ({:.0}%), zostało {:.4} USD",
+                    marker,
+                    provider,
+                    spent,
+                    limit,
+                    spent / limit * 100.0,
+                    (limit - spent).max(0.0)
+                )
+            }
+        };
+        lines.push(line);
+        let tokens = tracker.tokens(provider, month);
+        if tokens != TokenUsage::default() {
+            lines.push(format!("    tokeny: {} wejścia, {} wyjścia", tokens.prompt_tokens, tokens.completion_tokens));
+        }
+    }
+    lines.push(String::new());
+    lines.push(format!("Razem: {:.4} USD", tracker.total(month)));
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn tokens(prompt_tokens: u64, completion_tokens: u64) -> TokenUsage {
+        TokenUsage { prompt_tokens, completion_tokens }
+    }
+
+    #[test]
+    fn test_utc_date_and_month_key() {
+        assert_eq!(utc_date(0), (1970, 1, 1));
+        // 2024-02-29 12:00 UTC
+        assert_eq!(utc_date(1_709_208_000), (2024, 2, 29));
+        // 2026-10-18 00:00 UTC
+        assert_eq!(month_key(1_792_281_600), "2026-10");
+        assert_eq!(month_key(1_792_281_600 - 18 * SECS_PER_DAY), "2026-09");
+    }
+
+    #[test]
+    fn test_budget_status() {
+        assert_eq!(budget_status(100.0, None), BudgetStatus::Unlimited);
+        assert_eq!(budget_status(7.9, Some(10.0)), BudgetStatus::Ok);
+        assert_eq!(budget_status(8.0, Some(10.0)), BudgetStatus::Warning);
+        assert_eq!(budget_status(10.0, Some(10.0)), BudgetStatus::Exceeded);
+    }
+
+    #[test]
+    fn test_record_persists_and_resets_each_month() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("usage.json");
+
+        let mut tracker = UsageTracker::load(&path);
+        tracker.record("OpenAI", 0.5, tokens(1000, 200), "2026-10").unwrap();
+        tracker.record("OpenAI", 0.25, tokens(500, 100), "2026-10").unwrap();
+        tracker.record("Gemini", 0.1, tokens(300, 50), "2026-10").unwrap();
+
+        let mut reloaded = UsageTracker::load(&path);
+        assert!((reloaded.spent("OpenAI", "2026-10") - 0.75).abs() < 1e-9);
+        assert_eq!(reloaded.tokens("OpenAI", "2026-10"), tokens(1500, 300));
+        assert!((reloaded.total("2026-10") - 0.85).abs() < 1e-9);
+        assert_eq!(reloaded.spent("OpenAI", "2026-11"), 0.0);
+
+        reloaded.record("Gemini", 0.2, tokens(10, 5), "2026-11").unwrap();
+        assert_eq!(reloaded.spent("OpenAI", "2026-11"), 0.0);
+        assert!((reloaded.spent("Gemini", "2026-11") - 0.2).abs() < 1e-9);
+        assert_eq!(reloaded.tokens("OpenAI", "2026-11"), TokenUsage::default());
+    }
+
+    #[test]
+    fn test_summary_shows_remaining_budget() {
+        let dir = TempDir::new().unwrap();
+        let mut tracker = UsageTracker::load(dir.path().join("usage.json"));
+        tracker.record("OpenAI", 4.5, tokens(40_000, 9_000), "2026-10").unwrap();
+        tracker.record("Anthropic", 12.0, TokenUsage::default(), "2026-10").unwrap();
+
+        let mut budget = BudgetSettings::default();
+        budget.limits.insert("OpenAI".to_string(), 5.0);
+        budget.limits.insert("Anthropic".to_string(), 10.0);
+
+        let text = summary(&tracker, &budget, &["OpenAI", "Anthropic", "Gemini"], "2026-10");
+        assert!(text.contains("⚠️ OpenAI: 4.5000 z 5.00 USD (90%), zostało 0.5000 USD"));
+        assert!(text.contains("💰 Anthropic: 12.0000 z 10.00 USD (120%), zostało 0.0000 USD"));
+        assert!(text.contains("Gemini: 0.0000 USD (bez budżetu)"));
+        assert!(text.contains("tokeny: 40000 wejścia, 9000 wyjścia"));
+        assert!(text.contains("Razem: 16.5000 USD"));
+    }
+}
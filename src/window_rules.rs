@@ -0,0 +1,93 @@
+//! Reguły okien: ostatnio wybrany dostawca dla każdej aplikacji źródłowej
+//!
+//! Klasa okna aktywnego w chwili naciśnięcia skrótu wskazuje aplikację,
+//! z której pochodzi tekst. Wybór wyniku zapamiętuje dostawcę dla tej
+//! aplikacji, a następnym razem jego panel jest wyróżniony.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+
+pub struct WindowRules {
+    path: PathBuf,
+    rules: BTreeMap<String, String>,
+}
+
+/// Klucz aplikacji niezależny od wielkości liter
+fn app_key(app: &str) -> String {
+    app.trim().to_lowercase()
+}
+
+impl WindowRules {
+    /// Wczytuje reguły; brak lub uszkodzenie pliku daje pusty zestaw
+    pub fn load<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let rules = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self { path, rules }
+    }
+
+    /// Plik reguł obok `config.toml`
+    pub fn default_path() -> PathBuf {
+        Config::get_config_path().with_file_name("window_rules.json")
+    }
+
+    pub fn preferred(&self, app: &str) -> Option<&str> {
+        self.rules.get(&app_key(app)).map(String::as_str)
+    }
+
+    /// Zapamiętuje dostawcę dla aplikacji i zapisuje plik
+    pub fn remember(&mut self, app: &str, provider: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let key = app_key(app);
+        if key.is_empty() || self.preferred(app) == Some(provider) {
+            return Ok(());
+        }
+        self.rules.insert(key, provider.to_string());
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(&self.rules)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_remember_and_reload() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("window_rules.json");
+
+        let mut rules = WindowRules::load(&path);
+        assert_eq!(rules.preferred("Thunderbird"), None);
+
+        rules.remember("Thunderbird", "Anthropic").unwrap();
+        rules.remember("kitty", "OpenAI").unwrap();
+        rules.remember("thunderbird", "Gemini").unwrap();
+
+        let reloaded = WindowRules::load(&path);
+        assert_eq!(reloaded.preferred("THUNDERBIRD"), Some("Gemini"));
+        assert_eq!(reloaded.preferred("kitty"), Some("OpenAI"));
+    }
+
+    #[test]
+    fn test_empty_app_and_corrupt_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("window_rules.json");
+        fs::write(&path, "nie json").unwrap();
+
+        let mut rules = WindowRules::load(&path);
+        assert_eq!(rules.preferred("kitty"), None);
+
+        rules.remember("  ", "OpenAI").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "nie json");
+    }
+}
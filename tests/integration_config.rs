@@ -1,7 +1,8 @@
 //! Integration tests for config persistence and validation
 //! Tests full workflow: create config → save → load → verify equality
 
-use poprawiacz_tekstu_rs::config::Config;
+use poprawiacz_tekstu_rs::config::{Config, ReasoningEffort};
+use poprawiacz_tekstu_rs::prompts::CorrectionStyle;
 use std::fs;
 use tempfile::NamedTempFile;
 
@@ -17,7 +18,7 @@ fn test_config_roundtrip_persistence() {
     config.api_keys.deepseek = "test-deepseek-key".to_string();
     config.settings.auto_startup = true;
     config.settings.highlight_diffs = true;
-    config.ai_settings.reasoning_effort = "low".to_string();
+    config.ai_settings.reasoning_effort = ReasoningEffort::Low;
 
     config.save(temp_path).expect("Failed to save config");
 
@@ -26,7 +27,7 @@ fn test_config_roundtrip_persistence() {
     assert_eq!(config, loaded_config);
     assert_eq!(loaded_config.api_keys.openai, "sk-test-openai-key");
     assert_eq!(loaded_config.settings.auto_startup, true);
-    assert_eq!(loaded_config.ai_settings.reasoning_effort, "low");
+    assert_eq!(loaded_config.ai_settings.reasoning_effort, ReasoningEffort::Low);
 }
 
 #[test]
@@ -39,12 +40,12 @@ fn test_config_partial_update_persistence() {
 
     let mut loaded_config = Config::load(temp_path).unwrap();
     loaded_config.api_keys.openai = "new-key".to_string();
-    loaded_config.settings.default_style = "concise".to_string();
+    loaded_config.settings.default_style = CorrectionStyle::Professional;
     loaded_config.save(temp_path).unwrap();
 
     let final_config = Config::load(temp_path).unwrap();
     assert_eq!(final_config.api_keys.openai, "new-key");
-    assert_eq!(final_config.settings.default_style, "concise");
+    assert_eq!(final_config.settings.default_style, CorrectionStyle::Professional);
     assert_eq!(final_config.models.anthropic, "claude-3-7-sonnet-latest");
 }
 
@@ -139,12 +140,12 @@ fn test_config_unicode_values() {
 
     let mut config = Config::default();
     config.api_keys.openai = "test-🔑-key".to_string();
-    config.settings.default_style = "普通".to_string();
+    config.models.openai = "普通".to_string();
     config.save(temp_path).unwrap();
 
     let loaded = Config::load(temp_path).unwrap();
     assert_eq!(loaded.api_keys.openai, "test-🔑-key");
-    assert_eq!(loaded.settings.default_style, "普通");
+    assert_eq!(loaded.models.openai, "普通");
 }
 
 #[test]
@@ -169,7 +170,7 @@ fn test_config_special_characters_in_values() {
 
     let mut config = Config::default();
     config.api_keys.openai = r#"key"with"quotes"#.to_string();
-    config.settings.default_style = "style\nwith\nnewlines".to_string();
+    config.models.anthropic = "model\nwith\nnewlines".to_string();
     config.save(temp_path).unwrap();
 
     let loaded = Config::load(temp_path).unwrap();
@@ -250,9 +251,9 @@ fn test_config_default_values_completeness() {
     assert!(!config.models.gemini.is_empty());
     assert!(!config.models.deepseek.is_empty());
 
-    assert!(!config.settings.default_style.is_empty());
-    assert!(!config.ai_settings.reasoning_effort.is_empty());
-    assert!(!config.ai_settings.verbosity.is_empty());
+    assert!(!config.settings.default_style.as_str().is_empty());
+    assert!(!config.ai_settings.reasoning_effort.as_str().is_empty());
+    assert!(!config.ai_settings.verbosity.as_str().is_empty());
 }
 
 #[test]
@@ -1,4 +1,5 @@
-use poprawiacz_tekstu_rs::config::Config;
+use poprawiacz_tekstu_rs::config::{Config, ReasoningEffort, Verbosity};
+use poprawiacz_tekstu_rs::prompts::CorrectionStyle;
 use poprawiacz_tekstu_rs::api::openai::correct_text_openai;
 use poprawiacz_tekstu_rs::api::Provider;
 use poprawiacz_tekstu_rs::error::{ApiError, DEFAULT_TIMEOUT, CONNECTION_TIMEOUT, DEEPSEEK_TIMEOUT};
@@ -84,11 +85,11 @@ fn test_timeout_constants_consistency() {
 fn test_config_settings_affect_workflow() {
     let mut config1 = Config::default();
     config1.settings.highlight_diffs = false;
-    config1.ai_settings.reasoning_effort = "low".to_string();
+    config1.ai_settings.reasoning_effort = ReasoningEffort::Low;
 
     let mut config2 = Config::default();
     config2.settings.highlight_diffs = true;
-    config2.ai_settings.reasoning_effort = "high".to_string();
+    config2.ai_settings.reasoning_effort = ReasoningEffort::High;
 
     assert_ne!(config1.settings.highlight_diffs, config2.settings.highlight_diffs);
     assert_ne!(config1.ai_settings.reasoning_effort, config2.ai_settings.reasoning_effort);
@@ -171,8 +172,8 @@ async fn test_workflow_with_streaming_enabled() {
 fn test_ai_settings_from_config() {
     let config = Config::default();
 
-    assert_eq!(config.ai_settings.reasoning_effort, "high");
-    assert_eq!(config.ai_settings.verbosity, "medium");
+    assert_eq!(config.ai_settings.reasoning_effort, ReasoningEffort::High);
+    assert_eq!(config.ai_settings.verbosity, Verbosity::Medium);
 
     let valid_reasoning_efforts = ["low", "medium", "high"];
     let valid_verbosities = ["low", "medium", "high"];
@@ -191,13 +192,15 @@ fn test_ai_settings_from_config() {
 #[test]
 fn test_default_style_from_config() {
     let config = Config::default();
-    assert_eq!(config.settings.default_style, "normal");
+    assert_eq!(config.settings.default_style, CorrectionStyle::Normal);
 
-    let valid_styles = ["normal", "concise", "detailed"];
     assert!(
-        valid_styles.contains(&config.settings.default_style.as_str()),
+        CorrectionStyle::all().contains(&config.settings.default_style),
         "Default style should be valid"
     );
+    for style in CorrectionStyle::all() {
+        assert_eq!(CorrectionStyle::from_str(style.as_str()), *style, "{} should round-trip", style.as_str());
+    }
 }
 
 #[tokio::test]
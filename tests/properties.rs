@@ -0,0 +1,133 @@
+//! Testy właściwości dla kodu, który przekształca tekst: ochrona fragmentów
+//! (`protect`) oraz dzielenie i składanie wyników (`merge`).
+
+use proptest::prelude::*;
+use regex::Regex;
+
+use poprawiacz_tekstu_rs::config::ProtectedSpans;
+use poprawiacz_tekstu_rs::merge::{split_paragraphs, split_sentences, MergePlan};
+use poprawiacz_tekstu_rs::protect::{compile_patterns, ProtectedText};
+
+fn word() -> impl Strategy<Value = String> {
+    "[a-zA-Ząćęłńóśźż]{1,10}"
+}
+
+fn sentence() -> impl Strategy<Value = String> {
+    (prop::collection::vec(word(), 1..8), "[.!?]").prop_map(|(words, end)| words.join(" ") + &end)
+}
+
+/// Akapit jako jedna linia zdań
+fn paragraph() -> impl Strategy<Value = String> {
+    prop::collection::vec(sentence(), 1..5).prop_map(|s| s.join(" "))
+}
+
+/// Tekst ze słowami i fragmentami pasującymi do domyślnych wzorców ochrony
+fn text_with_spans() -> impl Strategy<Value = String> {
+    let token = prop_oneof![
+        4 => word(),
+        1 => "[A-Z]{2,4}-[0-9]{1,4}",
+        1 => "https://[a-z]{1,8}\\.(pl|com)/[a-z0-9]{0,6}",
+        1 => "`[a-z ]{1,10}`",
+    ];
+    let separator = prop_oneof![Just(" "), Just(", "), Just("\n")];
+    prop::collection::vec((token, separator), 0..20).prop_map(|parts| {
+        parts
+            .into_iter()
+            .map(|(token, sep)| token + sep)
+            .collect::<String>()
+    })
+}
+
+fn default_patterns() -> Vec<Regex> {
+    compile_patterns(&ProtectedSpans::default().patterns).unwrap()
+}
+
+/// Udaje model: zmienia wszystko poza znacznikami
+fn rewrite_outside_markers(text: &str) -> String {
+    let marker = Regex::new(r"\[\[KEEP_\d+\]\]").unwrap();
+    let mut output = String::new();
+    let mut pos = 0;
+    for m in marker.find_iter(text) {
+        output.push_str(&text[pos..m.start()].to_uppercase());
+        output.push_str(m.as_str());
+        pos = m.end();
+    }
+    output.push_str(&text[pos..].to_uppercase());
+    output
+}
+
+proptest! {
+    #[test]
+    fn protect_roundtrip_is_identity(text in text_with_spans()) {
+        let protected = ProtectedText::new(&text, &default_patterns());
+        let restored = protected.restore(&protected.text);
+
+        prop_assert_eq!(restored.text, text);
+        prop_assert!(restored.missing.is_empty());
+    }
+
+    #[test]
+    fn protect_never_changes_spans(text in text_with_spans()) {
+        let protected = ProtectedText::new(&text, &default_patterns());
+        for span in protected.spans() {
+            prop_assert!(!protected.text.contains(span.as_str()));
+        }
+
+        let restored = protected.restore(&rewrite_outside_markers(&protected.text));
+        prop_assert!(restored.missing.is_empty());
+
+        let mut rest = restored.text.as_str();
+        for span in protected.spans() {
+            let found = rest.find(span.as_str());
+            prop_assert!(found.is_some(), "brak fragmentu {:?}", span);
+            rest = &rest[found.unwrap() + span.len()..];
+        }
+    }
+
+    #[test]
+    fn protect_reports_exactly_dropped_spans(
+        text in text_with_spans(),
+        keep in prop::collection::vec(any::<bool>(), 20),
+    ) {
+        let protected = ProtectedText::new(&text, &default_patterns());
+        let mut output = protected.text.clone();
+        let mut dropped = Vec::new();
+        for (index, span) in protected.spans().iter().enumerate() {
+            if !keep[index % keep.len()] {
+                output = output.replace(&format!("[[KEEP_{}]]", index), "");
+                dropped.push(span.clone());
+            }
+        }
+
+        prop_assert_eq!(protected.restore(&output).missing, dropped);
+    }
+
+    #[test]
+    fn split_paragraphs_roundtrip(
+        paragraphs in prop::collection::vec(prop::collection::vec(paragraph(), 1..4), 0..5),
+    ) {
+        let paragraphs: Vec<String> = paragraphs.into_iter().map(|lines| lines.join("\n")).collect();
+        let text = paragraphs.join("\n\n");
+
+        prop_assert_eq!(split_paragraphs(&text), paragraphs);
+    }
+
+    #[test]
+    fn split_sentences_roundtrip(sentences in prop::collection::vec(sentence(), 0..10)) {
+        let text = sentences.join(" ");
+
+        prop_assert_eq!(split_sentences(&text), sentences);
+    }
+
+    #[test]
+    fn merge_of_unchanged_results_is_identity(
+        paragraphs in prop::collection::vec(paragraph(), 1..5),
+        source in 0usize..4,
+    ) {
+        let text = paragraphs.join("\n\n");
+        let mut plan = MergePlan::new(&[text.as_str(); 4]);
+        plan.select_source(source);
+
+        prop_assert_eq!(plan.compose(), text);
+    }
+}
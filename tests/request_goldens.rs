@@ -0,0 +1,74 @@
+//! Złote pliki z treścią żądań do API dla każdego dostawcy i stylu.
+//!
+//! Po zamierzonej zmianie promptów lub parametrów pliki odświeża się poleceniem:
+//! `UPDATE_GOLDENS=1 cargo test --test request_goldens`
+
+use poprawiacz_tekstu_rs::api::{build_request_body, Provider};
+use poprawiacz_tekstu_rs::config::Config;
+use poprawiacz_tekstu_rs::prompts::{get_instruction_prompt, get_system_prompt, CorrectionStyle, LanguageContext};
+use serde_json::json;
+use std::fs;
+use std::path::PathBuf;
+
+const SAMPLE_TEXT: &str = "Witam, chciał bym zapytać o status zamówienia nr 123 ktore złożyłem wczoraj.";
+
+fn goldens_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("goldens")
+}
+
+fn golden_path(provider: Provider, style: CorrectionStyle) -> PathBuf {
+    goldens_dir().join(format!("{}_{}.json", provider.name().to_lowercase(), style.as_str()))
+}
+
+fn render(provider: Provider, style: CorrectionStyle) -> String {
+    let config = Config::default();
+    let instruction = get_instruction_prompt(style);
+    let system = get_system_prompt(style, &LanguageContext::default());
+
+    let body = json!({
+        "streaming": build_request_body(provider, &config, SAMPLE_TEXT, instruction, system, true),
+        "batch": build_request_body(provider, &config, SAMPLE_TEXT, instruction, system, false),
+    });
+    serde_json::to_string_pretty(&body).unwrap() + "\n"
+}
+
+#[test]
+fn test_request_bodies_match_goldens() {
+    let update = std::env::var_os("UPDATE_GOLDENS").is_some();
+    let mut mismatches = Vec::new();
+
+    for &provider in Provider::all() {
+        for &style in CorrectionStyle::all() {
+            let path = golden_path(provider, style);
+            let actual = render(provider, style);
+
+            if update {
+                fs::create_dir_all(goldens_dir()).unwrap();
+                fs::write(&path, &actual).unwrap();
+                continue;
+            }
+
+            match fs::read_to_string(&path) {
+                Ok(expected) if expected == actual => {}
+                _ => mismatches.push(path.display().to_string()),
+            }
+        }
+    }
+
+    assert!(
+        mismatches.is_empty(),
+        "Request bodies differ from goldens (run with UPDATE_GOLDENS=1 if intended):\n{}",
+        mismatches.join("\n")
+    );
+}
+
+#[test]
+fn test_no_stale_goldens() {
+    let expected = Provider::all().len() * CorrectionStyle::all().len();
+    let count = fs::read_dir(goldens_dir())
+        .unwrap()
+        .filter(|e| e.as_ref().unwrap().path().extension().is_some_and(|ext| ext == "json"))
+        .count();
+
+    assert_eq!(count, expected);
+}